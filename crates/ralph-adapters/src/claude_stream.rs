@@ -14,22 +14,38 @@ use serde::{Deserialize, Serialize};
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClaudeStreamEvent {
     /// Session initialization - first event emitted.
+    ///
+    /// Later system events are told apart by `subtype`, e.g.
+    /// `compact_boundary` when the conversation was compacted to fit the
+    /// context window.
     System {
+        #[serde(default)]
+        subtype: Option<String>,
         session_id: String,
+        #[serde(default)]
         model: String,
         #[serde(default)]
         tools: Vec<serde_json::Value>,
     },
 
     /// Claude's response - contains text or tool invocations.
+    ///
+    /// `parent_tool_use_id` is set when the message comes from a sub-agent
+    /// spawned by a `Task` tool call.
     Assistant {
         message: AssistantMessage,
         #[serde(default)]
         usage: Option<Usage>,
+        #[serde(default)]
+        parent_tool_use_id: Option<String>,
     },
 
     /// Tool results returned to Claude.
-    User { message: UserMessage },
+    User {
+        message: UserMessage,
+        #[serde(default)]
+        parent_tool_use_id: Option<String>,
+    },
 
     /// Session complete - final event with stats.
     Result {
@@ -44,6 +60,9 @@ pub enum ClaudeStreamEvent {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AssistantMessage {
     pub content: Vec<ContentBlock>,
+    /// Token usage for this message (Claude reports it on the message itself).
+    #[serde(default)]
+    pub usage: Option<Usage>,
 }
 
 /// Message content from tool results (user turn).
@@ -141,6 +160,7 @@ mod tests {
                 session_id,
                 model,
                 tools,
+                ..
             } => {
                 assert_eq!(session_id, "abc123");
                 assert_eq!(model, "claude-opus");
@@ -195,7 +215,7 @@ mod tests {
         let event = ClaudeStreamParser::parse_line(json).unwrap();
 
         match event {
-            ClaudeStreamEvent::User { message } => {
+            ClaudeStreamEvent::User { message, .. } => {
                 assert_eq!(message.content.len(), 1);
                 match &message.content[0] {
                     UserContentBlock::ToolResult {
@@ -211,6 +231,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_sub_agent_message() {
+        let json = r#"{"type":"assistant","parent_tool_use_id":"task_1","message":{"content":[],"usage":{"input_tokens":120,"output_tokens":30}}}"#;
+        let event = ClaudeStreamParser::parse_line(json).unwrap();
+
+        match event {
+            ClaudeStreamEvent::Assistant {
+                message,
+                parent_tool_use_id,
+                ..
+            } => {
+                assert_eq!(parent_tool_use_id.as_deref(), Some("task_1"));
+                let usage = message.usage.unwrap();
+                assert_eq!(usage.input_tokens, 120);
+                assert_eq!(usage.output_tokens, 30);
+            }
+            _ => panic!("Expected Assistant event"),
+        }
+    }
+
     #[test]
     fn test_parse_result_event() {
         let json = r#"{"type":"result","duration_ms":5000,"total_cost_usd":0.02,"num_turns":2,"is_error":false}"#;
@@ -1,5 +1,7 @@
 //! CLI backend definitions for different AI tools.
 
+use crate::registry::AdapterRegistry;
+pub use ralph_core::PromptMode;
 use ralph_core::{CliConfig, HatBackend};
 use std::fmt;
 use std::io::Write;
@@ -30,17 +32,9 @@ impl fmt::Display for CustomBackendError {
 
 impl std::error::Error for CustomBackendError {}
 
-/// How to pass prompts to the CLI tool.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PromptMode {
-    /// Pass prompt as a command-line argument.
-    Arg,
-    /// Write prompt to stdin.
-    Stdin,
-}
-
 /// A CLI backend configuration for executing prompts.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct CliBackend {
     /// The command to execute.
     pub command: String,
@@ -55,22 +49,29 @@ pub struct CliBackend {
 }
 
 impl CliBackend {
+    /// Creates a plain-text backend that runs `command` with `args` and
+    /// passes the prompt as the last argument.
+    pub fn new(command: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            command: command.into(),
+            args,
+            prompt_mode: PromptMode::Arg,
+            prompt_flag: None,
+            output_format: OutputFormat::Text,
+        }
+    }
+
     /// Creates a backend from configuration.
     ///
     /// # Errors
     /// Returns `CustomBackendError` if backend is "custom" but no command is specified.
     pub fn from_config(config: &CliConfig) -> Result<Self, CustomBackendError> {
-        let mut backend = match config.backend.as_str() {
-            "claude" => Self::claude(),
-            "kiro" => Self::kiro(),
-            "gemini" => Self::gemini(),
-            "codex" => Self::codex(),
-            "amp" => Self::amp(),
-            "copilot" => Self::copilot(),
-            "opencode" => Self::opencode(),
-            "custom" => return Self::custom(config),
-            _ => Self::claude(), // Default to claude
-        };
+        if config.backend == "custom" {
+            return Self::custom(config);
+        }
+        let mut backend = AdapterRegistry::builtin()
+            .backend_named(&config.backend)
+            .unwrap_or_else(|_| Self::claude()); // Default to claude
 
         // Honor command override for named backends (e.g., custom binary path)
         if let Some(ref cmd) = config.command {
@@ -182,16 +183,9 @@ impl CliBackend {
     /// # Errors
     /// Returns error if the backend name is invalid.
     pub fn from_name(name: &str) -> Result<Self, CustomBackendError> {
-        match name {
-            "claude" => Ok(Self::claude()),
-            "kiro" => Ok(Self::kiro()),
-            "gemini" => Ok(Self::gemini()),
-            "codex" => Ok(Self::codex()),
-            "amp" => Ok(Self::amp()),
-            "copilot" => Ok(Self::copilot()),
-            "opencode" => Ok(Self::opencode()),
-            _ => Err(CustomBackendError),
-        }
+        AdapterRegistry::builtin()
+            .backend_named(name)
+            .map_err(|_| CustomBackendError)
     }
 
     /// Creates a backend from a HatBackend configuration.
@@ -199,22 +193,9 @@ impl CliBackend {
     /// # Errors
     /// Returns error if the backend configuration is invalid.
     pub fn from_hat_backend(hat_backend: &HatBackend) -> Result<Self, CustomBackendError> {
-        match hat_backend {
-            HatBackend::Named(name) => Self::from_name(name),
-            HatBackend::NamedWithArgs { backend_type, args } => {
-                Self::from_name_with_args(backend_type, args)
-            }
-            HatBackend::KiroAgent { agent, args, .. } => {
-                Ok(Self::kiro_with_agent(agent.clone(), args))
-            }
-            HatBackend::Custom { command, args } => Ok(Self {
-                command: command.clone(),
-                args: args.clone(),
-                prompt_mode: PromptMode::Arg,
-                prompt_flag: None,
-                output_format: OutputFormat::Text,
-            }),
-        }
+        AdapterRegistry::builtin()
+            .hat_backend(hat_backend)
+            .map_err(|_| CustomBackendError)
     }
 
     /// Creates the Gemini backend.
@@ -298,16 +279,9 @@ impl CliBackend {
     /// # Errors
     /// Returns `CustomBackendError` if the backend name is not recognized.
     pub fn for_interactive_prompt(backend_name: &str) -> Result<Self, CustomBackendError> {
-        match backend_name {
-            "claude" => Ok(Self::claude_interactive()),
-            "kiro" => Ok(Self::kiro_interactive()),
-            "gemini" => Ok(Self::gemini_interactive()),
-            "codex" => Ok(Self::codex_interactive()),
-            "amp" => Ok(Self::amp_interactive()),
-            "copilot" => Ok(Self::copilot_interactive()),
-            "opencode" => Ok(Self::opencode_interactive()),
-            _ => Err(CustomBackendError),
-        }
+        AdapterRegistry::builtin()
+            .interactive_backend(backend_name)
+            .map_err(|_| CustomBackendError)
     }
 
     /// Kiro in interactive mode (removes --no-interactive).
@@ -456,6 +430,27 @@ impl CliBackend {
         })
     }
 
+    /// Selects `model` with `--model <model>`, replacing any `--model`
+    /// already in the arguments.
+    pub fn set_model(&mut self, model: &str) {
+        self.remove_model();
+        self.args.push("--model".to_string());
+        self.args.push(model.to_string());
+    }
+
+    /// Drops any `--model` from the arguments, so the command runs its
+    /// default model.
+    pub fn remove_model(&mut self) {
+        let mut args = std::mem::take(&mut self.args).into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--model" {
+                args.next();
+            } else if !arg.starts_with("--model=") {
+                self.args.push(arg);
+            }
+        }
+    }
+
     /// Builds the full command with arguments for execution.
     ///
     /// # Arguments
@@ -1011,6 +1006,29 @@ mod tests {
         assert!(backend.args.contains(&"claude-sonnet-4".to_string()));
     }
 
+    #[test]
+    fn test_set_model_replaces_existing_model() {
+        let mut backend = CliBackend::claude();
+        backend
+            .args
+            .extend(["--model".to_string(), "sonnet".to_string()]);
+        backend.args.push("--model=haiku".to_string());
+        backend.set_model("opus");
+
+        assert_eq!(
+            backend
+                .args
+                .iter()
+                .filter(|a| a.starts_with("--model"))
+                .count(),
+            1
+        );
+        assert_eq!(backend.args[backend.args.len() - 2..], ["--model", "opus"]);
+
+        backend.remove_model();
+        assert_eq!(backend.args, CliBackend::claude().args);
+    }
+
     #[test]
     fn test_from_hat_backend_custom() {
         let hat_backend = HatBackend::Custom {
@@ -19,6 +19,7 @@ use tracing::{debug, warn};
 
 /// Result of a CLI execution.
 #[derive(Debug)]
+#[non_exhaustive]
 pub struct ExecutionResult {
     /// The full output from the CLI.
     pub output: String,
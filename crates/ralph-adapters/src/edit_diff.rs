@@ -0,0 +1,168 @@
+//! Word-level diffs of edit tool calls.
+//!
+//! When a tool call carries both the old and the new text (`old_string` and
+//! `new_string`, as in Claude's `Edit`, or a list of them under `edits`, as
+//! in `MultiEdit`), the stream handlers show what changed word by word right
+//! under the tool-call line, so edits can be audited as they happen.
+
+use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+
+/// Changed lines shown inline before the rest is cut off.
+pub(crate) const MAX_INLINE_LINES: usize = 8;
+
+/// How a run of text changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Change {
+    Same,
+    Removed,
+    Added,
+}
+
+/// The word-level diff of one tool call.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct EditDiff {
+    /// Lines with a change, each as runs of unchanged, removed and added text.
+    pub lines: Vec<Vec<(Change, String)>>,
+    /// Words removed.
+    pub removed: usize,
+    /// Words added.
+    pub added: usize,
+}
+
+impl EditDiff {
+    /// Diffs the old and new text in a tool call's input, if it has both.
+    pub(crate) fn from_input(input: &Value) -> Option<Self> {
+        let pairs: Vec<(&str, &str)> = match input.get("edits").and_then(Value::as_array) {
+            Some(edits) => edits.iter().filter_map(old_and_new).collect(),
+            None => old_and_new(input).into_iter().collect(),
+        };
+        if pairs.is_empty() {
+            return None;
+        }
+
+        let mut diff = Self::default();
+        for (old, new) in pairs {
+            diff.add(old, new);
+        }
+        (!diff.lines.is_empty()).then_some(diff)
+    }
+
+    fn add(&mut self, old: &str, new: &str) {
+        let mut line: Vec<(Change, String)> = Vec::new();
+        for change in TextDiff::from_words(old, new).iter_all_changes() {
+            let kind = match change.tag() {
+                ChangeTag::Equal => Change::Same,
+                ChangeTag::Delete => Change::Removed,
+                ChangeTag::Insert => Change::Added,
+            };
+            let value = change.value();
+            if !value.trim().is_empty() {
+                match kind {
+                    Change::Removed => self.removed += 1,
+                    Change::Added => self.added += 1,
+                    Change::Same => {}
+                }
+            }
+            for piece in value.split_inclusive('\n') {
+                let text = piece.strip_suffix('\n');
+                push_run(&mut line, kind, text.unwrap_or(piece));
+                if text.is_some() {
+                    self.finish_line(std::mem::take(&mut line));
+                }
+            }
+        }
+        self.finish_line(line);
+    }
+
+    /// Keeps `line` if anything on it changed.
+    fn finish_line(&mut self, line: Vec<(Change, String)>) {
+        if line.iter().any(|(kind, _)| *kind != Change::Same) {
+            self.lines.push(line);
+        }
+    }
+
+    /// One-line description, e.g. `+3 −1 words in 2 lines`.
+    pub(crate) fn summary(&self) -> String {
+        let lines = self.lines.len();
+        format!(
+            "+{} \u{2212}{} words in {lines} line{}",
+            self.added,
+            self.removed,
+            if lines == 1 { "" } else { "s" }
+        )
+    }
+
+    /// The diff as plain text, with removed runs as `[-text-]` and added runs
+    /// as `{+text+}` (like `git diff --word-diff=plain`).
+    pub(crate) fn to_plain(&self) -> String {
+        let mut plain = String::new();
+        for line in &self.lines {
+            for (kind, text) in line {
+                match kind {
+                    Change::Same => plain.push_str(text),
+                    Change::Removed => plain.push_str(&format!("[-{text}-]")),
+                    Change::Added => plain.push_str(&format!("{{+{text}+}}")),
+                }
+            }
+            plain.push('\n');
+        }
+        plain
+    }
+}
+
+fn old_and_new(input: &Value) -> Option<(&str, &str)> {
+    Some((
+        input.get("old_string")?.as_str()?,
+        input.get("new_string")?.as_str()?,
+    ))
+}
+
+/// Appends text to the last run if it changed the same way.
+fn push_run(line: &mut Vec<(Change, String)>, kind: Change, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match line.last_mut() {
+        Some((last, run)) if *last == kind => run.push_str(text),
+        _ => line.push((kind, text.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diffs_changed_words_and_skips_unchanged_lines() {
+        let input = json!({
+            "file_path": "src/lib.rs",
+            "old_string": "fn main() {\n    let x = 1;\n}\n",
+            "new_string": "fn main() {\n    let y = 2;\n}\n",
+        });
+        let diff = EditDiff::from_input(&input).unwrap();
+
+        assert_eq!(diff.lines.len(), 1);
+        assert_eq!(diff.to_plain(), "    let [-x-]{+y+} = [-1;-]{+2;+}\n");
+        assert_eq!(diff.summary(), "+2 \u{2212}2 words in 1 line");
+    }
+
+    #[test]
+    fn test_multi_edit_and_missing_text() {
+        let input = json!({
+            "edits": [
+                {"old_string": "alpha", "new_string": "beta"},
+                {"old_string": "one two", "new_string": "one two three"},
+            ]
+        });
+        let diff = EditDiff::from_input(&input).unwrap();
+        assert_eq!(diff.to_plain(), "[-alpha-]{+beta+}\none two{+ three+}\n");
+
+        assert_eq!(EditDiff::from_input(&json!({"content": "new file"})), None);
+        assert_eq!(
+            EditDiff::from_input(&json!({"old_string": "same", "new_string": "same"})),
+            None
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Per-hat colors that tell hats apart in streamed output.
+//!
+//! A hat's `color` setting wins; hats without one get a color picked from a
+//! fixed palette by hashing the hat ID, so the same hat keeps the same color
+//! from run to run.
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use crossterm::style::Color as TermColor;
+use ralph_proto::HatId;
+use ratatui::style::Color;
+
+/// Colors handed out to hats without a configured color.
+const PALETTE: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Blue,
+    Color::Green,
+    Color::LightRed,
+];
+
+/// Returns the color of `hat`: its configured color, else its palette color.
+pub fn hat_color<S: BuildHasher>(configured: &HashMap<HatId, Color, S>, hat: &str) -> Color {
+    configured
+        .get(&HatId::new(hat))
+        .copied()
+        .unwrap_or_else(|| default_hat_color(hat))
+}
+
+/// Returns the palette color of a hat without a configured color.
+pub fn default_hat_color(hat: &str) -> Color {
+    // FNV-1a, so the pick is stable across builds
+    let hash = hat.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    PALETTE[hash as usize % PALETTE.len()]
+}
+
+/// Converts a ratatui color to the matching crossterm color.
+pub(crate) fn term_color(color: Color) -> TermColor {
+    match color {
+        Color::Reset => TermColor::Reset,
+        Color::Black => TermColor::Black,
+        Color::Red => TermColor::DarkRed,
+        Color::Green => TermColor::DarkGreen,
+        Color::Yellow => TermColor::DarkYellow,
+        Color::Blue => TermColor::DarkBlue,
+        Color::Magenta => TermColor::DarkMagenta,
+        Color::Cyan => TermColor::DarkCyan,
+        Color::Gray => TermColor::Grey,
+        Color::DarkGray => TermColor::DarkGrey,
+        Color::LightRed => TermColor::Red,
+        Color::LightGreen => TermColor::Green,
+        Color::LightYellow => TermColor::Yellow,
+        Color::LightBlue => TermColor::Blue,
+        Color::LightMagenta => TermColor::Magenta,
+        Color::LightCyan => TermColor::Cyan,
+        Color::White => TermColor::White,
+        Color::Rgb(r, g, b) => TermColor::Rgb { r, g, b },
+        Color::Indexed(index) => TermColor::AnsiValue(index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_color_wins_over_stable_palette_color() {
+        let configured = HashMap::from([(HatId::new("builder"), Color::Rgb(255, 136, 0))]);
+
+        assert_eq!(hat_color(&configured, "builder"), Color::Rgb(255, 136, 0));
+        assert_eq!(
+            hat_color(&configured, "reviewer"),
+            default_hat_color("reviewer")
+        );
+        assert_eq!(default_hat_color("reviewer"), default_hat_color("reviewer"));
+        assert!(PALETTE.contains(&default_hat_color("planner")));
+    }
+}
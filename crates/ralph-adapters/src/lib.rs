@@ -27,9 +27,17 @@ mod auto_detect;
 mod claude_stream;
 mod cli_backend;
 mod cli_executor;
+mod edit_diff;
+mod hat_color;
+mod output_constraints;
+mod probe;
 mod pty_executor;
 pub mod pty_handle;
+mod registry;
+mod smooth_stream;
 mod stream_handler;
+mod sub_agent;
+mod tool_summary;
 
 pub use auto_detect::{
     DEFAULT_PRIORITY, NoBackendError, detect_backend, detect_backend_default, is_backend_available,
@@ -40,11 +48,19 @@ pub use claude_stream::{
 };
 pub use cli_backend::{CliBackend, CustomBackendError, OutputFormat, PromptMode};
 pub use cli_executor::{CliExecutor, ExecutionResult};
+pub use hat_color::{default_hat_color, hat_color};
+pub use output_constraints::{ConstrainedOutput, OutputConstraints};
+pub use probe::{PROBE_PROMPT, ProbeStatus, probe_backend};
 pub use pty_executor::{
-    CtrlCAction, CtrlCState, PtyConfig, PtyExecutionResult, PtyExecutor, TerminationType,
+    Checkpoint, CtrlCAction, CtrlCState, PtyConfig, PtyExecutionResult, PtyExecutor,
+    TerminationType,
 };
 pub use pty_handle::{ControlCommand, PtyHandle};
+pub use registry::{AdapterCapabilities, AdapterEntry, AdapterError, AdapterRegistry};
+pub use smooth_stream::SmoothStream;
 pub use stream_handler::{
-    ConsoleStreamHandler, PrettyStreamHandler, QuietStreamHandler, SessionResult, StreamHandler,
-    TuiStreamHandler,
+    ConsoleStreamHandler, IterationBuffers, IterationInfo, JsonStreamHandler, PrettyStreamHandler,
+    QuietStreamHandler, SessionResult, StreamHandler, TracingStreamHandler, TuiStreamHandler,
 };
+pub use sub_agent::SubAgentUsage;
+pub use tool_summary::ToolSummaries;
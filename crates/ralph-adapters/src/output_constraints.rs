@@ -0,0 +1,183 @@
+//! Per-hat limits on a backend's response.
+//!
+//! The CLI backends Ralph drives have no stop-sequence or max-token flags,
+//! so a hat's constraints are stated in its prompt and then enforced on the
+//! output here: the response is cut at the first stop sequence and at the
+//! token limit, and checked against the required response format. Tightly
+//! scoped hats (classifiers, routers) then behave the same whatever the
+//! model decides to add.
+
+use ralph_core::{HatConfig, ResponseFormat};
+
+/// Rough characters-per-token estimate, matching Ralph's prompt budgets.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Constraints on one hat's response.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputConstraints {
+    /// Sequences that end the response.
+    pub stop_sequences: Vec<String>,
+    /// Maximum response length in estimated tokens.
+    pub max_output_tokens: Option<usize>,
+    /// Required shape of the response.
+    pub response_format: ResponseFormat,
+}
+
+/// A response after its constraints were applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstrainedOutput {
+    /// The response, cut at the first stop sequence and the token limit.
+    pub output: String,
+    /// Why the response broke its constraints; empty if it didn't.
+    pub violations: Vec<String>,
+}
+
+impl OutputConstraints {
+    /// The constraints configured on `hat`.
+    pub fn from_hat(hat: &HatConfig) -> Self {
+        Self {
+            stop_sequences: hat.stop_sequences.clone(),
+            max_output_tokens: hat.max_output_tokens,
+            response_format: hat.response_format,
+        }
+    }
+
+    /// Whether there is nothing to enforce.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Prompt text telling the backend about the constraints, or an empty
+    /// string when there are none.
+    pub fn prompt_section(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let mut section = String::from("\n\n## OUTPUT CONSTRAINTS\n\n");
+        if self.response_format == ResponseFormat::Json {
+            section.push_str(
+                "- Your final response MUST be a single JSON value with no prose around it.\n",
+            );
+        }
+        if let Some(max) = self.max_output_tokens {
+            section.push_str(&format!(
+                "- Keep your response under {max} tokens; anything longer is cut off.\n"
+            ));
+        }
+        for stop in &self.stop_sequences {
+            section.push_str(&format!(
+                "- Writing `{stop}` ends your response; nothing after it is read.\n"
+            ));
+        }
+        section
+    }
+
+    /// Applies the constraints to a response.
+    pub fn apply(&self, output: &str) -> ConstrainedOutput {
+        let mut output = output;
+        let mut violations = Vec::new();
+
+        if let Some(end) = self
+            .stop_sequences
+            .iter()
+            .filter(|stop| !stop.is_empty())
+            .filter_map(|stop| output.find(stop.as_str()))
+            .min()
+        {
+            output = &output[..end];
+        }
+
+        if let Some(max) = self.max_output_tokens {
+            let max_chars = max.saturating_mul(CHARS_PER_TOKEN);
+            if output.len() > max_chars {
+                let mut end = max_chars;
+                while !output.is_char_boundary(end) {
+                    end -= 1;
+                }
+                output = &output[..end];
+                violations.push(format!("response exceeded {max} tokens and was cut off"));
+            }
+        }
+
+        if self.response_format == ResponseFormat::Json
+            && let Err(e) = serde_json::from_str::<serde_json::Value>(json_body(output))
+        {
+            violations.push(format!("response is not valid JSON: {e}"));
+        }
+
+        ConstrainedOutput {
+            output: output.to_string(),
+            violations,
+        }
+    }
+}
+
+/// The JSON to validate: the response without surrounding whitespace or a
+/// Markdown code fence around it.
+fn json_body(output: &str) -> &str {
+    let trimmed = output.trim();
+    trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map_or(trimmed, str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_sequence_cuts_response() {
+        let constraints = OutputConstraints {
+            stop_sequences: vec!["</answer>".to_string(), "STOP".to_string()],
+            ..OutputConstraints::default()
+        };
+        let constrained = constraints.apply("<answer>bug</answer> and then some musing STOP");
+        assert_eq!(constrained.output, "<answer>bug");
+        assert!(constrained.violations.is_empty());
+    }
+
+    #[test]
+    fn test_max_output_tokens_truncates_and_reports() {
+        let constraints = OutputConstraints {
+            max_output_tokens: Some(2),
+            ..OutputConstraints::default()
+        };
+        let constrained = constraints.apply("twelve chars");
+        assert_eq!(constrained.output, "twelve c");
+        assert_eq!(constrained.violations.len(), 1);
+        assert!(constraints.apply("short").violations.is_empty());
+    }
+
+    #[test]
+    fn test_json_format_is_validated() {
+        let constraints = OutputConstraints {
+            response_format: ResponseFormat::Json,
+            ..OutputConstraints::default()
+        };
+        assert!(
+            constraints
+                .apply("```json\n{\"label\": \"bug\"}\n```\n")
+                .violations
+                .is_empty()
+        );
+        let constrained = constraints.apply("Sure! {\"label\": \"bug\"}");
+        assert_eq!(constrained.violations.len(), 1);
+        assert!(constrained.violations[0].starts_with("response is not valid JSON"));
+    }
+
+    #[test]
+    fn test_prompt_section_lists_constraints() {
+        assert_eq!(OutputConstraints::default().prompt_section(), "");
+        let section = OutputConstraints {
+            stop_sequences: vec!["</answer>".to_string()],
+            max_output_tokens: Some(50),
+            response_format: ResponseFormat::Json,
+        }
+        .prompt_section();
+        assert!(section.contains("single JSON value"));
+        assert!(section.contains("under 50 tokens"));
+        assert!(section.contains("`</answer>`"));
+    }
+}
@@ -0,0 +1,165 @@
+//! Backend health probe run before the first iteration.
+//!
+//! A missing login, a wrong model name or a typo in `cli.command` otherwise
+//! only shows up once iteration 1 fails, possibly minutes into a run. The
+//! probe sends a one-line prompt through the configured backend and reports
+//! whether it answered, how long it took, and why not.
+
+use crate::cli_backend::CliBackend;
+use crate::cli_executor::CliExecutor;
+use std::time::{Duration, Instant};
+
+/// Prompt sent by the probe; cheap for every backend to answer.
+pub const PROBE_PROMPT: &str = "Reply with the single word OK and nothing else.";
+
+/// Characters of backend output kept in a failure reason.
+const MAX_REASON_CHARS: usize = 300;
+
+/// State of the backend probe, as shown before iteration 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeStatus {
+    /// The probe request is in flight.
+    Running { backend: String },
+    /// The backend answered; `latency` is the baseline for a minimal request.
+    Ok { backend: String, latency: Duration },
+    /// The backend could not be started or did not answer successfully.
+    Failed { backend: String, reason: String },
+}
+
+impl ProbeStatus {
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed { .. })
+    }
+
+    /// One-line description, e.g. `claude responded in 2.4s`.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Running { backend } => format!("Probing {backend}..."),
+            Self::Ok { backend, latency } => {
+                format!("{backend} responded in {:.1}s", latency.as_secs_f64())
+            }
+            Self::Failed { backend, reason } => format!("{backend} probe failed: {reason}"),
+        }
+    }
+}
+
+/// Sends [`PROBE_PROMPT`] through `backend` and waits up to `timeout`.
+///
+/// `name` is the backend name used in the returned status.
+pub async fn probe_backend(backend: &CliBackend, name: &str, timeout: Duration) -> ProbeStatus {
+    let backend_name = name.to_string();
+    let started = Instant::now();
+    let result = CliExecutor::new(backend.clone())
+        .execute_capture_with_timeout(PROBE_PROMPT, Some(timeout))
+        .await;
+    let latency = started.elapsed();
+
+    let reason = match result {
+        Ok(result) if result.success => {
+            return ProbeStatus::Ok {
+                backend: backend_name,
+                latency,
+            };
+        }
+        Ok(result) if result.timed_out => {
+            format!("no response within {}s", timeout.as_secs())
+        }
+        Ok(result) => {
+            let exit = result.exit_code.map_or_else(
+                || "killed by signal".to_string(),
+                |code| format!("exit {code}"),
+            );
+            match last_output(&result.output) {
+                Some(output) => format!("{exit}: {output}"),
+                None => exit,
+            }
+        }
+        Err(e) => format!("failed to start `{}`: {e}", backend.command),
+    };
+    ProbeStatus::Failed {
+        backend: backend_name,
+        reason,
+    }
+}
+
+/// The tail of the backend's output, where CLIs report auth and config
+/// errors, flattened to one line.
+fn last_output(output: &str) -> Option<String> {
+    let lines: Vec<&str> = output
+        .lines()
+        .map(|line| line.trim_start_matches("[stderr] ").trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let joined = lines[lines.len().saturating_sub(3)..].join(" | ");
+    if joined.is_empty() {
+        return None;
+    }
+    let chars = joined.chars().count();
+    Some(if chars > MAX_REASON_CHARS {
+        let tail: String = joined.chars().skip(chars - MAX_REASON_CHARS).collect();
+        format!("...{tail}")
+    } else {
+        joined
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli_backend::{OutputFormat, PromptMode};
+
+    fn backend(command: &str, args: &[&str]) -> CliBackend {
+        CliBackend {
+            command: command.to_string(),
+            args: args.iter().map(ToString::to_string).collect(),
+            prompt_mode: PromptMode::Arg,
+            prompt_flag: None,
+            output_format: OutputFormat::Text,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_succeeds_for_responding_backend() {
+        let status = probe_backend(&backend("echo", &[]), "echo", Duration::from_secs(5)).await;
+        assert!(matches!(status, ProbeStatus::Ok { .. }));
+        assert!(status.summary().starts_with("echo responded in "));
+    }
+
+    #[tokio::test]
+    async fn test_probe_reports_exit_code_and_error_output() {
+        let failing = backend("sh", &["-c", "echo 'Invalid API key' >&2; exit 2", "probe"]);
+        let status = probe_backend(&failing, "custom", Duration::from_secs(5)).await;
+        assert_eq!(
+            status,
+            ProbeStatus::Failed {
+                backend: "custom".to_string(),
+                reason: "exit 2: Invalid API key".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_reports_missing_command() {
+        let status = probe_backend(
+            &backend("ralph-no-such-backend", &[]),
+            "custom",
+            Duration::from_secs(5),
+        )
+        .await;
+        assert!(status.is_failed());
+        assert!(
+            status
+                .summary()
+                .contains("failed to start `ralph-no-such-backend`")
+        );
+    }
+
+    #[test]
+    fn test_last_output_keeps_tail_lines() {
+        assert_eq!(last_output("\n \n"), None);
+        assert_eq!(
+            last_output("a\nb\n[stderr] c\n[stderr] d\n"),
+            Some("b | c | d".to_string())
+        );
+    }
+}
@@ -21,11 +21,13 @@
 use crate::claude_stream::{ClaudeStreamEvent, ClaudeStreamParser, ContentBlock, UserContentBlock};
 use crate::cli_backend::{CliBackend, OutputFormat};
 use crate::stream_handler::{SessionResult, StreamHandler};
+use crate::sub_agent::SubAgentTracker;
 #[cfg(unix)]
 use nix::sys::signal::{Signal, kill};
 #[cfg(unix)]
 use nix::unistd::Pid;
 use portable_pty::{CommandBuilder, PtyPair, PtySize, native_pty_system};
+use ralph_core::{Clock, SystemClock};
 use std::io::{self, Read, Write};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -52,6 +54,15 @@ pub struct PtyExecutionResult {
     pub exit_code: Option<i32>,
     /// How the process was terminated.
     pub termination: TerminationType,
+    /// Session cost reported by the backend's final `result` event.
+    /// Zero for backends that don't report cost.
+    pub total_cost_usd: f64,
+    /// Input and output tokens reported by the backend's stream.
+    /// Zero for backends that don't report usage.
+    pub total_tokens: u64,
+    /// The agent's summary of where it was, when it was asked to wrap up
+    /// before being stopped (see [`Checkpoint`]).
+    pub checkpoint: Option<String>,
 }
 
 /// How the PTY process was terminated.
@@ -67,6 +78,54 @@ pub enum TerminationType {
     ForceKill,
 }
 
+/// A request to wrap up, typed into an interactive session before it is
+/// stopped, so an abort doesn't lose the agent's in-flight reasoning.
+///
+/// The agent's answer is expected between `<checkpoint>` and `</checkpoint>`
+/// tags; the session is stopped as soon as it arrives, or after `timeout`.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    /// Instruction typed into the session.
+    pub instruction: String,
+    /// How long to wait for the summary before stopping the agent anyway.
+    pub timeout: Duration,
+}
+
+const CHECKPOINT_OPEN: &str = "<checkpoint>";
+const CHECKPOINT_CLOSE: &str = "</checkpoint>";
+
+/// Returns the last checkpoint summary in `output`.
+///
+/// Interactive sessions echo what is typed into them, so tagged text that is
+/// part of the instruction itself is skipped.
+fn checkpoint_summary(output: &str, instruction: &str) -> Option<String> {
+    let tagged = |text: &str| -> Vec<String> {
+        let mut found = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find(CHECKPOINT_OPEN) {
+            let after = &rest[start + CHECKPOINT_OPEN.len()..];
+            let Some(end) = after.find(CHECKPOINT_CLOSE) else {
+                break;
+            };
+            found.push(after[..end].trim().to_string());
+            rest = &after[end + CHECKPOINT_CLOSE.len()..];
+        }
+        found
+    };
+    let echoed = tagged(instruction);
+    tagged(output)
+        .into_iter()
+        .rev()
+        .find(|summary| !summary.is_empty() && !echoed.contains(summary))
+}
+
+/// Types a checkpoint request into an interactive session.
+fn request_checkpoint(writer: &mut impl Write, request: &Checkpoint) -> io::Result<()> {
+    writer.write_all(request.instruction.as_bytes())?;
+    writer.write_all(b"\r")?;
+    writer.flush()
+}
+
 /// Configuration for PTY execution.
 #[derive(Debug, Clone)]
 pub struct PtyConfig {
@@ -82,6 +141,9 @@ pub struct PtyConfig {
     /// This is captured at startup to avoid `current_dir()` failures when the
     /// working directory no longer exists (e.g., in E2E test workspaces).
     pub workspace_root: std::path::PathBuf,
+    /// Wrap-up request for interactive sessions stopped by an interrupt;
+    /// `None` stops them right away.
+    pub checkpoint: Option<Checkpoint>,
 }
 
 impl Default for PtyConfig {
@@ -93,6 +155,7 @@ impl Default for PtyConfig {
             rows: 24,
             workspace_root: std::env::current_dir()
                 .unwrap_or_else(|_| std::path::PathBuf::from(".")),
+            checkpoint: None,
         }
     }
 }
@@ -191,6 +254,10 @@ pub struct PtyExecutor {
     // This replaces the previous inference via output_rx.is_none() which broke
     // after the streaming refactor (handle() is no longer called in TUI mode).
     tui_mode: bool,
+    // Asks an interactive session to wrap up without interrupting the loop
+    wrap_up_rx: Option<watch::Receiver<bool>>,
+    // Source of "now" for idle timeouts and the double Ctrl+C window
+    clock: Arc<dyn Clock>,
 }
 
 impl PtyExecutor {
@@ -213,6 +280,8 @@ impl PtyExecutor {
             terminated_tx,
             terminated_rx: Some(terminated_rx),
             tui_mode: false,
+            wrap_up_rx: None,
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -228,6 +297,22 @@ impl PtyExecutor {
         self.tui_mode = enabled;
     }
 
+    /// Sets a signal that asks an interactive session to wrap up, like a
+    /// first interrupt does, but without interrupting the loop.
+    ///
+    /// The run's controller sends it on `pause`: the agent's checkpoint
+    /// summary is returned and the session ends as if it had finished. It
+    /// has no effect unless [`PtyConfig::checkpoint`] is set.
+    pub fn set_wrap_up_signal(&mut self, wrap_up_rx: watch::Receiver<bool>) {
+        self.wrap_up_rx = Some(wrap_up_rx);
+    }
+
+    /// Sets the clock that idle timeouts and the double Ctrl+C window are
+    /// measured against, in place of the system clock.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
     /// Updates the backend configuration for this executor.
     ///
     /// This allows switching backends between iterations without recreating
@@ -355,7 +440,7 @@ impl PtyExecutor {
         };
 
         let mut termination = TerminationType::Natural;
-        let mut last_activity = Instant::now();
+        let mut last_activity = self.clock.now();
 
         // Flag for termination request (shared with reader thread)
         let should_terminate = Arc::new(AtomicBool::new(false));
@@ -415,14 +500,8 @@ impl PtyExecutor {
         // Main event loop using tokio::select! for interruptibility
         loop {
             // Calculate timeout for idle check
-            let idle_timeout = timeout_duration.map(|d| {
-                let elapsed = last_activity.elapsed();
-                if elapsed >= d {
-                    Duration::from_millis(1) // Trigger immediately
-                } else {
-                    d.saturating_sub(elapsed)
-                }
-            });
+            let now = self.clock.now();
+            let idle_timeout = timeout_duration.map(|d| idle_remaining(d, last_activity, now));
 
             tokio::select! {
                 // Check for interrupt signal from event loop
@@ -446,7 +525,7 @@ impl PtyExecutor {
                                 io::stdout().flush()?;
                             }
                             output.extend_from_slice(&data);
-                            last_activity = Instant::now();
+                            last_activity = self.clock.now();
                         }
                         Some(OutputEvent::Eof) | None => {
                             debug!("Output channel closed, process likely exited");
@@ -595,8 +674,8 @@ impl PtyExecutor {
 
         let mut output = Vec::new();
         let mut line_buffer = String::new();
-        // Accumulate extracted text from NDJSON for event parsing
-        let mut extracted_text = String::new();
+        // Accumulate extracted text, cost and sub-agent usage from NDJSON
+        let mut session = StreamSession::default();
         let timeout_duration = if !self.config.interactive || self.config.idle_timeout_secs == 0 {
             None
         } else {
@@ -606,7 +685,7 @@ impl PtyExecutor {
         };
 
         let mut termination = TerminationType::Natural;
-        let mut last_activity = Instant::now();
+        let mut last_activity = self.clock.now();
 
         let should_terminate = Arc::new(AtomicBool::new(false));
 
@@ -661,14 +740,8 @@ impl PtyExecutor {
 
         // Main event loop with JSON line parsing
         loop {
-            let idle_timeout = timeout_duration.map(|d| {
-                let elapsed = last_activity.elapsed();
-                if elapsed >= d {
-                    Duration::from_millis(1)
-                } else {
-                    d.saturating_sub(elapsed)
-                }
-            });
+            let now = self.clock.now();
+            let idle_timeout = timeout_duration.map(|d| idle_remaining(d, last_activity, now));
 
             tokio::select! {
                 _ = interrupt_rx.changed() => {
@@ -685,7 +758,7 @@ impl PtyExecutor {
                     match event {
                         Some(OutputEvent::Data(data)) => {
                             output.extend_from_slice(&data);
-                            last_activity = Instant::now();
+                            last_activity = self.clock.now();
 
                             if let Ok(text) = std::str::from_utf8(&data) {
                                 if is_stream_json {
@@ -698,7 +771,7 @@ impl PtyExecutor {
                                         line_buffer = line_buffer[newline_pos + 1..].to_string();
 
                                         if let Some(event) = ClaudeStreamParser::parse_line(&line) {
-                                            dispatch_stream_event(event, handler, &mut extracted_text);
+                                            dispatch_stream_event(event, handler, &mut session);
                                         }
                                     }
                                 } else {
@@ -714,7 +787,7 @@ impl PtyExecutor {
                             if is_stream_json && !line_buffer.is_empty()
                                 && let Some(event) = ClaudeStreamParser::parse_line(&line_buffer)
                             {
-                                dispatch_stream_event(event, handler, &mut extracted_text);
+                                dispatch_stream_event(event, handler, &mut session);
                             }
                             break;
                         }
@@ -764,7 +837,7 @@ impl PtyExecutor {
                                     let line = line_buffer[..newline_pos].to_string();
                                     line_buffer = line_buffer[newline_pos + 1..].to_string();
                                     if let Some(event) = ClaudeStreamParser::parse_line(&line) {
-                                        dispatch_stream_event(event, handler, &mut extracted_text);
+                                        dispatch_stream_event(event, handler, &mut session);
                                     }
                                 }
                             } else {
@@ -780,18 +853,22 @@ impl PtyExecutor {
                     && !line_buffer.is_empty()
                     && let Some(event) = ClaudeStreamParser::parse_line(&line_buffer)
                 {
-                    dispatch_stream_event(event, handler, &mut extracted_text);
+                    dispatch_stream_event(event, handler, &mut session);
                 }
 
                 let final_termination = resolve_termination_type(exit_code, termination);
                 // Pass extracted_text for event parsing from NDJSON
-                return Ok(build_result(
-                    &output,
-                    status.success(),
-                    Some(exit_code),
-                    final_termination,
-                    extracted_text,
-                ));
+                return Ok(PtyExecutionResult {
+                    total_cost_usd: session.total_cost_usd,
+                    total_tokens: session.sub_agents.total_tokens(),
+                    ..build_result(
+                        &output,
+                        status.success(),
+                        Some(exit_code),
+                        final_termination,
+                        session.extracted_text,
+                    )
+                });
             }
         }
 
@@ -817,13 +894,17 @@ impl PtyExecutor {
         };
 
         // Pass extracted_text for event parsing from NDJSON
-        Ok(build_result(
-            &output,
-            success,
-            exit_code,
-            final_termination,
-            extracted_text,
-        ))
+        Ok(PtyExecutionResult {
+            total_cost_usd: session.total_cost_usd,
+            total_tokens: session.sub_agents.total_tokens(),
+            ..build_result(
+                &output,
+                success,
+                exit_code,
+                final_termination,
+                session.extracted_text,
+            )
+        })
     }
 
     /// Runs in interactive mode (bidirectional I/O).
@@ -883,7 +964,13 @@ impl PtyExecutor {
 
         let mut ctrl_c_state = CtrlCState::new();
         let mut termination = TerminationType::Natural;
-        let mut last_activity = Instant::now();
+        let mut last_activity = self.clock.now();
+
+        // Once an interrupt has asked the agent to wrap up: where its answer
+        // starts in `output`, and when to stop waiting for it
+        let mut checkpoint_started: Option<(usize, Instant)> = None;
+        let mut checkpoint = None;
+        let mut wrap_up_rx = self.wrap_up_rx.clone();
 
         // Flag for termination request (shared with spawned tasks)
         let should_terminate = Arc::new(AtomicBool::new(false));
@@ -996,7 +1083,7 @@ impl PtyExecutor {
             writer.write_all(input.as_bytes())?;
             writer.write_all(b"\n")?;
             writer.flush()?;
-            last_activity = Instant::now();
+            last_activity = self.clock.now();
         }
 
         // Main select loop - this is the key fix for blocking I/O
@@ -1025,28 +1112,51 @@ impl PtyExecutor {
                 // Signal TUI that PTY has terminated
                 let _ = self.terminated_tx.send(true);
 
+                if let (Some((start, _)), Some(request)) =
+                    (checkpoint_started, &self.config.checkpoint)
+                {
+                    checkpoint =
+                        checkpoint_summary(&strip_ansi(&output[start..]), &request.instruction);
+                }
                 let final_termination = resolve_termination_type(exit_code, termination);
                 // run_interactive doesn't parse JSON, so extracted_text is empty
-                return Ok(build_result(
-                    &output,
-                    status.success(),
-                    Some(exit_code),
-                    final_termination,
-                    String::new(),
-                ));
+                return Ok(PtyExecutionResult {
+                    checkpoint,
+                    ..build_result(
+                        &output,
+                        status.success(),
+                        Some(exit_code),
+                        final_termination,
+                        String::new(),
+                    )
+                });
             }
 
+            let wrap_up = async {
+                let signalled = match wrap_up_rx.as_mut() {
+                    Some(rx) => rx.changed().await.is_ok(),
+                    None => false,
+                };
+                if !signalled {
+                    std::future::pending::<()>().await;
+                }
+            };
+
+            let checkpoint_deadline = async {
+                match checkpoint_started {
+                    Some((_, deadline)) => {
+                        tokio::time::sleep_until(tokio::time::Instant::from_std(deadline)).await;
+                    }
+                    None => std::future::pending::<()>().await,
+                }
+            };
+
             // Build the timeout future (or a never-completing one if disabled)
+            let idle_timeout =
+                timeout_duration.map(|d| idle_remaining(d, last_activity, self.clock.now()));
             let timeout_future = async {
-                match timeout_duration {
-                    Some(d) => {
-                        let elapsed = last_activity.elapsed();
-                        if elapsed >= d {
-                            tokio::time::sleep(Duration::ZERO).await
-                        } else {
-                            tokio::time::sleep(d.saturating_sub(elapsed)).await
-                        }
-                    }
+                match idle_timeout {
+                    Some(remaining) => tokio::time::sleep(remaining).await,
                     None => std::future::pending::<()>().await,
                 }
             };
@@ -1063,7 +1173,22 @@ impl PtyExecutor {
                             }
                             output.extend_from_slice(&data);
 
-                            last_activity = Instant::now();
+                            last_activity = self.clock.now();
+
+                            if let (Some((start, _)), Some(request)) =
+                                (checkpoint_started, &self.config.checkpoint)
+                            {
+                                checkpoint = checkpoint_summary(
+                                    &strip_ansi(&output[start..]),
+                                    &request.instruction,
+                                );
+                                if checkpoint.is_some() {
+                                    info!("Checkpoint summary received, terminating");
+                                    should_terminate.store(true, Ordering::SeqCst);
+                                    self.terminate_child(&mut child, true).await?;
+                                    break;
+                                }
+                            }
                         }
                         Some(OutputEvent::Eof) => {
                             debug!("PTY EOF received");
@@ -1089,12 +1214,12 @@ impl PtyExecutor {
                 } => {
                     match input_event {
                         Some(InputEvent::CtrlC) => {
-                            match ctrl_c_state.handle_ctrl_c(Instant::now()) {
+                            match ctrl_c_state.handle_ctrl_c(self.clock.now()) {
                                 CtrlCAction::ForwardAndStartWindow => {
                                     // Forward Ctrl+C to Claude
                                     let _ = writer.write_all(&[3]);
                                     let _ = writer.flush();
-                                    last_activity = Instant::now();
+                                    last_activity = self.clock.now();
                                 }
                                 CtrlCAction::Terminate => {
                                     info!("Double Ctrl+C detected, terminating");
@@ -1116,7 +1241,7 @@ impl PtyExecutor {
                             // Forward to Claude
                             let _ = writer.write_all(&data);
                             let _ = writer.flush();
-                            last_activity = Instant::now();
+                            last_activity = self.clock.now();
                         }
                         None => {
                             // Input channel closed (stdin EOF)
@@ -1130,11 +1255,11 @@ impl PtyExecutor {
                     if let Some(data) = tui_input {
                         match InputEvent::from_bytes(data) {
                             InputEvent::CtrlC => {
-                                match ctrl_c_state.handle_ctrl_c(Instant::now()) {
+                                match ctrl_c_state.handle_ctrl_c(self.clock.now()) {
                                     CtrlCAction::ForwardAndStartWindow => {
                                         let _ = writer.write_all(&[3]);
                                         let _ = writer.flush();
-                                        last_activity = Instant::now();
+                                        last_activity = self.clock.now();
                                     }
                                     CtrlCAction::Terminate => {
                                         info!("Double Ctrl+C detected, terminating");
@@ -1155,7 +1280,7 @@ impl PtyExecutor {
                             InputEvent::Data(bytes) => {
                                 let _ = writer.write_all(&bytes);
                                 let _ = writer.flush();
-                                last_activity = Instant::now();
+                                last_activity = self.clock.now();
                             }
                         }
                     }
@@ -1205,16 +1330,41 @@ impl PtyExecutor {
                     break;
                 }
 
-                // Interrupt signal from event loop
+                // Interrupt signal from event loop. With a checkpoint configured,
+                // the first one asks the agent to wrap up; another stops it now.
                 _ = interrupt_rx.changed() => {
                     if *interrupt_rx.borrow() {
-                        debug!("Interrupt received in interactive mode, terminating");
                         termination = TerminationType::UserInterrupt;
+                        if let (Some(request), None) = (&self.config.checkpoint, checkpoint_started) {
+                            info!("Interrupt received in interactive mode, asking the agent to wrap up");
+                            request_checkpoint(&mut writer, request)?;
+                            checkpoint_started = Some((output.len(), Instant::now() + request.timeout));
+                            continue;
+                        }
+                        debug!("Interrupt received in interactive mode, terminating");
                         should_terminate.store(true, Ordering::SeqCst);
                         self.terminate_child(&mut child, true).await?;
                         break;
                     }
                 }
+
+                // The run is pausing: checkpoint, then end the session as if
+                // the agent had finished
+                () = wrap_up, if checkpoint_started.is_none() => {
+                    if let Some(request) = &self.config.checkpoint {
+                        info!("Pause requested in interactive mode, asking the agent to wrap up");
+                        request_checkpoint(&mut writer, request)?;
+                        checkpoint_started = Some((output.len(), Instant::now() + request.timeout));
+                    }
+                }
+
+                // The agent didn't answer the wrap-up request in time
+                () = checkpoint_deadline => {
+                    warn!("No checkpoint summary before the timeout, terminating");
+                    should_terminate.store(true, Ordering::SeqCst);
+                    self.terminate_child(&mut child, true).await?;
+                    break;
+                }
             }
         }
 
@@ -1245,13 +1395,16 @@ impl PtyExecutor {
         };
 
         // run_interactive doesn't parse JSON, so extracted_text is empty
-        Ok(build_result(
-            &output,
-            success,
-            exit_code,
-            final_termination,
-            String::new(),
-        ))
+        Ok(PtyExecutionResult {
+            checkpoint,
+            ..build_result(
+                &output,
+                success,
+                exit_code,
+                final_termination,
+                String::new(),
+            )
+        })
     }
 
     /// Terminates the child process.
@@ -1398,6 +1551,12 @@ fn strip_ansi(bytes: &[u8]) -> String {
     String::from_utf8_lossy(&stripped).into_owned()
 }
 
+/// Time left before the idle timeout fires, given the last activity and the
+/// current time; zero once it has passed.
+fn idle_remaining(timeout: Duration, last_activity: Instant, now: Instant) -> Duration {
+    timeout.saturating_sub(now.saturating_duration_since(last_activity))
+}
+
 /// Determines the final termination type, accounting for SIGINT exit code.
 ///
 /// Exit code 130 indicates the process was killed by SIGINT (Ctrl+C forwarded to PTY).
@@ -1410,33 +1569,56 @@ fn resolve_termination_type(exit_code: i32, default: TerminationType) -> Termina
     }
 }
 
+/// State accumulated while consuming a Claude NDJSON stream.
+#[derive(Default)]
+struct StreamSession {
+    /// Text content, used for event parsing.
+    extracted_text: String,
+    /// Per-sub-agent token usage for the completion summary.
+    sub_agents: SubAgentTracker,
+    /// Cost reported by the final `result` event.
+    total_cost_usd: f64,
+}
+
 /// Dispatches a Claude stream event to the appropriate handler method.
-/// Also accumulates text content into `extracted_text` for event parsing.
+/// Also accumulates text, cost and sub-agent usage into `session`.
 fn dispatch_stream_event<H: StreamHandler>(
     event: ClaudeStreamEvent,
     handler: &mut H,
-    extracted_text: &mut String,
+    session: &mut StreamSession,
 ) {
     match event {
-        ClaudeStreamEvent::System { .. } => {
-            // Session initialization - could log in verbose mode but not user-facing
+        ClaudeStreamEvent::System { subtype, .. } => {
+            // Session initialization is not user-facing; a compaction is
+            if subtype.as_deref() == Some("compact_boundary") {
+                handler.on_warning("Context compacted: earlier conversation was summarized");
+            }
         }
-        ClaudeStreamEvent::Assistant { message, .. } => {
+        ClaudeStreamEvent::Assistant {
+            message,
+            usage,
+            parent_tool_use_id,
+        } => {
+            let parent = parent_tool_use_id.as_deref();
+            if let Some(usage) = usage.as_ref().or(message.usage.as_ref()) {
+                session.sub_agents.on_usage(usage, parent);
+            }
             for block in message.content {
                 match block {
                     ContentBlock::Text { text } => {
                         handler.on_text(&text);
                         // Accumulate text for event parsing
-                        extracted_text.push_str(&text);
-                        extracted_text.push('\n');
+                        session.extracted_text.push_str(&text);
+                        session.extracted_text.push('\n');
                     }
                     ContentBlock::ToolUse { name, id, input } => {
+                        session.sub_agents.on_tool_call(&name, &id, &input, parent);
                         handler.on_tool_call(&name, &id, &input)
                     }
                 }
             }
         }
-        ClaudeStreamEvent::User { message } => {
+        ClaudeStreamEvent::User { message, .. } => {
             for block in message.content {
                 match block {
                     UserContentBlock::ToolResult {
@@ -1457,11 +1639,13 @@ fn dispatch_stream_event<H: StreamHandler>(
             if is_error {
                 handler.on_error("Session ended with error");
             }
+            session.total_cost_usd = total_cost_usd;
             handler.on_complete(&SessionResult {
                 duration_ms,
                 total_cost_usd,
                 num_turns,
                 is_error,
+                sub_agents: session.sub_agents.breakdown(total_cost_usd),
             });
         }
     }
@@ -1489,6 +1673,9 @@ fn build_result(
         success,
         exit_code,
         termination,
+        total_cost_usd: 0.0,
+        total_tokens: 0,
+        checkpoint: None,
     }
 }
 
@@ -1604,26 +1791,31 @@ mod tests {
     /// The actual reset happens in the select! branches at lines 497, 523, and 545.
     #[test]
     fn test_idle_timeout_reset_logic() {
-        // Simulate the timeout calculation used in run_interactive
+        let clock = ralph_core::MockClock::new();
         let timeout_duration = Duration::from_secs(30);
+        let last_activity = clock.now();
 
-        // Simulate 25 seconds of inactivity
-        let simulated_25s = Duration::from_secs(25);
-
-        // Remaining time before timeout
-        let remaining = timeout_duration.saturating_sub(simulated_25s);
-        assert_eq!(remaining.as_secs(), 5);
-
-        // After activity (output or input), last_activity would be reset to now
-        let last_activity_after_reset = Instant::now();
+        // 25 seconds of inactivity leave 5 before the timeout
+        clock.advance(Duration::from_secs(25));
+        assert_eq!(
+            idle_remaining(timeout_duration, last_activity, clock.now()),
+            Duration::from_secs(5)
+        );
 
-        // Now elapsed is 0, full timeout duration available again
-        let elapsed = last_activity_after_reset.elapsed();
-        assert!(elapsed < Duration::from_millis(100)); // Should be near-zero
+        // Past the timeout it fires immediately
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(
+            idle_remaining(timeout_duration, last_activity, clock.now()),
+            Duration::ZERO
+        );
 
-        // Timeout calculation would give full duration minus small elapsed
-        let new_remaining = timeout_duration.saturating_sub(elapsed);
-        assert!(new_remaining > Duration::from_secs(29)); // Should be nearly full timeout
+        // After activity (output or input), last_activity is reset to now and
+        // the full timeout is available again
+        let last_activity_after_reset = clock.now();
+        assert_eq!(
+            idle_remaining(timeout_duration, last_activity_after_reset, clock.now()),
+            timeout_duration
+        );
     }
 
     #[test]
@@ -1637,6 +1829,9 @@ mod tests {
             success: true,
             exit_code: Some(0),
             termination: TerminationType::Natural,
+            total_cost_usd: 0.0,
+            total_tokens: 0,
+            checkpoint: None,
         };
 
         assert!(
@@ -1663,6 +1858,37 @@ mod tests {
         assert!(result.stripped_output.contains("raw output"));
     }
 
+    #[test]
+    fn test_compacted_context_is_a_warning() {
+        let mut handler = crate::TuiStreamHandler::new(false);
+        let mut session = StreamSession::default();
+        let event = ClaudeStreamParser::parse_line(
+            r#"{"type":"system","subtype":"compact_boundary","session_id":"abc123"}"#,
+        )
+        .unwrap();
+
+        dispatch_stream_event(event, &mut handler, &mut session);
+
+        let lines = handler.get_lines();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].to_string().contains("Warning: Context compacted"));
+    }
+
+    #[test]
+    fn test_result_event_records_session_cost() {
+        // The loop adds this to the spend the footer's budget segment shows
+        let mut handler = crate::TuiStreamHandler::new(false);
+        let mut session = StreamSession::default();
+        let event = ClaudeStreamParser::parse_line(
+            r#"{"type":"result","duration_ms":5000,"total_cost_usd":0.42,"num_turns":2,"is_error":false}"#,
+        )
+        .unwrap();
+
+        dispatch_stream_event(event, &mut handler, &mut session);
+
+        assert!((session.total_cost_usd - 0.42).abs() < f64::EPSILON);
+    }
+
     /// Regression test: TUI mode should not spawn stdin reader thread
     ///
     /// Bug: In TUI mode, Ctrl+C required double-press to exit because the stdin
@@ -1738,4 +1964,118 @@ mod tests {
             "tui_mode should be false after set_tui_mode(false)"
         );
     }
+
+    #[test]
+    fn test_checkpoint_summary_skips_echoed_instruction() {
+        let instruction = "Summarize between <checkpoint> and </checkpoint> tags.";
+        let echo = format!("{instruction}\r\n");
+
+        assert_eq!(checkpoint_summary(&echo, instruction), None);
+        assert_eq!(
+            checkpoint_summary(
+                &format!("{echo}<checkpoint>\n  parser half done\n</checkpoint>\r\n"),
+                instruction
+            ),
+            Some("parser half done".to_string())
+        );
+        // An unterminated summary isn't complete yet
+        assert_eq!(
+            checkpoint_summary(&format!("{echo}<checkpoint>parser"), instruction),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_interrupt_asks_interactive_session_to_wrap_up() {
+        use crate::cli_backend::PromptMode;
+
+        // Answers the first line it reads, then keeps working
+        let backend = CliBackend {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "read _; echo '<checkpoint>parser half done</checkpoint>'; sleep 30".to_string(),
+            ],
+            prompt_mode: PromptMode::Arg,
+            prompt_flag: None,
+            output_format: OutputFormat::Text,
+        };
+        let config = PtyConfig {
+            idle_timeout_secs: 0,
+            checkpoint: Some(Checkpoint {
+                instruction: "Summarize between <checkpoint> and </checkpoint> tags.".to_string(),
+                timeout: Duration::from_secs(10),
+            }),
+            ..PtyConfig::default()
+        };
+        let mut executor = PtyExecutor::new(backend, config);
+        executor.set_tui_mode(true);
+
+        // The sender stays alive, as the event loop's does: a closed channel
+        // counts as another interrupt
+        let (interrupt_tx, interrupt_rx) = watch::channel(false);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let _ = interrupt_tx.send(true);
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        });
+
+        let started = Instant::now();
+        let result = executor
+            .run_interactive("prompt", interrupt_rx)
+            .await
+            .unwrap();
+
+        assert_eq!(result.checkpoint.as_deref(), Some("parser half done"));
+        assert_eq!(result.termination, TerminationType::UserInterrupt);
+        assert!(started.elapsed() < Duration::from_secs(8));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_wrap_up_signal_checkpoints_without_interrupting() {
+        use crate::cli_backend::PromptMode;
+
+        let backend = CliBackend {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "read _; echo '<checkpoint>tests pass</checkpoint>'; sleep 30".to_string(),
+            ],
+            prompt_mode: PromptMode::Arg,
+            prompt_flag: None,
+            output_format: OutputFormat::Text,
+        };
+        let config = PtyConfig {
+            idle_timeout_secs: 0,
+            checkpoint: Some(Checkpoint {
+                instruction: "Summarize between <checkpoint> and </checkpoint> tags.".to_string(),
+                timeout: Duration::from_secs(10),
+            }),
+            ..PtyConfig::default()
+        };
+        let mut executor = PtyExecutor::new(backend, config);
+        executor.set_tui_mode(true);
+
+        let (wrap_up_tx, wrap_up_rx) = watch::channel(false);
+        executor.set_wrap_up_signal(wrap_up_rx);
+        let (interrupt_tx, interrupt_rx) = watch::channel(false);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let _ = wrap_up_tx.send(true);
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            drop(interrupt_tx);
+        });
+
+        let started = Instant::now();
+        let result = executor
+            .run_interactive("prompt", interrupt_rx)
+            .await
+            .unwrap();
+
+        assert_eq!(result.checkpoint.as_deref(), Some("tests pass"));
+        assert_eq!(result.termination, TerminationType::Natural);
+        assert!(started.elapsed() < Duration::from_secs(8));
+    }
 }
@@ -0,0 +1,384 @@
+//! Adapters by name.
+//!
+//! Config and CLI flags pick a backend with a string (`cli.backend`,
+//! `--backend`, a hat's `backend:`). The registry maps those names to
+//! backends: the built-in adapters, plugin adapters defined under
+//! `adapters.plugins` in `ralph.yml`, and any an embedder registers. The
+//! loop only ever asks the registry, so a new adapter needs no code changes
+//! elsewhere.
+
+use crate::cli_backend::{CliBackend, OutputFormat, PromptMode};
+use ralph_core::{CliConfig, HatBackend, PluginAdapterConfig, RalphConfig};
+use std::fmt;
+use std::sync::Arc;
+
+type BackendFactory = Arc<dyn Fn() -> CliBackend + Send + Sync>;
+
+/// What an adapter's backend supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AdapterCapabilities {
+    /// Streams structured events (tool calls, cost, turns) instead of text.
+    pub stream_json: bool,
+    /// Can open an interactive session with an initial prompt, as
+    /// `ralph plan` and `ralph code-task` do.
+    pub interactive: bool,
+    /// How the prompt is handed to the command.
+    pub prompt_mode: PromptMode,
+}
+
+/// A named adapter.
+#[derive(Clone)]
+pub struct AdapterEntry {
+    name: String,
+    description: String,
+    builtin: bool,
+    headless: BackendFactory,
+    interactive: Option<BackendFactory>,
+}
+
+impl AdapterEntry {
+    /// An adapter named `name` running the backend `headless` builds.
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        headless: impl Fn() -> CliBackend + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            builtin: false,
+            headless: Arc::new(headless),
+            interactive: None,
+        }
+    }
+
+    /// Adds the backend used for interactive sessions.
+    #[must_use]
+    pub fn with_interactive(
+        mut self,
+        interactive: impl Fn() -> CliBackend + Send + Sync + 'static,
+    ) -> Self {
+        self.interactive = Some(Arc::new(interactive));
+        self
+    }
+
+    /// The adapter for a plugin defined in config.
+    pub fn from_plugin(name: &str, plugin: &PluginAdapterConfig) -> Self {
+        let backend = CliBackend {
+            command: plugin.command.clone(),
+            args: plugin.args.clone(),
+            prompt_mode: plugin.prompt_mode,
+            prompt_flag: plugin.prompt_flag.clone(),
+            output_format: OutputFormat::Text,
+        };
+        let description = plugin
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("`{}`", plugin.command));
+        Self::new(name, description, move || backend.clone())
+    }
+
+    fn builtin(mut self) -> Self {
+        self.builtin = true;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Whether the adapter ships with Ralph rather than being a plugin.
+    pub fn is_builtin(&self) -> bool {
+        self.builtin
+    }
+
+    /// The backend for autonomous iterations.
+    pub fn backend(&self) -> CliBackend {
+        (self.headless)()
+    }
+
+    /// The backend for interactive sessions, if the adapter has one.
+    pub fn interactive_backend(&self) -> Option<CliBackend> {
+        self.interactive.as_ref().map(|factory| factory())
+    }
+
+    pub fn capabilities(&self) -> AdapterCapabilities {
+        let backend = self.backend();
+        AdapterCapabilities {
+            stream_json: backend.output_format == OutputFormat::StreamJson,
+            interactive: self.interactive.is_some(),
+            prompt_mode: backend.prompt_mode,
+        }
+    }
+}
+
+impl fmt::Debug for AdapterEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdapterEntry")
+            .field("name", &self.name)
+            .field("builtin", &self.builtin)
+            .field("backend", &self.backend())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Why no backend could be created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AdapterError {
+    /// No adapter is registered under the name.
+    Unknown {
+        name: String,
+        available: Vec<String>,
+    },
+    /// The adapter has no interactive mode.
+    NotInteractive(String),
+    /// `custom` was selected without a command.
+    MissingCommand,
+}
+
+impl fmt::Display for AdapterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown { name, available } => write!(
+                f,
+                "Unknown backend: {name}\n\nValid backends: {}",
+                available.join(", ")
+            ),
+            Self::NotInteractive(name) => {
+                write!(f, "backend '{name}' has no interactive mode")
+            }
+            Self::MissingCommand => {
+                write!(f, "custom backend requires a command to be specified")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AdapterError {}
+
+/// Adapters by name, in registration order.
+#[derive(Debug, Clone, Default)]
+pub struct AdapterRegistry {
+    entries: Vec<AdapterEntry>,
+}
+
+impl AdapterRegistry {
+    /// The adapters that ship with Ralph.
+    pub fn builtin() -> Self {
+        let entries = [
+            AdapterEntry::new("claude", "Claude Code", CliBackend::claude)
+                .with_interactive(CliBackend::claude_interactive),
+            AdapterEntry::new("kiro", "Kiro CLI", CliBackend::kiro)
+                .with_interactive(CliBackend::kiro_interactive),
+            AdapterEntry::new("gemini", "Gemini CLI", CliBackend::gemini)
+                .with_interactive(CliBackend::gemini_interactive),
+            AdapterEntry::new("codex", "OpenAI Codex CLI", CliBackend::codex)
+                .with_interactive(CliBackend::codex_interactive),
+            AdapterEntry::new("amp", "Amp", CliBackend::amp)
+                .with_interactive(CliBackend::amp_interactive),
+            AdapterEntry::new("copilot", "GitHub Copilot CLI", CliBackend::copilot)
+                .with_interactive(CliBackend::copilot_interactive),
+            AdapterEntry::new("opencode", "OpenCode", CliBackend::opencode)
+                .with_interactive(CliBackend::opencode_interactive),
+        ];
+        Self {
+            entries: entries.into_iter().map(AdapterEntry::builtin).collect(),
+        }
+    }
+
+    /// The built-in adapters plus the plugins in `config`.
+    pub fn from_config(config: &RalphConfig) -> Self {
+        let mut registry = Self::builtin();
+        for (name, plugin) in &config.adapters.plugins {
+            registry.register(AdapterEntry::from_plugin(name, plugin));
+        }
+        registry
+    }
+
+    /// Adds an adapter, replacing any registered under the same name.
+    pub fn register(&mut self, entry: AdapterEntry) {
+        match self.entries.iter_mut().find(|e| e.name == entry.name) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AdapterEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &AdapterEntry> {
+        self.entries.iter()
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    fn lookup(&self, name: &str) -> Result<&AdapterEntry, AdapterError> {
+        self.get(name).ok_or_else(|| AdapterError::Unknown {
+            name: name.to_string(),
+            available: self.names().into_iter().map(String::from).collect(),
+        })
+    }
+
+    /// The backend `cli.backend` names, with `cli.command` overriding the
+    /// command of a named adapter and `cli.model` selecting its model.
+    pub fn backend(&self, cli: &CliConfig) -> Result<CliBackend, AdapterError> {
+        let mut backend = if cli.backend == "custom" {
+            CliBackend::custom(cli).map_err(|_| AdapterError::MissingCommand)?
+        } else {
+            let mut backend = self.lookup(&cli.backend)?.backend();
+            if let Some(command) = &cli.command {
+                backend.command.clone_from(command);
+            }
+            backend
+        };
+        if let Some(model) = &cli.model {
+            backend.set_model(model);
+        }
+        Ok(backend)
+    }
+
+    /// The backend for the adapter named `name`.
+    pub fn backend_named(&self, name: &str) -> Result<CliBackend, AdapterError> {
+        Ok(self.lookup(name)?.backend())
+    }
+
+    /// The backend a hat's `backend:` setting selects.
+    pub fn hat_backend(&self, hat_backend: &HatBackend) -> Result<CliBackend, AdapterError> {
+        match hat_backend {
+            HatBackend::Named(name) => self.backend_named(name),
+            HatBackend::NamedWithArgs { backend_type, args } => {
+                let mut backend = self.backend_named(backend_type)?;
+                backend.args.extend(args.iter().cloned());
+                Ok(backend)
+            }
+            HatBackend::KiroAgent { agent, args, .. } => {
+                Ok(CliBackend::kiro_with_agent(agent.clone(), args))
+            }
+            HatBackend::Custom { command, args } => Ok(CliBackend {
+                command: command.clone(),
+                args: args.clone(),
+                prompt_mode: PromptMode::Arg,
+                prompt_flag: None,
+                output_format: OutputFormat::Text,
+            }),
+        }
+    }
+
+    /// The interactive backend of the adapter named `name`.
+    pub fn interactive_backend(&self, name: &str) -> Result<CliBackend, AdapterError> {
+        self.lookup(name)?
+            .interactive_backend()
+            .ok_or_else(|| AdapterError::NotInteractive(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin(command: &str) -> PluginAdapterConfig {
+        serde_json::from_value(serde_json::json!({
+            "command": command,
+            "prompt_mode": "stdin",
+            "timeout": 60,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_builtin_adapters_and_capabilities() {
+        let registry = AdapterRegistry::builtin();
+        assert_eq!(
+            registry.names(),
+            [
+                "claude", "kiro", "gemini", "codex", "amp", "copilot", "opencode"
+            ]
+        );
+        let claude = registry.get("claude").unwrap();
+        assert!(claude.is_builtin());
+        assert!(claude.capabilities().stream_json);
+        assert!(claude.capabilities().interactive);
+        assert!(!registry.get("gemini").unwrap().capabilities().stream_json);
+        assert_eq!(
+            registry.interactive_backend("gemini").unwrap().prompt_flag,
+            Some("-i".to_string())
+        );
+    }
+
+    #[test]
+    fn test_plugin_prompt_mode_typo_is_rejected() {
+        let parsed = serde_json::from_value::<PluginAdapterConfig>(serde_json::json!({
+            "command": "aider",
+            "prompt_mode": "stdn",
+        }));
+        assert!(parsed.is_err());
+
+        let default: PluginAdapterConfig =
+            serde_json::from_value(serde_json::json!({ "command": "aider" })).unwrap();
+        assert_eq!(default.prompt_mode, PromptMode::Arg);
+    }
+
+    #[test]
+    fn test_plugins_are_selected_by_name() {
+        let mut config = RalphConfig::default();
+        config
+            .adapters
+            .plugins
+            .insert("aider".to_string(), plugin("aider"));
+        let registry = AdapterRegistry::from_config(&config);
+
+        config.cli.backend = "aider".to_string();
+        let backend = registry.backend(&config.cli).unwrap();
+        assert_eq!(backend.command, "aider");
+        assert_eq!(backend.prompt_mode, PromptMode::Stdin);
+        assert!(!registry.get("aider").unwrap().is_builtin());
+        assert_eq!(config.adapter_settings("aider").timeout, 60);
+
+        let hat = HatBackend::NamedWithArgs {
+            backend_type: "aider".to_string(),
+            args: vec!["--yes".to_string()],
+        };
+        assert_eq!(registry.hat_backend(&hat).unwrap().args, ["--yes"]);
+        assert_eq!(
+            registry.interactive_backend("aider").unwrap_err(),
+            AdapterError::NotInteractive("aider".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unknown_backend_lists_the_available_ones() {
+        let mut cli = CliConfig::default();
+        cli.backend = "nope".to_string();
+        let err = AdapterRegistry::builtin().backend(&cli).unwrap_err();
+        assert!(err.to_string().contains("Valid backends: claude, kiro"));
+
+        cli.backend = "custom".to_string();
+        assert_eq!(
+            AdapterRegistry::builtin().backend(&cli).unwrap_err(),
+            AdapterError::MissingCommand
+        );
+    }
+
+    #[test]
+    fn test_register_replaces_by_name() {
+        let mut registry = AdapterRegistry::builtin();
+        registry.register(AdapterEntry::new("claude", "Wrapped", || CliBackend {
+            command: "claude-wrapper".to_string(),
+            ..CliBackend::claude()
+        }));
+        assert_eq!(registry.names().len(), 7);
+        assert_eq!(
+            registry.backend_named("claude").unwrap().command,
+            "claude-wrapper"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Smooth streaming: bursty text re-emitted at a steady character rate.
+//!
+//! Backends deliver text in bursts, a few words and then a whole paragraph
+//! at once, which makes live output jumpy to read. [`SmoothStream`] spreads
+//! each chunk over the time it would take at a steady `chars_per_second`.
+//!
+//! Pacing delays the output, so the delay owed is capped at `max_lag`. Time
+//! the backend spends quiet pays it back; while the display is already
+//! `max_lag` behind, chunks are shown at once, so it never falls further
+//! behind the stream.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Interval between the pieces of a chunk.
+const FRAME: Duration = Duration::from_millis(25);
+
+/// Paces text written through [`SmoothStream::emit`].
+#[derive(Debug)]
+pub struct SmoothStream {
+    chars_per_second: u32,
+    max_lag: Duration,
+    /// How far the display is behind the stream.
+    lag: Duration,
+    /// When the last chunk was finished.
+    last_emit: Option<Instant>,
+}
+
+impl SmoothStream {
+    /// Paces output at `chars_per_second`, never more than `max_lag` behind.
+    pub fn new(chars_per_second: u32, max_lag: Duration) -> Self {
+        Self {
+            chars_per_second,
+            max_lag,
+            lag: Duration::ZERO,
+            last_emit: None,
+        }
+    }
+
+    /// Passes `text` to `write` in pieces spread over its share of time.
+    ///
+    /// Blocks until the last piece is written.
+    pub fn emit(&mut self, text: &str, mut write: impl FnMut(&str)) {
+        let idle = self.last_emit.map_or(Duration::MAX, |last| last.elapsed());
+        let chars = text.chars().count();
+        let steps = (self.pace(chars, idle).as_millis() / FRAME.as_millis()) as usize;
+
+        if steps <= 1 {
+            write(text);
+        } else {
+            for (i, piece) in pieces(text, chars.div_ceil(steps)).enumerate() {
+                if i > 0 {
+                    thread::sleep(FRAME);
+                }
+                write(piece);
+            }
+        }
+        self.last_emit = Some(Instant::now());
+    }
+
+    /// Returns how long to spread `chars` over, given that the stream was
+    /// quiet for `idle` since the last chunk.
+    fn pace(&mut self, chars: usize, idle: Duration) -> Duration {
+        self.lag = self.lag.saturating_sub(idle);
+        if self.chars_per_second == 0 {
+            return Duration::ZERO;
+        }
+        let wanted = Duration::from_secs_f64(chars as f64 / f64::from(self.chars_per_second));
+        let spread = wanted.min(self.max_lag.saturating_sub(self.lag));
+        self.lag += spread;
+        spread
+    }
+}
+
+/// Splits `text` into pieces of `size` characters.
+fn pieces(text: &str, size: usize) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest
+            .char_indices()
+            .nth(size)
+            .map_or(rest.len(), |(index, _)| index);
+        let (piece, tail) = rest.split_at(end);
+        rest = tail;
+        Some(piece)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pace_spreads_chunks_and_caps_lag() {
+        let mut smooth = SmoothStream::new(100, Duration::from_secs(1));
+
+        // 50 chars at 100/s take half a second
+        assert_eq!(smooth.pace(50, Duration::MAX), Duration::from_millis(500));
+        // Arriving while still behind, only the rest of the lag budget is used
+        assert_eq!(smooth.pace(200, Duration::ZERO), Duration::from_millis(500));
+        // At the cap, chunks are shown at once
+        assert_eq!(smooth.pace(10, Duration::ZERO), Duration::ZERO);
+        // A quiet stream pays the lag back
+        assert_eq!(
+            smooth.pace(10, Duration::from_millis(300)),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[test]
+    fn test_emit_writes_all_text_in_order() {
+        let mut smooth = SmoothStream::new(500, Duration::from_secs(1));
+        let mut written = Vec::new();
+        smooth.emit("héllo wörld, streaming smoothly", |piece| {
+            written.push(piece.to_string());
+        });
+
+        assert!(written.len() > 1, "{written:?}");
+        assert_eq!(written.concat(), "héllo wörld, streaming smoothly");
+    }
+
+    #[test]
+    fn test_zero_rate_writes_at_once() {
+        let mut smooth = SmoothStream::new(0, Duration::from_secs(1));
+        let mut written = Vec::new();
+        smooth.emit("text", |piece| written.push(piece.to_string()));
+        assert_eq!(written, ["text"]);
+    }
+}
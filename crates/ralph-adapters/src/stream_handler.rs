@@ -8,14 +8,25 @@ use crossterm::{
     QueueableCommand,
     style::{self, Color},
 };
+use ralph_core::ToolResultStore;
+use ralph_core::diagnostics::{AgentOutputContent, DiagnosticStreamHandler};
+use ralph_proto::HatId;
 use ratatui::{
-    style::{Color as RatatuiColor, Style},
+    style::{Color as RatatuiColor, Modifier, Style},
     text::{Line, Span},
 };
+use std::collections::HashMap;
 use std::io::{self, Write};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use termimad::MadSkin;
 
+use crate::edit_diff::{Change, EditDiff, MAX_INLINE_LINES};
+use crate::hat_color::{hat_color, term_color};
+use crate::smooth_stream::SmoothStream;
+use crate::sub_agent::SubAgentUsage;
+use crate::tool_summary::ToolSummaries;
+
 /// Detects if text contains ANSI escape sequences.
 ///
 /// Checks for the common ANSI escape sequence prefix `\x1b[` (ESC + `[`)
@@ -27,11 +38,68 @@ pub(crate) fn contains_ansi(text: &str) -> bool {
 
 /// Session completion result data.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct SessionResult {
     pub duration_ms: u64,
     pub total_cost_usd: f64,
     pub num_turns: u32,
     pub is_error: bool,
+    /// Token/cost breakdown for sub-agents spawned via the `Task` tool.
+    pub sub_agents: Vec<SubAgentUsage>,
+}
+
+/// The iteration a handler's events belong to, passed to the iteration
+/// lifecycle hooks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct IterationInfo {
+    /// 1-based iteration number.
+    pub iteration: u32,
+    /// Hat the iteration runs as.
+    pub hat: String,
+    /// Short fingerprint of the prompt (empty when unknown), so iterations
+    /// that were given the same prompt can be told apart from changed ones.
+    pub prompt_hash: String,
+}
+
+impl SessionResult {
+    /// A session without sub-agents.
+    pub fn new(duration_ms: u64, total_cost_usd: f64, num_turns: u32, is_error: bool) -> Self {
+        Self {
+            duration_ms,
+            total_cost_usd,
+            num_turns,
+            is_error,
+            sub_agents: Vec::new(),
+        }
+    }
+}
+
+impl IterationInfo {
+    /// Describes iteration `iteration` running `prompt` as `hat`; pass an
+    /// empty prompt when it isn't known.
+    pub fn new(iteration: u32, hat: impl Into<String>, prompt: &str) -> Self {
+        Self {
+            iteration,
+            hat: hat.into(),
+            prompt_hash: if prompt.is_empty() {
+                String::new()
+            } else {
+                prompt_hash(prompt)
+            },
+        }
+    }
+}
+
+/// FNV-1a digest of `prompt` as 16 hex digits; stable across builds, unlike
+/// `DefaultHasher`.
+fn prompt_hash(prompt: &str) -> String {
+    let hash = prompt
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{hash:016x}")
 }
 
 /// Renders streaming output with colors and markdown.
@@ -42,6 +110,14 @@ pub struct PrettyStreamHandler {
     text_buffer: String,
     /// Skin for markdown rendering
     skin: MadSkin,
+    /// User-configured tool summary templates
+    tool_summaries: ToolSummaries,
+    /// Paces rendered text when smooth streaming is on
+    smooth: Option<SmoothStream>,
+    /// Configured hat colors (from the `color` field of hat config)
+    hat_colors: HashMap<HatId, RatatuiColor>,
+    /// Color of the gutter marking the current hat's text
+    gutter: Option<Color>,
 }
 
 impl PrettyStreamHandler {
@@ -52,18 +128,64 @@ impl PrettyStreamHandler {
             verbose,
             text_buffer: String::new(),
             skin: MadSkin::default(),
+            tool_summaries: ToolSummaries::default(),
+            smooth: None,
+            hat_colors: HashMap::new(),
+            gutter: None,
         }
     }
 
+    /// Sets custom tool summary templates (from `tool_summaries` in config).
+    #[must_use]
+    pub fn with_tool_summaries(mut self, tool_summaries: ToolSummaries) -> Self {
+        self.tool_summaries = tool_summaries;
+        self
+    }
+
+    /// Writes rendered text at a steady pace instead of all at once.
+    #[must_use]
+    pub fn with_smooth_streaming(mut self, smooth: SmoothStream) -> Self {
+        self.smooth = Some(smooth);
+        self
+    }
+
+    /// Sets per-hat colors for the gutter beside each iteration's text.
+    #[must_use]
+    pub fn with_hat_colors(mut self, colors: HashMap<HatId, RatatuiColor>) -> Self {
+        self.hat_colors = colors;
+        self
+    }
+
     /// Flush buffered text as rendered markdown.
     fn flush_text_buffer(&mut self) {
         if self.text_buffer.is_empty() {
             return;
         }
         // Render markdown to string, then write
-        let rendered = self.skin.term_text(&self.text_buffer);
-        let _ = self.stdout.write(rendered.to_string().as_bytes());
-        let _ = self.stdout.flush();
+        let rendered = match self.gutter {
+            Some(color) => {
+                let width = crossterm::terminal::size().map_or(80, |(width, _)| width as usize);
+                let text = self
+                    .skin
+                    .text(&self.text_buffer, Some(width.saturating_sub(2)))
+                    .to_string();
+                with_gutter(&text, color)
+            }
+            None => self.skin.term_text(&self.text_buffer).to_string(),
+        };
+        match self.smooth.as_mut() {
+            Some(smooth) => {
+                let stdout = &mut self.stdout;
+                smooth.emit(&rendered, |piece| {
+                    let _ = stdout.write(piece.as_bytes());
+                    let _ = stdout.flush();
+                });
+            }
+            None => {
+                let _ = self.stdout.write(rendered.as_bytes());
+                let _ = self.stdout.flush();
+            }
+        }
         self.text_buffer.clear();
     }
 }
@@ -99,6 +221,16 @@ impl StreamHandler for PrettyStreamHandler {
         let _ = self.stdout.flush();
     }
 
+    fn on_warning(&mut self, warning: &str) {
+        self.flush_text_buffer();
+        let _ = self.stdout.queue(style::SetForegroundColor(Color::Yellow));
+        let _ = self
+            .stdout
+            .write(format!("\u{26a0} Warning: {}\n", warning).as_bytes());
+        let _ = self.stdout.queue(style::ResetColor);
+        let _ = self.stdout.flush();
+    }
+
     fn on_complete(&mut self, result: &SessionResult) {
         // Flush any remaining buffered text
         self.flush_text_buffer();
@@ -117,6 +249,14 @@ impl StreamHandler for PrettyStreamHandler {
             )
             .as_bytes(),
         );
+        if !result.sub_agents.is_empty() {
+            let _ = self
+                .stdout
+                .queue(style::SetForegroundColor(Color::DarkGrey));
+            for line in format_sub_agent_breakdown(result) {
+                let _ = self.stdout.write(format!("{}\n", line).as_bytes());
+            }
+        }
         let _ = self.stdout.queue(style::ResetColor);
         let _ = self.stdout.flush();
     }
@@ -129,7 +269,7 @@ impl StreamHandler for PrettyStreamHandler {
         let _ = self.stdout.queue(style::SetForegroundColor(Color::Blue));
         let _ = self.stdout.write(format!("\u{2699} [{}]", name).as_bytes());
 
-        if let Some(summary) = format_tool_summary(name, input) {
+        if let Some(summary) = summarize_tool(&self.tool_summaries, name, input) {
             let _ = self
                 .stdout
                 .queue(style::SetForegroundColor(Color::DarkGrey));
@@ -137,9 +277,67 @@ impl StreamHandler for PrettyStreamHandler {
         } else {
             let _ = self.stdout.write(b"\n");
         }
+
+        // Word-level diff of an edit, under the tool call
+        if let Some(diff) = EditDiff::from_input(input) {
+            for line in diff.lines.iter().take(MAX_INLINE_LINES) {
+                let _ = self.stdout.write(b"    ");
+                for (kind, text) in line {
+                    let color = match kind {
+                        Change::Same => Color::DarkGrey,
+                        Change::Removed => Color::Red,
+                        Change::Added => Color::Green,
+                    };
+                    let _ = self.stdout.queue(style::SetForegroundColor(color));
+                    let _ = self.stdout.write(text.as_bytes());
+                }
+                let _ = self.stdout.write(b"\n");
+            }
+            if let Some(more) = more_diff_lines(&diff) {
+                let _ = self
+                    .stdout
+                    .queue(style::SetForegroundColor(Color::DarkGrey));
+                let _ = self.stdout.write(format!("    {more}\n").as_bytes());
+            }
+        }
         let _ = self.stdout.queue(style::ResetColor);
         let _ = self.stdout.flush();
     }
+
+    fn on_iteration_start(&mut self, info: &IterationInfo) {
+        self.flush_text_buffer();
+        self.gutter = Some(term_color(hat_color(&self.hat_colors, &info.hat)));
+    }
+
+    fn on_iteration_end(&mut self, info: &IterationInfo, _success: bool) {
+        self.flush_text_buffer();
+        let color = self.gutter.take().unwrap_or(Color::DarkGrey);
+        let _ = self.stdout.queue(style::SetForegroundColor(color));
+        let _ = self.stdout.write(iteration_end_marker(info).as_bytes());
+        let _ = self.stdout.queue(style::ResetColor);
+        let _ = self.stdout.flush();
+    }
+}
+
+/// Prefixes each line of rendered text with a bar in the hat's color.
+fn with_gutter(text: &str, color: Color) -> String {
+    use crossterm::style::Stylize;
+    use std::fmt::Write as _;
+
+    let bar = style::style("\u{258e}").with(color);
+    let mut out = String::new();
+    for line in text.split_inclusive('\n') {
+        let _ = write!(out, "{bar} {line}");
+    }
+    out
+}
+
+/// The line closing an iteration's output in console modes.
+fn iteration_end_marker(info: &IterationInfo) -> String {
+    format!(
+        "\n--- end of iteration {} ({}) ---\n",
+        info.iteration, info.hat
+    )
 }
 
 /// Handler for streaming output events from Claude.
@@ -164,8 +362,20 @@ pub trait StreamHandler: Send {
     /// Called when an error occurs.
     fn on_error(&mut self, error: &str);
 
+    /// Called for a non-fatal condition worth pointing out (a retry, a
+    /// compacted context); unlike `on_error`, it says nothing about whether
+    /// the session fails.
+    fn on_warning(&mut self, _warning: &str) {}
+
     /// Called when session completes (verbose only).
     fn on_complete(&mut self, result: &SessionResult);
+
+    /// Called before the backend starts on an iteration.
+    fn on_iteration_start(&mut self, _info: &IterationInfo) {}
+
+    /// Called once the iteration's session has ended, whether or not it
+    /// succeeded.
+    fn on_iteration_end(&mut self, _info: &IterationInfo, _success: bool) {}
 }
 
 /// Writes streaming output to stdout/stderr.
@@ -178,6 +388,8 @@ pub struct ConsoleStreamHandler {
     stderr: io::Stderr,
     /// Tracks whether last output ended with a newline
     last_was_newline: bool,
+    /// User-configured tool summary templates
+    tool_summaries: ToolSummaries,
 }
 
 impl ConsoleStreamHandler {
@@ -191,9 +403,17 @@ impl ConsoleStreamHandler {
             stdout: io::stdout(),
             stderr: io::stderr(),
             last_was_newline: true, // Start true so first output doesn't get extra newline
+            tool_summaries: ToolSummaries::default(),
         }
     }
 
+    /// Sets custom tool summary templates (from `tool_summaries` in config).
+    #[must_use]
+    pub fn with_tool_summaries(mut self, tool_summaries: ToolSummaries) -> Self {
+        self.tool_summaries = tool_summaries;
+        self
+    }
+
     /// Ensures output starts on a new line if the previous output didn't end with one.
     fn ensure_newline(&mut self) {
         if !self.last_was_newline {
@@ -211,7 +431,7 @@ impl StreamHandler for ConsoleStreamHandler {
 
     fn on_tool_call(&mut self, name: &str, _id: &str, input: &serde_json::Value) {
         self.ensure_newline();
-        match format_tool_summary(name, input) {
+        match summarize_tool(&self.tool_summaries, name, input) {
             Some(summary) => {
                 let _ = writeln!(self.stdout, "[Tool] {}: {}", name, summary);
             }
@@ -219,6 +439,15 @@ impl StreamHandler for ConsoleStreamHandler {
                 let _ = writeln!(self.stdout, "[Tool] {}", name);
             }
         }
+        if let Some(diff) = EditDiff::from_input(input) {
+            let plain = diff.to_plain();
+            for line in plain.lines().take(MAX_INLINE_LINES) {
+                let _ = writeln!(self.stdout, "    {}", line);
+            }
+            if let Some(more) = more_diff_lines(&diff) {
+                let _ = writeln!(self.stdout, "    {}", more);
+            }
+        }
         // writeln always ends with newline
         self.last_was_newline = true;
     }
@@ -235,6 +464,11 @@ impl StreamHandler for ConsoleStreamHandler {
         let _ = writeln!(self.stderr, "[Error] {}", error);
     }
 
+    fn on_warning(&mut self, warning: &str) {
+        self.ensure_newline();
+        let _ = writeln!(self.stdout, "[Warning] {}", warning);
+    }
+
     fn on_complete(&mut self, result: &SessionResult) {
         if self.verbose {
             let _ = writeln!(
@@ -242,8 +476,232 @@ impl StreamHandler for ConsoleStreamHandler {
                 "\n--- Session Complete ---\nDuration: {}ms | Est. cost: ${:.4} | Turns: {}",
                 result.duration_ms, result.total_cost_usd, result.num_turns
             );
+            for line in format_sub_agent_breakdown(result) {
+                let _ = writeln!(self.stdout, "{}", line);
+            }
         }
     }
+
+    fn on_iteration_end(&mut self, info: &IterationInfo, _success: bool) {
+        self.ensure_newline();
+        let _ = write!(self.stdout, "{}", iteration_end_marker(info));
+    }
+}
+
+/// Writes each stream event as one JSON object per line (NDJSON), for
+/// tools that consume a run's output.
+///
+/// Every object has a `type` (`text`, `tool_call`, `tool_result`, `error`,
+/// `warning`, `complete`, `iteration_start` or `iteration_end`) plus that
+/// event's fields.
+pub struct JsonStreamHandler<W: Write = io::Stdout> {
+    out: W,
+}
+
+impl JsonStreamHandler {
+    /// Creates a handler writing to stdout.
+    pub fn new() -> Self {
+        Self::with_writer(io::stdout())
+    }
+}
+
+impl Default for JsonStreamHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write> JsonStreamHandler<W> {
+    /// Creates a handler writing to `out`.
+    pub fn with_writer(out: W) -> Self {
+        Self { out }
+    }
+
+    /// Returns the writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn emit(&mut self, value: serde_json::Value) {
+        let _ = writeln!(self.out, "{value}");
+        let _ = self.out.flush();
+    }
+}
+
+impl<W: Write + Send> StreamHandler for JsonStreamHandler<W> {
+    fn on_text(&mut self, text: &str) {
+        self.emit(serde_json::json!({"type": "text", "text": text}));
+    }
+
+    fn on_tool_call(&mut self, name: &str, id: &str, input: &serde_json::Value) {
+        self.emit(serde_json::json!({
+            "type": "tool_call",
+            "name": name,
+            "id": id,
+            "input": input,
+        }));
+    }
+
+    fn on_tool_result(&mut self, id: &str, output: &str) {
+        self.emit(serde_json::json!({"type": "tool_result", "id": id, "output": output}));
+    }
+
+    fn on_error(&mut self, error: &str) {
+        self.emit(serde_json::json!({"type": "error", "message": error}));
+    }
+
+    fn on_warning(&mut self, warning: &str) {
+        self.emit(serde_json::json!({"type": "warning", "message": warning}));
+    }
+
+    fn on_complete(&mut self, result: &SessionResult) {
+        self.emit(serde_json::json!({
+            "type": "complete",
+            "duration_ms": result.duration_ms,
+            "total_cost_usd": result.total_cost_usd,
+            "num_turns": result.num_turns,
+            "is_error": result.is_error,
+        }));
+    }
+
+    fn on_iteration_start(&mut self, info: &IterationInfo) {
+        self.emit(serde_json::json!({
+            "type": "iteration_start",
+            "iteration": info.iteration,
+            "hat": info.hat,
+        }));
+    }
+
+    fn on_iteration_end(&mut self, info: &IterationInfo, success: bool) {
+        self.emit(serde_json::json!({
+            "type": "iteration_end",
+            "iteration": info.iteration,
+            "hat": info.hat,
+            "success": success,
+        }));
+    }
+}
+
+/// Target of the events [`TracingStreamHandler`] emits.
+const TRACING_TARGET: &str = "ralph::agent";
+
+/// Emits each stream event as a structured `tracing` event, for applications
+/// embedding Ralph that want agent activity in their own logging stack.
+///
+/// Events go to the `ralph::agent` target, carrying the iteration and hat
+/// once an iteration has started. Text and tool results are logged at
+/// `DEBUG`, tool calls, iterations and session summaries at `INFO`, warnings
+/// at `WARN` and errors at `ERROR`.
+#[derive(Debug, Default)]
+pub struct TracingStreamHandler {
+    iteration: Option<IterationInfo>,
+}
+
+impl TracingStreamHandler {
+    /// Creates a handler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn iteration(&self) -> Option<u32> {
+        self.iteration.as_ref().map(|info| info.iteration)
+    }
+
+    fn hat(&self) -> Option<&str> {
+        self.iteration.as_ref().map(|info| info.hat.as_str())
+    }
+}
+
+impl StreamHandler for TracingStreamHandler {
+    fn on_text(&mut self, text: &str) {
+        tracing::debug!(
+            target: TRACING_TARGET,
+            iteration = self.iteration(),
+            hat = self.hat(),
+            text,
+            "agent text"
+        );
+    }
+
+    fn on_tool_call(&mut self, name: &str, id: &str, input: &serde_json::Value) {
+        tracing::info!(
+            target: TRACING_TARGET,
+            iteration = self.iteration(),
+            hat = self.hat(),
+            tool = name,
+            tool_use_id = id,
+            %input,
+            "tool call"
+        );
+    }
+
+    fn on_tool_result(&mut self, id: &str, output: &str) {
+        tracing::debug!(
+            target: TRACING_TARGET,
+            iteration = self.iteration(),
+            hat = self.hat(),
+            tool_use_id = id,
+            output_len = output.len(),
+            output,
+            "tool result"
+        );
+    }
+
+    fn on_error(&mut self, error: &str) {
+        tracing::error!(
+            target: TRACING_TARGET,
+            iteration = self.iteration(),
+            hat = self.hat(),
+            error,
+            "agent error"
+        );
+    }
+
+    fn on_warning(&mut self, warning: &str) {
+        tracing::warn!(
+            target: TRACING_TARGET,
+            iteration = self.iteration(),
+            hat = self.hat(),
+            warning,
+            "agent warning"
+        );
+    }
+
+    fn on_complete(&mut self, result: &SessionResult) {
+        tracing::info!(
+            target: TRACING_TARGET,
+            iteration = self.iteration(),
+            hat = self.hat(),
+            duration_ms = result.duration_ms,
+            cost_usd = result.total_cost_usd,
+            num_turns = result.num_turns,
+            is_error = result.is_error,
+            sub_agents = result.sub_agents.len(),
+            "session complete"
+        );
+    }
+
+    fn on_iteration_start(&mut self, info: &IterationInfo) {
+        self.iteration = Some(info.clone());
+        tracing::info!(
+            target: TRACING_TARGET,
+            iteration = info.iteration,
+            hat = %info.hat,
+            prompt_hash = %info.prompt_hash,
+            "iteration start"
+        );
+    }
+
+    fn on_iteration_end(&mut self, info: &IterationInfo, success: bool) {
+        tracing::info!(
+            target: TRACING_TARGET,
+            iteration = info.iteration,
+            hat = %info.hat,
+            success,
+            "iteration end"
+        );
+        self.iteration = None;
+    }
 }
 
 /// Suppresses all streaming output (for CI/silent mode).
@@ -257,6 +715,69 @@ impl StreamHandler for QuietStreamHandler {
     fn on_complete(&mut self, _: &SessionResult) {}
 }
 
+/// Records every event to the wrapped logger before passing it on, so output
+/// can be replayed later (`ralph logs`) regardless of how it was displayed.
+impl<H: StreamHandler> StreamHandler for DiagnosticStreamHandler<H> {
+    fn on_text(&mut self, text: &str) {
+        self.log(AgentOutputContent::Text {
+            text: text.to_string(),
+        });
+        self.inner_mut().on_text(text);
+    }
+
+    fn on_tool_call(&mut self, name: &str, id: &str, input: &serde_json::Value) {
+        self.log(AgentOutputContent::ToolCall {
+            name: name.to_string(),
+            id: id.to_string(),
+            input: input.clone(),
+        });
+        self.inner_mut().on_tool_call(name, id, input);
+    }
+
+    fn on_tool_result(&mut self, id: &str, output: &str) {
+        self.log(AgentOutputContent::ToolResult {
+            id: id.to_string(),
+            output: output.to_string(),
+        });
+        self.inner_mut().on_tool_result(id, output);
+    }
+
+    fn on_error(&mut self, error: &str) {
+        self.log(AgentOutputContent::Error {
+            message: error.to_string(),
+        });
+        self.inner_mut().on_error(error);
+    }
+
+    fn on_warning(&mut self, warning: &str) {
+        self.log(AgentOutputContent::Warning {
+            message: warning.to_string(),
+        });
+        self.inner_mut().on_warning(warning);
+    }
+
+    fn on_complete(&mut self, result: &SessionResult) {
+        self.log(AgentOutputContent::Complete {
+            input_tokens: None,
+            output_tokens: None,
+        });
+        self.inner_mut().on_complete(result);
+    }
+
+    fn on_iteration_start(&mut self, info: &IterationInfo) {
+        self.set_context(info.iteration, &info.hat);
+        self.log(AgentOutputContent::IterationStart {
+            prompt_hash: info.prompt_hash.clone(),
+        });
+        self.inner_mut().on_iteration_start(info);
+    }
+
+    fn on_iteration_end(&mut self, info: &IterationInfo, success: bool) {
+        self.log(AgentOutputContent::IterationEnd { success });
+        self.inner_mut().on_iteration_end(info, success);
+    }
+}
+
 /// Converts text to styled ratatui Lines, handling both ANSI and markdown.
 ///
 /// When text contains ANSI escape sequences (e.g., from CLI tools like Kiro),
@@ -266,6 +787,44 @@ impl StreamHandler for QuietStreamHandler {
 ///
 /// Using `termimad` ensures parity between TUI and non-TUI modes, as both
 /// use the same markdown processing engine with the same line-breaking rules.
+/// Splits streamed text into the part that can be parsed as markdown and the
+/// provisional tail still being written.
+///
+/// Text is settled up to the last complete line outside a code fence; an
+/// unterminated line or an open fence stays provisional, since parsing either
+/// early renders formatting that changes once the rest arrives.
+fn split_provisional(text: &str) -> (&str, &str) {
+    let mut settled = 0;
+    let mut offset = 0;
+    let mut in_fence = false;
+    for line in text.split_inclusive('\n') {
+        if !line.ends_with('\n') {
+            break;
+        }
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        offset += line.len();
+        if !in_fence {
+            settled = offset;
+        }
+    }
+    text.split_at(settled)
+}
+
+/// Renders a provisional tail without markdown parsing.
+fn provisional_lines(text: &str) -> Vec<Line<'static>> {
+    if text.is_empty() {
+        Vec::new()
+    } else if contains_ansi(text) {
+        text_to_lines(text)
+    } else {
+        text.split('\n')
+            .map(|line| Line::from(line.to_string()))
+            .collect()
+    }
+}
+
 fn text_to_lines(text: &str) -> Vec<Line<'static>> {
     if text.is_empty() {
         return Vec::new();
@@ -318,8 +877,85 @@ enum ContentBlock {
     Text(String),
     /// A single non-text line (tool call, error, completion summary, etc.)
     NonText(Line<'static>),
+    /// A tool result preview line, tagged with its tool use ID
+    ToolResult { id: String, line: Line<'static> },
+}
+
+/// Caps how much agent text is rendered per second.
+///
+/// Every rendered chunk re-renders the iteration's text, so a flood of prose
+/// would hold up the errors and tool calls behind it. Text beyond the budget
+/// is condensed into a single marker line instead.
+#[derive(Debug)]
+struct TextBudget {
+    chars_per_second: usize,
+    window_start: Option<Instant>,
+    used: usize,
+}
+
+impl TextBudget {
+    fn new(chars_per_second: usize) -> Self {
+        Self {
+            chars_per_second,
+            window_start: None,
+            used: 0,
+        }
+    }
+
+    /// Takes up to `chars` from the budget of the second `now` falls in and
+    /// returns how many were granted.
+    fn take(&mut self, chars: usize, now: Instant) -> usize {
+        if self
+            .window_start
+            .is_none_or(|start| now.duration_since(start) >= Duration::from_secs(1))
+        {
+            self.window_start = Some(now);
+            self.used = 0;
+        }
+        let granted = chars.min(self.chars_per_second.saturating_sub(self.used));
+        self.used += granted;
+        granted
+    }
+}
+
+/// The marker line standing in for condensed text.
+#[derive(Debug)]
+struct Condensed {
+    chars: usize,
+    /// Index of the marker in `blocks`
+    block: usize,
+    /// Index of the marker in `lines`
+    line: usize,
 }
 
+fn condensed_line(chars: usize) -> Line<'static> {
+    let amount = if chars < 1000 {
+        chars.to_string()
+    } else {
+        format!("{:.1}k", chars as f64 / 1000.0)
+    };
+    Line::from(Span::styled(
+        format!("\u{2026} {amount} chars condensed \u{2026}"),
+        Style::default()
+            .fg(RatatuiColor::DarkGray)
+            .add_modifier(Modifier::ITALIC),
+    ))
+}
+
+/// Hands a [`TuiStreamHandler`] the output lines and tool result refs of each
+/// new iteration, so one handler can stream a whole run.
+pub type IterationBuffers = Box<
+    dyn FnMut(
+            &IterationInfo,
+        ) -> Option<(
+            Arc<Mutex<Vec<Line<'static>>>>,
+            Arc<Mutex<Vec<(usize, String)>>>,
+        )> + Send,
+>;
+
+/// Default number of characters of a tool result shown inline.
+const DEFAULT_RESULT_PREVIEW: usize = 200;
+
 /// Renders streaming output as ratatui Lines for TUI display.
 ///
 /// This handler produces output visually equivalent to `PrettyStreamHandler`
@@ -340,6 +976,25 @@ pub struct TuiStreamHandler {
     verbose: bool,
     /// Collected output lines for rendering
     lines: Arc<Mutex<Vec<Line<'static>>>>,
+    /// User-configured tool summary templates
+    tool_summaries: ToolSummaries,
+    /// Where full tool results are written (previews only stay in memory)
+    result_store: Option<ToolResultStore>,
+    /// Line index and tool use ID of each tool result preview in `lines`
+    result_refs: Arc<Mutex<Vec<(usize, String)>>>,
+    /// Characters of each tool result kept in the preview line
+    result_preview: usize,
+    /// Where each new iteration's buffers come from (else the handler keeps
+    /// writing to `lines`)
+    iteration_buffers: Option<IterationBuffers>,
+    /// Paces incoming text when smooth streaming is on
+    smooth: Option<SmoothStream>,
+    /// Configured hat colors, when each iteration opens with a separator
+    separator_colors: Option<HashMap<HatId, RatatuiColor>>,
+    /// Caps the agent text rendered per second
+    text_budget: Option<TextBudget>,
+    /// The marker of text condensed since the last rendered line
+    condensed: Option<Condensed>,
 }
 
 impl TuiStreamHandler {
@@ -353,6 +1008,15 @@ impl TuiStreamHandler {
             blocks: Vec::new(),
             verbose,
             lines: Arc::new(Mutex::new(Vec::new())),
+            tool_summaries: ToolSummaries::default(),
+            result_store: None,
+            result_refs: Arc::new(Mutex::new(Vec::new())),
+            result_preview: DEFAULT_RESULT_PREVIEW,
+            iteration_buffers: None,
+            smooth: None,
+            separator_colors: None,
+            text_budget: None,
+            condensed: None,
         }
     }
 
@@ -365,9 +1029,77 @@ impl TuiStreamHandler {
             blocks: Vec::new(),
             verbose,
             lines,
+            tool_summaries: ToolSummaries::default(),
+            result_store: None,
+            result_refs: Arc::new(Mutex::new(Vec::new())),
+            result_preview: DEFAULT_RESULT_PREVIEW,
+            iteration_buffers: None,
+            smooth: None,
+            separator_colors: None,
+            text_budget: None,
+            condensed: None,
         }
     }
 
+    /// Sets custom tool summary templates (from `tool_summaries` in config).
+    #[must_use]
+    pub fn with_tool_summaries(mut self, tool_summaries: ToolSummaries) -> Self {
+        self.tool_summaries = tool_summaries;
+        self
+    }
+
+    /// Persists full tool results to `store` and publishes the line index of
+    /// each result preview to `refs`, so the TUI can open the full result.
+    #[must_use]
+    pub fn with_tool_results(
+        mut self,
+        store: ToolResultStore,
+        refs: Arc<Mutex<Vec<(usize, String)>>>,
+    ) -> Self {
+        self.result_store = Some(store);
+        self.result_refs = refs;
+        self
+    }
+
+    /// Sets how many characters of each tool result are kept in memory.
+    #[must_use]
+    pub fn with_result_preview(mut self, chars: usize) -> Self {
+        self.result_preview = chars;
+        self
+    }
+
+    /// Switches to fresh buffers from `buffers` at every iteration start.
+    #[must_use]
+    pub fn with_iteration_buffers(mut self, buffers: IterationBuffers) -> Self {
+        self.iteration_buffers = Some(buffers);
+        self
+    }
+
+    /// Shows incoming text at a steady pace instead of in bursts.
+    #[must_use]
+    pub fn with_smooth_streaming(mut self, smooth: SmoothStream) -> Self {
+        self.smooth = Some(smooth);
+        self
+    }
+
+    /// Opens each iteration with a separator labelled with its number and
+    /// hat, in the hat's color (configured in `colors`, else from a palette).
+    #[must_use]
+    pub fn with_iteration_separators(mut self, colors: HashMap<HatId, RatatuiColor>) -> Self {
+        self.separator_colors = Some(colors);
+        self
+    }
+
+    /// Renders at most `chars_per_second` of agent text and condenses the
+    /// rest into a `… 1.2k chars condensed …` line, so errors, tool calls and
+    /// lifecycle lines show at once even when text floods in. `0` renders
+    /// all text.
+    #[must_use]
+    pub fn with_text_budget(mut self, chars_per_second: usize) -> Self {
+        self.text_budget = (chars_per_second > 0).then(|| TextBudget::new(chars_per_second));
+        self
+    }
+
     /// Returns a clone of the collected lines.
     pub fn get_lines(&self) -> Vec<Line<'static>> {
         self.lines.lock().unwrap().clone()
@@ -397,6 +1129,7 @@ impl TuiStreamHandler {
     /// interleaved ordering of text and non-text content.
     fn update_lines(&mut self) {
         let mut all_lines = Vec::new();
+        let mut result_refs = Vec::new();
 
         // Render frozen blocks in chronological order
         for block in &self.blocks {
@@ -407,33 +1140,124 @@ impl TuiStreamHandler {
                 ContentBlock::NonText(line) => {
                     all_lines.push(line.clone());
                 }
+                ContentBlock::ToolResult { id, line } => {
+                    result_refs.push((all_lines.len(), id.clone()));
+                    all_lines.push(line.clone());
+                }
             }
         }
 
-        // Render current (unfrozen) text buffer for real-time updates
-        if !self.current_text_buffer.is_empty() {
-            all_lines.extend(text_to_lines(&self.current_text_buffer));
-        }
+        // Render current (unfrozen) text buffer for real-time updates. Only
+        // settled text goes through the markdown parser; the provisional tail
+        // shows as typed until it settles, so half-parsed formatting never
+        // flashes on screen.
+        let (settled, provisional) = split_provisional(&self.current_text_buffer);
+        all_lines.extend(text_to_lines(settled));
+        all_lines.extend(provisional_lines(provisional));
 
         // Note: Long lines are NOT truncated here. The TUI's ContentPane widget
         // handles soft-wrapping at viewport boundaries, preserving full content.
 
         // Update shared lines
         *self.lines.lock().unwrap() = all_lines;
+        *self.result_refs.lock().unwrap() = result_refs;
     }
 
     /// Adds a non-text line (tool call, error, etc.) and updates display.
     ///
     /// First freezes any pending text buffer to preserve chronological order.
     fn add_non_text_line(&mut self, line: Line<'static>) {
+        self.add_block(ContentBlock::NonText(line));
+    }
+
+    /// Shows the word-level diff of an edit under its tool call.
+    ///
+    /// With a result store the diff is collapsed into a summary line and
+    /// saved as `<id>-diff`, which `o` opens like a full tool result.
+    /// Otherwise the changed lines are shown inline.
+    fn add_edit_diff(&mut self, id: &str, diff: &EditDiff) {
+        let dim = Style::default().fg(RatatuiColor::DarkGray);
+        let diff_id = format!("{id}-diff");
+        if let Some(store) = &self.result_store {
+            match store.save(&diff_id, &diff.to_plain()) {
+                Ok(()) => {
+                    self.add_block(ContentBlock::ToolResult {
+                        id: diff_id,
+                        line: Line::from(vec![
+                            Span::styled(format!("  \u{b1} {}", diff.summary()), dim),
+                            Span::styled(" [o: diff]", Style::default().fg(RatatuiColor::Cyan)),
+                        ]),
+                    });
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!(tool_use_id = %id, error = %e, "Failed to store edit diff");
+                }
+            }
+        }
+
+        for line in diff.lines.iter().take(MAX_INLINE_LINES) {
+            let mut spans = vec![Span::raw("    ")];
+            spans.extend(line.iter().map(|(kind, text)| {
+                let style = match kind {
+                    Change::Same => dim,
+                    Change::Removed => Style::default().fg(RatatuiColor::Red),
+                    Change::Added => Style::default().fg(RatatuiColor::Green),
+                };
+                Span::styled(text.clone(), style)
+            }));
+            self.add_non_text_line(Line::from(spans));
+        }
+        if let Some(more) = more_diff_lines(diff) {
+            self.add_non_text_line(Line::from(Span::styled(format!("    {more}"), dim)));
+        }
+    }
+
+    /// Appends a non-text block after freezing pending text, then updates display.
+    fn add_block(&mut self, block: ContentBlock) {
+        self.condensed = None;
         self.freeze_current_text();
-        self.blocks.push(ContentBlock::NonText(line));
+        self.blocks.push(block);
         self.update_lines();
     }
-}
 
-impl StreamHandler for TuiStreamHandler {
-    fn on_text(&mut self, text: &str) {
+    /// Counts `chars` of text as condensed.
+    ///
+    /// The first condensed chunk adds the marker line; later ones only update
+    /// its count in place, which is cheap enough to do for every chunk.
+    fn condense(&mut self, chars: usize) {
+        match &mut self.condensed {
+            Some(condensed) => {
+                condensed.chars += chars;
+                let line = condensed_line(condensed.chars);
+                if let Some(slot) = self.lines.lock().unwrap().get_mut(condensed.line) {
+                    *slot = line.clone();
+                }
+                self.blocks[condensed.block] = ContentBlock::NonText(line);
+            }
+            None => {
+                self.add_non_text_line(condensed_line(chars));
+                self.condensed = Some(Condensed {
+                    chars,
+                    block: self.blocks.len() - 1,
+                    line: self.lines.lock().unwrap().len() - 1,
+                });
+            }
+        }
+    }
+
+    /// Appends text to the current buffer and updates display.
+    fn push_text(&mut self, text: &str) {
+        // With smooth streaming, the chunk arrives in paced pieces
+        if let Some(mut smooth) = self.smooth.take() {
+            smooth.emit(text, |piece| {
+                self.current_text_buffer.push_str(piece);
+                self.update_lines();
+            });
+            self.smooth = Some(smooth);
+            return;
+        }
+
         // Append text to current buffer
         self.current_text_buffer.push_str(text);
 
@@ -441,15 +1265,40 @@ impl StreamHandler for TuiStreamHandler {
         // This handles streaming markdown correctly
         self.update_lines();
     }
+}
+
+impl StreamHandler for TuiStreamHandler {
+    fn on_text(&mut self, text: &str) {
+        let Some(budget) = self.text_budget.as_mut() else {
+            self.push_text(text);
+            return;
+        };
+
+        let chars = text.chars().count();
+        let granted = budget.take(chars, Instant::now());
+        let (shown, dropped) = text.split_at(
+            text.char_indices()
+                .nth(granted)
+                .map_or(text.len(), |(index, _)| index),
+        );
+        if !shown.is_empty() {
+            // Text after a marker starts below it
+            self.condensed = None;
+            self.push_text(shown);
+        }
+        if !dropped.is_empty() {
+            self.condense(chars - granted);
+        }
+    }
 
-    fn on_tool_call(&mut self, name: &str, _id: &str, input: &serde_json::Value) {
+    fn on_tool_call(&mut self, name: &str, id: &str, input: &serde_json::Value) {
         // Build spans: ⚙️ [ToolName] summary
         let mut spans = vec![Span::styled(
             format!("\u{2699} [{}]", name),
             Style::default().fg(RatatuiColor::Blue),
         )];
 
-        if let Some(summary) = format_tool_summary(name, input) {
+        if let Some(summary) = summarize_tool(&self.tool_summaries, name, input) {
             spans.push(Span::styled(
                 format!(" {}", summary),
                 Style::default().fg(RatatuiColor::DarkGray),
@@ -457,16 +1306,45 @@ impl StreamHandler for TuiStreamHandler {
         }
 
         self.add_non_text_line(Line::from(spans));
+
+        if let Some(diff) = EditDiff::from_input(input) {
+            self.add_edit_diff(id, &diff);
+        }
     }
 
-    fn on_tool_result(&mut self, _id: &str, output: &str) {
-        if self.verbose {
-            let line = Line::from(Span::styled(
-                format!(" \u{2713} {}", truncate(output, 200)),
-                Style::default().fg(RatatuiColor::DarkGray),
+    fn on_tool_result(&mut self, id: &str, output: &str) {
+        if !self.verbose {
+            return;
+        }
+
+        // Only a truncated preview gets a ref, so only then is the full
+        // result worth keeping on disk.
+        let truncated = output.chars().count() > self.result_preview;
+        let stored = match &self.result_store {
+            Some(store) if truncated => match store.save(id, output) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!(tool_use_id = %id, error = %e, "Failed to store tool result");
+                    false
+                }
+            },
+            _ => false,
+        };
+
+        let mut spans = vec![Span::styled(
+            format!(" \u{2713} {}", truncate(output, self.result_preview)),
+            Style::default().fg(RatatuiColor::DarkGray),
+        )];
+        if stored {
+            spans.push(Span::styled(
+                " [o: full result]",
+                Style::default().fg(RatatuiColor::Cyan),
             ));
-            self.add_non_text_line(line);
         }
+        self.add_block(ContentBlock::ToolResult {
+            id: id.to_string(),
+            line: Line::from(spans),
+        });
     }
 
     fn on_error(&mut self, error: &str) {
@@ -477,6 +1355,14 @@ impl StreamHandler for TuiStreamHandler {
         self.add_non_text_line(line);
     }
 
+    fn on_warning(&mut self, warning: &str) {
+        let line = Line::from(Span::styled(
+            format!("\u{26a0} Warning: {}", warning),
+            Style::default().fg(RatatuiColor::Yellow),
+        ));
+        self.add_non_text_line(line);
+    }
+
     fn on_complete(&mut self, result: &SessionResult) {
         // Flush any remaining buffered text
         self.flush_text_buffer();
@@ -496,9 +1382,91 @@ impl StreamHandler for TuiStreamHandler {
         );
         let line = Line::from(Span::styled(summary, Style::default().fg(color)));
         self.add_non_text_line(line);
+
+        for entry in format_sub_agent_breakdown(result) {
+            let line = Line::from(Span::styled(
+                entry,
+                Style::default().fg(RatatuiColor::DarkGray),
+            ));
+            self.add_non_text_line(line);
+        }
+    }
+
+    fn on_iteration_start(&mut self, info: &IterationInfo) {
+        if let Some((lines, result_refs)) =
+            self.iteration_buffers.as_mut().and_then(|next| next(info))
+        {
+            self.current_text_buffer.clear();
+            self.blocks.clear();
+            self.condensed = None;
+            self.lines = lines;
+            self.result_refs = result_refs;
+        }
+        if let Some(colors) = &self.separator_colors {
+            let color = hat_color(colors, &info.hat);
+            let line = Line::from(vec![
+                Span::styled("\u{2500}\u{2500} ", Style::default().fg(color)),
+                Span::styled(
+                    format!("iteration {} \u{b7} {}", info.iteration, info.hat),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(" \u{2500}\u{2500}", Style::default().fg(color)),
+            ]);
+            self.add_non_text_line(line);
+        }
     }
 }
 
+/// Notes the changed lines of an edit diff that are not shown inline.
+fn more_diff_lines(diff: &EditDiff) -> Option<String> {
+    let more = diff.lines.len().checked_sub(MAX_INLINE_LINES)?;
+    (more > 0).then(|| format!("\u{2026} {more} more changed lines"))
+}
+
+/// Formats the per-sub-agent breakdown shown below the session summary.
+///
+/// Returns no lines when the session spawned no sub-agents. Sub-agents are
+/// listed most expensive first so budget hogs stand out.
+fn format_sub_agent_breakdown(result: &SessionResult) -> Vec<String> {
+    if result.sub_agents.is_empty() {
+        return Vec::new();
+    }
+
+    let mut agents: Vec<&SubAgentUsage> = result.sub_agents.iter().collect();
+    agents.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+
+    let mut lines = vec!["Sub-agents:".to_string()];
+    for agent in agents {
+        let percent = if result.total_cost_usd > 0.0 {
+            agent.cost_usd / result.total_cost_usd * 100.0
+        } else {
+            0.0
+        };
+        lines.push(format!(
+            "  \u{21b3} {}: ${:.4} ({:.0}%) | {} in / {} out tokens | {} tool calls",
+            truncate(&agent.description, 40),
+            agent.cost_usd,
+            percent,
+            agent.input_tokens,
+            agent.output_tokens,
+            agent.tool_calls
+        ));
+    }
+    lines
+}
+
+/// Summarizes a tool call, preferring a user-configured template over the
+/// built-in summary so custom tools (and overrides) render readably.
+fn summarize_tool(
+    tool_summaries: &ToolSummaries,
+    name: &str,
+    input: &serde_json::Value,
+) -> Option<String> {
+    tool_summaries
+        .render(name, input)
+        .or_else(|| format_tool_summary(name, input))
+}
+
 /// Extracts the most relevant field from tool input for display.
 ///
 /// Returns a human-readable summary (file path, command, pattern, etc.) based on the tool type.
@@ -563,6 +1531,7 @@ mod tests {
             total_cost_usd: 0.01,
             num_turns: 1,
             is_error: false,
+            sub_agents: Vec::new(),
         });
     }
 
@@ -580,6 +1549,7 @@ mod tests {
             total_cost_usd: 0.01,
             num_turns: 1,
             is_error: false,
+            sub_agents: Vec::new(),
         }); // Should be silent
     }
 
@@ -598,7 +1568,178 @@ mod tests {
             total_cost_usd: 0.01,
             num_turns: 1,
             is_error: false,
+            sub_agents: Vec::new(),
+        });
+    }
+
+    #[test]
+    fn test_diagnostic_wrapper_records_events() {
+        use ralph_core::diagnostics::{AgentOutputEntry, AgentOutputLogger};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("run.jsonl");
+        let mut logger = AgentOutputLogger::append(&path).unwrap();
+        logger.set_context(3, "builder");
+        let mut handler =
+            DiagnosticStreamHandler::new(QuietStreamHandler, Arc::new(Mutex::new(logger)));
+
+        handler.on_text("Hello");
+        handler.on_tool_call("Bash", "tool_1", &json!({"command": "ls"}));
+        handler.on_tool_result("tool_1", "output");
+
+        let entries: Vec<AgentOutputEntry> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].iteration, 3);
+        assert_eq!(entries[0].hat, "builder");
+        assert_eq!(
+            entries[2].content,
+            AgentOutputContent::ToolResult {
+                id: "tool_1".to_string(),
+                output: "output".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_run_log_keeps_text_the_tui_condenses() {
+        use ralph_core::diagnostics::{AgentOutputEntry, AgentOutputLogger};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("run.jsonl");
+        let logger = AgentOutputLogger::append(&path).unwrap();
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let tui = TuiStreamHandler::with_lines(false, Arc::clone(&lines)).with_text_budget(10);
+        let mut handler = DiagnosticStreamHandler::new(tui, Arc::new(Mutex::new(logger)));
+
+        let flood = "x".repeat(1200);
+        handler.on_text(&flood);
+
+        let shown: Vec<String> = lines.lock().unwrap().iter().map(Line::to_string).collect();
+        assert_eq!(
+            shown,
+            ["xxxxxxxxxx", "\u{2026} 1.2k chars condensed \u{2026}"]
+        );
+        let entries: Vec<AgentOutputEntry> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries[0].content, AgentOutputContent::Text { text: flood });
+    }
+
+    #[test]
+    fn test_diagnostic_wrapper_records_iteration_markers() {
+        use ralph_core::diagnostics::{AgentOutputEntry, AgentOutputLogger};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("run.jsonl");
+        let logger = AgentOutputLogger::append(&path).unwrap();
+        let mut handler =
+            DiagnosticStreamHandler::new(QuietStreamHandler, Arc::new(Mutex::new(logger)));
+
+        let info = IterationInfo::new(4, "reviewer", "Review the change");
+        handler.on_iteration_start(&info);
+        handler.on_text("Looks good");
+        handler.on_iteration_end(&info, true);
+
+        let entries: Vec<AgentOutputEntry> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 3);
+        assert!(
+            entries
+                .iter()
+                .all(|entry| entry.iteration == 4 && entry.hat == "reviewer")
+        );
+        assert_eq!(
+            entries[0].content,
+            AgentOutputContent::IterationStart {
+                prompt_hash: info.prompt_hash.clone(),
+            }
+        );
+        assert_eq!(
+            entries[2].content,
+            AgentOutputContent::IterationEnd { success: true }
+        );
+    }
+
+    #[test]
+    fn test_tracing_handler_emits_structured_events() {
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let mut handler = TracingStreamHandler::new();
+            let info = IterationInfo::new(2, "builder", "prompt");
+            handler.on_iteration_start(&info);
+            handler.on_tool_call("Bash", "tool_1", &serde_json::json!({"command": "ls"}));
+            handler.on_complete(&SessionResult::new(1500, 0.25, 3, false));
+            handler.on_iteration_end(&info, true);
         });
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4, "{output}");
+        assert!(lines[0].contains("iteration start") && lines[0].contains("hat=builder"));
+        assert!(lines[1].contains("ralph::agent"), "{}", lines[1]);
+        assert!(
+            lines[1].contains("iteration=2")
+                && lines[1].contains("tool=\"Bash\"")
+                && lines[1].contains("tool_use_id=\"tool_1\""),
+            "{}",
+            lines[1]
+        );
+        assert!(lines[2].contains("cost_usd=0.25") && lines[2].contains("num_turns=3"));
+        assert!(lines[3].contains("success=true"));
+    }
+
+    #[test]
+    fn test_prompt_hash_is_stable() {
+        let info = IterationInfo::new(1, "ralph", "Build it");
+        assert_eq!(info.prompt_hash.len(), 16);
+        assert_eq!(
+            info.prompt_hash,
+            IterationInfo::new(2, "builder", "Build it").prompt_hash
+        );
+        assert_ne!(
+            info.prompt_hash,
+            IterationInfo::new(1, "ralph", "Build it!").prompt_hash
+        );
+    }
+
+    #[test]
+    fn test_gutter_prefixes_every_line() {
+        let text = with_gutter("first\nsecond\n", Color::Cyan);
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains('\u{258e}') && lines[0].ends_with(" first"));
+        assert!(lines[1].contains('\u{258e}') && lines[1].ends_with(" second"));
     }
 
     #[test]
@@ -691,12 +1832,103 @@ mod tests {
     mod tui_stream_handler {
         use super::*;
         use ratatui::style::{Color, Modifier};
+        use std::time::Duration;
 
         /// Helper to collect lines from TuiStreamHandler
         fn collect_lines(handler: &TuiStreamHandler) -> Vec<ratatui::text::Line<'static>> {
             handler.lines.lock().unwrap().clone()
         }
 
+        #[test]
+        fn iteration_start_switches_to_new_buffers() {
+            let iterations: Arc<Mutex<Vec<Arc<Mutex<Vec<Line<'static>>>>>>> = Arc::default();
+            let created = Arc::clone(&iterations);
+            let mut handler = TuiStreamHandler::new(false).with_iteration_buffers(Box::new(
+                move |_: &IterationInfo| {
+                    let lines = Arc::new(Mutex::new(Vec::new()));
+                    created.lock().unwrap().push(Arc::clone(&lines));
+                    Some((lines, Arc::default()))
+                },
+            ));
+
+            handler.on_iteration_start(&IterationInfo::new(1, "ralph", "first"));
+            handler.on_text("first output");
+            handler.on_iteration_start(&IterationInfo::new(2, "ralph", "second"));
+            handler.on_text("second output");
+
+            let iterations = iterations.lock().unwrap();
+            assert_eq!(iterations.len(), 2);
+            let text = |lines: &Arc<Mutex<Vec<Line<'static>>>>| {
+                lines
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<String>()
+            };
+            assert_eq!(text(&iterations[0]), "first output");
+            assert_eq!(text(&iterations[1]), "second output");
+        }
+
+        #[test]
+        fn iteration_separator_is_labelled_and_colored_by_hat() {
+            let colors = HashMap::from([(HatId::new("builder"), Color::LightGreen)]);
+            let mut handler = TuiStreamHandler::new(false).with_iteration_separators(colors);
+
+            handler.on_iteration_start(&IterationInfo::new(3, "builder", "prompt"));
+            handler.on_text("building\n");
+
+            let lines = handler.get_lines();
+            assert_eq!(
+                lines[0].to_string(),
+                "\u{2500}\u{2500} iteration 3 \u{b7} builder \u{2500}\u{2500}"
+            );
+            assert_eq!(lines[0].spans[1].style.fg, Some(Color::LightGreen));
+            assert_eq!(lines[1].to_string(), "building");
+        }
+
+        #[test]
+        fn smooth_streaming_shows_the_same_text() {
+            let mut handler = TuiStreamHandler::new(false)
+                .with_smooth_streaming(SmoothStream::new(500, Duration::from_secs(1)));
+
+            handler.on_text("a burst of text arriving all at once\n");
+
+            let lines = handler.get_lines();
+            assert_eq!(lines.len(), 1);
+            assert_eq!(lines[0].to_string(), "a burst of text arriving all at once");
+        }
+
+        #[test]
+        fn text_over_budget_is_condensed_but_tool_calls_still_show() {
+            let mut handler = TuiStreamHandler::new(false).with_text_budget(10);
+
+            handler.on_text("0123456789abc");
+            handler.on_text(&"x".repeat(1200));
+            handler.on_tool_call("Read", "tool_1", &json!({"file_path": "src/lib.rs"}));
+
+            let lines: Vec<String> = handler.get_lines().iter().map(Line::to_string).collect();
+            assert_eq!(
+                lines,
+                [
+                    "0123456789",
+                    "\u{2026} 1.2k chars condensed \u{2026}",
+                    "\u{2699} [Read] src/lib.rs",
+                ]
+            );
+        }
+
+        #[test]
+        fn text_budget_renews_every_second() {
+            let mut budget = TextBudget::new(100);
+            let start = Instant::now();
+
+            assert_eq!(budget.take(80, start), 80);
+            assert_eq!(budget.take(80, start + Duration::from_millis(500)), 20);
+            assert_eq!(budget.take(80, start + Duration::from_millis(900)), 0);
+            assert_eq!(budget.take(80, start + Duration::from_secs(1)), 80);
+        }
+
         #[test]
         fn text_creates_line_on_newline() {
             // Given TuiStreamHandler
@@ -766,6 +1998,27 @@ mod tests {
             );
         }
 
+        #[test]
+        fn tool_call_uses_configured_summary() {
+            let summaries = ToolSummaries::new(std::collections::HashMap::from([(
+                "Deploy".to_string(),
+                "{{input.environment}} {{input.service}}".to_string(),
+            )]));
+            let mut handler = TuiStreamHandler::new(false).with_tool_summaries(summaries);
+
+            handler.on_tool_call(
+                "Deploy",
+                "tool_1",
+                &json!({"environment": "prod", "service": "billing"}),
+            );
+            // Built-in summaries still apply to tools without a template
+            handler.on_tool_call("Read", "tool_2", &json!({"file_path": "src/main.rs"}));
+
+            let lines = collect_lines(&handler);
+            assert_eq!(lines[0].to_string(), "\u{2699} [Deploy] prod billing");
+            assert_eq!(lines[1].to_string(), "\u{2699} [Read] src/main.rs");
+        }
+
         #[test]
         fn tool_result_verbose_shows_content() {
             // Given TuiStreamHandler with verbose=true
@@ -790,6 +2043,60 @@ mod tests {
             );
         }
 
+        #[test]
+        fn tool_result_preview_capped_and_full_result_stored() {
+            let temp = tempfile::TempDir::new().unwrap();
+            let store = ToolResultStore::new(temp.path());
+            let refs = Arc::new(Mutex::new(Vec::new()));
+            let mut handler = TuiStreamHandler::new(true)
+                .with_tool_results(store.clone(), Arc::clone(&refs))
+                .with_result_preview(10);
+
+            let output = "0123456789abcdefghij";
+            handler.on_tool_call("Bash", "tool_1", &json!({"command": "ls"}));
+            handler.on_tool_result("tool_1", output);
+
+            let lines = collect_lines(&handler);
+            assert_eq!(
+                lines[1].to_string(),
+                " \u{2713} 0123456789... [o: full result]"
+            );
+            assert_eq!(*refs.lock().unwrap(), vec![(1, "tool_1".to_string())]);
+            assert_eq!(store.load("tool_1").unwrap(), output);
+        }
+
+        #[test]
+        fn edit_diff_collapsed_with_store_and_inline_without() {
+            let edit = json!({
+                "file_path": "src/lib.rs",
+                "old_string": "let x = 1;",
+                "new_string": "let y = 1;",
+            });
+
+            let temp = tempfile::TempDir::new().unwrap();
+            let store = ToolResultStore::new(temp.path());
+            let refs = Arc::new(Mutex::new(Vec::new()));
+            let mut handler =
+                TuiStreamHandler::new(false).with_tool_results(store.clone(), Arc::clone(&refs));
+            handler.on_tool_call("Edit", "tool_1", &edit);
+
+            let lines = collect_lines(&handler);
+            assert_eq!(
+                lines[1].to_string(),
+                "  \u{b1} +1 \u{2212}1 words in 1 line [o: diff]"
+            );
+            assert_eq!(*refs.lock().unwrap(), vec![(1, "tool_1-diff".to_string())]);
+            assert_eq!(store.load("tool_1-diff").unwrap(), "let [-x-]{+y+} = 1;\n");
+
+            let mut handler = TuiStreamHandler::new(false);
+            handler.on_tool_call("Edit", "tool_1", &edit);
+            let lines = collect_lines(&handler);
+            assert_eq!(lines.len(), 2);
+            assert_eq!(lines[1].to_string(), "    let xy = 1;");
+            assert_eq!(lines[1].spans[2].style.fg, Some(Color::Red));
+            assert_eq!(lines[1].spans[3].style.fg, Some(Color::Green));
+        }
+
         #[test]
         fn tool_result_quiet_is_silent() {
             // Given TuiStreamHandler with verbose=false
@@ -806,6 +2113,26 @@ mod tests {
             );
         }
 
+        #[test]
+        fn tool_result_stored_only_when_ref_shown() {
+            let temp = tempfile::TempDir::new().unwrap();
+            let store = ToolResultStore::new(temp.path());
+            let refs = Arc::new(Mutex::new(Vec::new()));
+            let mut quiet = TuiStreamHandler::new(false)
+                .with_tool_results(store.clone(), Arc::clone(&refs))
+                .with_result_preview(10);
+            quiet.on_tool_result("tool_1", "0123456789abcdefghij");
+
+            let mut verbose = TuiStreamHandler::new(true)
+                .with_tool_results(store.clone(), Arc::clone(&refs))
+                .with_result_preview(10);
+            verbose.on_tool_result("tool_2", "short");
+
+            assert_eq!(collect_lines(&verbose)[0].to_string(), " \u{2713} short");
+            assert!(store.load("tool_1").is_err());
+            assert!(store.load("tool_2").is_err());
+        }
+
         #[test]
         fn error_produces_red_styled_line() {
             // Given TuiStreamHandler
@@ -843,6 +2170,18 @@ mod tests {
             );
         }
 
+        #[test]
+        fn warning_produces_yellow_line() {
+            let mut handler = TuiStreamHandler::new(false);
+
+            handler.on_warning("Retrying in 5s");
+
+            let lines = collect_lines(&handler);
+            assert_eq!(lines.len(), 1);
+            assert_eq!(lines[0].to_string(), "\u{26a0} Warning: Retrying in 5s");
+            assert_eq!(lines[0].spans[0].style.fg, Some(Color::Yellow));
+        }
+
         #[test]
         fn long_lines_preserved_without_truncation() {
             // Given TuiStreamHandler
@@ -1025,6 +2364,7 @@ mod tests {
                 total_cost_usd: 0.0025,
                 num_turns: 3,
                 is_error: false,
+                sub_agents: Vec::new(),
             });
 
             // Then buffer is flushed and summary line appears
@@ -1055,6 +2395,7 @@ mod tests {
                 total_cost_usd: 0.01,
                 num_turns: 1,
                 is_error: true,
+                sub_agents: Vec::new(),
             });
 
             let lines = collect_lines(&handler);
@@ -1077,6 +2418,7 @@ mod tests {
                 total_cost_usd: 0.01,
                 num_turns: 1,
                 is_error: false,
+                sub_agents: Vec::new(),
             });
 
             let lines = collect_lines(&handler);
@@ -1091,6 +2433,57 @@ mod tests {
             );
         }
 
+        #[test]
+        fn on_complete_lists_sub_agents_by_cost() {
+            let mut handler = TuiStreamHandler::new(true);
+            let sub_agent = |id: &str, description: &str, cost_usd: f64| SubAgentUsage {
+                tool_use_id: id.to_string(),
+                description: description.to_string(),
+                input_tokens: 1000,
+                output_tokens: 200,
+                tool_calls: 4,
+                cost_usd,
+            };
+            handler.on_complete(&SessionResult {
+                duration_ms: 1000,
+                total_cost_usd: 0.10,
+                num_turns: 5,
+                is_error: false,
+                sub_agents: vec![
+                    sub_agent("task_1", "Explore tests", 0.02),
+                    sub_agent("task_2", "Refactor parser", 0.05),
+                ],
+            });
+
+            let lines: Vec<String> = collect_lines(&handler)
+                .iter()
+                .map(|l| l.to_string())
+                .collect();
+            let header = lines
+                .iter()
+                .position(|l| l == "Sub-agents:")
+                .expect("Should have sub-agent header");
+            assert!(lines[header + 1].contains("Refactor parser"));
+            assert!(lines[header + 1].contains("$0.0500 (50%)"));
+            assert!(lines[header + 2].contains("Explore tests"));
+            assert!(lines[header + 2].contains("4 tool calls"));
+        }
+
+        #[test]
+        fn on_complete_without_sub_agents_has_no_breakdown() {
+            let mut handler = TuiStreamHandler::new(true);
+            handler.on_complete(&SessionResult {
+                duration_ms: 1000,
+                total_cost_usd: 0.01,
+                num_turns: 1,
+                is_error: false,
+                sub_agents: Vec::new(),
+            });
+
+            let lines = collect_lines(&handler);
+            assert!(!lines.iter().any(|l| l.to_string() == "Sub-agents:"));
+        }
+
         #[test]
         fn tool_call_with_no_summary_shows_just_name() {
             let mut handler = TuiStreamHandler::new(false);
@@ -1342,6 +2735,36 @@ mod tests {
             let _ = lines; // Use the variable to avoid warning
         }
 
+        #[test]
+        fn markdown_tail_stays_literal_until_the_line_completes() {
+            let mut handler = TuiStreamHandler::new(false);
+
+            handler.on_text("Done\n**bo");
+            let lines = collect_lines(&handler);
+            assert_eq!(lines.last().unwrap().to_string(), "**bo");
+
+            handler.on_text("ld**\n");
+            let lines = collect_lines(&handler);
+            assert!(lines.iter().all(|line| !line.to_string().contains("**")));
+            assert!(lines.iter().any(|line| {
+                line.spans
+                    .iter()
+                    .any(|span| span.style.add_modifier.contains(Modifier::BOLD))
+            }));
+        }
+
+        #[test]
+        fn open_code_fence_stays_provisional() {
+            assert_eq!(
+                split_provisional("Intro\n```rust\nfn main() {}\n"),
+                ("Intro\n", "```rust\nfn main() {}\n")
+            );
+            assert_eq!(
+                split_provisional("```\ncode\n```\nafter"),
+                ("```\ncode\n```\n", "after")
+            );
+        }
+
         // =====================================================================
         // ANSI Color Preservation Tests
         // =====================================================================
@@ -1566,4 +2989,24 @@ mod ansi_detection_tests {
     fn contains_ansi_with_escape_in_middle() {
         assert!(contains_ansi("prefix \x1b[31mred\x1b[0m suffix"));
     }
+
+    #[test]
+    fn json_handler_writes_one_object_per_event() {
+        let mut handler = JsonStreamHandler::with_writer(Vec::new());
+        handler.on_iteration_start(&IterationInfo::new(2, "builder", "prompt"));
+        handler.on_text("Hello\nworld");
+        handler.on_tool_call("Bash", "t1", &serde_json::json!({"command": "ls"}));
+        handler.on_iteration_end(&IterationInfo::new(2, "builder", "prompt"), true);
+
+        let out = String::from_utf8(handler.into_inner()).unwrap();
+        let events: Vec<serde_json::Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0]["type"], "iteration_start");
+        assert_eq!(events[1]["text"], "Hello\nworld");
+        assert_eq!(events[2]["input"]["command"], "ls");
+        assert_eq!(events[3]["success"], true);
+    }
 }
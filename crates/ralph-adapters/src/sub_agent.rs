@@ -0,0 +1,214 @@
+//! Sub-agent usage tracking for Claude stream sessions.
+//!
+//! When Claude delegates work via the `Task` tool, the spawned sub-agent's
+//! messages are emitted on the same stream with a `parent_tool_use_id` that
+//! points back at the originating `Task` invocation. The tracker groups token
+//! usage by that id so the session's total cost can be split per sub-agent.
+
+use crate::claude_stream::Usage;
+
+/// Name of the tool Claude uses to spawn sub-agents.
+const TASK_TOOL: &str = "Task";
+
+/// Token and cost totals attributed to a single sub-agent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubAgentUsage {
+    /// The `Task` tool invocation ID that spawned this sub-agent.
+    pub tool_use_id: String,
+    /// Human-readable label (the `Task` description, or the sub-agent type).
+    pub description: String,
+    /// Input tokens consumed by the sub-agent.
+    pub input_tokens: u64,
+    /// Output tokens produced by the sub-agent.
+    pub output_tokens: u64,
+    /// Number of tool calls the sub-agent made.
+    pub tool_calls: u32,
+    /// Share of the session cost, proportional to the sub-agent's token usage.
+    pub cost_usd: f64,
+}
+
+impl SubAgentUsage {
+    /// Total tokens (input + output) used by this sub-agent.
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+/// Accumulates per-sub-agent token usage over a single session.
+#[derive(Debug, Default)]
+pub(crate) struct SubAgentTracker {
+    /// Sub-agents in the order their `Task` calls were seen.
+    agents: Vec<SubAgentUsage>,
+    /// Tokens used by the top-level agent.
+    main_tokens: u64,
+}
+
+impl SubAgentTracker {
+    /// Registers a sub-agent if the tool call is a `Task` invocation.
+    ///
+    /// Tool calls made *by* a sub-agent are counted against it.
+    pub fn on_tool_call(
+        &mut self,
+        name: &str,
+        id: &str,
+        input: &serde_json::Value,
+        parent_tool_use_id: Option<&str>,
+    ) {
+        if let Some(parent) = parent_tool_use_id {
+            self.agent_mut(parent).tool_calls += 1;
+        }
+
+        if name == TASK_TOOL {
+            let description = input
+                .get("description")
+                .or_else(|| input.get("subagent_type"))
+                .and_then(|v| v.as_str())
+                .unwrap_or(TASK_TOOL);
+            self.agent_mut(id).description = description.to_string();
+        }
+    }
+
+    /// Records token usage for a message, attributing it to its sub-agent if any.
+    pub fn on_usage(&mut self, usage: &Usage, parent_tool_use_id: Option<&str>) {
+        match parent_tool_use_id {
+            Some(parent) => {
+                let agent = self.agent_mut(parent);
+                agent.input_tokens += usage.input_tokens;
+                agent.output_tokens += usage.output_tokens;
+            }
+            None => self.main_tokens += usage.input_tokens + usage.output_tokens,
+        }
+    }
+
+    /// Tokens used in the session, by the top-level agent and sub-agents.
+    pub fn total_tokens(&self) -> u64 {
+        self.main_tokens
+            + self
+                .agents
+                .iter()
+                .map(SubAgentUsage::total_tokens)
+                .sum::<u64>()
+    }
+
+    /// Splits `total_cost_usd` across sub-agents by token share.
+    ///
+    /// Sub-agents that never reported usage are omitted. Returns an empty list
+    /// when no sub-agent usage was observed.
+    pub fn breakdown(&self, total_cost_usd: f64) -> Vec<SubAgentUsage> {
+        let sub_tokens: u64 = self.agents.iter().map(SubAgentUsage::total_tokens).sum();
+        let all_tokens = sub_tokens + self.main_tokens;
+        if sub_tokens == 0 {
+            return Vec::new();
+        }
+
+        self.agents
+            .iter()
+            .filter(|agent| agent.total_tokens() > 0)
+            .map(|agent| {
+                let share = agent.total_tokens() as f64 / all_tokens as f64;
+                SubAgentUsage {
+                    cost_usd: total_cost_usd * share,
+                    ..agent.clone()
+                }
+            })
+            .collect()
+    }
+
+    fn agent_mut(&mut self, tool_use_id: &str) -> &mut SubAgentUsage {
+        let idx = match self
+            .agents
+            .iter()
+            .position(|agent| agent.tool_use_id == tool_use_id)
+        {
+            Some(idx) => idx,
+            None => {
+                self.agents.push(SubAgentUsage {
+                    tool_use_id: tool_use_id.to_string(),
+                    description: TASK_TOOL.to_string(),
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    tool_calls: 0,
+                    cost_usd: 0.0,
+                });
+                self.agents.len() - 1
+            }
+        };
+        &mut self.agents[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn usage(input_tokens: u64, output_tokens: u64) -> Usage {
+        Usage {
+            input_tokens,
+            output_tokens,
+        }
+    }
+
+    #[test]
+    fn test_no_sub_agents_yields_empty_breakdown() {
+        let mut tracker = SubAgentTracker::default();
+        tracker.on_usage(&usage(100, 50), None);
+        assert!(tracker.breakdown(1.0).is_empty());
+    }
+
+    #[test]
+    fn test_cost_split_by_token_share() {
+        let mut tracker = SubAgentTracker::default();
+        tracker.on_tool_call(
+            "Task",
+            "task_1",
+            &json!({"description": "Search codebase"}),
+            None,
+        );
+        tracker.on_tool_call(
+            "Task",
+            "task_2",
+            &json!({"subagent_type": "reviewer"}),
+            None,
+        );
+        tracker.on_usage(&usage(200, 0), None);
+        tracker.on_usage(&usage(500, 100), Some("task_1"));
+        tracker.on_usage(&usage(150, 50), Some("task_2"));
+        assert_eq!(tracker.total_tokens(), 1000);
+
+        let breakdown = tracker.breakdown(1.0);
+        assert_eq!(breakdown.len(), 2);
+
+        assert_eq!(breakdown[0].description, "Search codebase");
+        assert_eq!(breakdown[0].total_tokens(), 600);
+        assert!((breakdown[0].cost_usd - 0.6).abs() < 1e-9);
+
+        assert_eq!(breakdown[1].description, "reviewer");
+        assert!((breakdown[1].cost_usd - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tool_calls_counted_against_parent() {
+        let mut tracker = SubAgentTracker::default();
+        tracker.on_tool_call("Task", "task_1", &json!({"description": "Explore"}), None);
+        tracker.on_tool_call("Read", "t2", &json!({}), Some("task_1"));
+        tracker.on_tool_call("Grep", "t3", &json!({}), Some("task_1"));
+        tracker.on_tool_call("Bash", "t4", &json!({}), None);
+        tracker.on_usage(&usage(10, 10), Some("task_1"));
+
+        let breakdown = tracker.breakdown(0.5);
+        assert_eq!(breakdown[0].tool_calls, 2);
+        assert!((breakdown[0].cost_usd - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unknown_parent_is_tracked() {
+        let mut tracker = SubAgentTracker::default();
+        tracker.on_usage(&usage(10, 0), Some("orphan"));
+
+        let breakdown = tracker.breakdown(0.1);
+        assert_eq!(breakdown.len(), 1);
+        assert_eq!(breakdown[0].tool_use_id, "orphan");
+        assert_eq!(breakdown[0].description, "Task");
+    }
+}
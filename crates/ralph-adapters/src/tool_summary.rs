@@ -0,0 +1,163 @@
+//! User-defined one-line summaries for tool calls.
+//!
+//! Built-in tools get hand-written summaries in the stream handlers. Custom
+//! tools (MCP servers, project-specific tools) can be given one through the
+//! `tool_summaries` config map, which maps a tool name to a template:
+//!
+//! ```yaml
+//! tool_summaries:
+//!   Deploy: "{{input.environment}} {{input.service}}"
+//!   mcp__jira__create_issue: "{{/project}}: {{/summary}}"
+//! ```
+//!
+//! A placeholder is either a dotted path under `input` or a JSON pointer
+//! into the tool input. Array elements are addressed by index
+//! (`{{input.targets.0}}`). Missing fields render as empty text.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Maximum length of a rendered summary, in characters.
+const MAX_SUMMARY_LEN: usize = 80;
+
+/// Templates that summarize tool calls by name.
+#[derive(Debug, Clone, Default)]
+pub struct ToolSummaries {
+    templates: HashMap<String, String>,
+}
+
+impl ToolSummaries {
+    /// Creates summaries from a tool-name to template map.
+    pub fn new(templates: HashMap<String, String>) -> Self {
+        Self { templates }
+    }
+
+    /// Renders the summary for a tool call, if a template is configured.
+    ///
+    /// Returns `None` when the tool has no template or every placeholder in it
+    /// resolved to nothing.
+    pub fn render(&self, name: &str, input: &Value) -> Option<String> {
+        let template = self.templates.get(name)?;
+        let rendered = render_template(template, input);
+        let rendered = rendered.split_whitespace().collect::<Vec<_>>().join(" ");
+        if rendered.is_empty() {
+            return None;
+        }
+        Some(truncate(&rendered, MAX_SUMMARY_LEN))
+    }
+}
+
+/// Substitutes every `{{...}}` placeholder in `template`.
+///
+/// An unterminated `{{` is kept as literal text.
+fn render_template(template: &str, input: &Value) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        if let Some(value) = lookup(input, after[..end].trim()) {
+            out.push_str(&value);
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Resolves a placeholder expression against the tool input.
+fn lookup(input: &Value, expr: &str) -> Option<String> {
+    let value = if expr.starts_with('/') {
+        input.pointer(expr)?
+    } else {
+        let path = expr.strip_prefix("input")?;
+        if path.is_empty() {
+            input
+        } else {
+            path.strip_prefix('.')?
+                .split('.')
+                .try_fold(input, |value, key| match value {
+                    Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+                    _ => value.get(key),
+                })?
+        }
+    };
+
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn truncate(s: &str, max_len: usize) -> String {
+    match s.char_indices().nth(max_len) {
+        Some((idx, _)) => format!("{}...", &s[..idx]),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn summaries(name: &str, template: &str) -> ToolSummaries {
+        ToolSummaries::new(HashMap::from([(name.to_string(), template.to_string())]))
+    }
+
+    #[test]
+    fn test_dotted_paths() {
+        let rules = summaries("Deploy", "{{input.environment}} {{input.service}}");
+        let input = json!({"environment": "staging", "service": "api"});
+        assert_eq!(
+            rules.render("Deploy", &input).as_deref(),
+            Some("staging api")
+        );
+    }
+
+    #[test]
+    fn test_json_pointer_and_array_index() {
+        let rules = summaries(
+            "Ship",
+            "{{/target/region}} -> {{input.hosts.1}} ({{input.count}})",
+        );
+        let input = json!({"target": {"region": "eu"}, "hosts": ["a", "b"], "count": 3});
+        assert_eq!(rules.render("Ship", &input).as_deref(), Some("eu -> b (3)"));
+    }
+
+    #[test]
+    fn test_missing_fields_render_empty() {
+        let rules = summaries("Deploy", "{{input.environment}} {{input.service}}");
+        assert_eq!(
+            rules
+                .render("Deploy", &json!({"service": "api"}))
+                .as_deref(),
+            Some("api")
+        );
+        assert_eq!(rules.render("Deploy", &json!({})), None);
+    }
+
+    #[test]
+    fn test_unknown_tool_and_unterminated_placeholder() {
+        let rules = summaries("Deploy", "to {{input.environment");
+        assert_eq!(rules.render("Other", &json!({})), None);
+        assert_eq!(
+            rules.render("Deploy", &json!({})).as_deref(),
+            Some("to {{input.environment")
+        );
+    }
+
+    #[test]
+    fn test_long_summary_truncated() {
+        let rules = summaries("Echo", "{{input.text}}");
+        let rendered = rules
+            .render("Echo", &json!({"text": "x".repeat(200)}))
+            .unwrap();
+        assert_eq!(rendered.chars().count(), MAX_SUMMARY_LEN + 3);
+    }
+}
@@ -532,6 +532,10 @@ fn format_termination_reason(reason: &TerminationReason) -> String {
         TerminationReason::ChaosModeComplete => "ChaosModeComplete".to_string(),
         TerminationReason::ChaosModeMaxIterations => "ChaosModeMaxIterations".to_string(),
         TerminationReason::RestartRequested => "RestartRequested".to_string(),
+        other => {
+            tracing::error!(reason = ?other, "No results label for termination reason");
+            format!("{other:?}")
+        }
     }
 }
 
@@ -0,0 +1,668 @@
+//! CLI command for `ralph attach`.
+//!
+//! Attaches a read-only spectator to a run. Spectators replay the run's
+//! events file and agent output log, so any number of them can watch the
+//! same run without coordinating with the loop. Watching never writes to the
+//! run: pausing or aborting is reserved for the controller (see
+//! `ralph control`), which HTTP clients can act as with a controller token.
+//!
+//! Two views are available:
+//! - the TUI (default), rebuilt from the recorded output; with `--all` it
+//!   watches every active run of the repository and `r` switches between them
+//! - `--http <addr>`, which streams the output as server-sent events and,
+//!   for controller tokens, accepts pause/resume/abort requests
+//!
+//! `ralph open iter12:3401` opens the same TUI scrolled to one line (see
+//! [`LineId`]), for following links from alerts, search results and reports.
+
+use crate::ConfigSource;
+use crate::display::{build_tui_hat_colors, build_tui_hat_map};
+use crate::http_api::{self, ApiAuth, Request, read_head, respond};
+use crate::logs::{self, JsonlTail};
+use anyhow::{Context, Result, bail};
+use clap::Parser;
+use ralph_adapters::{IterationInfo, StreamHandler, ToolSummaries, TuiStreamHandler};
+use ralph_core::diagnostics::AgentOutputEntry;
+use ralph_core::{
+    ApiRole, AuditAction, AuditEntry, AuditLog, AuditSource, Cipher, ControlCommand, EventRecord,
+    HatRegistry, HttpApiConfig, LoopContext, LoopLock, LoopRegistry, RalphConfig, RunControl,
+    RunPhase, RunStatus,
+};
+use ralph_proto::Event;
+use ralph_tui::{LineId, RunList, Tui, TuiState};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// How often the run's files are polled for new records.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Polls between SSE keepalive comments (about every 15 seconds).
+const KEEPALIVE_POLLS: u32 = 60;
+
+/// How often `--all` looks for new runs and refreshes their status.
+const RUNS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Selector label of the loop running in the repository itself.
+const PRIMARY_RUN: &str = "(primary)";
+
+/// Arguments for the attach subcommand.
+#[derive(Parser, Debug)]
+pub struct AttachArgs {
+    /// Run ID (e.g. 20260127-143022) or a unique prefix; defaults to the
+    /// current run
+    pub run_id: Option<String>,
+
+    /// Serve the run's output as server-sent events on this address instead
+    /// of opening the TUI
+    #[arg(long, value_name = "ADDR")]
+    pub http: Option<SocketAddr>,
+
+    /// Watch every active run (the primary loop and worktree loops) in one
+    /// TUI; press r to switch runs
+    #[arg(long, conflicts_with_all = ["run_id", "http"])]
+    pub all: bool,
+}
+
+/// Arguments for the open subcommand.
+#[derive(Parser, Debug)]
+pub struct OpenArgs {
+    /// Line to show, e.g. iter12:3401
+    pub location: LineId,
+
+    /// Run ID or a unique prefix; defaults to the current run
+    #[arg(long = "run", value_name = "RUN_ID")]
+    pub run_id: Option<String>,
+}
+
+/// Files a spectator reads from.
+#[derive(Debug, Clone)]
+struct RunFiles {
+    events: PathBuf,
+    output: PathBuf,
+    control: PathBuf,
+    audit: AuditLog,
+}
+
+/// Execute the attach command.
+pub async fn execute(
+    config_sources: &[ConfigSource],
+    args: AttachArgs,
+    verbose: bool,
+) -> Result<()> {
+    if args.all {
+        return watch_all_in_tui(config_sources, verbose).await;
+    }
+    let files = RunFiles::resolve(args.run_id.as_deref())?;
+
+    match args.http {
+        Some(addr) => {
+            let config = logs::load_local_config(config_sources)
+                .map(|config| config.http_api)
+                .unwrap_or_default();
+            let cipher = logs::local_cipher(config_sources)?;
+            serve(addr, files, &config, cipher).await
+        }
+        None => watch_in_tui(config_sources, &files, verbose, None).await,
+    }
+}
+
+/// Execute the open command: the attach TUI, jumping to a line once the
+/// replay reaches it.
+pub async fn open(config_sources: &[ConfigSource], args: OpenArgs, verbose: bool) -> Result<()> {
+    let files = RunFiles::resolve(args.run_id.as_deref())?;
+    watch_in_tui(config_sources, &files, verbose, Some(args.location)).await
+}
+
+impl RunFiles {
+    /// Locates the files of `run_id` (or the current run) in this workspace.
+    fn resolve(run_id: Option<&str>) -> Result<Self> {
+        Self::in_context(&LoopContext::primary(std::env::current_dir()?), run_id)
+    }
+
+    /// Locates the files of `run_id` (or the current run) of a loop.
+    fn in_context(ctx: &LoopContext, run_id: Option<&str>) -> Result<Self> {
+        let run_id = logs::resolve_run_id(ctx, run_id)?;
+        Ok(Self {
+            events: ctx.ralph_dir().join(format!("events-{run_id}.jsonl")),
+            output: ctx.run_log_path(&run_id),
+            control: ctx.control_path(),
+            audit: AuditLog::new(ctx.audit_path()).with_run_id(Some(run_id)),
+        })
+    }
+}
+
+/// How spectator TUIs render runs, from the workspace config if there is one.
+struct Replay {
+    config: Option<RalphConfig>,
+    tool_summaries: ToolSummaries,
+    /// Opens encrypted run logs
+    cipher: Option<Cipher>,
+    verbose: bool,
+}
+
+impl Replay {
+    fn new(config_sources: &[ConfigSource], verbose: bool) -> Result<Self> {
+        Ok(Self {
+            config: logs::load_local_config(config_sources),
+            tool_summaries: logs::tool_summaries(config_sources),
+            cipher: logs::local_cipher(config_sources)?,
+            verbose,
+        })
+    }
+
+    /// A TUI styled like the live one.
+    fn tui(&self) -> Result<Tui> {
+        let mut tui = Tui::new();
+        if let Some(config) = &self.config {
+            tui = tui
+                .with_hat_map(build_tui_hat_map(&HatRegistry::from_config(config)))
+                .with_hat_colors(build_tui_hat_colors(config))
+                .with_footer_segments(config.tui.footer.clone())
+                .with_alerts(ralph_core::AlertMatcher::new(&config.alerts)?);
+        }
+        Ok(tui)
+    }
+
+    /// Empty state for one of several watched runs.
+    fn state(&self) -> TuiState {
+        let Some(config) = &self.config else {
+            return TuiState::new();
+        };
+        let mut state =
+            TuiState::with_hat_map(build_tui_hat_map(&HatRegistry::from_config(config)));
+        state.set_hat_colors(build_tui_hat_colors(config));
+        state
+    }
+
+    /// Feeds the run's recorded events and output into `state` until aborted.
+    fn spawn_feeder(
+        &self,
+        files: &RunFiles,
+        state: Arc<Mutex<TuiState>>,
+    ) -> Result<JoinHandle<()>> {
+        let mut output =
+            JsonlTail::<AgentOutputEntry>::open(&files.output)?.with_cipher(self.cipher.clone());
+        // Runs started before event logging was enabled have no events file
+        let mut events = JsonlTail::<EventRecord>::open(&files.events).ok();
+
+        // No tool result store, so spectators never write result files of their own
+        let mut handler = TuiStreamHandler::with_lines(self.verbose, Arc::default())
+            .with_tool_summaries(self.tool_summaries.clone())
+            .with_iteration_buffers(TuiState::iteration_buffers(&state));
+        // Line ids only match the live TUI if tool results are previewed alike
+        if let Some(config) = &self.config {
+            handler = handler
+                .with_result_preview(config.tui.tool_result_preview)
+                .with_iteration_separators(build_tui_hat_colors(config));
+        }
+        Ok(tokio::spawn(async move {
+            let mut current = None;
+            loop {
+                if let Some(events) = events.as_mut() {
+                    while let Ok(Some(record)) = events.next_record() {
+                        if let Ok(mut s) = state.lock() {
+                            s.update(&Event::new(record.topic.as_str(), record.payload));
+                        }
+                    }
+                }
+
+                while let Ok(Some(entry)) = output.next_record() {
+                    if current != Some(entry.iteration) {
+                        handler.on_iteration_start(&IterationInfo::new(
+                            entry.iteration,
+                            entry.hat.as_str(),
+                            "",
+                        ));
+                        current = Some(entry.iteration);
+                    }
+                    logs::replay(&mut handler, &entry.content);
+                }
+                if let Ok(mut s) = state.lock() {
+                    s.apply_pending_jump();
+                }
+
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        }))
+    }
+}
+
+/// Shows the run in a TUI that is fed from the recorded files.
+async fn watch_in_tui(
+    config_sources: &[ConfigSource],
+    files: &RunFiles,
+    verbose: bool,
+    jump: Option<LineId>,
+) -> Result<()> {
+    let replay = Replay::new(config_sources, verbose)?;
+
+    // The run outlives the spectator: the TUI only exits on q or Ctrl+C, and
+    // without an interrupt channel neither reaches the loop.
+    let (_terminated_tx, terminated_rx) = tokio::sync::watch::channel(false);
+    let tui = replay.tui()?.with_termination_signal(terminated_rx);
+
+    let state = tui.state();
+    if let Some(id) = jump
+        && let Ok(mut s) = state.lock()
+    {
+        s.request_jump(id);
+    }
+    let feeder = replay.spawn_feeder(files, state)?;
+
+    let result = tui.run().await;
+    feeder.abort();
+    result
+}
+
+/// Shows every active run of the repository in one TUI, with `r` to switch
+/// between them. Runs that start later are added as they appear; finished
+/// runs stay in the list.
+async fn watch_all_in_tui(config_sources: &[ConfigSource], verbose: bool) -> Result<()> {
+    let repo_root = std::env::current_dir()?;
+    if active_runs(&repo_root).is_empty() {
+        bail!("No active runs. Start one with `ralph run`.");
+    }
+    let replay = Replay::new(config_sources, verbose)?;
+    let runs = Arc::new(Mutex::new(RunList::default()));
+
+    let (_terminated_tx, terminated_rx) = tokio::sync::watch::channel(false);
+    let tui = replay
+        .tui()?
+        .with_runs(Arc::clone(&runs))
+        .with_termination_signal(terminated_rx);
+
+    let watcher = tokio::spawn(async move {
+        let mut watched: Vec<(String, LoopContext, JoinHandle<()>)> = Vec::new();
+        loop {
+            for (id, ctx) in active_runs(&repo_root) {
+                if watched.iter().any(|(watched_id, ..)| *watched_id == id) {
+                    continue;
+                }
+                // The run may not have written its log yet; retried next round
+                let Ok(files) = RunFiles::in_context(&ctx, None) else {
+                    continue;
+                };
+                let state = runs.lock().unwrap().add(&id, replay.state());
+                match replay.spawn_feeder(&files, state) {
+                    Ok(feeder) => watched.push((id, ctx, feeder)),
+                    Err(e) => debug!("Can't watch run {}: {}", id, e),
+                }
+            }
+
+            for (id, ctx, _) in &watched {
+                let status = RunStatus::read(&ctx.run_status_path()).ok().flatten();
+                let (line, active) = status_line(status.as_ref());
+                runs.lock().unwrap().set_status(id, line, active);
+            }
+
+            tokio::time::sleep(RUNS_POLL_INTERVAL).await;
+        }
+    });
+
+    let result = tui.run().await;
+    watcher.abort();
+    result
+}
+
+/// The runs going on in the repository at `repo_root`: the primary loop if it
+/// holds the loop lock, and the worktree loops in the loop registry.
+fn active_runs(repo_root: &Path) -> Vec<(String, LoopContext)> {
+    let mut runs = Vec::new();
+    if LoopLock::is_locked(repo_root).unwrap_or(false) {
+        runs.push((
+            PRIMARY_RUN.to_string(),
+            LoopContext::primary(repo_root.to_path_buf()),
+        ));
+    }
+    for entry in LoopRegistry::new(repo_root).list().unwrap_or_default() {
+        if let Some(worktree) = &entry.worktree_path
+            && entry.is_alive()
+        {
+            let ctx = LoopContext::worktree(
+                entry.id.clone(),
+                PathBuf::from(worktree),
+                repo_root.to_path_buf(),
+            );
+            runs.push((entry.id, ctx));
+        }
+    }
+    runs
+}
+
+/// The run selector's line for a run, and whether the run is still going.
+fn status_line(status: Option<&RunStatus>) -> (String, bool) {
+    let Some(status) = status else {
+        return ("starting".to_string(), true);
+    };
+    match status.phase {
+        RunPhase::Running => {
+            let mut line = format!(
+                "iteration {}/{} · ${:.2}",
+                status.iteration, status.max_iterations, status.cost_usd
+            );
+            if let Some(hat) = &status.active_hat {
+                line.push_str(&format!(" · {hat}"));
+            }
+            (line, true)
+        }
+        RunPhase::Finished => {
+            let reason = status.termination_reason.as_deref().unwrap_or("stopped");
+            (
+                format!("finished ({reason}) after {} iterations", status.iteration),
+                false,
+            )
+        }
+    }
+}
+
+/// Serves the run over HTTP until interrupted.
+///
+/// - `GET /` or `GET /events`: agent output as server-sent events, one
+///   JSON-encoded entry per `data:` line, starting from the beginning of the run
+/// - `GET /control`: the current controller and pause state as JSON
+/// - `POST /control/{take,release,pause,resume,abort}`: control the run
+///   (controller tokens only, see [`crate::http_api`])
+async fn serve(
+    addr: SocketAddr,
+    files: RunFiles,
+    config: &HttpApiConfig,
+    cipher: Option<Cipher>,
+) -> Result<()> {
+    let auth = ApiAuth::from_config(config)?;
+    auth.check_bind(addr)?;
+    let tls = config
+        .tls
+        .as_ref()
+        .map(http_api::tls_acceptor)
+        .transpose()?;
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind {addr}"))?;
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    println!(
+        "Serving run output on {scheme}://{}/events (Ctrl+C to stop)",
+        listener.local_addr()?
+    );
+
+    let server = Arc::new(Server {
+        files,
+        auth,
+        cipher,
+    });
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted?;
+                let server = Arc::clone(&server);
+                let tls = tls.clone();
+                tokio::spawn(async move {
+                    let result = match tls {
+                        Some(tls) => match tls.accept(stream).await {
+                            Ok(stream) => server.handle(stream).await,
+                            Err(e) => Err(e),
+                        },
+                        None => server.handle(stream).await,
+                    };
+                    if let Err(e) = result {
+                        debug!("Spectator {} disconnected: {}", peer, e);
+                    }
+                });
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// State shared by all HTTP connections.
+struct Server {
+    files: RunFiles,
+    auth: ApiAuth,
+    /// Opens an encrypted run log before it is streamed
+    cipher: Option<Cipher>,
+}
+
+impl Server {
+    async fn handle<S>(&self, stream: S) -> std::io::Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let (mut stream, request_line, headers) = read_head(stream).await?;
+
+        let Some(request) = Request::parse(&request_line, &headers) else {
+            return respond(
+                &mut stream,
+                "400 Bad Request",
+                "text/plain",
+                "bad request\n",
+            )
+            .await;
+        };
+        let (role, command) = match (request.method, request.path) {
+            ("GET", "/" | "/events" | "/control") => (ApiRole::Viewer, None),
+            ("POST", path) if path.starts_with("/control/") => {
+                (ApiRole::Controller, Some(&path["/control/".len()..]))
+            }
+            _ => return respond(&mut stream, "404 Not Found", "text/plain", "not found\n").await,
+        };
+        let client =
+            match self
+                .auth
+                .authorize(request.token.as_deref(), role, std::time::Instant::now())
+            {
+                Ok(client) => client,
+                Err(e) => {
+                    let body = format!("{}\n", e.status());
+                    return respond(&mut stream, e.status(), "text/plain", &body).await;
+                }
+            };
+
+        match (command, client) {
+            (Some(command), Some(client)) => {
+                let (status, body) = match self.apply(command, client) {
+                    Ok(()) => ("200 OK", serde_json::json!({ "ok": true })),
+                    Err(e) => (
+                        "409 Conflict",
+                        serde_json::json!({ "error": e.to_string() }),
+                    ),
+                };
+                respond(&mut stream, status, "application/json", &body.to_string()).await
+            }
+            (Some(_), None) => unreachable!("controller requests always carry a token"),
+            (None, _) if request.path == "/control" => {
+                let body = match RunControl::new(&self.files.control).state() {
+                    Ok(state) => serde_json::to_string(&state).map_err(std::io::Error::other)?,
+                    Err(e) => serde_json::json!({ "error": e.to_string() }).to_string(),
+                };
+                respond(&mut stream, "200 OK", "application/json", &body).await
+            }
+            (None, _) => stream_output(&mut stream, &self.files.output, self.cipher.clone()).await,
+        }
+    }
+
+    /// Runs a control command on behalf of the token `client`.
+    fn apply(&self, command: &str, client: &str) -> Result<()> {
+        let control = RunControl::new(&self.files.control);
+        let action = match command {
+            "take" => {
+                control.claim(client, false)?;
+                AuditAction::TakeControl
+            }
+            "release" => {
+                control.release(client)?;
+                AuditAction::ReleaseControl
+            }
+            "pause" => {
+                control.send(client, ControlCommand::Pause)?;
+                AuditAction::Pause
+            }
+            "resume" => {
+                control.send(client, ControlCommand::Resume)?;
+                AuditAction::Resume
+            }
+            "abort" => {
+                control.send(client, ControlCommand::Abort)?;
+                AuditAction::Abort
+            }
+            other => bail!("unknown control command '{other}'"),
+        };
+        self.files
+            .audit
+            .record_or_warn(AuditEntry::new(action, AuditSource::Http).with_actor(client));
+        Ok(())
+    }
+}
+
+/// Streams the run log as server-sent events until the client disconnects.
+async fn stream_output<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    path: &std::path::Path,
+    cipher: Option<Cipher>,
+) -> std::io::Result<()> {
+    let mut tail = match JsonlTail::<AgentOutputEntry>::open(path) {
+        Ok(tail) => tail.with_cipher(cipher),
+        Err(e) => {
+            let body = format!("{e:#}\n");
+            return respond(stream, "404 Not Found", "text/plain", &body).await;
+        }
+    };
+
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+        )
+        .await?;
+
+    let mut idle_polls = 0;
+    loop {
+        while let Some(entry) = tail.next_record()? {
+            let json = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+            stream
+                .write_all(format!("data: {json}\n\n").as_bytes())
+                .await?;
+            idle_polls = 0;
+        }
+        stream.flush().await?;
+
+        idle_polls += 1;
+        if idle_polls >= KEEPALIVE_POLLS {
+            // Comment lines keep proxies from closing an idle stream and
+            // reveal disconnected clients
+            stream.write_all(b": keepalive\n\n").await?;
+            idle_polls = 0;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralph_core::ApiTokenConfig;
+    use tempfile::TempDir;
+    use tokio::io::AsyncReadExt;
+
+    fn server(temp: &TempDir, tokens: Vec<ApiTokenConfig>) -> Server {
+        let files = RunFiles {
+            events: temp.path().join("events.jsonl"),
+            output: temp.path().join("output.jsonl"),
+            control: temp.path().join("control.json"),
+            audit: AuditLog::new(temp.path().join("audit.jsonl")),
+        };
+        RunControl::new(&files.control).reset(Some("tui")).unwrap();
+        let auth = ApiAuth::from_config(&HttpApiConfig { tokens, tls: None }).unwrap();
+        Server {
+            files,
+            auth,
+            cipher: None,
+        }
+    }
+
+    async fn request(server: &Server, request: &str) -> String {
+        let (mut client, stream) = tokio::io::duplex(4096);
+        client.write_all(request.as_bytes()).await.unwrap();
+        server.handle(stream).await.unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[test]
+    fn test_status_line_describes_progress() {
+        let mut status = RunStatus::new(None, 100, None);
+        status.iteration = 3;
+        status.cost_usd = 0.421;
+        status.active_hat = Some("builder".to_string());
+        assert_eq!(
+            status_line(Some(&status)),
+            ("iteration 3/100 · $0.42 · builder".to_string(), true)
+        );
+
+        status.phase = RunPhase::Finished;
+        status.termination_reason = Some("completed".to_string());
+        assert_eq!(
+            status_line(Some(&status)),
+            ("finished (completed) after 3 iterations".to_string(), false)
+        );
+        assert_eq!(status_line(None), ("starting".to_string(), true));
+    }
+
+    #[tokio::test]
+    async fn test_control_endpoint_reports_controller() {
+        let temp = TempDir::new().unwrap();
+        let server = server(&temp, Vec::new());
+
+        let response = request(&server, "GET /control HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains(r#""controller":"tui""#));
+    }
+
+    #[tokio::test]
+    async fn test_only_controller_tokens_can_control() {
+        let temp = TempDir::new().unwrap();
+        let token = |name: &str, role| ApiTokenConfig {
+            name: name.to_string(),
+            token: Some(format!("{name}-secret")),
+            token_env: None,
+            role,
+            rate_limit_per_minute: None,
+        };
+        let server = server(
+            &temp,
+            vec![
+                token("dashboard", ApiRole::Viewer),
+                token("oncall", ApiRole::Controller),
+            ],
+        );
+
+        let response = request(&server, "GET /control HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 401"));
+
+        let response = request(
+            &server,
+            "POST /control/take HTTP/1.1\r\nAuthorization: Bearer dashboard-secret\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 403"));
+
+        // The TUI holds the role until the token takes it over
+        let pause = "POST /control/pause HTTP/1.1\r\nAuthorization: Bearer oncall-secret\r\n\r\n";
+        assert!(request(&server, pause).await.starts_with("HTTP/1.1 409"));
+        RunControl::new(&server.files.control)
+            .hand_over("tui", "oncall")
+            .unwrap();
+        assert!(request(&server, pause).await.starts_with("HTTP/1.1 200"));
+
+        let audit = server.files.audit.entries().unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].action, AuditAction::Pause);
+        assert_eq!(audit[0].source, AuditSource::Http);
+        assert_eq!(audit[0].actor.as_deref(), Some("oncall"));
+    }
+}
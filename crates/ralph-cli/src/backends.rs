@@ -0,0 +1,218 @@
+//! CLI commands for the `ralph backends` namespace.
+//!
+//! Subcommands:
+//! - `list`: Show the adapters `cli.backend` and hats can select, with their
+//!   capabilities
+
+use crate::ConfigSource;
+use crate::display::colors;
+use crate::hats::ListFormat;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use ralph_adapters::{AdapterRegistry, PromptMode, is_backend_available};
+use std::io::Write;
+
+/// Inspect the available backends.
+#[derive(Parser, Debug)]
+pub struct BackendsArgs {
+    #[command(subcommand)]
+    pub command: Option<BackendsCommands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BackendsCommands {
+    /// List built-in and plugin adapters (default if no subcommand)
+    List {
+        /// Output format (table, json)
+        #[arg(long, default_value = "table")]
+        format: ListFormat,
+        /// Check whether each adapter's command is installed
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+/// Execute a backends command.
+pub fn execute(
+    config_sources: &[ConfigSource],
+    args: BackendsArgs,
+    use_colors: bool,
+) -> Result<()> {
+    let config = crate::load_config_with_overrides(config_sources)?;
+    let adapters = AdapterRegistry::from_config(&config);
+    let (format, check) = match args.command {
+        None => (ListFormat::Table, false),
+        Some(BackendsCommands::List { format, check }) => (format, check),
+    };
+
+    let installed: Option<Vec<bool>> = check.then(|| {
+        adapters
+            .iter()
+            .map(|entry| is_backend_available(&entry.backend().command))
+            .collect()
+    });
+    let mut stdout = std::io::stdout();
+    match format {
+        ListFormat::Table => list_backends(
+            &mut stdout,
+            &adapters,
+            &config.cli.backend,
+            installed.as_deref(),
+            use_colors,
+        ),
+        ListFormat::Json => list_backends_json(
+            &mut stdout,
+            &adapters,
+            &config.cli.backend,
+            installed.as_deref(),
+        ),
+    }
+}
+
+fn list_backends<W: Write>(
+    writer: &mut W,
+    adapters: &AdapterRegistry,
+    selected: &str,
+    installed: Option<&[bool]>,
+    use_colors: bool,
+) -> Result<()> {
+    let check_header = if installed.is_some() {
+        "INSTALLED  "
+    } else {
+        ""
+    };
+    writeln!(
+        writer,
+        "  {:<12} {:<9} {:<12} {:<12} {check_header}DESCRIPTION",
+        "NAME", "SOURCE", "OUTPUT", "INTERACTIVE"
+    )?;
+    writeln!(writer, "{}", "-".repeat(80))?;
+
+    for (i, entry) in adapters.iter().enumerate() {
+        let capabilities = entry.capabilities();
+        let marker = if entry.name() == selected { "*" } else { " " };
+        let source = if entry.is_builtin() {
+            "built-in"
+        } else {
+            "plugin"
+        };
+        let output = if capabilities.stream_json {
+            "stream-json"
+        } else {
+            "text"
+        };
+        let interactive = if capabilities.interactive {
+            "yes"
+        } else {
+            "no"
+        };
+        let check = match installed.map(|installed| installed[i]) {
+            Some(true) => format!("{:<11}", "yes"),
+            Some(false) if use_colors => {
+                format!("{}{:<11}{}", colors::DIM, "no", colors::RESET)
+            }
+            Some(false) => format!("{:<11}", "no"),
+            None => String::new(),
+        };
+        writeln!(
+            writer,
+            "{marker} {:<12} {source:<9} {output:<12} {interactive:<12} {check}{}",
+            entry.name(),
+            entry.description()
+        )?;
+    }
+
+    if selected == "custom" || selected == "auto" {
+        writeln!(writer, "\nSelected: {selected} (cli.backend)")?;
+    } else {
+        writeln!(writer, "\n* selected by cli.backend")?;
+    }
+    Ok(())
+}
+
+fn list_backends_json<W: Write>(
+    writer: &mut W,
+    adapters: &AdapterRegistry,
+    selected: &str,
+    installed: Option<&[bool]>,
+) -> Result<()> {
+    let backends: Vec<_> = adapters
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let capabilities = entry.capabilities();
+            let mut backend = serde_json::json!({
+                "name": entry.name(),
+                "description": entry.description(),
+                "builtin": entry.is_builtin(),
+                "command": entry.backend().command,
+                "selected": entry.name() == selected,
+                "capabilities": {
+                    "stream_json": capabilities.stream_json,
+                    "interactive": capabilities.interactive,
+                    "prompt_mode": match capabilities.prompt_mode {
+                        PromptMode::Arg => "arg",
+                        PromptMode::Stdin => "stdin",
+                    },
+                },
+            });
+            if let Some(installed) = installed {
+                backend["installed"] = installed[i].into();
+            }
+            backend
+        })
+        .collect();
+    serde_json::to_writer_pretty(&mut *writer, &backends)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralph_core::{PluginAdapterConfig, RalphConfig};
+
+    fn registry_with_plugin() -> AdapterRegistry {
+        let mut config = RalphConfig::default();
+        let plugin: PluginAdapterConfig = serde_json::from_value(serde_json::json!({
+            "command": "aider",
+            "description": "Aider in --message mode",
+        }))
+        .unwrap();
+        config.adapters.plugins.insert("aider".to_string(), plugin);
+        AdapterRegistry::from_config(&config)
+    }
+
+    #[test]
+    fn test_list_shows_capabilities_and_selection() {
+        let mut out = Vec::new();
+        list_backends(&mut out, &registry_with_plugin(), "aider", None, false).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains("  claude       built-in  stream-json  yes          Claude Code"));
+        assert!(out.contains(
+            "* aider        plugin    text         no           Aider in --message mode"
+        ));
+        assert!(!out.contains("INSTALLED"));
+    }
+
+    #[test]
+    fn test_list_json() {
+        let mut out = Vec::new();
+        list_backends_json(
+            &mut out,
+            &registry_with_plugin(),
+            "claude",
+            Some(&[true; 8]),
+        )
+        .unwrap();
+        let backends: Vec<serde_json::Value> = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(backends.len(), 8);
+        assert_eq!(backends[0]["selected"], true);
+        assert_eq!(backends[0]["capabilities"]["stream_json"], true);
+        assert_eq!(backends[7]["name"], "aider");
+        assert_eq!(backends[7]["builtin"], false);
+        assert_eq!(backends[7]["installed"], true);
+    }
+}
@@ -62,7 +62,11 @@ pub struct TestArgs {
 }
 
 #[derive(Parser, Debug)]
-pub struct DaemonArgs {}
+pub struct DaemonArgs {
+    /// Serve scheduled runs as /status.json and /schedule.ics on this address
+    #[arg(long, value_name = "ADDR")]
+    pub status_http: Option<std::net::SocketAddr>,
+}
 
 // ─────────────────────────────────────────────────────────────────────────────
 // DISPATCHER
@@ -398,9 +402,10 @@ async fn bot_test(args: TestArgs, use_colors: bool) -> Result<()> {
 /// Run the bot daemon — delegates to the configured communication adapter.
 ///
 /// Currently only Telegram is supported. The adapter implements
-/// [`DaemonAdapter`] and handles all platform-specific concerns.
+/// [`DaemonAdapter`] and handles all platform-specific concerns. Each run's
+/// outcome is recorded for the status feeds (see [`crate::schedule`]).
 async fn run_daemon(
-    _args: DaemonArgs,
+    args: DaemonArgs,
     config_sources: &[ConfigSource],
     use_colors: bool,
 ) -> Result<()> {
@@ -418,11 +423,13 @@ async fn run_daemon(
     }
 
     let config_path = match primary_sources.first() {
-        Some(ConfigSource::File(path)) => Some(if path.is_absolute() {
-            path.clone()
-        } else {
-            workspace_root.join(path)
-        }),
+        Some(ConfigSource::File(path)) => {
+            if path.is_absolute() {
+                path.clone()
+            } else {
+                workspace_root.join(path)
+            }
+        }
         Some(ConfigSource::Builtin(_)) => {
             anyhow::bail!(
                 "Builtin presets are not supported for `ralph bot daemon`. Use a file path via -c/--config."
@@ -434,12 +441,10 @@ async fn run_daemon(
             );
         }
         Some(ConfigSource::Override { .. }) => unreachable!("Partitioned out overrides"),
-        None => Some(workspace_root.join("ralph.yml")),
+        None => workspace_root.join("ralph.yml"),
     };
-    if let Some(ref path) = config_path
-        && !path.exists()
-    {
-        anyhow::bail!("Config file not found: {}", path.display());
+    if !config_path.exists() {
+        anyhow::bail!("Config file not found: {}", config_path.display());
     }
 
     // Resolve bot token and chat_id for Telegram adapter
@@ -455,22 +460,49 @@ async fn run_daemon(
         println!("Ralph Daemon (Telegram)");
     }
 
+    // The status feeds are served next to the adapter
+    let mut status_server = None;
+    if let Some(addr) = args.status_http {
+        let mut config =
+            crate::load_config_with_overrides(&[ConfigSource::File(config_path.clone())])?;
+        config.normalize();
+        config
+            .validate()
+            .context("Configuration validation failed")?;
+        let root = workspace_root.clone();
+        status_server = Some(tokio::spawn(async move {
+            let schedules = config.daemon.schedules;
+            if let Err(e) =
+                crate::schedule::serve_status(addr, schedules, root, &config.http_api).await
+            {
+                warn!("Schedule status server stopped: {:#}", e);
+            }
+        }));
+    }
+
     // Build the adapter
     let adapter = ralph_telegram::TelegramDaemon::new(token, chat_id);
 
     // Build the start_loop callback — wraps our CLI loop runner
+    let history = crate::schedule::ScheduleHistory::new(&workspace_root);
     let start_loop: ralph_proto::StartLoopFn = Box::new(move |prompt: String| {
         let config_path = config_path.clone();
+        let history = history.clone();
         Box::pin(async move {
             let ws = std::env::current_dir()?;
-            let reason = crate::loop_runner::start_loop(prompt, ws, config_path).await?;
-            Ok(format!("{:?}", reason))
+            let started_at = chrono::Utc::now();
+            let result =
+                crate::loop_runner::start_loop(prompt.clone(), ws, Some(config_path)).await;
+            history.record_run(&prompt, started_at, &result);
+            Ok(format!("{:?}", result?))
         })
     });
 
-    adapter.run_daemon(workspace_root, start_loop).await?;
-
-    Ok(())
+    let result = adapter.run_daemon(workspace_root, start_loop).await;
+    if let Some(task) = status_server {
+        task.abort();
+    }
+    result
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -0,0 +1,136 @@
+//! CLI commands for the `ralph control` namespace.
+//!
+//! Any number of clients can watch a run (`ralph attach`, `ralph logs -f`),
+//! but only the run's controller can pause, resume or abort it.
+//!
+//! Subcommands:
+//! - `status`: Show the controller and whether the loop is paused
+//! - `take` / `give` / `release`: Move the controller role
+//! - `pause` / `resume` / `abort`: Send a command as the controller
+//!
+//! Successful role changes and commands are recorded in the audit log.
+
+use crate::display::colors;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use ralph_core::{AuditAction, AuditEntry, AuditSource, ControlCommand, LoopContext, RunControl};
+
+/// Inspect or change who controls the running loop.
+#[derive(Parser, Debug)]
+pub struct ControlArgs {
+    #[command(subcommand)]
+    pub command: Option<ControlCommands>,
+
+    /// Client name to act as (default: $RALPH_CLIENT, then $USER)
+    #[arg(long = "as", global = true)]
+    pub client: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ControlCommands {
+    /// Show the controller and pause state (default if no subcommand)
+    Status,
+    /// Become the controller
+    Take {
+        /// Take the role even if another client holds it
+        #[arg(long)]
+        force: bool,
+    },
+    /// Hand the controller role to another client (the local TUI is "tui")
+    Give {
+        /// Client to hand control to
+        to: String,
+    },
+    /// Give up the controller role
+    Release,
+    /// Pause the loop before its next iteration
+    Pause,
+    /// Resume a paused loop
+    Resume,
+    /// Stop the loop
+    Abort,
+}
+
+/// Execute a control command.
+pub fn execute(args: ControlArgs, use_colors: bool) -> Result<()> {
+    let ctx = LoopContext::primary(std::env::current_dir()?);
+    let control = RunControl::new(ctx.control_path());
+    let client = args.client.unwrap_or_else(default_client);
+
+    let audit = |action| {
+        ctx.audit_log()
+            .record_or_warn(AuditEntry::new(action, AuditSource::Cli).with_actor(&client));
+    };
+
+    let message = match args.command.unwrap_or(ControlCommands::Status) {
+        ControlCommands::Status => {
+            let state = control.state()?;
+            let controller = state.controller.as_deref().unwrap_or("none");
+            let status = if state.paused { "paused" } else { "running" };
+            if use_colors {
+                println!(
+                    "Controller: {}{}{}",
+                    colors::CYAN,
+                    controller,
+                    colors::RESET
+                );
+            } else {
+                println!("Controller: {controller}");
+            }
+            println!("Loop:       {status}");
+            if !state.pending.is_empty() {
+                println!("Pending:    {} command(s)", state.pending.len());
+            }
+            return Ok(());
+        }
+        ControlCommands::Take { force } => {
+            control.claim(&client, force)?;
+            audit(AuditAction::TakeControl);
+            format!("'{client}' is now the controller")
+        }
+        ControlCommands::Give { to } => {
+            control.hand_over(&client, &to)?;
+            ctx.audit_log().record_or_warn(
+                AuditEntry::new(AuditAction::HandOverControl, AuditSource::Cli)
+                    .with_actor(&client)
+                    .with_detail(&to),
+            );
+            format!("Handed control to '{to}'")
+        }
+        ControlCommands::Release => {
+            control.release(&client)?;
+            audit(AuditAction::ReleaseControl);
+            "Released control".to_string()
+        }
+        ControlCommands::Pause => {
+            control.send(&client, ControlCommand::Pause)?;
+            audit(AuditAction::Pause);
+            "Pause requested; the loop stops before its next iteration".to_string()
+        }
+        ControlCommands::Resume => {
+            control.send(&client, ControlCommand::Resume)?;
+            audit(AuditAction::Resume);
+            "Resume requested".to_string()
+        }
+        ControlCommands::Abort => {
+            control.send(&client, ControlCommand::Abort)?;
+            audit(AuditAction::Abort);
+            "Abort requested".to_string()
+        }
+    };
+
+    if use_colors {
+        println!("{}✓{} {}", colors::GREEN, colors::RESET, message);
+    } else {
+        println!("{message}");
+    }
+    Ok(())
+}
+
+/// Name used for this client when `--as` is not given.
+fn default_client() -> String {
+    ["RALPH_CLIENT", "USER", "USERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_else(|| "cli".to_string())
+}
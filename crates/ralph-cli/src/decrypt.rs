@@ -0,0 +1,48 @@
+//! CLI command for `ralph decrypt`.
+//!
+//! Prints a file written with `encryption.enabled` (a run log, session
+//! recording, post-mortem or tool result) as plain text, e.g. to read a
+//! post-mortem or to replay a recorded session with `ralph-bench`.
+
+use crate::ConfigSource;
+use crate::logs::local_cipher;
+use anyhow::{Context, Result, bail};
+use clap::Parser;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Arguments for the decrypt subcommand.
+#[derive(Parser, Debug)]
+pub struct DecryptArgs {
+    /// File to decrypt
+    pub file: PathBuf,
+
+    /// Write the plain text here instead of to stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Execute the decrypt command.
+pub fn execute(config_sources: &[ConfigSource], args: DecryptArgs) -> Result<()> {
+    let Some(cipher) = local_cipher(config_sources)? else {
+        bail!("Encryption is not enabled; set `encryption.enabled: true` in ralph.yml");
+    };
+    let content = std::fs::read_to_string(&args.file)
+        .with_context(|| format!("Failed to read {}", args.file.display()))?;
+
+    let mut plain = String::with_capacity(content.len());
+    for (number, line) in content.lines().enumerate() {
+        let line = cipher
+            .open(line)
+            .with_context(|| format!("{}:{}", args.file.display(), number + 1))?;
+        plain.push_str(&line);
+        plain.push('\n');
+    }
+
+    match args.output {
+        Some(path) => std::fs::write(&path, plain)
+            .with_context(|| format!("Failed to write {}", path.display()))?,
+        None => std::io::stdout().write_all(plain.as_bytes())?,
+    }
+    Ok(())
+}
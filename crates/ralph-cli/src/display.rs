@@ -6,8 +6,10 @@
 
 use ralph_core::{EventRecord, TerminationReason};
 use ralph_proto::HatId;
+use ratatui::style::Color;
 use std::collections::HashMap;
 use std::time::Duration;
+use tracing::warn;
 
 /// ANSI color codes for terminal output.
 pub mod colors {
@@ -43,11 +45,15 @@ pub fn hat_emoji(hat_id: &str) -> &'static str {
 ///  ITERATION 3 | ? builder | 2m 15s elapsed | 3/100
 /// ===============================================================================
 /// ```
+///
+/// The separator is drawn in the hat's color, so multi-hat runs can be
+/// scanned by color.
 pub fn print_iteration_separator(
     iteration: u32,
     hat_id: &str,
     elapsed: Duration,
     max_iterations: u32,
+    hat_color: Color,
     use_colors: bool,
 ) {
     use colors::*;
@@ -66,9 +72,10 @@ pub fn print_iteration_separator(
     let separator = "=".repeat(box_width);
 
     if use_colors {
-        println!("\n{BOLD}{CYAN}{separator}{RESET}");
-        println!("{BOLD}{CYAN}{content}{RESET}");
-        println!("{BOLD}{CYAN}{separator}{RESET}");
+        let color = ansi_fg(hat_color);
+        println!("\n{BOLD}{color}{separator}{RESET}");
+        println!("{BOLD}{color}{content}{RESET}");
+        println!("{BOLD}{color}{separator}{RESET}");
     } else {
         println!("\n{separator}");
         println!("{content}");
@@ -76,6 +83,32 @@ pub fn print_iteration_separator(
     }
 }
 
+/// Returns the ANSI escape code setting the foreground to `color`.
+pub fn ansi_fg(color: Color) -> String {
+    let code = match color {
+        Color::Reset => "39".to_string(),
+        Color::Black => "30".to_string(),
+        Color::Red => "31".to_string(),
+        Color::Green => "32".to_string(),
+        Color::Yellow => "33".to_string(),
+        Color::Blue => "34".to_string(),
+        Color::Magenta => "35".to_string(),
+        Color::Cyan => "36".to_string(),
+        Color::Gray => "37".to_string(),
+        Color::DarkGray => "90".to_string(),
+        Color::LightRed => "91".to_string(),
+        Color::LightGreen => "92".to_string(),
+        Color::LightYellow => "93".to_string(),
+        Color::LightBlue => "94".to_string(),
+        Color::LightMagenta => "95".to_string(),
+        Color::LightCyan => "96".to_string(),
+        Color::White => "97".to_string(),
+        Color::Rgb(r, g, b) => format!("38;2;{r};{g};{b}"),
+        Color::Indexed(index) => format!("38;5;{index}"),
+    };
+    format!("\x1b[{code}m")
+}
+
 /// Formats elapsed duration as human-readable string.
 pub fn format_elapsed(d: Duration) -> String {
     let total_secs = d.as_secs();
@@ -153,6 +186,10 @@ pub fn print_termination(
             (YELLOW, "?", "Chaos mode max iterations reached")
         }
         TerminationReason::RestartRequested => (CYAN, "↻", "Restarting by human request"),
+        other => {
+            tracing::error!(reason = ?other, "No termination message for reason");
+            (YELLOW, "?", "Loop stopped")
+        }
     };
 
     let separator = "-".repeat(58);
@@ -191,6 +228,33 @@ pub fn print_termination(
     }
 }
 
+/// Prints a triggered output alert, e.g. `⚠ Alert [payments]: "payments_service"`.
+pub fn print_alert(hit: &ralph_core::AlertHit, use_colors: bool) {
+    use colors::*;
+
+    let matched = truncate(&hit.matched, 60);
+    if use_colors {
+        println!(
+            "{BOLD}{RED}⚠ Alert{RESET} {YELLOW}[{}]{RESET}: \"{matched}\"",
+            hit.name
+        );
+    } else {
+        println!("⚠ Alert [{}]: \"{matched}\"", hit.name);
+    }
+}
+
+/// Prints an iteration whose cost or tokens were an outlier, e.g.
+/// `⚠ Cost anomaly: iteration 7 (builder) used 48.0k tokens, 4.0× the median of 12.0k tokens`.
+pub fn print_cost_anomaly(anomaly: &ralph_core::CostAnomaly, use_colors: bool) {
+    use colors::*;
+
+    if use_colors {
+        println!("{BOLD}{YELLOW}⚠ Cost anomaly:{RESET} {anomaly}");
+    } else {
+        println!("⚠ Cost anomaly: {anomaly}");
+    }
+}
+
 /// Gets the color for a topic based on its prefix.
 pub fn get_topic_color(topic: &str) -> &'static str {
     use colors::*;
@@ -318,11 +382,39 @@ pub fn build_tui_hat_map(registry: &ralph_core::HatRegistry) -> HashMap<String,
     map
 }
 
+/// Builds a map of hat IDs to TUI display colors from each hat's `color` setting.
+///
+/// Accepts ratatui color names ("cyan", "lightmagenta") and hex codes ("#ff8800").
+/// Unrecognized colors are logged and skipped so the hat falls back to the default style.
+pub fn build_tui_hat_colors(config: &ralph_core::RalphConfig) -> HashMap<HatId, Color> {
+    config
+        .hats
+        .iter()
+        .filter_map(|(id, hat)| {
+            let raw = hat.color.as_deref()?;
+            match raw.parse::<Color>() {
+                Ok(color) => Some((HatId::new(id), color)),
+                Err(_) => {
+                    warn!(hat = %id, color = %raw, "Ignoring unrecognized hat color");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use ralph_core::RalphConfig;
 
+    #[test]
+    fn test_ansi_fg_matches_named_and_rgb_colors() {
+        assert_eq!(ansi_fg(Color::Cyan), colors::CYAN);
+        assert_eq!(ansi_fg(Color::LightRed), "\x1b[91m");
+        assert_eq!(ansi_fg(Color::Rgb(255, 136, 0)), "\x1b[38;2;255;136;0m");
+    }
+
     #[test]
     fn test_format_elapsed_seconds_only() {
         let d = Duration::from_secs(45);
@@ -472,6 +564,38 @@ hats:
         );
     }
 
+    #[test]
+    fn test_build_tui_hat_colors_parses_names_and_hex() {
+        let yaml = r##"
+hats:
+  builder:
+    name: "🔨 Builder"
+    triggers: ["build.task"]
+    color: "cyan"
+  reviewer:
+    name: "🔍 Reviewer"
+    triggers: ["review.request"]
+    color: "#ff8800"
+  planner:
+    name: "📋 Planner"
+    triggers: ["plan.request"]
+    color: "not-a-color"
+  tester:
+    name: "🧪 Tester"
+    triggers: ["test.request"]
+"##;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+
+        let colors = build_tui_hat_colors(&config);
+
+        assert_eq!(colors.len(), 2, "invalid and missing colors are skipped");
+        assert_eq!(colors.get(&HatId::new("builder")), Some(&Color::Cyan));
+        assert_eq!(
+            colors.get(&HatId::new("reviewer")),
+            Some(&Color::Rgb(0xff, 0x88, 0x00))
+        );
+    }
+
     #[test]
     fn test_build_tui_hat_map_empty_registry() {
         // Given: An empty registry (solo mode)
@@ -0,0 +1,633 @@
+//! CLI command for `ralph doctor`.
+//!
+//! Checks the workspace for the setup problems that most often stop a loop
+//! before its first iteration: a missing or invalid `ralph.yml`, no prompt
+//! file, missing `.ralph/` directories, no git repository, git hooks that
+//! lost their executable bit, and a backend CLI that isn't installed.
+//!
+//! With `--fix`, the problems that can be repaired without guessing are
+//! repaired and each repair is reported. Initializing a git repository asks
+//! first unless `--yes` is given.
+
+use crate::display::colors;
+use crate::init;
+use anyhow::{Context, Result, bail};
+use clap::Parser;
+use ralph_core::{LoopContext, RalphConfig};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prompt file written by `--fix` when the configured one is missing.
+const PROMPT_TEMPLATE: &str = "# Task
+
+Describe what Ralph should build or change.
+
+## Done when
+
+- The change is implemented and the tests pass.
+";
+
+/// Check the workspace for setup problems.
+#[derive(Parser, Debug)]
+pub struct DoctorArgs {
+    /// Fix the problems that can be fixed automatically
+    #[arg(long)]
+    pub fix: bool,
+
+    /// With --fix, don't ask before running `git init`
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// A repair `--fix` knows how to make.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Fix {
+    /// Write a config from the `ralph init` template.
+    WriteConfig,
+    /// Write a placeholder prompt file.
+    WritePrompt(PathBuf),
+    /// Create missing directories.
+    CreateDirs(Vec<PathBuf>),
+    /// Run `git init` (asks first).
+    GitInit,
+    /// Add executable bits to hook scripts.
+    MakeExecutable(Vec<PathBuf>),
+}
+
+#[derive(Debug)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    fix: Option<Fix>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(
+        name: &'static str,
+        status: Status,
+        detail: impl Into<String>,
+        fix: Option<Fix>,
+    ) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+            fix,
+        }
+    }
+}
+
+/// Execute the doctor command.
+pub fn execute(config_path: &Path, args: DoctorArgs, use_colors: bool) -> Result<()> {
+    let root = std::env::current_dir()?;
+    let mut stdout = std::io::stdout();
+
+    if args.fix {
+        let mut confirm = |question: &str| {
+            if args.yes {
+                return true;
+            }
+            if !std::io::stdin().is_terminal() {
+                return false;
+            }
+            eprint!("{question} [y/N] ");
+            let mut input = String::new();
+            let answered = std::io::stdin().read_line(&mut input).is_ok();
+            answered && input.trim().eq_ignore_ascii_case("y")
+        };
+
+        // A fix can unlock further checks (the prompt file is only checked
+        // once a config exists), so diagnose again until nothing new is fixed.
+        let mut fixed = Vec::new();
+        let mut attempted = Vec::new();
+        loop {
+            let pending: Vec<Check> = diagnose(&root, config_path)
+                .into_iter()
+                .filter(|check| check.fix.is_some() && !attempted.contains(&check.name))
+                .collect();
+            if pending.is_empty() {
+                break;
+            }
+            for check in pending {
+                attempted.push(check.name);
+                let Some(fix) = check.fix else {
+                    continue;
+                };
+                match apply_fix(&root, config_path, &fix, &mut confirm) {
+                    Ok(Some(done)) => fixed.push(done),
+                    Ok(None) => writeln!(
+                        stdout,
+                        "Skipped {}: rerun with --yes to allow it",
+                        check.name
+                    )?,
+                    Err(e) => writeln!(stdout, "Could not fix {}: {e:#}", check.name)?,
+                }
+            }
+        }
+        if fixed.is_empty() {
+            writeln!(stdout, "Nothing to fix.")?;
+        } else {
+            writeln!(stdout, "Fixed:")?;
+            for done in &fixed {
+                writeln!(stdout, "  {done}")?;
+            }
+        }
+        writeln!(stdout)?;
+    }
+
+    let checks = diagnose(&root, config_path);
+    for check in &checks {
+        let (mark, color) = match check.status {
+            Status::Ok => ("✓", colors::GREEN),
+            Status::Warning => ("!", colors::YELLOW),
+            Status::Error => ("✗", colors::RED),
+        };
+        let fixable = if check.fix.is_some() {
+            " (fixable)"
+        } else {
+            ""
+        };
+        if use_colors {
+            writeln!(
+                stdout,
+                "{color}{mark}{} {:<10} {}{fixable}",
+                colors::RESET,
+                check.name,
+                check.detail
+            )?;
+        } else {
+            writeln!(
+                stdout,
+                "{mark} {:<10} {}{fixable}",
+                check.name, check.detail
+            )?;
+        }
+    }
+
+    let fixable = checks.iter().filter(|check| check.fix.is_some()).count();
+    if fixable > 0 && !args.fix {
+        writeln!(stdout)?;
+        writeln!(
+            stdout,
+            "Run `ralph doctor --fix` to fix {fixable} problem(s)."
+        )?;
+    }
+
+    let errors = checks
+        .iter()
+        .filter(|check| check.status == Status::Error)
+        .count();
+    if errors > 0 {
+        bail!("{errors} problem(s) must be fixed before running Ralph");
+    }
+    Ok(())
+}
+
+/// Runs every check against the workspace at `root`.
+fn diagnose(root: &Path, config_path: &Path) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let config_file = root.join(config_path);
+    let config = if config_file.exists() {
+        match load_config(&config_file) {
+            Ok(config) => {
+                checks.push(Check::ok(
+                    "config",
+                    format!("{} is valid", config_path.display()),
+                ));
+                Some(config)
+            }
+            Err(e) => {
+                checks.push(Check::problem(
+                    "config",
+                    Status::Error,
+                    format!("{e:#}"),
+                    None,
+                ));
+                None
+            }
+        }
+    } else {
+        checks.push(Check::problem(
+            "config",
+            Status::Error,
+            format!("{} not found", config_path.display()),
+            Some(Fix::WriteConfig),
+        ));
+        None
+    };
+
+    if let Some(config) = &config {
+        checks.push(check_prompt(root, config));
+        if let Some(check) = check_hats(config) {
+            checks.push(check);
+        }
+    }
+    checks.push(check_workspace(root));
+    match git_dir(root) {
+        Some(git_dir) => {
+            checks.push(Check::ok("git", "workspace is a git repository"));
+            checks.push(check_hooks(&git_dir.join("hooks")));
+        }
+        None => checks.push(Check::problem(
+            "git",
+            Status::Warning,
+            "not a git repository; parallel loops and merges need one",
+            Some(Fix::GitInit),
+        )),
+    }
+    if let Some(config) = &config {
+        checks.push(check_backend(config));
+    }
+
+    checks
+}
+
+fn load_config(path: &Path) -> Result<RalphConfig> {
+    let mut config = RalphConfig::from_file(path)?;
+    config.normalize();
+    config.validate()?;
+    Ok(config)
+}
+
+fn check_prompt(root: &Path, config: &RalphConfig) -> Check {
+    if config.event_loop.prompt.is_some() {
+        return Check::ok("prompt", "inline prompt in config");
+    }
+    let prompt_file = &config.event_loop.prompt_file;
+    if root.join(prompt_file).exists() {
+        Check::ok("prompt", format!("{prompt_file} found"))
+    } else {
+        Check::problem(
+            "prompt",
+            Status::Warning,
+            format!("{prompt_file} not found; `ralph run` will need -p or -P"),
+            Some(Fix::WritePrompt(PathBuf::from(prompt_file))),
+        )
+    }
+}
+
+/// Hats without instructions only get the generic hat prompt. Their YAML
+/// is left alone since rewriting it would drop the user's comments.
+fn check_hats(config: &RalphConfig) -> Option<Check> {
+    if config.hats.is_empty() {
+        return None;
+    }
+    let mut bare: Vec<&str> = config
+        .hats
+        .iter()
+        .filter(|(_, hat)| hat.instructions.trim().is_empty())
+        .map(|(id, _)| id.as_str())
+        .collect();
+    bare.sort_unstable();
+    Some(if bare.is_empty() {
+        Check::ok("hats", format!("{} hat(s) configured", config.hats.len()))
+    } else {
+        Check::problem(
+            "hats",
+            Status::Warning,
+            format!("no instructions for: {}", bare.join(", ")),
+            None,
+        )
+    })
+}
+
+fn check_workspace(root: &Path) -> Check {
+    let ctx = LoopContext::primary(root.to_path_buf());
+    let missing: Vec<PathBuf> = [
+        ctx.ralph_dir(),
+        ctx.agent_dir(),
+        ctx.specs_dir(),
+        ctx.code_tasks_dir(),
+    ]
+    .into_iter()
+    .filter(|dir| !dir.exists())
+    .collect();
+
+    if missing.is_empty() {
+        Check::ok("workspace", ".ralph directories present")
+    } else {
+        Check::problem(
+            "workspace",
+            Status::Warning,
+            format!("missing {}", display_paths(root, &missing)),
+            Some(Fix::CreateDirs(missing)),
+        )
+    }
+}
+
+fn check_hooks(hooks_dir: &Path) -> Check {
+    let not_executable = non_executable_hooks(hooks_dir);
+    if not_executable.is_empty() {
+        Check::ok("hooks", "git hooks are executable")
+    } else {
+        Check::problem(
+            "hooks",
+            Status::Warning,
+            format!(
+                "not executable: {}",
+                display_paths(hooks_dir, &not_executable)
+            ),
+            Some(Fix::MakeExecutable(not_executable)),
+        )
+    }
+}
+
+/// Hook scripts git would skip because they lack an executable bit.
+/// Git's own `*.sample` files are meant to be inactive.
+#[cfg(unix)]
+fn non_executable_hooks(hooks_dir: &Path) -> Vec<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Ok(entries) = std::fs::read_dir(hooks_dir) else {
+        return Vec::new();
+    };
+    let mut hooks: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_none_or(|ext| ext != "sample"))
+        .filter(|path| {
+            std::fs::metadata(path)
+                .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 == 0)
+        })
+        .collect();
+    hooks.sort();
+    hooks
+}
+
+#[cfg(not(unix))]
+fn non_executable_hooks(_hooks_dir: &Path) -> Vec<PathBuf> {
+    Vec::new()
+}
+
+fn check_backend(config: &RalphConfig) -> Check {
+    let backend = config.cli.backend.as_str();
+    if backend == "auto" {
+        let priority = config.get_agent_priority();
+        return match ralph_adapters::detect_backend(&priority, |backend| {
+            config.adapter_settings(backend).enabled
+        }) {
+            Ok(detected) => Check::ok("backend", format!("auto-detected {detected}")),
+            Err(e) => Check::problem("backend", Status::Error, e.to_string(), None),
+        };
+    }
+
+    let available = match &config.cli.command {
+        Some(command) => Command::new(command).arg("--version").output().is_ok(),
+        None => ralph_adapters::is_backend_available(backend),
+    };
+    let command = config.cli.command.as_deref().unwrap_or(backend);
+    if available {
+        Check::ok("backend", format!("{command} is installed"))
+    } else {
+        Check::problem(
+            "backend",
+            Status::Error,
+            format!("{command} not found on PATH"),
+            None,
+        )
+    }
+}
+
+/// Returns the repository's git directory, if `root` is inside one.
+fn git_dir(root: &Path) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--absolute-git-dir"])
+        .current_dir(root)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// Applies `fix`, returning what was done, or `None` if the user declined.
+fn apply_fix(
+    root: &Path,
+    config_path: &Path,
+    fix: &Fix,
+    confirm: &mut dyn FnMut(&str) -> bool,
+) -> Result<Option<String>> {
+    let done = match fix {
+        Fix::WriteConfig => {
+            let backend =
+                ralph_adapters::detect_backend_default().unwrap_or_else(|_| "claude".to_string());
+            let path = root.join(config_path);
+            std::fs::write(&path, init::generate_template(&backend))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            format!("created {} for {backend}", config_path.display())
+        }
+        Fix::WritePrompt(prompt_file) => {
+            let path = root.join(prompt_file);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, PROMPT_TEMPLATE)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            format!(
+                "created {} (edit it to describe your task)",
+                prompt_file.display()
+            )
+        }
+        Fix::CreateDirs(dirs) => {
+            for dir in dirs {
+                std::fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create {}", dir.display()))?;
+            }
+            format!("created {}", display_paths(root, dirs))
+        }
+        Fix::GitInit => {
+            if !confirm(&format!(
+                "Initialize a git repository in {}?",
+                root.display()
+            )) {
+                return Ok(None);
+            }
+            let output = Command::new("git")
+                .arg("init")
+                .current_dir(root)
+                .output()
+                .context("Failed to run git")?;
+            if !output.status.success() {
+                bail!(
+                    "git init failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+            "initialized a git repository".to_string()
+        }
+        Fix::MakeExecutable(hooks) => {
+            make_executable(hooks)?;
+            let names: Vec<String> = hooks
+                .iter()
+                .filter_map(|hook| hook.file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+                .collect();
+            format!("made executable: {}", names.join(", "))
+        }
+    };
+    Ok(Some(done))
+}
+
+#[cfg(unix)]
+fn make_executable(paths: &[PathBuf]) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    for path in paths {
+        let mut permissions = std::fs::metadata(path)?.permissions();
+        // Executable by whoever can read it, like `chmod +x`.
+        permissions.set_mode(permissions.mode() | ((permissions.mode() & 0o444) >> 2));
+        std::fs::set_permissions(path, permissions)
+            .with_context(|| format!("Failed to chmod {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_paths: &[PathBuf]) -> Result<()> {
+    Ok(())
+}
+
+fn display_paths(base: &Path, paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| {
+            path.strip_prefix(base)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn find<'a>(checks: &'a [Check], name: &str) -> &'a Check {
+        checks
+            .iter()
+            .find(|check| check.name == name)
+            .unwrap_or_else(|| panic!("no {name} check"))
+    }
+
+    fn fix_all(root: &Path, confirm: bool) {
+        for check in diagnose(root, Path::new("ralph.yml")) {
+            if let Some(fix) = check.fix {
+                apply_fix(root, Path::new("ralph.yml"), &fix, &mut |_| confirm).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_directory_problems_are_fixable() {
+        let temp = TempDir::new().unwrap();
+        let checks = diagnose(temp.path(), Path::new("ralph.yml"));
+
+        let config = find(&checks, "config");
+        assert_eq!(config.status, Status::Error);
+        assert_eq!(config.fix, Some(Fix::WriteConfig));
+        assert_eq!(find(&checks, "workspace").status, Status::Warning);
+        assert!(find(&checks, "workspace").fix.is_some());
+    }
+
+    #[test]
+    fn test_fix_scaffolds_config_prompt_and_directories() {
+        let temp = TempDir::new().unwrap();
+        fix_all(temp.path(), false);
+        // The prompt is only checked once there is a config.
+        fix_all(temp.path(), false);
+
+        let checks = diagnose(temp.path(), Path::new("ralph.yml"));
+        assert_eq!(find(&checks, "config").status, Status::Ok);
+        assert_eq!(find(&checks, "prompt").status, Status::Ok);
+        assert_eq!(find(&checks, "workspace").status, Status::Ok);
+        assert!(temp.path().join(".ralph/tasks").is_dir());
+    }
+
+    #[test]
+    fn test_git_init_needs_confirmation() {
+        let temp = TempDir::new().unwrap();
+        if git_dir(temp.path()).is_some() {
+            // The temp dir is inside a repository; nothing to initialize.
+            return;
+        }
+
+        let declined = apply_fix(
+            temp.path(),
+            Path::new("ralph.yml"),
+            &Fix::GitInit,
+            &mut |_| false,
+        )
+        .unwrap();
+        assert_eq!(declined, None);
+        assert!(git_dir(temp.path()).is_none());
+
+        let accepted = apply_fix(
+            temp.path(),
+            Path::new("ralph.yml"),
+            &Fix::GitInit,
+            &mut |_| true,
+        )
+        .unwrap();
+        assert!(accepted.is_some());
+        assert!(git_dir(temp.path()).is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hooks_without_exec_bit_are_fixed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let hooks = temp.path().join("hooks");
+        std::fs::create_dir(&hooks).unwrap();
+        std::fs::write(hooks.join("pre-commit"), "#!/bin/sh\n").unwrap();
+        std::fs::write(hooks.join("pre-push.sample"), "#!/bin/sh\n").unwrap();
+        for name in ["pre-commit", "pre-push.sample"] {
+            std::fs::set_permissions(hooks.join(name), std::fs::Permissions::from_mode(0o644))
+                .unwrap();
+        }
+
+        let check = check_hooks(&hooks);
+        assert_eq!(
+            check.fix,
+            Some(Fix::MakeExecutable(vec![hooks.join("pre-commit")]))
+        );
+        apply_fix(
+            temp.path(),
+            Path::new("ralph.yml"),
+            &check.fix.unwrap(),
+            &mut |_| false,
+        )
+        .unwrap();
+
+        let mode = std::fs::metadata(hooks.join("pre-commit"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+        assert_eq!(check_hooks(&hooks).status, Status::Ok);
+    }
+}
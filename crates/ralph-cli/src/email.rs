@@ -0,0 +1,536 @@
+//! Minimal SMTP client for email notifications.
+//!
+//! Supports plain, STARTTLS and implicit-TLS connections and `AUTH PLAIN`,
+//! which covers the usual relays and hosted mail providers. Messages are
+//! plain UTF-8 text, sent as 8-bit data where the server advertises
+//! `8BITMIME` and base64-encoded otherwise.
+
+use crate::notifications::{Notification, Notifier, render};
+use anyhow::{Context, Result, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ralph_core::{EmailNotifierConfig, NotificationEvent, SmtpSecurity};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+
+/// Connect, read and write timeout for the SMTP conversation.
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Name announced in `EHLO`.
+const EHLO_NAME: &str = "ralph";
+
+/// Bytes of header text per RFC 2047 encoded-word. 39 bytes make 52 base64
+/// characters, so a word stays well under the 75-character limit and a
+/// `Subject:` line holding one stays under 76.
+const ENCODED_WORD_BYTES: usize = 39;
+
+/// Length of the base64 lines of a message body.
+const BASE64_LINE_LEN: usize = 76;
+
+/// Sends email through the configured SMTP server.
+#[derive(Debug)]
+pub(crate) struct EmailNotifier {
+    config: EmailNotifierConfig,
+    password: Option<String>,
+}
+
+impl EmailNotifier {
+    /// Validates the config and resolves the password.
+    pub(crate) fn from_config(config: &EmailNotifierConfig) -> Result<Self> {
+        if config.to.is_empty() {
+            bail!("notifications.email.to must list at least one recipient");
+        }
+        let password = config.resolve_password();
+        if config.username.is_some() && password.is_none() {
+            bail!("notifications.email has a username but no password; set `password_env`");
+        }
+        if config.username.is_some()
+            && config.security == SmtpSecurity::None
+            && !is_loopback(&config.smtp_host)
+        {
+            bail!(
+                "notifications.email would send the password to {} unencrypted; use `security: starttls` or `tls`",
+                config.smtp_host
+            );
+        }
+        Ok(Self {
+            config: config.clone(),
+            password,
+        })
+    }
+
+    /// Sends one message to all recipients.
+    pub(crate) fn send_message(&self, subject: &str, body: &str) -> Result<()> {
+        let host = self.config.smtp_host.as_str();
+        let port = self.config.port();
+        let addr = (host, port)
+            .to_socket_addrs()
+            .with_context(|| format!("Failed to resolve SMTP host {host}"))?
+            .next()
+            .with_context(|| format!("SMTP host {host} has no address"))?;
+        let tcp = TcpStream::connect_timeout(&addr, SMTP_TIMEOUT)
+            .with_context(|| format!("Failed to connect to {host}:{port}"))?;
+        tcp.set_read_timeout(Some(SMTP_TIMEOUT))?;
+        tcp.set_write_timeout(Some(SMTP_TIMEOUT))?;
+
+        let conn = match self.config.security {
+            SmtpSecurity::Tls => Connection::tls(tcp, host)?,
+            SmtpSecurity::Starttls | SmtpSecurity::None => Connection::Plain(tcp),
+        };
+        let mut session = Session::new(conn);
+        session.expect(220)?;
+        let mut extensions = session.command(&format!("EHLO {EHLO_NAME}"), 250)?;
+
+        if self.config.security == SmtpSecurity::Starttls {
+            session.command("STARTTLS", 220)?;
+            session = Session::new(session.into_connection().upgrade(host)?);
+            extensions = session.command(&format!("EHLO {EHLO_NAME}"), 250)?;
+        }
+        let eight_bit = !body.is_ascii() && supports_8bitmime(&extensions);
+
+        if let (Some(user), Some(password)) = (&self.config.username, &self.password) {
+            let credentials = BASE64.encode(format!("\0{user}\0{password}"));
+            session
+                .command(&format!("AUTH PLAIN {credentials}"), 235)
+                .context("SMTP authentication failed")?;
+        }
+
+        let body_param = if eight_bit { " BODY=8BITMIME" } else { "" };
+        session.command(
+            &format!("MAIL FROM:<{}>{body_param}", address(&self.config.from)),
+            250,
+        )?;
+        for to in &self.config.to {
+            session.command(&format!("RCPT TO:<{}>", address(to)), 250)?;
+        }
+        session.command("DATA", 354)?;
+        let message = format_message(
+            &self.config.from,
+            &self.config.to,
+            subject,
+            body,
+            &chrono::Utc::now().to_rfc2822(),
+            eight_bit,
+        );
+        session.write_raw(&message)?;
+        session.command(".", 250)?;
+        // The message is accepted at this point; a failed QUIT doesn't matter
+        let _ = session.command("QUIT", 221);
+        Ok(())
+    }
+}
+
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn accepts(&self, notification: &Notification) -> bool {
+        self.config.events.contains(&notification.event)
+            && (notification.failure || !self.config.only_on_failure)
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        // The subject and body templates describe finished runs
+        if notification.event == NotificationEvent::RunFinished {
+            let vars = &notification.vars;
+            self.send_message(
+                &render(&self.config.subject, vars),
+                &render(&self.config.body, vars),
+            )
+        } else {
+            self.send_message(&notification.title, &notification.message)
+        }
+    }
+}
+
+/// Builds the message text sent after `DATA`, with CRLF line endings and
+/// dot-stuffing applied.
+///
+/// A non-ASCII body is sent as 8-bit data when `eight_bit` is set (the
+/// server advertised `8BITMIME`), and base64-encoded otherwise.
+fn format_message(
+    from: &str,
+    to: &[String],
+    subject: &str,
+    body: &str,
+    date: &str,
+    eight_bit: bool,
+) -> String {
+    let encoding = match (body.is_ascii(), eight_bit) {
+        (true, _) => "7bit",
+        (false, true) => "8bit",
+        (false, false) => "base64",
+    };
+    let mut message = format!(
+        "From: {from}\r\nTo: {}\r\nSubject: {}\r\nDate: {date}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: {encoding}\r\n\r\n",
+        to.join(", "),
+        encode_header(subject),
+    );
+    if encoding == "base64" {
+        let text: String = body.lines().flat_map(|line| [line, "\r\n"]).collect();
+        let encoded = BASE64.encode(text);
+        // Base64 is ASCII, so any byte offset is a char boundary
+        for start in (0..encoded.len()).step_by(BASE64_LINE_LEN) {
+            message.push_str(&encoded[start..encoded.len().min(start + BASE64_LINE_LEN)]);
+            message.push_str("\r\n");
+        }
+        return message;
+    }
+    for line in body.lines() {
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message
+}
+
+/// Encodes a header value as RFC 2047 words if it isn't plain ASCII.
+///
+/// Long values are split between characters into several words on folded
+/// lines, each within the 75-character limit. Line breaks become spaces,
+/// so a value can't end the header and start another one.
+fn encode_header(value: &str) -> String {
+    let value = value.replace(['\r', '\n'], " ");
+    if value.is_ascii() {
+        return value;
+    }
+    let mut words = Vec::new();
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        if i + c.len_utf8() - start > ENCODED_WORD_BYTES {
+            words.push(&value[start..i]);
+            start = i;
+        }
+    }
+    words.push(&value[start..]);
+    words
+        .iter()
+        .map(|word| format!("=?utf-8?B?{}?=", BASE64.encode(word)))
+        .collect::<Vec<_>>()
+        .join("\r\n ")
+}
+
+/// Returns whether an `EHLO` reply advertises the `8BITMIME` extension.
+fn supports_8bitmime(ehlo_reply: &str) -> bool {
+    ehlo_reply.lines().skip(1).any(|line| {
+        line.get(4..)
+            .and_then(|extension| extension.split_whitespace().next())
+            .is_some_and(|keyword| keyword.eq_ignore_ascii_case("8BITMIME"))
+    })
+}
+
+/// Returns whether `host` is this machine, where an unencrypted connection
+/// never leaves it.
+fn is_loopback(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Extracts the address from a mailbox like `Name <user@host>`.
+fn address(mailbox: &str) -> &str {
+    mailbox
+        .rsplit_once('<')
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .map_or(mailbox.trim(), |(addr, _)| addr.trim())
+}
+
+enum Connection {
+    Plain(TcpStream),
+    Tls(Box<StreamOwned<ClientConnection, TcpStream>>),
+}
+
+impl Connection {
+    fn tls(tcp: TcpStream, host: &str) -> Result<Self> {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let config = ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let name = ServerName::try_from(host.to_string())
+            .with_context(|| format!("Invalid SMTP host name {host}"))?;
+        let conn = ClientConnection::new(Arc::new(config), name)?;
+        Ok(Self::Tls(Box::new(StreamOwned::new(conn, tcp))))
+    }
+
+    fn upgrade(self, host: &str) -> Result<Self> {
+        match self {
+            Self::Plain(tcp) => Self::tls(tcp, host),
+            tls @ Self::Tls(_) => Ok(tls),
+        }
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(s) => s.read(buf),
+            Self::Tls(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(s) => s.write(buf),
+            Self::Tls(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(s) => s.flush(),
+            Self::Tls(s) => s.flush(),
+        }
+    }
+}
+
+/// One SMTP conversation.
+struct Session<S> {
+    stream: BufReader<S>,
+}
+
+impl<S: Read + Write> Session<S> {
+    fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+        }
+    }
+
+    fn into_connection(self) -> S {
+        self.stream.into_inner()
+    }
+
+    /// Sends a command and checks the reply code.
+    fn command(&mut self, line: &str, expected: u16) -> Result<String> {
+        self.write_raw(&format!("{line}\r\n"))?;
+        self.expect(expected).with_context(|| {
+            // Don't echo credentials into error messages
+            let verb = line.split_whitespace().next().unwrap_or(line);
+            format!("SMTP {verb} failed")
+        })
+    }
+
+    fn write_raw(&mut self, data: &str) -> Result<()> {
+        let stream = self.stream.get_mut();
+        stream.write_all(data.as_bytes())?;
+        stream.flush()?;
+        Ok(())
+    }
+
+    /// Reads a (possibly multi-line) reply and checks its code.
+    fn expect(&mut self, expected: u16) -> Result<String> {
+        let mut reply = String::new();
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line)? == 0 {
+                bail!("SMTP server closed the connection");
+            }
+            reply.push_str(&line);
+            // "250-..." continues the reply, "250 ..." ends it
+            if line.as_bytes().get(3) != Some(&b'-') {
+                break;
+            }
+        }
+        let code: u16 = reply
+            .get(..3)
+            .and_then(|code| code.parse().ok())
+            .with_context(|| format!("Malformed SMTP reply: {}", reply.trim_end()))?;
+        if code != expected {
+            bail!("server replied {}", reply.trim_end());
+        }
+        Ok(reply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_format_message() {
+        let message = format_message(
+            "Ralph <ralph@example.com>",
+            &["a@example.com".to_string(), "b@example.com".to_string()],
+            "Loop done ✓",
+            "line one\n.hidden\nend",
+            "Thu, 1 Jan 2026 00:00:00 +0000",
+            false,
+        );
+        assert!(message.starts_with(
+            "From: Ralph <ralph@example.com>\r\nTo: a@example.com, b@example.com\r\n"
+        ));
+        assert!(message.contains("Subject: =?utf-8?B?"));
+        assert!(message.contains("Content-Transfer-Encoding: 7bit\r\n"));
+        assert!(message.ends_with("\r\n\r\nline one\r\n..hidden\r\nend\r\n"));
+    }
+
+    #[test]
+    fn test_long_subject_is_split_into_encoded_words() {
+        let subject = format!("Loop fertig: {} ✓", "größere Änderungen ".repeat(6));
+        let header = format!("Subject: {}", encode_header(&subject));
+
+        let mut decoded = String::new();
+        for line in header.split("\r\n") {
+            assert!(line.len() <= 76, "line too long: {line}");
+            let word = line.trim_start_matches("Subject:").trim();
+            assert!(word.len() <= 75, "encoded-word too long: {word}");
+            let encoded = word
+                .strip_prefix("=?utf-8?B?")
+                .and_then(|w| w.strip_suffix("?="))
+                .unwrap();
+            decoded.push_str(&String::from_utf8(BASE64.decode(encoded).unwrap()).unwrap());
+        }
+        assert!(header.contains("\r\n "));
+        assert_eq!(decoded, subject);
+    }
+
+    #[test]
+    fn test_non_ascii_body_is_base64_without_8bitmime() {
+        let format = |eight_bit| {
+            format_message(
+                "ralph@example.com",
+                &["a@example.com".to_string()],
+                "Done",
+                "Ergebnis: grün ✓\n.hidden",
+                "Thu, 1 Jan 2026 00:00:00 +0000",
+                eight_bit,
+            )
+        };
+
+        let message = format(false);
+        assert!(message.contains("Content-Transfer-Encoding: base64\r\n"));
+        let (_, body) = message.split_once("\r\n\r\n").unwrap();
+        assert!(body.is_ascii());
+        let decoded = BASE64.decode(body.replace("\r\n", "")).unwrap();
+        assert_eq!(
+            String::from_utf8(decoded).unwrap(),
+            "Ergebnis: grün ✓\r\n.hidden\r\n"
+        );
+
+        let message = format(true);
+        assert!(message.contains("Content-Transfer-Encoding: 8bit\r\n"));
+        assert!(message.ends_with("\r\n\r\nErgebnis: grün ✓\r\n..hidden\r\n"));
+    }
+
+    #[test]
+    fn test_supports_8bitmime() {
+        assert!(supports_8bitmime(
+            "250-smtp.example.com\r\n250-8BITMIME\r\n250 AUTH PLAIN\r\n"
+        ));
+        assert!(supports_8bitmime("250-test\r\n250 8bitmime\r\n"));
+        assert!(!supports_8bitmime("250-test\r\n250 AUTH PLAIN\r\n"));
+        // The greeting line names the server, not an extension
+        assert!(!supports_8bitmime("250 8BITMIME.example.com\r\n"));
+    }
+
+    #[test]
+    fn test_subject_line_breaks_cannot_inject_headers() {
+        let message = format_message(
+            "ralph@example.com",
+            &["a@example.com".to_string()],
+            "Done\r\nBcc: victim@example.com",
+            "body",
+            "Thu, 1 Jan 2026 00:00:00 +0000",
+            false,
+        );
+        assert!(message.contains("Subject: Done  Bcc: victim@example.com\r\n"));
+        assert!(!message.contains("\r\nBcc:"));
+    }
+
+    #[test]
+    fn test_refuses_password_over_unencrypted_remote_smtp() {
+        let config = |host: &str| -> EmailNotifierConfig {
+            serde_yaml::from_str(&format!(
+                "smtp_host: {host}\nsecurity: none\nusername: ralph\npassword: pw\nfrom: ralph@example.com\nto: [team@example.com]\n"
+            ))
+            .unwrap()
+        };
+        let err = EmailNotifier::from_config(&config("smtp.example.com")).unwrap_err();
+        assert!(err.to_string().contains("unencrypted"));
+        assert!(EmailNotifier::from_config(&config("localhost")).is_ok());
+        assert!(EmailNotifier::from_config(&config("127.0.0.1")).is_ok());
+    }
+
+    #[test]
+    fn test_address() {
+        assert_eq!(address("Ralph <ralph@example.com>"), "ralph@example.com");
+        assert_eq!(address(" team@example.com "), "team@example.com");
+    }
+
+    #[test]
+    fn test_send_over_plain_smtp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            let mut transcript = Vec::new();
+            writer.write_all(b"220 test ready\r\n").unwrap();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+                transcript.push(line.trim_end().to_string());
+                let reply: &[u8] = if in_data {
+                    if line != ".\r\n" {
+                        continue;
+                    }
+                    in_data = false;
+                    b"250 queued\r\n"
+                } else if line.starts_with("EHLO") {
+                    b"250-test\r\n250 AUTH PLAIN\r\n"
+                } else if line.starts_with("AUTH") {
+                    b"235 ok\r\n"
+                } else if line.starts_with("DATA") {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line.starts_with("QUIT") {
+                    writer.write_all(b"221 bye\r\n").unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                writer.write_all(reply).unwrap();
+            }
+            transcript
+        });
+
+        let config: EmailNotifierConfig = serde_yaml::from_str(&format!(
+            "smtp_host: 127.0.0.1\nsmtp_port: {port}\nsecurity: none\nusername: ralph\npassword: pw\nfrom: Ralph <ralph@example.com>\nto: [team@example.com]\n"
+        ))
+        .unwrap();
+        EmailNotifier::from_config(&config)
+            .unwrap()
+            .send_message("Done", "All good")
+            .unwrap();
+
+        let transcript = server.join().unwrap();
+        assert_eq!(transcript[0], "EHLO ralph");
+        assert_eq!(
+            transcript[1],
+            format!("AUTH PLAIN {}", BASE64.encode("\0ralph\0pw"))
+        );
+        assert_eq!(transcript[2], "MAIL FROM:<ralph@example.com>");
+        assert_eq!(transcript[3], "RCPT TO:<team@example.com>");
+        assert!(transcript.contains(&"Subject: Done".to_string()));
+        assert!(transcript.contains(&"All good".to_string()));
+        assert_eq!(transcript.last().unwrap(), "QUIT");
+    }
+}
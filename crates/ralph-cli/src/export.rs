@@ -0,0 +1,434 @@
+//! CLI command for `ralph export`.
+//!
+//! Bundles what a run recorded (its events, agent output, audit entries and
+//! final status) into one JSON file to attach to a bug report.
+//!
+//! With `--anonymized` the bundle keeps only the shape and timing of the
+//! run, which is what reproducing an orchestrator bug needs:
+//! - text, tool results, event payloads and audit details are replaced by
+//!   their length, and tool inputs keep their keys but not their values
+//! - error and warning messages are replaced by their length and error class
+//!   (`E0308`, `ParseIntError`), since they often quote source lines
+//! - tool-use IDs and audit actors become sequence numbers
+//!
+//! `--keep-errors` keeps error and warning messages instead, with URLs,
+//! e-mail addresses, paths and hashes replaced by placeholders.
+//!
+//! Topics, hats, tool names, iterations, timestamps, token counts and cost
+//! are kept as they are.
+
+use crate::ConfigSource;
+use crate::logs::{local_cipher, resolve_run_id};
+use anyhow::{Context, Result};
+use clap::Parser;
+use ralph_core::diagnostics::{AgentOutputContent, AgentOutputEntry};
+use ralph_core::{AuditEntry, AuditLog, Cipher, EventRecord, LoopContext, RunStatus, open_lines};
+use regex::Regex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Version of the bundle layout, bumped on incompatible changes.
+const BUNDLE_FORMAT: u32 = 1;
+
+/// Export a run as a JSON bundle for bug reports.
+#[derive(Parser, Debug)]
+pub struct ExportArgs {
+    /// Run ID (e.g. 20260127-143022) or a unique prefix; defaults to the
+    /// current run
+    pub run_id: Option<String>,
+
+    /// Strip contents and redact identifiers, keeping only the run's
+    /// structure and timings
+    #[arg(long)]
+    pub anonymized: bool,
+
+    /// With --anonymized, keep error and warning messages, redacting only
+    /// URLs, e-mail addresses, paths and hashes
+    #[arg(long, requires = "anonymized")]
+    pub keep_errors: bool,
+
+    /// File to write (default: ralph-export-<run-id>.json in the current
+    /// directory)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// The exported run.
+#[derive(Debug, Serialize)]
+struct Bundle {
+    format: u32,
+    ralph_version: &'static str,
+    run_id: String,
+    anonymized: bool,
+    /// Final (or current) status, when the status file still describes this
+    /// run.
+    status: Option<RunStatus>,
+    events: Vec<EventRecord>,
+    output: Vec<AgentOutputEntry>,
+    audit: Vec<AuditEntry>,
+}
+
+/// Execute the export command.
+pub fn execute(config_sources: &[ConfigSource], args: ExportArgs) -> Result<()> {
+    let ctx = LoopContext::primary(std::env::current_dir()?);
+    let run_id = resolve_run_id(&ctx, args.run_id.as_deref())?;
+    let cipher = local_cipher(config_sources)?;
+
+    let mut bundle = read_bundle(&ctx, &run_id, cipher.as_ref())?;
+    if args.anonymized {
+        Anonymizer::new(args.keep_errors).bundle(&mut bundle);
+    }
+
+    let path = args.output.unwrap_or_else(|| {
+        let suffix = if args.anonymized { "-anonymized" } else { "" };
+        PathBuf::from(format!("ralph-export-{run_id}{suffix}.json"))
+    });
+    let json = serde_json::to_string_pretty(&bundle)?;
+    std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!(
+        "Exported run {} ({} events, {} output entries{}) to {}",
+        run_id,
+        bundle.events.len(),
+        bundle.output.len(),
+        if args.anonymized { ", anonymized" } else { "" },
+        path.display()
+    );
+    if !args.anonymized {
+        println!(
+            "The bundle contains prompts, code and tool output; use --anonymized to share it."
+        );
+    }
+    Ok(())
+}
+
+fn read_bundle(ctx: &LoopContext, run_id: &str, cipher: Option<&Cipher>) -> Result<Bundle> {
+    let status = RunStatus::read(&ctx.run_status_path())
+        .unwrap_or_default()
+        .filter(|status| status.run_id.as_deref() == Some(run_id));
+    let audit = AuditLog::new(ctx.audit_path())
+        .entries()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| entry.run_id.as_deref() == Some(run_id))
+        .collect();
+
+    Ok(Bundle {
+        format: BUNDLE_FORMAT,
+        ralph_version: env!("CARGO_PKG_VERSION"),
+        run_id: run_id.to_string(),
+        anonymized: false,
+        status,
+        events: read_jsonl(
+            &ctx.ralph_dir().join(format!("events-{run_id}.jsonl")),
+            None,
+        )?,
+        output: read_jsonl(&ctx.run_log_path(run_id), cipher)?,
+        audit,
+    })
+}
+
+/// Reads the records of a JSONL file, skipping lines that don't parse and
+/// opening sealed lines with `cipher`. A missing file has no records.
+pub(crate) fn read_jsonl<T: DeserializeOwned>(
+    path: &Path,
+    cipher: Option<&Cipher>,
+) -> Result<Vec<T>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    Ok(open_lines(cipher, &content)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect())
+}
+
+/// Strips a bundle down to its structure.
+struct Anonymizer {
+    /// Keep error and warning messages, redacting only identifiers.
+    keep_errors: bool,
+    /// Identifier patterns and their placeholders, applied in order.
+    identifiers: Vec<(Regex, &'static str)>,
+    /// Error classes worth keeping from a removed message.
+    error_class: Regex,
+    /// Original ID → replacement, so references stay consistent.
+    tool_ids: HashMap<String, String>,
+    actors: HashMap<String, String>,
+}
+
+impl Anonymizer {
+    fn new(keep_errors: bool) -> Self {
+        let pattern = |re: &str| Regex::new(re).expect("valid identifier pattern");
+        Self {
+            keep_errors,
+            identifiers: vec![
+                (pattern(r"[a-zA-Z][a-zA-Z0-9+.-]*://\S+"), "<url>"),
+                (pattern(r"[\w.+-]+@[\w-]+(\.[\w-]+)+"), "<email>"),
+                (
+                    pattern(r"\b[0-9a-fA-F]{8}(-[0-9a-fA-F]{4}){3}-[0-9a-fA-F]{12}\b"),
+                    "<uuid>",
+                ),
+                (pattern(r"(~|\.{1,2})?(/[\w.@-]+)+/?"), "<path>"),
+                (pattern(r"\b[0-9a-f]{7,}\b"), "<hash>"),
+            ],
+            error_class: pattern(r"\b(E[0-9]{4}|[A-Z][A-Za-z0-9]*(Error|Exception))\b"),
+            tool_ids: HashMap::new(),
+            actors: HashMap::new(),
+        }
+    }
+
+    fn bundle(&mut self, bundle: &mut Bundle) {
+        bundle.anonymized = true;
+        if let Some(status) = &mut bundle.status {
+            status.pid = 0;
+        }
+        for event in &mut bundle.events {
+            if !event.payload.is_empty() {
+                event.payload = length(&event.payload);
+            }
+        }
+        for entry in &mut bundle.output {
+            self.content(&mut entry.content);
+        }
+        for entry in &mut bundle.audit {
+            if let Some(actor) = &entry.actor {
+                entry.actor = Some(pseudonym(&mut self.actors, actor, "user"));
+            }
+            if let Some(detail) = &entry.detail {
+                entry.detail = Some(length(detail));
+            }
+        }
+    }
+
+    fn content(&mut self, content: &mut AgentOutputContent) {
+        match content {
+            AgentOutputContent::Text { text } => *text = length(text),
+            AgentOutputContent::ToolCall { id, input, .. } => {
+                *id = pseudonym(&mut self.tool_ids, id, "tool");
+                structure(input);
+            }
+            AgentOutputContent::ToolResult { id, output } => {
+                *id = pseudonym(&mut self.tool_ids, id, "tool");
+                *output = length(output);
+            }
+            AgentOutputContent::Error { message } | AgentOutputContent::Warning { message } => {
+                *message = if self.keep_errors {
+                    self.redact(message)
+                } else {
+                    self.message(message)
+                };
+            }
+            AgentOutputContent::Complete { .. }
+            | AgentOutputContent::IterationStart { .. }
+            | AgentOutputContent::IterationEnd { .. } => {}
+        }
+    }
+
+    /// `[N chars]` for an error message, naming its error class when it has
+    /// one, e.g. `[120 chars, E0308]`.
+    fn message(&self, message: &str) -> String {
+        match self.error_class.find(message) {
+            Some(class) => format!("[{} chars, {}]", message.chars().count(), class.as_str()),
+            None => length(message),
+        }
+    }
+
+    /// Replaces identifiers in `text` with placeholders.
+    fn redact(&self, text: &str) -> String {
+        self.identifiers
+            .iter()
+            .fold(text.to_string(), |text, (pattern, placeholder)| {
+                pattern.replace_all(&text, *placeholder).into_owned()
+            })
+    }
+}
+
+/// `[N chars]`, standing in for removed text.
+fn length(text: &str) -> String {
+    format!("[{} chars]", text.chars().count())
+}
+
+/// Keeps the keys and nesting of a JSON value but not its strings.
+fn structure(value: &mut Value) {
+    match value {
+        Value::String(text) => *text = length(text),
+        Value::Array(items) => items.iter_mut().for_each(structure),
+        Value::Object(fields) => fields.values_mut().for_each(structure),
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+/// Stable replacement for `original`, numbered in order of first use.
+fn pseudonym(seen: &mut HashMap<String, String>, original: &str, prefix: &str) -> String {
+    let next = seen.len() + 1;
+    seen.entry(original.to_string())
+        .or_insert_with(|| format!("{prefix}-{next}"))
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralph_core::{AuditAction, AuditSource};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn output(content: AgentOutputContent) -> AgentOutputEntry {
+        AgentOutputEntry {
+            ts: "2026-01-27T14:30:22Z".to_string(),
+            iteration: 1,
+            hat: "builder".to_string(),
+            content,
+        }
+    }
+
+    #[test]
+    fn test_redact_identifiers() {
+        let anonymizer = Anonymizer::new(true);
+        assert_eq!(
+            anonymizer.redact(
+                "failed to open /home/alice/app/src/main.rs (see https://example.com/x?y=1, \
+                 mail bob@corp.example) at commit 3f2a9c1d"
+            ),
+            "failed to open <path> (see <url> mail <email>) at commit <hash>"
+        );
+        assert_eq!(
+            anonymizer.redact("session 123e4567-e89b-12d3-a456-426614174000 expired"),
+            "session <uuid> expired"
+        );
+        assert_eq!(anonymizer.redact("exit code 2"), "exit code 2");
+    }
+
+    #[test]
+    fn test_error_messages_keep_only_length_and_class() {
+        let mut anonymizer = Anonymizer::new(false);
+        let mut error = AgentOutputContent::Error {
+            message: "error[E0308]: mismatched types\n  let total: u32 = price;".to_string(),
+        };
+        let mut warning = AgentOutputContent::Warning {
+            message: "ParseIntError { kind: InvalidDigit } in parse_price".to_string(),
+        };
+        anonymizer.content(&mut error);
+        anonymizer.content(&mut warning);
+        assert_eq!(
+            error,
+            AgentOutputContent::Error {
+                message: "[56 chars, E0308]".to_string(),
+            }
+        );
+        assert_eq!(
+            warning,
+            AgentOutputContent::Warning {
+                message: "[51 chars, ParseIntError]".to_string(),
+            }
+        );
+
+        let mut kept = AgentOutputContent::Error {
+            message: "cannot read /etc/hosts".to_string(),
+        };
+        Anonymizer::new(true).content(&mut kept);
+        assert_eq!(
+            kept,
+            AgentOutputContent::Error {
+                message: "cannot read <path>".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_output_keeps_structure_only() {
+        let mut anonymizer = Anonymizer::new(false);
+        let mut call = AgentOutputContent::ToolCall {
+            name: "Read".to_string(),
+            id: "toolu_01ABC".to_string(),
+            input: json!({"file_path": "/srv/secret.rs", "limit": 20, "flags": ["a"]}),
+        };
+        let mut result = AgentOutputContent::ToolResult {
+            id: "toolu_01ABC".to_string(),
+            output: "fn main() {}".to_string(),
+        };
+        let mut text = AgentOutputContent::Text {
+            text: "Reading the file".to_string(),
+        };
+        anonymizer.content(&mut call);
+        anonymizer.content(&mut result);
+        anonymizer.content(&mut text);
+
+        assert_eq!(
+            call,
+            AgentOutputContent::ToolCall {
+                name: "Read".to_string(),
+                id: "tool-1".to_string(),
+                input: json!({"file_path": "[14 chars]", "limit": 20, "flags": ["[1 chars]"]}),
+            }
+        );
+        assert_eq!(
+            result,
+            AgentOutputContent::ToolResult {
+                id: "tool-1".to_string(),
+                output: "[12 chars]".to_string(),
+            }
+        );
+        assert_eq!(
+            text,
+            AgentOutputContent::Text {
+                text: "[16 chars]".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_anonymized_bundle_from_run_files() {
+        let temp = TempDir::new().unwrap();
+        let ctx = LoopContext::primary(temp.path().to_path_buf());
+        let run_id = "20260127-143022";
+        std::fs::create_dir_all(ctx.run_logs_dir()).unwrap();
+        std::fs::write(
+            ctx.ralph_dir().join(format!("events-{run_id}.jsonl")),
+            concat!(
+                r#"{"ts":"2026-01-27T14:30:22Z","iteration":1,"hat":"builder","topic":"build.done","payload":"tests: pass"}"#,
+                "\n",
+                "not json\n",
+            ),
+        )
+        .unwrap();
+        let entry = output(AgentOutputContent::Error {
+            message: "cannot read /etc/hosts".to_string(),
+        });
+        std::fs::write(
+            ctx.run_log_path(run_id),
+            format!("{}\n", serde_json::to_string(&entry).unwrap()),
+        )
+        .unwrap();
+        AuditLog::new(ctx.audit_path())
+            .with_run_id(Some(run_id.to_string()))
+            .record(
+                AuditEntry::new(AuditAction::Guidance, AuditSource::Tui)
+                    .with_actor("alice")
+                    .with_detail("use the staging database"),
+            )
+            .unwrap();
+
+        let mut bundle = read_bundle(&ctx, run_id, None).unwrap();
+        assert_eq!(bundle.events.len(), 1);
+        assert_eq!(bundle.audit.len(), 1);
+        assert!(bundle.status.is_none());
+
+        Anonymizer::new(false).bundle(&mut bundle);
+        assert!(bundle.anonymized);
+        assert_eq!(bundle.events[0].topic, "build.done");
+        assert_eq!(bundle.events[0].payload, "[11 chars]");
+        assert_eq!(
+            bundle.output[0].content,
+            AgentOutputContent::Error {
+                message: "[22 chars]".to_string(),
+            }
+        );
+        assert_eq!(bundle.audit[0].actor.as_deref(), Some("user-1"));
+        assert_eq!(bundle.audit[0].detail.as_deref(), Some("[24 chars]"));
+    }
+}
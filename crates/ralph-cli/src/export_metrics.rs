@@ -0,0 +1,380 @@
+//! CLI command for `ralph export-metrics`.
+//!
+//! Dumps one row per completed iteration, read from the loop history
+//! (`.ralph/history.jsonl`), as CSV or Parquet for analysis in pandas or
+//! DuckDB. Tool counts come from each run's agent output log.
+
+use crate::ConfigSource;
+use crate::export::read_jsonl;
+use crate::logs::{local_cipher, parse_since};
+use crate::parquet::{self, Column};
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use ralph_core::diagnostics::{AgentOutputContent, AgentOutputEntry};
+use ralph_core::{IterationRecord, LoopContext, LoopHistory};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// Export per-iteration metrics of recorded runs as CSV or Parquet.
+#[derive(Parser, Debug)]
+pub struct ExportMetricsArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = MetricsFormat::Csv)]
+    pub format: MetricsFormat,
+
+    /// Only export these runs (run IDs or unique prefixes); repeatable
+    #[arg(long = "run", value_name = "RUN_ID")]
+    pub runs: Vec<String>,
+
+    /// Only export iterations completed since a duration ago (30m, 2h, 7d)
+    /// or an RFC 3339 timestamp
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// File to write (default: ralph-metrics.csv or ralph-metrics.parquet in
+    /// the current directory)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetricsFormat {
+    Csv,
+    Parquet,
+}
+
+impl MetricsFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            MetricsFormat::Csv => "csv",
+            MetricsFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// One exported iteration.
+#[derive(Debug, PartialEq)]
+struct MetricsRow {
+    run_id: String,
+    iteration: u32,
+    hat: String,
+    /// Milliseconds since the Unix epoch.
+    completed_at: i64,
+    duration_ms: u64,
+    cost_usd: f64,
+    tokens: u64,
+    tool_calls: u32,
+    /// Calls per tool, most used first, e.g. `Read=4 Bash=2`.
+    tools: String,
+    /// `success` or `failed`.
+    outcome: String,
+    /// How the run ended; empty while it is going or if it crashed.
+    run_outcome: String,
+}
+
+/// Execute the export-metrics command.
+pub fn execute(config_sources: &[ConfigSource], args: ExportMetricsArgs) -> Result<()> {
+    let ctx = LoopContext::primary(std::env::current_dir()?);
+    let cipher = local_cipher(config_sources)?;
+    let records = LoopHistory::from_context(&ctx)
+        .iteration_metrics()
+        .with_context(|| format!("Failed to read {}", ctx.history_path().display()))?;
+    let records = select(
+        records,
+        &args.runs,
+        args.since.as_deref().map(parse_since).transpose()?,
+    )?;
+
+    let mut tool_counts = HashMap::new();
+    for record in &records {
+        let Some(run_id) = &record.metrics.run_id else {
+            continue;
+        };
+        if !tool_counts.contains_key(run_id) {
+            let output: Vec<AgentOutputEntry> =
+                read_jsonl(&ctx.run_log_path(run_id), cipher.as_ref())?;
+            tool_counts.insert(run_id.clone(), count_tools(&output));
+        }
+    }
+    let rows: Vec<MetricsRow> = records
+        .into_iter()
+        .map(|record| {
+            let tools = record
+                .metrics
+                .run_id
+                .as_ref()
+                .and_then(|run_id| tool_counts.get(run_id)?.get(&record.iteration));
+            to_row(record, tools)
+        })
+        .collect();
+
+    let path = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("ralph-metrics.{}", args.format.extension())));
+    let bytes = match args.format {
+        MetricsFormat::Csv => to_csv(&rows).into_bytes(),
+        MetricsFormat::Parquet => to_parquet(&rows),
+    };
+    std::fs::write(&path, bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let runs = rows
+        .iter()
+        .map(|row| row.run_id.as_str())
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+    println!(
+        "Exported {} iterations from {} runs to {}",
+        rows.len(),
+        runs,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Keeps the iterations of the requested runs (all when none are given)
+/// completed since `since`.
+fn select(
+    records: Vec<IterationRecord>,
+    runs: &[String],
+    since: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<Vec<IterationRecord>> {
+    let mut known: Vec<&str> = records
+        .iter()
+        .filter_map(|record| record.metrics.run_id.as_deref())
+        .collect();
+    known.sort_unstable();
+    known.dedup();
+
+    let mut wanted = Vec::new();
+    for requested in runs {
+        let matches: Vec<&str> = known
+            .iter()
+            .copied()
+            .filter(|run| run.starts_with(requested.as_str()))
+            .collect();
+        match matches.as_slice() {
+            [] => anyhow::bail!("No iterations recorded for run '{}'", requested),
+            [run] => wanted.push(run.to_string()),
+            _ if matches.contains(&requested.as_str()) => wanted.push(requested.clone()),
+            _ => anyhow::bail!(
+                "Run ID '{}' is ambiguous ({} matches)",
+                requested,
+                matches.len()
+            ),
+        }
+    }
+
+    Ok(records
+        .into_iter()
+        .filter(|record| {
+            wanted.is_empty()
+                || record
+                    .metrics
+                    .run_id
+                    .as_ref()
+                    .is_some_and(|run| wanted.contains(run))
+        })
+        .filter(|record| since.is_none_or(|since| record.completed_at >= since))
+        .collect())
+}
+
+/// Counts the tool calls in a run's output, per iteration and tool.
+fn count_tools(output: &[AgentOutputEntry]) -> HashMap<u32, BTreeMap<String, u32>> {
+    let mut counts: HashMap<u32, BTreeMap<String, u32>> = HashMap::new();
+    for entry in output {
+        if let AgentOutputContent::ToolCall { name, .. } = &entry.content {
+            *counts
+                .entry(entry.iteration)
+                .or_default()
+                .entry(name.clone())
+                .or_default() += 1;
+        }
+    }
+    counts
+}
+
+fn to_row(record: IterationRecord, tools: Option<&BTreeMap<String, u32>>) -> MetricsRow {
+    let mut by_use: Vec<(&String, &u32)> = tools.into_iter().flatten().collect();
+    by_use.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    MetricsRow {
+        run_id: record.metrics.run_id.unwrap_or_default(),
+        iteration: record.iteration,
+        hat: record.metrics.hat,
+        completed_at: record.completed_at.timestamp_millis(),
+        duration_ms: record.metrics.duration_ms,
+        cost_usd: record.metrics.cost_usd,
+        tokens: record.metrics.tokens,
+        tool_calls: by_use.iter().map(|(_, count)| **count).sum(),
+        tools: by_use
+            .iter()
+            .map(|(name, count)| format!("{name}={count}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+        outcome: if record.success { "success" } else { "failed" }.to_string(),
+        run_outcome: record.run_outcome.unwrap_or_default(),
+    }
+}
+
+const COLUMNS: [&str; 11] = [
+    "run_id",
+    "iteration",
+    "hat",
+    "completed_at",
+    "duration_ms",
+    "cost_usd",
+    "tokens",
+    "tool_calls",
+    "tools",
+    "outcome",
+    "run_outcome",
+];
+
+fn to_csv(rows: &[MetricsRow]) -> String {
+    let mut csv = COLUMNS.join(",");
+    csv.push('\n');
+    for row in rows {
+        let completed_at = chrono::DateTime::from_timestamp_millis(row.completed_at)
+            .map(|ts| ts.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+            .unwrap_or_default();
+        let fields = [
+            csv_field(&row.run_id),
+            row.iteration.to_string(),
+            csv_field(&row.hat),
+            completed_at,
+            row.duration_ms.to_string(),
+            row.cost_usd.to_string(),
+            row.tokens.to_string(),
+            row.tool_calls.to_string(),
+            csv_field(&row.tools),
+            row.outcome.clone(),
+            csv_field(&row.run_outcome),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quotes a field holding a comma, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_parquet(rows: &[MetricsRow]) -> Vec<u8> {
+    let strings = |get: fn(&MetricsRow) -> &String| {
+        Column::Utf8(rows.iter().map(|row| get(row).clone()).collect())
+    };
+    let columns = [
+        strings(|row| &row.run_id),
+        Column::Int32(rows.iter().map(|row| row.iteration as i32).collect()),
+        strings(|row| &row.hat),
+        Column::TimestampMillis(rows.iter().map(|row| row.completed_at).collect()),
+        Column::Int64(rows.iter().map(|row| row.duration_ms as i64).collect()),
+        Column::Double(rows.iter().map(|row| row.cost_usd).collect()),
+        Column::Int64(rows.iter().map(|row| row.tokens as i64).collect()),
+        Column::Int32(rows.iter().map(|row| row.tool_calls as i32).collect()),
+        strings(|row| &row.tools),
+        strings(|row| &row.outcome),
+        strings(|row| &row.run_outcome),
+    ];
+    let named: Vec<(&str, Column)> = COLUMNS.into_iter().zip(columns).collect();
+    parquet::write(&named)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralph_core::IterationMetrics;
+    use tempfile::TempDir;
+
+    fn record(run: &str, iteration: u32, success: bool) -> IterationRecord {
+        IterationRecord {
+            completed_at: chrono::DateTime::from_timestamp_millis(1_760_000_000_000).unwrap(),
+            iteration,
+            success,
+            metrics: IterationMetrics {
+                run_id: Some(run.to_string()),
+                hat: "builder".to_string(),
+                duration_ms: 4200,
+                cost_usd: 0.05,
+                tokens: 1800,
+            },
+            run_outcome: Some("completion_promise".to_string()),
+        }
+    }
+
+    fn tool_call(iteration: u32, name: &str) -> AgentOutputEntry {
+        AgentOutputEntry {
+            ts: String::new(),
+            iteration,
+            hat: "builder".to_string(),
+            content: AgentOutputContent::ToolCall {
+                name: name.to_string(),
+                id: "t".to_string(),
+                input: serde_json::Value::Null,
+            },
+        }
+    }
+
+    #[test]
+    fn test_rows_and_csv() {
+        let counts = count_tools(&[
+            tool_call(1, "Bash"),
+            tool_call(1, "Read"),
+            tool_call(1, "Read"),
+            tool_call(2, "Edit"),
+        ]);
+        let rows = vec![
+            to_row(record("20260101-120000", 1, true), counts.get(&1)),
+            to_row(record("20260101-120000", 3, false), None),
+        ];
+
+        assert_eq!(rows[0].tool_calls, 3);
+        assert_eq!(rows[0].tools, "Read=2 Bash=1");
+        assert_eq!(
+            to_csv(&rows),
+            "run_id,iteration,hat,completed_at,duration_ms,cost_usd,tokens,tool_calls,tools,outcome,run_outcome\n\
+             20260101-120000,1,builder,2025-10-09T08:53:20.000Z,4200,0.05,1800,3,Read=2 Bash=1,success,completion_promise\n\
+             20260101-120000,3,builder,2025-10-09T08:53:20.000Z,4200,0.05,1800,0,,failed,completion_promise\n"
+        );
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn test_select_by_run_prefix() {
+        let records = vec![
+            record("20260101-120000", 1, true),
+            record("20260102-090000", 1, true),
+            record("20260102-100000", 1, true),
+        ];
+
+        let selected = select(records.clone(), &["20260101".to_string()], None).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert!(select(records.clone(), &["20260102".to_string()], None).is_err());
+        assert!(select(records.clone(), &["2027".to_string()], None).is_err());
+        assert_eq!(select(records, &[], None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_export_writes_parquet_from_history() {
+        let dir = TempDir::new().unwrap();
+        let ctx = LoopContext::primary(dir.path().to_path_buf());
+        let history = LoopHistory::from_context(&ctx);
+        history.record_started("prompt").unwrap();
+        history
+            .record_iteration_metrics(1, true, &record("20260101-120000", 1, true).metrics)
+            .unwrap();
+
+        let records = history.iteration_metrics().unwrap();
+        let rows: Vec<MetricsRow> = records.into_iter().map(|r| to_row(r, None)).collect();
+        let bytes = to_parquet(&rows);
+
+        assert!(bytes.starts_with(b"PAR1") && bytes.ends_with(b"PAR1"));
+        assert!(bytes.windows(15).any(|w| w == b"20260101-120000"));
+    }
+}
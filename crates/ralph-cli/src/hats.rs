@@ -12,7 +12,7 @@ use crate::presets;
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 use indicatif::{ProgressBar, ProgressStyle};
-use ralph_adapters::{CliBackend, detect_backend_default};
+use ralph_adapters::{AdapterRegistry, detect_backend_default};
 use ralph_core::{HatRegistry, RalphConfig};
 use std::collections::HashSet;
 use std::io::Write;
@@ -402,7 +402,8 @@ fn render_hat_dag_via_ai(
     let prompt = build_diagram_prompt(registry);
 
     // Create backend and generate diagram
-    let backend = CliBackend::from_name(&backend_name)
+    let backend = AdapterRegistry::from_config(config)
+        .backend_named(&backend_name)
         .map_err(|e| anyhow::anyhow!("Failed to create backend '{}': {}", backend_name, e))?;
 
     // Show spinner while generating
@@ -477,7 +478,7 @@ fn render_hat_dag_via_ai(
 fn resolve_backend(flag_override: Option<&str>, config: &RalphConfig) -> Result<String> {
     // 1. CLI flag takes precedence
     if let Some(backend) = flag_override {
-        validate_backend_name(backend)?;
+        validate_backend_name(backend, &AdapterRegistry::from_config(config))?;
         return Ok(backend.to_string());
     }
 
@@ -490,15 +491,10 @@ fn resolve_backend(flag_override: Option<&str>, config: &RalphConfig) -> Result<
     detect_backend_default().map_err(|e| anyhow::anyhow!("{}", e))
 }
 
-/// Validates a backend name.
-fn validate_backend_name(name: &str) -> Result<()> {
-    match name {
-        "claude" | "kiro" | "gemini" | "codex" | "amp" | "copilot" | "opencode" => Ok(()),
-        _ => Err(anyhow::anyhow!(
-            "Unknown backend: {}\n\nValid backends: claude, kiro, gemini, codex, amp, copilot, opencode",
-            name
-        )),
-    }
+/// Validates a backend name against the registered adapters.
+fn validate_backend_name(name: &str, adapters: &AdapterRegistry) -> Result<()> {
+    adapters.backend_named(name)?;
+    Ok(())
 }
 
 /// Builds the prompt for diagram generation.
@@ -1054,16 +1050,17 @@ mod tests {
 
     #[test]
     fn test_validate_backend_name_valid() {
-        assert!(validate_backend_name("claude").is_ok());
-        assert!(validate_backend_name("kiro").is_ok());
-        assert!(validate_backend_name("gemini").is_ok());
-        assert!(validate_backend_name("codex").is_ok());
-        assert!(validate_backend_name("amp").is_ok());
+        let adapters = AdapterRegistry::builtin();
+        assert!(validate_backend_name("claude", &adapters).is_ok());
+        assert!(validate_backend_name("kiro", &adapters).is_ok());
+        assert!(validate_backend_name("gemini", &adapters).is_ok());
+        assert!(validate_backend_name("codex", &adapters).is_ok());
+        assert!(validate_backend_name("amp", &adapters).is_ok());
     }
 
     #[test]
     fn test_validate_backend_name_invalid() {
-        let result = validate_backend_name("unknown-backend");
+        let result = validate_backend_name("unknown-backend", &AdapterRegistry::builtin());
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Unknown backend"));
     }
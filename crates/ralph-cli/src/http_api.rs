@@ -0,0 +1,486 @@
+//! Access control for the HTTP API served by `ralph attach --http`.
+//!
+//! Requests authenticate with a bearer token from `http_api.tokens` (sent as
+//! `Authorization: Bearer <token>`, or `?token=` for browser `EventSource`
+//! clients that cannot set headers). Viewer tokens can watch; controller
+//! tokens can also pause, resume and abort. Each token may carry its own
+//! per-minute rate limit.
+//!
+//! Without tokens anyone who can connect may watch, so the API then only
+//! binds to loopback addresses.
+//!
+//! Optionally the API is served over TLS, and with a client CA configured
+//! only clients presenting a certificate signed by it can connect.
+
+use anyhow::{Context, Result, bail};
+use percent_encoding::percent_decode_str;
+use ralph_core::{ApiRole, HttpApiConfig, HttpTlsConfig};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+
+/// Length of a rate limit window.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Largest request line plus headers accepted before authentication.
+const MAX_HEAD_BYTES: u64 = 16 * 1024;
+
+/// Most headers accepted in a request.
+const MAX_HEADERS: usize = 64;
+
+/// How long a client may take to send its request head.
+const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a request was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AuthError {
+    /// No token, or an unknown one.
+    Unauthorized,
+    /// The token's role does not allow the request.
+    Forbidden,
+    /// The token used up its requests for this minute.
+    RateLimited,
+}
+
+impl AuthError {
+    /// HTTP status line for the refusal.
+    pub(crate) fn status(self) -> &'static str {
+        match self {
+            Self::Unauthorized => "401 Unauthorized",
+            Self::Forbidden => "403 Forbidden",
+            Self::RateLimited => "429 Too Many Requests",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Token {
+    name: String,
+    value: String,
+    role: ApiRole,
+    rate_limit: Option<u32>,
+}
+
+/// Checks request tokens against the configured roles and rate limits.
+#[derive(Debug, Default)]
+pub(crate) struct ApiAuth {
+    tokens: Vec<Token>,
+    /// Start of the current window and requests made in it, per token name.
+    windows: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl ApiAuth {
+    /// Resolves the configured tokens.
+    ///
+    /// Fails if a token has no value, so a missing environment variable
+    /// doesn't silently leave the API open.
+    pub(crate) fn from_config(config: &HttpApiConfig) -> Result<Self> {
+        let mut tokens = Vec::new();
+        for token in &config.tokens {
+            let Some(value) = token.resolve() else {
+                bail!(
+                    "HTTP API token '{}' has no value; set `token_env` to a defined variable or `token`",
+                    token.name
+                );
+            };
+            tokens.push(Token {
+                name: token.name.clone(),
+                value,
+                role: token.role,
+                rate_limit: token.rate_limit_per_minute,
+            });
+        }
+        Ok(Self {
+            tokens,
+            windows: Mutex::default(),
+        })
+    }
+
+    /// Refuses to serve on `addr` if anyone who can reach it could watch:
+    /// without tokens only loopback addresses are allowed.
+    pub(crate) fn check_bind(&self, addr: SocketAddr) -> Result<()> {
+        if self.tokens.is_empty() && !addr.ip().is_loopback() {
+            bail!(
+                "Refusing to serve on {addr} without an access token; add one to `http_api.tokens` or bind to 127.0.0.1"
+            );
+        }
+        Ok(())
+    }
+
+    /// Authorizes a request needing `role`, returning the token's name.
+    ///
+    /// Without configured tokens the API is open for viewing only, and the
+    /// caller is anonymous (`None`).
+    pub(crate) fn authorize(
+        &self,
+        presented: Option<&str>,
+        role: ApiRole,
+        now: Instant,
+    ) -> Result<Option<&str>, AuthError> {
+        if self.tokens.is_empty() {
+            return match role {
+                ApiRole::Viewer => Ok(None),
+                ApiRole::Controller => Err(AuthError::Forbidden),
+            };
+        }
+
+        let presented = presented.ok_or(AuthError::Unauthorized)?;
+        let token = self
+            .tokens
+            .iter()
+            .find(|token| constant_time_eq(token.value.as_bytes(), presented.as_bytes()))
+            .ok_or(AuthError::Unauthorized)?;
+        if role == ApiRole::Controller && token.role != ApiRole::Controller {
+            return Err(AuthError::Forbidden);
+        }
+
+        if let Some(limit) = token.rate_limit {
+            let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+            let window = windows.entry(token.name.clone()).or_insert((now, 0));
+            if now.duration_since(window.0) >= RATE_WINDOW {
+                *window = (now, 0);
+            }
+            if window.1 >= limit {
+                return Err(AuthError::RateLimited);
+            }
+            window.1 += 1;
+        }
+        Ok(Some(&token.name))
+    }
+}
+
+/// Compares two byte strings without exiting early on the first difference.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// The parts of an HTTP request the API looks at.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Request<'a> {
+    pub method: &'a str,
+    /// Path without the query string.
+    pub path: &'a str,
+    /// Bearer token from the `Authorization` header or the `token` query
+    /// parameter, percent-decoded.
+    pub token: Option<Cow<'a, str>>,
+}
+
+impl<'a> Request<'a> {
+    pub(crate) fn parse(request_line: &'a str, headers: &'a [String]) -> Option<Self> {
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next()?;
+        let target = parts.next()?;
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+        let header_token = headers.iter().find_map(|header| {
+            let (name, value) = header.split_once(':')?;
+            if !name.trim().eq_ignore_ascii_case("authorization") {
+                return None;
+            }
+            value.trim().strip_prefix("Bearer ").map(str::trim)
+        });
+        let query_token = query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .map(|value| percent_decode_str(value).decode_utf8_lossy());
+
+        Some(Self {
+            method,
+            path,
+            token: header_token.map(Cow::Borrowed).or(query_token),
+        })
+    }
+}
+
+/// Writes a complete response and closes the exchange.
+pub(crate) async fn respond<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await
+}
+
+/// Reads the request line and headers, returning the stream for the response.
+///
+/// This runs before the request is authenticated, so the head is limited to
+/// [`MAX_HEAD_BYTES`] and [`MAX_HEADERS`] and must arrive within
+/// [`HEAD_TIMEOUT`]; a client exceeding any of them gets an error.
+pub(crate) async fn read_head<S: AsyncRead + Unpin>(
+    stream: S,
+) -> std::io::Result<(S, String, Vec<String>)> {
+    read_head_within(stream, HEAD_TIMEOUT).await
+}
+
+async fn read_head_within<S: AsyncRead + Unpin>(
+    stream: S,
+    timeout: Duration,
+) -> std::io::Result<(S, String, Vec<String>)> {
+    use std::io::{Error, ErrorKind};
+
+    let mut reader = BufReader::new(stream).take(MAX_HEAD_BYTES);
+    let head = async {
+        let mut request_line = String::new();
+        read_head_line(&mut reader, &mut request_line).await?;
+        let mut headers = Vec::new();
+        loop {
+            let mut header = String::new();
+            if read_head_line(&mut reader, &mut header).await? == 0 || header.trim_end().is_empty()
+            {
+                break;
+            }
+            if headers.len() == MAX_HEADERS {
+                return Err(Error::new(ErrorKind::InvalidData, "too many headers"));
+            }
+            headers.push(header);
+        }
+        Ok((request_line, headers))
+    };
+    let (request_line, headers) = tokio::time::timeout(timeout, head)
+        .await
+        .map_err(|_| Error::new(ErrorKind::TimedOut, "request head timed out"))??;
+    Ok((reader.into_inner().into_inner(), request_line, headers))
+}
+
+/// Reads one line of the request head; a line cut off by [`MAX_HEAD_BYTES`]
+/// is an error.
+async fn read_head_line<R: tokio::io::AsyncBufRead + Unpin>(
+    reader: &mut tokio::io::Take<R>,
+    line: &mut String,
+) -> std::io::Result<usize> {
+    let read = reader.read_line(line).await?;
+    if read > 0 && !line.ends_with('\n') && reader.limit() == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "request head too large",
+        ));
+    }
+    Ok(read)
+}
+
+/// Builds a TLS acceptor, requiring client certificates if a CA is set.
+pub(crate) fn tls_acceptor(config: &HttpTlsConfig) -> Result<TlsAcceptor> {
+    let certs = read_pem(&config.cert, |pem| {
+        CertificateDer::pem_slice_iter(pem).collect::<Result<Vec<_>, _>>()
+    })?;
+    let key = read_pem(&config.key, PrivateKeyDer::from_pem_slice)?;
+
+    let builder = ServerConfig::builder();
+    let builder = match &config.client_ca {
+        Some(ca) => {
+            let mut roots = RootCertStore::empty();
+            for cert in read_pem(ca, |pem| {
+                CertificateDer::pem_slice_iter(pem).collect::<Result<Vec<_>, _>>()
+            })? {
+                roots
+                    .add(cert)
+                    .with_context(|| format!("Invalid CA certificate in {}", ca.display()))?;
+            }
+            let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                .build()
+                .context("Failed to set up client certificate verification")?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+    let server = builder
+        .with_single_cert(certs, key)
+        .context("Server certificate and key don't match")?;
+    Ok(TlsAcceptor::from(Arc::new(server)))
+}
+
+fn read_pem<T, E: std::fmt::Display>(
+    path: &Path,
+    parse: impl FnOnce(&[u8]) -> Result<T, E>,
+) -> Result<T> {
+    let pem = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&pem).map_err(|e| anyhow::anyhow!("Invalid PEM in {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralph_core::ApiTokenConfig;
+
+    #[tokio::test]
+    async fn test_read_head_limits_size_and_header_count() {
+        let head = b"GET /events HTTP/1.1\r\nAuthorization: Bearer x\r\n\r\nbody";
+        let (_, request_line, headers) = read_head(&head[..]).await.unwrap();
+        assert_eq!(request_line, "GET /events HTTP/1.1\r\n");
+        assert_eq!(headers, ["Authorization: Bearer x\r\n"]);
+
+        let endless = format!("GET / HTTP/1.1\r\nX-Pad: {}", "a".repeat(20_000));
+        let err = read_head(endless.as_bytes()).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let many = format!("GET / HTTP/1.1\r\n{}\r\n", "X-A: b\r\n".repeat(100));
+        let err = read_head(many.as_bytes()).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_read_head_times_out_on_a_stalled_client() {
+        let (client, server) = tokio::io::duplex(64);
+        let err = read_head_within(server, Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        drop(client);
+    }
+
+    fn token(name: &str, role: ApiRole, rate_limit: Option<u32>) -> ApiTokenConfig {
+        ApiTokenConfig {
+            name: name.to_string(),
+            token: Some(format!("{name}-secret")),
+            token_env: None,
+            role,
+            rate_limit_per_minute: rate_limit,
+        }
+    }
+
+    fn auth(tokens: Vec<ApiTokenConfig>) -> ApiAuth {
+        ApiAuth::from_config(&HttpApiConfig { tokens, tls: None }).unwrap()
+    }
+
+    #[test]
+    fn test_open_api_is_view_only() {
+        let auth = auth(Vec::new());
+        let now = Instant::now();
+        assert_eq!(auth.authorize(None, ApiRole::Viewer, now), Ok(None));
+        assert_eq!(
+            auth.authorize(Some("anything"), ApiRole::Controller, now),
+            Err(AuthError::Forbidden)
+        );
+    }
+
+    #[test]
+    fn test_roles_are_enforced() {
+        let auth = auth(vec![
+            token("dashboard", ApiRole::Viewer, None),
+            token("oncall", ApiRole::Controller, None),
+        ]);
+        let now = Instant::now();
+
+        assert_eq!(
+            auth.authorize(None, ApiRole::Viewer, now),
+            Err(AuthError::Unauthorized)
+        );
+        assert_eq!(
+            auth.authorize(Some("wrong"), ApiRole::Viewer, now),
+            Err(AuthError::Unauthorized)
+        );
+        assert_eq!(
+            auth.authorize(Some("dashboard-secret"), ApiRole::Viewer, now),
+            Ok(Some("dashboard"))
+        );
+        assert_eq!(
+            auth.authorize(Some("dashboard-secret"), ApiRole::Controller, now),
+            Err(AuthError::Forbidden)
+        );
+        assert_eq!(
+            auth.authorize(Some("oncall-secret"), ApiRole::Controller, now),
+            Ok(Some("oncall"))
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_resets_each_minute() {
+        let auth = auth(vec![token("dashboard", ApiRole::Viewer, Some(2))]);
+        let start = Instant::now();
+
+        for _ in 0..2 {
+            assert!(
+                auth.authorize(Some("dashboard-secret"), ApiRole::Viewer, start)
+                    .is_ok()
+            );
+        }
+        assert_eq!(
+            auth.authorize(Some("dashboard-secret"), ApiRole::Viewer, start),
+            Err(AuthError::RateLimited)
+        );
+        assert!(
+            auth.authorize(
+                Some("dashboard-secret"),
+                ApiRole::Viewer,
+                start + RATE_WINDOW
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_request_parse() {
+        let headers = vec!["Authorization: Bearer abc\r\n".to_string()];
+        assert_eq!(
+            Request::parse("POST /control/pause HTTP/1.1\r\n", &headers),
+            Some(Request {
+                method: "POST",
+                path: "/control/pause",
+                token: Some(Cow::Borrowed("abc")),
+            })
+        );
+        assert_eq!(
+            Request::parse("GET /events?token=xyz HTTP/1.1\r\n", &[])
+                .unwrap()
+                .token
+                .as_deref(),
+            Some("xyz")
+        );
+        assert_eq!(
+            Request::parse("GET /events?since=0&token=a%2Bb%2Fc%3D HTTP/1.1\r\n", &[])
+                .unwrap()
+                .token
+                .as_deref(),
+            Some("a+b/c=")
+        );
+        assert_eq!(Request::parse("", &[]), None);
+    }
+
+    #[test]
+    fn test_open_api_binds_only_to_loopback() {
+        let open = ApiAuth::default();
+        assert!(open.check_bind("127.0.0.1:8080".parse().unwrap()).is_ok());
+        assert!(open.check_bind("[::1]:8080".parse().unwrap()).is_ok());
+        assert!(open.check_bind("0.0.0.0:8080".parse().unwrap()).is_err());
+        assert!(
+            open.check_bind("192.168.1.5:8080".parse().unwrap())
+                .is_err()
+        );
+
+        let guarded = ApiAuth::from_config(&HttpApiConfig {
+            tokens: vec![token("dashboard", ApiRole::Viewer, None)],
+            tls: None,
+        })
+        .unwrap();
+        assert!(guarded.check_bind("0.0.0.0:8080".parse().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_unresolved_token_is_an_error() {
+        let mut missing = token("ci", ApiRole::Viewer, None);
+        missing.token = None;
+        missing.token_env = Some("RALPH_TEST_TOKEN_THAT_IS_NOT_SET".to_string());
+        assert!(
+            ApiAuth::from_config(&HttpApiConfig {
+                tokens: vec![missing],
+                tls: None
+            })
+            .is_err()
+        );
+    }
+}
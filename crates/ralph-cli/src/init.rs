@@ -34,7 +34,7 @@ const VALID_BACKENDS: &[&str] = &[
 ];
 
 /// Generates the minimal config template for a given backend.
-fn generate_template(backend: &str) -> String {
+pub(crate) fn generate_template(backend: &str) -> String {
     format!(
         r#"# Ralph Orchestrator Configuration
 # Generated by: ralph init --backend {backend}
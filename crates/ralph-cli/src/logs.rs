@@ -0,0 +1,482 @@
+//! CLI command for `ralph logs`.
+//!
+//! Prints a run's recorded agent output (`.ralph/runs/<run-id>.jsonl`) the
+//! same way the console stream handler shows it live, and optionally keeps
+//! following the log while the run is still writing. This is a lightweight
+//! alternative to the TUI, e.g. over slow SSH connections.
+
+use crate::ConfigSource;
+use crate::display::colors;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use clap::Parser;
+use ralph_adapters::{ConsoleStreamHandler, StreamHandler, ToolSummaries};
+use ralph_core::diagnostics::{AgentOutputContent, AgentOutputEntry};
+use ralph_core::{Cipher, LoopContext, RalphConfig, is_sealed};
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How often the log is polled for new output in follow mode.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Arguments for the logs subcommand.
+#[derive(Parser, Debug)]
+pub struct LogsArgs {
+    /// Run ID (e.g. 20260127-143022) or a unique prefix; defaults to the
+    /// current run
+    pub run_id: Option<String>,
+
+    /// Keep printing new output as the run produces it
+    #[arg(short, long)]
+    pub follow: bool,
+
+    /// Only show output newer than a duration (30s, 10m, 2h, 1d) or an
+    /// RFC 3339 timestamp
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Only show output from this iteration
+    #[arg(long)]
+    pub iteration: Option<u32>,
+
+    /// Only show entries matching this regex
+    #[arg(long)]
+    pub grep: Option<String>,
+}
+
+/// Execute the logs command.
+pub fn execute(
+    config_sources: &[ConfigSource],
+    args: LogsArgs,
+    verbose: bool,
+    use_colors: bool,
+) -> Result<()> {
+    let filter = LogFilter {
+        since: args.since.as_deref().map(parse_since).transpose()?,
+        iteration: args.iteration,
+        grep: args
+            .grep
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .context("Invalid --grep pattern")?,
+    };
+
+    let ctx = LoopContext::primary(std::env::current_dir()?);
+    let run_id = resolve_run_id(&ctx, args.run_id.as_deref())?;
+    let path = ctx.run_log_path(&run_id);
+
+    let mut printer = LogPrinter::new(
+        ConsoleStreamHandler::new(verbose).with_tool_summaries(tool_summaries(config_sources)),
+        verbose,
+        use_colors,
+    );
+
+    let mut tail =
+        JsonlTail::<AgentOutputEntry>::open(&path)?.with_cipher(local_cipher(config_sources)?);
+    loop {
+        while let Some(entry) = tail.next_record()? {
+            if filter.matches(&entry) {
+                printer.print(&entry);
+            }
+        }
+        if !args.follow {
+            break;
+        }
+        std::io::stdout().flush()?;
+        std::thread::sleep(FOLLOW_POLL_INTERVAL);
+    }
+
+    Ok(())
+}
+
+/// Returns the project's tool summary templates, if its config file exists.
+pub(crate) fn tool_summaries(config_sources: &[ConfigSource]) -> ToolSummaries {
+    let templates = load_local_config(config_sources)
+        .map(|config| config.tool_summaries)
+        .unwrap_or_default();
+    ToolSummaries::new(templates)
+}
+
+/// Loads the config only if the config file exists, so commands that merely
+/// read a run don't warn about a missing `ralph.yml`.
+pub(crate) fn load_local_config(config_sources: &[ConfigSource]) -> Option<RalphConfig> {
+    let has_config = matches!(config_sources.first(), Some(ConfigSource::File(p)) if p.exists());
+    has_config
+        .then(|| crate::load_config_with_overrides(config_sources).ok())
+        .flatten()
+}
+
+/// Returns the cipher for encrypted run logs, if the project's config turns
+/// encryption on.
+pub(crate) fn local_cipher(config_sources: &[ConfigSource]) -> Result<Option<Cipher>> {
+    let Some(config) = load_local_config(config_sources) else {
+        return Ok(None);
+    };
+    let ctx = LoopContext::primary(std::env::current_dir()?);
+    Cipher::load(&config.encryption, ctx.repo_root()).context("Failed to load the encryption key")
+}
+
+/// Reads records from a JSONL file (run log or events file) as the loop
+/// appends them.
+pub(crate) struct JsonlTail<T> {
+    reader: BufReader<File>,
+    pending: String,
+    /// Opens sealed lines of encrypted run logs
+    cipher: Option<Cipher>,
+    /// Whether skipping sealed lines was already warned about
+    warned_sealed: bool,
+    _record: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> JsonlTail<T> {
+    pub(crate) fn open(path: &Path) -> Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        Ok(Self {
+            reader: BufReader::new(file),
+            pending: String::new(),
+            cipher: None,
+            warned_sealed: false,
+            _record: PhantomData,
+        })
+    }
+
+    /// Opens sealed lines with `cipher`.
+    pub(crate) fn with_cipher(mut self, cipher: Option<Cipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Returns the next complete record, or `None` once caught up with the
+    /// writer. A line without its trailing newline is still being written and
+    /// is kept until the rest arrives.
+    pub(crate) fn next_record(&mut self) -> std::io::Result<Option<T>> {
+        loop {
+            let read = self.reader.read_line(&mut self.pending)?;
+            if read == 0 || !self.pending.ends_with('\n') {
+                return Ok(None);
+            }
+            let line = std::mem::take(&mut self.pending);
+            let line = match &self.cipher {
+                Some(cipher) => match cipher.open(line.trim_end()) {
+                    Ok(line) => line.into_owned(),
+                    Err(e) => {
+                        if !self.warned_sealed {
+                            self.warned_sealed = true;
+                            tracing::warn!("Skipping encrypted lines: {}", e);
+                        }
+                        continue;
+                    }
+                },
+                None => {
+                    if is_sealed(&line) && !self.warned_sealed {
+                        self.warned_sealed = true;
+                        tracing::warn!("Skipping encrypted lines: `encryption.enabled` is not set");
+                    }
+                    line
+                }
+            };
+            match serde_json::from_str(line.trim_end()) {
+                Ok(record) => return Ok(Some(record)),
+                Err(e) => tracing::debug!("Skipping malformed line: {}", e),
+            }
+        }
+    }
+}
+
+/// Feeds a recorded entry to a stream handler, as if it were arriving live.
+pub(crate) fn replay<H: StreamHandler>(handler: &mut H, content: &AgentOutputContent) {
+    match content {
+        AgentOutputContent::Text { text } => handler.on_text(text),
+        AgentOutputContent::ToolCall { name, id, input } => handler.on_tool_call(name, id, input),
+        AgentOutputContent::ToolResult { id, output } => handler.on_tool_result(id, output),
+        AgentOutputContent::Error { message } => handler.on_error(message),
+        AgentOutputContent::Warning { message } => handler.on_warning(message),
+        // Session totals aren't recorded, so there is nothing to show
+        AgentOutputContent::Complete { .. } => {}
+        // Callers start iterations from the entries' iteration numbers, which
+        // logs written before these markers existed have too
+        AgentOutputContent::IterationStart { .. } | AgentOutputContent::IterationEnd { .. } => {}
+    }
+}
+
+/// Picks the run log to show.
+///
+/// With no ID, uses the current run (from the events marker) and falls back to
+/// the most recent log. An ID may be abbreviated to any unique prefix.
+pub(crate) fn resolve_run_id(ctx: &LoopContext, requested: Option<&str>) -> Result<String> {
+    let runs = list_runs(&ctx.run_logs_dir());
+
+    let Some(requested) = requested else {
+        if let Some(current) = ctx.current_run_id()
+            && runs.contains(&current)
+        {
+            return Ok(current);
+        }
+        return runs
+            .last()
+            .cloned()
+            .context("No run logs found. Run `ralph run` to record one.");
+    };
+
+    let matches: Vec<&String> = runs
+        .iter()
+        .filter(|run| run.starts_with(requested))
+        .collect();
+    match matches.as_slice() {
+        [] => bail!("No run log found for '{}'", requested),
+        [run] => Ok((*run).clone()),
+        _ if runs.iter().any(|run| run == requested) => Ok(requested.to_string()),
+        _ => bail!(
+            "Run ID '{}' is ambiguous ({} matches)",
+            requested,
+            matches.len()
+        ),
+    }
+}
+
+/// Returns the IDs of all recorded runs, oldest first.
+fn list_runs(dir: &Path) -> Vec<String> {
+    let mut runs: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path: PathBuf = entry.ok()?.path();
+            if path.extension()? != "jsonl" {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        })
+        .collect();
+    // Run IDs are timestamps, so lexical order is chronological
+    runs.sort();
+    runs
+}
+
+/// Parses `--since` as a duration before now or an absolute timestamp.
+pub(crate) fn parse_since(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    let split = value.char_indices().last().map_or(0, |(i, _)| i);
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .with_context(|| format!("Invalid --since value '{}'", value))?;
+    let duration = match unit {
+        "s" => ChronoDuration::seconds(amount),
+        "m" => ChronoDuration::minutes(amount),
+        "h" => ChronoDuration::hours(amount),
+        "d" => ChronoDuration::days(amount),
+        _ => bail!(
+            "Invalid --since value '{}' (expected e.g. 30s, 10m, 2h, 1d or an RFC 3339 timestamp)",
+            value
+        ),
+    };
+    Ok(Utc::now() - duration)
+}
+
+/// Entry filters from the command line.
+struct LogFilter {
+    since: Option<DateTime<Utc>>,
+    iteration: Option<u32>,
+    grep: Option<Regex>,
+}
+
+impl LogFilter {
+    fn matches(&self, entry: &AgentOutputEntry) -> bool {
+        if self.iteration.is_some_and(|n| entry.iteration != n) {
+            return false;
+        }
+        if let Some(since) = self.since {
+            let newer = DateTime::parse_from_rfc3339(&entry.ts)
+                .is_ok_and(|ts| ts.with_timezone(&Utc) >= since);
+            if !newer {
+                return false;
+            }
+        }
+        if let Some(grep) = &self.grep {
+            return match &entry.content {
+                AgentOutputContent::Text { text } => grep.is_match(text),
+                AgentOutputContent::ToolCall { name, input, .. } => {
+                    grep.is_match(name) || grep.is_match(&input.to_string())
+                }
+                AgentOutputContent::ToolResult { output, .. } => grep.is_match(output),
+                AgentOutputContent::Error { message } | AgentOutputContent::Warning { message } => {
+                    grep.is_match(message)
+                }
+                AgentOutputContent::Complete { .. }
+                | AgentOutputContent::IterationStart { .. }
+                | AgentOutputContent::IterationEnd { .. } => false,
+            };
+        }
+        true
+    }
+}
+
+/// Replays log entries through a stream handler, marking iteration changes.
+struct LogPrinter<H> {
+    handler: H,
+    verbose: bool,
+    use_colors: bool,
+    current: Option<(u32, String)>,
+}
+
+impl<H: StreamHandler> LogPrinter<H> {
+    fn new(handler: H, verbose: bool, use_colors: bool) -> Self {
+        Self {
+            handler,
+            verbose,
+            use_colors,
+            current: None,
+        }
+    }
+
+    fn print(&mut self, entry: &AgentOutputEntry) {
+        // Skip entries the handler would not show, so they don't produce
+        // empty iteration headers
+        let shown = match entry.content {
+            AgentOutputContent::ToolResult { .. } => self.verbose,
+            AgentOutputContent::Complete { .. }
+            | AgentOutputContent::IterationStart { .. }
+            | AgentOutputContent::IterationEnd { .. } => false,
+            _ => true,
+        };
+        if !shown {
+            return;
+        }
+
+        let context = (entry.iteration, entry.hat.clone());
+        if self.current.as_ref() != Some(&context) {
+            let header = format!("── iteration {} · {} ──", entry.iteration, entry.hat);
+            if self.use_colors {
+                println!("\n{}{}{}", colors::CYAN, header, colors::RESET);
+            } else {
+                println!("\n{header}");
+            }
+            self.current = Some(context);
+        }
+
+        replay(&mut self.handler, &entry.content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(iteration: u32, ts: &str, content: AgentOutputContent) -> AgentOutputEntry {
+        AgentOutputEntry {
+            ts: ts.to_string(),
+            iteration,
+            hat: "builder".to_string(),
+            content,
+        }
+    }
+
+    fn text(s: &str) -> AgentOutputContent {
+        AgentOutputContent::Text {
+            text: s.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_since_durations_and_timestamps() {
+        let before = Utc::now();
+        let since = parse_since("10m").unwrap();
+        assert!(since <= before - ChronoDuration::minutes(10) + ChronoDuration::seconds(1));
+
+        let since = parse_since("2026-01-27T14:30:00Z").unwrap();
+        assert_eq!(since.to_rfc3339(), "2026-01-27T14:30:00+00:00");
+
+        assert!(parse_since("10x").is_err());
+        assert!(parse_since("").is_err());
+    }
+
+    #[test]
+    fn test_filter_by_iteration_since_and_grep() {
+        let filter = LogFilter {
+            since: Some(parse_since("2026-01-27T14:30:00Z").unwrap()),
+            iteration: Some(2),
+            grep: Some(Regex::new("cargo").unwrap()),
+        };
+        let ts = "2026-01-27T14:31:00+00:00";
+
+        assert!(filter.matches(&entry(2, ts, text("running cargo test"))));
+        assert!(!filter.matches(&entry(1, ts, text("running cargo test"))));
+        assert!(!filter.matches(&entry(
+            2,
+            "2026-01-27T14:29:00+00:00",
+            text("running cargo test")
+        )));
+        assert!(!filter.matches(&entry(2, ts, text("done"))));
+        assert!(filter.matches(&entry(
+            2,
+            ts,
+            AgentOutputContent::ToolCall {
+                name: "Bash".to_string(),
+                id: "t1".to_string(),
+                input: serde_json::json!({"command": "cargo build"}),
+            }
+        )));
+    }
+
+    #[test]
+    fn test_tail_waits_for_complete_lines() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("run.jsonl");
+        let line = r#"{"ts":"2026-01-27T14:31:00+00:00","iteration":1,"hat":"ralph","type":"text","text":"hi"}"#;
+        std::fs::write(&path, &line[..20]).unwrap();
+
+        let mut tail = JsonlTail::<AgentOutputEntry>::open(&path).unwrap();
+        assert!(tail.next_record().unwrap().is_none());
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        writeln!(file, "{}\nnot json", &line[20..]).unwrap();
+        writeln!(file).unwrap();
+        let entry = tail.next_record().unwrap().unwrap();
+        assert_eq!(entry.content, text("hi"));
+        assert!(tail.next_record().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_resolve_run_id() {
+        let temp = TempDir::new().unwrap();
+        let ctx = LoopContext::primary(temp.path().to_path_buf());
+        assert!(resolve_run_id(&ctx, None).is_err());
+
+        std::fs::create_dir_all(ctx.run_logs_dir()).unwrap();
+        for run in ["20260127-143022", "20260128-090000", "20260128-093000"] {
+            std::fs::write(ctx.run_log_path(run), "").unwrap();
+        }
+
+        // Latest run when there is no current-events marker
+        assert_eq!(resolve_run_id(&ctx, None).unwrap(), "20260128-093000");
+
+        // Current run wins when its log exists
+        std::fs::write(
+            ctx.current_events_marker(),
+            ".ralph/events-20260127-143022.jsonl",
+        )
+        .unwrap();
+        assert_eq!(resolve_run_id(&ctx, None).unwrap(), "20260127-143022");
+
+        assert_eq!(
+            resolve_run_id(&ctx, Some("20260127")).unwrap(),
+            "20260127-143022"
+        );
+        assert!(resolve_run_id(&ctx, Some("20260128")).is_err());
+        assert!(resolve_run_id(&ctx, Some("2025")).is_err());
+    }
+}
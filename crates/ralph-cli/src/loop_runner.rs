@@ -6,33 +6,495 @@
 
 use anyhow::{Context, Result};
 use ralph_adapters::{
-    CliBackend, CliExecutor, ConsoleStreamHandler, OutputFormat as BackendOutputFormat,
-    PrettyStreamHandler, PtyConfig, PtyExecutor, QuietStreamHandler, TuiStreamHandler,
+    AdapterRegistry, Checkpoint, CliBackend, CliExecutor, ConsoleStreamHandler, IterationInfo,
+    JsonStreamHandler, OutputConstraints, OutputFormat as BackendOutputFormat, PrettyStreamHandler,
+    ProbeStatus, PtyConfig, PtyExecutionResult, PtyExecutor, QuietStreamHandler, SmoothStream,
+    StreamHandler, ToolSummaries, TuiStreamHandler, probe_backend,
 };
+use ralph_core::diagnostics::{AgentOutputLogger, DiagnosticStreamHandler};
 use ralph_core::{
-    CompletionAction, EventLogger, EventLoop, EventParser, EventRecord, LoopCompletionHandler,
-    LoopContext, LoopHistory, LoopRegistry, MergeQueue, RalphConfig, Record, SessionRecorder,
-    SummaryWriter, TerminationReason,
+    AlertAction, AlertHit, AlertMatcher, AuditAction, AuditEntry, AuditLog, AuditSource,
+    CheckpointConfig, Cipher, CompletionAction, ConcurrencyGroups, ConcurrencySlot, ControlCommand,
+    CostAnomaly, CostAnomalyDetector, EnvironmentSnapshot, EventHistory, EventLogger, EventLoop,
+    EventParser, EventRecord, IterationMetrics, LocalSettings, LoopCompletionHandler, LoopContext,
+    LoopHistory, LoopRegistry, MergeQueue, PostMortemWriter, PowerMonitor, PromptArchive,
+    PromptRecord, RalphConfig, Record, RepoSet, RouterDecision, RunControl, RunPhase, RunStatus,
+    SealingWriter, SessionRecorder, SmoothStreamingConfig, SummaryWriter, TerminationReason,
+    TokenThrottle, ToolResultStore, estimate_tokens,
 };
 use ralph_proto::{Event, HatId};
-use ralph_tui::Tui;
+use ralph_tui::{Tui, TuiState};
+use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{BufWriter, IsTerminal, stdin, stdout};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tracing::{debug, error, info, warn};
 
-use crate::display::{build_tui_hat_map, print_iteration_separator, print_termination};
+use crate::display::{
+    build_tui_hat_colors, build_tui_hat_map, print_alert, print_cost_anomaly,
+    print_iteration_separator, print_termination,
+};
+use crate::maintenance::Maintenance;
+use crate::notifications::{Notification, NotifierRegistry, RunOutcome};
 use crate::process_management;
-use crate::{ColorMode, Verbosity};
+use crate::startup::{startup_summary, wait_for_start};
+use crate::{ColorMode, UiMode, Verbosity};
 
 /// Outcome of executing a prompt via PTY or CLI executor.
 pub(crate) struct ExecutionOutcome {
     pub output: String,
     pub success: bool,
     pub termination: Option<TerminationReason>,
+    /// Cost reported by the backend for this execution (USD).
+    pub cost_usd: f64,
+    /// Tokens reported by the backend for this execution; zero if unknown.
+    pub tokens: u64,
+    /// The agent's wrap-up summary, when an abort asked it for one.
+    pub checkpoint: Option<String>,
+}
+
+/// Acts on output alert hits after an iteration.
+///
+/// Every hit is logged (and printed outside the TUI, where highlighting
+/// isn't available). `notify` goes out through the configured notification
+/// channels (by default the terminal bell). `pause` blocks until
+/// the user resumes: Enter in the TUI, or a line on stdin otherwise. An
+/// interrupt also ends the pause so the loop can shut down. The pause and the
+/// resume are both recorded in the audit log.
+async fn handle_alert_hits(
+    hits: &[AlertHit],
+    tui_state: Option<&Arc<std::sync::Mutex<TuiState>>>,
+    use_colors: bool,
+    audit_log: &AuditLog,
+    notifiers: &NotifierRegistry,
+    mut interrupt_rx: tokio::sync::watch::Receiver<bool>,
+) {
+    for hit in hits {
+        warn!(alert = %hit.name, matched = %hit.matched, "Output alert triggered");
+        if tui_state.is_none() {
+            print_alert(hit, use_colors);
+        }
+    }
+
+    for hit in hits.iter().filter(|hit| hit.wants(AlertAction::Notify)) {
+        notifiers.notify(&Notification::alert(hit));
+    }
+
+    let Some(pause) = hits.iter().find(|hit| hit.wants(AlertAction::Pause)) else {
+        return;
+    };
+
+    match tui_state {
+        Some(state) => {
+            audit_log.record_or_warn(
+                AuditEntry::new(AuditAction::Pause, AuditSource::Alert).with_detail(&pause.name),
+            );
+            if let Ok(mut s) = state.lock() {
+                s.pause_for_alert(&pause.name, &pause.matched);
+            }
+            loop {
+                let paused = state.lock().is_ok_and(|s| s.alert_pause.is_some());
+                if !paused {
+                    audit_log
+                        .record_or_warn(AuditEntry::new(AuditAction::Resume, AuditSource::Tui));
+                    break;
+                }
+                tokio::select! {
+                    () = tokio::time::sleep(Duration::from_millis(100)) => {}
+                    _ = interrupt_rx.changed() => {
+                        if let Ok(mut s) = state.lock() {
+                            s.alert_pause = None;
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        None if stdin().is_terminal() => {
+            audit_log.record_or_warn(
+                AuditEntry::new(AuditAction::Pause, AuditSource::Alert).with_detail(&pause.name),
+            );
+            println!("Paused by alert '{}'. Press Enter to resume...", pause.name);
+            tokio::select! {
+                _ = ConsoleReader::shared().read_line() => audit_log.record_or_warn(
+                    AuditEntry::new(AuditAction::Resume, AuditSource::Console),
+                ),
+                _ = interrupt_rx.changed() => {}
+            }
+        }
+        None => {
+            warn!(alert = %pause.name, "Cannot pause on alert: stdin is not a terminal");
+        }
+    }
+}
+
+/// Reads lines from stdin on one thread kept for the whole process.
+///
+/// A read abandoned because an interrupt ended the pause stays pending on
+/// that thread, where it can't hold up the runtime's shutdown the way a
+/// blocking-pool task would, and the next read picks up its line instead of
+/// starting another.
+struct ConsoleReader {
+    requests: std::sync::mpsc::Sender<()>,
+    lines: tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<String>>,
+    pending: AtomicBool,
+}
+
+impl ConsoleReader {
+    fn shared() -> &'static Self {
+        static READER: std::sync::OnceLock<ConsoleReader> = std::sync::OnceLock::new();
+        READER.get_or_init(|| {
+            let (requests, requested) = std::sync::mpsc::channel::<()>();
+            let (line_tx, lines) = tokio::sync::mpsc::unbounded_channel();
+            std::thread::spawn(move || {
+                // Reads only when asked, so stdin is left alone between pauses
+                while requested.recv().is_ok() {
+                    let mut line = String::new();
+                    if stdin().read_line(&mut line).is_err() || line_tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+            Self {
+                requests,
+                lines: tokio::sync::Mutex::new(lines),
+                pending: AtomicBool::new(false),
+            }
+        })
+    }
+
+    /// Waits for the next line; `None` once stdin can no longer be read.
+    async fn read_line(&self) -> Option<String> {
+        if !self.pending.swap(true, Ordering::SeqCst) {
+            self.requests.send(()).ok()?;
+        }
+        let line = self.lines.lock().await.recv().await;
+        self.pending.store(false, Ordering::SeqCst);
+        line
+    }
+}
+
+/// Controller name of the TUI attached to the loop process, which holds the
+/// controller role when a run starts.
+pub(crate) const LOCAL_CONTROLLER: &str = "tui";
+
+/// Applies commands queued by the run's controller until the task is aborted.
+///
+/// `pause` and `resume` toggle `paused`, which the loop checks between
+/// iterations; `pause` also asks an interactive session to wrap up through
+/// `wrap_up_tx`. `abort` interrupts the loop like Ctrl+C.
+async fn poll_run_control(
+    run_control: RunControl,
+    paused: Arc<AtomicBool>,
+    interrupt_tx: tokio::sync::watch::Sender<bool>,
+    wrap_up_tx: tokio::sync::watch::Sender<bool>,
+) {
+    let mut tick = tokio::time::interval(Duration::from_millis(500));
+    loop {
+        tick.tick().await;
+        let commands = match run_control.take_pending() {
+            Ok(commands) => commands,
+            Err(e) => {
+                debug!("Failed to read run control file: {}", e);
+                continue;
+            }
+        };
+        for command in commands {
+            info!(?command, "Controller command received");
+            match command {
+                ControlCommand::Pause => {
+                    paused.store(true, Ordering::SeqCst);
+                    let _ = wrap_up_tx.send(true);
+                }
+                ControlCommand::Resume => paused.store(false, Ordering::SeqCst),
+                ControlCommand::Abort => {
+                    paused.store(false, Ordering::SeqCst);
+                    let _ = interrupt_tx.send(true);
+                }
+            }
+        }
+    }
+}
+
+/// Holds the loop between iterations while the controller has it paused.
+async fn wait_while_control_paused(
+    paused: &AtomicBool,
+    run_control: &RunControl,
+    tui_state: Option<&Arc<std::sync::Mutex<TuiState>>>,
+    mut interrupt_rx: tokio::sync::watch::Receiver<bool>,
+) {
+    if !paused.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let controller = run_control
+        .state()
+        .ok()
+        .and_then(|state| state.controller)
+        .unwrap_or_else(|| "unknown".to_string());
+    info!(controller = %controller, "Loop paused by controller");
+    match tui_state {
+        Some(state) => {
+            if let Ok(mut s) = state.lock() {
+                s.control_pause = Some(controller);
+            }
+        }
+        None => println!(
+            "Paused by controller '{}'. Waiting for resume...",
+            controller
+        ),
+    }
+
+    while paused.load(Ordering::SeqCst) {
+        tokio::select! {
+            () = tokio::time::sleep(Duration::from_millis(250)) => {}
+            _ = interrupt_rx.changed() => break,
+        }
+    }
+
+    if let Some(state) = tui_state
+        && let Ok(mut s) = state.lock()
+    {
+        s.control_pause = None;
+    }
+}
+
+/// How often a power pause re-reads the battery and suspend state.
+const POWER_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Holds the loop between iterations while the machine is on low battery or
+/// about to suspend, until power is back or the loop is interrupted.
+async fn wait_while_power_paused(
+    monitor: Option<&PowerMonitor>,
+    tui_state: Option<&Arc<std::sync::Mutex<TuiState>>>,
+    audit_log: &AuditLog,
+    mut interrupt_rx: tokio::sync::watch::Receiver<bool>,
+) {
+    let Some(monitor) = monitor else {
+        return;
+    };
+    let Some(reason) = monitor.pause_reason() else {
+        return;
+    };
+
+    info!(reason = %reason, "Loop paused for power");
+    audit_log.record_or_warn(
+        AuditEntry::new(AuditAction::Pause, AuditSource::Power).with_detail(&reason),
+    );
+    match tui_state {
+        Some(state) => {
+            if let Ok(mut s) = state.lock() {
+                s.power_pause = Some(reason);
+            }
+        }
+        None => println!("Paused: {}. Waiting for power...", reason),
+    }
+
+    loop {
+        tokio::select! {
+            () = tokio::time::sleep(POWER_POLL_INTERVAL) => {}
+            _ = interrupt_rx.changed() => break,
+        }
+        match monitor.pause_reason() {
+            Some(reason) => {
+                if let Some(state) = tui_state
+                    && let Ok(mut s) = state.lock()
+                {
+                    s.power_pause = Some(reason);
+                }
+            }
+            None => {
+                info!("Power restored, resuming loop");
+                audit_log.record_or_warn(AuditEntry::new(AuditAction::Resume, AuditSource::Power));
+                if tui_state.is_none() {
+                    println!("Power restored. Resuming...");
+                }
+                break;
+            }
+        }
+    }
+
+    if let Some(state) = tui_state
+        && let Ok(mut s) = state.lock()
+    {
+        s.power_pause = None;
+    }
+}
+
+/// Asks the router backend which of several hats with pending events works
+/// next, and logs its decision. Without a usable answer every pending hat is
+/// activated, as without a router.
+async fn route_next_hat(
+    event_loop: &mut EventLoop,
+    config: &RalphConfig,
+    adapters: &AdapterRegistry,
+    event_logger: &mut EventLogger,
+    iteration: u32,
+) {
+    let candidates = event_loop.routing_candidates();
+    if candidates.is_empty() {
+        return;
+    }
+    let router = &config.features.router;
+    let history = EventHistory::new(event_logger.path())
+        .read_last(router.history_events)
+        .unwrap_or_default();
+    let prompt = event_loop.routing_prompt(&candidates, &history);
+
+    let backend = match &router.backend {
+        Some(hat_backend) => adapters.hat_backend(hat_backend),
+        None => adapters.backend(&config.cli),
+    };
+    let backend = match backend {
+        Ok(backend) => backend,
+        Err(e) => {
+            warn!("Router backend unavailable: {}", e);
+            return;
+        }
+    };
+    let backend_name = router
+        .backend
+        .as_ref()
+        .map_or_else(|| config.cli.backend.clone(), |b| b.to_cli_backend());
+    let timeout = Duration::from_secs(config.adapter_settings(&backend_name).timeout);
+
+    let output = match CliExecutor::new(backend)
+        .execute_capture_with_timeout(&prompt, Some(timeout))
+        .await
+    {
+        Ok(result) => result.output,
+        Err(e) => {
+            warn!("Router call failed: {}", e);
+            return;
+        }
+    };
+    let Some(decision) = RouterDecision::parse(&output, &candidates) else {
+        warn!("Router gave no usable decision; activating all pending hats");
+        return;
+    };
+
+    let event = event_loop.route_to(&decision);
+    let record = EventRecord::new(iteration, "router", &event, Some(&decision.hat));
+    if let Err(e) = event_logger.log(&record) {
+        warn!("Failed to log router decision: {}", e);
+    }
+}
+
+/// Holds the next iteration while `backend` is over its token-per-minute
+/// limit. The TUI footer counts down to the start; an interrupt ends the
+/// wait early.
+async fn wait_for_token_budget(
+    throttle: &mut TokenThrottle,
+    backend: &str,
+    tokens_per_minute: Option<u64>,
+    tui_state: Option<&Arc<std::sync::Mutex<TuiState>>>,
+    mut interrupt_rx: tokio::sync::watch::Receiver<bool>,
+) {
+    let Some(limit) = tokens_per_minute else {
+        return;
+    };
+    let Some(wait) = throttle.wait_for(backend, limit) else {
+        return;
+    };
+
+    let reason = format!("{backend} at {}/{limit} tokens/min", throttle.used(backend));
+    info!(reason = %reason, wait_secs = wait.as_secs(), "Throttling next iteration");
+    match tui_state {
+        Some(state) => {
+            if let Ok(mut s) = state.lock() {
+                s.throttle_wait = Some((reason, s.now() + wait));
+            }
+        }
+        None => println!(
+            "Throttled: {}. Next iteration in {}s...",
+            reason,
+            wait.as_secs() + 1
+        ),
+    }
+
+    tokio::select! {
+        () = tokio::time::sleep(wait) => {}
+        _ = interrupt_rx.changed() => {}
+    }
+
+    if let Some(state) = tui_state
+        && let Ok(mut s) = state.lock()
+    {
+        s.throttle_wait = None;
+    }
+}
+
+/// How often a run queued for a concurrency slot retries.
+const SLOT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Takes a slot in `backend`'s concurrency group, queueing while other runs
+/// hold all of them. The queue shows in the run status (`ralph status`) and
+/// the TUI footer.
+///
+/// Returns `None` when no slot is held: the slots could not be read, or the
+/// loop was interrupted while queued.
+async fn acquire_concurrency_slot(
+    groups: &ConcurrencyGroups,
+    backend: &str,
+    run_status: &mut RunStatus,
+    run_status_path: &Path,
+    tui_state: Option<&Arc<std::sync::Mutex<TuiState>>>,
+    mut interrupt_rx: tokio::sync::watch::Receiver<bool>,
+) -> Option<ConcurrencySlot> {
+    let mut queued = false;
+    let slot = loop {
+        match groups.try_acquire(backend) {
+            Ok(Some(slot)) => break Some(slot),
+            Ok(None) => {}
+            Err(e) => {
+                warn!("Failed to take a concurrency slot, running without: {}", e);
+                break None;
+            }
+        }
+
+        if !queued {
+            queued = true;
+            let label = groups.group_for(backend).map_or_else(String::new, |group| {
+                let busy = groups.busy(group).unwrap_or(group.max_concurrent);
+                format!(
+                    "{} ({busy}/{} slots busy)",
+                    group.name, group.max_concurrent
+                )
+            });
+            info!(backend = %backend, group = %label, "Queued for a concurrency slot");
+            run_status.queued_for = Some(label.clone());
+            if let Err(e) = run_status.write(run_status_path) {
+                warn!("Failed to write run status: {}", e);
+            }
+            match tui_state {
+                Some(state) => {
+                    if let Ok(mut s) = state.lock() {
+                        s.queued_for = Some(label);
+                    }
+                }
+                None => println!("Queued: waiting for a slot in {}...", label),
+            }
+        }
+
+        tokio::select! {
+            () = tokio::time::sleep(SLOT_POLL_INTERVAL) => {}
+            _ = interrupt_rx.changed() => break None,
+        }
+    };
+
+    if queued {
+        run_status.queued_for = None;
+        if let Some(state) = tui_state
+            && let Ok(mut s) = state.lock()
+        {
+            s.queued_for = None;
+        }
+    }
+    slot
 }
 
 /// Core loop implementation supporting both fresh start and continue modes.
@@ -41,15 +503,16 @@ pub(crate) struct ExecutionOutcome {
 ///
 /// * `resume` - If true, publishes `task.resume` instead of `task.start`,
 ///   signaling the planner to read existing scratchpad rather than doing fresh gap analysis.
+/// * `ui` - Output mode; `None` streams in the style that suits the backend.
 /// * `record_session` - If provided, records all events to the specified JSONL file for replay testing.
 /// * `auto_merge_override` - Explicit auto-merge setting. If `Some(false)`, disables auto-merge
 ///   (equivalent to `--no-auto-merge`). If `None`, uses `config.features.auto_merge`.
 pub async fn run_loop_impl(
-    config: RalphConfig,
+    mut config: RalphConfig,
     color_mode: ColorMode,
     resume: bool,
-    enable_tui: bool,
-    verbosity: Verbosity,
+    mut ui: Option<UiMode>,
+    mut verbosity: Verbosity,
     record_session: Option<PathBuf>,
     loop_context: Option<LoopContext>,
     custom_args: Vec<String>,
@@ -64,6 +527,7 @@ pub async fn run_loop_impl(
     // Determine effective execution mode (with fallback logic)
     // Per spec: Claude backend requires PTY mode to avoid hangs
     // TUI mode is observation-only - uses streaming mode, not interactive
+    let enable_tui = ui == Some(UiMode::Tui);
     let interactive_requested = config.cli.default_mode == "interactive" && !enable_tui;
     let user_interactive = if interactive_requested {
         if stdout().is_terminal() {
@@ -87,6 +551,9 @@ pub async fn run_loop_impl(
     // Use watch channel for interrupt notification so we can race execution vs interrupt
     // Note: Signal handlers are spawned AFTER TUI initialization to avoid deadlock
     let (interrupt_tx, interrupt_rx) = tokio::sync::watch::channel(false);
+    // SIGTERM and SIGHUP also stop an interactive session that is writing a
+    // checkpoint: nobody is there to wait for it
+    let (abort_now_tx, mut abort_now_rx) = tokio::sync::watch::channel(false);
 
     // Resolve prompt content with precedence:
     // 1. CLI -p (inline text)
@@ -102,6 +569,18 @@ pub async fn run_loop_impl(
         .clone()
         .unwrap_or_else(|| LoopContext::primary(config.core.workspace_root.clone()));
 
+    // Settings saved from the TUI settings popup apply on top of ralph.yml;
+    // a saved `verbose = true` counts like RALPH_VERBOSE, below the CLI flags
+    let local_settings_path = ctx.local_settings_path();
+    let local_settings = LocalSettings::load(&local_settings_path).unwrap_or_else(|e| {
+        warn!("Ignoring {}: {}", local_settings_path.display(), e);
+        LocalSettings::default()
+    });
+    local_settings.apply(&mut config);
+    if local_settings.verbose == Some(true) && verbosity == Verbosity::Normal {
+        verbosity = Verbosity::Verbose;
+    }
+
     // Write loop ID to marker file for task ownership tracking.
     // For worktree loops, use the loop_id; for primary loops, generate one.
     // This file is read by `ralph tools task add` to tag new tasks.
@@ -109,6 +588,14 @@ pub async fn run_loop_impl(
         // Primary loop gets a timestamped ID
         format!("primary-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S"))
     });
+    // Transcripts in .ralph/ are sealed when encryption is on
+    let cipher = Cipher::load(&config.encryption, ctx.repo_root())
+        .context("Failed to load the encryption key")?;
+
+    // Full tool results are kept on disk; the TUI only holds previews
+    let tool_result_store =
+        ToolResultStore::new(ctx.tool_results_dir()).with_cipher(cipher.clone());
+
     let loop_id_marker = ctx.ralph_dir().join("current-loop-id");
     fs::write(&loop_id_marker, &loop_id).context("Failed to write current-loop-id marker")?;
     debug!(loop_id = %loop_id, marker = ?loop_id_marker, "Wrote loop ID marker file");
@@ -128,6 +615,10 @@ pub async fn run_loop_impl(
 
         debug!("Created events file for this run: {}", relative_events_path);
 
+        if let Err(e) = tool_result_store.clear() {
+            warn!("Failed to clear stored tool results: {}", e);
+        }
+
         // Clear scratchpad for fresh objective start
         // Stale content from previous runs can confuse the agent about current task state
         let scratchpad_path = ctx.scratchpad_path();
@@ -141,8 +632,38 @@ pub async fn run_loop_impl(
         }
     }
 
+    // Record agent output per run so `ralph logs` can replay or follow it.
+    // Resumed runs keep appending to the log of the run they continue.
+    let run_log = ctx.current_run_id().and_then(|run_id| {
+        match AgentOutputLogger::append(&ctx.run_log_path(&run_id)) {
+            Ok(logger) => Some(Arc::new(std::sync::Mutex::new(
+                logger.with_cipher(cipher.clone()),
+            ))),
+            Err(e) => {
+                warn!("Failed to open run log for {}: {}", run_id, e);
+                None
+            }
+        }
+    });
+
+    // Archive each iteration's exact prompt for `ralph prompts`
+    let prompt_archive = config
+        .archive_prompts
+        .then(|| ctx.current_run_id())
+        .flatten()
+        .map(|run_id| {
+            PromptArchive::new(ctx.prompt_archive_path(&run_id)).with_cipher(cipher.clone())
+        });
+
+    // Housekeeping that overlaps the waits between iterations
+    let mut maintenance = Maintenance::new(&config.maintenance, &config.core.workspace_root);
+
+    // Control actions (pause, abort, guidance, ...) are recorded for the run
+    let audit_log = ctx.audit_log();
+
     // Initialize event loop with context for proper path resolution
     let mut event_loop = EventLoop::with_context(config.clone(), ctx.clone());
+    event_loop.seal_diagnostics(cipher.clone());
 
     // Capture the Telegram shutdown flag so signal handlers can interrupt wait_for_response()
     let telegram_shutdown = event_loop.telegram_shutdown_flag();
@@ -157,12 +678,15 @@ pub async fn run_loop_impl(
 
     // Set up session recording if requested
     // This records all events to a JSONL file for replay testing
-    let _session_recorder: Option<Arc<SessionRecorder<BufWriter<File>>>> =
+    let session_recorder: Option<Arc<SessionRecorder<SealingWriter<BufWriter<File>>>>> =
         if let Some(record_path) = record_session {
             let file = File::create(&record_path).with_context(|| {
                 format!("Failed to create session recording file: {:?}", record_path)
             })?;
-            let recorder = Arc::new(SessionRecorder::new(BufWriter::new(file)));
+            let recorder = Arc::new(SessionRecorder::new(SealingWriter::new(
+                BufWriter::new(file),
+                cipher.clone(),
+            )));
 
             // Record metadata for the session
             recorder.record_meta(Record::meta_loop_start(
@@ -201,7 +725,10 @@ pub async fn run_loop_impl(
 
     // Create backend from config - TUI mode uses the same backend as non-TUI
     // The TUI is an observation layer that displays output, not a different mode
-    let mut backend = CliBackend::from_config(&config.cli).map_err(|e| anyhow::Error::new(e))?;
+    let adapters = AdapterRegistry::from_config(&config);
+    let mut backend = adapters
+        .backend(&config.cli)
+        .map_err(|e| anyhow::Error::new(e))?;
 
     // Append custom args from CLI if provided (e.g., `ralph run -b opencode -- --model="some-model"`)
     if !custom_args.is_empty() {
@@ -219,6 +746,7 @@ pub async fn run_loop_impl(
             interactive: user_interactive,
             idle_timeout_secs,
             workspace_root: config.core.workspace_root.clone(),
+            checkpoint: pty_checkpoint(&config.checkpoint),
             ..PtyConfig::from_env()
         };
         Some(PtyExecutor::new(backend.clone(), pty_config))
@@ -226,14 +754,31 @@ pub async fn run_loop_impl(
         None
     };
 
+    // Output alert rules (patterns were checked during config validation)
+    let alert_matcher =
+        AlertMatcher::new(&config.alerts).context("Invalid alert pattern in config")?;
+
+    // Backend `--version` output by command, probed once per loop
+    let mut backend_versions: HashMap<String, Option<String>> = HashMap::new();
+
     // Create termination signal for TUI shutdown
     let (terminated_tx, terminated_rx) = tokio::sync::watch::channel(false);
 
     // Wire TUI with termination signal and shared state
     // TUI is observation-only - works in both interactive and autonomous modes
-    // Requirements: both stdin and stdout must be terminals for TUI
-    // (Crossterm requires stdin for keyboard input, stdout for rendering)
-    let enable_tui = enable_tui && stdin().is_terminal() && stdout().is_terminal();
+    // A TUI that can't start here (no TTY, dumb terminal) degrades to
+    // pretty streaming rather than failing the run
+    let tui_unavailable = if enable_tui {
+        ralph_tui::terminal_unavailable()
+    } else {
+        None
+    };
+    if let Some(reason) = tui_unavailable {
+        warn!("TUI unavailable ({reason}); falling back to --ui pretty");
+        ui = Some(UiMode::Pretty);
+    }
+    let enable_tui = ui == Some(UiMode::Tui);
+    let hat_colors = build_tui_hat_colors(&config);
     let (mut tui_handle, tui_state) = if enable_tui {
         // Build hat map for dynamic topic-to-hat resolution
         // This allows TUI to display custom hats (e.g., "Security Reviewer")
@@ -241,14 +786,49 @@ pub async fn run_loop_impl(
         let hat_map = build_tui_hat_map(event_loop.registry());
         let tui = Tui::new()
             .with_hat_map(hat_map)
+            .with_hat_colors(hat_colors.clone())
+            .with_footer_segments(config.tui.footer.clone())
+            .with_alerts(alert_matcher.clone())
+            .with_tool_result_store(tool_result_store.clone())
             .with_termination_signal(terminated_rx);
 
         // Get shared state before spawning (for content streaming)
         let state = tui.state();
+        if let Ok(mut s) = state.lock() {
+            s.max_iterations = Some(config.event_loop.max_iterations);
+            s.max_cost_usd = config.event_loop.max_cost_usd;
+            s.model = config.cli.model.clone();
+            s.verbose = verbosity == Verbosity::Verbose;
+            s.theme = config.tui.theme;
+            if let Some(follow) = local_settings.follow {
+                s.set_following(follow);
+            }
+            s.settings = local_settings.clone();
+            s.settings_path = Some(local_settings_path.clone());
+            // The status file still describes the previous run at this point
+            let previous_run = RunStatus::read(&ctx.run_status_path()).ok().flatten();
+            s.startup_summary = startup_summary(
+                &config,
+                &backend,
+                &prompt_content,
+                event_loop.gates(),
+                previous_run.as_ref(),
+            );
+        }
 
         // Wire interrupt channel so TUI can signal main loop on Ctrl+C
-        // (raw mode prevents SIGINT from being generated by the OS)
-        let tui = tui.with_interrupt_tx(interrupt_tx.clone());
+        // (raw mode prevents SIGINT from being generated by the OS). The TUI
+        // gets its own channel so the abort can be audited before forwarding.
+        let (tui_interrupt_tx, mut tui_interrupt_rx) = tokio::sync::watch::channel(false);
+        let tui = tui.with_interrupt_tx(tui_interrupt_tx);
+        let interrupt_tx_tui = interrupt_tx.clone();
+        let audit_log_tui = audit_log.clone();
+        tokio::spawn(async move {
+            if tui_interrupt_rx.changed().await.is_ok() {
+                audit_log_tui.record_or_warn(AuditEntry::new(AuditAction::Abort, AuditSource::Tui));
+                let _ = interrupt_tx_tui.send(true);
+            }
+        });
 
         let observer = tui.observer();
         event_loop.add_observer(observer);
@@ -266,18 +846,44 @@ pub async fn run_loop_impl(
         tokio::time::sleep(Duration::from_millis(100)).await;
     }
 
+    // Spectators can watch the run freely; pause/abort is reserved for the
+    // controller, which starts out as the local TUI (if any).
+    let run_control = RunControl::new(ctx.control_path());
+    if let Err(e) = run_control.reset(enable_tui.then_some(LOCAL_CONTROLLER)) {
+        warn!("Failed to reset run control file: {}", e);
+    }
+    let control_paused = Arc::new(AtomicBool::new(false));
+    let (wrap_up_tx, _) = tokio::sync::watch::channel(false);
+    let control_task = tokio::spawn(poll_run_control(
+        run_control.clone(),
+        Arc::clone(&control_paused),
+        interrupt_tx.clone(),
+        wrap_up_tx.clone(),
+    ));
+    let _control_task = scopeguard::guard(control_task, |task| task.abort());
+    let power_monitor = PowerMonitor::from_config(&config.power);
+    let mut token_throttle = TokenThrottle::new();
+    let mut cost_anomalies = CostAnomalyDetector::new(config.cost_anomaly.clone());
+    let concurrency_groups =
+        ConcurrencyGroups::new(&config.daemon.concurrency_groups, ctx.repo_root());
+
     // Spawn signal handlers AFTER TUI initialization to avoid deadlock
     // (TUI must enter raw mode and create EventStream before signal handlers are registered)
 
-    // Spawn task to listen for SIGINT (Ctrl+C)
+    // Spawn task to listen for SIGINT (Ctrl+C). The handlers keep listening,
+    // so a second signal stops an agent that is writing its checkpoint.
     let interrupt_tx_sigint = interrupt_tx.clone();
     let telegram_shutdown_sigint = telegram_shutdown.clone();
+    let audit_log_sigint = audit_log.clone();
     tokio::spawn(async move {
-        if tokio::signal::ctrl_c().await.is_ok() {
+        while tokio::signal::ctrl_c().await.is_ok() {
             debug!("Interrupt received (SIGINT), terminating immediately...");
             if let Some(ref flag) = telegram_shutdown_sigint {
                 flag.store(true, std::sync::atomic::Ordering::Relaxed);
             }
+            audit_log_sigint.record_or_warn(
+                AuditEntry::new(AuditAction::Abort, AuditSource::Signal).with_actor("SIGINT"),
+            );
             let _ = interrupt_tx_sigint.send(true);
         }
     });
@@ -286,17 +892,24 @@ pub async fn run_loop_impl(
     #[cfg(unix)]
     {
         let interrupt_tx_sigterm = interrupt_tx.clone();
+        let abort_now_tx_sigterm = abort_now_tx.clone();
         let telegram_shutdown_sigterm = telegram_shutdown.clone();
+        let audit_log_sigterm = audit_log.clone();
         tokio::spawn(async move {
             let mut sigterm =
                 tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
                     .expect("Failed to register SIGTERM handler");
-            sigterm.recv().await;
-            debug!("SIGTERM received, terminating immediately...");
-            if let Some(ref flag) = telegram_shutdown_sigterm {
-                flag.store(true, std::sync::atomic::Ordering::Relaxed);
+            while sigterm.recv().await.is_some() {
+                debug!("SIGTERM received, terminating immediately...");
+                if let Some(ref flag) = telegram_shutdown_sigterm {
+                    flag.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+                audit_log_sigterm.record_or_warn(
+                    AuditEntry::new(AuditAction::Abort, AuditSource::Signal).with_actor("SIGTERM"),
+                );
+                let _ = abort_now_tx_sigterm.send(true);
+                let _ = interrupt_tx_sigterm.send(true);
             }
-            let _ = interrupt_tx_sigterm.send(true);
         });
     }
 
@@ -304,19 +917,79 @@ pub async fn run_loop_impl(
     #[cfg(unix)]
     {
         let interrupt_tx_sighup = interrupt_tx.clone();
+        let abort_now_tx_sighup = abort_now_tx.clone();
         let telegram_shutdown_sighup = telegram_shutdown.clone();
+        let audit_log_sighup = audit_log.clone();
         tokio::spawn(async move {
             let mut sighup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
                 .expect("Failed to register SIGHUP handler");
-            sighup.recv().await;
-            warn!("SIGHUP received (terminal closed), terminating immediately...");
-            if let Some(ref flag) = telegram_shutdown_sighup {
-                flag.store(true, std::sync::atomic::Ordering::Relaxed);
+            while sighup.recv().await.is_some() {
+                warn!("SIGHUP received (terminal closed), terminating immediately...");
+                if let Some(ref flag) = telegram_shutdown_sighup {
+                    flag.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+                audit_log_sighup.record_or_warn(
+                    AuditEntry::new(AuditAction::Abort, AuditSource::Signal).with_actor("SIGHUP"),
+                );
+                let _ = abort_now_tx_sighup.send(true);
+                let _ = interrupt_tx_sighup.send(true);
             }
-            let _ = interrupt_tx_sighup.send(true);
         });
     }
 
+    // Probe the backend before iteration 1 so auth/config problems fail fast
+    if config.cli.probe {
+        let running = ProbeStatus::Running {
+            backend: config.cli.backend.clone(),
+        };
+        if let Some(state) = &tui_state
+            && let Ok(mut s) = state.lock()
+        {
+            s.probe = Some(running.clone());
+        } else {
+            eprintln!("{}", running.summary());
+        }
+
+        let timeout = Duration::from_secs(u64::from(config.cli.probe_timeout_secs));
+        let mut interrupt_rx_probe = interrupt_rx.clone();
+        let probe = tokio::select! {
+            status = probe_backend(&backend, &config.cli.backend, timeout) => status,
+            _ = interrupt_rx_probe.changed() => {
+                let _ = terminated_tx.send(true);
+                return Ok(TerminationReason::Interrupted);
+            }
+        };
+        info!(status = %probe.summary(), "Backend probe finished");
+
+        if let Some(state) = &tui_state
+            && let Ok(mut s) = state.lock()
+        {
+            s.probe = Some(probe.clone());
+        } else {
+            eprintln!("{}", probe.summary());
+        }
+        if let ProbeStatus::Failed { reason, .. } = probe {
+            // Leave the failure on screen until the user exits the TUI
+            if let Some(handle) = tui_handle.take() {
+                let _ = handle.await;
+            }
+            anyhow::bail!(
+                "Backend '{}' failed its startup probe: {reason}",
+                config.cli.backend
+            );
+        }
+    }
+
+    // Hold iteration 1 until the user confirms the run summary
+    if config.tui.confirm_start
+        && let (Some(state), Some(handle)) = (&tui_state, &tui_handle)
+        && !wait_for_start(state, handle, interrupt_rx.clone()).await
+    {
+        info!("Run cancelled from the startup screen");
+        let _ = terminated_tx.send(true);
+        return Ok(TerminationReason::Interrupted);
+    }
+
     // Log execution mode - hat info already logged by initialize()
     let exec_mode = if user_interactive {
         "interactive"
@@ -375,6 +1048,34 @@ pub async fn run_loop_impl(
         }
     }
 
+    let notifiers = NotifierRegistry::from_config(&config.notifications);
+    let run_id = ctx.current_run_id();
+
+    // Progress for `ralph status`, rewritten at each iteration boundary
+    let run_status_path = ctx.run_status_path();
+    let mut run_status = RunStatus::new(
+        run_id.clone(),
+        config.event_loop.max_iterations,
+        config.event_loop.max_cost_usd,
+    );
+    if let Err(e) = run_status.write(&run_status_path) {
+        warn!("Failed to write run status: {}", e);
+    }
+
+    // Other repositories of a multi-repo run; relative paths are from the main repo,
+    // so worktree loops resolve them the same way as the primary loop
+    let repos = RepoSet::from_config(&config.repos, ctx.repo_root());
+
+    // Written on abnormal termination, next to the run's output log
+    let post_mortem_writer = run_id
+        .as_deref()
+        .filter(|_| config.post_mortem.enabled)
+        .map(|run_id| {
+            PostMortemWriter::from_context(&ctx, run_id)
+                .with_max_diff_lines(config.post_mortem.max_diff_lines)
+                .with_cipher(cipher.clone())
+        });
+
     // Helper closure to handle termination (writes summary, prints status, records history)
     let handle_termination = |reason: &TerminationReason,
                               state: &ralph_core::LoopState,
@@ -384,7 +1085,7 @@ pub async fn run_loop_impl(
                               auto_merge: bool,
                               prompt: &str| {
         // Per spec: Write summary file on termination
-        let summary_writer = SummaryWriter::default();
+        let summary_writer = SummaryWriter::default().with_audit_log(audit_log.clone());
         let scratchpad_path = std::path::Path::new(scratchpad);
         let scratchpad_opt = if scratchpad_path.exists() {
             Some(scratchpad_path)
@@ -400,6 +1101,40 @@ pub async fn run_loop_impl(
             warn!("Failed to write summary file: {}", e);
         }
 
+        // Spend reported by the backends, as recorded at each iteration boundary
+        let mut cost_usd = 0.0;
+        if let Ok(Some(mut status)) = RunStatus::read(&run_status_path) {
+            cost_usd = status.cost_usd;
+            status.phase = RunPhase::Finished;
+            status.iteration = state.iteration;
+            status.termination_reason = Some(reason.as_str().to_string());
+            if let Err(e) = status.write(&run_status_path) {
+                warn!("Failed to write run status: {}", e);
+            }
+        }
+
+        let outcome = RunOutcome {
+            reason,
+            iterations: state.iteration,
+            elapsed: state.elapsed(),
+            cost_usd,
+            run_id: run_id.clone(),
+            summary: fs::read_to_string(summary_writer.path()).unwrap_or_default(),
+        };
+        notifiers.notify(&Notification::run_finished(&outcome));
+
+        if let Some(writer) = post_mortem_writer.as_ref().filter(|_| reason.is_abnormal()) {
+            match writer.write(reason, state) {
+                Ok(post_mortem) => {
+                    info!("Post-mortem written to {}", writer.path().display());
+                    if config.post_mortem.notify {
+                        notifiers.notify(&Notification::post_mortem(&outcome, post_mortem));
+                    }
+                }
+                Err(e) => warn!("Failed to write post-mortem: {}", e),
+            }
+        }
+
         // Record termination in history
         if let Some(hist) = history {
             let reason_str = match reason {
@@ -415,6 +1150,10 @@ pub async fn run_loop_impl(
                 TerminationReason::ChaosModeComplete => "chaos_complete",
                 TerminationReason::ChaosModeMaxIterations => "chaos_max_iterations",
                 TerminationReason::RestartRequested => "restart_requested",
+                other => {
+                    error!(reason = ?other, "No history label for termination reason");
+                    "terminated"
+                }
             };
 
             if matches!(reason, TerminationReason::Interrupted) {
@@ -485,6 +1224,10 @@ pub async fn run_loop_impl(
                     TerminationReason::ChaosModeComplete => "chaos mode complete",
                     TerminationReason::ChaosModeMaxIterations => "chaos mode max iterations",
                     TerminationReason::RestartRequested => "restart requested",
+                    other => {
+                        error!(reason = ?other, "No review label for termination reason");
+                        "terminated"
+                    }
                 };
                 if let Err(e) = queue.mark_needs_review(loop_id, reason_str) {
                     warn!(loop_id = %loop_id, error = %e, "Failed to mark merge as needs-review");
@@ -498,7 +1241,7 @@ pub async fn run_loop_impl(
         // Per spec: merge loops do NOT enqueue themselves, even if run in worktree context
         if let Some(ctx) = context {
             if merge_loop_id.is_none() && matches!(reason, TerminationReason::CompletionPromise) {
-                let handler = LoopCompletionHandler::new(auto_merge);
+                let handler = LoopCompletionHandler::new(auto_merge).with_repos(repos.clone());
                 match handler.handle_completion(ctx, prompt) {
                     Ok(CompletionAction::None) => {
                         debug!("Loop completed, no action needed");
@@ -563,8 +1306,9 @@ pub async fn run_loop_impl(
             }
         }
 
-        // Print termination info to console (skip in TUI mode - TUI handles display)
-        if !enable_tui {
+        // Print termination info to console (skip in TUI mode - TUI handles display -
+        // and in JSON mode, where stdout carries only the event stream)
+        if !enable_tui && ui != Some(UiMode::Json) {
             print_termination(reason, state, use_colors);
         }
     };
@@ -712,6 +1456,18 @@ pub async fn run_loop_impl(
 
         let iteration = event_loop.state().iteration + 1;
 
+        // Let the router pick among competing hats before the prompt is built
+        if config.features.router.enabled {
+            route_next_hat(
+                &mut event_loop,
+                &config,
+                &adapters,
+                &mut event_logger,
+                iteration,
+            )
+            .await;
+        }
+
         // Determine which hat to display in iteration separator
         // When Ralph is coordinating (hat_id == "ralph"), show the active hat being worked on
         let display_hat = if hat_id.as_str() == "ralph" {
@@ -723,13 +1479,15 @@ pub async fn run_loop_impl(
         // Per spec: Print iteration demarcation separator
         // "Each iteration must be clearly demarcated in the output so users can
         // visually distinguish where one iteration ends and another begins."
-        // Skip when TUI is enabled - TUI has its own header showing iteration info
-        if tui_state.is_none() {
+        // Skip when TUI is enabled - TUI has its own header showing iteration info -
+        // and in JSON mode, whose stream has its own iteration_start records
+        if tui_state.is_none() && ui != Some(UiMode::Json) {
             print_iteration_separator(
                 iteration,
                 display_hat.as_str(),
                 event_loop.state().elapsed(),
                 config.event_loop.max_iterations,
+                ralph_adapters::hat_color(&hat_colors, display_hat.as_str()),
                 use_colors,
             );
         }
@@ -751,15 +1509,27 @@ pub async fn run_loop_impl(
             iteration, config.event_loop.max_iterations, hat_id
         );
 
-        // Build prompt for this hat
+        // Build prompt for this hat, stating the active hat's output constraints
+        let output_constraints = event_loop
+            .registry()
+            .get_config(&display_hat)
+            .map(OutputConstraints::from_hat)
+            .unwrap_or_default();
         let prompt = match event_loop.build_prompt(&hat_id) {
-            Some(p) => p,
+            Some(p) => p + &output_constraints.prompt_section(),
             None => {
                 error!("Failed to build prompt for hat '{}'", hat_id);
                 continue;
             }
         };
 
+        if let Some(archive) = &prompt_archive
+            && let Err(e) =
+                archive.append(&PromptRecord::new(iteration, display_hat.as_str(), &prompt))
+        {
+            warn!("Failed to archive prompt: {}", e);
+        }
+
         // In verbose mode, print the full prompt before execution
         if verbosity == Verbosity::Verbose {
             eprintln!("\n{}", "=".repeat(80));
@@ -769,6 +1539,32 @@ pub async fn run_loop_impl(
             eprintln!("{}\n", "=".repeat(80));
         }
 
+        // Settings changed in the TUI settings popup apply from this iteration
+        let changed_settings = tui_state.as_ref().and_then(|state| {
+            let mut s = state.lock().ok()?;
+            std::mem::take(&mut s.settings_changed).then(|| s.settings.clone())
+        });
+        if let Some(settings) = changed_settings {
+            if let Some(budget) = settings.budget() {
+                event_loop.set_max_cost_usd(budget);
+            }
+            match settings.model.as_deref() {
+                Some("") => backend.remove_model(),
+                Some(model) => backend.set_model(model),
+                None => {}
+            }
+            if let Some(verbose) = settings.verbose
+                && verbosity != Verbosity::Quiet
+            {
+                verbosity = if verbose {
+                    Verbosity::Verbose
+                } else {
+                    Verbosity::Normal
+                };
+            }
+            info!(?settings, "Applied settings changed in the TUI");
+        }
+
         // Execute the prompt (interactive or autonomous mode)
         // Determine which backend to use for this hat and the appropriate timeout
         // Hat-level backend configuration takes precedence over global cli.backend
@@ -783,7 +1579,7 @@ pub async fn run_loop_impl(
             match hat_backend_opt {
                 Some(hat_backend) => {
                     // Hat has custom backend configuration
-                    match CliBackend::from_hat_backend(hat_backend) {
+                    match adapters.hat_backend(hat_backend) {
                         Ok(hat_backend_instance) => {
                             debug!(
                                 "Using hat-level backend for '{}': {:?}",
@@ -842,40 +1638,84 @@ pub async fn run_loop_impl(
         let timeout_secs = config.adapter_settings(&backend_name_for_timeout).timeout;
         let timeout = Some(Duration::from_secs(timeout_secs));
 
-        // For TUI mode, get the shared lines buffer for this iteration.
-        // The buffer is owned by TuiState's IterationBuffer, so writes from
-        // TuiStreamHandler appear immediately in the TUI (real-time streaming).
-        let tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>> =
-            if let Some(ref state) = tui_state {
-                // Start new iteration and get handle to the LATEST iteration's lines buffer.
-                // We must use latest_iteration_lines_handle() instead of current_iteration_lines_handle()
-                // because the user may be viewing an older iteration while a new one executes.
-                if let Ok(mut s) = state.lock() {
-                    s.start_new_iteration();
-                    s.latest_iteration_lines_handle()
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
+        wait_for_token_budget(
+            &mut token_throttle,
+            &backend_name_for_timeout,
+            config
+                .adapter_settings(&backend_name_for_timeout)
+                .tokens_per_minute,
+            tui_state.as_ref(),
+            interrupt_rx.clone(),
+        )
+        .await;
+        let concurrency_slot = acquire_concurrency_slot(
+            &concurrency_groups,
+            &backend_name_for_timeout,
+            &mut run_status,
+            &run_status_path,
+            tui_state.as_ref(),
+            interrupt_rx.clone(),
+        )
+        .await;
+        // Don't let the agent race a formatter still rewriting files
+        if let Some(maintenance) = &mut maintenance {
+            maintenance.finish().await;
+        }
+
+        // Snapshot the environment for the session record and the TUI's
+        // iteration details
+        if session_recorder.is_some() || tui_state.is_some() {
+            let version = backend_versions
+                .entry(effective_backend.command.clone())
+                .or_insert_with(|| ralph_core::backend_version(&effective_backend.command))
+                .clone();
+            let snapshot = EnvironmentSnapshot::capture(
+                &config.core.workspace_root,
+                backend_name_for_timeout.as_str(),
+                version,
+            );
+            if let Some(recorder) = &session_recorder {
+                recorder.record_meta(Record::meta_environment(iteration, &snapshot));
+            }
+            // The TUI buffer is created when the iteration starts streaming
+            if let Some(state) = &tui_state
+                && let Ok(mut s) = state.lock()
+            {
+                s.set_next_environment(snapshot.summary_lines());
+            }
+        }
+
+        run_status.iteration = iteration;
+        run_status.active_hat = Some(display_hat.to_string());
+        if let Err(e) = run_status.write(&run_status_path) {
+            warn!("Failed to write run status: {}", e);
+        }
 
         // Race execution against interrupt signal for immediate termination on Ctrl+C
+        let execution_started = std::time::Instant::now();
         let mut interrupt_rx_clone = interrupt_rx.clone();
         let interrupt_rx_for_pty = interrupt_rx.clone();
-        let tui_lines_for_pty = tui_lines.clone();
+        let wrap_up_rx = wrap_up_tx.subscribe();
         let execute_future = async {
             if use_pty {
-                execute_pty(
+                // Boxed: the streaming state would otherwise sit inline in
+                // this loop's (already large) future
+                Box::pin(execute_pty(
                     pty_executor.as_mut(),
                     &effective_backend,
                     &config,
                     &prompt,
                     user_interactive,
                     interrupt_rx_for_pty,
+                    wrap_up_rx,
+                    ui,
                     verbosity,
-                    tui_lines_for_pty,
-                )
+                    tui_state.as_ref(),
+                    &tool_result_store,
+                    run_log.clone(),
+                    &hat_colors,
+                    &IterationInfo::new(iteration, display_hat.as_str(), &prompt),
+                ))
                 .await
             } else {
                 let executor = CliExecutor::new(effective_backend.clone());
@@ -886,13 +1726,28 @@ pub async fn run_loop_impl(
                     output: result.output,
                     success: result.success,
                     termination: None,
+                    cost_usd: 0.0,
+                    tokens: 0,
+                    checkpoint: None,
                 })
             }
         };
 
+        // Interactive sessions are asked to wrap up before an abort stops
+        // them, so the execution is left to finish on its own unless the
+        // abort can't wait (SIGTERM, SIGHUP)
+        let checkpointing =
+            use_pty && user_interactive && tui_state.is_none() && config.checkpoint.enabled;
+        let abort = async {
+            if checkpointing {
+                abort_now_rx.changed().await
+            } else {
+                interrupt_rx_clone.changed().await
+            }
+        };
         let outcome = tokio::select! {
-            result = execute_future => result?,
-            _ = interrupt_rx_clone.changed() => {
+            result = execute_future => Some(result?),
+            _ = abort => {
                 // Immediately terminate children via process group signal
                 #[cfg(unix)]
                 {
@@ -906,17 +1761,77 @@ pub async fn run_loop_impl(
                     tokio::time::sleep(Duration::from_millis(250)).await;
                     let _ = killpg(pgid, Signal::SIGKILL);
                 }
-
+                None
+            }
+        };
+        let outcome = match outcome {
+            Some(outcome) if !(checkpointing && *interrupt_rx.borrow()) => outcome,
+            outcome => {
+                if let Some(summary) = outcome.and_then(|outcome| outcome.checkpoint) {
+                    save_checkpoint_or_warn(
+                        &config,
+                        iteration,
+                        display_hat.as_str(),
+                        "interrupted",
+                        &summary,
+                    );
+                }
                 let reason = TerminationReason::Interrupted;
                 let terminate_event = event_loop.publish_terminate_event(&reason);
-                log_terminate_event(&mut event_logger, event_loop.state().iteration, &terminate_event);
-                handle_termination(&reason, event_loop.state(), &config.core.scratchpad, &loop_history, &loop_context, auto_merge, &prompt_content);
+                log_terminate_event(
+                    &mut event_logger,
+                    event_loop.state().iteration,
+                    &terminate_event,
+                );
+                handle_termination(
+                    &reason,
+                    event_loop.state(),
+                    &config.core.scratchpad,
+                    &loop_history,
+                    &loop_context,
+                    auto_merge,
+                    &prompt_content,
+                );
                 // Signal TUI to exit immediately on interrupt
                 let _ = terminated_tx.send(true);
                 return Ok(reason);
             }
         };
 
+        // A pause asked the agent to wrap up; the loop pauses below
+        if let Some(ref summary) = outcome.checkpoint {
+            save_checkpoint_or_warn(&config, iteration, display_hat.as_str(), "paused", summary);
+        }
+
+        // Spend reported by the backend, for the footer's budget and `ralph status`
+        run_status.cost_usd += outcome.cost_usd;
+        let tokens = match outcome.tokens {
+            0 => estimate_tokens(&prompt) + estimate_tokens(&outcome.output),
+            reported => reported,
+        };
+        token_throttle.record(&backend_name_for_timeout, tokens);
+        drop(concurrency_slot);
+        let anomaly =
+            cost_anomalies.record(iteration, display_hat.as_str(), outcome.cost_usd, tokens);
+        if let Some(ref anomaly) = anomaly {
+            warn!("Cost anomaly: {}", anomaly);
+            if tui_state.is_none() {
+                print_cost_anomaly(anomaly, use_colors);
+            }
+        }
+        if let Some(ref state) = tui_state
+            && let Ok(mut s) = state.lock()
+        {
+            s.total_cost_usd = run_status.cost_usd;
+            s.cost_anomaly = anomaly.as_ref().map(CostAnomaly::short);
+        }
+        if let Some(anomaly) = anomaly {
+            event_loop.record_cost_anomaly(anomaly);
+        }
+        if let Err(e) = run_status.write(&run_status_path) {
+            warn!("Failed to write run status: {}", e);
+        }
+
         if let Some(reason) = outcome.termination {
             let terminate_event = event_loop.publish_terminate_event(&reason);
             log_terminate_event(
@@ -940,8 +1855,43 @@ pub async fn run_loop_impl(
             return Ok(reason);
         }
 
-        let output = outcome.output;
-        let success = outcome.success;
+        // Enforce the hat's output constraints; a response breaking them
+        // fails the iteration
+        let constrained = output_constraints.apply(&outcome.output);
+        for violation in &constrained.violations {
+            warn!("Hat '{}' {}", display_hat, violation);
+        }
+        let output = constrained.output;
+        let success = outcome.success && constrained.violations.is_empty();
+
+        // Act on output alerts before the next iteration starts
+        let alert_hits = alert_matcher.scan(&output);
+        if !alert_hits.is_empty() {
+            handle_alert_hits(
+                &alert_hits,
+                tui_state.as_ref(),
+                use_colors,
+                &audit_log,
+                &notifiers,
+                interrupt_rx.clone(),
+            )
+            .await;
+        }
+
+        wait_while_control_paused(
+            &control_paused,
+            &run_control,
+            tui_state.as_ref(),
+            interrupt_rx.clone(),
+        )
+        .await;
+        wait_while_power_paused(
+            power_monitor.as_ref(),
+            tui_state.as_ref(),
+            &audit_log,
+            interrupt_rx.clone(),
+        )
+        .await;
 
         // Note: TUI lines are now written directly to IterationBuffer during streaming,
         // so no post-execution transfer is needed.
@@ -955,6 +1905,20 @@ pub async fn run_loop_impl(
             event_loop.registry(),
         );
 
+        // Record what the iteration cost for `ralph export-metrics`
+        if let Some(history) = &loop_history {
+            let metrics = IterationMetrics {
+                run_id: run_id.clone(),
+                hat: display_hat.to_string(),
+                duration_ms: execution_started.elapsed().as_millis() as u64,
+                cost_usd: outcome.cost_usd,
+                tokens,
+            };
+            if let Err(e) = history.record_iteration_metrics(iteration, success, &metrics) {
+                warn!("Failed to record iteration in history: {}", e);
+            }
+        }
+
         // Process output
         if let Some(reason) = event_loop.process_output(&hat_id, &output, success) {
             // Per spec: Log "All done! {promise} detected." when completion promise found
@@ -1049,6 +2013,10 @@ pub async fn run_loop_impl(
             );
         }
 
+        if let Some(maintenance) = &mut maintenance {
+            maintenance.start(iteration);
+        }
+
         // Cooldown delay between iterations (skip for human events)
         let cooldown = config.event_loop.cooldown_delay_seconds;
         if cooldown > 0 && !event_loop.has_pending_human_events() {
@@ -1096,6 +2064,65 @@ fn convert_termination_type(
     }
 }
 
+/// The wrap-up request interactive sessions get before an abort stops them.
+fn pty_checkpoint(config: &CheckpointConfig) -> Option<Checkpoint> {
+    config.enabled.then(|| Checkpoint {
+        instruction: config.instruction.clone(),
+        timeout: Duration::from_secs(config.timeout_seconds),
+    })
+}
+
+/// Saves a checkpoint summary to the configured scratchpad, warning on failure.
+fn save_checkpoint_or_warn(
+    config: &RalphConfig,
+    iteration: u32,
+    hat: &str,
+    why: &str,
+    summary: &str,
+) {
+    let scratchpad = config.core.workspace_root.join(&config.core.scratchpad);
+    match save_checkpoint(&scratchpad, iteration, hat, why, summary) {
+        Ok(()) => info!("Saved the agent's checkpoint to {}", scratchpad.display()),
+        Err(e) => warn!("Failed to save checkpoint: {}", e),
+    }
+}
+
+/// Appends a stopped agent's checkpoint summary to the scratchpad, so the
+/// next iteration or run (`ralph run --continue`) starts from it.
+fn save_checkpoint(
+    scratchpad: &Path,
+    iteration: u32,
+    hat: &str,
+    why: &str,
+    summary: &str,
+) -> Result<()> {
+    use std::io::Write;
+
+    if let Some(parent) = scratchpad.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(scratchpad)
+        .with_context(|| format!("Failed to open {}", scratchpad.display()))?;
+    writeln!(
+        file,
+        "\n## Checkpoint (iteration {iteration}, {hat}, {why})\n\n{summary}"
+    )?;
+    Ok(())
+}
+
+/// The pacer for agent text, when smooth streaming is enabled.
+fn smooth_stream(config: &SmoothStreamingConfig) -> Option<SmoothStream> {
+    config.enabled.then(|| {
+        SmoothStream::new(
+            config.chars_per_second,
+            Duration::from_millis(config.max_lag_ms),
+        )
+    })
+}
+
 async fn execute_pty(
     executor: Option<&mut PtyExecutor>,
     backend: &CliBackend,
@@ -1103,8 +2130,14 @@ async fn execute_pty(
     prompt: &str,
     interactive: bool,
     interrupt_rx: tokio::sync::watch::Receiver<bool>,
+    wrap_up_rx: tokio::sync::watch::Receiver<bool>,
+    ui: Option<UiMode>,
     verbosity: Verbosity,
-    tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>>,
+    tui_state: Option<&Arc<std::sync::Mutex<TuiState>>>,
+    tool_result_store: &ToolResultStore,
+    run_log: Option<Arc<std::sync::Mutex<AgentOutputLogger>>>,
+    hat_colors: &HashMap<HatId, ratatui::style::Color>,
+    iteration: &IterationInfo,
 ) -> Result<ExecutionOutcome> {
     use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 
@@ -1128,15 +2161,16 @@ async fn execute_pty(
             interactive,
             idle_timeout_secs,
             workspace_root: config.core.workspace_root.clone(),
+            checkpoint: pty_checkpoint(&config.checkpoint),
             ..PtyConfig::from_env()
         };
         temp_executor = PtyExecutor::new(backend.clone(), pty_config);
         &mut temp_executor
     };
 
-    // Set TUI mode flag when TUI is connected (tui_lines is Some)
+    // Set TUI mode flag when TUI is connected (tui_state is Some)
     // This replaces the broken output_rx.is_none() detection in PtyExecutor
-    if tui_lines.is_some() {
+    if tui_state.is_some() {
         exec.set_tui_mode(true);
     }
 
@@ -1154,50 +2188,73 @@ async fn execute_pty(
         }
     });
 
+    // Custom tool summary templates from config
+    let tool_summaries = ToolSummaries::new(config.tool_summaries.clone());
+
     // Run PTY executor with shared interrupt channel
-    let result = if interactive && tui_lines.is_none() {
+    let result = if interactive && tui_state.is_none() {
         // Raw interactive mode only when not using TUI (TUI handles its own terminal)
+        exec.set_wrap_up_signal(wrap_up_rx);
         exec.run_interactive(prompt, interrupt_rx).await
-    } else if let Some(lines) = tui_lines {
-        // TUI mode: use TuiStreamHandler to capture output for TUI display
+    } else if let Some(state) = tui_state {
+        // TUI mode: the handler's iteration start creates the iteration's
+        // buffers in the TUI, so output streams into them as it arrives
         let verbose = verbosity == Verbosity::Verbose;
-        let mut handler = TuiStreamHandler::with_lines(verbose, lines);
-        exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-            .await
+        let mut handler = TuiStreamHandler::with_lines(verbose, Arc::default())
+            .with_tool_summaries(tool_summaries)
+            .with_result_preview(config.tui.tool_result_preview)
+            .with_iteration_separators(hat_colors.clone())
+            .with_text_budget(config.tui.text_render_budget)
+            .with_tool_results(tool_result_store.clone(), Arc::default())
+            .with_iteration_buffers(TuiState::iteration_buffers(state));
+        if let Some(smooth) = smooth_stream(&config.tui.smooth_streaming) {
+            handler = handler.with_smooth_streaming(smooth);
+        }
+        observe(exec, prompt, interrupt_rx, handler, run_log, iteration).await
     } else {
         // Use streaming handler for non-interactive mode (respects verbosity)
-        // Use PrettyStreamHandler for StreamJson backends (Claude) on TTY for markdown rendering
-        // Use ConsoleStreamHandler for Text format backends (Kiro, Gemini, etc.) for immediate output
+        // Unless --ui picks one: PrettyStreamHandler for StreamJson backends (Claude) on TTY
+        // for markdown rendering, ConsoleStreamHandler for Text format backends (Kiro,
+        // Gemini, etc.) for immediate output
         let use_pretty =
             backend.output_format == BackendOutputFormat::StreamJson && stdout().is_terminal();
+        let mode = match ui {
+            Some(mode) if mode != UiMode::Tui => mode,
+            _ if verbosity == Verbosity::Quiet => UiMode::Quiet,
+            _ if use_pretty => UiMode::Pretty,
+            _ => UiMode::Plain,
+        };
+        let verbose = verbosity == Verbosity::Verbose;
 
-        match verbosity {
-            Verbosity::Quiet => {
-                let mut handler = QuietStreamHandler;
-                exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-                    .await
+        match mode {
+            UiMode::Quiet => {
+                observe(
+                    exec,
+                    prompt,
+                    interrupt_rx,
+                    QuietStreamHandler,
+                    run_log,
+                    iteration,
+                )
+                .await
             }
-            Verbosity::Normal => {
-                if use_pretty {
-                    let mut handler = PrettyStreamHandler::new(false);
-                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-                        .await
-                } else {
-                    let mut handler = ConsoleStreamHandler::new(false);
-                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-                        .await
-                }
+            UiMode::Json => {
+                let handler = JsonStreamHandler::new();
+                observe(exec, prompt, interrupt_rx, handler, run_log, iteration).await
             }
-            Verbosity::Verbose => {
-                if use_pretty {
-                    let mut handler = PrettyStreamHandler::new(true);
-                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-                        .await
-                } else {
-                    let mut handler = ConsoleStreamHandler::new(true);
-                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-                        .await
+            UiMode::Pretty => {
+                let mut handler = PrettyStreamHandler::new(verbose)
+                    .with_tool_summaries(tool_summaries)
+                    .with_hat_colors(hat_colors.clone());
+                if let Some(smooth) = smooth_stream(&config.tui.smooth_streaming) {
+                    handler = handler.with_smooth_streaming(smooth);
                 }
+                observe(exec, prompt, interrupt_rx, handler, run_log, iteration).await
+            }
+            UiMode::Plain | UiMode::Tui => {
+                let handler =
+                    ConsoleStreamHandler::new(verbose).with_tool_summaries(tool_summaries);
+                observe(exec, prompt, interrupt_rx, handler, run_log, iteration).await
             }
         }
     };
@@ -1219,6 +2276,9 @@ async fn execute_pty(
                 output: output_for_parsing,
                 success: pty_result.success,
                 termination,
+                cost_usd: pty_result.total_cost_usd,
+                tokens: pty_result.total_tokens,
+                checkpoint: pty_result.checkpoint,
             })
         }
         Err(e) => {
@@ -1229,6 +2289,40 @@ async fn execute_pty(
     }
 }
 
+/// Streams a PTY execution through `handler`, recording the output to the
+/// run log when one is open.
+async fn observe<H: StreamHandler>(
+    exec: &mut PtyExecutor,
+    prompt: &str,
+    interrupt_rx: tokio::sync::watch::Receiver<bool>,
+    handler: H,
+    run_log: Option<Arc<std::sync::Mutex<AgentOutputLogger>>>,
+    iteration: &IterationInfo,
+) -> std::io::Result<PtyExecutionResult> {
+    async fn run<H: StreamHandler>(
+        exec: &mut PtyExecutor,
+        prompt: &str,
+        interrupt_rx: tokio::sync::watch::Receiver<bool>,
+        mut handler: H,
+        iteration: &IterationInfo,
+    ) -> std::io::Result<PtyExecutionResult> {
+        handler.on_iteration_start(iteration);
+        let result = exec
+            .run_observe_streaming(prompt, interrupt_rx, &mut handler)
+            .await;
+        handler.on_iteration_end(iteration, result.as_ref().is_ok_and(|r| r.success));
+        result
+    }
+
+    match run_log {
+        Some(logger) => {
+            let handler = DiagnosticStreamHandler::new(handler, logger);
+            run(exec, prompt, interrupt_rx, handler, iteration).await
+        }
+        None => run(exec, prompt, interrupt_rx, handler, iteration).await,
+    }
+}
+
 /// Logs events parsed from output to the event history file.
 ///
 /// When an event has no subscriber (orphan), also logs an `event.orphaned`
@@ -1628,18 +2722,18 @@ pub async fn start_loop(
 
     let loop_context = ralph_core::LoopContext::primary(workspace_root);
 
-    // Run the loop headlessly
-    run_loop_impl(
+    // Run the loop headlessly; boxed, as the loop's future is large
+    Box::pin(run_loop_impl(
         config,
         ColorMode::Never,
         false, // not resume
-        false, // no TUI
+        None,  // stream in the backend's style
         Verbosity::Normal,
         None, // no session recording
         Some(loop_context),
         Vec::new(), // no custom args
         None,       // default auto-merge
-    )
+    ))
     .await
 }
 
@@ -959,6 +959,7 @@ fn spawn_merge_ralph(cwd: &std::path::Path, loop_id: &str) -> Result<()> {
             "-c",
             ".ralph/merge-loop-config.yml",
             "--exclusive",
+            "--yes",
             "-p",
             &format!("Merge loop {} from branch ralph/{}", loop_id, loop_id),
         ])
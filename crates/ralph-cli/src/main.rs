@@ -12,33 +12,53 @@
 //! - Code task generation via `ralph code-task`
 //! - Work item tracking via `ralph task`
 
+mod attach;
+mod backends;
 mod bot;
+mod control;
+mod decrypt;
 mod display;
+mod doctor;
+mod email;
+mod export;
+mod export_metrics;
 mod hats;
+mod http_api;
 mod init;
 mod interact;
+mod logs;
 mod loop_runner;
 mod loops;
+mod maintenance;
 mod memory;
+mod notifications;
+mod parquet;
 mod presets;
+mod prompts;
+mod repos;
+mod schedule;
+mod selftest;
 mod skill_cli;
 mod sop_runner;
+mod startup;
+mod status;
 mod task_cli;
 mod tools;
 mod web;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use clap::{ArgAction, Parser, Subcommand, ValueEnum};
-use ralph_adapters::detect_backend;
+use ralph_adapters::{AdapterRegistry, detect_backend};
 use ralph_core::{
-    EventHistory, LockError, LoopContext, LoopEntry, LoopLock, LoopRegistry, RalphConfig,
-    TerminationReason,
+    AuditAction, AuditEntry, AuditSource, EventHistory, LockError, LoopContext, LoopEntry,
+    LoopLock, LoopRegistry, RalphConfig, TerminationReason, truncate_with_ellipsis,
     worktree::{WorktreeConfig, create_worktree, ensure_gitignore},
 };
 use std::fs;
 use std::io::{IsTerminal, Write, stdout};
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 
 // Unix-specific process management for process group leadership
 #[cfg(unix)]
@@ -195,6 +215,47 @@ impl Verbosity {
     }
 }
 
+/// How `run` and `resume` present the agent's output (`--ui`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum UiMode {
+    /// Full-screen terminal UI
+    Tui,
+    /// Streamed output with rendered markdown
+    Pretty,
+    /// Streamed raw text
+    Plain,
+    /// No streamed output
+    Quiet,
+    /// One JSON object per stream event (NDJSON)
+    Json,
+}
+
+impl UiMode {
+    /// Settles `ui` before the run starts. Without `--ui` the TUI is used
+    /// unless `--no-tui` or `--autonomous` is given; otherwise `ui` stays
+    /// unset and the stream style follows the backend.
+    ///
+    /// A default TUI that can't start falls back to `pretty` and the reason
+    /// is returned for a notice; an explicit `--ui tui` fails instead.
+    fn settle(ui: &mut Option<UiMode>, no_tui: bool, autonomous: bool) -> Result<Option<String>> {
+        let explicit = ui.is_some();
+        if !explicit && !no_tui && !autonomous {
+            *ui = Some(UiMode::Tui);
+        }
+        if *ui != Some(UiMode::Tui) {
+            return Ok(None);
+        }
+        let Some(reason) = ralph_tui::terminal_unavailable() else {
+            return Ok(None);
+        };
+        if explicit {
+            bail!("--ui tui: {reason}");
+        }
+        *ui = Some(UiMode::Pretty);
+        Ok(Some(reason))
+    }
+}
+
 /// Output format for events command.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
 pub enum OutputFormat {
@@ -346,6 +407,17 @@ pub(crate) fn load_config_with_overrides(
     Ok(config)
 }
 
+/// Returns the config file named on the command line, or `ralph.yml`.
+fn primary_config_path(config_sources: &[ConfigSource]) -> PathBuf {
+    config_sources
+        .iter()
+        .find_map(|source| match source {
+            ConfigSource::File(path) => Some(path.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| PathBuf::from("ralph.yml"))
+}
+
 /// Ralph Orchestrator - Multi-agent orchestration framework
 #[derive(Parser, Debug)]
 #[command(name = "ralph", version, about)]
@@ -383,12 +455,45 @@ enum Commands {
     /// View event history for debugging
     Events(EventsArgs),
 
+    /// Print or follow a run's agent output without the TUI
+    Logs(logs::LogsArgs),
+
+    /// List, show and diff the prompts archived for each iteration
+    Prompts(prompts::PromptsArgs),
+
+    /// Export a run as a JSON bundle, optionally anonymized for bug reports
+    Export(export::ExportArgs),
+
+    /// Export per-iteration metrics (duration, cost, tokens, tools) as CSV or Parquet
+    ExportMetrics(export_metrics::ExportMetricsArgs),
+
+    /// Print a file written with encryption enabled as plain text
+    Decrypt(decrypt::DecryptArgs),
+
+    /// Watch a run read-only in a TUI or over HTTP
+    Attach(attach::AttachArgs),
+
+    /// Open a run's TUI at a line, e.g. `ralph open iter12:3401`
+    Open(attach::OpenArgs),
+
+    /// Inspect or change who controls the running loop
+    Control(control::ControlArgs),
+
+    /// Show the current run's progress
+    Status(status::StatusArgs),
+
     /// Initialize a new ralph.yml configuration file
     Init(InitArgs),
 
     /// Clean up Ralph artifacts (.agent/ directory)
     Clean(CleanArgs),
 
+    /// Check the workspace for setup problems, and optionally fix them
+    Doctor(doctor::DoctorArgs),
+
+    /// Run a scripted loop with a mock backend to check the installation
+    Selftest(selftest::SelftestArgs),
+
     /// Emit an event to the current run's events file with proper JSON formatting
     Emit(EmitArgs),
 
@@ -410,6 +515,12 @@ enum Commands {
     /// Manage configured hats
     Hats(hats::HatsArgs),
 
+    /// List the available backends and their capabilities
+    Backends(backends::BackendsArgs),
+
+    /// Inspect the repositories of a multi-repo run
+    Repos(repos::ReposArgs),
+
     /// Run the web dashboard
     Web(web::WebArgs),
 
@@ -484,12 +595,25 @@ struct RunArgs {
     #[arg(short, long, conflicts_with = "no_tui")]
     autonomous: bool,
 
+    /// Output mode: tui, pretty, plain, quiet or json.
+    /// Without it, the TUI is used when the terminal supports it.
+    #[arg(long, value_enum, conflicts_with = "no_tui")]
+    ui: Option<UiMode>,
+
     /// Idle timeout in seconds for interactive mode (default: 30).
     /// Process is terminated after this many seconds of inactivity.
     /// Set to 0 to disable idle timeout.
     #[arg(long)]
     idle_timeout: Option<u32>,
 
+    /// Start without confirming the run summary in the TUI
+    #[arg(short = 'y', long)]
+    yes: bool,
+
+    /// Start even though max_cost_usd is above the confirmation threshold
+    #[arg(long)]
+    confirm_budget: bool,
+
     // ─────────────────────────────────────────────────────────────────────────
     // Multi-Loop Concurrency Options
     // ─────────────────────────────────────────────────────────────────────────
@@ -554,10 +678,22 @@ struct ResumeArgs {
     #[arg(short, long, conflicts_with = "no_tui")]
     autonomous: bool,
 
+    /// Output mode: tui, pretty, plain, quiet or json
+    #[arg(long, value_enum, conflicts_with = "no_tui")]
+    ui: Option<UiMode>,
+
     /// Idle timeout in seconds for TUI mode
     #[arg(long)]
     idle_timeout: Option<u32>,
 
+    /// Start without confirming the run summary in the TUI
+    #[arg(short = 'y', long)]
+    yes: bool,
+
+    /// Start even though max_cost_usd is above the confirmation threshold
+    #[arg(long)]
+    confirm_budget: bool,
+
     /// Enable verbose output (show tool results and session summary)
     #[arg(short = 'v', long, conflicts_with = "quiet")]
     verbose: bool,
@@ -680,13 +816,27 @@ async fn main() -> Result<()> {
     // This prevents the terminal from being left in raw mode or alternate screen
     install_panic_hook();
 
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+
+    // Settle the output mode up front: it decides where logs go, and a TUI
+    // that can't start must fall back before anything is drawn
+    let tui_fallback = match &mut cli.command {
+        Some(Commands::Run(args)) => UiMode::settle(&mut args.ui, args.no_tui, args.autonomous)?,
+        Some(Commands::Resume(args)) => UiMode::settle(&mut args.ui, args.no_tui, args.autonomous)?,
+        _ => None,
+    };
 
     // Detect if TUI mode is requested - TUI owns the terminal, so logs must not go to stdout
-    // TUI is enabled by default unless --no-tui is specified or --autonomous is used
     let tui_enabled = match &cli.command {
-        Some(Commands::Run(args)) => !args.no_tui && !args.autonomous,
-        Some(Commands::Resume(args)) => !args.no_tui && !args.autonomous,
+        Some(Commands::Run(args)) => args.ui == Some(UiMode::Tui),
+        Some(Commands::Resume(args)) => args.ui == Some(UiMode::Tui),
+        Some(Commands::Attach(args)) => args.http.is_none(),
+        Some(Commands::Open(_)) => true,
+        _ => false,
+    };
+    let json_ui = match &cli.command {
+        Some(Commands::Run(args)) => args.ui == Some(UiMode::Json),
+        Some(Commands::Resume(args)) => args.ui == Some(UiMode::Json),
         _ => false,
     };
 
@@ -739,7 +889,12 @@ async fn main() -> Result<()> {
         }
         // If log file creation fails, silently continue without logging
     } else {
-        // Normal mode: logs go to stdout
+        // Normal mode: logs go to stdout, unless it carries the JSON event stream
+        let log_writer = if json_ui {
+            BoxMakeWriter::new(std::io::stderr)
+        } else {
+            BoxMakeWriter::new(std::io::stdout)
+        };
         if diagnostics_enabled {
             // Normal mode + diagnostics: stdout + trace layer
             use ralph_core::diagnostics::DiagnosticTraceLayer;
@@ -751,38 +906,79 @@ async fn main() -> Result<()> {
             {
                 if let Ok(trace_layer) = DiagnosticTraceLayer::new(session_dir) {
                     tracing_subscriber::registry()
-                        .with(tracing_subscriber::fmt::layer())
+                        .with(tracing_subscriber::fmt::layer().with_writer(log_writer))
                         .with(tracing_subscriber::EnvFilter::new(filter))
                         .with(trace_layer)
                         .init();
                 } else {
                     // Fallback: just stdout
-                    tracing_subscriber::fmt().with_env_filter(filter).init();
+                    tracing_subscriber::fmt()
+                        .with_env_filter(filter)
+                        .with_writer(log_writer)
+                        .init();
                 }
             } else {
                 // Fallback: just stdout
-                tracing_subscriber::fmt().with_env_filter(filter).init();
+                tracing_subscriber::fmt()
+                    .with_env_filter(filter)
+                    .with_writer(log_writer)
+                    .init();
             }
         } else {
             // Normal mode without diagnostics: just stdout
-            tracing_subscriber::fmt().with_env_filter(filter).init();
+            tracing_subscriber::fmt()
+                .with_env_filter(filter)
+                .with_writer(log_writer)
+                .init();
         }
     }
 
+    if let Some(reason) = tui_fallback {
+        warn!("TUI unavailable ({reason}); falling back to --ui pretty");
+    }
+
     // Parse all config sources from CLI
     let config_sources: Vec<ConfigSource> =
         cli.config.iter().map(|s| ConfigSource::parse(s)).collect();
 
     match cli.command {
         Some(Commands::Run(args)) => {
-            run_command(&config_sources, cli.verbose, cli.color, args).await
+            Box::pin(run_command(&config_sources, cli.verbose, cli.color, args)).await
         }
         Some(Commands::Resume(args)) => {
-            resume_command(&config_sources, cli.verbose, cli.color, args).await
+            Box::pin(resume_command(
+                &config_sources,
+                cli.verbose,
+                cli.color,
+                args,
+            ))
+            .await
         }
         Some(Commands::Events(args)) => events_command(cli.color, args),
+        Some(Commands::Logs(args)) => logs::execute(
+            &config_sources,
+            args,
+            cli.verbose,
+            cli.color.should_use_colors(),
+        ),
+        Some(Commands::Prompts(args)) => {
+            prompts::execute(&config_sources, args, cli.color.should_use_colors())
+        }
+        Some(Commands::Export(args)) => export::execute(&config_sources, args),
+        Some(Commands::ExportMetrics(args)) => export_metrics::execute(&config_sources, args),
+        Some(Commands::Decrypt(args)) => decrypt::execute(&config_sources, args),
+        Some(Commands::Attach(args)) => attach::execute(&config_sources, args, cli.verbose).await,
+        Some(Commands::Open(args)) => attach::open(&config_sources, args, cli.verbose).await,
+        Some(Commands::Control(args)) => control::execute(args, cli.color.should_use_colors()),
+        Some(Commands::Status(args)) => status::execute(args, cli.color.should_use_colors()),
         Some(Commands::Init(args)) => init_command(cli.color, args),
         Some(Commands::Clean(args)) => clean_command(&config_sources, cli.color, args),
+        Some(Commands::Doctor(args)) => doctor::execute(
+            &primary_config_path(&config_sources),
+            args,
+            cli.color.should_use_colors(),
+        ),
+        Some(Commands::Selftest(args)) => selftest::execute(args, cli.color.should_use_colors()),
         Some(Commands::Emit(args)) => emit_command(cli.color, args),
         Some(Commands::Plan(args)) => plan_command(&config_sources, cli.color, args),
         Some(Commands::CodeTask(args)) => code_task_command(&config_sources, cli.color, args),
@@ -792,6 +988,12 @@ async fn main() -> Result<()> {
         Some(Commands::Hats(args)) => {
             hats::execute(&config_sources, args, cli.color.should_use_colors())
         }
+        Some(Commands::Backends(args)) => {
+            backends::execute(&config_sources, args, cli.color.should_use_colors())
+        }
+        Some(Commands::Repos(args)) => {
+            repos::execute(&config_sources, args, cli.color.should_use_colors())
+        }
         Some(Commands::Web(args)) => web::execute(args).await,
         Some(Commands::Bot(args)) => {
             bot::execute(args, &config_sources, cli.color.should_use_colors()).await
@@ -808,7 +1010,10 @@ async fn main() -> Result<()> {
                 continue_mode: false,
                 no_tui: false, // TUI enabled by default
                 autonomous: false,
+                ui: None,
                 idle_timeout: None,
+                yes: false,
+                confirm_budget: false,
                 exclusive: false,
                 no_auto_merge: false,
                 chaos: false,
@@ -818,7 +1023,7 @@ async fn main() -> Result<()> {
                 record_session: None,
                 custom_args: Vec::new(),
             };
-            run_command(&config_sources, cli.verbose, cli.color, args).await
+            Box::pin(run_command(&config_sources, cli.verbose, cli.color, args)).await
         }
     }
 }
@@ -949,10 +1154,13 @@ async fn run_command(
     }
 
     // Apply execution mode overrides per spec
-    // TUI is enabled by default (unless --no-tui is specified)
+    // TUI is enabled by default (unless --no-tui or another --ui is given)
+    let ui = args
+        .ui
+        .or((!args.no_tui && !args.autonomous).then_some(UiMode::Tui));
     if args.autonomous {
         config.cli.default_mode = "autonomous".to_string();
-    } else if !args.no_tui {
+    } else if ui == Some(UiMode::Tui) {
         config.cli.default_mode = "interactive".to_string();
     }
 
@@ -960,6 +1168,9 @@ async fn run_command(
     if let Some(timeout) = args.idle_timeout {
         config.cli.idle_timeout_secs = timeout;
     }
+    if args.yes {
+        config.tui.confirm_start = false;
+    }
 
     // Apply backend override from CLI (takes precedence over config)
     if let Some(backend) = args.backend {
@@ -1037,6 +1248,8 @@ async fn run_command(
         return Ok(());
     }
 
+    startup::confirm_budget(&config, args.confirm_budget)?;
+
     // Ensure scratchpad directory exists (auto-create with depth limit)
     // This is done after dry-run check to avoid creating directories during dry-run
     ensure_scratchpad_directory(&config)?;
@@ -1199,8 +1412,6 @@ async fn run_command(
         .context("Failed to create loop directories")?;
 
     // Run the orchestration loop and exit with proper exit code
-    // TUI is enabled by default (unless --no-tui or --autonomous is specified)
-    let enable_tui = !args.no_tui && !args.autonomous;
     let verbosity = Verbosity::resolve(verbose || args.verbose, args.quiet);
     let custom_args = args.custom_args;
     // --no-auto-merge CLI flag overrides config.features.auto_merge
@@ -1214,7 +1425,7 @@ async fn run_command(
         config,
         color_mode,
         resume,
-        enable_tui,
+        ui,
         verbosity,
         args.record_session,
         Some(loop_context),
@@ -1301,10 +1512,13 @@ async fn resume_command(
     }
 
     // Apply execution mode overrides
-    // TUI is enabled by default (unless --no-tui is specified)
+    // TUI is enabled by default (unless --no-tui or another --ui is given)
+    let ui = args
+        .ui
+        .or((!args.no_tui && !args.autonomous).then_some(UiMode::Tui));
     if args.autonomous {
         config.cli.default_mode = "autonomous".to_string();
-    } else if !args.no_tui {
+    } else if ui == Some(UiMode::Tui) {
         config.cli.default_mode = "interactive".to_string();
     }
 
@@ -1312,6 +1526,9 @@ async fn resume_command(
     if let Some(timeout) = args.idle_timeout {
         config.cli.idle_timeout_secs = timeout;
     }
+    if args.yes {
+        config.tui.confirm_start = false;
+    }
 
     // Validate configuration
     let warnings = config
@@ -1340,17 +1557,17 @@ async fn resume_command(
         }
     }
 
+    startup::confirm_budget(&config, args.confirm_budget)?;
+
     // Run the orchestration loop in resume mode
     // The key difference: we publish task.resume instead of task.start,
     // signaling the planner to read the existing scratchpad
-    // TUI is enabled by default (unless --no-tui or --autonomous is specified)
-    let enable_tui = !args.no_tui && !args.autonomous;
     let verbosity = Verbosity::resolve(verbose || args.verbose, args.quiet);
     let reason = loop_runner::run_loop_impl(
         config,
         color_mode,
         true,
-        enable_tui,
+        ui,
         verbosity,
         args.record_session,
         None,       // Deprecated resume command doesn't have loop_context
@@ -1446,7 +1663,10 @@ fn init_command(color_mode: ColorMode, args: InitArgs) -> Result<()> {
     println!("  ralph init --backend <backend>   Generate minimal config for backend");
     println!("  ralph init --preset <preset>     Use an embedded preset");
     println!("  ralph init --list-presets        Show available presets\n");
-    println!("Backends: claude, kiro, gemini, codex, amp, custom");
+    println!(
+        "Backends: {}, custom",
+        AdapterRegistry::builtin().names().join(", ")
+    );
     println!("\nRun 'ralph init --list-presets' to see available presets.");
 
     Ok(())
@@ -1641,6 +1861,17 @@ fn emit_command(color_mode: ColorMode, args: EmitArgs) -> Result<()> {
         args.payload
     };
 
+    // Guidance and answers steer the run, so they are audited like other
+    // control actions
+    let audit = match args.topic.as_str() {
+        "human.guidance" => Some(AuditAction::Guidance),
+        "human.response" => Some(AuditAction::Response),
+        _ => None,
+    }
+    .map(|action| {
+        AuditEntry::new(action, AuditSource::Cli).with_detail(truncate_with_ellipsis(&payload, 200))
+    });
+
     // Build the event record
     // We use serde_json directly to ensure proper escaping
     let record = serde_json::json!({
@@ -1681,6 +1912,12 @@ fn emit_command(color_mode: ColorMode, args: EmitArgs) -> Result<()> {
     let json_line = serde_json::to_string(&record)?;
     writeln!(file, "{}", json_line)?;
 
+    if let Some(entry) = audit {
+        LoopContext::primary(std::env::current_dir()?)
+            .audit_log()
+            .record_or_warn(entry);
+    }
+
     // Success message
     if use_colors {
         println!(
@@ -1742,8 +1979,9 @@ fn plan_command(
     sop_runner::run_sop(config).map_err(|e| match e {
         SopRunError::NoBackend(no_backend) => anyhow::Error::new(no_backend),
         SopRunError::UnknownBackend(name) => anyhow::anyhow!(
-            "Unknown backend: {}\n\nValid backends: claude, kiro, gemini, codex, amp",
-            name
+            "Unknown backend: {}\n\nValid backends: {}, custom",
+            name,
+            AdapterRegistry::builtin().names().join(", ")
         ),
         SopRunError::SpawnError(io_err) => anyhow::anyhow!("Failed to spawn backend: {}", io_err),
     })
@@ -1795,8 +2033,9 @@ fn code_task_command(
     sop_runner::run_sop(config).map_err(|e| match e {
         SopRunError::NoBackend(no_backend) => anyhow::Error::new(no_backend),
         SopRunError::UnknownBackend(name) => anyhow::anyhow!(
-            "Unknown backend: {}\n\nValid backends: claude, kiro, gemini, codex, amp",
-            name
+            "Unknown backend: {}\n\nValid backends: {}, custom",
+            name,
+            AdapterRegistry::builtin().names().join(", ")
         ),
         SopRunError::SpawnError(io_err) => anyhow::anyhow!("Failed to spawn backend: {}", io_err),
     })
@@ -1934,6 +2173,24 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_ui_flag_parses_and_settles() {
+        let cli = Cli::try_parse_from(["ralph", "run", "--ui", "json"]).expect("CLI parse failed");
+        let Some(Commands::Run(mut args)) = cli.command else {
+            panic!("Expected Run command");
+        };
+        assert_eq!(args.ui, Some(UiMode::Json));
+        assert_eq!(UiMode::settle(&mut args.ui, false, false).unwrap(), None);
+        assert_eq!(args.ui, Some(UiMode::Json));
+
+        // --no-tui leaves the stream style to the backend
+        let mut ui = None;
+        assert_eq!(UiMode::settle(&mut ui, true, false).unwrap(), None);
+        assert_eq!(ui, None);
+
+        assert!(Cli::try_parse_from(["ralph", "run", "--ui", "plain", "--no-tui"]).is_err());
+    }
+
     #[test]
     fn test_config_source_parse_file_with_equals() {
         // Paths containing '=' but not starting with 'core.' should be treated as files
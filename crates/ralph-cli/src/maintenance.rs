@@ -0,0 +1,179 @@
+//! Housekeeping commands run between iterations (`maintenance:` config).
+//!
+//! A batch starts as soon as an iteration ends, so re-indexing or formatting
+//! overlaps the cooldown and rate-limit waits instead of adding to them. The
+//! loop waits for the batch before the next agent starts.
+
+use ralph_core::{MaintenanceCommand, MaintenanceConfig};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+/// How one maintenance command ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MaintenanceOutcome {
+    Succeeded,
+    /// Exited unsuccessfully; carries the tail of its stderr.
+    Failed(String),
+    TimedOut,
+}
+
+/// Runs the configured commands in the background between iterations.
+pub(crate) struct Maintenance {
+    commands: Vec<MaintenanceCommand>,
+    timeout: Duration,
+    workspace: PathBuf,
+    running: JoinSet<(String, MaintenanceOutcome, Duration)>,
+}
+
+impl Maintenance {
+    /// Returns `None` when no commands are configured.
+    pub(crate) fn new(config: &MaintenanceConfig, workspace: &Path) -> Option<Self> {
+        if config.commands.is_empty() {
+            return None;
+        }
+        Some(Self {
+            commands: config.commands.clone(),
+            timeout: Duration::from_secs(config.timeout_seconds),
+            workspace: workspace.to_path_buf(),
+            running: JoinSet::new(),
+        })
+    }
+
+    /// Starts the commands due after `iteration`, all at once.
+    pub(crate) fn start(&mut self, iteration: u32) {
+        for command in &self.commands {
+            if !iteration.is_multiple_of(command.every.max(1)) {
+                continue;
+            }
+            let name = command.name.clone();
+            let shell = command.command.clone();
+            let workspace = self.workspace.clone();
+            let timeout = self.timeout;
+            self.running.spawn(async move {
+                let started = Instant::now();
+                let outcome = run(&shell, &workspace, timeout).await;
+                (name, outcome, started.elapsed())
+            });
+        }
+    }
+
+    /// Waits for the running batch and logs how each command went.
+    pub(crate) async fn finish(&mut self) -> Vec<(String, MaintenanceOutcome)> {
+        let mut outcomes = Vec::new();
+        while let Some(joined) = self.running.join_next().await {
+            let Ok((name, outcome, elapsed)) = joined else {
+                continue;
+            };
+            match &outcome {
+                MaintenanceOutcome::Succeeded => {
+                    info!(command = %name, elapsed_ms = elapsed.as_millis(), "Maintenance done");
+                }
+                MaintenanceOutcome::Failed(stderr) => {
+                    warn!("Maintenance command '{}' failed: {}", name, stderr);
+                }
+                MaintenanceOutcome::TimedOut => {
+                    warn!(
+                        "Maintenance command '{}' timed out after {}s",
+                        name,
+                        self.timeout.as_secs()
+                    );
+                }
+            }
+            outcomes.push((name, outcome));
+        }
+        outcomes
+    }
+}
+
+async fn run(shell: &str, workspace: &Path, timeout: Duration) -> MaintenanceOutcome {
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(shell)
+        .current_dir(workspace)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let child = match child {
+        Ok(child) => child,
+        Err(e) => return MaintenanceOutcome::Failed(e.to_string()),
+    };
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Err(_) => MaintenanceOutcome::TimedOut,
+        Ok(Err(e)) => MaintenanceOutcome::Failed(e.to_string()),
+        Ok(Ok(output)) if output.status.success() => MaintenanceOutcome::Succeeded,
+        Ok(Ok(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let tail: Vec<&str> = stderr.trim_end().lines().rev().take(3).collect();
+            let tail: Vec<&str> = tail.into_iter().rev().collect();
+            MaintenanceOutcome::Failed(format!("{} {}", output.status, tail.join(" | ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn command(name: &str, shell: &str, every: u32) -> MaintenanceCommand {
+        MaintenanceCommand {
+            name: name.to_string(),
+            command: shell.to_string(),
+            every,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_due_commands_run_concurrently() {
+        let temp = TempDir::new().unwrap();
+        let config = MaintenanceConfig {
+            commands: vec![
+                command("a", "sleep 0.5 && touch a", 1),
+                command("b", "sleep 0.5 && touch b", 1),
+                command("c", "touch c", 2),
+            ],
+            timeout_seconds: 10,
+        };
+        let mut maintenance = Maintenance::new(&config, temp.path()).unwrap();
+
+        let started = Instant::now();
+        maintenance.start(3);
+        let outcomes = maintenance.finish().await;
+        assert_eq!(outcomes.len(), 2);
+        // Run one after the other, they would take a second
+        assert!(started.elapsed() < Duration::from_millis(900));
+        assert!(temp.path().join("a").exists() && temp.path().join("b").exists());
+        assert!(
+            !temp.path().join("c").exists(),
+            "c only runs every 2nd iteration"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failures_and_timeouts_are_reported() {
+        let temp = TempDir::new().unwrap();
+        let config = MaintenanceConfig {
+            commands: vec![
+                command("fail", "echo broken >&2; exit 3", 1),
+                command("slow", "sleep 5", 1),
+            ],
+            timeout_seconds: 1,
+        };
+        let mut maintenance = Maintenance::new(&config, temp.path()).unwrap();
+        maintenance.start(1);
+        let mut outcomes = maintenance.finish().await;
+        outcomes.sort_by(|a, b| a.0.cmp(&b.0));
+
+        match &outcomes[0].1 {
+            MaintenanceOutcome::Failed(message) => assert!(message.contains("broken")),
+            other => panic!("expected failure, got {other:?}"),
+        }
+        assert_eq!(outcomes[1].1, MaintenanceOutcome::TimedOut);
+    }
+}
@@ -0,0 +1,850 @@
+//! Notifications about finished runs and output alerts.
+//!
+//! Every channel (terminal bell, desktop, webhook, Slack, email) implements
+//! [`Notifier`]. [`NotifierRegistry`] builds the channels enabled under
+//! `notifications` in the config and fans each [`Notification`] out to the
+//! ones subscribed to its event, dropping repeats and enforcing a per-channel
+//! hourly cap.
+//!
+//! Templates use `{{name}}` placeholders filled from the run's outcome; see
+//! [`RunOutcome::vars`] for the available names.
+
+use crate::display::format_elapsed;
+use crate::email::EmailNotifier;
+use anyhow::{Context, Result, bail};
+use ralph_core::{
+    AlertHit, NotificationEvent, NotificationsConfig, TerminationReason, WebhookNotifierConfig,
+};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// How long dropping the registry waits for deliveries still in flight.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(15);
+
+/// Window for the `max_per_hour` cap.
+const RATE_WINDOW: Duration = Duration::from_secs(3600);
+
+/// Timeout for webhook requests.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wait before the first webhook retry; doubled for each further one.
+const WEBHOOK_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Header carrying `sha256=<hex HMAC of the body>` when a secret is set.
+const SIGNATURE_HEADER: &str = "X-Ralph-Signature-256";
+
+/// Header identifying a delivery; retries of one notification share it.
+const DELIVERY_HEADER: &str = "X-Ralph-Delivery";
+
+/// How a run ended, as reported in notifications.
+#[derive(Debug)]
+pub(crate) struct RunOutcome<'a> {
+    pub reason: &'a TerminationReason,
+    pub iterations: u32,
+    pub elapsed: Duration,
+    pub cost_usd: f64,
+    pub run_id: Option<String>,
+    /// Contents of the loop summary file.
+    pub summary: String,
+}
+
+impl RunOutcome<'_> {
+    /// Values available to notification templates.
+    fn vars(&self) -> HashMap<&'static str, String> {
+        HashMap::from([
+            ("status", self.reason.as_str().to_string()),
+            ("iterations", self.iterations.to_string()),
+            ("duration", format_elapsed(self.elapsed)),
+            ("cost", format!("${:.2}", self.cost_usd)),
+            (
+                "run_id",
+                self.run_id.clone().unwrap_or_else(|| "unknown".to_string()),
+            ),
+            ("summary", self.summary.clone()),
+        ])
+    }
+}
+
+/// Replaces `{{name}}` placeholders with values from `vars`. Unknown
+/// placeholders are left as they are.
+pub(crate) fn render(template: &str, vars: &HashMap<&str, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + len].trim();
+        out.push_str(&rest[..start]);
+        match vars.get(name) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// One message for the configured channels.
+#[derive(Debug, Clone)]
+pub(crate) struct Notification {
+    pub event: NotificationEvent,
+    /// Identifies repeats of the same notification.
+    pub key: String,
+    pub title: String,
+    pub message: String,
+    /// Values for channel templates.
+    pub vars: HashMap<&'static str, String>,
+    /// True for failed runs and alerts.
+    pub failure: bool,
+}
+
+impl Notification {
+    /// Reports a finished run.
+    pub(crate) fn run_finished(outcome: &RunOutcome<'_>) -> Self {
+        let vars = outcome.vars();
+        Self {
+            event: NotificationEvent::RunFinished,
+            key: format!("run_finished:{}", vars["run_id"]),
+            title: render("Ralph loop {{status}}", &vars),
+            message: render(
+                "Run {{run_id}} finished: {{status}} ({{iterations}} iterations, {{duration}}, {{cost}}).",
+                &vars,
+            ),
+            vars,
+            failure: !outcome.reason.is_success(),
+        }
+    }
+
+    /// Sends the post-mortem of a run that ended abnormally.
+    pub(crate) fn post_mortem(outcome: &RunOutcome<'_>, post_mortem: String) -> Self {
+        let mut vars = outcome.vars();
+        vars.insert("post_mortem", post_mortem.clone());
+        Self {
+            event: NotificationEvent::PostMortem,
+            key: format!("post_mortem:{}", vars["run_id"]),
+            title: render("Ralph post-mortem: run {{run_id}} {{status}}", &vars),
+            message: post_mortem,
+            vars,
+            failure: true,
+        }
+    }
+
+    /// Reports an output alert.
+    pub(crate) fn alert(hit: &AlertHit) -> Self {
+        Self {
+            event: NotificationEvent::Alert,
+            key: format!("alert:{}", hit.name),
+            title: format!("Ralph alert: {}", hit.name),
+            message: hit.matched.clone(),
+            vars: HashMap::from([
+                ("alert", hit.name.clone()),
+                ("matched", hit.matched.clone()),
+            ]),
+            failure: true,
+        }
+    }
+}
+
+/// A notification channel.
+///
+/// `send` may block on the network; the registry calls it on a thread of its
+/// own, so it never holds up the loop.
+pub(crate) trait Notifier: Send + Sync {
+    /// Channel name, used in logs and for rate limiting.
+    fn name(&self) -> &'static str;
+
+    /// Returns true if the channel wants this notification.
+    fn accepts(&self, notification: &Notification) -> bool;
+
+    /// Delivers the notification.
+    fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+/// Rings the terminal bell.
+struct BellNotifier;
+
+impl Notifier for BellNotifier {
+    fn name(&self) -> &'static str {
+        "bell"
+    }
+
+    fn accepts(&self, notification: &Notification) -> bool {
+        notification.event == NotificationEvent::Alert
+    }
+
+    fn send(&self, _notification: &Notification) -> Result<()> {
+        eprint!("\x07");
+        Ok(())
+    }
+}
+
+/// Shows a desktop notification through the platform's command-line tool.
+struct DesktopNotifier {
+    events: Vec<NotificationEvent>,
+}
+
+impl Notifier for DesktopNotifier {
+    fn name(&self) -> &'static str {
+        "desktop"
+    }
+
+    fn accepts(&self, notification: &Notification) -> bool {
+        self.events.contains(&notification.event)
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        let mut command = if cfg!(target_os = "macos") {
+            let mut command = Command::new("osascript");
+            command.arg("-e").arg(format!(
+                "display notification {} with title {}",
+                applescript_string(&notification.message),
+                applescript_string(&notification.title)
+            ));
+            command
+        } else {
+            let mut command = Command::new("notify-send");
+            command
+                .arg("--app-name=ralph")
+                .arg(&notification.title)
+                .arg(&notification.message);
+            command
+        };
+        let program = command.get_program().to_string_lossy().into_owned();
+        let status = command
+            .status()
+            .with_context(|| format!("Failed to run {program}"))?;
+        if !status.success() {
+            bail!("{program} exited with {status}");
+        }
+        Ok(())
+    }
+}
+
+/// Quotes a string for AppleScript.
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Payload shape for a webhook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WebhookFormat {
+    /// `{"event", "title", "message", "vars"}`.
+    Json,
+    /// Slack incoming webhook `{"text"}`.
+    Slack,
+}
+
+/// POSTs notifications to a URL, signing them when a secret is configured
+/// and retrying failed deliveries.
+struct WebhookNotifier {
+    format: WebhookFormat,
+    url: String,
+    events: Vec<NotificationEvent>,
+    secret: Option<String>,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl WebhookNotifier {
+    fn from_config(config: &WebhookNotifierConfig, format: WebhookFormat) -> Result<Self> {
+        let Some(url) = config.resolve_url() else {
+            bail!("no URL; set `url_env` to a defined variable or `url`");
+        };
+        Ok(Self {
+            format,
+            url,
+            events: config.events.clone(),
+            secret: config.resolve_secret(),
+            retries: config.retries,
+            backoff: WEBHOOK_BACKOFF,
+        })
+    }
+
+    fn payload(&self, notification: &Notification) -> serde_json::Value {
+        match self.format {
+            WebhookFormat::Json => serde_json::json!({
+                "event": notification.event,
+                "title": notification.title,
+                "message": notification.message,
+                "vars": notification.vars,
+            }),
+            WebhookFormat::Slack => serde_json::json!({
+                "text": format!("*{}*\n{}", notification.title, notification.message),
+            }),
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        match self.format {
+            WebhookFormat::Json => "webhook",
+            WebhookFormat::Slack => "slack",
+        }
+    }
+
+    fn accepts(&self, notification: &Notification) -> bool {
+        self.events.contains(&notification.event)
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        let body = serde_json::to_vec(&self.payload(notification))?;
+        let signature = self.secret.as_deref().map(|secret| sign(secret, &body));
+        let delivery = delivery_id();
+        let client = reqwest::blocking::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()?;
+
+        let mut attempt = 0;
+        loop {
+            let mut request = client
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(DELIVERY_HEADER, &delivery)
+                .body(body.clone());
+            if let Some(signature) = &signature {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+            let error = match request.send() {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    let status = response.status();
+                    // Other client errors won't go away by sending again
+                    if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+                    {
+                        bail!("server replied {status}");
+                    }
+                    anyhow::anyhow!("server replied {status}")
+                }
+                // The URL often embeds a secret, so keep it out of the error
+                Err(e) => anyhow::anyhow!("request failed: {}", e.without_url()),
+            };
+            if attempt >= self.retries {
+                return Err(error.context(format!("gave up after {} attempts", attempt + 1)));
+            }
+            let wait = self.backoff.saturating_mul(1 << attempt.min(16));
+            debug!(
+                channel = self.name(),
+                "Webhook delivery failed ({error}); retrying in {wait:?}"
+            );
+            std::thread::sleep(wait);
+            attempt += 1;
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC-SHA256 signature of `body` under `secret`.
+fn sign(secret: &str, body: &[u8]) -> String {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = ring::hmac::sign(&key, body);
+    tag.as_ref()
+        .iter()
+        .fold(String::from("sha256="), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// A unique ID for one delivery, so receivers can drop retried duplicates.
+fn delivery_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("{nanos:x}-{:x}", std::process::id())
+}
+
+/// Delivery history used for deduplication and rate limiting.
+#[derive(Debug, Default)]
+struct Limits {
+    /// Last delivery per channel and notification key.
+    last_sent: HashMap<(&'static str, String), Instant>,
+    /// Deliveries in the current rate window, per channel.
+    recent: HashMap<&'static str, VecDeque<Instant>>,
+    /// Notifications dropped since the last delivery, per channel and key.
+    suppressed: HashMap<(&'static str, String), u32>,
+}
+
+impl Limits {
+    /// Decides whether a channel may deliver a notification now. On yes,
+    /// returns how many repeats were dropped since its last delivery.
+    fn admit(
+        &mut self,
+        channel: &'static str,
+        key: &str,
+        now: Instant,
+        dedup_window: Duration,
+        max_per_hour: u32,
+    ) -> Option<u32> {
+        let id = (channel, key.to_string());
+        let repeated = self
+            .last_sent
+            .get(&id)
+            .is_some_and(|last| now.duration_since(*last) < dedup_window);
+
+        let recent = self.recent.entry(channel).or_default();
+        while recent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= RATE_WINDOW)
+        {
+            recent.pop_front();
+        }
+        let capped = max_per_hour > 0 && recent.len() >= max_per_hour as usize;
+
+        if repeated || capped {
+            *self.suppressed.entry(id).or_default() += 1;
+            return None;
+        }
+        recent.push_back(now);
+        self.last_sent.insert(id.clone(), now);
+        Some(self.suppressed.remove(&id).unwrap_or(0))
+    }
+}
+
+/// The configured notification channels.
+pub(crate) struct NotifierRegistry {
+    notifiers: Vec<Arc<dyn Notifier>>,
+    dedup_window: Duration,
+    max_per_hour: u32,
+    limits: Mutex<Limits>,
+    /// Deliveries that may still be running.
+    pending: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl NotifierRegistry {
+    /// Creates a registry delivering to `notifiers` with the limits from `config`.
+    pub(crate) fn new(notifiers: Vec<Box<dyn Notifier>>, config: &NotificationsConfig) -> Self {
+        Self {
+            notifiers: notifiers.into_iter().map(Arc::from).collect(),
+            dedup_window: Duration::from_secs(config.dedup_window_secs),
+            max_per_hour: config.max_per_hour,
+            limits: Mutex::default(),
+            pending: Mutex::default(),
+        }
+    }
+
+    /// Builds the channels enabled in `config`.
+    ///
+    /// A misconfigured channel is skipped with a warning rather than failing
+    /// the loop.
+    pub(crate) fn from_config(config: &NotificationsConfig) -> Self {
+        let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+        if config.bell {
+            notifiers.push(Box::new(BellNotifier));
+        }
+        if let Some(desktop) = &config.desktop {
+            notifiers.push(Box::new(DesktopNotifier {
+                events: desktop.events.clone(),
+            }));
+        }
+        let webhooks = [
+            (&config.webhook, WebhookFormat::Json, "webhook"),
+            (&config.slack, WebhookFormat::Slack, "slack"),
+        ];
+        for (webhook, format, key) in webhooks {
+            let Some(webhook) = webhook else { continue };
+            match WebhookNotifier::from_config(webhook, format) {
+                Ok(notifier) => notifiers.push(Box::new(notifier)),
+                Err(e) => warn!("Ignoring notifications.{}: {:#}", key, e),
+            }
+        }
+        if let Some(email) = &config.email {
+            match EmailNotifier::from_config(email) {
+                Ok(notifier) => notifiers.push(Box::new(notifier)),
+                Err(e) => warn!("Ignoring notifications.email: {:#}", e),
+            }
+        }
+        Self::new(notifiers, config)
+    }
+
+    /// Delivers a notification to every channel that wants it.
+    ///
+    /// Returns right away; each channel sends from a thread of its own.
+    /// Failures are logged, never returned: a broken mail server must not
+    /// change how the loop runs or exits.
+    pub(crate) fn notify(&self, notification: &Notification) {
+        self.notify_at(notification, Instant::now());
+    }
+
+    fn notify_at(&self, notification: &Notification, now: Instant) {
+        let deliveries: Vec<(Arc<dyn Notifier>, Notification)> = {
+            let mut limits = self.limits.lock().unwrap_or_else(|e| e.into_inner());
+            self.notifiers
+                .iter()
+                .filter(|notifier| notifier.accepts(notification))
+                .filter_map(|notifier| {
+                    let admitted = limits.admit(
+                        notifier.name(),
+                        &notification.key,
+                        now,
+                        self.dedup_window,
+                        self.max_per_hour,
+                    );
+                    let Some(suppressed) = admitted else {
+                        debug!(
+                            channel = notifier.name(),
+                            key = %notification.key,
+                            "Notification suppressed"
+                        );
+                        return None;
+                    };
+                    let mut notification = notification.clone();
+                    if suppressed > 0 {
+                        notification.message.push_str(&format!(
+                            "\n\n({suppressed} similar notifications were suppressed)"
+                        ));
+                    }
+                    Some((Arc::clone(notifier), notification))
+                })
+                .collect()
+        };
+
+        // Channels block on the network (a webhook may retry for most of a
+        // minute): send from detached threads, so neither the loop nor the
+        // other channels wait on a slow one
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.retain(|handle| !handle.is_finished());
+        for (notifier, notification) in deliveries {
+            pending.push(std::thread::spawn(move || {
+                match notifier.send(&notification) {
+                    Ok(()) => info!(
+                        "Sent {} notification: {}",
+                        notifier.name(),
+                        notification.title
+                    ),
+                    Err(e) => warn!("Failed to send {} notification: {:#}", notifier.name(), e),
+                }
+            }));
+        }
+    }
+
+    /// Waits up to `timeout` for deliveries still in flight.
+    pub(crate) fn wait(&self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.retain(|handle| !handle.is_finished());
+            if pending.is_empty() {
+                return;
+            }
+            if Instant::now() >= deadline {
+                warn!(
+                    "Giving up on {} notification(s) still being sent",
+                    pending.len()
+                );
+                return;
+            }
+            drop(pending);
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+}
+
+impl Drop for NotifierRegistry {
+    /// Gives the run-end notifications a bounded chance to go out before the
+    /// process exits.
+    fn drop(&mut self) {
+        self.wait(SHUTDOWN_GRACE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_fills_known_placeholders() {
+        let vars = HashMap::from([("status", "completed".to_string())]);
+        assert_eq!(
+            render("Loop {{status}} / {{ status }} / {{other}} / {{", &vars),
+            "Loop completed / completed / {{other}} / {{"
+        );
+    }
+
+    #[test]
+    fn test_outcome_vars() {
+        let outcome = RunOutcome {
+            reason: &TerminationReason::MaxCost,
+            iterations: 7,
+            elapsed: Duration::from_secs(125),
+            cost_usd: 3.5,
+            run_id: None,
+            summary: "# Loop Summary".to_string(),
+        };
+        let rendered = render(
+            "{{status}} {{iterations}} {{duration}} {{cost}} {{run_id}}\n{{summary}}",
+            &outcome.vars(),
+        );
+        assert_eq!(rendered, "max_cost 7 2m 5s $3.50 unknown\n# Loop Summary");
+
+        let notification = Notification::run_finished(&outcome);
+        assert_eq!(notification.title, "Ralph loop max_cost");
+        assert!(notification.failure);
+
+        let notification = Notification::post_mortem(&outcome, "# Post-Mortem".to_string());
+        assert_eq!(notification.event, NotificationEvent::PostMortem);
+        assert_eq!(
+            notification.title,
+            "Ralph post-mortem: run unknown max_cost"
+        );
+        assert_eq!(notification.message, "# Post-Mortem");
+        assert_eq!(notification.vars["post_mortem"], "# Post-Mortem");
+    }
+
+    /// Records the messages it is asked to send.
+    struct Recorder {
+        events: Vec<NotificationEvent>,
+        sent: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Notifier for Recorder {
+        fn name(&self) -> &'static str {
+            "recorder"
+        }
+
+        fn accepts(&self, notification: &Notification) -> bool {
+            self.events.contains(&notification.event)
+        }
+
+        fn send(&self, notification: &Notification) -> Result<()> {
+            self.sent.lock().unwrap().push(notification.message.clone());
+            Ok(())
+        }
+    }
+
+    fn registry(
+        events: Vec<NotificationEvent>,
+        dedup_window_secs: u64,
+        max_per_hour: u32,
+    ) -> (NotifierRegistry, Arc<Mutex<Vec<String>>>) {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let config = NotificationsConfig {
+            dedup_window_secs,
+            max_per_hour,
+            ..NotificationsConfig::default()
+        };
+        let recorder = Recorder {
+            events,
+            sent: Arc::clone(&sent),
+        };
+        (
+            NotifierRegistry::new(vec![Box::new(recorder)], &config),
+            sent,
+        )
+    }
+
+    fn alert(name: &str) -> Notification {
+        Notification::alert(&AlertHit {
+            name: name.to_string(),
+            matched: format!("{name} matched"),
+            actions: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_repeats_are_dropped_and_counted() {
+        let (registry, sent) = registry(vec![NotificationEvent::Alert], 60, 0);
+        let start = Instant::now();
+
+        let notify_at = |notification: &Notification, at: Instant| {
+            registry.notify_at(notification, at);
+            registry.wait(Duration::from_secs(5));
+        };
+        for secs in 0..5 {
+            notify_at(&alert("flaky"), start + Duration::from_secs(secs));
+        }
+        notify_at(&alert("other"), start);
+        notify_at(&alert("flaky"), start + Duration::from_secs(61));
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(
+            *sent,
+            vec![
+                "flaky matched".to_string(),
+                "other matched".to_string(),
+                "flaky matched\n\n(4 similar notifications were suppressed)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hourly_cap_per_channel() {
+        let (registry, sent) = registry(vec![NotificationEvent::Alert], 0, 2);
+        let start = Instant::now();
+
+        for name in ["a", "b", "c"] {
+            registry.notify_at(&alert(name), start);
+        }
+        registry.wait(Duration::from_secs(5));
+        assert_eq!(sent.lock().unwrap().len(), 2);
+
+        registry.notify_at(&alert("d"), start + RATE_WINDOW);
+        registry.wait(Duration::from_secs(5));
+        assert_eq!(sent.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_channels_only_get_subscribed_events() {
+        let (registry, sent) = registry(vec![NotificationEvent::RunFinished], 0, 0);
+        registry.notify(&alert("ignored"));
+        registry.wait(Duration::from_secs(5));
+        assert!(sent.lock().unwrap().is_empty());
+    }
+
+    /// Takes a second to send.
+    struct Slow;
+
+    impl Notifier for Slow {
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+
+        fn accepts(&self, _notification: &Notification) -> bool {
+            true
+        }
+
+        fn send(&self, _notification: &Notification) -> Result<()> {
+            std::thread::sleep(Duration::from_secs(1));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_notify_does_not_wait_for_delivery() {
+        let registry = NotifierRegistry::new(vec![Box::new(Slow)], &NotificationsConfig::default());
+        let started = Instant::now();
+        registry.notify(&alert("build"));
+        assert!(started.elapsed() < Duration::from_millis(500));
+
+        registry.wait(Duration::from_millis(10));
+        assert_eq!(registry.pending.lock().unwrap().len(), 1);
+        registry.wait(Duration::from_secs(5));
+        assert!(registry.pending.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_config_skips_broken_channels() {
+        let config: NotificationsConfig = serde_yaml::from_str(
+            "bell: false\nslack:\n  url_env: RALPH_TEST_SLACK_URL_THAT_IS_NOT_SET\nwebhook:\n  url: http://127.0.0.1:9/hook\n  events: [alert]\n",
+        )
+        .unwrap();
+        let registry = NotifierRegistry::from_config(&config);
+        let names: Vec<_> = registry.notifiers.iter().map(|n| n.name()).collect();
+        assert_eq!(names, vec!["webhook"]);
+    }
+
+    #[test]
+    fn test_sign_matches_rfc_4231_vector() {
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    /// Serves one HTTP response per entry of `statuses`, returning each
+    /// request's head and body.
+    fn serve(statuses: &'static [u16]) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for status in statuses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                request.push_str(&String::from_utf8(body).unwrap());
+                requests.push(request);
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {status} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    fn webhook(url: String, retries: u32) -> WebhookNotifier {
+        WebhookNotifier {
+            format: WebhookFormat::Json,
+            url,
+            events: Vec::new(),
+            secret: Some("s3cret".to_string()),
+            retries,
+            backoff: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn test_webhook_is_signed_and_retried() {
+        let (url, server) = serve(&[503, 200]);
+        webhook(url, 3).send(&alert("build")).unwrap();
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        let body = requests[1].split("\r\n\r\n").nth(1).unwrap();
+        let signature = sign("s3cret", body.as_bytes()).to_lowercase();
+        let delivery = |request: &str| {
+            request
+                .lines()
+                .find(|l| l.to_lowercase().starts_with("x-ralph-delivery:"))
+                .map(str::to_string)
+        };
+        for request in &requests {
+            assert!(
+                request
+                    .to_lowercase()
+                    .contains(&format!("x-ralph-signature-256: {signature}"))
+            );
+        }
+        assert!(delivery(&requests[0]).is_some());
+        assert_eq!(delivery(&requests[0]), delivery(&requests[1]));
+    }
+
+    #[test]
+    fn test_retrying_webhook_does_not_hold_up_notify() {
+        // Nothing listens on the discard port, so every attempt fails
+        let mut notifier = webhook("http://127.0.0.1:9/hook".to_string(), 3);
+        notifier.events = vec![NotificationEvent::Alert];
+        notifier.backoff = Duration::from_millis(200);
+        let registry =
+            NotifierRegistry::new(vec![Box::new(notifier)], &NotificationsConfig::default());
+
+        let started = Instant::now();
+        registry.notify(&alert("build"));
+        assert!(started.elapsed() < Duration::from_millis(200));
+        registry.wait(Duration::from_secs(10));
+        assert!(started.elapsed() >= Duration::from_millis(1400));
+    }
+
+    #[test]
+    fn test_webhook_gives_up_on_client_errors() {
+        let (url, server) = serve(&[400]);
+        let error = webhook(url, 3).send(&alert("build")).unwrap_err();
+        assert_eq!(error.to_string(), "server replied 400 Bad Request");
+        assert_eq!(server.join().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,343 @@
+//! A minimal Parquet writer for flat tables.
+//!
+//! Writes every column as required (no nulls), PLAIN-encoded and
+//! uncompressed, in a single row group: enough for pandas, DuckDB and Polars
+//! to read the exported metrics without pulling in the Arrow stack.
+//!
+//! The footer and page headers are Thrift structs in the compact protocol,
+//! written by [`Thrift`] for the handful of fields the format requires.
+
+/// Physical types (`Type` in parquet.thrift).
+const TYPE_INT32: i32 = 1;
+const TYPE_INT64: i32 = 2;
+const TYPE_DOUBLE: i32 = 5;
+const TYPE_BYTE_ARRAY: i32 = 6;
+
+/// Converted types (`ConvertedType` in parquet.thrift).
+const CONVERTED_UTF8: i32 = 0;
+const CONVERTED_TIMESTAMP_MILLIS: i32 = 9;
+
+const REPETITION_REQUIRED: i32 = 0;
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const CODEC_UNCOMPRESSED: i32 = 0;
+const PAGE_DATA: i32 = 0;
+
+const MAGIC: &[u8] = b"PAR1";
+
+/// The values of one column.
+#[derive(Debug)]
+pub(crate) enum Column {
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    Double(Vec<f64>),
+    Utf8(Vec<String>),
+    /// Milliseconds since the Unix epoch, UTC.
+    TimestampMillis(Vec<i64>),
+}
+
+impl Column {
+    fn len(&self) -> usize {
+        match self {
+            Column::Int32(values) => values.len(),
+            Column::Int64(values) | Column::TimestampMillis(values) => values.len(),
+            Column::Double(values) => values.len(),
+            Column::Utf8(values) => values.len(),
+        }
+    }
+
+    fn physical_type(&self) -> i32 {
+        match self {
+            Column::Int32(_) => TYPE_INT32,
+            Column::Int64(_) | Column::TimestampMillis(_) => TYPE_INT64,
+            Column::Double(_) => TYPE_DOUBLE,
+            Column::Utf8(_) => TYPE_BYTE_ARRAY,
+        }
+    }
+
+    fn converted_type(&self) -> Option<i32> {
+        match self {
+            Column::Utf8(_) => Some(CONVERTED_UTF8),
+            Column::TimestampMillis(_) => Some(CONVERTED_TIMESTAMP_MILLIS),
+            _ => None,
+        }
+    }
+
+    /// The values, PLAIN-encoded.
+    fn plain(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            Column::Int32(values) => values
+                .iter()
+                .for_each(|v| out.extend_from_slice(&v.to_le_bytes())),
+            Column::Int64(values) | Column::TimestampMillis(values) => values
+                .iter()
+                .for_each(|v| out.extend_from_slice(&v.to_le_bytes())),
+            Column::Double(values) => values
+                .iter()
+                .for_each(|v| out.extend_from_slice(&v.to_le_bytes())),
+            Column::Utf8(values) => {
+                for value in values {
+                    out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+                    out.extend_from_slice(value.as_bytes());
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Where a column chunk landed in the file.
+struct ChunkInfo {
+    offset: i64,
+    size: i64,
+}
+
+/// Encodes `columns` (name and values, all the same length) as a Parquet file.
+pub(crate) fn write(columns: &[(&str, Column)]) -> Vec<u8> {
+    let rows = columns.first().map_or(0, |(_, column)| column.len());
+    debug_assert!(columns.iter().all(|(_, column)| column.len() == rows));
+
+    let mut file = MAGIC.to_vec();
+    let mut chunks = Vec::new();
+    if rows > 0 {
+        for (_, column) in columns {
+            let data = column.plain();
+            let mut header = Thrift::default();
+            header.i32(1, PAGE_DATA);
+            header.i32(2, data.len() as i32);
+            header.i32(3, data.len() as i32);
+            header.begin_struct(5);
+            header.i32(1, rows as i32);
+            header.i32(2, ENCODING_PLAIN);
+            header.i32(3, ENCODING_RLE);
+            header.i32(4, ENCODING_RLE);
+            header.end_struct();
+            let header = header.finish();
+
+            chunks.push(ChunkInfo {
+                offset: file.len() as i64,
+                size: (header.len() + data.len()) as i64,
+            });
+            file.extend_from_slice(&header);
+            file.extend_from_slice(&data);
+        }
+    }
+
+    let footer = file_metadata(columns, rows, &chunks);
+    file.extend_from_slice(&footer);
+    file.extend_from_slice(&(footer.len() as u32).to_le_bytes());
+    file.extend_from_slice(MAGIC);
+    file
+}
+
+/// The `FileMetaData` footer.
+fn file_metadata(columns: &[(&str, Column)], rows: usize, chunks: &[ChunkInfo]) -> Vec<u8> {
+    let mut meta = Thrift::default();
+    meta.i32(1, 1);
+
+    meta.list(2, THRIFT_STRUCT, columns.len() + 1);
+    meta.begin_element();
+    meta.binary(4, b"schema");
+    meta.i32(5, columns.len() as i32);
+    meta.end_struct();
+    for (name, column) in columns {
+        meta.begin_element();
+        meta.i32(1, column.physical_type());
+        meta.i32(3, REPETITION_REQUIRED);
+        meta.binary(4, name.as_bytes());
+        if let Some(converted) = column.converted_type() {
+            meta.i32(6, converted);
+        }
+        meta.end_struct();
+    }
+
+    meta.i64(3, rows as i64);
+
+    let row_groups = usize::from(!chunks.is_empty());
+    meta.list(4, THRIFT_STRUCT, row_groups);
+    if row_groups > 0 {
+        meta.begin_element();
+        meta.list(1, THRIFT_STRUCT, columns.len());
+        for ((name, column), chunk) in columns.iter().zip(chunks) {
+            meta.begin_element();
+            meta.i64(2, chunk.offset);
+            meta.begin_struct(3);
+            meta.i32(1, column.physical_type());
+            meta.list(2, THRIFT_I32, 2);
+            meta.list_i32(ENCODING_PLAIN);
+            meta.list_i32(ENCODING_RLE);
+            meta.list(3, THRIFT_BINARY, 1);
+            meta.list_binary(name.as_bytes());
+            meta.i32(4, CODEC_UNCOMPRESSED);
+            meta.i64(5, rows as i64);
+            meta.i64(6, chunk.size);
+            meta.i64(7, chunk.size);
+            meta.i64(9, chunk.offset);
+            meta.end_struct();
+            meta.end_struct();
+        }
+        meta.i64(2, chunks.iter().map(|chunk| chunk.size).sum());
+        meta.i64(3, rows as i64);
+        meta.end_struct();
+    }
+
+    meta.binary(6, format!("ralph {}", env!("CARGO_PKG_VERSION")).as_bytes());
+    meta.finish()
+}
+
+/// Compact protocol type IDs.
+const THRIFT_I32: u8 = 5;
+const THRIFT_I64: u8 = 6;
+const THRIFT_BINARY: u8 = 8;
+const THRIFT_LIST: u8 = 9;
+const THRIFT_STRUCT: u8 = 12;
+
+/// Writes one top-level struct in Thrift's compact protocol.
+#[derive(Default)]
+struct Thrift {
+    out: Vec<u8>,
+    /// ID of the last field written in each open struct, since field
+    /// headers hold the delta to it.
+    last_field: Vec<i16>,
+}
+
+impl Thrift {
+    fn field(&mut self, id: i16, kind: u8) {
+        if self.last_field.is_empty() {
+            self.last_field.push(0);
+        }
+        let last = self.last_field.last_mut().expect("open struct");
+        match id - *last {
+            delta @ 1..=15 => self.out.push(((delta as u8) << 4) | kind),
+            _ => {
+                self.out.push(kind);
+                let zigzag = ((id << 1) ^ (id >> 15)) as u16;
+                varint(&mut self.out, u64::from(zigzag));
+            }
+        }
+        *last = id;
+    }
+
+    fn i32(&mut self, id: i16, value: i32) {
+        self.field(id, THRIFT_I32);
+        varint(
+            &mut self.out,
+            u64::from(((value << 1) ^ (value >> 31)) as u32),
+        );
+    }
+
+    fn i64(&mut self, id: i16, value: i64) {
+        self.field(id, THRIFT_I64);
+        varint(&mut self.out, ((value << 1) ^ (value >> 63)) as u64);
+    }
+
+    fn binary(&mut self, id: i16, value: &[u8]) {
+        self.field(id, THRIFT_BINARY);
+        self.list_binary(value);
+    }
+
+    fn begin_struct(&mut self, id: i16) {
+        self.field(id, THRIFT_STRUCT);
+        self.last_field.push(0);
+    }
+
+    /// Starts a struct that is an element of a list.
+    fn begin_element(&mut self) {
+        self.last_field.push(0);
+    }
+
+    fn end_struct(&mut self) {
+        self.out.push(0);
+        self.last_field.pop();
+    }
+
+    fn list(&mut self, id: i16, element: u8, len: usize) {
+        self.field(id, THRIFT_LIST);
+        if len < 15 {
+            self.out.push(((len as u8) << 4) | element);
+        } else {
+            self.out.push(0xf0 | element);
+            varint(&mut self.out, len as u64);
+        }
+    }
+
+    fn list_i32(&mut self, value: i32) {
+        varint(
+            &mut self.out,
+            u64::from(((value << 1) ^ (value >> 31)) as u32),
+        );
+    }
+
+    fn list_binary(&mut self, value: &[u8]) {
+        varint(&mut self.out, value.len() as u64);
+        self.out.extend_from_slice(value);
+    }
+
+    /// Closes the top-level struct and returns its bytes.
+    fn finish(mut self) -> Vec<u8> {
+        self.out.push(0);
+        self.out
+    }
+}
+
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_protocol_encoding() {
+        let mut thrift = Thrift::default();
+        thrift.i32(1, -1);
+        thrift.i64(2, 300);
+        thrift.binary(20, b"ab");
+        thrift.begin_struct(21);
+        thrift.i32(1, 2);
+        thrift.end_struct();
+
+        assert_eq!(
+            thrift.finish(),
+            [
+                0x15, 0x01, // field 1, i32 -1
+                0x16, 0xd8, 0x04, // field 2, i64 300
+                0x08, 0x28, 0x02, b'a', b'b', // field 20 (long form), binary "ab"
+                0x1c, 0x15, 0x04, 0x00, // field 21, struct { field 1: 2 }
+                0x00,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_layout() {
+        let file = write(&[
+            ("iteration", Column::Int32(vec![1, 2])),
+            ("hat", Column::Utf8(vec!["builder".into(), "ralph".into()])),
+        ]);
+
+        assert!(file.starts_with(MAGIC) && file.ends_with(MAGIC));
+        let footer_len =
+            u32::from_le_bytes(file[file.len() - 8..file.len() - 4].try_into().unwrap()) as usize;
+        let footer = &file[file.len() - 8 - footer_len..file.len() - 8];
+        // The footer names the columns and the writer
+        let contains = |needle: &[u8]| footer.windows(needle.len()).any(|w| w == needle);
+        assert!(contains(b"iteration") && contains(b"hat") && contains(b"ralph "));
+        // PLAIN data follows each page header
+        let contains = |needle: &[u8]| file.windows(needle.len()).any(|w| w == needle);
+        assert!(contains(&[1, 0, 0, 0, 2, 0, 0, 0]));
+        assert!(contains(b"\x07\x00\x00\x00builder\x05\x00\x00\x00ralph"));
+    }
+
+    #[test]
+    fn test_empty_table_has_no_row_groups() {
+        let file = write(&[("iteration", Column::Int32(Vec::new()))]);
+        assert!(file.starts_with(MAGIC) && file.ends_with(MAGIC));
+    }
+}
@@ -0,0 +1,295 @@
+//! CLI commands for the `ralph prompts` namespace.
+//!
+//! Inspect the prompts archived with `archive_prompts: true`
+//! (`.ralph/prompts/<run-id>.jsonl`).
+//!
+//! Subcommands:
+//! - `list`: One line per iteration with the prompt's size and its change
+//! - `show`: Print the exact prompt of an iteration
+//! - `diff`: Compare two iterations' prompts, section sizes first
+
+use crate::ConfigSource;
+use crate::display::colors;
+use crate::logs::{local_cipher, resolve_run_id};
+use anyhow::{Result, bail};
+use clap::{Parser, Subcommand};
+use ralph_core::{LoopContext, PromptArchive, PromptRecord, estimate_tokens, prompt_sections};
+use similar::{ChangeTag, TextDiff};
+
+/// Inspect the prompts sent in each iteration.
+#[derive(Parser, Debug)]
+pub struct PromptsArgs {
+    /// Run ID or a unique prefix; defaults to the current run
+    #[arg(long, global = true)]
+    pub run: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<PromptsCommands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PromptsCommands {
+    /// List archived prompts (default if no subcommand)
+    List,
+    /// Print the prompt of an iteration
+    Show {
+        /// Iteration number; defaults to the latest
+        iteration: Option<u32>,
+    },
+    /// Diff two iterations' prompts
+    Diff {
+        /// Older iteration; defaults to the one before `to`
+        from: Option<u32>,
+        /// Newer iteration; defaults to the latest (or `from` + 1)
+        to: Option<u32>,
+        /// Lines of context around each change
+        #[arg(long, default_value_t = 3)]
+        context: usize,
+    },
+}
+
+/// Execute a prompts command.
+pub fn execute(config_sources: &[ConfigSource], args: PromptsArgs, use_colors: bool) -> Result<()> {
+    let ctx = LoopContext::primary(std::env::current_dir()?);
+    let run_id = resolve_run_id(&ctx, args.run.as_deref())?;
+    let records = PromptArchive::new(ctx.prompt_archive_path(&run_id))
+        .with_cipher(local_cipher(config_sources)?)
+        .read_all()?;
+    if records.is_empty() {
+        println!(
+            "No prompts archived for run {run_id}. Set `archive_prompts: true` in ralph.yml to record them."
+        );
+        return Ok(());
+    }
+
+    match args.command {
+        None | Some(PromptsCommands::List) => {
+            list_prompts(&records, use_colors);
+            Ok(())
+        }
+        Some(PromptsCommands::Show { iteration }) => {
+            let record = find(&records, iteration)?;
+            print!("{}", record.prompt);
+            if !record.prompt.ends_with('\n') {
+                println!();
+            }
+            Ok(())
+        }
+        Some(PromptsCommands::Diff { from, to, context }) => {
+            let (old, new) = diff_pair(&records, from, to)?;
+            print!("{}", render_diff(old, new, context, use_colors));
+            Ok(())
+        }
+    }
+}
+
+fn list_prompts(records: &[PromptRecord], use_colors: bool) {
+    let mut previous: Option<&PromptRecord> = None;
+    for record in records {
+        let chars = record.prompt.chars().count();
+        let change = previous.map_or_else(String::new, |prev| {
+            let delta = chars as i64 - prev.prompt.chars().count() as i64;
+            format!("  {}", signed(delta, use_colors))
+        });
+        println!(
+            "#{:<4} {:<16} {:>7} chars  ~{:>6} tokens{change}",
+            record.iteration,
+            record.hat,
+            chars,
+            estimate_tokens(&record.prompt)
+        );
+        previous = Some(record);
+    }
+}
+
+/// The latest prompt of `iteration` (a retried iteration is archived more
+/// than once), or the latest prompt overall.
+fn find(records: &[PromptRecord], iteration: Option<u32>) -> Result<&PromptRecord> {
+    let found = match iteration {
+        Some(iteration) => records.iter().rev().find(|r| r.iteration == iteration),
+        None => records.last(),
+    };
+    match (found, iteration) {
+        (Some(record), _) => Ok(record),
+        (None, Some(iteration)) => bail!("No prompt archived for iteration {iteration}"),
+        (None, None) => bail!("No prompts archived"),
+    }
+}
+
+/// Resolves the `diff` arguments to an (older, newer) pair of prompts.
+fn diff_pair(
+    records: &[PromptRecord],
+    from: Option<u32>,
+    to: Option<u32>,
+) -> Result<(&PromptRecord, &PromptRecord)> {
+    let new = match (from, to) {
+        (_, Some(to)) => find(records, Some(to))?,
+        (Some(from), None) => match records.iter().find(|r| r.iteration > from) {
+            Some(record) => record,
+            None => bail!("No prompt archived after iteration {from}"),
+        },
+        (None, None) => find(records, None)?,
+    };
+    let old = match from {
+        Some(from) => find(records, Some(from))?,
+        None => {
+            let index = records
+                .iter()
+                .position(|r| std::ptr::eq(r, new))
+                .unwrap_or_default();
+            match index.checked_sub(1) {
+                Some(index) => &records[index],
+                None => bail!("Iteration {} has no earlier prompt to diff", new.iteration),
+            }
+        }
+    };
+    Ok((old, new))
+}
+
+/// Renders how the prompt changed: per-section sizes, then a unified diff.
+fn render_diff(old: &PromptRecord, new: &PromptRecord, context: usize, use_colors: bool) -> String {
+    let mut out = format!(
+        "{}\n",
+        bold(
+            &format!(
+                "Iteration {} ({}) -> iteration {} ({})",
+                old.iteration, old.hat, new.iteration, new.hat
+            ),
+            use_colors
+        )
+    );
+
+    let old_sections = prompt_sections(&old.prompt);
+    let new_sections = prompt_sections(&new.prompt);
+    let mut titles: Vec<&str> = new_sections.iter().map(|s| s.title.as_str()).collect();
+    for section in &old_sections {
+        if !titles.contains(&section.title.as_str()) {
+            titles.push(&section.title);
+        }
+    }
+    let size = |sections: &[ralph_core::PromptSection], title: &str| {
+        sections
+            .iter()
+            .filter(|s| s.title == title)
+            .map(|s| s.chars)
+            .sum::<usize>()
+    };
+    out.push_str("\nSections (chars):\n");
+    for title in titles {
+        let (before, after) = (size(&old_sections, title), size(&new_sections, title));
+        let change = match (before, after) {
+            (0, _) => "new".to_string(),
+            (_, 0) => "removed".to_string(),
+            _ if before == after => "unchanged".to_string(),
+            _ => signed(after as i64 - before as i64, use_colors),
+        };
+        out.push_str(&format!(
+            "  {title:<32} {before:>7} -> {after:>7}  {change}\n"
+        ));
+    }
+    out.push('\n');
+
+    let diff = TextDiff::from_lines(&old.prompt, &new.prompt);
+    if diff.ratio() >= 1.0 {
+        out.push_str("(prompts are identical)\n");
+        return out;
+    }
+    for (i, group) in diff.grouped_ops(context).iter().enumerate() {
+        if i > 0 {
+            out.push_str(&dim("...\n", use_colors));
+        }
+        for op in group {
+            for change in diff.iter_changes(op) {
+                let (sign, color) = match change.tag() {
+                    ChangeTag::Delete => ('-', colors::RED),
+                    ChangeTag::Insert => ('+', colors::GREEN),
+                    ChangeTag::Equal => (' ', ""),
+                };
+                let mut line = format!("{sign}{change}");
+                if !line.ends_with('\n') {
+                    line.push('\n');
+                }
+                if use_colors && !color.is_empty() {
+                    line = format!("{color}{line}{}", colors::RESET);
+                }
+                out.push_str(&line);
+            }
+        }
+    }
+    out
+}
+
+fn signed(delta: i64, use_colors: bool) -> String {
+    let text = format!("{delta:+}");
+    if !use_colors || delta == 0 {
+        return text;
+    }
+    let color = if delta > 0 {
+        colors::YELLOW
+    } else {
+        colors::CYAN
+    };
+    format!("{color}{text}{}", colors::RESET)
+}
+
+fn bold(text: &str, use_colors: bool) -> String {
+    if use_colors {
+        format!("{}{text}{}", colors::BOLD, colors::RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+fn dim(text: &str, use_colors: bool) -> String {
+    if use_colors {
+        format!("{}{text}{}", colors::DIM, colors::RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(iteration: u32, prompt: &str) -> PromptRecord {
+        PromptRecord::new(iteration, "builder", prompt)
+    }
+
+    #[test]
+    fn test_diff_pair_defaults_to_the_last_two() {
+        let records = vec![record(1, "a"), record(2, "b"), record(3, "c")];
+        let (old, new) = diff_pair(&records, None, None).unwrap();
+        assert_eq!((old.iteration, new.iteration), (2, 3));
+
+        let (old, new) = diff_pair(&records, Some(1), None).unwrap();
+        assert_eq!((old.iteration, new.iteration), (1, 2));
+
+        assert!(diff_pair(&records[..1], None, None).is_err());
+        assert!(diff_pair(&records, Some(7), None).is_err());
+    }
+
+    #[test]
+    fn test_render_diff_shows_section_sizes_and_changes() {
+        let old = record(
+            1,
+            "# Memories\n- old fact\n## PENDING EVENTS\nbuild.task\n## DONE\nok\n",
+        );
+        let new = record(
+            2,
+            "# Memories\n- old fact\n- new fact\n## PENDING EVENTS\nbuild.task\n<gates>\nx\n</gates>\n",
+        );
+        let rendered = render_diff(&old, &new, 1, false);
+
+        assert!(rendered.starts_with("Iteration 1 (builder) -> iteration 2 (builder)"));
+        assert!(rendered.contains("# Memories                            22 ->      33  +11"));
+        assert!(
+            rendered.contains("## PENDING EVENTS                     29 ->      29  unchanged")
+        );
+        assert!(rendered.contains("<gates>                                0 ->      19  new"));
+        assert!(rendered.contains("## DONE                               11 ->       0  removed"));
+        assert!(rendered.contains("+- new fact\n"));
+        assert!(rendered.contains("-## DONE\n"));
+        assert!(!rendered.contains(" - old fact\n- old fact"));
+    }
+}
@@ -0,0 +1,187 @@
+//! CLI commands for the `ralph repos` namespace.
+//!
+//! Inspect the repositories of a multi-repo run (`repos:` in the config).
+//!
+//! Subcommands:
+//! - `list`: Show each repository's path, branch and uncommitted file count
+//! - `diff`: Show uncommitted changes grouped by repository
+
+use crate::ConfigSource;
+use crate::display::colors;
+use anyhow::{Context, Result, bail};
+use clap::{Parser, Subcommand};
+use ralph_core::{Repo, RepoSet, get_current_branch, get_uncommitted_files};
+use std::process::Command;
+
+/// Inspect the repositories of a multi-repo run.
+#[derive(Parser, Debug)]
+pub struct ReposArgs {
+    #[command(subcommand)]
+    pub command: Option<ReposCommands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ReposCommands {
+    /// List configured repositories (default if no subcommand)
+    List,
+    /// Show uncommitted changes grouped by repository
+    Diff(ReposDiffArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ReposDiffArgs {
+    /// Only show this repository
+    pub repo: Option<String>,
+
+    /// Show stat only (no diff content)
+    #[arg(long)]
+    pub stat: bool,
+}
+
+/// Execute a repos command.
+pub fn execute(config_sources: &[ConfigSource], args: ReposArgs, use_colors: bool) -> Result<()> {
+    let config = crate::load_config_with_overrides(config_sources)?;
+    let repos = RepoSet::from_config(&config.repos, &config.core.workspace_root);
+    if repos.is_empty() {
+        println!("No repositories configured. Add a 'repos:' list to ralph.yml.");
+        return Ok(());
+    }
+
+    match args.command {
+        None | Some(ReposCommands::List) => {
+            list_repos(&repos, use_colors);
+            Ok(())
+        }
+        Some(ReposCommands::Diff(diff_args)) => show_diff(&repos, &diff_args, use_colors),
+    }
+}
+
+fn list_repos(repos: &RepoSet, use_colors: bool) {
+    for repo in repos.iter() {
+        let status = match get_current_branch(&repo.path) {
+            Ok(branch) => {
+                let changed = get_uncommitted_files(&repo.path).map_or(0, |files| files.len());
+                format!("{branch}, {changed} uncommitted")
+            }
+            Err(_) => "not a git repository".to_string(),
+        };
+        println!(
+            "{}  {}  ({status})",
+            heading(&repo.name, use_colors),
+            repo.path.display()
+        );
+        if let Some(description) = &repo.description {
+            println!("    {description}");
+        }
+    }
+}
+
+fn show_diff(repos: &RepoSet, args: &ReposDiffArgs, use_colors: bool) -> Result<()> {
+    let selected: Vec<&Repo> = match &args.repo {
+        Some(name) => match repos.get(name) {
+            Some(repo) => vec![repo],
+            None => bail!("Unknown repo '{name}'. Run `ralph repos list` to see configured repos."),
+        },
+        None => repos.iter().collect(),
+    };
+
+    for (i, repo) in selected.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!(
+            "{} {}",
+            heading(&format!("━━ {}", repo.name), use_colors),
+            repo.path.display()
+        );
+        print!("{}", repo_diff(repo, args.stat)?);
+    }
+    Ok(())
+}
+
+/// Renders one repository's uncommitted changes: the diff against HEAD
+/// followed by untracked files, which `git diff` leaves out.
+fn repo_diff(repo: &Repo, stat: bool) -> Result<String> {
+    let mut git_args = vec!["diff", "HEAD", "--no-ext-diff"];
+    if stat {
+        git_args.push("--stat");
+    }
+    let output = Command::new("git")
+        .args(&git_args)
+        .current_dir(&repo.path)
+        .output()
+        .with_context(|| format!("Failed to run git diff in {}", repo.path.display()))?;
+    if !output.status.success() {
+        return Ok(format!(
+            "(no diff: {})\n",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let mut rendered = String::from_utf8_lossy(&output.stdout).into_owned();
+    let untracked = Command::new("git")
+        .args(["ls-files", "--others", "--exclude-standard"])
+        .current_dir(&repo.path)
+        .output()?;
+    for file in String::from_utf8_lossy(&untracked.stdout).lines() {
+        rendered.push_str(&format!("untracked: {file}\n"));
+    }
+    if rendered.is_empty() {
+        rendered.push_str("(no changes)\n");
+    }
+    Ok(rendered)
+}
+
+fn heading(text: &str, use_colors: bool) -> String {
+    if use_colors {
+        format!("{}{text}{}", colors::BOLD, colors::RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralph_core::RepoConfig;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_repo_diff_includes_untracked_files() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("api");
+        std::fs::create_dir(&dir).unwrap();
+        git(&dir, &["init", "--initial-branch=main"]);
+        git(&dir, &["config", "user.email", "test@test.local"]);
+        git(&dir, &["config", "user.name", "Test User"]);
+        std::fs::write(dir.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(&dir, &["add", "lib.rs"]);
+        git(&dir, &["commit", "-m", "Initial commit"]);
+
+        let repos = RepoSet::from_config(
+            &[RepoConfig {
+                name: "api".to_string(),
+                path: "api".into(),
+                description: None,
+            }],
+            temp.path(),
+        );
+        let repo = repos.get("api").unwrap();
+        assert_eq!(repo_diff(repo, false).unwrap(), "(no changes)\n");
+
+        std::fs::write(dir.join("lib.rs"), "fn b() {}\n").unwrap();
+        std::fs::write(dir.join("new.rs"), "").unwrap();
+        let diff = repo_diff(repo, false).unwrap();
+        assert!(diff.contains("+fn b() {}"));
+        assert!(diff.ends_with("untracked: new.rs\n"));
+    }
+}
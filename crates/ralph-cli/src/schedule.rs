@@ -0,0 +1,492 @@
+//! Status feeds for `ralph bot daemon`.
+//!
+//! `daemon.schedules` declares when the team's scheduled runs happen; the
+//! daemon appends the outcome of each run it starts to
+//! `.ralph/schedule-history.jsonl`.
+//!
+//! With `--status-http <addr>` the daemon serves what is coming up and how
+//! recent runs went:
+//! - `GET /status.json`: next run per schedule and recent outcomes
+//! - `GET /schedule.ics`: an iCal feed of upcoming and recent runs, for
+//!   subscribing from a team calendar
+//!
+//! Both endpoints need a viewer token when `http_api.tokens` is configured.
+
+use crate::http_api::{self, ApiAuth, Request, read_head, respond};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Days, Local, TimeZone, Utc};
+use ralph_core::{
+    ApiRole, HttpApiConfig, LoopLock, ScheduledRunConfig, TerminationReason, truncate_with_ellipsis,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Write as _};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tracing::{debug, warn};
+
+/// How far ahead the calendar feed lists upcoming runs.
+const CALENDAR_HORIZON: Days = Days::new(7);
+
+/// Number of past runs included in the status feeds.
+const RECENT_RUNS: usize = 20;
+
+/// Longest run name taken from the first line of its prompt.
+const RUN_NAME_CHARS: usize = 60;
+
+/// Returns the first start of `schedule` strictly after `after`.
+pub(crate) fn next_run<Tz: TimeZone>(
+    schedule: &ScheduledRunConfig,
+    after: &DateTime<Tz>,
+) -> Option<DateTime<Tz>> {
+    let time = schedule.time()?;
+    (0..=7).find_map(|offset| {
+        let date = after.date_naive().checked_add_days(Days::new(offset))?;
+        if !schedule.days.is_empty() && !schedule.days.contains(&date.weekday()) {
+            return None;
+        }
+        let start = after
+            .timezone()
+            .from_local_datetime(&date.and_time(time))
+            .earliest()?;
+        (start > *after).then_some(start)
+    })
+}
+
+/// Outcome of one run the daemon started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ScheduledRunRecord {
+    pub name: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// Termination reason, or `error` if the loop failed to run.
+    pub outcome: String,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The append-only record of the daemon's runs.
+#[derive(Debug, Clone)]
+pub(crate) struct ScheduleHistory {
+    path: PathBuf,
+}
+
+impl ScheduleHistory {
+    pub(crate) fn new(workspace_root: &Path) -> Self {
+        Self {
+            path: workspace_root.join(".ralph/schedule-history.jsonl"),
+        }
+    }
+
+    fn record(&self, record: &ScheduledRunRecord) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let line = serde_json::to_string(record)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(format!("{line}\n").as_bytes())
+    }
+
+    /// Records a finished run, named after the first line of its prompt.
+    pub(crate) fn record_run(
+        &self,
+        prompt: &str,
+        started_at: DateTime<Utc>,
+        result: &Result<TerminationReason>,
+    ) {
+        let (outcome, success, error) = match result {
+            Ok(reason) => (reason.as_str().to_string(), reason.is_success(), None),
+            Err(e) => ("error".to_string(), false, Some(format!("{e:#}"))),
+        };
+        let record = ScheduledRunRecord {
+            name: truncate_with_ellipsis(prompt.lines().next().unwrap_or_default(), RUN_NAME_CHARS),
+            started_at,
+            finished_at: Utc::now(),
+            outcome,
+            success,
+            error,
+        };
+        if let Err(e) = self.record(&record) {
+            warn!("Failed to record daemon run: {}", e);
+        }
+    }
+
+    /// Returns up to `limit` runs, newest first. Malformed lines are skipped.
+    pub(crate) fn recent(&self, limit: usize) -> io::Result<Vec<ScheduledRunRecord>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(limit)
+            .collect())
+    }
+}
+
+/// Builds the `/status.json` document.
+fn status_json<Tz>(
+    schedules: &[ScheduledRunConfig],
+    recent: &[ScheduledRunRecord],
+    now: &DateTime<Tz>,
+    loop_running: bool,
+) -> serde_json::Value
+where
+    Tz: TimeZone,
+    Tz::Offset: Display,
+{
+    let schedules: Vec<_> = schedules
+        .iter()
+        .map(|schedule| {
+            serde_json::json!({
+                "name": schedule.name,
+                "at": schedule.at,
+                "days": schedule.days.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                "next_run": next_run(schedule, now).map(|at| at.to_rfc3339()),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "generated_at": now.to_rfc3339(),
+        "loop_running": loop_running,
+        "schedules": schedules,
+        "recent": recent,
+    })
+}
+
+/// Builds the `/schedule.ics` calendar: upcoming runs within
+/// [`CALENDAR_HORIZON`] and the given past runs.
+fn ical<Tz: TimeZone>(
+    schedules: &[ScheduledRunConfig],
+    recent: &[ScheduledRunRecord],
+    now: &DateTime<Tz>,
+) -> String {
+    let stamp = ical_time(&now.to_utc());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//ralph//daemon schedule//EN".to_string(),
+        "X-WR-CALNAME:Ralph scheduled runs".to_string(),
+    ];
+
+    let horizon = now.clone() + CALENDAR_HORIZON;
+    for schedule in schedules {
+        let mut after = now.clone();
+        while let Some(at) = next_run(schedule, &after).filter(|at| *at <= horizon) {
+            let start = ical_time(&at.to_utc());
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{}-{start}@ralph", ical_text(&schedule.name)),
+                format!("DTSTAMP:{stamp}"),
+                format!("DTSTART:{start}"),
+                format!("SUMMARY:ralph: {}", ical_text(&schedule.name)),
+                format!("DESCRIPTION:{}", ical_text(&schedule.prompt)),
+                "END:VEVENT".to_string(),
+            ]);
+            after = at;
+        }
+    }
+
+    for run in recent {
+        let start = ical_time(&run.started_at);
+        let mark = if run.success { "✓" } else { "✗" };
+        let mut description = format!("Outcome: {}", run.outcome);
+        if let Some(error) = &run.error {
+            let _ = write!(description, "\n{error}");
+        }
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}-{start}@ralph", ical_text(&run.name)),
+            format!("DTSTAMP:{stamp}"),
+            format!("DTSTART:{start}"),
+            format!("DTEND:{}", ical_time(&run.finished_at)),
+            format!("SUMMARY:{mark} ralph: {}", ical_text(&run.name)),
+            format!("DESCRIPTION:{}", ical_text(&description)),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line)).collect()
+}
+
+fn ical_time(time: &DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes a TEXT value (RFC 5545 §3.3.11).
+fn ical_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line at 75 octets and terminates it with CRLF.
+fn fold_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + 4);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// State shared by the status server's connections.
+struct StatusServer {
+    schedules: Vec<ScheduledRunConfig>,
+    history: ScheduleHistory,
+    workspace_root: PathBuf,
+    auth: ApiAuth,
+}
+
+impl StatusServer {
+    async fn handle<S>(&self, stream: S) -> io::Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let (mut stream, request_line, headers) = read_head(stream).await?;
+        let Some(request) = Request::parse(&request_line, &headers) else {
+            return respond(
+                &mut stream,
+                "400 Bad Request",
+                "text/plain",
+                "bad request\n",
+            )
+            .await;
+        };
+        if request.method != "GET" || !matches!(request.path, "/status.json" | "/schedule.ics") {
+            return respond(&mut stream, "404 Not Found", "text/plain", "not found\n").await;
+        }
+        if let Err(e) = self.auth.authorize(
+            request.token.as_deref(),
+            ApiRole::Viewer,
+            std::time::Instant::now(),
+        ) {
+            let body = format!("{}\n", e.status());
+            return respond(&mut stream, e.status(), "text/plain", &body).await;
+        }
+
+        let recent = self.history.recent(RECENT_RUNS)?;
+        let now = Local::now();
+        if request.path == "/schedule.ics" {
+            let body = ical(&self.schedules, &recent, &now);
+            respond(&mut stream, "200 OK", "text/calendar; charset=utf-8", &body).await
+        } else {
+            let loop_running = LoopLock::is_locked(&self.workspace_root).unwrap_or(false);
+            let body = status_json(&self.schedules, &recent, &now, loop_running);
+            respond(&mut stream, "200 OK", "application/json", &body.to_string()).await
+        }
+    }
+}
+
+/// Serves the status feeds until the task is dropped.
+pub(crate) async fn serve_status(
+    addr: SocketAddr,
+    schedules: Vec<ScheduledRunConfig>,
+    workspace_root: PathBuf,
+    config: &HttpApiConfig,
+) -> Result<()> {
+    let auth = ApiAuth::from_config(config)?;
+    auth.check_bind(addr)?;
+    let tls = config
+        .tls
+        .as_ref()
+        .map(http_api::tls_acceptor)
+        .transpose()?;
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind {addr}"))?;
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    println!(
+        "Serving schedule status on {scheme}://{}/status.json and /schedule.ics",
+        listener.local_addr()?
+    );
+
+    let server = Arc::new(StatusServer {
+        schedules,
+        history: ScheduleHistory::new(&workspace_root),
+        workspace_root,
+        auth,
+    });
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let server = Arc::clone(&server);
+        let tls = tls.clone();
+        tokio::spawn(async move {
+            let result = match tls {
+                Some(tls) => match tls.accept(stream).await {
+                    Ok(stream) => server.handle(stream).await,
+                    Err(e) => Err(e),
+                },
+                None => server.handle(stream).await,
+            };
+            if let Err(e) = result {
+                debug!("Status client {} disconnected: {}", peer, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn schedule(at: &str, days: Vec<Weekday>) -> ScheduledRunConfig {
+        ScheduledRunConfig {
+            name: "nightly".to_string(),
+            prompt: "Update deps, then test".to_string(),
+            at: at.to_string(),
+            days,
+        }
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_next_run_daily_and_weekdays() {
+        // 2026-01-01 is a Thursday
+        let now = utc("2026-01-01T10:00:00Z");
+        let daily = schedule("02:30", Vec::new());
+        assert_eq!(next_run(&daily, &now), Some(utc("2026-01-02T02:30:00Z")));
+        assert_eq!(
+            next_run(&schedule("10:00", Vec::new()), &now),
+            Some(utc("2026-01-02T10:00:00Z"))
+        );
+
+        let mondays = schedule("09:00", vec![Weekday::Mon]);
+        assert_eq!(next_run(&mondays, &now), Some(utc("2026-01-05T09:00:00Z")));
+        assert_eq!(next_run(&schedule("bad", Vec::new()), &now), None);
+    }
+
+    #[test]
+    fn test_history_returns_newest_first() {
+        let temp = TempDir::new().unwrap();
+        let history = ScheduleHistory::new(temp.path());
+        for (i, outcome) in ["completed", "max_cost"].iter().enumerate() {
+            history
+                .record(&ScheduledRunRecord {
+                    name: format!("run{i}"),
+                    started_at: utc("2026-01-01T02:30:00Z"),
+                    finished_at: utc("2026-01-01T03:00:00Z"),
+                    outcome: (*outcome).to_string(),
+                    success: i == 0,
+                    error: None,
+                })
+                .unwrap();
+        }
+
+        let recent = history.recent(1).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].outcome, "max_cost");
+    }
+
+    #[test]
+    fn test_record_run_names_run_after_prompt() {
+        let temp = TempDir::new().unwrap();
+        let history = ScheduleHistory::new(temp.path());
+        let started_at = utc("2026-01-01T02:30:00Z");
+        history.record_run(
+            "Update deps\nthen run the tests",
+            started_at,
+            &Ok(TerminationReason::CompletionPromise),
+        );
+        history.record_run(
+            "Fix the build",
+            started_at,
+            &Err(anyhow::anyhow!("Failed to acquire loop lock")),
+        );
+
+        let recent = history.recent(RECENT_RUNS).unwrap();
+        assert_eq!(recent[0].name, "Fix the build");
+        assert_eq!(recent[0].outcome, "error");
+        assert_eq!(
+            recent[0].error.as_deref(),
+            Some("Failed to acquire loop lock")
+        );
+        assert_eq!(recent[1].name, "Update deps");
+        assert!(recent[1].success);
+    }
+
+    #[test]
+    fn test_ical_lists_upcoming_and_recent_runs() {
+        let now = utc("2026-01-01T10:00:00Z");
+        let recent = vec![ScheduledRunRecord {
+            name: "nightly".to_string(),
+            started_at: utc("2026-01-01T02:30:00Z"),
+            finished_at: utc("2026-01-01T03:15:00Z"),
+            outcome: "error".to_string(),
+            success: false,
+            error: Some("Failed to acquire loop lock".to_string()),
+        }];
+        let calendar = ical(&[schedule("02:30", Vec::new())], &recent, &now);
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        // Seven upcoming nights plus the failed run
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 8);
+        assert!(calendar.contains("DTSTART:20260102T023000Z\r\n"));
+        assert!(calendar.contains("DESCRIPTION:Update deps\\, then test\r\n"));
+        assert!(calendar.contains("SUMMARY:✗ ralph: nightly\r\n"));
+        assert!(calendar.contains("DTEND:20260101T031500Z\r\n"));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let folded = fold_line(&"x".repeat(80));
+        assert_eq!(
+            folded,
+            format!("{}\r\n {}\r\n", "x".repeat(75), "x".repeat(5))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status_endpoint() {
+        let temp = TempDir::new().unwrap();
+        let server = StatusServer {
+            schedules: vec![schedule("02:30", Vec::new())],
+            history: ScheduleHistory::new(temp.path()),
+            workspace_root: temp.path().to_path_buf(),
+            auth: ApiAuth::default(),
+        };
+
+        let (mut client, stream) = tokio::io::duplex(4096);
+        client
+            .write_all(b"GET /status.json HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        server.handle(stream).await.unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        let body: serde_json::Value =
+            serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body["loop_running"], false);
+        assert_eq!(body["schedules"][0]["name"], "nightly");
+        assert!(body["schedules"][0]["next_run"].is_string());
+    }
+}
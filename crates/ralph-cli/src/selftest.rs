@@ -0,0 +1,321 @@
+//! CLI command for `ralph selftest`.
+//!
+//! Runs a short scripted loop through the same pipeline a real run uses —
+//! mock backend → event loop → stream handler → TUI (rendered headless) →
+//! event, status and output logs — in a scratch workspace, and reports each
+//! stage. A passing selftest means the installation works; what's left to
+//! check before a paid run is the backend CLI itself (`ralph doctor`).
+
+use crate::display::colors;
+use anyhow::{Context, Result, bail};
+use clap::Parser;
+use ralph_adapters::{SessionResult, StreamHandler, TuiStreamHandler};
+use ralph_core::diagnostics::{AgentOutputContent, AgentOutputLogger};
+use ralph_core::testing::mock_backend::MockBackend;
+use ralph_core::{
+    EventHistory, EventLogger, EventLoop, EventRecord, LoopContext, RalphConfig, RunPhase,
+    RunStatus, TerminationReason,
+};
+use ralph_proto::Event;
+use ralph_tui::TuiState;
+use ralph_tui::widgets::content::ContentPane;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// What the mock backend answers, one reply per iteration.
+const SCRIPT: [&str; 2] = [
+    "Selftest: reading the task and planning the change.",
+    "Selftest: all checks pass. Done! LOOP_COMPLETE",
+];
+
+/// Upper bound on iterations, in case the loop never sees the completion.
+const MAX_ITERATIONS: usize = 5;
+
+/// Run a scripted loop with a mock backend to check the installation.
+#[derive(Parser, Debug)]
+pub struct SelftestArgs {
+    /// Keep the scratch workspace instead of deleting it
+    #[arg(long)]
+    pub keep: bool,
+}
+
+/// Execute the selftest command.
+pub fn execute(args: SelftestArgs, use_colors: bool) -> Result<()> {
+    let workspace = std::env::temp_dir().join(format!("ralph-selftest-{}", std::process::id()));
+    std::fs::create_dir_all(&workspace)
+        .with_context(|| format!("Failed to create {}", workspace.display()))?;
+
+    let stages = run_stages(&workspace);
+
+    let mut stdout = std::io::stdout();
+    for (name, result) in &stages {
+        let (mark, color, detail) = match result {
+            Ok(detail) => ("✓", colors::GREEN, detail.clone()),
+            Err(e) => ("✗", colors::RED, format!("{e:#}")),
+        };
+        if use_colors {
+            writeln!(stdout, "{color}{mark}{} {name:<12} {detail}", colors::RESET)?;
+        } else {
+            writeln!(stdout, "{mark} {name:<12} {detail}")?;
+        }
+    }
+
+    if args.keep {
+        writeln!(stdout, "\nWorkspace kept at {}", workspace.display())?;
+    } else {
+        let _ = std::fs::remove_dir_all(&workspace);
+    }
+
+    if stages.iter().any(|(_, result)| result.is_err()) {
+        bail!("Selftest failed; this installation cannot run loops reliably");
+    }
+    Ok(())
+}
+
+/// The state the stages pass along.
+struct Pipeline {
+    workspace: PathBuf,
+    tui: Arc<Mutex<TuiState>>,
+    events: Arc<Mutex<Vec<Event>>>,
+    outputs: Vec<String>,
+}
+
+/// Runs the stages in order, stopping at the first failure.
+fn run_stages(workspace: &Path) -> Vec<(&'static str, Result<String>)> {
+    let mut pipeline = Pipeline {
+        workspace: workspace.to_path_buf(),
+        tui: Arc::new(Mutex::new(TuiState::new())),
+        events: Arc::new(Mutex::new(Vec::new())),
+        outputs: Vec::new(),
+    };
+    let stages: [(&'static str, fn(&mut Pipeline) -> Result<String>); 4] = [
+        ("loop", run_loop),
+        ("handler", check_handler),
+        ("tui", render_tui),
+        ("persistence", persist),
+    ];
+
+    let mut results = Vec::new();
+    for (name, stage) in stages {
+        let result = stage(&mut pipeline);
+        let failed = result.is_err();
+        results.push((name, result));
+        if failed {
+            break;
+        }
+    }
+    results
+}
+
+/// Drives the event loop with the mock backend, streaming each reply
+/// through the TUI stream handler like a real iteration.
+fn run_loop(pipeline: &mut Pipeline) -> Result<String> {
+    let backend = MockBackend::new(SCRIPT.iter().map(ToString::to_string).collect());
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = pipeline.workspace.clone();
+    config.core.scratchpad = pipeline
+        .workspace
+        .join(".agent/scratchpad.md")
+        .to_string_lossy()
+        .to_string();
+    let mut event_loop =
+        EventLoop::with_context(config, LoopContext::primary(pipeline.workspace.clone()));
+
+    let tui = Arc::clone(&pipeline.tui);
+    let events = Arc::clone(&pipeline.events);
+    event_loop.add_observer(move |event| {
+        if let Ok(mut state) = tui.lock() {
+            state.update(event);
+        }
+        if let Ok(mut events) = events.lock() {
+            events.push(event.clone());
+        }
+    });
+    event_loop.initialize("Selftest: check that the pipeline works end to end.");
+
+    let mut termination = None;
+    for _ in 0..MAX_ITERATIONS {
+        let hat = event_loop
+            .next_hat()
+            .cloned()
+            .context("No hat to run the next iteration")?;
+        let prompt = event_loop
+            .build_prompt(&hat)
+            .with_context(|| format!("No prompt built for hat '{hat}'"))?;
+
+        let lines = {
+            let mut state = pipeline.tui.lock().expect("TUI state lock poisoned");
+            state.start_new_iteration();
+            state.latest_iteration_lines_handle()
+        }
+        .context("The TUI has no iteration buffer to stream into")?;
+        let output = backend.execute(&prompt);
+        let mut handler = TuiStreamHandler::with_lines(false, lines);
+        handler.on_tool_call(
+            "Read",
+            "selftest-read",
+            &serde_json::json!({ "file_path": "PROMPT.md" }),
+        );
+        handler.on_text(&output);
+        handler.on_complete(&SessionResult::new(1, 0.0, 1, false));
+        pipeline.outputs.push(output.clone());
+
+        termination = event_loop.process_output(&hat, &output, true);
+        if termination.is_some() {
+            break;
+        }
+        if !event_loop.has_pending_events() {
+            event_loop.inject_fallback_event();
+        }
+    }
+
+    match termination {
+        Some(TerminationReason::CompletionPromise) => Ok(format!(
+            "mock backend ran {} iteration(s), loop completed",
+            backend.execution_count()
+        )),
+        Some(other) => bail!("Loop stopped early: {other:?}"),
+        None => bail!("Loop did not complete within {MAX_ITERATIONS} iterations"),
+    }
+}
+
+/// Checks that every reply reached an iteration buffer as lines.
+fn check_handler(pipeline: &mut Pipeline) -> Result<String> {
+    let state = pipeline.tui.lock().expect("TUI state lock poisoned");
+    if state.iterations.len() != pipeline.outputs.len() {
+        bail!(
+            "{} iteration buffer(s) for {} iteration(s)",
+            state.iterations.len(),
+            pipeline.outputs.len()
+        );
+    }
+    let mut total = 0;
+    for (buffer, output) in state.iterations.iter().zip(&pipeline.outputs) {
+        let lines = buffer.lines_handle();
+        let lines = lines.lock().expect("iteration lines lock poisoned");
+        let text: String = lines.iter().map(ToString::to_string).collect();
+        if !text.contains(output.as_str()) {
+            bail!("Iteration {} is missing the backend output", buffer.number);
+        }
+        total += lines.len();
+    }
+    Ok(format!(
+        "{} line(s) streamed into {} iteration buffer(s)",
+        total,
+        state.iterations.len()
+    ))
+}
+
+/// Renders the TUI into an off-screen terminal and checks the frame.
+fn render_tui(pipeline: &mut Pipeline) -> Result<String> {
+    let state = pipeline.tui.lock().expect("TUI state lock poisoned");
+    let (width, height) = (100, 20);
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    terminal.draw(|f| {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Min(0),
+                Constraint::Length(2),
+            ])
+            .split(f.area());
+        f.render_widget(
+            ralph_tui::header::render(&state, chunks[0].width),
+            chunks[0],
+        );
+        if let Some(buffer) = state.current_iteration() {
+            f.render_widget(ContentPane::new(buffer), chunks[1]);
+        }
+        f.render_widget(ralph_tui::footer::render(&state), chunks[2]);
+    })?;
+
+    let frame: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    let last = pipeline.outputs.last().context("No output to render")?;
+    if !frame.contains("LOOP_COMPLETE") {
+        bail!("The rendered frame doesn't show the last iteration's output ({last:?})");
+    }
+    Ok(format!(
+        "rendered a {width}x{height} frame of the last iteration"
+    ))
+}
+
+/// Writes the run's events, status and output the way a run does and reads
+/// them back.
+fn persist(pipeline: &mut Pipeline) -> Result<String> {
+    let ralph_dir = pipeline.workspace.join(".ralph");
+    let events = pipeline.events.lock().expect("event list lock poisoned");
+
+    let events_path = ralph_dir.join("events.jsonl");
+    let mut logger = EventLogger::new(&events_path);
+    for event in events.iter() {
+        logger.log(&EventRecord::new(0, "ralph", event, None))?;
+    }
+    let logged = EventHistory::new(&events_path).read_all()?;
+    if logged.len() != events.len() {
+        bail!(
+            "{} event(s) logged but {} read back",
+            events.len(),
+            logged.len()
+        );
+    }
+
+    let status_path = ralph_dir.join("status.json");
+    let mut status = RunStatus::new(Some("selftest".to_string()), 0, None);
+    status.iteration = u32::try_from(pipeline.outputs.len())?;
+    status.phase = RunPhase::Finished;
+    status.write(&status_path)?;
+    let read = RunStatus::read(&status_path)?.context("Status file missing after write")?;
+    if read.phase != RunPhase::Finished || read.iteration != status.iteration {
+        bail!("Status file read back differently than written");
+    }
+
+    let output_path = ralph_dir.join("output.jsonl");
+    let mut output_log = AgentOutputLogger::append(&output_path)?;
+    for (iteration, output) in (1..).zip(&pipeline.outputs) {
+        output_log.set_context(iteration, "ralph");
+        output_log.log(AgentOutputContent::Text {
+            text: output.clone(),
+        })?;
+    }
+    let output_lines = std::fs::read_to_string(&output_path)?.lines().count();
+    if output_lines != pipeline.outputs.len() {
+        bail!(
+            "{} output(s) logged but {output_lines} line(s) read back",
+            pipeline.outputs.len()
+        );
+    }
+
+    Ok(format!(
+        "{} event(s), status and {output_lines} output record(s) round-tripped",
+        logged.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_all_stages_pass() {
+        let temp = TempDir::new().unwrap();
+        let stages = run_stages(temp.path());
+        let names: Vec<_> = stages.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["loop", "handler", "tui", "persistence"]);
+        for (name, result) in &stages {
+            assert!(result.is_ok(), "{name}: {:?}", result.as_ref().err());
+        }
+        assert!(temp.path().join(".ralph/events.jsonl").exists());
+    }
+}
@@ -6,7 +6,9 @@
 //! 2. Build a prompt with the SOP content wrapped in XML tags
 //! 3. Spawn an interactive session with the backend
 
-use ralph_adapters::{CliBackend, CustomBackendError, NoBackendError, detect_backend_default};
+use ralph_adapters::{
+    AdapterRegistry, CliBackend, CustomBackendError, NoBackendError, detect_backend_default,
+};
 use ralph_core::RalphConfig;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
@@ -90,10 +92,19 @@ impl From<CustomBackendError> for SopRunError {
 /// This is the main entry point for `ralph plan` and `ralph code-task` commands.
 /// It resolves the backend, builds the prompt, and spawns an interactive session.
 pub fn run_sop(config: SopRunConfig) -> Result<(), SopRunError> {
-    // 1. Resolve backend
+    // 1. Resolve backend, including plugin adapters from the config file
+    let ralph_config = config
+        .config_path
+        .as_ref()
+        .filter(|path| path.exists())
+        .and_then(|path| RalphConfig::from_file(path).ok());
+    let registry = ralph_config
+        .as_ref()
+        .map_or_else(AdapterRegistry::builtin, AdapterRegistry::from_config);
     let backend_name = resolve_backend(
         config.backend_override.as_deref(),
-        config.config_path.as_ref(),
+        ralph_config.as_ref(),
+        &registry,
     )?;
 
     // 2. Build the prompt
@@ -108,16 +119,8 @@ pub fn run_sop(config: SopRunConfig) -> Result<(), SopRunError> {
                     "custom (no command specified in args)".to_string(),
                 ));
             }
-            let command = args[0].clone();
-            let cli_args = args[1..].to_vec();
-
-            CliBackend {
-                command,
-                args: cli_args,
-                prompt_mode: ralph_adapters::PromptMode::Arg,
-                prompt_flag: None, // Prompt appended as last arg by default
-                output_format: ralph_adapters::OutputFormat::Text,
-            }
+            // Prompt appended as last arg by default
+            CliBackend::new(args[0].clone(), args[1..].to_vec())
         } else {
             // For custom backend from config, we need to load the configuration to get the command/args
             let config_path = config
@@ -140,7 +143,13 @@ pub fn run_sop(config: SopRunConfig) -> Result<(), SopRunError> {
             }
         }
     } else {
-        CliBackend::for_interactive_prompt(&backend_name)?
+        // Plugins have no separate interactive mode, so they run as configured
+        let entry = registry
+            .get(&backend_name)
+            .ok_or_else(|| SopRunError::UnknownBackend(backend_name.clone()))?;
+        entry
+            .interactive_backend()
+            .unwrap_or_else(|| entry.backend())
     };
 
     // 4. Spawn the interactive session
@@ -157,34 +166,32 @@ pub fn run_sop(config: SopRunConfig) -> Result<(), SopRunError> {
 /// 3. Auto-detect (first available from claude → kiro → gemini → codex → amp)
 fn resolve_backend(
     flag_override: Option<&str>,
-    config_path: Option<&PathBuf>,
+    config: Option<&RalphConfig>,
+    registry: &AdapterRegistry,
 ) -> Result<String, SopRunError> {
     // 1. CLI flag takes precedence
     if let Some(backend) = flag_override {
-        validate_backend_name(backend)?;
+        validate_backend_name(backend, registry)?;
         return Ok(backend.to_string());
     }
 
     // 2. Check config file
-    if let Some(path) = config_path
-        && path.exists()
-        && let Ok(config) = RalphConfig::from_file(path)
+    if let Some(config) = config
         && config.cli.backend != "auto"
     {
-        return Ok(config.cli.backend);
+        return Ok(config.cli.backend.clone());
     }
 
     // 3. Auto-detect
     detect_backend_default().map_err(SopRunError::NoBackend)
 }
 
-/// Validates a backend name.
-fn validate_backend_name(name: &str) -> Result<(), SopRunError> {
-    match name {
-        "claude" | "kiro" | "gemini" | "codex" | "amp" | "copilot" | "opencode" | "custom" => {
-            Ok(())
-        }
-        _ => Err(SopRunError::UnknownBackend(name.to_string())),
+/// Validates a backend name against the built-in and configured adapters.
+fn validate_backend_name(name: &str, registry: &AdapterRegistry) -> Result<(), SopRunError> {
+    if name == "custom" || registry.get(name).is_some() {
+        Ok(())
+    } else {
+        Err(SopRunError::UnknownBackend(name.to_string()))
     }
 }
 
@@ -290,19 +297,38 @@ mod tests {
 
     #[test]
     fn test_validate_backend_name_valid() {
-        assert!(validate_backend_name("claude").is_ok());
-        assert!(validate_backend_name("kiro").is_ok());
-        assert!(validate_backend_name("gemini").is_ok());
-        assert!(validate_backend_name("codex").is_ok());
-        assert!(validate_backend_name("amp").is_ok());
-        assert!(validate_backend_name("copilot").is_ok());
-        assert!(validate_backend_name("opencode").is_ok());
-        assert!(validate_backend_name("custom").is_ok());
+        let registry = AdapterRegistry::builtin();
+        assert!(validate_backend_name("claude", &registry).is_ok());
+        assert!(validate_backend_name("kiro", &registry).is_ok());
+        assert!(validate_backend_name("gemini", &registry).is_ok());
+        assert!(validate_backend_name("codex", &registry).is_ok());
+        assert!(validate_backend_name("amp", &registry).is_ok());
+        assert!(validate_backend_name("copilot", &registry).is_ok());
+        assert!(validate_backend_name("opencode", &registry).is_ok());
+        assert!(validate_backend_name("custom", &registry).is_ok());
+    }
+
+    #[test]
+    fn test_plugin_backend_selected_by_flag() {
+        let config: RalphConfig = serde_yaml::from_str(
+            "adapters:\n  plugins:\n    aider:\n      command: aider\n      prompt_flag: --message\n",
+        )
+        .unwrap();
+        let registry = AdapterRegistry::from_config(&config);
+
+        assert_eq!(
+            resolve_backend(Some("aider"), Some(&config), &registry).unwrap(),
+            "aider"
+        );
+        assert!(matches!(
+            resolve_backend(Some("aider"), None, &AdapterRegistry::builtin()),
+            Err(SopRunError::UnknownBackend(_))
+        ));
     }
 
     #[test]
     fn test_validate_backend_name_invalid() {
-        let result = validate_backend_name("invalid_backend");
+        let result = validate_backend_name("invalid_backend", &AdapterRegistry::builtin());
         assert!(result.is_err());
 
         if let Err(SopRunError::UnknownBackend(name)) = result {
@@ -0,0 +1,322 @@
+//! Checks between `ralph run` and iteration 1.
+//!
+//! The TUI splash lists what the run is about to use (backend, model, hats,
+//! limits, gates, the task and an estimated cost) and, unless
+//! `tui.confirm_start` is off or `--yes` was passed, holds the loop until the
+//! user presses Enter. A run started with the wrong profile can then be
+//! cancelled with `q` before it spends anything.
+//!
+//! Before that, a `max_cost_usd` above `confirm_budget_above_usd` has to be
+//! typed back (or confirmed with `--confirm-budget`), so a mistyped budget
+//! doesn't go unnoticed into an overnight run.
+
+use crate::display::{format_elapsed, truncate};
+use anyhow::{Result, bail};
+use ralph_adapters::CliBackend;
+use ralph_core::{Gates, RalphConfig, RunPhase, RunStatus};
+use ralph_tui::TuiState;
+use std::io::{IsTerminal, Write, stderr, stdin};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Characters of the prompt shown as the task.
+const TASK_PREVIEW_CHARS: usize = 120;
+
+/// Builds the label/value rows of the splash screen.
+///
+/// `previous` is the status the last run in this workspace left behind,
+/// used to estimate cost.
+pub(crate) fn startup_summary(
+    config: &RalphConfig,
+    backend: &CliBackend,
+    prompt: &str,
+    gates: &Gates,
+    previous: Option<&RunStatus>,
+) -> Vec<(String, String)> {
+    let mut hats: Vec<&str> = config.hats.values().map(|hat| hat.name.as_str()).collect();
+    hats.sort_unstable();
+    let hats = if hats.is_empty() {
+        "none (Ralph solo)".to_string()
+    } else {
+        hats.join(", ")
+    };
+
+    let mut limits = vec![
+        format!("{} iterations", config.event_loop.max_iterations),
+        format_elapsed(Duration::from_secs(config.event_loop.max_runtime_seconds)),
+    ];
+    if let Some(max_cost) = config.event_loop.max_cost_usd {
+        limits.push(format!("${max_cost:.2}"));
+    }
+
+    let gates = [
+        ("tests", &gates.tests),
+        ("lint", &gates.lint),
+        ("typecheck", &gates.typecheck),
+    ]
+    .into_iter()
+    .filter_map(|(check, command)| {
+        command
+            .as_ref()
+            .map(|command| format!("{check}: {command}"))
+    })
+    .collect::<Vec<_>>();
+
+    let task = prompt
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    vec![
+        ("Backend".to_string(), config.cli.backend.clone()),
+        (
+            "Model".to_string(),
+            backend_model(&backend.args).unwrap_or_else(|| "backend default".to_string()),
+        ),
+        ("Hats".to_string(), hats),
+        ("Limits".to_string(), limits.join(" · ")),
+        (
+            "Gates".to_string(),
+            if gates.is_empty() {
+                "none detected".to_string()
+            } else {
+                gates.join(" · ")
+            },
+        ),
+        ("Task".to_string(), truncate(&task, TASK_PREVIEW_CHARS)),
+        (
+            "Est. cost".to_string(),
+            estimate_cost(
+                config.event_loop.max_iterations,
+                config.event_loop.max_cost_usd,
+                previous,
+            ),
+        ),
+    ]
+}
+
+/// The model selected by `--model <name>`, `--model=<name>` or `-m <name>`
+/// in the backend arguments.
+fn backend_model(args: &[String]) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if let Some(model) = arg.strip_prefix("--model=") {
+            return Some(model.to_string());
+        }
+        if arg == "--model" || arg == "-m" {
+            return args.next().cloned();
+        }
+    }
+    None
+}
+
+/// Worst-case cost of the run: the last finished run's cost per iteration
+/// times the iteration limit, capped by `max_cost_usd`.
+fn estimate_cost(
+    max_iterations: u32,
+    max_cost_usd: Option<f64>,
+    previous: Option<&RunStatus>,
+) -> String {
+    let per_iteration = previous
+        .filter(|status| status.phase == RunPhase::Finished && status.iteration > 0)
+        .map(|status| status.cost_usd / f64::from(status.iteration))
+        .filter(|cost| *cost > 0.0);
+
+    match (per_iteration, max_cost_usd) {
+        (Some(per_iteration), max_cost) => {
+            let estimate = per_iteration * f64::from(max_iterations);
+            let capped = max_cost.map_or(estimate, |max| estimate.min(max));
+            format!("up to ${capped:.2} (${per_iteration:.2}/iteration in the last run)")
+        }
+        (None, Some(max_cost)) => format!("up to ${max_cost:.2} (max_cost_usd)"),
+        (None, None) => "unknown (no previous run, no max_cost_usd)".to_string(),
+    }
+}
+
+/// Asks for the budget to be typed back when `max_cost_usd` is above
+/// `confirm_budget_above_usd`. `confirmed` (from `--confirm-budget`) skips
+/// the question; without a terminal to ask on, the run is refused.
+pub(crate) fn confirm_budget(config: &RalphConfig, confirmed: bool) -> Result<()> {
+    let (Some(budget), Some(threshold)) = (
+        config.event_loop.max_cost_usd,
+        config.event_loop.confirm_budget_above_usd,
+    ) else {
+        return Ok(());
+    };
+    if budget <= threshold || confirmed {
+        return Ok(());
+    }
+    if !stdin().is_terminal() {
+        bail!(
+            "The budget of ${budget:.2} is above the ${threshold:.2} confirmation threshold \
+             (event_loop.confirm_budget_above_usd). Pass --confirm-budget to start it."
+        );
+    }
+
+    eprint!(
+        "This run may spend up to ${budget:.2}, above the ${threshold:.2} confirmation \
+         threshold.\nType the budget ({budget}) to start: "
+    );
+    stderr().flush()?;
+    let mut typed = String::new();
+    stdin().read_line(&mut typed)?;
+    if !budget_matches(&typed, budget) {
+        bail!(
+            "Budget not confirmed (typed '{}'); the run was not started.",
+            typed.trim()
+        );
+    }
+    Ok(())
+}
+
+/// Whether `typed` is `budget`, allowing a leading `$` and thousands
+/// separators.
+fn budget_matches(typed: &str, budget: f64) -> bool {
+    typed
+        .trim()
+        .trim_start_matches('$')
+        .replace(',', "")
+        .parse::<f64>()
+        .is_ok_and(|typed| (typed - budget).abs() < 0.005)
+}
+
+/// Holds the loop on the splash screen until the user presses Enter.
+///
+/// Returns `false` if the run should not start: the user quit the TUI or
+/// the loop was interrupted.
+pub(crate) async fn wait_for_start(
+    state: &Arc<Mutex<TuiState>>,
+    tui_handle: &tokio::task::JoinHandle<anyhow::Result<()>>,
+    mut interrupt_rx: tokio::sync::watch::Receiver<bool>,
+) -> bool {
+    if let Ok(mut s) = state.lock() {
+        s.awaiting_start = true;
+    }
+    loop {
+        if !state.lock().is_ok_and(|s| s.awaiting_start) {
+            return true;
+        }
+        if tui_handle.is_finished() {
+            return false;
+        }
+        tokio::select! {
+            () = tokio::time::sleep(Duration::from_millis(100)) => {}
+            _ = interrupt_rx.changed() => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralph_core::HatConfig;
+
+    fn finished_run(iteration: u32, cost_usd: f64) -> RunStatus {
+        let mut status = RunStatus::new(None, 100, None);
+        status.phase = RunPhase::Finished;
+        status.iteration = iteration;
+        status.cost_usd = cost_usd;
+        status
+    }
+
+    #[test]
+    fn test_budget_must_be_typed_exactly() {
+        assert!(budget_matches("120\n", 120.0));
+        assert!(budget_matches("$120.00", 120.0));
+        assert!(budget_matches("1,200", 1200.0));
+        assert!(!budget_matches("12", 120.0));
+        assert!(!budget_matches("yes", 120.0));
+    }
+
+    #[test]
+    fn test_confirm_budget_below_threshold_or_confirmed() {
+        let mut config = RalphConfig::default();
+        config.event_loop.max_cost_usd = Some(20.0);
+        assert!(confirm_budget(&config, false).is_ok());
+
+        config.event_loop.max_cost_usd = Some(500.0);
+        assert!(confirm_budget(&config, true).is_ok());
+
+        config.event_loop.confirm_budget_above_usd = None;
+        assert!(confirm_budget(&config, false).is_ok());
+    }
+
+    #[test]
+    fn test_backend_model_forms() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            backend_model(&args(&["--verbose", "--model", "opus"])),
+            Some("opus".to_string())
+        );
+        assert_eq!(
+            backend_model(&args(&["--model=sonnet"])),
+            Some("sonnet".to_string())
+        );
+        assert_eq!(backend_model(&args(&["-m", "o3"])), Some("o3".to_string()));
+        assert_eq!(backend_model(&args(&["--verbose"])), None);
+    }
+
+    #[test]
+    fn test_estimate_cost_from_previous_run() {
+        let previous = finished_run(4, 2.0);
+        assert_eq!(
+            estimate_cost(10, None, Some(&previous)),
+            "up to $5.00 ($0.50/iteration in the last run)"
+        );
+        assert_eq!(
+            estimate_cost(10, Some(3.0), Some(&previous)),
+            "up to $3.00 ($0.50/iteration in the last run)"
+        );
+        assert_eq!(
+            estimate_cost(10, Some(3.0), None),
+            "up to $3.00 (max_cost_usd)"
+        );
+
+        // A run still in progress says nothing about a full run's cost.
+        let mut running = finished_run(4, 2.0);
+        running.phase = RunPhase::Running;
+        assert!(estimate_cost(10, None, Some(&running)).starts_with("unknown"));
+    }
+
+    #[test]
+    fn test_summary_rows() {
+        let mut config = RalphConfig::default();
+        config.cli.backend = "claude".to_string();
+        config.event_loop.max_iterations = 20;
+        config.event_loop.max_runtime_seconds = 3600;
+        config.event_loop.max_cost_usd = Some(10.0);
+        config.hats.insert(
+            "builder".to_string(),
+            serde_yaml::from_str::<HatConfig>("name: Builder\ndescription: Builds\n").unwrap(),
+        );
+        let mut backend = CliBackend::claude();
+        backend
+            .args
+            .extend(["--model".to_string(), "opus".to_string()]);
+        let gates = Gates {
+            tests: Some("cargo test".to_string()),
+            ..Gates::default()
+        };
+
+        let rows = startup_summary(
+            &config,
+            &backend,
+            "\n# Add dark mode\n\nToggle in settings.\n",
+            &gates,
+            None,
+        );
+        let row = |label: &str| {
+            rows.iter()
+                .find(|(l, _)| l == label)
+                .map(|(_, value)| value.as_str())
+                .unwrap()
+        };
+        assert_eq!(row("Model"), "opus");
+        assert_eq!(row("Hats"), "Builder");
+        assert_eq!(row("Limits"), "20 iterations · 1h 0m 0s · $10.00");
+        assert_eq!(row("Gates"), "tests: cargo test");
+        assert_eq!(row("Task"), "# Add dark mode Toggle in settings.");
+    }
+}
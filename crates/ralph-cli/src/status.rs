@@ -0,0 +1,311 @@
+//! CLI command for `ralph status`.
+//!
+//! Reports the current run's progress from `.ralph/run-status.json`, the
+//! control file and the tail of the run's events file. Nothing here talks to
+//! the loop process, so the command is cheap enough to run from a shell
+//! prompt on every redraw.
+//!
+//! `--tmux` prints a one-line segment with tmux color markup for
+//! `status-right`.
+
+use crate::display::{colors, format_elapsed, hat_emoji};
+use anyhow::Result;
+use clap::Parser;
+use ralph_core::{EventRecord, LoopContext, RunControl, RunPhase, RunStatus};
+use serde::Serialize;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// How much of the events file is read when looking for the last event.
+const EVENTS_TAIL_BYTES: u64 = 64 * 1024;
+
+/// Show the current run's progress.
+#[derive(Parser, Debug)]
+pub struct StatusArgs {
+    /// Print the status as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Print a compact segment for the tmux status line
+    #[arg(long, conflicts_with = "json")]
+    pub tmux: bool,
+}
+
+/// What the run is doing, as seen from outside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RunState {
+    Running,
+    Paused,
+    Finished,
+    /// The status says running but the process is gone (e.g. killed).
+    Dead,
+}
+
+#[derive(Debug, Serialize)]
+struct LastEvent {
+    topic: String,
+    ts: String,
+}
+
+#[derive(Debug, Serialize)]
+struct StatusReport {
+    running: bool,
+    state: RunState,
+    run_id: Option<String>,
+    pid: u32,
+    iteration: u32,
+    max_iterations: u32,
+    elapsed_secs: i64,
+    cost_usd: f64,
+    max_cost_usd: Option<f64>,
+    active_hat: Option<String>,
+    last_event: Option<LastEvent>,
+    termination_reason: Option<String>,
+    /// Concurrency group the next iteration waits for a slot in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    queued_for: Option<String>,
+}
+
+impl StatusReport {
+    fn new(ctx: &LoopContext, status: RunStatus) -> Self {
+        let state = match status.phase {
+            RunPhase::Finished => RunState::Finished,
+            RunPhase::Running if !status.is_running() => RunState::Dead,
+            RunPhase::Running => {
+                let paused = RunControl::new(ctx.control_path())
+                    .state()
+                    .is_ok_and(|control| control.paused);
+                if paused {
+                    RunState::Paused
+                } else {
+                    RunState::Running
+                }
+            }
+        };
+        let events_path = |run_id: &String| ctx.ralph_dir().join(format!("events-{run_id}.jsonl"));
+        let last_event = status
+            .run_id
+            .as_ref()
+            .and_then(|run_id| last_event(&events_path(run_id)));
+
+        Self {
+            running: matches!(state, RunState::Running | RunState::Paused),
+            state,
+            elapsed_secs: status.elapsed(chrono::Utc::now()).num_seconds(),
+            run_id: status.run_id,
+            pid: status.pid,
+            iteration: status.iteration,
+            max_iterations: status.max_iterations,
+            cost_usd: status.cost_usd,
+            max_cost_usd: status.max_cost_usd,
+            active_hat: status.active_hat,
+            last_event,
+            termination_reason: status.termination_reason,
+            queued_for: status.queued_for.filter(|_| state == RunState::Running),
+        }
+    }
+}
+
+/// Execute the status command.
+pub fn execute(args: StatusArgs, use_colors: bool) -> Result<()> {
+    let ctx = LoopContext::primary(std::env::current_dir()?);
+    let report =
+        RunStatus::read(&ctx.run_status_path())?.map(|status| StatusReport::new(&ctx, status));
+
+    if args.tmux {
+        // An empty segment keeps the status line clean when nothing ran.
+        println!("{}", report.as_ref().map(tmux_segment).unwrap_or_default());
+        return Ok(());
+    }
+
+    if args.json {
+        match &report {
+            Some(report) => println!("{}", serde_json::to_string(report)?),
+            None => println!("{}", serde_json::json!({ "running": false })),
+        }
+        return Ok(());
+    }
+
+    let Some(report) = report else {
+        println!("No run has been recorded in this workspace.");
+        return Ok(());
+    };
+    print_report(&report, use_colors);
+    Ok(())
+}
+
+fn print_report(report: &StatusReport, use_colors: bool) {
+    let (label, color) = match report.state {
+        RunState::Running => ("running", colors::GREEN),
+        RunState::Paused => ("paused", colors::YELLOW),
+        RunState::Finished => ("finished", colors::DIM),
+        RunState::Dead => ("not running (process exited)", colors::RED),
+    };
+    let label = if use_colors {
+        format!("{color}{label}{}", colors::RESET)
+    } else {
+        label.to_string()
+    };
+    println!(
+        "Run {} (pid {}): {label}",
+        report.run_id.as_deref().unwrap_or("-"),
+        report.pid
+    );
+    if let Some(reason) = &report.termination_reason {
+        println!("  Reason:     {reason}");
+    }
+    println!(
+        "  Iteration:  {}/{}",
+        report.iteration, report.max_iterations
+    );
+    println!(
+        "  Elapsed:    {}",
+        format_elapsed(std::time::Duration::from_secs(
+            report.elapsed_secs.max(0).unsigned_abs()
+        ))
+    );
+    match report.max_cost_usd {
+        Some(max) => println!("  Cost:       ${:.2} / ${max:.2}", report.cost_usd),
+        None => println!("  Cost:       ${:.2}", report.cost_usd),
+    }
+    if let Some(hat) = &report.active_hat {
+        println!("  Hat:        {hat}");
+    }
+    if let Some(group) = &report.queued_for {
+        println!("  Queued:     waiting for a slot in {group}");
+    }
+    if let Some(event) = &report.last_event {
+        println!("  Last event: {} ({})", event.topic, event.ts);
+    }
+}
+
+/// Formats the run as a tmux status segment, e.g. `▶ ? builder 3/100 $0.42`
+/// with the state glyph colored.
+fn tmux_segment(report: &StatusReport) -> String {
+    let (glyph, color) = match report.state {
+        RunState::Running => ("▶", "green"),
+        RunState::Paused => ("⏸", "yellow"),
+        RunState::Finished
+            if report.termination_reason.as_deref() == Some("completion_promise") =>
+        {
+            ("✓", "colour244")
+        }
+        RunState::Finished => ("■", "colour244"),
+        RunState::Dead => ("✗", "red"),
+    };
+    let hat = report
+        .active_hat
+        .as_deref()
+        .map(|hat| format!(" {} {hat}", hat_emoji(hat)))
+        .unwrap_or_default();
+    format!(
+        "#[fg={color}]{glyph}#[default]{hat} {}/{} ${:.2}",
+        report.iteration, report.max_iterations, report.cost_usd
+    )
+}
+
+/// Returns the last event recorded in `path`, reading only the file's tail.
+fn last_event(path: &Path) -> Option<LastEvent> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(EVENTS_TAIL_BYTES)))
+        .ok()?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).ok()?;
+
+    // The first line may be cut off by the seek; it just fails to parse.
+    String::from_utf8_lossy(&tail)
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str::<EventRecord>(line).ok())
+        .map(|record| LastEvent {
+            topic: record.topic,
+            ts: record.ts,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_last_event_skips_trailing_garbage() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("events.jsonl");
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"ts":"2026-01-27T14:30:22Z","topic":"task.start","payload":""}"#,
+                "\n",
+                r#"{"ts":"2026-01-27T14:31:00Z","topic":"build.done","payload":"ok"}"#,
+                "\n",
+                "{\"ts\": \"partial",
+            ),
+        )
+        .unwrap();
+
+        let event = last_event(&path).unwrap();
+        assert_eq!(event.topic, "build.done");
+        assert_eq!(event.ts, "2026-01-27T14:31:00Z");
+        assert!(last_event(&temp.path().join("missing.jsonl")).is_none());
+    }
+
+    #[test]
+    fn test_report_for_finished_run() {
+        let temp = TempDir::new().unwrap();
+        let ctx = LoopContext::primary(temp.path().to_path_buf());
+        let mut status = RunStatus::new(Some("20260127-143022".to_string()), 50, None);
+        status.phase = RunPhase::Finished;
+        status.iteration = 7;
+        status.termination_reason = Some("completion_promise".to_string());
+
+        let report = StatusReport::new(&ctx, status);
+        assert_eq!(report.state, RunState::Finished);
+        assert!(!report.running);
+        assert_eq!(report.iteration, 7);
+        assert!(report.last_event.is_none());
+        assert_eq!(
+            tmux_segment(&report),
+            "#[fg=colour244]✓#[default] 7/50 $0.00"
+        );
+    }
+
+    #[test]
+    fn test_tmux_segment_for_running_run() {
+        let temp = TempDir::new().unwrap();
+        let ctx = LoopContext::primary(temp.path().to_path_buf());
+        let mut status = RunStatus::new(None, 100, None);
+        status.iteration = 3;
+        status.cost_usd = 0.421;
+        status.active_hat = Some("builder".to_string());
+
+        // This process wrote the status, so it is alive.
+        let report = StatusReport::new(&ctx, status);
+        assert_eq!(
+            tmux_segment(&report),
+            format!(
+                "#[fg=green]▶#[default] {} builder 3/100 $0.42",
+                hat_emoji("builder")
+            )
+        );
+    }
+
+    #[test]
+    fn test_report_shows_queue_only_while_running() {
+        let temp = TempDir::new().unwrap();
+        let ctx = LoopContext::primary(temp.path().to_path_buf());
+        let mut status = RunStatus::new(None, 100, None);
+        status.queued_for = Some("anthropic-team (2/2 slots busy)".to_string());
+
+        let report = StatusReport::new(&ctx, status.clone());
+        assert_eq!(
+            report.queued_for.as_deref(),
+            Some("anthropic-team (2/2 slots busy)")
+        );
+
+        status.phase = RunPhase::Finished;
+        assert!(StatusReport::new(&ctx, status).queued_for.is_none());
+    }
+}
@@ -0,0 +1,163 @@
+//! Output alert matching.
+//!
+//! Alert rules (`alerts:` in config) are regexes checked against agent output.
+//! A match can highlight the text in the TUI, notify the user, or pause the
+//! loop until the user resumes it. The matcher only reports hits; acting on
+//! them is up to the caller.
+
+use crate::config::{AlertAction, AlertRule};
+use regex::Regex;
+use std::ops::Range;
+
+/// A single alert rule that matched some output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertHit {
+    /// The rule's label (its name, or the pattern when unnamed).
+    pub name: String,
+    /// The first text the rule matched.
+    pub matched: String,
+    /// Actions configured for the rule.
+    pub actions: Vec<AlertAction>,
+}
+
+impl AlertHit {
+    /// Returns true if the rule asked for `action`.
+    pub fn wants(&self, action: AlertAction) -> bool {
+        self.actions.contains(&action)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    name: String,
+    regex: Regex,
+    actions: Vec<AlertAction>,
+}
+
+/// Compiled set of alert rules.
+#[derive(Debug, Clone, Default)]
+pub struct AlertMatcher {
+    rules: Vec<CompiledRule>,
+}
+
+impl AlertMatcher {
+    /// Compiles the given rules.
+    ///
+    /// Patterns are checked by `RalphConfig::validate`, so this only fails for
+    /// configs that skipped validation.
+    pub fn new(rules: &[AlertRule]) -> Result<Self, regex::Error> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                Ok(CompiledRule {
+                    name: rule.label().to_string(),
+                    regex: Regex::new(&rule.pattern)?,
+                    actions: rule.actions.clone(),
+                })
+            })
+            .collect::<Result<_, regex::Error>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Returns true if no rules are configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns one hit per rule that matches `text`, in rule order.
+    pub fn scan(&self, text: &str) -> Vec<AlertHit> {
+        self.rules
+            .iter()
+            .filter_map(|rule| {
+                rule.regex.find(text).map(|m| AlertHit {
+                    name: rule.name.clone(),
+                    matched: m.as_str().to_string(),
+                    actions: rule.actions.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the byte ranges of `text` matched by rules with the
+    /// `highlight` action, sorted and merged so they never overlap.
+    pub fn highlight_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.actions.contains(&AlertAction::Highlight))
+            .flat_map(|rule| rule.regex.find_iter(text).map(|m| m.range()))
+            .filter(|range| !range.is_empty())
+            .collect();
+        ranges.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: Option<&str>, pattern: &str, actions: &[AlertAction]) -> AlertRule {
+        AlertRule {
+            name: name.map(str::to_string),
+            pattern: pattern.to_string(),
+            actions: actions.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_scan_reports_one_hit_per_matching_rule() {
+        let matcher = AlertMatcher::new(&[
+            rule(None, r"(?i)delet\w+", &[AlertAction::Notify]),
+            rule(Some("payments"), "payments_service", &[AlertAction::Pause]),
+            rule(None, "unrelated", &[AlertAction::Highlight]),
+        ])
+        .unwrap();
+
+        let hits = matcher.scan("Deleting old rows, then deleting the payments_service cache");
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].name, r"(?i)delet\w+");
+        assert_eq!(hits[0].matched, "Deleting");
+        assert!(hits[0].wants(AlertAction::Notify));
+        assert_eq!(hits[1].name, "payments");
+        assert!(hits[1].wants(AlertAction::Pause));
+        assert!(!hits[1].wants(AlertAction::Highlight));
+    }
+
+    #[test]
+    fn test_highlight_ranges_only_use_highlight_rules() {
+        let matcher = AlertMatcher::new(&[
+            rule(None, "foo", &[AlertAction::Highlight]),
+            rule(None, "bar", &[AlertAction::Notify]),
+        ])
+        .unwrap();
+
+        assert_eq!(matcher.highlight_ranges("foo bar foo"), vec![0..3, 8..11]);
+    }
+
+    #[test]
+    fn test_highlight_ranges_merge_overlaps() {
+        let matcher = AlertMatcher::new(&[
+            rule(None, "delete", &[AlertAction::Highlight]),
+            rule(None, "let", &[AlertAction::Highlight]),
+            rule(None, "ted", &[AlertAction::Highlight]),
+        ])
+        .unwrap();
+
+        assert_eq!(matcher.highlight_ranges("deleted"), vec![0..7]);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_error() {
+        assert!(AlertMatcher::new(&[rule(None, "(", &[AlertAction::Highlight])]).is_err());
+        assert!(AlertMatcher::new(&[]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+//! Append-only audit log of control actions.
+//!
+//! When several people watch a run, it matters who paused, aborted or steered
+//! it. Every such action is appended to `.ralph/audit.jsonl` together with a
+//! timestamp, where it came from, and (when known) who did it. Entries are
+//! never rewritten; the loop summary lists the entries for its run.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// What was done to the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// The loop was paused.
+    Pause,
+    /// A paused loop was resumed.
+    Resume,
+    /// The loop was stopped.
+    Abort,
+    /// A client became the run's controller.
+    TakeControl,
+    /// The controller handed the role to another client.
+    HandOverControl,
+    /// The controller gave up the role.
+    ReleaseControl,
+    /// Guidance was injected into the next prompt (`human.guidance`).
+    Guidance,
+    /// A question from the agent was answered (`human.response`).
+    Response,
+}
+
+impl AuditAction {
+    /// Returns the name used in the audit file.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::Abort => "abort",
+            Self::TakeControl => "take_control",
+            Self::HandOverControl => "hand_over_control",
+            Self::ReleaseControl => "release_control",
+            Self::Guidance => "guidance",
+            Self::Response => "response",
+        }
+    }
+}
+
+/// Where an action came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSource {
+    /// The TUI attached to the loop process.
+    Tui,
+    /// The terminal of a loop running without the TUI.
+    Console,
+    /// A `ralph` subcommand such as `ralph control` or `ralph emit`.
+    Cli,
+    /// The HTTP control API (`ralph attach --http`).
+    Http,
+    /// A process signal (SIGINT, SIGTERM, SIGHUP).
+    Signal,
+    /// An output alert rule.
+    Alert,
+    /// The Telegram bot.
+    Telegram,
+    /// A battery or suspend event (`power:`).
+    Power,
+}
+
+impl AuditSource {
+    /// Returns the name used in the audit file.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tui => "tui",
+            Self::Console => "console",
+            Self::Cli => "cli",
+            Self::Http => "http",
+            Self::Signal => "signal",
+            Self::Alert => "alert",
+            Self::Telegram => "telegram",
+            Self::Power => "power",
+        }
+    }
+}
+
+/// One line of the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// RFC 3339 timestamp.
+    pub ts: String,
+
+    /// Run the action applied to, if one was active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+
+    pub action: AuditAction,
+    pub source: AuditSource,
+
+    /// Who performed the action (controller name, user, signal name).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+
+    /// Free-form detail, e.g. the alert name or the injected text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl AuditEntry {
+    /// Creates an entry timestamped now.
+    pub fn new(action: AuditAction, source: AuditSource) -> Self {
+        Self {
+            ts: chrono::Utc::now().to_rfc3339(),
+            run_id: None,
+            action,
+            source,
+            actor: None,
+            detail: None,
+        }
+    }
+
+    /// Sets who performed the action.
+    #[must_use]
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    /// Sets a detail for the action.
+    #[must_use]
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// Appends to and reads the audit log.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+    run_id: Option<String>,
+}
+
+impl AuditLog {
+    /// Creates a log writing to `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            run_id: None,
+        }
+    }
+
+    /// Tags entries recorded through this log with `run_id`.
+    #[must_use]
+    pub fn with_run_id(mut self, run_id: Option<String>) -> Self {
+        self.run_id = run_id;
+        self
+    }
+
+    /// Returns the audit file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends an entry, filling in the run ID if the entry has none.
+    pub fn record(&self, mut entry: AuditEntry) -> io::Result<()> {
+        if entry.run_id.is_none() {
+            entry.run_id.clone_from(&self.run_id);
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let line = serde_json::to_string(&entry)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        // One write per line keeps concurrent appends from interleaving
+        file.write_all(format!("{line}\n").as_bytes())
+    }
+
+    /// Appends an entry, logging rather than returning a failure.
+    ///
+    /// Control actions must not fail because the audit file is unwritable.
+    pub fn record_or_warn(&self, entry: AuditEntry) {
+        if let Err(e) = self.record(entry) {
+            tracing::warn!("Failed to write audit log {}: {}", self.path.display(), e);
+        }
+    }
+
+    /// Reads all entries, skipping malformed lines. A missing file is empty.
+    pub fn entries(&self) -> io::Result<Vec<AuditEntry>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Reads the entries recorded for this log's run.
+    pub fn run_entries(&self) -> io::Result<Vec<AuditEntry>> {
+        let mut entries = self.entries()?;
+        entries.retain(|entry| entry.run_id == self.run_id);
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_appends_and_tags_run() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(".ralph/audit.jsonl");
+        let log = AuditLog::new(&path).with_run_id(Some("20260101-000000".to_string()));
+
+        log.record(AuditEntry::new(AuditAction::Pause, AuditSource::Cli).with_actor("alice"))
+            .unwrap();
+        log.record(AuditEntry::new(AuditAction::Abort, AuditSource::Signal).with_detail("SIGTERM"))
+            .unwrap();
+
+        let entries = log.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, AuditAction::Pause);
+        assert_eq!(entries[0].actor.as_deref(), Some("alice"));
+        assert_eq!(entries[1].run_id.as_deref(), Some("20260101-000000"));
+        assert!(
+            fs::read_to_string(&path)
+                .unwrap()
+                .contains(r#""source":"signal""#)
+        );
+    }
+
+    #[test]
+    fn test_run_entries_filters_other_runs() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("audit.jsonl");
+        let first = AuditLog::new(&path).with_run_id(Some("a".to_string()));
+        let second = AuditLog::new(&path).with_run_id(Some("b".to_string()));
+
+        first
+            .record(AuditEntry::new(AuditAction::TakeControl, AuditSource::Cli))
+            .unwrap();
+        second
+            .record(AuditEntry::new(AuditAction::Resume, AuditSource::Tui))
+            .unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        let entries = second.run_entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, AuditAction::Resume);
+    }
+
+    #[test]
+    fn test_missing_file_has_no_entries() {
+        let temp = TempDir::new().unwrap();
+        let log = AuditLog::new(temp.path().join("audit.jsonl"));
+        assert!(log.entries().unwrap().is_empty());
+    }
+}
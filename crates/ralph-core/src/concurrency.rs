@@ -0,0 +1,180 @@
+//! Concurrency groups: limits on iterations running at once across runs.
+//!
+//! Daemon runs and parallel worktree loops each respect their own limits,
+//! but they often share one API key whose rate limit they can exceed
+//! together. A group (`daemon.concurrency_groups`) names the backends drawing
+//! on one key and how many iterations may use it at once. Each slot is an
+//! exclusive `flock` on `.ralph/concurrency/<group>/slot-<n>` in the main
+//! repository, so the limit holds across processes and is released when a
+//! run dies. The holder also writes its pid to `slot-<n>.pid`, so slots can
+//! be counted without touching the locks.
+
+use crate::config::ConcurrencyGroupConfig;
+use crate::file_lock::{FileLock, LockGuard};
+use crate::run_status::process_exists;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The configured groups of one repository.
+#[derive(Debug, Clone)]
+pub struct ConcurrencyGroups {
+    dir: PathBuf,
+    groups: Vec<ConcurrencyGroupConfig>,
+}
+
+/// Permission to run one iteration; the slot is released on drop.
+#[derive(Debug)]
+pub struct ConcurrencySlot {
+    /// Pid file naming this process as the holder.
+    pid_file: Option<PathBuf>,
+    _guard: Option<LockGuard>,
+}
+
+impl Drop for ConcurrencySlot {
+    fn drop(&mut self) {
+        // Before the lock is released, so the next holder's file survives
+        if let Some(pid_file) = &self.pid_file {
+            let _ = std::fs::remove_file(pid_file);
+        }
+    }
+}
+
+impl ConcurrencyGroups {
+    /// Groups whose slots live under `repo_root/.ralph/concurrency`.
+    pub fn new(groups: &[ConcurrencyGroupConfig], repo_root: &Path) -> Self {
+        Self {
+            dir: repo_root.join(".ralph/concurrency"),
+            groups: groups.to_vec(),
+        }
+    }
+
+    /// The first group listing `backend`, if any.
+    pub fn group_for(&self, backend: &str) -> Option<&ConcurrencyGroupConfig> {
+        self.groups
+            .iter()
+            .find(|group| group.backends.iter().any(|b| b == backend))
+    }
+
+    /// Takes a free slot for an iteration on `backend`.
+    ///
+    /// Returns `Ok(None)` while every slot of the backend's group is taken.
+    /// Backends outside any group always get a (lock-free) slot.
+    pub fn try_acquire(&self, backend: &str) -> io::Result<Option<ConcurrencySlot>> {
+        let Some(group) = self.group_for(backend) else {
+            return Ok(Some(ConcurrencySlot {
+                pid_file: None,
+                _guard: None,
+            }));
+        };
+        for slot in 0..group.max_concurrent.max(1) {
+            if let Some(guard) = self.slot_lock(&group.name, slot)?.try_exclusive()? {
+                let pid_file = self.slot_pid_file(&group.name, slot);
+                std::fs::write(&pid_file, std::process::id().to_string())?;
+                return Ok(Some(ConcurrencySlot {
+                    pid_file: Some(pid_file),
+                    _guard: Some(guard),
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Number of slots of `group` currently held by iterations.
+    ///
+    /// Counted from the holders' pid files rather than by probing the locks,
+    /// which would briefly take free slots from runs trying to acquire them.
+    /// A holder that died without cleaning up doesn't count.
+    pub fn busy(&self, group: &ConcurrencyGroupConfig) -> io::Result<u32> {
+        let mut busy = 0;
+        for slot in 0..group.max_concurrent.max(1) {
+            let holder = match std::fs::read_to_string(self.slot_pid_file(&group.name, slot)) {
+                Ok(pid) => pid.trim().parse::<u32>().ok(),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e),
+            };
+            if holder.is_some_and(process_exists) {
+                busy += 1;
+            }
+        }
+        Ok(busy)
+    }
+
+    fn slot_lock(&self, group: &str, slot: u32) -> io::Result<FileLock> {
+        FileLock::new(self.dir.join(group).join(format!("slot-{slot}")))
+    }
+
+    fn slot_pid_file(&self, group: &str, slot: u32) -> PathBuf {
+        self.dir.join(group).join(format!("slot-{slot}.pid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn team_key_groups(root: &Path) -> ConcurrencyGroups {
+        ConcurrencyGroups::new(
+            &[ConcurrencyGroupConfig {
+                name: "team-key".to_string(),
+                backends: vec!["claude".to_string()],
+                max_concurrent: 2,
+            }],
+            root,
+        )
+    }
+
+    #[test]
+    fn test_slots_limit_concurrent_iterations() {
+        let temp = TempDir::new().unwrap();
+        let groups = team_key_groups(temp.path());
+        let group = groups.group_for("claude").unwrap().clone();
+
+        let first = groups.try_acquire("claude").unwrap();
+        let second = groups.try_acquire("claude").unwrap();
+        assert!(first.is_some() && second.is_some());
+        assert_eq!(groups.busy(&group).unwrap(), 2);
+        assert!(groups.try_acquire("claude").unwrap().is_none());
+
+        // Another run sharing the repository sees the same slots
+        assert!(
+            team_key_groups(temp.path())
+                .try_acquire("claude")
+                .unwrap()
+                .is_none()
+        );
+
+        drop(first);
+        assert_eq!(groups.busy(&group).unwrap(), 1);
+        assert!(groups.try_acquire("claude").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_busy_leaves_free_slots_alone() {
+        let temp = TempDir::new().unwrap();
+        let groups = team_key_groups(temp.path());
+        let group = groups.group_for("claude").unwrap().clone();
+        let _held = groups.try_acquire("claude").unwrap().unwrap();
+
+        // Counting holds no lock, so the free slot stays free meanwhile
+        let lock = groups.slot_lock("team-key", 1).unwrap();
+        let probe = lock.try_exclusive().unwrap();
+        assert!(probe.is_some());
+        assert_eq!(groups.busy(&group).unwrap(), 1);
+        drop(probe);
+
+        // A holder that died without releasing its pid file isn't counted
+        std::fs::write(groups.slot_pid_file("team-key", 1), "4294967").unwrap();
+        assert_eq!(groups.busy(&group).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_backend_outside_groups_is_not_limited() {
+        let temp = TempDir::new().unwrap();
+        let groups = team_key_groups(temp.path());
+        assert!(groups.group_for("gemini").is_none());
+        let _held: Vec<_> = (0..3)
+            .map(|_| groups.try_acquire("gemini").unwrap().unwrap())
+            .collect();
+    }
+}
@@ -5,7 +5,7 @@
 
 use ralph_proto::Topic;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use tracing::debug;
 
@@ -16,6 +16,7 @@ use tracing::debug;
 /// - v2: `cli: { backend: claude }`, `event_loop: { max_iterations: 100 }`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::struct_excessive_bools)] // Configuration struct with multiple feature flags
+#[non_exhaustive]
 pub struct RalphConfig {
     /// Event loop configuration (v2 nested style).
     #[serde(default)]
@@ -80,7 +81,7 @@ pub struct RalphConfig {
     #[serde(default)]
     pub verbose: bool,
 
-    /// Archive prompts after completion (DEFERRED: warn if enabled).
+    /// Archive each iteration's composed prompt for `ralph prompts`.
     #[serde(default)]
     pub archive_prompts: bool,
 
@@ -133,6 +134,66 @@ pub struct RalphConfig {
     /// RObot (Ralph-Orchestrator bot) configuration for Telegram-based interaction.
     #[serde(default, rename = "RObot")]
     pub robot: RobotConfig,
+
+    /// Output alert rules, checked against agent output after each iteration.
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+
+    /// One-line summary templates for tool calls, keyed by tool name.
+    ///
+    /// Placeholders are `{{input.<dotted.path>}}` or a JSON pointer such as
+    /// `{{/target/region}}`, e.g. `Deploy: "{{input.environment}} {{input.service}}"`.
+    #[serde(default)]
+    pub tool_summaries: HashMap<String, String>,
+
+    /// Access control for the HTTP API served by `ralph attach --http`.
+    #[serde(default)]
+    pub http_api: HttpApiConfig,
+
+    /// Notification channels for finished runs and alerts.
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+
+    /// Settings for `ralph bot daemon`.
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+
+    /// Further repositories the run works across, e.g. an API and its client.
+    #[serde(default)]
+    pub repos: Vec<RepoConfig>,
+
+    /// Monorepo packages the run is limited to.
+    #[serde(default)]
+    pub scope: ScopeConfig,
+
+    /// Commands behind the backpressure checks; unset ones are detected
+    /// from the project type.
+    #[serde(default)]
+    pub gates: GatesConfig,
+
+    /// Pausing the loop on low battery or before suspend.
+    #[serde(default)]
+    pub power: PowerConfig,
+
+    /// Housekeeping commands run between iterations.
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+
+    /// Post-mortem written when a run ends abnormally.
+    #[serde(default)]
+    pub post_mortem: PostMortemConfig,
+
+    /// Encryption of run logs, session recordings and tool results at rest.
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+
+    /// Flagging iterations whose cost or tokens are outliers.
+    #[serde(default)]
+    pub cost_anomaly: CostAnomalyConfig,
+
+    /// Asking an interactive agent to wrap up before an abort stops it.
+    #[serde(default)]
+    pub checkpoint: CheckpointConfig,
 }
 
 fn default_true() -> bool {
@@ -178,6 +239,30 @@ impl Default for RalphConfig {
             features: FeaturesConfig::default(),
             // RObot (Ralph-Orchestrator bot)
             robot: RobotConfig::default(),
+            // Output alerts
+            alerts: Vec::new(),
+            // Tool call summaries
+            tool_summaries: HashMap::new(),
+            // HTTP API access
+            http_api: HttpApiConfig::default(),
+            // Run notifications
+            notifications: NotificationsConfig::default(),
+            // Daemon mode
+            daemon: DaemonConfig::default(),
+            // Multi-repo runs
+            repos: Vec::new(),
+            // Monorepo scoping
+            scope: ScopeConfig::default(),
+            // Backpressure gate commands
+            gates: GatesConfig::default(),
+            // Battery and suspend pauses
+            power: PowerConfig::default(),
+            // Between-iteration housekeeping
+            maintenance: MaintenanceConfig::default(),
+            post_mortem: PostMortemConfig::default(),
+            encryption: EncryptionConfig::default(),
+            cost_anomaly: CostAnomalyConfig::default(),
+            checkpoint: CheckpointConfig::default(),
         }
     }
 }
@@ -204,6 +289,40 @@ pub struct AdaptersConfig {
     /// Amp adapter settings.
     #[serde(default)]
     pub amp: AdapterSettings,
+
+    /// Adapters defined in config, selectable by name like the built-in ones.
+    #[serde(default)]
+    pub plugins: BTreeMap<String, PluginAdapterConfig>,
+}
+
+/// A command-line agent registered as a named adapter.
+///
+/// Unlike `backend: custom`, any number of these can be defined and hats
+/// can pick them by name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginAdapterConfig {
+    /// Command to execute.
+    pub command: String,
+
+    /// Arguments passed before the prompt.
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// How to pass the prompt: "arg" or "stdin".
+    #[serde(default)]
+    pub prompt_mode: PromptMode,
+
+    /// Flag preceding the prompt in arg mode; positional if unset.
+    #[serde(default)]
+    pub prompt_flag: Option<String>,
+
+    /// One-line summary shown by `ralph backends list`.
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Timeout, auto-detection and rate limit, as for the built-ins.
+    #[serde(flatten)]
+    pub settings: AdapterSettings,
 }
 
 /// Per-adapter settings.
@@ -220,6 +339,11 @@ pub struct AdapterSettings {
     /// Tool permissions (DROPPED: CLI tool manages its own permissions).
     #[serde(default)]
     pub tool_permissions: Option<Vec<String>>,
+
+    /// Token-per-minute limit; iterations on this backend wait until the
+    /// last minute's usage is below it.
+    #[serde(default)]
+    pub tokens_per_minute: Option<u64>,
 }
 
 fn default_timeout() -> u64 {
@@ -232,6 +356,7 @@ impl Default for AdapterSettings {
             timeout: default_timeout(),
             enabled: true,
             tool_permissions: None,
+            tokens_per_minute: None,
         }
     }
 }
@@ -335,7 +460,7 @@ impl RalphConfig {
     /// Validates the configuration and returns warnings.
     ///
     /// This method checks for:
-    /// - Deferred features that are enabled (archive_prompts, enable_metrics)
+    /// - Deferred features that are enabled (enable_metrics)
     /// - Dropped fields that are present (max_tokens, retry_delay, tool_permissions)
     /// - Ambiguous trigger routing across custom hats
     /// - Mutual exclusivity of prompt and prompt_file
@@ -367,13 +492,6 @@ impl RalphConfig {
         }
 
         // Check for deferred features
-        if self.archive_prompts {
-            warnings.push(ConfigWarning::DeferredFeature {
-                field: "archive_prompts".to_string(),
-                message: "Feature not yet available in v2".to_string(),
-            });
-        }
-
         if self.enable_metrics {
             warnings.push(ConfigWarning::DeferredFeature {
                 field: "enable_metrics".to_string(),
@@ -411,6 +529,33 @@ impl RalphConfig {
         // Validate RObot config
         self.robot.validate()?;
 
+        // Alert patterns must be valid regexes
+        for rule in &self.alerts {
+            if let Err(e) = regex::Regex::new(&rule.pattern) {
+                return Err(ConfigError::InvalidAlertPattern {
+                    pattern: rule.pattern.clone(),
+                    message: e.to_string(),
+                });
+            }
+        }
+
+        // Schedule times must parse, or the run would silently drop out of the feeds
+        if let Some(schedule) = self.daemon.schedules.iter().find(|s| s.time().is_none()) {
+            return Err(ConfigError::InvalidScheduleTime {
+                name: schedule.name.clone(),
+                at: schedule.at.clone(),
+            });
+        }
+
+        // Repo names label checkpoints and diffs, so they must be unique
+        for (i, repo) in self.repos.iter().enumerate() {
+            if self.repos[..i].iter().any(|other| other.name == repo.name) {
+                return Err(ConfigError::DuplicateRepo {
+                    name: repo.name.clone(),
+                });
+            }
+        }
+
         // Check for required description field on all hats
         for (hat_id, hat_config) in &self.hats {
             if hat_config
@@ -483,7 +628,11 @@ impl RalphConfig {
             "kiro" => &self.adapters.kiro,
             "codex" => &self.adapters.codex,
             "amp" => &self.adapters.amp,
-            _ => &self.adapters.claude, // Default fallback
+            name => self
+                .adapters
+                .plugins
+                .get(name)
+                .map_or(&self.adapters.claude, |plugin| &plugin.settings), // Default fallback
         }
     }
 }
@@ -539,6 +688,12 @@ pub struct EventLoopConfig {
     /// Maximum cost in USD before stopping.
     pub max_cost_usd: Option<f64>,
 
+    /// A `max_cost_usd` above this many USD must be confirmed before the
+    /// run starts, by typing the amount or passing `--confirm-budget`.
+    /// `null` turns the check off.
+    #[serde(default = "default_confirm_budget_above")]
+    pub confirm_budget_above_usd: Option<f64>,
+
     /// Stop after this many consecutive failures.
     #[serde(default = "default_max_failures")]
     pub max_consecutive_failures: u32,
@@ -561,6 +716,19 @@ pub struct EventLoopConfig {
     /// If not specified and hats are defined, Ralph will determine the appropriate
     /// event from the hat topology.
     pub starting_event: Option<String>,
+
+    /// Token budget for the failing output and diff hunks attached to
+    /// `build.blocked` / `review.blocked` when backpressure rejects
+    /// `build.done` or `review.done`. 0 sends only the generic note.
+    #[serde(default = "default_failure_feedback_tokens")]
+    pub failure_feedback_tokens: usize,
+
+    /// Token budget for each event payload carried into the next prompt.
+    /// Larger payloads (pasted logs, file dumps) are cut down to their
+    /// error lines, head and tail; the full text is kept under
+    /// `.ralph/tool-results/`. 0 passes payloads through unchanged.
+    #[serde(default = "default_payload_summary_tokens")]
+    pub payload_summary_tokens: usize,
 }
 
 fn default_prompt_file() -> String {
@@ -583,6 +751,18 @@ fn default_max_failures() -> u32 {
     5
 }
 
+fn default_failure_feedback_tokens() -> usize {
+    2000
+}
+
+fn default_payload_summary_tokens() -> usize {
+    4000
+}
+
+fn default_confirm_budget_above() -> Option<f64> {
+    Some(50.0)
+}
+
 impl Default for EventLoopConfig {
     fn default() -> Self {
         Self {
@@ -592,10 +772,13 @@ impl Default for EventLoopConfig {
             max_iterations: default_max_iterations(),
             max_runtime_seconds: default_max_runtime(),
             max_cost_usd: None,
+            confirm_budget_above_usd: default_confirm_budget_above(),
             max_consecutive_failures: default_max_failures(),
             cooldown_delay_seconds: 0,
             starting_hat: None,
             starting_event: None,
+            failure_feedback_tokens: default_failure_feedback_tokens(),
+            payload_summary_tokens: default_payload_summary_tokens(),
         }
     }
 }
@@ -719,6 +902,20 @@ pub struct CliConfig {
     /// If None, defaults to "-p" for arg mode.
     #[serde(default)]
     pub prompt_flag: Option<String>,
+
+    /// Send a minimal request through the backend before iteration 1, so
+    /// auth or configuration problems stop the run immediately.
+    #[serde(default)]
+    pub probe: bool,
+
+    /// Seconds the probe may take before it counts as failed.
+    #[serde(default = "default_probe_timeout")]
+    pub probe_timeout_secs: u32,
+
+    /// Model passed to the backend as `--model <model>`, replacing any
+    /// `--model` already in its arguments. Unset leaves the backend default.
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 fn default_backend() -> String {
@@ -737,6 +934,10 @@ fn default_idle_timeout() -> u32 {
     30 // 30 seconds per spec
 }
 
+fn default_probe_timeout() -> u32 {
+    60
+}
+
 impl Default for CliConfig {
     fn default() -> Self {
         Self {
@@ -747,6 +948,9 @@ impl Default for CliConfig {
             idle_timeout_secs: default_idle_timeout(),
             args: Vec::new(),
             prompt_flag: None,
+            probe: false,
+            probe_timeout_secs: default_probe_timeout(),
+            model: None,
         }
     }
 }
@@ -757,6 +961,228 @@ pub struct TuiConfig {
     /// Prefix key combination (e.g., "ctrl-a", "ctrl-b").
     #[serde(default = "default_prefix_key")]
     pub prefix_key: String,
+
+    /// Footer segments, rendered left to right in this order.
+    ///
+    /// A `spacer` pushes the segments after it to the right edge. When the
+    /// terminal is too narrow, lower-priority segments are dropped first.
+    #[serde(default = "default_footer_segments")]
+    pub footer: Vec<FooterSegment>,
+
+    /// Characters of each tool result kept in the TUI buffer.
+    ///
+    /// Full results are written to `.ralph/tool-results/` and can be opened
+    /// from the TUI with `o`.
+    #[serde(default = "default_tool_result_preview")]
+    pub tool_result_preview: usize,
+
+    /// Show the run summary before iteration 1 and wait for Enter.
+    /// `ralph run --yes` skips the wait.
+    #[serde(default = "default_true")]
+    pub confirm_start: bool,
+
+    /// Color theme of the TUI.
+    #[serde(default)]
+    pub theme: TuiTheme,
+
+    /// Steady-rate display of bursty agent text, in the TUI and the pretty
+    /// console output.
+    #[serde(default)]
+    pub smooth_streaming: SmoothStreamingConfig,
+
+    /// Characters of agent text rendered per second; text beyond that is
+    /// condensed so errors and tool calls are never held up behind it.
+    /// `0` renders everything.
+    #[serde(default = "default_text_render_budget")]
+    pub text_render_budget: usize,
+}
+
+/// Color theme of the TUI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TuiTheme {
+    /// Colors for dark terminal backgrounds.
+    #[default]
+    Dark,
+    /// Pale colors darkened for light terminal backgrounds.
+    Light,
+    /// No colors; emphasis through text attributes only.
+    Mono,
+}
+
+impl TuiTheme {
+    /// The theme after this one, for cycling through them.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Dark => Self::Light,
+            Self::Light => Self::Mono,
+            Self::Mono => Self::Dark,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Dark => "dark",
+            Self::Light => "light",
+            Self::Mono => "mono",
+        }
+    }
+}
+
+fn default_tool_result_preview() -> usize {
+    200
+}
+
+fn default_text_render_budget() -> usize {
+    8000
+}
+
+/// Smooth streaming settings.
+///
+/// Agent text arrives in bursts; with smooth streaming it is shown at a
+/// steady character rate instead. The display may trail the stream by at
+/// most `max_lag_ms`; beyond that, text is shown as it arrives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmoothStreamingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Characters shown per second.
+    #[serde(default = "default_stream_chars_per_second")]
+    pub chars_per_second: u32,
+
+    /// How far the display may trail the stream, in milliseconds.
+    #[serde(default = "default_stream_max_lag_ms")]
+    pub max_lag_ms: u64,
+}
+
+fn default_stream_chars_per_second() -> u32 {
+    600
+}
+
+fn default_stream_max_lag_ms() -> u64 {
+    1000
+}
+
+impl Default for SmoothStreamingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chars_per_second: default_stream_chars_per_second(),
+            max_lag_ms: default_stream_max_lag_ms(),
+        }
+    }
+}
+
+/// A segment of the TUI footer status bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FooterSegment {
+    /// Most recent event topic.
+    LastEvent,
+    /// Hat that will handle the next event.
+    Hat,
+    /// Loop activity indicator (ACTIVE/DONE).
+    Indicator,
+    /// Accumulated cost, against `max_cost_usd` when set.
+    Budget,
+    /// Total elapsed run time.
+    Clock,
+    /// Local wall-clock time.
+    WallClock,
+    /// Estimated time until the run reaches its iteration or budget limit.
+    Eta,
+    /// Iteration counter, against `max_iterations`.
+    Iteration,
+    /// Key binding hints.
+    Keys,
+    /// Flexible gap; segments after it are right-aligned.
+    Spacer,
+}
+
+impl FooterSegment {
+    /// Drop priority on narrow terminals (lower = kept longer).
+    pub fn priority(self) -> u8 {
+        match self {
+            Self::Indicator | Self::Spacer => 0,
+            Self::Clock => 1,
+            Self::Iteration => 2,
+            Self::Budget | Self::Eta => 3,
+            Self::Hat => 4,
+            Self::LastEvent | Self::WallClock => 5,
+            Self::Keys => 6,
+        }
+    }
+}
+
+/// A regex rule that fires when it matches agent output.
+///
+/// Example configuration:
+/// ```yaml
+/// alerts:
+///   - pattern: "(?i)\\bdelet(e|ing)\\b"
+///     actions: [highlight, notify]
+///   - name: payments
+///     pattern: "payments_service"
+///     actions: [highlight, pause]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertRule {
+    /// Label shown when the rule fires. Defaults to the pattern itself.
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Regex matched against agent output.
+    pub pattern: String,
+
+    /// What to do when the pattern matches.
+    #[serde(default = "default_alert_actions")]
+    pub actions: Vec<AlertAction>,
+}
+
+impl AlertRule {
+    /// Returns the rule's display label.
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.pattern)
+    }
+}
+
+fn default_alert_actions() -> Vec<AlertAction> {
+    vec![AlertAction::Highlight]
+}
+
+/// Action taken when an alert rule matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertAction {
+    /// Highlight the matched text in the output.
+    Highlight,
+    /// Ring the terminal bell and log a warning.
+    Notify,
+    /// Pause the loop after the iteration until the user resumes it.
+    Pause,
+}
+
+/// How to pass prompts to a CLI tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptMode {
+    /// Pass prompt as a command-line argument.
+    #[default]
+    Arg,
+    /// Write prompt to stdin.
+    Stdin,
+}
+
+/// Required shape of a hat's response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    /// Free-form text (the default).
+    #[default]
+    Text,
+    /// A single JSON value and nothing else.
+    Json,
 }
 
 /// Memory injection mode.
@@ -1108,6 +1534,13 @@ pub struct FeaturesConfig {
     /// improvements and learnings based on the original objective.
     #[serde(default)]
     pub chaos_mode: ChaosModeConfig,
+
+    /// Router configuration.
+    ///
+    /// When several hats have pending events, a cheap classification call
+    /// picks the one that works next instead of activating them all.
+    #[serde(default)]
+    pub router: RouterConfig,
 }
 
 impl Default for FeaturesConfig {
@@ -1117,6 +1550,47 @@ impl Default for FeaturesConfig {
             auto_merge: false, // Auto-merge disabled by default for safety
             loop_naming: crate::loop_name::LoopNamingConfig::default(),
             chaos_mode: ChaosModeConfig::default(),
+            router: RouterConfig::default(),
+        }
+    }
+}
+
+/// Router configuration.
+///
+/// Example configuration:
+/// ```yaml
+/// features:
+///   router:
+///     enabled: true
+///     backend: "claude"      # Defaults to cli.backend; a cheap model is best
+///     history_events: 10
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouterConfig {
+    /// Whether the router picks among competing hats.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Backend for the classification call (inherits from cli.backend if
+    /// not specified).
+    #[serde(default)]
+    pub backend: Option<HatBackend>,
+
+    /// Number of recent events from the run's history shown to the router.
+    #[serde(default = "default_router_history_events")]
+    pub history_events: usize,
+}
+
+fn default_router_history_events() -> usize {
+    10
+}
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: None,
+            history_events: default_router_history_events(),
         }
     }
 }
@@ -1125,10 +1599,24 @@ fn default_prefix_key() -> String {
     "ctrl-a".to_string()
 }
 
+fn default_footer_segments() -> Vec<FooterSegment> {
+    vec![
+        FooterSegment::Clock,
+        FooterSegment::Spacer,
+        FooterSegment::Indicator,
+    ]
+}
+
 impl Default for TuiConfig {
     fn default() -> Self {
         Self {
             prefix_key: default_prefix_key(),
+            footer: default_footer_segments(),
+            tool_result_preview: default_tool_result_preview(),
+            confirm_start: true,
+            theme: TuiTheme::default(),
+            smooth_streaming: SmoothStreamingConfig::default(),
+            text_render_budget: default_text_render_budget(),
         }
     }
 }
@@ -1249,6 +1737,7 @@ impl HatBackend {
 
 /// Configuration for a single hat.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct HatConfig {
     /// Human-readable name for the hat.
     pub name: String,
@@ -1283,6 +1772,25 @@ pub struct HatConfig {
     /// When the limit is exceeded, the orchestrator publishes `<hat_id>.exhausted`
     /// instead of activating the hat again.
     pub max_activations: Option<u32>,
+
+    /// Display color for this hat in the TUI (e.g., "cyan", "lightmagenta", "#ff8800").
+    #[serde(default)]
+    pub color: Option<String>,
+
+    /// Sequences that end the hat's response; output from the first one on
+    /// is discarded.
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
+
+    /// Upper bound on the hat's response, in (estimated) tokens. Longer
+    /// output is cut off.
+    #[serde(default)]
+    pub max_output_tokens: Option<usize>,
+
+    /// Shape the hat's response must have. A response that doesn't match
+    /// fails the iteration.
+    #[serde(default)]
+    pub response_format: ResponseFormat,
 }
 
 impl HatConfig {
@@ -1387,56 +1895,802 @@ pub struct TelegramBotConfig {
     pub bot_token: Option<String>,
 }
 
-/// Configuration errors.
-#[derive(Debug, thiserror::Error)]
-pub enum ConfigError {
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
-
-    #[error("YAML parse error: {0}")]
-    Yaml(#[from] serde_yaml::Error),
+/// HTTP API access configuration.
+///
+/// Without tokens the API is read-only and open to anyone who can reach it.
+/// Once tokens are configured every request must present one, and only
+/// controller tokens may pause, resume or abort the run.
+///
+/// Example configuration:
+/// ```yaml
+/// http_api:
+///   tokens:
+///     - name: dashboard
+///       token_env: RALPH_DASHBOARD_TOKEN
+///       role: viewer
+///       rate_limit_per_minute: 120
+///     - name: oncall
+///       token_env: RALPH_ONCALL_TOKEN
+///       role: controller
+///   tls:
+///     cert: certs/server.pem
+///     key: certs/server-key.pem
+///     client_ca: certs/team-ca.pem  # Optional: require client certificates
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HttpApiConfig {
+    /// Tokens accepted by the API.
+    #[serde(default)]
+    pub tokens: Vec<ApiTokenConfig>,
 
-    #[error("Ambiguous routing: trigger '{trigger}' is claimed by both '{hat1}' and '{hat2}'")]
-    AmbiguousRouting {
-        trigger: String,
-        hat1: String,
-        hat2: String,
-    },
+    /// Serve over TLS instead of plain HTTP.
+    #[serde(default)]
+    pub tls: Option<HttpTlsConfig>,
+}
 
-    #[error("Mutually exclusive fields: '{field1}' and '{field2}' cannot both be specified")]
-    MutuallyExclusive { field1: String, field2: String },
+/// A bearer token for the HTTP API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTokenConfig {
+    /// Name recorded in the audit log and used as the controller name.
+    pub name: String,
 
-    #[error("Custom backend requires a command - set 'cli.command' in config")]
-    CustomBackendRequiresCommand,
+    /// Token value. Prefer `token_env` to keep secrets out of the config file.
+    #[serde(default)]
+    pub token: Option<String>,
 
-    #[error(
-        "Reserved trigger '{trigger}' used by hat '{hat}' - task.start and task.resume are reserved for Ralph (the coordinator). Use a delegated event like 'work.start' instead."
-    )]
-    ReservedTrigger { trigger: String, hat: String },
+    /// Environment variable holding the token.
+    #[serde(default)]
+    pub token_env: Option<String>,
 
-    #[error(
-        "Hat '{hat}' is missing required 'description' field - add a short description of the hat's purpose"
-    )]
-    MissingDescription { hat: String },
+    /// What the token may do.
+    #[serde(default)]
+    pub role: ApiRole,
 
-    #[error("RObot config error: {field} - {hint}")]
-    RobotMissingField { field: String, hint: String },
+    /// Maximum requests per minute for this token. Unlimited if unset.
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<u32>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_default_config() {
-        let config = RalphConfig::default();
-        // Default config has no custom hats (uses default planner+builder)
-        assert!(config.hats.is_empty());
-        assert_eq!(config.event_loop.max_iterations, 100);
-        assert!(!config.verbose);
+impl ApiTokenConfig {
+    /// Resolves the token value, preferring `token_env` over `token`.
+    pub fn resolve(&self) -> Option<String> {
+        self.token_env
+            .as_ref()
+            .and_then(|var| std::env::var(var).ok())
+            .or_else(|| self.token.clone())
+            .filter(|token| !token.is_empty())
     }
+}
 
-    #[test]
+/// Permission level of an HTTP API token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiRole {
+    /// May watch the run.
+    #[default]
+    Viewer,
+    /// May also pause, resume and abort the run.
+    Controller,
+}
+
+/// TLS settings for the HTTP API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpTlsConfig {
+    /// PEM certificate chain presented by the server.
+    pub cert: PathBuf,
+
+    /// PEM private key for `cert`.
+    pub key: PathBuf,
+
+    /// PEM CA bundle. When set, clients must present a certificate signed by
+    /// it (mutual TLS).
+    #[serde(default)]
+    pub client_ca: Option<PathBuf>,
+}
+
+/// Notification channels and delivery limits.
+///
+/// Each configured channel receives the events it subscribes to. Repeats of
+/// the same notification within `dedup_window_secs` are dropped, and no
+/// channel sends more than `max_per_hour` messages, so a flapping alert
+/// doesn't flood anyone.
+///
+/// Example configuration:
+/// ```yaml
+/// notifications:
+///   desktop: {}
+///   slack:
+///     url_env: RALPH_SLACK_WEBHOOK
+///     events: [run_finished]
+///   max_per_hour: 10
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    /// Ring the terminal bell for output alerts with the `notify` action.
+    #[serde(default = "default_true")]
+    pub bell: bool,
+
+    /// Desktop notifications (`notify-send` on Linux, `osascript` on macOS).
+    #[serde(default)]
+    pub desktop: Option<DesktopNotifierConfig>,
+
+    /// JSON POST to an arbitrary URL.
+    #[serde(default)]
+    pub webhook: Option<WebhookNotifierConfig>,
+
+    /// Slack incoming webhook.
+    #[serde(default)]
+    pub slack: Option<WebhookNotifierConfig>,
+
+    /// Email via SMTP.
+    #[serde(default)]
+    pub email: Option<EmailNotifierConfig>,
+
+    /// Drop repeats of the same notification within this many seconds (0 disables).
+    #[serde(default = "default_dedup_window_secs")]
+    pub dedup_window_secs: u64,
+
+    /// Per-channel cap on messages sent in any hour (0 disables).
+    #[serde(default = "default_max_notifications_per_hour")]
+    pub max_per_hour: u32,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            bell: true,
+            desktop: None,
+            webhook: None,
+            slack: None,
+            email: None,
+            dedup_window_secs: default_dedup_window_secs(),
+            max_per_hour: default_max_notifications_per_hour(),
+        }
+    }
+}
+
+fn default_dedup_window_secs() -> u64 {
+    300
+}
+
+fn default_max_notifications_per_hour() -> u32 {
+    20
+}
+
+/// Something worth telling people about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// The loop terminated, for any reason.
+    RunFinished,
+    /// An output alert with the `notify` action matched.
+    Alert,
+    /// A post-mortem was written for an abnormal run (`post_mortem.notify`).
+    PostMortem,
+}
+
+fn default_notification_events() -> Vec<NotificationEvent> {
+    vec![
+        NotificationEvent::RunFinished,
+        NotificationEvent::Alert,
+        NotificationEvent::PostMortem,
+    ]
+}
+
+/// Desktop notification settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DesktopNotifierConfig {
+    /// Events to notify about. Defaults to all.
+    #[serde(default = "default_notification_events")]
+    pub events: Vec<NotificationEvent>,
+}
+
+/// A webhook notification target (generic JSON or Slack).
+///
+/// The generic webhook receives `{"event", "title", "message", "vars"}`;
+/// Slack receives `{"text"}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookNotifierConfig {
+    /// Target URL. Prefer `url_env`, since webhook URLs usually embed a secret.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Environment variable holding the target URL.
+    #[serde(default)]
+    pub url_env: Option<String>,
+
+    /// Events to notify about. Defaults to all.
+    #[serde(default = "default_notification_events")]
+    pub events: Vec<NotificationEvent>,
+
+    /// Key for signing payloads with HMAC-SHA256. Prefer `secret_env`.
+    #[serde(default)]
+    pub secret: Option<String>,
+
+    /// Environment variable holding the signing key.
+    #[serde(default)]
+    pub secret_env: Option<String>,
+
+    /// Extra attempts after a failed delivery, with exponential backoff.
+    #[serde(default = "default_webhook_retries")]
+    pub retries: u32,
+}
+
+fn default_webhook_retries() -> u32 {
+    3
+}
+
+impl WebhookNotifierConfig {
+    /// Resolves the URL, preferring `url_env` over `url`.
+    pub fn resolve_url(&self) -> Option<String> {
+        self.url_env
+            .as_ref()
+            .and_then(|var| std::env::var(var).ok())
+            .or_else(|| self.url.clone())
+    }
+
+    /// Resolves the signing key, preferring `secret_env` over `secret`.
+    pub fn resolve_secret(&self) -> Option<String> {
+        self.secret_env
+            .as_ref()
+            .and_then(|var| std::env::var(var).ok())
+            .or_else(|| self.secret.clone())
+    }
+}
+
+/// SMTP email notifications, for environments without chat webhooks.
+///
+/// Subject and body are templates; `{{status}}`, `{{iterations}}`,
+/// `{{duration}}`, `{{cost}}`, `{{run_id}}` and `{{summary}}` (the loop
+/// summary markdown) are replaced when the email is sent.
+///
+/// Example configuration:
+/// ```yaml
+/// notifications:
+///   email:
+///     smtp_host: smtp.example.com
+///     username: ralph@example.com
+///     password_env: RALPH_SMTP_PASSWORD
+///     from: "Ralph <ralph@example.com>"
+///     to: [team@example.com]
+///     only_on_failure: true
+///     subject: "[ralph] {{status}} after {{iterations}} iterations"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailNotifierConfig {
+    /// SMTP server host name.
+    pub smtp_host: String,
+
+    /// SMTP server port. Defaults to 587 for STARTTLS, 465 for TLS, 25 otherwise.
+    #[serde(default)]
+    pub smtp_port: Option<u16>,
+
+    /// How the connection is secured.
+    #[serde(default)]
+    pub security: SmtpSecurity,
+
+    /// Login user name. No authentication is attempted if unset.
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Login password. Prefer `password_env` to keep secrets out of the config file.
+    #[serde(default)]
+    pub password: Option<String>,
+
+    /// Environment variable holding the login password.
+    #[serde(default)]
+    pub password_env: Option<String>,
+
+    /// Sender, e.g. `Ralph <ralph@example.com>`.
+    pub from: String,
+
+    /// Recipients.
+    pub to: Vec<String>,
+
+    /// Events to notify about. Defaults to finished runs only.
+    #[serde(default = "default_email_events")]
+    pub events: Vec<NotificationEvent>,
+
+    /// Only send when the loop did not complete successfully.
+    #[serde(default)]
+    pub only_on_failure: bool,
+
+    /// Subject template.
+    #[serde(default = "default_email_subject")]
+    pub subject: String,
+
+    /// Body template.
+    #[serde(default = "default_email_body")]
+    pub body: String,
+}
+
+impl EmailNotifierConfig {
+    /// Returns the configured port, or the usual port for `security`.
+    pub fn port(&self) -> u16 {
+        self.smtp_port.unwrap_or(match self.security {
+            SmtpSecurity::Starttls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::None => 25,
+        })
+    }
+
+    /// Resolves the password, preferring `password_env` over `password`.
+    pub fn resolve_password(&self) -> Option<String> {
+        self.password_env
+            .as_ref()
+            .and_then(|var| std::env::var(var).ok())
+            .or_else(|| self.password.clone())
+    }
+}
+
+fn default_email_events() -> Vec<NotificationEvent> {
+    vec![NotificationEvent::RunFinished]
+}
+
+fn default_email_subject() -> String {
+    "[ralph] Loop {{status}} after {{iterations}} iterations".to_string()
+}
+
+fn default_email_body() -> String {
+    "Run {{run_id}} finished: {{status}} ({{iterations}} iterations, {{duration}}, {{cost}}).\n\n{{summary}}".to_string()
+}
+
+/// Settings for daemon mode (`ralph bot daemon`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DaemonConfig {
+    /// Runs scheduled on a timetable, listed in the daemon's status feeds.
+    #[serde(default)]
+    pub schedules: Vec<ScheduledRunConfig>,
+
+    /// Limits on iterations running at once across runs, per API key.
+    #[serde(default)]
+    pub concurrency_groups: Vec<ConcurrencyGroupConfig>,
+}
+
+/// Backends sharing one API key, and how many iterations may use it at once
+/// across all runs in the repository.
+///
+/// Example configuration:
+/// ```yaml
+/// daemon:
+///   concurrency_groups:
+///     - name: anthropic-team
+///       backends: [claude]
+///       max_concurrent: 2
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcurrencyGroupConfig {
+    /// Name shown in `ralph status` while a run waits for a slot.
+    pub name: String,
+
+    /// Backends drawing on this key.
+    pub backends: Vec<String>,
+
+    /// Iterations that may run at once.
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: u32,
+}
+
+fn default_max_concurrent() -> u32 {
+    1
+}
+
+/// A run scheduled at a fixed local time.
+///
+/// Example configuration:
+/// ```yaml
+/// daemon:
+///   schedules:
+///     - name: nightly-deps
+///       prompt: "Update dependencies and fix any breakage"
+///       at: "02:30"
+///       days: [mon, tue, wed, thu, fri]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledRunConfig {
+    /// Name shown in the status feed and calendar.
+    pub name: String,
+
+    /// Prompt the run starts with.
+    pub prompt: String,
+
+    /// Local start time, `HH:MM`.
+    pub at: String,
+
+    /// Days the run happens on. Empty means every day.
+    #[serde(default)]
+    pub days: Vec<chrono::Weekday>,
+}
+
+impl ScheduledRunConfig {
+    /// Parses `at`, returning `None` if it isn't a valid `HH:MM` time.
+    pub fn time(&self) -> Option<chrono::NaiveTime> {
+        chrono::NaiveTime::parse_from_str(&self.at, "%H:%M").ok()
+    }
+}
+
+/// A repository taking part in a multi-repo run.
+///
+/// Example configuration:
+/// ```yaml
+/// repos:
+///   - name: api
+///     path: ../billing-api
+///     description: "REST API (Rust)"
+///   - name: client
+///     path: ../billing-web
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepoConfig {
+    /// Short name used in prompts, checkpoints and `ralph repos diff`.
+    pub name: String,
+
+    /// Repository root, relative to the workspace root unless absolute.
+    pub path: std::path::PathBuf,
+
+    /// What the repository contains, shown to the agent.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Limits a run to some packages of a monorepo.
+///
+/// The prompt points the agent at the selected packages and the test
+/// commands that cover them, and `build.done` is rejected while files
+/// outside the packages are changed.
+///
+/// Example configuration:
+/// ```yaml
+/// scope:
+///   packages: [crates/ralph-tui, crates/ralph-core]
+///   allow: [Cargo.lock]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScopeConfig {
+    /// Package directories, relative to the workspace root. Empty means the
+    /// whole workspace is in scope.
+    #[serde(default)]
+    pub packages: Vec<String>,
+
+    /// Further paths (files or directories) the run may change, e.g. a
+    /// shared lockfile.
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+/// Commands behind `tests: pass`, `lint: pass` and `typecheck: pass`.
+///
+/// Unset gates default from the project type detected at the workspace
+/// root (`Cargo.toml`, `package.json`, `pyproject.toml`, `go.mod`); an
+/// empty string turns a gate off.
+///
+/// Example configuration:
+/// ```yaml
+/// gates:
+///   tests: "cargo nextest run"
+///   lint: ""
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GatesConfig {
+    #[serde(default)]
+    pub tests: Option<String>,
+    #[serde(default)]
+    pub lint: Option<String>,
+    #[serde(default)]
+    pub typecheck: Option<String>,
+}
+
+/// When to pause the loop for power events.
+///
+/// The loop checks between iterations and resumes by itself once the
+/// machine is back on AC (or charged above the threshold) and awake.
+///
+/// Example configuration:
+/// ```yaml
+/// power:
+///   pause_on_battery_below: 20
+///   pause_on_suspend: true
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerConfig {
+    /// Pause while on battery with less than this charge (percent).
+    #[serde(default)]
+    pub pause_on_battery_below: Option<u8>,
+
+    /// Pause while the system prepares to suspend, and briefly after it
+    /// wakes up (Linux, via logind).
+    #[serde(default)]
+    pub pause_on_suspend: bool,
+}
+
+/// Housekeeping commands run between iterations.
+///
+/// Commands start when an iteration ends and run concurrently with each
+/// other and with the cooldown, throttle and concurrency-slot waits; the
+/// next iteration's agent starts only once they have finished, so it never
+/// races a formatter.
+///
+/// Example configuration:
+/// ```yaml
+/// maintenance:
+///   commands:
+///     - name: fmt
+///       command: "cargo fmt"
+///     - name: repo-map
+///       command: "ctags -R -f .ralph/tags"
+///       every: 5
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    #[serde(default)]
+    pub commands: Vec<MaintenanceCommand>,
+
+    /// Seconds a command may run before it is killed.
+    #[serde(default = "default_maintenance_timeout")]
+    pub timeout_seconds: u64,
+}
+
+fn default_maintenance_timeout() -> u64 {
+    300
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            commands: Vec::new(),
+            timeout_seconds: default_maintenance_timeout(),
+        }
+    }
+}
+
+/// One maintenance command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceCommand {
+    /// Name shown in logs.
+    pub name: String,
+
+    /// Shell command, run with `sh -c` in the workspace root.
+    pub command: String,
+
+    /// Run after every Nth iteration.
+    #[serde(default = "default_maintenance_every")]
+    pub every: u32,
+}
+
+fn default_maintenance_every() -> u32 {
+    1
+}
+
+/// Post-mortem of a run that ended abnormally.
+///
+/// When a run hits a limit, fails repeatedly or is aborted, a markdown
+/// post-mortem is written next to its output log in `.ralph/runs/`.
+///
+/// Example configuration:
+/// ```yaml
+/// post_mortem:
+///   notify: true
+///   max_diff_lines: 100
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostMortemConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Also send the post-mortem to the `post_mortem` notification event.
+    #[serde(default)]
+    pub notify: bool,
+
+    /// Lines of the last diff included before it is cut off.
+    #[serde(default = "default_post_mortem_diff_lines")]
+    pub max_diff_lines: usize,
+}
+
+fn default_post_mortem_diff_lines() -> usize {
+    200
+}
+
+impl Default for PostMortemConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            notify: false,
+            max_diff_lines: default_post_mortem_diff_lines(),
+        }
+    }
+}
+
+/// Encryption at rest of transcripts under `.ralph/`.
+///
+/// Example configuration:
+/// ```yaml
+/// encryption:
+///   enabled: true
+///   key_file: /run/secrets/ralph.key
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// File holding the base64-encoded 256-bit key, relative to the
+    /// workspace root. Without one the key is kept in the OS keychain.
+    #[serde(default)]
+    pub key_file: Option<PathBuf>,
+}
+
+/// Flagging of iterations whose cost or tokens are outliers.
+///
+/// An iteration that used `ratio` times the median of the last `window`
+/// iterations or more gets a footer alert and a note in the summary.
+///
+/// Example configuration:
+/// ```yaml
+/// cost_anomaly:
+///   ratio: 4.0
+///   window: 10
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostAnomalyConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// How many times the median counts as an outlier.
+    #[serde(default = "default_cost_anomaly_ratio")]
+    pub ratio: f64,
+
+    /// Number of recent iterations the median is taken over.
+    #[serde(default = "default_cost_anomaly_window")]
+    pub window: usize,
+
+    /// Iterations needed before anything is flagged.
+    #[serde(default = "default_cost_anomaly_min_samples")]
+    pub min_samples: usize,
+}
+
+fn default_cost_anomaly_ratio() -> f64 {
+    3.0
+}
+
+fn default_cost_anomaly_window() -> usize {
+    20
+}
+
+fn default_cost_anomaly_min_samples() -> usize {
+    3
+}
+
+impl Default for CostAnomalyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ratio: default_cost_anomaly_ratio(),
+            window: default_cost_anomaly_window(),
+            min_samples: default_cost_anomaly_min_samples(),
+        }
+    }
+}
+
+/// Wrap-up request sent to the agent when a run is aborted.
+///
+/// In interactive sessions, which read further input while the agent works,
+/// an abort first types `instruction` into the session and waits up to
+/// `timeout_seconds` for a summary between `<checkpoint>` tags. The summary is
+/// appended to the scratchpad, so `ralph run --continue` picks up from it.
+///
+/// Example configuration:
+/// ```yaml
+/// checkpoint:
+///   timeout_seconds: 60
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// How long to wait for the summary before stopping the agent anyway.
+    #[serde(default = "default_checkpoint_timeout")]
+    pub timeout_seconds: u64,
+
+    /// Instruction typed into the session; it should ask for the summary
+    /// between `<checkpoint>` and `</checkpoint>` tags.
+    #[serde(default = "default_checkpoint_instruction")]
+    pub instruction: String,
+}
+
+fn default_checkpoint_timeout() -> u64 {
+    30
+}
+
+fn default_checkpoint_instruction() -> String {
+    "Ralph is stopping this session. Stop working and summarize where you are for whoever \
+     picks this up: what is done, what is in progress, and what should happen next. Write the \
+     summary between <checkpoint> and </checkpoint> tags."
+        .to_string()
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_seconds: default_checkpoint_timeout(),
+            instruction: default_checkpoint_instruction(),
+        }
+    }
+}
+
+/// Connection security for SMTP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS.
+    #[default]
+    Starttls,
+    /// TLS from the start (SMTPS).
+    Tls,
+    /// No encryption, e.g. a local relay.
+    None,
+}
+
+/// Configuration errors.
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("YAML parse error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("Ambiguous routing: trigger '{trigger}' is claimed by both '{hat1}' and '{hat2}'")]
+    AmbiguousRouting {
+        trigger: String,
+        hat1: String,
+        hat2: String,
+    },
+
+    #[error("Mutually exclusive fields: '{field1}' and '{field2}' cannot both be specified")]
+    MutuallyExclusive { field1: String, field2: String },
+
+    #[error("Custom backend requires a command - set 'cli.command' in config")]
+    CustomBackendRequiresCommand,
+
+    #[error(
+        "Reserved trigger '{trigger}' used by hat '{hat}' - task.start and task.resume are reserved for Ralph (the coordinator). Use a delegated event like 'work.start' instead."
+    )]
+    ReservedTrigger { trigger: String, hat: String },
+
+    #[error(
+        "Hat '{hat}' is missing required 'description' field - add a short description of the hat's purpose"
+    )]
+    MissingDescription { hat: String },
+
+    #[error("RObot config error: {field} - {hint}")]
+    RobotMissingField { field: String, hint: String },
+
+    #[error("Invalid alert pattern '{pattern}': {message}")]
+    InvalidAlertPattern { pattern: String, message: String },
+
+    #[error("Schedule '{name}' has invalid time '{at}' - use HH:MM")]
+    InvalidScheduleTime { name: String, at: String },
+
+    #[error("Repo '{name}' is listed more than once in 'repos'")]
+    DuplicateRepo { name: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = RalphConfig::default();
+        // Default config has no custom hats (uses default planner+builder)
+        assert!(config.hats.is_empty());
+        assert_eq!(config.event_loop.max_iterations, 100);
+        assert!(!config.verbose);
+    }
+
+    #[test]
     fn test_parse_yaml_with_custom_hats() {
         let yaml = r#"
 event_loop:
@@ -1519,10 +2773,8 @@ enable_metrics: true
         let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
         let warnings = config.validate().unwrap();
 
-        assert_eq!(warnings.len(), 2);
-        assert!(warnings
-            .iter()
-            .any(|w| matches!(w, ConfigWarning::DeferredFeature { field, .. } if field == "archive_prompts")));
+        // archive_prompts is implemented now; only enable_metrics is deferred
+        assert_eq!(warnings.len(), 1);
         assert!(warnings
             .iter()
             .any(|w| matches!(w, ConfigWarning::DeferredFeature { field, .. } if field == "enable_metrics")));
@@ -1573,6 +2825,7 @@ adapters:
   claude:
     timeout: 600
     enabled: true
+    tokens_per_minute: 40000
   gemini:
     timeout: 300
     enabled: false
@@ -1582,10 +2835,12 @@ adapters:
         let claude = config.adapter_settings("claude");
         assert_eq!(claude.timeout, 600);
         assert!(claude.enabled);
+        assert_eq!(claude.tokens_per_minute, Some(40000));
 
         let gemini = config.adapter_settings("gemini");
         assert_eq!(gemini.timeout, 300);
         assert!(!gemini.enabled);
+        assert_eq!(gemini.tokens_per_minute, None);
     }
 
     #[test]
@@ -1926,10 +3181,126 @@ tui:
         assert_eq!(key_modifiers, KeyModifiers::CONTROL);
     }
 
+    #[test]
+    fn test_tui_footer_segments_default() {
+        let config = RalphConfig::default();
+        assert_eq!(
+            config.tui.footer,
+            vec![
+                FooterSegment::Clock,
+                FooterSegment::Spacer,
+                FooterSegment::Indicator
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tui_footer_segments_from_yaml() {
+        let yaml = r"
+tui:
+  footer: [iteration, hat, last_event, spacer, budget, indicator]
+";
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.tui.prefix_key, "ctrl-a");
+        assert_eq!(
+            config.tui.footer,
+            vec![
+                FooterSegment::Iteration,
+                FooterSegment::Hat,
+                FooterSegment::LastEvent,
+                FooterSegment::Spacer,
+                FooterSegment::Budget,
+                FooterSegment::Indicator,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alert_rules_from_yaml() {
+        let yaml = r#"
+alerts:
+  - pattern: "(?i)deleting"
+  - name: payments
+    pattern: "payments_service"
+    actions: [notify, pause]
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.alerts.len(), 2);
+        assert_eq!(config.alerts[0].actions, vec![AlertAction::Highlight]);
+        assert_eq!(config.alerts[0].label(), "(?i)deleting");
+        assert_eq!(config.alerts[1].label(), "payments");
+        assert_eq!(
+            config.alerts[1].actions,
+            vec![AlertAction::Notify, AlertAction::Pause]
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_alert_pattern_rejected() {
+        let yaml = r#"
+alerts:
+  - pattern: "unclosed("
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::InvalidAlertPattern { pattern, .. } if pattern == "unclosed("),
+            "Expected InvalidAlertPattern error, got: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_daemon_schedules_from_yaml() {
+        let yaml = r#"
+daemon:
+  schedules:
+    - name: nightly
+      prompt: "Update dependencies"
+      at: "02:30"
+      days: [mon, Friday]
+    - name: broken
+      prompt: "x"
+      at: "25:00"
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let nightly = &config.daemon.schedules[0];
+        assert_eq!(
+            nightly.days,
+            vec![chrono::Weekday::Mon, chrono::Weekday::Fri]
+        );
+        assert_eq!(nightly.time(), chrono::NaiveTime::from_hms_opt(2, 30, 0));
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::InvalidScheduleTime { name, .. } if name == "broken"
+        ));
+    }
+
+    #[test]
+    fn test_repos_from_yaml_reject_duplicate_names() {
+        let yaml = r#"
+repos:
+  - name: api
+    path: ../api
+    description: "REST API"
+  - name: api
+    path: ../client
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.repos[0].path, std::path::PathBuf::from("../api"));
+        assert_eq!(config.repos[1].description, None);
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::DuplicateRepo { name } if name == "api"
+        ));
+    }
+
     #[test]
     fn test_tui_config_parse_invalid_format() {
         let tui_config = TuiConfig {
             prefix_key: "invalid".to_string(),
+            ..TuiConfig::default()
         };
         let result = tui_config.parse_prefix();
         assert!(result.is_err());
@@ -1940,6 +3311,7 @@ tui:
     fn test_tui_config_parse_invalid_modifier() {
         let tui_config = TuiConfig {
             prefix_key: "alt-a".to_string(),
+            ..TuiConfig::default()
         };
         let result = tui_config.parse_prefix();
         assert!(result.is_err());
@@ -1950,6 +3322,7 @@ tui:
     fn test_tui_config_parse_invalid_key() {
         let tui_config = TuiConfig {
             prefix_key: "ctrl-abc".to_string(),
+            ..TuiConfig::default()
         };
         let result = tui_config.parse_prefix();
         assert!(result.is_err());
@@ -0,0 +1,214 @@
+//! Detection of iterations whose cost or token usage is an outlier.
+//!
+//! A loop that goes pathological (an agent re-reading the whole repo every
+//! iteration, a tool call stuck in a retry loop) usually shows up as an
+//! iteration far more expensive than the ones before it. The detector keeps a
+//! rolling window of recent per-iteration cost and tokens and flags an
+//! iteration that used `ratio` times the window's median or more.
+
+use crate::config::CostAnomalyConfig;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// What an anomalous iteration used too much of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostMetric {
+    /// Cost in USD, for backends that report it.
+    Cost,
+    /// Tokens, reported or estimated.
+    Tokens,
+}
+
+/// An iteration that used far more than the recent median.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostAnomaly {
+    pub iteration: u32,
+    pub hat: String,
+    pub metric: CostMetric,
+    /// What the iteration used.
+    pub value: f64,
+    /// Median of the iterations before it.
+    pub median: f64,
+}
+
+impl CostAnomaly {
+    /// How many times the median the iteration used.
+    pub fn ratio(&self) -> f64 {
+        self.value / self.median
+    }
+
+    /// Short form for the TUI footer, e.g. "iter 7 used 4.1× median tokens".
+    pub fn short(&self) -> String {
+        let metric = match self.metric {
+            CostMetric::Cost => "cost",
+            CostMetric::Tokens => "tokens",
+        };
+        format!(
+            "iter {} used {:.1}× median {metric}",
+            self.iteration,
+            self.ratio()
+        )
+    }
+
+    fn format_value(&self, value: f64) -> String {
+        match self.metric {
+            CostMetric::Cost => format!("${value:.2}"),
+            CostMetric::Tokens if value >= 1000.0 => format!("{:.1}k tokens", value / 1000.0),
+            CostMetric::Tokens => format!("{value:.0} tokens"),
+        }
+    }
+}
+
+impl fmt::Display for CostAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "iteration {} ({}) used {}, {:.1}× the median of {}",
+            self.iteration,
+            self.hat,
+            self.format_value(self.value),
+            self.ratio(),
+            self.format_value(self.median)
+        )
+    }
+}
+
+/// Rolling per-iteration cost and token usage.
+#[derive(Debug)]
+pub struct CostAnomalyDetector {
+    config: CostAnomalyConfig,
+    costs: VecDeque<f64>,
+    tokens: VecDeque<f64>,
+}
+
+impl CostAnomalyDetector {
+    pub fn new(config: CostAnomalyConfig) -> Self {
+        Self {
+            config,
+            costs: VecDeque::new(),
+            tokens: VecDeque::new(),
+        }
+    }
+
+    /// Records an iteration that just ended, returning an anomaly if it used
+    /// `ratio` times the recent median or more.
+    ///
+    /// Cost is checked first; iterations without a reported cost (0) are left
+    /// out of its window so backends that don't report cost are judged on
+    /// tokens alone.
+    pub fn record(
+        &mut self,
+        iteration: u32,
+        hat: &str,
+        cost_usd: f64,
+        tokens: u64,
+    ) -> Option<CostAnomaly> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let mut anomaly = None;
+        if cost_usd > 0.0 {
+            anomaly = self.check(CostMetric::Cost, cost_usd);
+            push(&mut self.costs, cost_usd, self.config.window);
+        }
+        if tokens > 0 {
+            let tokens = tokens as f64;
+            anomaly = anomaly.or_else(|| self.check(CostMetric::Tokens, tokens));
+            push(&mut self.tokens, tokens, self.config.window);
+        }
+
+        anomaly.map(|(metric, value, median)| CostAnomaly {
+            iteration,
+            hat: hat.to_string(),
+            metric,
+            value,
+            median,
+        })
+    }
+
+    fn check(&self, metric: CostMetric, value: f64) -> Option<(CostMetric, f64, f64)> {
+        let window = match metric {
+            CostMetric::Cost => &self.costs,
+            CostMetric::Tokens => &self.tokens,
+        };
+        if window.len() < self.config.min_samples.max(1) {
+            return None;
+        }
+        let median = median(window);
+        (median > 0.0 && value >= median * self.config.ratio).then_some((metric, value, median))
+    }
+}
+
+fn push(window: &mut VecDeque<f64>, value: f64, size: usize) {
+    window.push_back(value);
+    while window.len() > size.max(1) {
+        window.pop_front();
+    }
+}
+
+fn median(values: &VecDeque<f64>) -> f64 {
+    let mut sorted: Vec<f64> = values.iter().copied().collect();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        f64::midpoint(sorted[mid - 1], sorted[mid])
+    } else {
+        sorted[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> CostAnomalyDetector {
+        CostAnomalyDetector::new(CostAnomalyConfig::default())
+    }
+
+    #[test]
+    fn test_flags_iteration_over_ratio_times_median() {
+        let mut detector = detector();
+        for (iteration, tokens) in [(1, 10_000), (2, 12_000), (3, 11_000)] {
+            assert!(detector.record(iteration, "builder", 0.0, tokens).is_none());
+        }
+
+        let anomaly = detector.record(4, "builder", 0.0, 48_000).unwrap();
+        assert_eq!(anomaly.metric, CostMetric::Tokens);
+        assert_eq!(
+            anomaly.to_string(),
+            "iteration 4 (builder) used 48.0k tokens, 4.4× the median of 11.0k tokens"
+        );
+        assert_eq!(anomaly.short(), "iter 4 used 4.4× median tokens");
+
+        // Just under the ratio is fine
+        assert!(detector.record(5, "builder", 0.0, 34_000).is_none());
+    }
+
+    #[test]
+    fn test_needs_min_samples_and_prefers_cost() {
+        let mut detector = detector();
+        assert!(detector.record(1, "ralph", 0.10, 1_000).is_none());
+        assert!(detector.record(2, "ralph", 0.90, 1_000).is_none());
+        assert!(detector.record(3, "ralph", 0.10, 1_000).is_none());
+
+        let anomaly = detector.record(4, "ralph", 1.20, 9_000).unwrap();
+        assert_eq!(anomaly.metric, CostMetric::Cost);
+        assert_eq!(
+            anomaly.to_string(),
+            "iteration 4 (ralph) used $1.20, 12.0× the median of $0.10"
+        );
+    }
+
+    #[test]
+    fn test_disabled_never_flags() {
+        let mut detector = CostAnomalyDetector::new(CostAnomalyConfig {
+            enabled: false,
+            ..CostAnomalyConfig::default()
+        });
+        for iteration in 1..=5 {
+            detector.record(iteration, "ralph", 0.0, 1_000);
+        }
+        assert!(detector.record(6, "ralph", 0.0, 100_000).is_none());
+    }
+}
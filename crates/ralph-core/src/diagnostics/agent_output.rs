@@ -2,15 +2,18 @@
 
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::fs::File;
+use std::fs::{self, File, OpenOptions};
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
+use crate::encryption::Cipher;
+
 /// Logger for agent output events.
 pub struct AgentOutputLogger {
     file: BufWriter<File>,
     iteration: u32,
     hat: String,
+    cipher: Option<Cipher>,
 }
 
 /// Single agent output entry in JSONL format.
@@ -43,11 +46,23 @@ pub enum AgentOutputContent {
     #[serde(rename = "error")]
     Error { message: String },
 
+    #[serde(rename = "warning")]
+    Warning { message: String },
+
     #[serde(rename = "complete")]
     Complete {
         input_tokens: Option<u64>,
         output_tokens: Option<u64>,
     },
+
+    /// Marks where an iteration's output begins; `prompt_hash` fingerprints
+    /// the prompt it was given.
+    #[serde(rename = "iteration_start")]
+    IterationStart { prompt_hash: String },
+
+    /// Marks where an iteration's output ends.
+    #[serde(rename = "iteration_end")]
+    IterationEnd { success: bool },
 }
 
 impl AgentOutputLogger {
@@ -60,9 +75,35 @@ impl AgentOutputLogger {
             file: BufWriter::new(file),
             iteration: 0,
             hat: String::new(),
+            cipher: None,
+        })
+    }
+
+    /// Creates a logger that appends to `path`, creating the file and its
+    /// parent directories if needed.
+    ///
+    /// Used for per-run output logs, which keep growing when a run is resumed.
+    pub fn append(path: &Path) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            file: BufWriter::new(file),
+            iteration: 0,
+            hat: String::new(),
+            cipher: None,
         })
     }
 
+    /// Seals every entry with `cipher` (see [`crate::Cipher`]).
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: Option<Cipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
     /// Sets the current iteration and hat context.
     pub fn set_context(&mut self, iteration: u32, hat: &str) {
         self.iteration = iteration;
@@ -78,7 +119,10 @@ impl AgentOutputLogger {
             content,
         };
 
-        let json = serde_json::to_string(&entry)?;
+        let mut json = serde_json::to_string(&entry)?;
+        if let Some(cipher) = &self.cipher {
+            json = cipher.seal(&json);
+        }
         writeln!(self.file, "{}", json)?;
         self.file.flush()?;
 
@@ -151,6 +195,24 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_append_keeps_existing_entries() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("runs").join("run-1.jsonl");
+
+        for text in ["first", "second"] {
+            let mut logger = AgentOutputLogger::append(&path).unwrap();
+            logger
+                .log(AgentOutputContent::Text {
+                    text: text.to_string(),
+                })
+                .unwrap();
+        }
+
+        let reader = BufReader::new(File::open(&path).unwrap());
+        assert_eq!(reader.lines().count(), 2);
+    }
+
     #[test]
     fn test_immediate_flush() {
         let temp = TempDir::new().unwrap();
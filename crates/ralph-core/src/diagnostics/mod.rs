@@ -22,6 +22,7 @@ pub use performance::{PerformanceLogger, PerformanceMetric};
 pub use stream_handler::DiagnosticStreamHandler;
 pub use trace_layer::{DiagnosticTraceLayer, TraceEntry};
 
+use crate::encryption::Cipher;
 use chrono::Local;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -37,6 +38,7 @@ pub struct DiagnosticsCollector {
     orchestration_logger: Option<Arc<Mutex<orchestration::OrchestrationLogger>>>,
     performance_logger: Option<Arc<Mutex<performance::PerformanceLogger>>>,
     error_logger: Option<Arc<Mutex<errors::ErrorLogger>>>,
+    cipher: Option<Cipher>,
 }
 
 impl DiagnosticsCollector {
@@ -80,6 +82,7 @@ impl DiagnosticsCollector {
             orchestration_logger,
             performance_logger,
             error_logger,
+            cipher: None,
         })
     }
 
@@ -91,9 +94,15 @@ impl DiagnosticsCollector {
             orchestration_logger: None,
             performance_logger: None,
             error_logger: None,
+            cipher: None,
         }
     }
 
+    /// Seals the captured agent output with `cipher` (see [`crate::Cipher`]).
+    pub fn set_cipher(&mut self, cipher: Option<Cipher>) {
+        self.cipher = cipher;
+    }
+
     /// Returns whether diagnostics are enabled.
     pub fn is_enabled(&self) -> bool {
         self.enabled
@@ -111,7 +120,7 @@ impl DiagnosticsCollector {
         if let Some(session_dir) = &self.session_dir {
             match AgentOutputLogger::new(session_dir) {
                 Ok(logger) => {
-                    let logger = Arc::new(Mutex::new(logger));
+                    let logger = Arc::new(Mutex::new(logger.with_cipher(self.cipher.clone())));
                     Ok(DiagnosticStreamHandler::new(handler, logger))
                 }
                 Err(_) => Err(handler), // Return original handler on error
@@ -200,6 +209,31 @@ mod tests {
         assert!(dir_name.chars().nth(16) == Some('-'));
     }
 
+    #[test]
+    fn test_agent_output_is_sealed_with_cipher() {
+        let temp = TempDir::new().unwrap();
+        let mut collector = DiagnosticsCollector::with_enabled(temp.path(), true).unwrap();
+        let cipher = Cipher::from_base64(&Cipher::generate_key()).unwrap();
+        collector.set_cipher(Some(cipher.clone()));
+
+        let Ok(handler) = collector.wrap_stream_handler(()) else {
+            panic!("diagnostics are enabled");
+        };
+        handler.log(AgentOutputContent::Text {
+            text: "fn secret() {}".to_string(),
+        });
+
+        let output = collector.session_dir().unwrap().join("agent-output.jsonl");
+        let content = std::fs::read_to_string(output).unwrap();
+        assert!(!content.contains("secret"));
+        assert!(
+            cipher
+                .open(content.trim_end())
+                .unwrap()
+                .contains("fn secret() {}")
+        );
+    }
+
     #[test]
     fn test_performance_logger_integration() {
         let temp = TempDir::new().unwrap();
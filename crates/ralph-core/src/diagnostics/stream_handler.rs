@@ -1,13 +1,12 @@
 //! Diagnostic stream handler wrapper.
 
-use crate::diagnostics::agent_output::AgentOutputLogger;
+use crate::diagnostics::agent_output::{AgentOutputContent, AgentOutputLogger};
 use std::sync::{Arc, Mutex};
 
 /// Wrapper that logs agent output while delegating to inner handler.
 ///
-/// Note: Fields are used in the test module's StreamHandler implementation.
-/// This struct is scaffolded for future production integration with ralph-adapters.
-#[allow(dead_code)]
+/// The `StreamHandler` implementation lives in ralph-adapters, which owns the
+/// trait; it uses [`inner_mut`](Self::inner_mut) and [`log`](Self::log).
 pub struct DiagnosticStreamHandler<H> {
     inner: H,
     logger: Arc<Mutex<AgentOutputLogger>>,
@@ -18,12 +17,32 @@ impl<H> DiagnosticStreamHandler<H> {
     pub fn new(inner: H, logger: Arc<Mutex<AgentOutputLogger>>) -> Self {
         Self { inner, logger }
     }
+
+    /// Returns the wrapped handler.
+    pub fn inner_mut(&mut self) -> &mut H {
+        &mut self.inner
+    }
+
+    /// Sets the iteration and hat recorded with the following entries.
+    pub fn set_context(&self, iteration: u32, hat: &str) {
+        if let Ok(mut logger) = self.logger.lock() {
+            logger.set_context(iteration, hat);
+        }
+    }
+
+    /// Records an output entry. Write failures are ignored so logging can
+    /// never interrupt the agent's output.
+    pub fn log(&self, content: AgentOutputContent) {
+        if let Ok(mut logger) = self.logger.lock() {
+            let _ = logger.log(content);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::diagnostics::agent_output::{AgentOutputContent, AgentOutputEntry};
+    use crate::diagnostics::agent_output::AgentOutputEntry;
     use std::fs::File;
     use std::io::{BufRead, BufReader};
     use tempfile::TempDir;
@@ -0,0 +1,330 @@
+//! Encryption at rest for transcripts under `.ralph/`.
+//!
+//! With `encryption.enabled`, run logs, session recordings, full tool
+//! results, archived prompts and diagnostics output are sealed with
+//! AES-256-GCM. Each JSONL line is sealed on its own
+//! (`ralph-enc:v1:<base64 of nonce and ciphertext>`), so logs can still be
+//! appended to and followed while a run is going. Plain lines read back
+//! unchanged, so files written before encryption was turned on stay readable.
+//!
+//! Files the agent itself reads and writes (scratchpad, events, memories)
+//! stay plain.
+//!
+//! The key is resolved like the Telegram bot token:
+//! 1. `RALPH_ENCRYPTION_KEY` environment variable
+//! 2. `encryption.key_file`
+//! 3. OS keychain (service: "ralph", user: "encryption-key")
+//!
+//! A key file or keychain entry that does not exist yet is created with a
+//! fresh random key.
+
+use std::borrow::Cow;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+use tracing::{info, warn};
+
+use crate::config::EncryptionConfig;
+
+/// Marks a sealed line.
+const PREFIX: &str = "ralph-enc:v1:";
+
+/// Key length of AES-256.
+const KEY_LEN: usize = 32;
+
+/// Errors loading a key or opening sealed text.
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    #[error("encryption key file error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("keychain error: {0}")]
+    Keychain(#[from] keyring::Error),
+
+    #[error("invalid encryption key: expected {KEY_LEN} bytes, base64-encoded")]
+    InvalidKey,
+
+    #[error("cannot decrypt: wrong key or corrupted data")]
+    Decrypt,
+}
+
+/// Seals and opens text with one AES-256-GCM key.
+#[derive(Clone)]
+pub struct Cipher {
+    key: Arc<LessSafeKey>,
+}
+
+impl std::fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Cipher { .. }")
+    }
+}
+
+impl Cipher {
+    /// Creates a cipher from a base64-encoded 256-bit key.
+    pub fn from_base64(key: &str) -> Result<Self, EncryptionError> {
+        let key = BASE64
+            .decode(key.trim())
+            .map_err(|_| EncryptionError::InvalidKey)?;
+        let key = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| EncryptionError::InvalidKey)?;
+        Ok(Self {
+            key: Arc::new(LessSafeKey::new(key)),
+        })
+    }
+
+    /// Returns a fresh random key, base64-encoded.
+    pub fn generate_key() -> String {
+        let mut key = [0u8; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut key)
+            .expect("system random source available");
+        BASE64.encode(key)
+    }
+
+    /// Returns the configured cipher, or `None` when encryption is off.
+    pub fn load(
+        config: &EncryptionConfig,
+        workspace_root: &Path,
+    ) -> Result<Option<Self>, EncryptionError> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        if let Ok(key) = std::env::var("RALPH_ENCRYPTION_KEY") {
+            return Self::from_base64(&key).map(Some);
+        }
+        let key = match &config.key_file {
+            Some(path) => key_from_file(&workspace_root.join(path))?,
+            None => key_from_keychain()?,
+        };
+        Self::from_base64(&key).map(Some)
+    }
+
+    /// Seals `plain` into a single line.
+    pub fn seal(&self, plain: &str) -> String {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .expect("system random source available");
+
+        let mut data = plain.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+            .expect("AES-GCM input within size limit");
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&data);
+        format!("{PREFIX}{}", BASE64.encode(sealed))
+    }
+
+    /// Opens text written by [`Cipher::seal`]; plain text is returned as is.
+    pub fn open<'a>(&self, text: &'a str) -> Result<Cow<'a, str>, EncryptionError> {
+        let Some(encoded) = text.trim_end().strip_prefix(PREFIX) else {
+            return Ok(Cow::Borrowed(text));
+        };
+        let mut sealed = BASE64
+            .decode(encoded)
+            .map_err(|_| EncryptionError::Decrypt)?;
+        if sealed.len() < NONCE_LEN {
+            return Err(EncryptionError::Decrypt);
+        }
+        let mut data = sealed.split_off(NONCE_LEN);
+        let nonce =
+            Nonce::try_assume_unique_for_key(&sealed).map_err(|_| EncryptionError::Decrypt)?;
+        let plain = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut data)
+            .map_err(|_| EncryptionError::Decrypt)?;
+        String::from_utf8(plain.to_vec())
+            .map(Cow::Owned)
+            .map_err(|_| EncryptionError::Decrypt)
+    }
+}
+
+/// Returns whether `text` was written by [`Cipher::seal`].
+pub fn is_sealed(text: &str) -> bool {
+    text.starts_with(PREFIX)
+}
+
+/// Iterates over the lines of `content`, opening sealed ones.
+///
+/// Sealed lines that cannot be opened (encryption off, or the wrong key) are
+/// skipped with a single warning.
+pub fn open_lines<'a>(
+    cipher: Option<&'a Cipher>,
+    content: &'a str,
+) -> impl Iterator<Item = Cow<'a, str>> + 'a {
+    let mut warned = false;
+    content.lines().filter_map(move |line| {
+        let opened = match cipher {
+            Some(cipher) => cipher.open(line).ok(),
+            None => (!is_sealed(line)).then_some(Cow::Borrowed(line)),
+        };
+        if opened.is_none() && !warned {
+            warned = true;
+            warn!(
+                "Skipping encrypted lines that cannot be decrypted (is `encryption.enabled` set, with the key they were written with?)"
+            );
+        }
+        opened
+    })
+}
+
+/// A writer that seals every line written through it.
+///
+/// Bytes are held back until their line is complete; a partial last line is
+/// sealed when the writer is dropped. Without a cipher, writes pass through.
+pub struct SealingWriter<W: Write> {
+    inner: W,
+    cipher: Option<Cipher>,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> SealingWriter<W> {
+    pub fn new(inner: W, cipher: Option<Cipher>) -> Self {
+        Self {
+            inner,
+            cipher,
+            pending: Vec::new(),
+        }
+    }
+
+    fn write_sealed(&mut self, line: &[u8]) -> io::Result<()> {
+        let Some(cipher) = &self.cipher else {
+            return self.inner.write_all(line);
+        };
+        let sealed = cipher.seal(&String::from_utf8_lossy(line));
+        writeln!(self.inner, "{sealed}")
+    }
+}
+
+impl<W: Write> Write for SealingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.cipher.is_none() {
+            return self.inner.write(buf);
+        }
+        self.pending.extend_from_slice(buf);
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            self.write_sealed(&line[..end])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for SealingWriter<W> {
+    fn drop(&mut self) {
+        if !self.pending.is_empty() {
+            let line = std::mem::take(&mut self.pending);
+            let _ = self.write_sealed(&line);
+            let _ = self.inner.flush();
+        }
+    }
+}
+
+/// Reads the key from `path`, creating the file with a new key if needed.
+fn key_from_file(path: &Path) -> Result<String, EncryptionError> {
+    match fs::read_to_string(path) {
+        Ok(key) => Ok(key),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let key = Cipher::generate_key();
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut options = fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            writeln!(options.open(path)?, "{key}")?;
+            info!("Generated encryption key in {}", path.display());
+            Ok(key)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Reads the key from the OS keychain, storing a new key if there is none.
+fn key_from_keychain() -> Result<String, EncryptionError> {
+    let entry = keyring::Entry::new("ralph", "encryption-key")?;
+    match entry.get_password() {
+        Ok(key) => Ok(key),
+        Err(keyring::Error::NoEntry) => {
+            let key = Cipher::generate_key();
+            entry.set_password(&key)?;
+            info!("Generated encryption key in the OS keychain");
+            Ok(key)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_seal_and_open_round_trip() {
+        let cipher = Cipher::from_base64(&Cipher::generate_key()).unwrap();
+        let sealed = cipher.seal(r#"{"type":"text","text":"fn secret() {}"}"#);
+
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("secret") && !sealed.contains('\n'));
+        assert_eq!(
+            cipher.open(&sealed).unwrap(),
+            r#"{"type":"text","text":"fn secret() {}"}"#
+        );
+        // Plain text passes through; a different key cannot open the line
+        assert_eq!(cipher.open("plain").unwrap(), "plain");
+        let other = Cipher::from_base64(&Cipher::generate_key()).unwrap();
+        assert!(matches!(other.open(&sealed), Err(EncryptionError::Decrypt)));
+    }
+
+    #[test]
+    fn test_sealing_writer_seals_each_line() {
+        let cipher = Cipher::from_base64(&Cipher::generate_key()).unwrap();
+        let mut out = Vec::new();
+        {
+            let mut writer = SealingWriter::new(&mut out, Some(cipher.clone()));
+            write!(writer, "first").unwrap();
+            writeln!(writer, " line").unwrap();
+            write!(writer, "partial").unwrap();
+        }
+        let content = String::from_utf8(out).unwrap();
+        let mixed = format!("plain line\n{content}");
+
+        assert_eq!(content.lines().count(), 2);
+        assert!(content.lines().all(is_sealed));
+        assert_eq!(
+            open_lines(Some(&cipher), &mixed).collect::<Vec<_>>(),
+            ["plain line", "first line", "partial"]
+        );
+        assert_eq!(open_lines(None, &mixed).collect::<Vec<_>>(), ["plain line"]);
+    }
+
+    #[test]
+    fn test_key_file_is_created_once() {
+        let temp = TempDir::new().unwrap();
+        let config = EncryptionConfig {
+            enabled: true,
+            key_file: Some("keys/ralph.key".into()),
+        };
+
+        let first = Cipher::load(&config, temp.path()).unwrap().unwrap();
+        let sealed = first.seal("text");
+        let second = Cipher::load(&config, temp.path()).unwrap().unwrap();
+        assert_eq!(second.open(&sealed).unwrap(), "text");
+
+        let disabled = EncryptionConfig::default();
+        assert!(Cipher::load(&disabled, temp.path()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,280 @@
+//! Snapshot of the environment an iteration started in.
+//!
+//! When a loop "behaved differently yesterday", the difference is often
+//! outside the prompt: another commit checked out, stray local edits, an
+//! upgraded backend CLI, a full disk, or a variable set in one shell but not
+//! another. A snapshot captures those at iteration start. Only the *names* of
+//! environment variables are kept, never their values, since they commonly
+//! hold API keys.
+
+use crate::git_ops::{get_current_branch, get_head_sha};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Prefixes of environment variables that affect Ralph or its backends.
+const KEY_ENV_PREFIXES: &[&str] = &[
+    "RALPH_",
+    "ANTHROPIC_",
+    "CLAUDE_",
+    "OPENAI_",
+    "CODEX_",
+    "GEMINI_",
+    "GOOGLE_",
+    "AMP_",
+    "KIRO_",
+    "AWS_",
+    "AZURE_",
+];
+
+/// Environment variables that affect networking for every backend.
+const KEY_ENV_NAMES: &[&str] = &["HTTP_PROXY", "HTTPS_PROXY", "NO_PROXY", "ALL_PROXY"];
+
+/// At most this many dirty files are listed; the count is always exact.
+const MAX_DIRTY_FILES: usize = 50;
+
+/// How long `<backend> --version` may take before it is abandoned.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The environment at the start of an iteration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvironmentSnapshot {
+    /// Commit checked out in the workspace.
+    pub git_head: Option<String>,
+    /// Branch checked out, unless HEAD is detached.
+    pub git_branch: Option<String>,
+    /// `git status --porcelain` lines, at most `MAX_DIRTY_FILES` of them.
+    pub dirty_files: Vec<String>,
+    /// Total number of dirty files.
+    pub dirty_count: usize,
+    /// Backend the iteration runs on.
+    pub backend: String,
+    /// First line of the backend's `--version` output.
+    pub backend_version: Option<String>,
+    /// Free space on the workspace's filesystem.
+    pub free_disk_bytes: Option<u64>,
+    /// Names of relevant environment variables that are set.
+    pub env_vars: Vec<String>,
+}
+
+impl EnvironmentSnapshot {
+    /// Captures the environment of `workspace_root`.
+    ///
+    /// `backend_version` is passed in rather than probed here because running
+    /// the backend CLI on every iteration is slow; see [`backend_version`].
+    pub fn capture(
+        workspace_root: &Path,
+        backend: impl Into<String>,
+        backend_version: Option<String>,
+    ) -> Self {
+        let status = dirty_files(workspace_root);
+        Self {
+            git_head: get_head_sha(workspace_root).ok(),
+            git_branch: get_current_branch(workspace_root).ok(),
+            dirty_count: status.len(),
+            dirty_files: status.into_iter().take(MAX_DIRTY_FILES).collect(),
+            backend: backend.into(),
+            backend_version,
+            free_disk_bytes: free_disk_bytes(workspace_root),
+            env_vars: key_env_vars(std::env::vars().map(|(name, _)| name)),
+        }
+    }
+
+    /// Formats the snapshot for display, one fact per line.
+    pub fn summary_lines(&self) -> Vec<String> {
+        let unknown = || "unknown".to_string();
+        let mut lines = vec![
+            format!(
+                "Git HEAD:  {} ({})",
+                self.git_head
+                    .as_deref()
+                    .map_or_else(unknown, |sha| { sha.chars().take(12).collect() }),
+                self.git_branch.as_deref().unwrap_or("detached"),
+            ),
+            format!(
+                "Backend:   {} {}",
+                self.backend,
+                self.backend_version
+                    .as_deref()
+                    .unwrap_or("(version unknown)")
+            ),
+            format!(
+                "Free disk: {}",
+                self.free_disk_bytes.map_or_else(unknown, format_bytes)
+            ),
+            format!(
+                "Env vars:  {}",
+                if self.env_vars.is_empty() {
+                    "none".to_string()
+                } else {
+                    self.env_vars.join(", ")
+                }
+            ),
+            format!("Dirty files: {}", self.dirty_count),
+        ];
+        lines.extend(self.dirty_files.iter().map(|file| format!("  {file}")));
+        if self.dirty_count > self.dirty_files.len() {
+            lines.push(format!(
+                "  … and {} more",
+                self.dirty_count - self.dirty_files.len()
+            ));
+        }
+        lines
+    }
+}
+
+/// Returns the first line of `<command> --version`, or `None` if the command
+/// fails or takes longer than a few seconds.
+pub fn backend_version(command: &str) -> Option<String> {
+    let mut child = Command::new(command)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => break,
+            Ok(Some(_)) | Err(_) => return None,
+            Ok(None) if started.elapsed() >= VERSION_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+        }
+    }
+
+    let output = child.wait_with_output().ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+fn dirty_files(workspace_root: &Path) -> Vec<String> {
+    Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(workspace_root)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.trim_end().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn free_disk_bytes(path: &Path) -> Option<u64> {
+    let stat = nix::sys::statvfs::statvfs(path).ok()?;
+    #[allow(clippy::useless_conversion)] // The field widths differ between platforms
+    Some(u64::from(stat.blocks_available()) * u64::from(stat.fragment_size()))
+}
+
+#[cfg(not(unix))]
+fn free_disk_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Picks the relevant names out of `names`, sorted.
+fn key_env_vars(names: impl Iterator<Item = String>) -> Vec<String> {
+    let mut names: Vec<String> = names
+        .filter(|name| {
+            KEY_ENV_NAMES.contains(&name.as_str())
+                || KEY_ENV_PREFIXES
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix))
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+#[allow(clippy::cast_precision_loss)] // Display only
+fn format_bytes(bytes: u64) -> String {
+    const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    const MIB: f64 = 1024.0 * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= GIB {
+        format!("{:.1} GiB", bytes / GIB)
+    } else {
+        format!("{:.0} MiB", bytes / MIB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_key_env_vars_keeps_only_relevant_names() {
+        let names = [
+            "PATH",
+            "RALPH_DEBUG",
+            "ANTHROPIC_API_KEY",
+            "HTTPS_PROXY",
+            "HOME",
+        ]
+        .into_iter()
+        .map(str::to_string);
+        assert_eq!(
+            key_env_vars(names),
+            vec!["ANTHROPIC_API_KEY", "HTTPS_PROXY", "RALPH_DEBUG"]
+        );
+    }
+
+    #[test]
+    fn test_capture_outside_git_repo() {
+        let temp = TempDir::new().unwrap();
+        let snapshot = EnvironmentSnapshot::capture(temp.path(), "claude", None);
+        assert_eq!(snapshot.git_head, None);
+        assert_eq!(snapshot.dirty_count, 0);
+        #[cfg(unix)]
+        assert!(snapshot.free_disk_bytes.is_some());
+    }
+
+    #[test]
+    fn test_summary_lines_truncates_dirty_files() {
+        let snapshot = EnvironmentSnapshot {
+            git_head: Some("0123456789abcdef".to_string()),
+            git_branch: Some("main".to_string()),
+            dirty_files: vec![" M src/lib.rs".to_string()],
+            dirty_count: 3,
+            backend: "claude".to_string(),
+            backend_version: Some("1.0.0 (Claude Code)".to_string()),
+            free_disk_bytes: Some(5 * 1024 * 1024 * 1024),
+            env_vars: Vec::new(),
+        };
+        assert_eq!(
+            snapshot.summary_lines(),
+            vec![
+                "Git HEAD:  0123456789ab (main)",
+                "Backend:   claude 1.0.0 (Claude Code)",
+                "Free disk: 5.0 GiB",
+                "Env vars:  none",
+                "Dirty files: 3",
+                "   M src/lib.rs",
+                "  … and 2 more",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backend_version_of_missing_command() {
+        assert_eq!(
+            backend_version("ralph-test-command-that-does-not-exist"),
+            None
+        );
+    }
+}
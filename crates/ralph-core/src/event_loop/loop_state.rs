@@ -4,12 +4,14 @@
 //! state of the orchestration loop including iteration count, failures,
 //! timing, and hat activation tracking.
 
+use crate::cost_anomaly::CostAnomaly;
 use ralph_proto::HatId;
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 
 /// Current state of the event loop.
 #[derive(Debug)]
+#[non_exhaustive]
 pub struct LoopState {
     /// Current iteration number (1-indexed).
     pub iteration: u32,
@@ -43,6 +45,9 @@ pub struct LoopState {
     /// When the last Telegram check-in message was sent.
     /// `None` means no check-in has been sent yet.
     pub last_checkin_at: Option<Instant>,
+
+    /// Iterations whose cost or tokens were outliers.
+    pub cost_anomalies: Vec<CostAnomaly>,
 }
 
 impl Default for LoopState {
@@ -62,6 +67,7 @@ impl Default for LoopState {
             hat_activation_counts: HashMap::new(),
             exhausted_hats: HashSet::new(),
             last_checkin_at: None,
+            cost_anomalies: Vec::new(),
         }
     }
 }
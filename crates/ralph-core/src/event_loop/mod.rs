@@ -8,15 +8,25 @@ mod tests;
 
 pub use loop_state::LoopState;
 
+use crate::audit_log::{AuditAction, AuditEntry, AuditSource};
 use crate::config::{HatBackend, InjectMode, RalphConfig};
+use crate::cost_anomaly::CostAnomaly;
+use crate::encryption::Cipher;
+use crate::event_logger::EventRecord;
 use crate::event_parser::EventParser;
 use crate::event_reader::EventReader;
+use crate::gates::Gates;
 use crate::hat_registry::HatRegistry;
 use crate::hatless_ralph::HatlessRalph;
 use crate::instructions::InstructionBuilder;
 use crate::loop_context::LoopContext;
 use crate::memory_store::{MarkdownMemoryStore, format_memories_as_markdown, truncate_to_budget};
+use crate::repos::RepoSet;
+use crate::router::{RouterCandidate, RouterDecision};
+use crate::scope::Scope;
 use crate::skill_registry::SkillRegistry;
+use crate::text::truncate_with_ellipsis;
+use crate::tool_result_store::ToolResultStore;
 use ralph_proto::{Event, EventBus, Hat, HatId};
 use ralph_telegram::TelegramService;
 use std::path::PathBuf;
@@ -27,6 +37,7 @@ use tracing::{debug, info, warn};
 
 /// Reason the event loop terminated.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum TerminationReason {
     /// Completion promise was detected in output.
     CompletionPromise,
@@ -108,6 +119,17 @@ impl TerminationReason {
         )
     }
 
+    /// Returns true if the run ended without finishing its work: a limit,
+    /// repeated failures or an abort. A restart or the end of chaos mode's
+    /// exploration is not abnormal.
+    pub fn is_abnormal(&self) -> bool {
+        !self.is_success()
+            && !matches!(
+                self,
+                TerminationReason::RestartRequested | TerminationReason::ChaosModeMaxIterations
+            )
+    }
+
     /// Returns true if this termination triggers chaos mode.
     ///
     /// Chaos mode ONLY activates after LOOP_COMPLETE - not on other termination reasons.
@@ -137,6 +159,14 @@ pub struct EventLoop {
     /// Telegram service for human-in-the-loop communication.
     /// Only initialized when `human.enabled` is true and this is the primary loop.
     telegram_service: Option<TelegramService>,
+    /// Monorepo packages the run is limited to (empty = unscoped).
+    scope: Scope,
+    /// HEAD when the run started; scope checks cover changes since then.
+    scope_base: Option<String>,
+    /// Commands behind the backpressure checks, shown in every prompt.
+    gates: Gates,
+    /// Hat the router chose to work next; consumed by the next prompt.
+    routed_hat: Option<HatId>,
 }
 
 impl EventLoop {
@@ -255,6 +285,8 @@ impl EventLoop {
         // Initialize Telegram service if human-in-the-loop is enabled
         // and this is the primary loop (has a loop context with is_primary).
         let telegram_service = Self::create_telegram_service(&config, Some(&context));
+        let scope = Scope::from_config(&config.scope, context.workspace());
+        let gates = Gates::resolve(&config.gates, context.workspace(), &scope);
 
         Self {
             config,
@@ -269,6 +301,10 @@ impl EventLoop {
             loop_context: Some(context),
             skill_registry,
             telegram_service,
+            scope,
+            scope_base: None,
+            gates,
+            routed_hat: None,
         }
     }
 
@@ -349,6 +385,8 @@ impl EventLoop {
         // Initialize Telegram service if human-in-the-loop is enabled.
         // Legacy single-loop mode (no context) is treated as primary.
         let telegram_service = Self::create_telegram_service(&config, None);
+        let scope = Scope::from_config(&config.scope, &config.core.workspace_root);
+        let gates = Gates::resolve(&config.gates, &config.core.workspace_root, &scope);
 
         Self {
             config,
@@ -363,6 +401,10 @@ impl EventLoop {
             loop_context: None,
             skill_registry,
             telegram_service,
+            scope,
+            scope_base: None,
+            gates,
+            routed_hat: None,
         }
     }
 
@@ -437,6 +479,44 @@ impl EventLoop {
             .unwrap_or_else(|| PathBuf::from(".ralph/agent/tasks.jsonl"))
     }
 
+    /// Returns the workspace root based on loop context or config.
+    fn workspace_root(&self) -> PathBuf {
+        self.loop_context
+            .as_ref()
+            .map(|ctx| ctx.workspace().to_path_buf())
+            .unwrap_or_else(|| self.config.core.workspace_root.clone())
+    }
+
+    /// Builds a `*.blocked` payload: `note` plus the failing output from the
+    /// rejected `payload` and the uncommitted diff hunks, within budget.
+    fn failure_feedback(&self, note: &str, payload: &str) -> String {
+        crate::failure_feedback::failure_feedback(
+            note,
+            payload,
+            &self.workspace_root(),
+            self.config.event_loop.failure_feedback_tokens,
+        )
+    }
+
+    /// Returns where full tool results (and summarized payloads) are stored.
+    fn tool_results_dir(&self) -> PathBuf {
+        self.loop_context
+            .as_ref()
+            .map(|ctx| ctx.tool_results_dir())
+            .unwrap_or_else(|| self.workspace_root().join(".ralph/tool-results"))
+    }
+
+    /// Returns the scratchpad path, relative paths resolved against the
+    /// workspace root.
+    fn resolved_scratchpad_path(&self) -> PathBuf {
+        let scratchpad_path = self.scratchpad_path();
+        if scratchpad_path.is_relative() {
+            self.config.core.workspace_root.join(&scratchpad_path)
+        } else {
+            scratchpad_path
+        }
+    }
+
     /// Returns the scratchpad path based on loop context or config.
     fn scratchpad_path(&self) -> PathBuf {
         self.loop_context
@@ -455,11 +535,21 @@ impl EventLoop {
         &self.config
     }
 
+    /// Changes the cost budget of the running loop.
+    pub fn set_max_cost_usd(&mut self, max_cost_usd: Option<f64>) {
+        self.config.event_loop.max_cost_usd = max_cost_usd;
+    }
+
     /// Returns the hat registry.
     pub fn registry(&self) -> &HatRegistry {
         &self.registry
     }
 
+    /// Returns the commands behind the backpressure checks.
+    pub fn gates(&self) -> &Gates {
+        &self.gates
+    }
+
     /// Gets the backend configuration for a hat.
     ///
     /// If the hat has a backend configured, returns that.
@@ -563,6 +653,10 @@ impl EventLoop {
         // so without this the objective would be invisible to later hats.
         self.ralph.set_objective(prompt_content.to_string());
 
+        if !self.scope.is_empty() {
+            self.scope_base = crate::git_ops::get_head_sha(self.workspace_root()).ok();
+        }
+
         let start_event = Event::new(topic, prompt_content);
         self.bus.publish(start_event);
         debug!(topic = topic, "Published {} event", topic);
@@ -691,7 +785,7 @@ impl EventLoop {
 
                 let events_context = regular_events
                     .iter()
-                    .map(|e| Self::format_event(e))
+                    .map(|e| self.format_event(e))
                     .collect::<Vec<_>>()
                     .join("\n");
 
@@ -704,7 +798,9 @@ impl EventLoop {
                 self.ralph.clear_robot_guidance();
                 let with_skills = self.prepend_auto_inject_skills(base_prompt);
                 let with_scratchpad = self.prepend_scratchpad(with_skills);
-                let final_prompt = self.prepend_ready_tasks(with_scratchpad);
+                let with_repos = self.prepend_repositories(with_scratchpad);
+                let with_scope = self.prepend_scope(with_repos);
+                let final_prompt = self.prepend_ready_tasks(with_scope);
 
                 debug!("build_prompt: routing to HatlessRalph (solo mode)");
                 return Some(final_prompt);
@@ -716,8 +812,15 @@ impl EventLoop {
 
                 let mut all_events = Vec::new();
                 let mut system_events = Vec::new();
+                let routed = self.routed_hat.take();
 
                 for id in &all_hat_ids {
+                    // Hats the router passed over keep their events for later
+                    if routed.as_ref().is_some_and(|routed| routed != id)
+                        && self.registry.get(id).is_some()
+                    {
+                        continue;
+                    }
                     let pending = self.bus.take_pending(id);
                     if pending.is_empty() {
                         continue;
@@ -760,7 +863,7 @@ impl EventLoop {
                 // Format events for context
                 let events_context = regular_events
                     .iter()
-                    .map(|e| Self::format_event(e))
+                    .map(|e| self.format_event(e))
                     .collect::<Vec<_>>()
                     .join("\n");
 
@@ -780,7 +883,9 @@ impl EventLoop {
                 self.ralph.clear_robot_guidance();
                 let with_skills = self.prepend_auto_inject_skills(base_prompt);
                 let with_scratchpad = self.prepend_scratchpad(with_skills);
-                let final_prompt = self.prepend_ready_tasks(with_scratchpad);
+                let with_repos = self.prepend_repositories(with_scratchpad);
+                let with_scope = self.prepend_scope(with_repos);
+                let final_prompt = self.prepend_ready_tasks(with_scope);
 
                 return Some(final_prompt);
             }
@@ -792,7 +897,7 @@ impl EventLoop {
         let events = self.bus.take_pending(&hat_id.clone());
         let events_context = events
             .iter()
-            .map(|e| Self::format_event(e))
+            .map(|e| self.format_event(e))
             .collect::<Vec<_>>()
             .join("\n");
 
@@ -1056,13 +1161,7 @@ impl EventLoop {
     /// Auto-injecting saves one tool call per iteration.
     /// When the file exceeds the budget, the TAIL is kept (most recent entries).
     fn prepend_scratchpad(&self, prompt: String) -> String {
-        let scratchpad_path = self.scratchpad_path();
-
-        let resolved_path = if scratchpad_path.is_relative() {
-            self.config.core.workspace_root.join(&scratchpad_path)
-        } else {
-            scratchpad_path
-        };
+        let resolved_path = self.resolved_scratchpad_path();
 
         if !resolved_path.exists() {
             debug!(
@@ -1221,6 +1320,33 @@ impl EventLoop {
         final_prompt
     }
 
+    /// Prepends the `<repositories>` block for multi-repo runs.
+    ///
+    /// Relative repo paths resolve against the main repository, so worktree
+    /// loops see the same repositories as the primary loop.
+    fn prepend_repositories(&self, prompt: String) -> String {
+        if self.config.repos.is_empty() {
+            return prompt;
+        }
+        let root = self
+            .loop_context
+            .as_ref()
+            .map(|ctx| ctx.repo_root().to_path_buf())
+            .unwrap_or_else(|| self.config.core.workspace_root.clone());
+        let mut section = RepoSet::from_config(&self.config.repos, &root).prompt_section();
+        section.push_str(&prompt);
+        section
+    }
+
+    /// Prepends the `<scope>` block when the run is limited to some packages,
+    /// followed by the `<gates>` block.
+    fn prepend_scope(&self, prompt: String) -> String {
+        let mut section = self.scope.prompt_section();
+        section.push_str(&self.gates.prompt_section());
+        section.push_str(&prompt);
+        section
+    }
+
     /// Builds the Ralph prompt (coordination mode).
     pub fn build_ralph_prompt(&self, prompt_content: &str) -> String {
         self.ralph.build_prompt(prompt_content, &[])
@@ -1255,7 +1381,10 @@ impl EventLoop {
     ///
     /// For top-level prompts (task.start, task.resume), wraps the payload in
     /// `<top-level-prompt>` XML tags to clearly delineate the user's original request.
-    fn format_event(event: &Event) -> String {
+    ///
+    /// Other payloads over `payload_summary_tokens` are cut down to their
+    /// relevant excerpts; the user's own prompt is always passed in full.
+    fn format_event(&self, event: &Event) -> String {
         let topic = &event.topic;
         let payload = &event.payload;
 
@@ -1265,6 +1394,11 @@ impl EventLoop {
                 topic, payload
             )
         } else {
+            let payload = crate::payload_summary::summarize_payload(
+                payload,
+                self.config.event_loop.payload_summary_tokens,
+                &ToolResultStore::new(self.tool_results_dir()),
+            );
             format!("Event: {} - {}", topic, payload)
         }
     }
@@ -1327,9 +1461,70 @@ impl EventLoop {
         }
     }
 
+    /// Returns the hats with pending events when more than one could be
+    /// activated, so the router can choose between them. Empty otherwise.
+    pub fn routing_candidates(&self) -> Vec<RouterCandidate> {
+        let mut hat_ids: Vec<&HatId> = self.bus.hat_ids().collect();
+        hat_ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let mut candidates: Vec<RouterCandidate> = Vec::new();
+        for id in hat_ids {
+            for event in self.bus.peek_pending(id).into_iter().flatten() {
+                let Some(hat) = self.registry.get_for_topic(event.topic.as_str()) else {
+                    continue;
+                };
+                let topic = event.topic.to_string();
+                match candidates.iter_mut().find(|c| c.hat == hat.id) {
+                    Some(candidate) if !candidate.topics.contains(&topic) => {
+                        candidate.topics.push(topic);
+                    }
+                    Some(_) => {}
+                    None => candidates.push(RouterCandidate {
+                        hat: hat.id.clone(),
+                        description: self
+                            .registry
+                            .get_config(&hat.id)
+                            .and_then(|config| config.description.clone())
+                            .unwrap_or_else(|| hat.name.clone()),
+                        topics: vec![topic],
+                    }),
+                }
+            }
+        }
+        if candidates.len() < 2 {
+            candidates.clear();
+        }
+        candidates
+    }
+
+    /// Builds the router's classification prompt for `candidates`, from the
+    /// scratchpad and the run's recent `history`.
+    pub fn routing_prompt(
+        &self,
+        candidates: &[RouterCandidate],
+        history: &[EventRecord],
+    ) -> String {
+        let summary = std::fs::read_to_string(self.resolved_scratchpad_path()).unwrap_or_default();
+        crate::router::router_prompt(candidates, &summary, history)
+    }
+
+    /// Makes the router's choice the only hat activated by the next prompt,
+    /// and shows the decision to observers. Returns the `router.decision`
+    /// event for the event log.
+    pub fn route_to(&mut self, decision: &RouterDecision) -> Event {
+        info!(hat = %decision.hat, "Router chose {}: {}", decision.hat, decision.rationale);
+        self.routed_hat = Some(decision.hat.clone());
+        let event = decision.to_event();
+        self.bus.notify_observers(&event);
+        event
+    }
+
     /// Returns the primary active hat ID for display purposes.
     /// Returns the first active hat, or "ralph" if no specific hat is active.
     pub fn get_active_hat_id(&self) -> HatId {
+        if let Some(routed) = &self.routed_hat {
+            return routed.clone();
+        }
         // Peek at pending events (don't consume them)
         for hat_id in self.bus.hat_ids() {
             let Some(events) = self.bus.peek_pending(hat_id) else {
@@ -1514,6 +1709,11 @@ impl EventLoop {
         self.state.cumulative_cost += cost;
     }
 
+    /// Records an iteration whose cost or tokens were an outlier, for the summary.
+    pub fn record_cost_anomaly(&mut self, anomaly: CostAnomaly) {
+        self.state.cost_anomalies.push(anomaly);
+    }
+
     /// Verifies all tasks in scratchpad are complete or cancelled.
     ///
     /// Returns:
@@ -1658,8 +1858,43 @@ impl EventLoop {
             if event.topic == "build.done" {
                 // Validate build.done events have backpressure evidence
                 if let Some(evidence) = EventParser::parse_backpressure_evidence(&payload) {
-                    if evidence.all_passed() {
+                    let out_of_scope = if evidence.all_passed() {
+                        self.scope
+                            .changes_outside(&self.workspace_root(), self.scope_base.as_deref())
+                    } else {
+                        Vec::new()
+                    };
+                    if evidence.all_passed() && out_of_scope.is_empty() {
                         validated_events.push(Event::new(event.topic.as_str(), &payload));
+                    } else if evidence.all_passed() {
+                        // Checks pass but files outside the scoped packages changed
+                        warn!(
+                            files = ?out_of_scope,
+                            "build.done rejected: changes outside scope"
+                        );
+
+                        self.diagnostics.log_orchestration(
+                            self.state.iteration,
+                            "jsonl",
+                            crate::diagnostics::OrchestrationEvent::BackpressureTriggered {
+                                reason: format!(
+                                    "changes outside scope: {}",
+                                    out_of_scope.join(", ")
+                                ),
+                            },
+                        );
+
+                        validated_events.push(Event::new(
+                            "build.blocked",
+                            format!(
+                                "Changes outside the run's scope. Revert or move these before emitting build.done:\n{}",
+                                out_of_scope
+                                    .iter()
+                                    .map(|file| format!("- {file}"))
+                                    .collect::<Vec<_>>()
+                                    .join("\n")
+                            ),
+                        ));
                     } else {
                         // Evidence present but checks failed - synthesize build.blocked
                         warn!(
@@ -1684,7 +1919,10 @@ impl EventLoop {
 
                         validated_events.push(Event::new(
                             "build.blocked",
-                            "Backpressure checks failed. Fix tests/lint/typecheck before emitting build.done.",
+                            self.failure_feedback(
+                                "Backpressure checks failed. Fix tests/lint/typecheck before emitting build.done.",
+                                &payload,
+                            ),
                         ));
                     }
                 } else {
@@ -1730,7 +1968,10 @@ impl EventLoop {
 
                         validated_events.push(Event::new(
                             "review.blocked",
-                            "Review verification failed. Run tests and build before emitting review.done.",
+                            self.failure_feedback(
+                                "Review verification failed. Run tests and build before emitting review.done.",
+                                &payload,
+                            ),
                         ));
                     }
                 } else {
@@ -1875,6 +2116,12 @@ impl EventLoop {
                                 response = %response,
                                 "Received human.response — continuing loop"
                             );
+                            if let Some(ctx) = &self.loop_context {
+                                ctx.audit_log().record_or_warn(
+                                    AuditEntry::new(AuditAction::Response, AuditSource::Telegram)
+                                        .with_detail(truncate_with_ellipsis(&response, 200)),
+                                );
+                            }
                             // Create a human.response event to inject into the bus
                             response_event = Some(Event::new("human.response", &response));
                         }
@@ -1982,6 +2229,11 @@ impl EventLoop {
         event
     }
 
+    /// Seals the agent output captured by `RALPH_DIAGNOSTICS` with `cipher`.
+    pub fn seal_diagnostics(&mut self, cipher: Option<Cipher>) {
+        self.diagnostics.set_cipher(cipher);
+    }
+
     /// Returns the Telegram service's shutdown flag, if active.
     ///
     /// Signal handlers can set this flag to interrupt `wait_for_response()`
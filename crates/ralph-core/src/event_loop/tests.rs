@@ -35,7 +35,10 @@ hats:
 
 #[test]
 fn test_guidance_persists_across_iterations_solo_mode() {
-    let config = RalphConfig::default();
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = dir.path().to_path_buf();
+    config.core.scratchpad = dir.path().join("scratchpad.md").display().to_string();
     let mut event_loop = EventLoop::new(config);
     let ralph_id = HatId::new("ralph");
 
@@ -73,7 +76,10 @@ hats:
     triggers: ["task.start"]
     publishes: ["task.plan"]
 "#;
-    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let mut config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    config.core.workspace_root = dir.path().to_path_buf();
+    config.core.scratchpad = dir.path().join("scratchpad.md").display().to_string();
     let mut event_loop = EventLoop::new(config);
     let ralph_id = HatId::new("ralph");
 
@@ -1002,6 +1008,10 @@ fn test_default_publishes_injects_when_no_events() {
             backend: None,
             default_publishes: Some("task.done".to_string()),
             max_activations: None,
+            color: None,
+            stop_sequences: Vec::new(),
+            max_output_tokens: None,
+            response_format: crate::config::ResponseFormat::Text,
         },
     );
     config.hats = hats;
@@ -1050,6 +1060,10 @@ fn test_default_publishes_not_injected_when_events_written() {
             backend: None,
             default_publishes: Some("task.done".to_string()),
             max_activations: None,
+            color: None,
+            stop_sequences: Vec::new(),
+            max_output_tokens: None,
+            response_format: crate::config::ResponseFormat::Text,
         },
     );
     config.hats = hats;
@@ -1100,6 +1114,10 @@ fn test_default_publishes_not_injected_when_not_configured() {
             backend: None,
             default_publishes: None, // No default configured
             max_activations: None,
+            color: None,
+            stop_sequences: Vec::new(),
+            max_output_tokens: None,
+            response_format: crate::config::ResponseFormat::Text,
         },
     );
     config.hats = hats;
@@ -1959,6 +1977,81 @@ fn test_scratchpad_injection_empty_file() {
     );
 }
 
+#[test]
+fn test_repositories_injected_for_multi_repo_runs() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.repos = vec![crate::config::RepoConfig {
+        name: "client".to_string(),
+        path: "client".into(),
+        description: Some("Web client".to_string()),
+    }];
+
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test prompt");
+
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+    let section = prompt
+        .find("<repositories>")
+        .expect("multi-repo prompt should list repositories");
+    assert!(prompt[section..].contains("## client ("));
+    assert!(prompt[section..].contains("Web client\n"));
+}
+
+#[test]
+fn test_scoped_run_rejects_build_done_outside_scope() {
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .output()
+            .unwrap();
+    };
+    std::fs::create_dir_all(root.join("packages/api")).unwrap();
+    std::fs::write(root.join("packages/api/package.json"), "{}").unwrap();
+    git(&["init", "--initial-branch=main"]);
+    git(&["config", "user.email", "test@test.local"]);
+    git(&["config", "user.name", "Test User"]);
+    git(&["add", "."]);
+    git(&["commit", "-m", "Initial commit"]);
+
+    let yaml = "scope:\n  packages: [packages/api]\n";
+    let mut config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    config.core.workspace_root = root.to_path_buf();
+    let events_path = root.join(".ralph/events.jsonl");
+    std::fs::create_dir_all(events_path.parent().unwrap()).unwrap();
+    let mut event_loop = EventLoop::new(config);
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+    event_loop.initialize("Test prompt");
+
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+    assert!(prompt.contains("<scope>"));
+    assert!(prompt.contains("<gates>\n"));
+    assert!(prompt.contains("- tests: `npm test --workspace=packages/api`\n"));
+
+    std::fs::write(root.join("README.md"), "outside").unwrap();
+    let evidence = "tests: pass\nlint: pass\ntypecheck: pass";
+    write_event_to_jsonl(&events_path, "build.done", evidence);
+    let _ = event_loop.process_events_from_jsonl();
+
+    let empty = Vec::new();
+    let blocked = event_loop
+        .bus
+        .hat_ids()
+        .flat_map(|id| event_loop.bus.peek_pending(id).unwrap_or(&empty).iter())
+        .find(|e| e.topic.as_str() == "build.blocked")
+        .expect("build.done with out-of-scope changes should be blocked");
+    assert!(blocked.payload.contains("- README.md"));
+}
+
 #[test]
 fn test_scratchpad_injection_ordering() {
     use tempfile::TempDir;
@@ -2173,6 +2266,40 @@ fn test_review_done_backpressure_rejects_failed_checks() {
     );
 }
 
+#[test]
+fn test_build_blocked_includes_failing_output() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    // A workspace outside any repository: no diff, only the failing output.
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    let mut event_loop = EventLoop::new(config);
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    write_event_to_jsonl(
+        &events_path,
+        "build.done",
+        "tests: fail\nlint: pass\ntypecheck: pass\nFAILED parser::parses_empty",
+    );
+    let _ = event_loop.process_events_from_jsonl();
+
+    let empty = Vec::new();
+    let blocked = event_loop
+        .bus
+        .hat_ids()
+        .flat_map(|id| event_loop.bus.peek_pending(id).unwrap_or(&empty).iter())
+        .find(|e| e.topic.as_str() == "build.blocked")
+        .expect("failed build.done should be blocked");
+
+    assert!(blocked.payload.starts_with("Backpressure checks failed."));
+    assert!(blocked.payload.contains("## Failing output"));
+    assert!(blocked.payload.contains("FAILED parser::parses_empty"));
+    assert!(!blocked.payload.contains("lint: pass"));
+}
+
 // === RObot Interaction Skill Injection Tests ===
 
 #[test]
@@ -2216,3 +2343,105 @@ fn test_inject_robot_skill_skipped_when_disabled() {
         "Prompt should NOT contain <robot-skill> when RObot is disabled"
     );
 }
+
+#[test]
+fn test_prompt_suggests_gates_for_detected_project() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join("Cargo.toml"), "[workspace]\n").unwrap();
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.gates.lint = Some("cargo fmt --check".to_string());
+
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test prompt");
+
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+    assert!(prompt.contains("<gates>\nProject: Rust (Cargo.toml)\n"));
+    assert!(prompt.contains("- tests: `cargo test`\n"));
+    assert!(prompt.contains("- lint: `cargo fmt --check`\n"));
+}
+
+#[test]
+fn test_oversized_payload_is_summarized_in_prompt() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.event_loop.payload_summary_tokens = 100;
+    let mut event_loop = EventLoop::new(config);
+
+    let task = "Fix the build. ".repeat(100);
+    event_loop.initialize(&task);
+    let mut log: Vec<String> = (0..300).map(|i| format!("test case_{i} ... ok")).collect();
+    log[150] = "test case_150 ... FAILED".to_string();
+    event_loop
+        .bus
+        .publish(Event::new("build.blocked", log.join("\n")));
+
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+    assert!(
+        prompt.contains(&task),
+        "The user's prompt is never summarized"
+    );
+    assert!(prompt.contains("[Summarized from 300 lines"));
+    assert!(prompt.contains("test case_150 ... FAILED"));
+    assert!(!prompt.contains("test case_100 ... ok"));
+
+    let stored = std::fs::read_dir(temp_dir.path().join(".ralph/tool-results"))
+        .unwrap()
+        .count();
+    assert_eq!(stored, 1);
+}
+
+#[test]
+fn test_router_decision_activates_only_the_chosen_hat() {
+    let yaml = r#"
+hats:
+  builder:
+    name: "Builder"
+    description: "Implements tasks"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+  reviewer:
+    name: "Reviewer"
+    triggers: ["review.request"]
+    publishes: ["review.done"]
+"#;
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test task");
+    event_loop.build_prompt(&HatId::new("ralph")); // Consume task.start
+
+    event_loop
+        .bus
+        .publish(Event::new("build.task", "Implement the parser"));
+    event_loop
+        .bus
+        .publish(Event::new("review.request", "Review the lexer"));
+
+    let candidates = event_loop.routing_candidates();
+    let hats: Vec<&str> = candidates.iter().map(|c| c.hat.as_str()).collect();
+    assert_eq!(hats, ["builder", "reviewer"]);
+    assert_eq!(candidates[0].description, "Implements tasks");
+    assert_eq!(candidates[1].description, "Reviewer");
+
+    let decision = RouterDecision {
+        hat: HatId::new("reviewer"),
+        rationale: "Review before building on the lexer.".to_string(),
+    };
+    let event = event_loop.route_to(&decision);
+    assert_eq!(event.topic.as_str(), crate::router::ROUTER_DECISION_TOPIC);
+    assert_eq!(event_loop.get_active_hat_id().as_str(), "reviewer");
+
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+    assert!(prompt.contains("Review the lexer"));
+    assert!(!prompt.contains("Implement the parser"));
+
+    // The builder's work is still queued, and with one hat left there is
+    // nothing to route
+    assert_eq!(event_loop.get_active_hat_id().as_str(), "builder");
+    assert!(event_loop.routing_candidates().is_empty());
+}
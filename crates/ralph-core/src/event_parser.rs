@@ -12,7 +12,7 @@ use ralph_proto::{Event, HatId};
 ///
 /// Handles CSI sequences (\x1b[...m), OSC sequences (\x1b]...\x07),
 /// and simple escape sequences (\x1b followed by a single char).
-fn strip_ansi(s: &str) -> String {
+pub(crate) fn strip_ansi(s: &str) -> String {
     let bytes = s.as_bytes();
     let mut result = Vec::with_capacity(bytes.len());
     let mut i = 0;
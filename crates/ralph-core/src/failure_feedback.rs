@@ -0,0 +1,264 @@
+//! Concrete feedback for rejected `build.done` and `review.done` events.
+//!
+//! When backpressure rejects a completion event, the next iteration used to
+//! see only a generic "tests failed" note and had to rediscover what broke.
+//! This module attaches the failing output the agent reported and the
+//! uncommitted diff hunks to the synthesized `build.blocked` /
+//! `review.blocked` payload. Hunks in files named by the failing output come
+//! first, and everything is trimmed to a token budget.
+
+use crate::event_parser::strip_ansi;
+use std::path::Path;
+use std::process::Command;
+
+/// Rough estimate used for all prompt budgets in Ralph.
+pub(crate) const CHARS_PER_TOKEN: usize = 4;
+
+/// Builds the payload of a synthesized `*.blocked` event.
+///
+/// `note` is the generic rejection message, `payload` the rejected event's
+/// payload and `workspace` the repository to diff. With a budget of 0 only
+/// the note is returned.
+pub(crate) fn failure_feedback(
+    note: &str,
+    payload: &str,
+    workspace: &Path,
+    budget_tokens: usize,
+) -> String {
+    if budget_tokens == 0 {
+        return note.to_string();
+    }
+    let mut budget = budget_tokens * CHARS_PER_TOKEN;
+    let mut feedback = note.to_string();
+
+    let output = failing_output(payload);
+    if !output.is_empty() {
+        // Failures are usually reported last, so keep the tail.
+        let output = keep_tail(&output, budget / 2);
+        budget = budget.saturating_sub(output.len());
+        feedback.push_str("\n\n## Failing output\n```text\n");
+        feedback.push_str(&output);
+        feedback.push_str("\n```");
+    }
+
+    let diff = uncommitted_diff(workspace);
+    let hunks = select_hunks(&parse_diff(&diff), &output, budget);
+    if !hunks.is_empty() {
+        feedback.push_str("\n\n## Uncommitted changes\n```diff\n");
+        feedback.push_str(&hunks);
+        feedback.push_str("```");
+    }
+
+    feedback
+}
+
+/// Returns the payload without its `check: pass` evidence lines.
+fn failing_output(payload: &str) -> String {
+    strip_ansi(payload)
+        .lines()
+        .filter(|line| !is_passing_evidence(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+fn is_passing_evidence(line: &str) -> bool {
+    line.trim()
+        .strip_suffix(": pass")
+        .is_some_and(|check| !check.is_empty() && check.chars().all(char::is_alphanumeric))
+}
+
+/// Keeps the last `max_chars` of `text`, starting at a line boundary.
+fn keep_tail(text: &str, max_chars: usize) -> String {
+    if text.len() <= max_chars {
+        return text.to_string();
+    }
+    let mut start = text.len() - max_chars;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    let start = text[start..].find('\n').map_or(start, |n| start + n + 1);
+    format!("[... {start} chars omitted ...]\n{}", &text[start..])
+}
+
+/// Runs `git diff HEAD` in `workspace`. Outside a repository (or before the
+/// first commit) there is nothing to show.
+pub(crate) fn uncommitted_diff(workspace: &Path) -> String {
+    Command::new("git")
+        .args(["diff", "HEAD", "--no-color", "--no-ext-diff"])
+        .current_dir(workspace)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default()
+}
+
+/// One file's section of a unified diff.
+#[derive(Debug, PartialEq)]
+struct FileDiff {
+    path: String,
+    /// Lines from `diff --git` up to the first hunk.
+    header: String,
+    hunks: Vec<String>,
+}
+
+fn parse_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    for line in diff.split_inclusive('\n') {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            let path = paths
+                .trim_end()
+                .rsplit_once(" b/")
+                .map_or(paths.trim_end(), |(_, b)| b);
+            files.push(FileDiff {
+                path: path.to_string(),
+                header: line.to_string(),
+                hunks: Vec::new(),
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if line.starts_with("@@") {
+            file.hunks.push(line.to_string());
+        } else if let Some(hunk) = file.hunks.last_mut() {
+            hunk.push_str(line);
+        } else {
+            file.header.push_str(line);
+        }
+    }
+    files
+}
+
+/// Concatenates as many hunks as fit in `max_chars`, files mentioned in
+/// `output` first, and notes how many were left out.
+fn select_hunks(files: &[FileDiff], output: &str, max_chars: usize) -> String {
+    let (mut relevant, others): (Vec<&FileDiff>, Vec<&FileDiff>) = files
+        .iter()
+        .partition(|file| is_mentioned(&file.path, output));
+    relevant.extend(others);
+
+    let mut selected = String::new();
+    let mut omitted = 0;
+    for file in relevant {
+        let mut section = file.header.clone();
+        for hunk in &file.hunks {
+            if selected.len() + section.len() + hunk.len() <= max_chars {
+                section.push_str(hunk);
+            } else {
+                omitted += 1;
+            }
+        }
+        if section.len() > file.header.len() {
+            selected.push_str(&section);
+        }
+    }
+    if omitted > 0 && !selected.is_empty() {
+        selected.push_str(&format!("[... {omitted} more hunks omitted ...]\n"));
+    }
+    selected
+}
+
+/// Whether the failing output names `path` or, for paths in directories,
+/// its file name.
+fn is_mentioned(path: &str, output: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    output.contains(path) || (file_name != path && output.contains(file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ fn a() {}
+-fn b() {}
++fn b() { todo!() }
+@@ -10,2 +10,2 @@
+-fn c() {}
++fn c() { panic!() }
+diff --git a/src/parser.rs b/src/parser.rs
+index 3333333..4444444 100644
+--- a/src/parser.rs
++++ b/src/parser.rs
+@@ -5,1 +5,1 @@
+-let x = 1;
++let x = 2;
+";
+
+    #[test]
+    fn test_parse_diff_splits_files_and_hunks() {
+        let files = parse_diff(DIFF);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].hunks.len(), 2);
+        assert!(files[0].header.ends_with("+++ b/src/lib.rs\n"));
+        assert_eq!(files[1].path, "src/parser.rs");
+        assert_eq!(
+            files[1].hunks,
+            vec!["@@ -5,1 +5,1 @@\n-let x = 1;\n+let x = 2;\n"]
+        );
+    }
+
+    #[test]
+    fn test_select_hunks_puts_mentioned_files_first_and_respects_budget() {
+        let files = parse_diff(DIFF);
+        let output = "thread 'parses' panicked at src/parser.rs:5:9";
+
+        let all = select_hunks(&files, output, usize::MAX);
+        assert!(all.starts_with("diff --git a/src/parser.rs"));
+        assert!(all.contains("fn c() { panic!() }"));
+
+        // Room for the parser diff only.
+        let parser_len = files[1].header.len() + files[1].hunks[0].len();
+        let trimmed = select_hunks(&files, output, parser_len);
+        assert!(trimmed.contains("+let x = 2;"));
+        assert!(!trimmed.contains("src/lib.rs"));
+        assert!(trimmed.ends_with("[... 2 more hunks omitted ...]\n"));
+    }
+
+    #[test]
+    fn test_failing_output_drops_passing_evidence() {
+        let payload =
+            "tests: fail\nlint: pass\ntypecheck: pass\n\nFAILED parses: expected 1, got 2";
+        assert_eq!(
+            failing_output(payload),
+            "tests: fail\n\nFAILED parses: expected 1, got 2"
+        );
+        assert_eq!(failing_output("tests: pass\nlint: pass"), "");
+    }
+
+    #[test]
+    fn test_keep_tail_cuts_at_line_boundary() {
+        let text = "first line\nsecond line\nthird line";
+        let tail = keep_tail(text, 15);
+        assert!(tail.ends_with("\nthird line"));
+        assert!(!tail.contains("second"));
+        assert_eq!(keep_tail(text, 100), text);
+    }
+
+    #[test]
+    fn test_feedback_without_budget_is_the_note() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(
+            failure_feedback("Checks failed.", "tests: fail", temp.path(), 0),
+            "Checks failed."
+        );
+
+        // Outside a repository only the failing output is added.
+        let feedback = failure_feedback("Checks failed.", "tests: fail\nboom", temp.path(), 100);
+        assert_eq!(
+            feedback,
+            "Checks failed.\n\n## Failing output\n```text\ntests: fail\nboom\n```"
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! Backpressure gates: the commands behind `tests: pass`, `lint: pass` and
+//! `typecheck: pass`.
+//!
+//! `build.done` must carry all three results. Without configuration the
+//! agent has to guess which commands produce them, so Ralph detects the
+//! project type from the workspace root and suggests sensible defaults,
+//! e.g. `cargo test` / `cargo clippy` / `cargo check` for a Cargo project.
+//! Each gate can be overridden (or turned off with an empty string) under
+//! `gates:`, and a scoped monorepo run tests only its packages.
+
+use crate::config::GatesConfig;
+use crate::scope::Scope;
+use std::path::Path;
+
+/// Project type detected from the manifest at the workspace root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectType {
+    Rust,
+    Node,
+    Python,
+    Go,
+}
+
+impl ProjectType {
+    /// Detects the project type from the manifests in `root`.
+    pub fn detect(root: &Path) -> Option<Self> {
+        [
+            ("Cargo.toml", Self::Rust),
+            ("package.json", Self::Node),
+            ("pyproject.toml", Self::Python),
+            ("setup.py", Self::Python),
+            ("go.mod", Self::Go),
+        ]
+        .into_iter()
+        .find(|(manifest, _)| root.join(manifest).is_file())
+        .map(|(_, project)| project)
+    }
+
+    /// Name of the manifest the type is detected from, for display.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Rust => "Rust (Cargo.toml)",
+            Self::Node => "Node (package.json)",
+            Self::Python => "Python",
+            Self::Go => "Go (go.mod)",
+        }
+    }
+}
+
+/// The resolved command for each gate. `None` means the gate has no
+/// command and its check is reported as passing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Gates {
+    pub project: Option<ProjectType>,
+    pub tests: Option<String>,
+    pub lint: Option<String>,
+    pub typecheck: Option<String>,
+}
+
+impl Gates {
+    /// Resolves gates from configuration, falling back to the defaults for
+    /// the project found in `root`. A non-empty `scope` replaces the default
+    /// test command with its packages' test commands.
+    pub fn resolve(config: &GatesConfig, root: &Path, scope: &Scope) -> Self {
+        let project = ProjectType::detect(root);
+        let mut defaults = project.map_or_else(Self::default, |project| defaults(project, root));
+        let scoped_tests = scope.test_commands();
+        if !scoped_tests.is_empty() {
+            defaults.tests = Some(scoped_tests.join(" && "));
+        }
+
+        let pick = |configured: &Option<String>, default: Option<String>| match configured {
+            Some(command) if command.trim().is_empty() => None,
+            Some(command) => Some(command.clone()),
+            None => default,
+        };
+        Self {
+            project,
+            tests: pick(&config.tests, defaults.tests),
+            lint: pick(&config.lint, defaults.lint),
+            typecheck: pick(&config.typecheck, defaults.typecheck),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tests.is_none() && self.lint.is_none() && self.typecheck.is_none()
+    }
+
+    /// Builds the `<gates>` prompt section, or an empty string when no gate
+    /// has a command.
+    pub fn prompt_section(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut section = String::from("<gates>\n");
+        if let Some(project) = self.project {
+            section.push_str(&format!("Project: {}\n", project.label()));
+        }
+        section.push_str(
+            "Run these before emitting build.done and report each result in the payload \
+             (`tests: pass`, `lint: pass`, `typecheck: pass`):\n",
+        );
+        for (check, command) in [
+            ("tests", &self.tests),
+            ("lint", &self.lint),
+            ("typecheck", &self.typecheck),
+        ] {
+            match command {
+                Some(command) => section.push_str(&format!("- {check}: `{command}`\n")),
+                None => {
+                    section.push_str(&format!("- {check}: no command, report `{check}: pass`\n"))
+                }
+            }
+        }
+        section.push_str("</gates>\n\n");
+        section
+    }
+}
+
+fn defaults(project: ProjectType, root: &Path) -> Gates {
+    let some = |command: &str| Some(command.to_string());
+    match project {
+        ProjectType::Rust => Gates {
+            project: Some(project),
+            tests: some("cargo test"),
+            lint: some("cargo clippy --all-targets -- -D warnings"),
+            typecheck: some("cargo check --all-targets"),
+        },
+        ProjectType::Node => {
+            let scripts = npm_scripts(root);
+            let has = |script: &str| scripts.iter().any(|s| s == script);
+            Gates {
+                project: Some(project),
+                tests: has("test").then(|| "npm test".to_string()),
+                lint: has("lint").then(|| "npm run lint".to_string()),
+                typecheck: if has("typecheck") {
+                    some("npm run typecheck")
+                } else if root.join("tsconfig.json").is_file() {
+                    some("npx tsc --noEmit")
+                } else {
+                    None
+                },
+            }
+        }
+        ProjectType::Python => {
+            let pyproject =
+                std::fs::read_to_string(root.join("pyproject.toml")).unwrap_or_default();
+            Gates {
+                project: Some(project),
+                tests: some("pytest"),
+                lint: pyproject
+                    .contains("[tool.ruff")
+                    .then(|| "ruff check .".to_string()),
+                typecheck: pyproject
+                    .contains("[tool.mypy")
+                    .then(|| "mypy .".to_string()),
+            }
+        }
+        ProjectType::Go => Gates {
+            project: Some(project),
+            tests: some("go test ./..."),
+            lint: some("go vet ./..."),
+            typecheck: some("go build ./..."),
+        },
+    }
+}
+
+/// Names of the scripts in `package.json`.
+fn npm_scripts(root: &Path) -> Vec<String> {
+    std::fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|package| {
+            package
+                .get("scripts")?
+                .as_object()
+                .map(|scripts| scripts.keys().cloned().collect())
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ScopeConfig;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rust_defaults_and_overrides() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("Cargo.toml"), "[workspace]\n").unwrap();
+
+        let gates = Gates::resolve(&GatesConfig::default(), temp.path(), &Scope::default());
+        assert_eq!(gates.project, Some(ProjectType::Rust));
+        assert_eq!(gates.tests.as_deref(), Some("cargo test"));
+
+        let config = GatesConfig {
+            tests: Some("cargo nextest run".to_string()),
+            lint: Some(String::new()),
+            typecheck: None,
+        };
+        let gates = Gates::resolve(&config, temp.path(), &Scope::default());
+        assert_eq!(gates.tests.as_deref(), Some("cargo nextest run"));
+        assert_eq!(gates.lint, None);
+        assert_eq!(
+            gates.typecheck.as_deref(),
+            Some("cargo check --all-targets")
+        );
+        assert!(
+            gates
+                .prompt_section()
+                .contains("- lint: no command, report `lint: pass`\n")
+        );
+    }
+
+    #[test]
+    fn test_node_gates_follow_package_scripts() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("package.json"),
+            r#"{"scripts": {"test": "vitest", "lint": "eslint ."}}"#,
+        )
+        .unwrap();
+        fs::write(temp.path().join("tsconfig.json"), "{}").unwrap();
+
+        let gates = Gates::resolve(&GatesConfig::default(), temp.path(), &Scope::default());
+        assert_eq!(gates.tests.as_deref(), Some("npm test"));
+        assert_eq!(gates.lint.as_deref(), Some("npm run lint"));
+        assert_eq!(gates.typecheck.as_deref(), Some("npx tsc --noEmit"));
+    }
+
+    #[test]
+    fn test_python_gates_and_unknown_project() {
+        let temp = TempDir::new().unwrap();
+        assert!(
+            Gates::resolve(&GatesConfig::default(), temp.path(), &Scope::default())
+                .prompt_section()
+                .is_empty()
+        );
+
+        fs::write(temp.path().join("pyproject.toml"), "[tool.ruff]\n").unwrap();
+        let gates = Gates::resolve(&GatesConfig::default(), temp.path(), &Scope::default());
+        assert_eq!(gates.project, Some(ProjectType::Python));
+        assert_eq!(gates.tests.as_deref(), Some("pytest"));
+        assert_eq!(gates.lint.as_deref(), Some("ruff check ."));
+        assert_eq!(gates.typecheck, None);
+    }
+
+    #[test]
+    fn test_scope_narrows_tests() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("Cargo.toml"), "[workspace]\n").unwrap();
+        fs::create_dir_all(temp.path().join("crates/a")).unwrap();
+        fs::write(
+            temp.path().join("crates/a/Cargo.toml"),
+            "[package]\nname = \"a\"\n",
+        )
+        .unwrap();
+        let scope = Scope::from_config(
+            &ScopeConfig {
+                packages: vec!["crates/a".to_string()],
+                allow: Vec::new(),
+            },
+            temp.path(),
+        );
+
+        let gates = Gates::resolve(&GatesConfig::default(), temp.path(), &scope);
+        assert_eq!(gates.tests.as_deref(), Some("cargo test -p a"));
+    }
+}
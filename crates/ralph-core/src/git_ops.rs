@@ -330,6 +330,34 @@ pub fn is_working_tree_clean(path: impl AsRef<Path>) -> Result<bool, GitOpsError
     has_uncommitted_changes(path).map(|has_changes| !has_changes)
 }
 
+/// Get the paths of uncommitted changes (staged, unstaged and untracked).
+///
+/// Renamed files are reported under their new path.
+///
+/// # Arguments
+///
+/// * `path` - Path to the git repository (or worktree)
+pub fn get_uncommitted_files(path: impl AsRef<Path>) -> Result<Vec<String>, GitOpsError> {
+    let path = path.as_ref();
+    let output = Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=all"])
+        .current_dir(path)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitOpsError::Git(stderr.to_string()));
+    }
+
+    let files = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.get(3..))
+        .map(|file| file.rsplit(" -> ").next().unwrap_or(file).to_string())
+        .collect();
+
+    Ok(files)
+}
+
 /// Get a short summary of the HEAD commit.
 ///
 /// Returns a string like "abc1234: commit message subject"
@@ -732,6 +760,21 @@ mod tests {
         assert!(!is_working_tree_clean(temp.path()).unwrap());
     }
 
+    #[test]
+    fn test_get_uncommitted_files() {
+        let temp = TempDir::new().unwrap();
+        init_git_repo(temp.path());
+        assert!(get_uncommitted_files(temp.path()).unwrap().is_empty());
+
+        fs::write(temp.path().join("README.md"), "# Changed").unwrap();
+        fs::create_dir(temp.path().join("src")).unwrap();
+        fs::write(temp.path().join("src/new.rs"), "").unwrap();
+
+        let mut files = get_uncommitted_files(temp.path()).unwrap();
+        files.sort();
+        assert_eq!(files, vec!["README.md", "src/new.rs"]);
+    }
+
     #[test]
     fn test_get_commit_summary() {
         let temp = TempDir::new().unwrap();
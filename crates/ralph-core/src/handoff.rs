@@ -4,6 +4,7 @@
 //! - What was completed (closed tasks)
 //! - What remains (open tasks with dependencies)
 //! - Context (last commit, branch, key files)
+//! - For multi-repo runs, each repository's branch and changed files
 //! - Ready-to-paste prompt for next session
 //!
 //! This enables clean session boundaries and seamless handoffs between
@@ -11,6 +12,7 @@
 
 use crate::git_ops::{get_commit_summary, get_current_branch, get_head_sha, get_recent_files};
 use crate::loop_context::LoopContext;
+use crate::repos::RepoSet;
 use crate::task::{Task, TaskStatus};
 use crate::task_store::TaskStore;
 use std::io;
@@ -43,12 +45,23 @@ pub enum HandoffError {
 /// Generates handoff files for session continuity.
 pub struct HandoffWriter {
     context: LoopContext,
+    repos: RepoSet,
 }
 
 impl HandoffWriter {
     /// Creates a new handoff writer for the given loop context.
     pub fn new(context: LoopContext) -> Self {
-        Self { context }
+        Self {
+            context,
+            repos: RepoSet::default(),
+        }
+    }
+
+    /// Adds a section per repository of a multi-repo run.
+    #[must_use]
+    pub fn with_repos(mut self, repos: RepoSet) -> Self {
+        self.repos = repos;
+        self
     }
 
     /// Generates the handoff file with session context.
@@ -106,6 +119,11 @@ impl HandoffWriter {
         content.push_str("\n## Key Files\n\n");
         self.write_key_files(&mut content);
 
+        if !self.repos.is_empty() {
+            content.push_str("\n## Repositories\n\n");
+            self.write_repos_section(&mut content);
+        }
+
         // Continuation prompt section
         content.push_str("\n## Next Session\n\n");
         self.write_continuation_prompt(&mut content, original_prompt);
@@ -211,6 +229,32 @@ impl HandoffWriter {
         }
     }
 
+    /// Writes each repository's branch, HEAD and recently modified files.
+    fn write_repos_section(&self, content: &mut String) {
+        for repo in self.repos.iter() {
+            content.push_str(&format!("### {}\n\n", repo.name));
+            content.push_str(&format!("- **Path:** `{}`\n", repo.path.display()));
+            let Ok(branch) = get_current_branch(&repo.path) else {
+                content.push_str("- _Not a git repository_\n\n");
+                continue;
+            };
+            content.push_str(&format!("- **Branch:** `{}`\n", branch));
+            if let Ok(summary) = get_commit_summary(&repo.path) {
+                content.push_str(&format!("- **HEAD:** {}\n", summary));
+            }
+            match get_recent_files(&repo.path, 10) {
+                Ok(files) if !files.is_empty() => {
+                    content.push_str("- **Recently modified:**\n");
+                    for file in files {
+                        content.push_str(&format!("  - `{}`\n", file));
+                    }
+                }
+                _ => content.push_str("- _No recent file modifications tracked._\n"),
+            }
+            content.push('\n');
+        }
+    }
+
     /// Writes the continuation prompt for the next session.
     fn write_continuation_prompt(&self, content: &mut String, original_prompt: &str) {
         let tasks_path = self.context.tasks_path();
@@ -365,6 +409,28 @@ mod tests {
         assert!(content.contains("Remaining tasks"));
     }
 
+    #[test]
+    fn test_handoff_lists_repos() {
+        let (temp, ctx) = setup_test_context();
+        let repos = RepoSet::from_config(
+            &[crate::config::RepoConfig {
+                name: "client".to_string(),
+                path: "client".into(),
+                description: None,
+            }],
+            temp.path(),
+        );
+
+        HandoffWriter::new(ctx.clone())
+            .with_repos(repos)
+            .write("Test prompt")
+            .unwrap();
+
+        let content = fs::read_to_string(ctx.handoff_path()).unwrap();
+        assert!(content.contains("## Repositories\n\n### client\n"));
+        assert!(content.contains("- _Not a git repository_"));
+    }
+
     #[test]
     fn test_truncate_prompt_short() {
         let result = truncate_prompt("short prompt", 100);
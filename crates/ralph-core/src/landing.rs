@@ -2,7 +2,7 @@
 //!
 //! Orchestrates the "land the plane" sequence on loop completion:
 //! 1. Verify task state (log warnings for open tasks)
-//! 2. Auto-commit uncommitted changes
+//! 2. Auto-commit uncommitted changes (and in each configured repo)
 //! 3. Clean git state (stashes, prune refs)
 //! 4. Generate handoff prompt
 //!
@@ -14,6 +14,7 @@ use crate::git_ops::{
 };
 use crate::handoff::{HandoffError, HandoffWriter};
 use crate::loop_context::LoopContext;
+use crate::repos::{RepoCheckpoint, RepoSet};
 use crate::task_store::TaskStore;
 use std::path::PathBuf;
 use tracing::{debug, info, warn};
@@ -38,6 +39,9 @@ pub struct LandingResult {
 
     /// Whether the working tree is clean after landing.
     pub working_tree_clean: bool,
+
+    /// Commits made in the run's other repositories, one per repo.
+    pub repo_checkpoints: Vec<RepoCheckpoint>,
 }
 
 /// Errors that can occur during landing.
@@ -89,6 +93,7 @@ impl Default for LandingConfig {
 pub struct LandingHandler {
     context: LoopContext,
     config: LandingConfig,
+    repos: RepoSet,
 }
 
 impl LandingHandler {
@@ -97,12 +102,24 @@ impl LandingHandler {
         Self {
             context,
             config: LandingConfig::default(),
+            repos: RepoSet::default(),
         }
     }
 
     /// Creates a landing handler with custom configuration.
     pub fn with_config(context: LoopContext, config: LandingConfig) -> Self {
-        Self { context, config }
+        Self {
+            context,
+            config,
+            repos: RepoSet::default(),
+        }
+    }
+
+    /// Also checkpoints these repositories and reports them in the handoff.
+    #[must_use]
+    pub fn with_repos(mut self, repos: RepoSet) -> Self {
+        self.repos = repos;
+        self
     }
 
     /// Executes the landing sequence.
@@ -155,6 +172,30 @@ impl LandingHandler {
             AutoCommitResult::no_commit()
         };
 
+        let repo_checkpoints = if self.config.auto_commit {
+            self.repos.checkpoint(&loop_id)
+        } else {
+            Vec::new()
+        };
+        for checkpoint in &repo_checkpoints {
+            match &checkpoint.result {
+                Ok(result) if result.committed => info!(
+                    repo = %checkpoint.repo,
+                    commit = ?result.commit_sha,
+                    files = result.files_staged,
+                    "Auto-committed repo changes during landing"
+                ),
+                Ok(_) => {}
+                Err(e) => {
+                    warn!(
+                        repo = %checkpoint.repo,
+                        error = %e,
+                        "Repo auto-commit failed during landing"
+                    );
+                }
+            }
+        }
+
         // Step 3: Clean git state
         let stashes_cleared = if self.config.clear_stashes {
             match clean_stashes(workspace) {
@@ -181,7 +222,7 @@ impl LandingHandler {
 
         // Step 4: Generate handoff prompt
         let handoff_path = if self.config.generate_handoff {
-            let writer = HandoffWriter::new(self.context.clone());
+            let writer = HandoffWriter::new(self.context.clone()).with_repos(self.repos.clone());
             match writer.write(prompt) {
                 Ok(result) => {
                     info!(
@@ -212,6 +253,7 @@ impl LandingHandler {
             open_tasks,
             stashes_cleared,
             working_tree_clean,
+            repo_checkpoints,
         })
     }
 
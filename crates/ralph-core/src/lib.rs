@@ -10,21 +10,31 @@
 //! - Terminal capture for session recording
 //! - Benchmark task definitions and workspace isolation
 
+mod alerts;
+mod audit_log;
 pub mod chaos_mode;
 mod cli_capture;
+mod clock;
+mod concurrency;
 mod config;
+mod cost_anomaly;
 pub mod diagnostics;
+mod encryption;
+mod environment;
 mod event_logger;
 mod event_loop;
 mod event_parser;
 mod event_reader;
+mod failure_feedback;
 pub mod file_lock;
+mod gates;
 mod git_ops;
 mod handoff;
 mod hat_registry;
 mod hatless_ralph;
 mod instructions;
 mod landing;
+mod local_settings;
 pub mod loop_completion;
 pub mod loop_context;
 pub mod loop_history;
@@ -35,7 +45,16 @@ mod memory;
 pub mod memory_parser;
 mod memory_store;
 pub mod merge_queue;
+mod payload_summary;
 pub mod planning_session;
+mod post_mortem;
+mod power;
+mod prompt_archive;
+mod repos;
+mod router;
+mod run_control;
+mod run_status;
+mod scope;
 mod session_player;
 mod session_recorder;
 pub mod skill;
@@ -46,37 +65,57 @@ pub mod task_definition;
 pub mod task_store;
 pub mod testing;
 mod text;
+mod throttle;
+mod tool_result_store;
 pub mod utils;
 pub mod workspace;
 pub mod worktree;
 
+pub use alerts::{AlertHit, AlertMatcher};
+pub use audit_log::{AuditAction, AuditEntry, AuditLog, AuditSource};
 pub use chaos_mode::{CHAOS_COMPLETION_PROMISE, ChaosModeState};
 pub use cli_capture::{CliCapture, CliCapturePair};
+pub use clock::{Clock, MockClock, SystemClock};
+pub use concurrency::{ConcurrencyGroups, ConcurrencySlot};
 pub use config::{
-    ChaosModeConfig, ChaosOutput, CliConfig, CoreConfig, EventLoopConfig, EventMetadata,
-    FeaturesConfig, HatBackend, HatConfig, InjectMode, MemoriesConfig, MemoriesFilter, RalphConfig,
-    ResearchFocus, SkillOverride, SkillsConfig,
+    AlertAction, AlertRule, ApiRole, ApiTokenConfig, ChaosModeConfig, ChaosOutput,
+    CheckpointConfig, CliConfig, ConcurrencyGroupConfig, CoreConfig, CostAnomalyConfig,
+    DaemonConfig, DesktopNotifierConfig, EmailNotifierConfig, EncryptionConfig, EventLoopConfig,
+    EventMetadata, FeaturesConfig, FooterSegment, GatesConfig, HatBackend, HatConfig,
+    HttpApiConfig, HttpTlsConfig, InjectMode, MaintenanceCommand, MaintenanceConfig,
+    MemoriesConfig, MemoriesFilter, NotificationEvent, NotificationsConfig, PluginAdapterConfig,
+    PostMortemConfig, PowerConfig, PromptMode, RalphConfig, RepoConfig, ResearchFocus,
+    ResponseFormat, RouterConfig, ScheduledRunConfig, ScopeConfig, SkillOverride, SkillsConfig,
+    SmoothStreamingConfig, SmtpSecurity, TuiTheme, WebhookNotifierConfig,
 };
 // Re-export loop_name types (also available via FeaturesConfig.loop_naming)
+pub use cost_anomaly::{CostAnomaly, CostAnomalyDetector, CostMetric};
 pub use diagnostics::DiagnosticsCollector;
+pub use encryption::{Cipher, EncryptionError, SealingWriter, is_sealed, open_lines};
+pub use environment::{EnvironmentSnapshot, backend_version};
 pub use event_logger::{EventHistory, EventLogger, EventRecord};
 pub use event_loop::{EventLoop, LoopState, TerminationReason, UserPrompt};
 pub use event_parser::EventParser;
 pub use event_reader::{Event, EventReader, MalformedLine, ParseResult};
 pub use file_lock::{FileLock, LockGuard as FileLockGuard, LockedFile};
+pub use gates::{Gates, ProjectType};
 pub use git_ops::{
     AutoCommitResult, GitOpsError, auto_commit_changes, clean_stashes, get_commit_summary,
-    get_current_branch, get_head_sha, get_recent_files, has_uncommitted_changes,
-    is_working_tree_clean, prune_remote_refs,
+    get_current_branch, get_head_sha, get_recent_files, get_uncommitted_files,
+    has_uncommitted_changes, is_working_tree_clean, prune_remote_refs,
 };
 pub use handoff::{HandoffError, HandoffResult, HandoffWriter};
 pub use hat_registry::HatRegistry;
 pub use hatless_ralph::{HatInfo, HatTopology, HatlessRalph};
 pub use instructions::InstructionBuilder;
 pub use landing::{LandingConfig, LandingError, LandingHandler, LandingResult};
+pub use local_settings::{LocalSettings, LocalSettingsError};
 pub use loop_completion::{CompletionAction, CompletionError, LoopCompletionHandler};
 pub use loop_context::LoopContext;
-pub use loop_history::{HistoryError, HistoryEvent, HistoryEventType, HistorySummary, LoopHistory};
+pub use loop_history::{
+    HistoryError, HistoryEvent, HistoryEventType, HistorySummary, IterationMetrics,
+    IterationRecord, LoopHistory,
+};
 pub use loop_lock::{LockError, LockGuard, LockMetadata, LoopLock};
 pub use loop_name::{LoopNameGenerator, LoopNamingConfig};
 pub use loop_registry::{LoopEntry, LoopRegistry, RegistryError};
@@ -93,6 +132,14 @@ pub use planning_session::{
     ConversationEntry, ConversationType, PlanningSession, PlanningSessionError, SessionMetadata,
     SessionStatus,
 };
+pub use post_mortem::PostMortemWriter;
+pub use power::{PowerMonitor, PowerState};
+pub use prompt_archive::{PromptArchive, PromptRecord, PromptSection, prompt_sections};
+pub use repos::{Repo, RepoCheckpoint, RepoSet};
+pub use router::{ROUTER_DECISION_TOPIC, RouterCandidate, RouterDecision};
+pub use run_control::{ControlCommand, ControlError, ControlState, RunControl};
+pub use run_status::{RunPhase, RunStatus};
+pub use scope::{PackageKind, Scope, ScopedPackage};
 pub use session_player::{PlayerConfig, ReplayMode, SessionPlayer, TimestampedRecord};
 pub use session_recorder::{Record, SessionRecorder};
 pub use skill::{SkillEntry, SkillFrontmatter, SkillSource, parse_frontmatter};
@@ -104,6 +151,8 @@ pub use task_definition::{
 };
 pub use task_store::TaskStore;
 pub use text::truncate_with_ellipsis;
+pub use throttle::{TokenThrottle, estimate_tokens};
+pub use tool_result_store::ToolResultStore;
 pub use workspace::{
     CleanupPolicy, TaskWorkspace, VerificationResult, WorkspaceError, WorkspaceInfo,
     WorkspaceManager,
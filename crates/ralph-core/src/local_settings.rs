@@ -0,0 +1,151 @@
+//! Settings changed at runtime, kept in `.ralph/local.toml`.
+//!
+//! The TUI settings popup adjusts a handful of settings while a loop runs.
+//! Saving writes them here, and the next run in the workspace applies them
+//! on top of `ralph.yml`. Only the settings that were set are written, so the
+//! file stays a short list of local overrides.
+
+use crate::config::{RalphConfig, TuiTheme};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Local overrides of the runtime-adjustable settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocalSettings {
+    /// Cost budget in USD (`event_loop.max_cost_usd`); `0` removes it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_usd: Option<f64>,
+
+    /// Model passed to the backend (`cli.model`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Verbose backend output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verbose: Option<bool>,
+
+    /// Whether the TUI follows the latest iteration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow: Option<bool>,
+
+    /// TUI color theme (`tui.theme`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<TuiTheme>,
+}
+
+/// Errors reading or writing the local settings file.
+#[derive(Debug, thiserror::Error)]
+pub enum LocalSettingsError {
+    #[error("local settings file error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid local settings: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("could not serialize local settings: {0}")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+impl LocalSettings {
+    /// Reads the settings at `path`; a missing file means no overrides.
+    pub fn load(path: &Path) -> Result<Self, LocalSettingsError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(toml::from_str(&content)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the settings to `path`, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<(), LocalSettingsError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// The cost budget these settings select, if they override it.
+    pub fn budget(&self) -> Option<Option<f64>> {
+        self.max_cost_usd
+            .map(|budget| (budget > 0.0).then_some(budget))
+    }
+
+    /// Applies the overrides to `config`. `follow` has no config setting and
+    /// is left to the TUI.
+    pub fn apply(&self, config: &mut RalphConfig) {
+        if let Some(budget) = self.budget() {
+            config.event_loop.max_cost_usd = budget;
+        }
+        if let Some(model) = &self.model {
+            config.cli.model = (!model.is_empty()).then(|| model.clone());
+        }
+        if let Some(verbose) = self.verbose {
+            config.verbose = verbose;
+        }
+        if let Some(theme) = self.theme {
+            config.tui.theme = theme;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_trip_writes_only_set_settings() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(".ralph/local.toml");
+        assert_eq!(
+            LocalSettings::load(&path).unwrap(),
+            LocalSettings::default()
+        );
+
+        let settings = LocalSettings {
+            max_cost_usd: Some(2.5),
+            theme: Some(TuiTheme::Mono),
+            ..LocalSettings::default()
+        };
+        settings.save(&path).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert_eq!(written, "max_cost_usd = 2.5\ntheme = \"mono\"\n");
+        assert_eq!(LocalSettings::load(&path).unwrap(), settings);
+    }
+
+    #[test]
+    fn test_apply_overrides_config() {
+        let mut config = RalphConfig::default();
+        config.event_loop.max_cost_usd = Some(10.0);
+        config.cli.model = Some("opus".to_string());
+
+        LocalSettings {
+            max_cost_usd: Some(0.0),
+            model: Some(String::new()),
+            verbose: Some(true),
+            follow: Some(false),
+            theme: Some(TuiTheme::Light),
+        }
+        .apply(&mut config);
+
+        assert_eq!(config.event_loop.max_cost_usd, None);
+        assert_eq!(config.cli.model, None);
+        assert!(config.verbose);
+        assert_eq!(config.tui.theme, TuiTheme::Light);
+    }
+
+    #[test]
+    fn test_invalid_file_is_an_error() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("local.toml");
+        fs::write(&path, "theme = \"neon\"\n").unwrap();
+        assert!(matches!(
+            LocalSettings::load(&path),
+            Err(LocalSettingsError::Parse(_))
+        ));
+    }
+}
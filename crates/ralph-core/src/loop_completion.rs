@@ -37,6 +37,7 @@ use crate::git_ops::auto_commit_changes;
 use crate::landing::{LandingHandler, LandingResult};
 use crate::loop_context::LoopContext;
 use crate::merge_queue::{MergeQueue, MergeQueueError};
+use crate::repos::RepoSet;
 use tracing::{debug, info, warn};
 
 /// Action taken upon loop completion.
@@ -109,6 +110,9 @@ pub enum CompletionError {
 pub struct LoopCompletionHandler {
     /// Whether auto-merge is enabled (default: true).
     auto_merge: bool,
+
+    /// The run's other repositories, checkpointed during landing.
+    repos: RepoSet,
 }
 
 impl Default for LoopCompletionHandler {
@@ -125,7 +129,17 @@ impl LoopCompletionHandler {
     /// * `auto_merge` - If true, completed worktree loops are enqueued for merge-ralph.
     ///   If false, worktrees are left for manual merge.
     pub fn new(auto_merge: bool) -> Self {
-        Self { auto_merge }
+        Self {
+            auto_merge,
+            repos: RepoSet::default(),
+        }
+    }
+
+    /// Sets the repositories of a multi-repo run.
+    #[must_use]
+    pub fn with_repos(mut self, repos: RepoSet) -> Self {
+        self.repos = repos;
+        self
     }
 
     /// Handles loop completion, taking appropriate action based on context.
@@ -229,7 +243,7 @@ impl LoopCompletionHandler {
     ///
     /// Returns the landing result if successful, or None if landing failed.
     fn execute_landing(&self, context: &LoopContext, prompt: &str) -> Option<LandingResult> {
-        let handler = LandingHandler::new(context.clone());
+        let handler = LandingHandler::new(context.clone()).with_repos(self.repos.clone());
 
         match handler.land(prompt) {
             Ok(result) => {
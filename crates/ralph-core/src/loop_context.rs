@@ -54,6 +54,7 @@
 //!            "/project/.worktrees/loop-1234-abcd/.ralph/events.jsonl");
 //! ```
 
+use crate::audit_log::AuditLog;
 use std::path::{Path, PathBuf};
 
 /// Context for resolving paths within a Ralph loop.
@@ -174,6 +175,65 @@ impl LoopContext {
         self.ralph_dir().join("current-events")
     }
 
+    /// Returns the ID of the current run, taken from the current-events marker.
+    ///
+    /// The marker points at `.ralph/events-<run-id>.jsonl`; returns `None` if it
+    /// is missing or does not follow that pattern.
+    pub fn current_run_id(&self) -> Option<String> {
+        let marker = std::fs::read_to_string(self.current_events_marker()).ok()?;
+        let file_name = Path::new(marker.trim()).file_name()?.to_str()?;
+        let run_id = file_name.strip_prefix("events-")?.strip_suffix(".jsonl")?;
+        (!run_id.is_empty()).then(|| run_id.to_string())
+    }
+
+    /// Path to the run control file (controller role and queued commands).
+    pub fn control_path(&self) -> PathBuf {
+        self.ralph_dir().join("control.json")
+    }
+
+    /// Path to the settings changed at runtime from the TUI.
+    pub fn local_settings_path(&self) -> PathBuf {
+        self.ralph_dir().join("local.toml")
+    }
+
+    /// Path to the run status file polled by `ralph status`.
+    pub fn run_status_path(&self) -> PathBuf {
+        self.ralph_dir().join("run-status.json")
+    }
+
+    /// Path to the append-only audit log of control actions.
+    pub fn audit_path(&self) -> PathBuf {
+        self.ralph_dir().join("audit.jsonl")
+    }
+
+    /// Audit log that tags entries with the current run.
+    pub fn audit_log(&self) -> AuditLog {
+        AuditLog::new(self.audit_path()).with_run_id(self.current_run_id())
+    }
+
+    /// Path to the directory of per-run agent output logs.
+    pub fn run_logs_dir(&self) -> PathBuf {
+        self.ralph_dir().join("runs")
+    }
+
+    /// Path to the agent output log for a run, read by `ralph logs`.
+    pub fn run_log_path(&self, run_id: &str) -> PathBuf {
+        self.run_logs_dir().join(format!("{run_id}.jsonl"))
+    }
+
+    /// Path to the post-mortem of a run that ended abnormally, next to its
+    /// output log.
+    pub fn post_mortem_path(&self, run_id: &str) -> PathBuf {
+        self.run_logs_dir().join(format!("{run_id}-post-mortem.md"))
+    }
+
+    /// Path to a run's archived prompts, read by `ralph prompts`.
+    pub fn prompt_archive_path(&self, run_id: &str) -> PathBuf {
+        self.ralph_dir()
+            .join("prompts")
+            .join(format!("{run_id}.jsonl"))
+    }
+
     /// Path to the tasks JSONL file.
     ///
     /// Each loop has its own isolated tasks file.
@@ -267,6 +327,13 @@ impl LoopContext {
         self.ralph_dir().join("diagnostics")
     }
 
+    /// Path to the directory holding full tool results.
+    ///
+    /// The TUI keeps only previews in memory and loads full results from here.
+    pub fn tool_results_dir(&self) -> PathBuf {
+        self.ralph_dir().join("tool-results")
+    }
+
     /// Path to the loop history JSONL file.
     ///
     /// Event-sourced history for crash recovery and debugging.
@@ -645,6 +712,18 @@ mod tests {
             ctx.diagnostics_dir(),
             PathBuf::from("/project/.ralph/diagnostics")
         );
+        assert_eq!(
+            ctx.tool_results_dir(),
+            PathBuf::from("/project/.ralph/tool-results")
+        );
+        assert_eq!(
+            ctx.control_path(),
+            PathBuf::from("/project/.ralph/control.json")
+        );
+        assert_eq!(
+            ctx.audit_path(),
+            PathBuf::from("/project/.ralph/audit.jsonl")
+        );
         assert_eq!(
             ctx.history_path(),
             PathBuf::from("/project/.ralph/history.jsonl")
@@ -791,6 +870,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_current_run_id_from_marker() {
+        let temp = TempDir::new().unwrap();
+        let ctx = LoopContext::primary(temp.path().to_path_buf());
+        assert_eq!(ctx.current_run_id(), None);
+
+        std::fs::create_dir_all(ctx.ralph_dir()).unwrap();
+        std::fs::write(
+            ctx.current_events_marker(),
+            ".ralph/events-20260127-143022.jsonl\n",
+        )
+        .unwrap();
+        assert_eq!(ctx.current_run_id().as_deref(), Some("20260127-143022"));
+        assert_eq!(
+            ctx.run_log_path("20260127-143022"),
+            temp.path().join(".ralph/runs/20260127-143022.jsonl")
+        );
+
+        std::fs::write(ctx.current_events_marker(), ".ralph/events.jsonl").unwrap();
+        assert_eq!(ctx.current_run_id(), None);
+    }
+
     #[test]
     fn test_planning_sessions_paths() {
         let ctx = LoopContext::primary(PathBuf::from("/project"));
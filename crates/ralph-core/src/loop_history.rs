@@ -310,6 +310,66 @@ impl LoopHistory {
         }))
     }
 
+    /// Record iteration completed event, with what the iteration cost as its
+    /// data.
+    pub fn record_iteration_metrics(
+        &self,
+        iteration: u32,
+        success: bool,
+        metrics: &IterationMetrics,
+    ) -> Result<(), HistoryError> {
+        self.append(HistoryEvent::with_data(
+            HistoryEventType::IterationCompleted { iteration, success },
+            serde_json::to_value(metrics)?,
+        ))
+    }
+
+    /// Get the recorded metrics of every completed iteration, oldest first.
+    ///
+    /// Iterations completed without metrics are skipped. Each record carries
+    /// how its run ended, once the run has ended.
+    pub fn iteration_metrics(&self) -> Result<Vec<IterationRecord>, HistoryError> {
+        let mut records: Vec<IterationRecord> = Vec::new();
+        // Records of the run in progress, still waiting for its outcome
+        let mut open = 0;
+
+        for event in self.read_all()? {
+            let run_outcome = match &event.event_type {
+                HistoryEventType::LoopStarted { .. } => {
+                    open = 0;
+                    continue;
+                }
+                HistoryEventType::IterationCompleted { iteration, success } => {
+                    let Some(metrics) = event
+                        .data
+                        .and_then(|data| serde_json::from_value(data).ok())
+                    else {
+                        continue;
+                    };
+                    records.push(IterationRecord {
+                        completed_at: event.timestamp,
+                        iteration: *iteration,
+                        success: *success,
+                        metrics,
+                        run_outcome: None,
+                    });
+                    open += 1;
+                    continue;
+                }
+                HistoryEventType::LoopCompleted { reason } => reason.clone(),
+                HistoryEventType::LoopTerminated { .. } => "interrupted".to_string(),
+                _ => continue,
+            };
+            let len = records.len();
+            for record in &mut records[len - open..] {
+                record.run_outcome = Some(run_outcome.clone());
+            }
+            open = 0;
+        }
+
+        Ok(records)
+    }
+
     /// Record loop completed event.
     pub fn record_completed(&self, reason: &str) -> Result<(), HistoryError> {
         self.append(HistoryEvent::new(HistoryEventType::LoopCompleted {
@@ -363,6 +423,47 @@ impl LoopHistory {
     }
 }
 
+/// What an iteration cost, recorded as the data of its `IterationCompleted`
+/// event.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct IterationMetrics {
+    /// Run the iteration belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+
+    /// Hat the iteration ran as.
+    pub hat: String,
+
+    /// Wall-clock time the backend took.
+    pub duration_ms: u64,
+
+    /// Estimated cost in USD (0 when the backend doesn't report it).
+    pub cost_usd: f64,
+
+    /// Tokens used, reported by the backend or estimated.
+    pub tokens: u64,
+}
+
+/// A completed iteration and its metrics, read back from history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IterationRecord {
+    /// When the iteration completed.
+    pub completed_at: DateTime<Utc>,
+
+    /// Iteration number within its run.
+    pub iteration: u32,
+
+    /// Whether the iteration succeeded.
+    pub success: bool,
+
+    /// What the iteration cost.
+    pub metrics: IterationMetrics,
+
+    /// How the iteration's run ended (a completion reason, or `interrupted`);
+    /// `None` while the run is going or if it crashed.
+    pub run_outcome: Option<String>,
+}
+
 /// Summary statistics for a loop history.
 #[derive(Debug, Default)]
 pub struct HistorySummary {
@@ -533,6 +634,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_iteration_metrics_carry_run_outcome() {
+        let (_dir, history) = temp_history();
+        let metrics = |run: &str, cost_usd| IterationMetrics {
+            run_id: Some(run.to_string()),
+            hat: "builder".to_string(),
+            duration_ms: 1200,
+            cost_usd,
+            tokens: 3000,
+        };
+
+        history.record_started("first").unwrap();
+        history
+            .record_iteration_metrics(1, true, &metrics("run-1", 0.1))
+            .unwrap();
+        history.record_iteration_completed(2, false).unwrap();
+        history
+            .record_iteration_metrics(3, false, &metrics("run-1", 0.2))
+            .unwrap();
+        history.record_completed("max_iterations").unwrap();
+        history.record_started("second").unwrap();
+        history
+            .record_iteration_metrics(1, true, &metrics("run-2", 0.3))
+            .unwrap();
+
+        let records = history.iteration_metrics().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].metrics, metrics("run-1", 0.1));
+        assert_eq!(records[1].iteration, 3);
+        assert!(!records[1].success);
+        assert_eq!(records[1].run_outcome.as_deref(), Some("max_iterations"));
+        assert_eq!(records[2].metrics.run_id.as_deref(), Some("run-2"));
+        assert_eq!(records[2].run_outcome, None);
+    }
+
     #[test]
     fn test_empty_file() {
         let (_dir, history) = temp_history();
@@ -0,0 +1,200 @@
+//! Summaries of oversized event payloads before they re-enter a prompt.
+//!
+//! Agents often paste whole test logs or file dumps into the payload of the
+//! events they emit, and the next iteration's prompt carries every payload
+//! verbatim. Past a token budget, only the relevant excerpts are kept:
+//! lines reporting errors, failures and panics with a little context, plus
+//! the start and the end of the text. The full payload is written to the
+//! tool result store so the agent can still read it.
+
+use crate::event_parser::strip_ansi;
+use crate::failure_feedback::CHARS_PER_TOKEN;
+use crate::tool_result_store::ToolResultStore;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+/// Lines kept around each relevant line.
+const CONTEXT_LINES: usize = 2;
+
+/// Lines always considered from the start and the end of the payload.
+const HEAD_LINES: usize = 5;
+const TAIL_LINES: usize = 10;
+
+/// Words marking a line worth keeping, matched case-insensitively.
+const RELEVANT_MARKERS: [&str; 8] = [
+    "error",
+    "fail",
+    "panic",
+    "warning",
+    "exception",
+    "traceback",
+    "assert",
+    "-->",
+];
+
+/// Returns `payload` as it should appear in a prompt.
+///
+/// Payloads within `budget_tokens` are returned unchanged, as is everything
+/// when the budget is 0. Larger ones are saved to `store` and replaced by
+/// their excerpts and a note saying where the original is.
+pub(crate) fn summarize_payload(
+    payload: &str,
+    budget_tokens: usize,
+    store: &ToolResultStore,
+) -> String {
+    let budget = budget_tokens * CHARS_PER_TOKEN;
+    if budget == 0 || payload.len() <= budget {
+        return payload.to_string();
+    }
+
+    let id = payload_id(payload);
+    let location = match store.save(&id, payload) {
+        Ok(()) => format!("full text in {}", store.dir().join(&id).display()),
+        Err(_) => "full text not saved".to_string(),
+    };
+    let text = strip_ansi(payload);
+    let lines: Vec<&str> = text.lines().collect();
+    format!(
+        "[Summarized from {} lines ({} chars); {location}]\n{}",
+        lines.len(),
+        payload.len(),
+        excerpts(&lines, budget)
+    )
+}
+
+/// Joins the excerpts of `lines` that fit in `max_chars`, in their original
+/// order, marking the gaps between them.
+fn excerpts(lines: &[&str], max_chars: usize) -> String {
+    let mut chosen: Vec<Range<usize>> = Vec::new();
+    let mut used = 0;
+    for range in candidate_ranges(lines) {
+        let range = subtract(range, &chosen);
+        let size: usize = lines[range.clone()].iter().map(|l| l.len() + 1).sum();
+        if range.is_empty() || used + size > max_chars {
+            continue;
+        }
+        used += size;
+        chosen.push(range);
+    }
+    chosen.sort_by_key(|range| range.start);
+
+    let mut out = String::new();
+    let mut next = 0;
+    for range in chosen {
+        if range.start > next {
+            out.push_str(&format!("[... {} lines omitted ...]\n", range.start - next));
+        }
+        for line in &lines[range.clone()] {
+            out.push_str(line);
+            out.push('\n');
+        }
+        next = range.end;
+    }
+    if next < lines.len() {
+        out.push_str(&format!("[... {} lines omitted ...]\n", lines.len() - next));
+    }
+    out
+}
+
+/// Ranges worth keeping, most important first: the tail (where failures
+/// are usually summarized), relevant lines from the end backwards, then
+/// the head.
+fn candidate_ranges(lines: &[&str]) -> Vec<Range<usize>> {
+    let tail = lines.len().saturating_sub(TAIL_LINES)..lines.len();
+    let relevant = lines
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, line)| is_relevant(line))
+        .map(|(i, _)| i.saturating_sub(CONTEXT_LINES)..(i + CONTEXT_LINES + 1).min(lines.len()));
+    let head = 0..HEAD_LINES.min(lines.len());
+    std::iter::once(tail)
+        .chain(relevant)
+        .chain(std::iter::once(head))
+        .collect()
+}
+
+fn is_relevant(line: &str) -> bool {
+    let line = line.to_lowercase();
+    RELEVANT_MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+/// Trims `range` so it doesn't overlap ranges already chosen. Overlap at
+/// both ends leaves the part before the first overlapping range.
+fn subtract(mut range: Range<usize>, chosen: &[Range<usize>]) -> Range<usize> {
+    for taken in chosen {
+        if taken.start <= range.start && range.start < taken.end {
+            range.start = taken.end;
+        }
+        if range.start < taken.start && taken.start < range.end {
+            range.end = taken.start;
+        }
+    }
+    range.start..range.end.max(range.start)
+}
+
+/// Names the stored original after its content, so an unchanged payload
+/// seen again overwrites the same file.
+fn payload_id(payload: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    payload.hash(&mut hasher);
+    format!("payload-{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_log(lines: usize) -> String {
+        let mut log: Vec<String> = (0..lines).map(|i| format!("compiling crate {i}")).collect();
+        log[40] = "error[E0308]: mismatched types".to_string();
+        log[41] = "  --> src/lib.rs:12:5".to_string();
+        log.join("\n")
+    }
+
+    #[test]
+    fn test_small_payload_is_unchanged() {
+        let temp = TempDir::new().unwrap();
+        let store = ToolResultStore::new(temp.path());
+        assert_eq!(summarize_payload("tests: pass", 10, &store), "tests: pass");
+        assert!(!temp.path().join(payload_id("tests: pass")).exists());
+    }
+
+    #[test]
+    fn test_large_payload_keeps_relevant_excerpts() {
+        let temp = TempDir::new().unwrap();
+        let store = ToolResultStore::new(temp.path());
+        let log = test_log(200);
+
+        let summary = summarize_payload(&log, 200, &store);
+        assert!(summary.len() < log.len());
+        assert!(summary.starts_with("[Summarized from 200 lines"));
+        assert!(summary.contains("error[E0308]: mismatched types"));
+        assert!(summary.contains("--> src/lib.rs:12:5"));
+        assert!(summary.contains("compiling crate 38"));
+        assert!(summary.contains("compiling crate 199"));
+        assert!(summary.contains("lines omitted"));
+        assert!(!summary.contains("compiling crate 100\n"));
+
+        assert_eq!(store.load(&payload_id(&log)).unwrap(), log);
+    }
+
+    #[test]
+    fn test_excerpts_respect_budget_and_order() {
+        let log = test_log(200);
+        let lines: Vec<&str> = log.lines().collect();
+        let kept = excerpts(&lines, 400);
+        let kept_chars: usize = kept
+            .lines()
+            .filter(|line| !line.starts_with("[..."))
+            .map(|line| line.len() + 1)
+            .sum();
+        assert!(kept_chars <= 400);
+
+        let error = kept.find("error[E0308]").unwrap();
+        let tail = kept.find("compiling crate 199").unwrap();
+        assert!(error < tail);
+    }
+}
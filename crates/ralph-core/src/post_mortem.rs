@@ -0,0 +1,472 @@
+//! Post-mortems for runs that end abnormally.
+//!
+//! When a run hits a limit, fails repeatedly or is aborted, the summary says
+//! *that* it stopped but not why. The post-mortem pulls together what is
+//! needed to pick it up again: a timeline of the key events, the errors that
+//! kept coming back, the last diff and suggested next steps. It is written
+//! next to the run's output log as `.ralph/runs/<run-id>-post-mortem.md`.
+
+use crate::diagnostics::{AgentOutputContent, AgentOutputEntry};
+use crate::encryption::{Cipher, open_lines};
+use crate::event_logger::{EventHistory, EventLogger, EventRecord};
+use crate::event_loop::{LoopState, TerminationReason};
+use crate::failure_feedback::uncommitted_diff;
+use crate::loop_context::LoopContext;
+use crate::summary_writer::{format_duration, status_text};
+use crate::text::truncate_with_ellipsis;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Most recent timeline entries shown.
+const MAX_TIMELINE: usize = 30;
+
+/// Recurring errors shown, most frequent first.
+const MAX_ERRORS: usize = 5;
+
+/// Characters of an error or payload shown on one line.
+const MAX_LINE_CHARS: usize = 160;
+
+/// Writes the post-mortem of an abnormal run.
+#[derive(Debug)]
+pub struct PostMortemWriter {
+    path: PathBuf,
+    events_path: PathBuf,
+    /// The run's agent output log, for the errors the backend reported.
+    run_log_path: PathBuf,
+    /// Repository the last diff is taken from.
+    workspace: PathBuf,
+    max_diff_lines: usize,
+    /// Opens the run log and seals the post-mortem, when encryption is on.
+    cipher: Option<Cipher>,
+}
+
+impl PostMortemWriter {
+    /// Creates a writer for `run_id` using the paths of `context`.
+    pub fn from_context(context: &LoopContext, run_id: &str) -> Self {
+        Self {
+            path: context.post_mortem_path(run_id),
+            events_path: EventLogger::from_context(context).path().to_path_buf(),
+            run_log_path: context.run_log_path(run_id),
+            workspace: context.workspace().to_path_buf(),
+            max_diff_lines: 200,
+            cipher: None,
+        }
+    }
+
+    /// Reads the run log with, and seals the post-mortem with, `cipher`.
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: Option<Cipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Cuts the last diff off after `lines` lines.
+    #[must_use]
+    pub fn with_max_diff_lines(mut self, lines: usize) -> Self {
+        self.max_diff_lines = lines;
+        self
+    }
+
+    /// Returns the post-mortem file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes the post-mortem and returns its content.
+    pub fn write(&self, reason: &TerminationReason, state: &LoopState) -> io::Result<String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = self.generate(reason, state);
+        match &self.cipher {
+            Some(cipher) => fs::write(&self.path, cipher.seal(&content))?,
+            None => fs::write(&self.path, &content)?,
+        }
+        Ok(content)
+    }
+
+    /// Generates the markdown content of the post-mortem.
+    fn generate(&self, reason: &TerminationReason, state: &LoopState) -> String {
+        let events = EventHistory::new(&self.events_path)
+            .read_all()
+            .unwrap_or_default();
+        let output = read_run_log(&self.run_log_path, self.cipher.as_ref());
+
+        let mut content = String::from("# Post-Mortem\n\n");
+        content.push_str(&format!("**Status:** {}\n", status_text(reason)));
+        content.push_str(&format!("**Iterations:** {}\n", state.iteration));
+        content.push_str(&format!(
+            "**Duration:** {}\n",
+            format_duration(state.elapsed())
+        ));
+        if state.cumulative_cost > 0.0 {
+            content.push_str(&format!("**Est. cost:** ${:.2}\n", state.cumulative_cost));
+        }
+
+        content.push_str("\n## Timeline\n\n");
+        content.push_str(&timeline(&events, &output, reason));
+
+        content.push_str("\n## Recurring Errors\n\n");
+        let errors = recurring_errors(&events, &output);
+        if errors.is_empty() {
+            content.push_str("_No errors recorded._\n");
+        }
+        for (count, message) in &errors {
+            content.push_str(&format!("- {count}× `{message}`\n"));
+        }
+
+        content.push_str("\n## Last Diff\n\n");
+        content.push_str(&self.last_diff());
+
+        content.push_str("\n## Next Steps\n\n");
+        for step in next_steps(reason, state, !errors.is_empty()) {
+            content.push_str(&format!("- {step}\n"));
+        }
+
+        content
+    }
+
+    /// The uncommitted changes or, on a clean tree, the last commit.
+    fn last_diff(&self) -> String {
+        let (title, diff) = match uncommitted_diff(&self.workspace) {
+            diff if !diff.trim().is_empty() => ("Uncommitted changes".to_string(), diff),
+            _ => match last_commit(&self.workspace) {
+                Some((subject, diff)) => (format!("Last commit: {subject}"), diff),
+                None => return "_No changes found._\n".to_string(),
+            },
+        };
+        format!(
+            "{title}\n\n```diff\n{}```\n",
+            truncate_lines(&diff, self.max_diff_lines)
+        )
+    }
+}
+
+/// Reads the run's output log, skipping lines that don't parse.
+fn read_run_log(path: &Path, cipher: Option<&Cipher>) -> Vec<AgentOutputEntry> {
+    fs::read_to_string(path)
+        .map(|content| {
+            open_lines(cipher, &content)
+                .filter_map(|line| serde_json::from_str(&line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether an event marks a turn for the worse.
+fn is_failure_topic(topic: &str) -> bool {
+    [".blocked", ".failed", ".exhausted", ".abandoned"]
+        .iter()
+        .any(|suffix| topic.ends_with(suffix))
+        || topic == "event.malformed"
+}
+
+/// Events worth a line in the timeline.
+fn is_key_topic(topic: &str) -> bool {
+    is_failure_topic(topic) || topic.starts_with("loop.") || topic == "task.abort"
+}
+
+/// The first line of `text`, shortened for a one-line listing.
+fn first_line(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    truncate_with_ellipsis(line, MAX_LINE_CHARS)
+}
+
+/// Lists hat changes, key events and backend errors in order, ending with
+/// the termination.
+fn timeline(
+    events: &[EventRecord],
+    output: &[AgentOutputEntry],
+    reason: &TerminationReason,
+) -> String {
+    let mut entries: Vec<(Option<DateTime<Utc>>, String)> = Vec::new();
+    let parse_ts = |ts: &str| {
+        DateTime::parse_from_rfc3339(ts)
+            .ok()
+            .map(|ts| ts.with_timezone(&Utc))
+    };
+
+    let mut last_hat = "";
+    for record in events {
+        let ts = parse_ts(&record.ts);
+        if !record.hat.is_empty() && record.hat != last_hat {
+            last_hat = &record.hat;
+            entries.push((
+                ts,
+                format!(
+                    "iteration {} · hat `{}` active",
+                    record.iteration, record.hat
+                ),
+            ));
+        }
+        if is_key_topic(&record.topic) {
+            let payload = first_line(&record.payload);
+            let line = if payload.is_empty() {
+                format!("iteration {} · `{}`", record.iteration, record.topic)
+            } else {
+                format!(
+                    "iteration {} · `{}`: {payload}",
+                    record.iteration, record.topic
+                )
+            };
+            entries.push((ts, line));
+        }
+    }
+    for entry in output {
+        if let AgentOutputContent::Error { message } = &entry.content {
+            entries.push((
+                parse_ts(&entry.ts),
+                format!(
+                    "iteration {} · error: {}",
+                    entry.iteration,
+                    first_line(message)
+                ),
+            ));
+        }
+    }
+    // Stable, so entries without a timestamp keep their relative order
+    entries.sort_by_key(|(ts, _)| *ts);
+
+    let mut timeline = String::new();
+    let omitted = entries.len().saturating_sub(MAX_TIMELINE);
+    if omitted > 0 {
+        timeline.push_str(&format!("- _{omitted} earlier entries omitted_\n"));
+    }
+    for (ts, line) in &entries[omitted..] {
+        match ts {
+            Some(ts) => timeline.push_str(&format!("- {} {line}\n", ts.format("%H:%M:%S"))),
+            None => timeline.push_str(&format!("- {line}\n")),
+        }
+    }
+    timeline.push_str(&format!("- **{}**\n", status_text(reason)));
+    timeline
+}
+
+/// Counts errors and failure payloads by their first line, with numbers
+/// masked so the same error at different lines or counts is grouped.
+/// Returns the most frequent, each with the first message seen.
+fn recurring_errors(events: &[EventRecord], output: &[AgentOutputEntry]) -> Vec<(usize, String)> {
+    let messages = events
+        .iter()
+        .filter(|record| is_failure_topic(&record.topic))
+        .map(|record| record.payload.as_str())
+        .chain(output.iter().filter_map(|entry| match &entry.content {
+            AgentOutputContent::Error { message } => Some(message.as_str()),
+            _ => None,
+        }))
+        .map(first_line)
+        .filter(|message| !message.is_empty());
+
+    let mut counts: HashMap<String, (usize, usize, String)> = HashMap::new();
+    for (order, message) in messages.enumerate() {
+        counts
+            .entry(mask_numbers(&message))
+            .or_insert((0, order, message))
+            .0 += 1;
+    }
+
+    let mut errors: Vec<_> = counts.into_values().collect();
+    errors.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    errors
+        .into_iter()
+        .take(MAX_ERRORS)
+        .map(|(count, _, message)| (count, message))
+        .collect()
+}
+
+/// Replaces each run of digits with `N`.
+fn mask_numbers(text: &str) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut in_number = false;
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            if !in_number {
+                masked.push('N');
+            }
+            in_number = true;
+        } else {
+            masked.push(c);
+            in_number = false;
+        }
+    }
+    masked
+}
+
+/// Runs `git show HEAD` in `workspace`, returning the commit's subject line
+/// and diff.
+fn last_commit(workspace: &Path) -> Option<(String, String)> {
+    let output = Command::new("git")
+        .args([
+            "show",
+            "HEAD",
+            "--no-color",
+            "--no-ext-diff",
+            "--format=%h %s",
+        ])
+        .current_dir(workspace)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (subject, diff) = text.split_once('\n').unwrap_or((&text, ""));
+    Some((subject.to_string(), diff.trim_start().to_string()))
+}
+
+/// Keeps the first `max_lines` lines of `text`, noting how many were cut.
+fn truncate_lines(text: &str, max_lines: usize) -> String {
+    let mut kept = String::new();
+    for line in text.lines().take(max_lines) {
+        kept.push_str(line);
+        kept.push('\n');
+    }
+    let total = text.lines().count();
+    if total > max_lines {
+        kept.push_str(&format!("[... {} more lines ...]\n", total - max_lines));
+    }
+    kept
+}
+
+/// Suggestions for picking the run up again.
+fn next_steps(reason: &TerminationReason, state: &LoopState, has_errors: bool) -> Vec<String> {
+    let mut steps = Vec::new();
+    if has_errors {
+        steps.push("Start with the most frequent error above.".to_string());
+    }
+    let step = match reason {
+        TerminationReason::MaxCost => {
+            "Raise `event_loop.max_cost_usd` if the spend was expected, then resume with `ralph run --continue`."
+        }
+        TerminationReason::MaxIterations | TerminationReason::ChaosModeMaxIterations => {
+            "Raise `event_loop.max_iterations`, or split the task into smaller ones, then resume with `ralph run --continue`."
+        }
+        TerminationReason::MaxRuntime => {
+            "Raise `event_loop.max_runtime_seconds`, then resume with `ralph run --continue`."
+        }
+        TerminationReason::ConsecutiveFailures => {
+            "Check that the backend is installed and authenticated, and read the failing iterations with `ralph logs`."
+        }
+        TerminationReason::LoopThrashing => {
+            "The same work kept getting blocked: fix the blocker by hand or make the task more specific in the prompt."
+        }
+        TerminationReason::ValidationFailure => {
+            "The agent kept writing malformed events: check the event instructions in the prompt and the backend's output."
+        }
+        TerminationReason::Stopped | TerminationReason::Interrupted => {
+            "Resume with `ralph run --continue` when ready."
+        }
+        _ => "Review the timeline and resume with `ralph run --continue`.",
+    };
+    steps.push(step.to_string());
+    if let Some(anomaly) = state
+        .cost_anomalies
+        .iter()
+        .max_by(|a, b| a.ratio().total_cmp(&b.ratio()))
+    {
+        steps.push(format!(
+            "Check what the costliest outlier was doing: {anomaly}."
+        ));
+    }
+    if !state.abandoned_tasks.is_empty() {
+        steps.push(format!(
+            "Revisit abandoned tasks: {}.",
+            state.abandoned_tasks.join(", ")
+        ));
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn writer(temp: &TempDir) -> PostMortemWriter {
+        PostMortemWriter::from_context(&LoopContext::primary(temp.path().to_path_buf()), "run-1")
+    }
+
+    #[test]
+    fn test_post_mortem_lists_timeline_errors_and_steps() {
+        let temp = TempDir::new().unwrap();
+        let writer = writer(&temp);
+        fs::create_dir_all(temp.path().join(".ralph/runs")).unwrap();
+        fs::write(
+            temp.path().join(".ralph/events.jsonl"),
+            [
+                r#"{"ts":"2026-01-01T10:00:00Z","iteration":1,"hat":"builder","topic":"build.task","payload":"add parser"}"#,
+                r#"{"ts":"2026-01-01T10:05:00Z","iteration":2,"hat":"builder","topic":"build.blocked","payload":"test parser::tests::nested failed at line 12\nmore"}"#,
+                r#"{"ts":"2026-01-01T10:09:00Z","iteration":3,"hat":"builder","topic":"build.blocked","payload":"test parser::tests::nested failed at line 40"}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        fs::write(
+            temp.path().join(".ralph/runs/run-1.jsonl"),
+            r#"{"ts":"2026-01-01T10:07:00Z","iteration":3,"hat":"builder","type":"error","message":"rate limited"}"#,
+        )
+        .unwrap();
+
+        let state = LoopState {
+            iteration: 3,
+            abandoned_tasks: vec!["task-7".to_string()],
+            ..LoopState::default()
+        };
+        let content = writer
+            .write(&TerminationReason::ConsecutiveFailures, &state)
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(writer.path()).unwrap(), content);
+        assert!(writer.path().ends_with(".ralph/runs/run-1-post-mortem.md"));
+        assert!(content.contains("**Status:** Failed: too many consecutive failures"));
+        assert!(content.contains("- 10:00:00 iteration 1 · hat `builder` active\n"));
+        assert!(content.contains(
+            "- 10:05:00 iteration 2 · `build.blocked`: test parser::tests::nested failed at line 12\n- 10:07:00 iteration 3 · error: rate limited\n"
+        ));
+        assert!(!content.contains("`build.task`"));
+        assert!(content.contains("- **Failed: too many consecutive failures**\n"));
+        assert!(content.contains(
+            "- 2× `test parser::tests::nested failed at line 12`\n- 1× `rate limited`\n"
+        ));
+        assert!(content.contains("_No changes found._"));
+        assert!(content.contains("- Start with the most frequent error above.\n"));
+        assert!(content.contains("`ralph logs`"));
+        assert!(content.contains("Revisit abandoned tasks: task-7."));
+    }
+
+    #[test]
+    fn test_post_mortem_without_history() {
+        let temp = TempDir::new().unwrap();
+        let content = writer(&temp)
+            .write(&TerminationReason::MaxCost, &LoopState::default())
+            .unwrap();
+
+        assert!(content.contains("- **Stopped: max cost exceeded**\n"));
+        assert!(content.contains("_No errors recorded._"));
+        assert!(content.contains("event_loop.max_cost_usd"));
+        assert!(!content.contains("most frequent error"));
+    }
+
+    #[test]
+    fn test_truncate_lines_notes_the_cut() {
+        assert_eq!(truncate_lines("a\nb\n", 5), "a\nb\n");
+        assert_eq!(
+            truncate_lines("a\nb\nc\n", 1),
+            "a\n[... 2 more lines ...]\n"
+        );
+    }
+
+    #[test]
+    fn test_abnormal_reasons() {
+        assert!(TerminationReason::MaxCost.is_abnormal());
+        assert!(TerminationReason::Interrupted.is_abnormal());
+        assert!(!TerminationReason::CompletionPromise.is_abnormal());
+        assert!(!TerminationReason::RestartRequested.is_abnormal());
+    }
+}
@@ -0,0 +1,453 @@
+//! Pausing the loop around battery and suspend events.
+//!
+//! A long run on a laptop can die when the battery runs out, and an
+//! iteration caught by a suspend usually fails on a timed-out connection.
+//! With `power:` configured, the loop checks the power supply between
+//! iterations and waits while the machine is on battery below the
+//! threshold or preparing to sleep, then carries on once it is back on AC
+//! (or charged) and awake. A suspend is remembered until the loop next
+//! checks, so one that happens mid-iteration still holds the next iteration
+//! until the machine has been awake for a moment.
+//!
+//! The battery is read from `/sys/class/power_supply` on Linux and from
+//! `pmset -g batt` on macOS. Suspend is detected on Linux only, by watching
+//! logind's `PrepareForSleep` signal through `dbus-monitor`.
+
+use crate::config::PowerConfig;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// How long the loop keeps waiting after the system wakes up, so network
+/// connections are back before the next iteration starts.
+const WAKE_SETTLE: Duration = Duration::from_secs(30);
+
+/// Power supply state at one point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerState {
+    /// Running from the battery (no AC adapter online).
+    pub on_battery: bool,
+    /// Remaining charge, when the machine has a battery.
+    pub battery_percent: Option<u8>,
+}
+
+impl PowerState {
+    /// Reads the current state, or `None` on machines (or platforms) without
+    /// a readable power supply.
+    pub fn read() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+            parse_pmset(&String::from_utf8_lossy(&output.stdout))
+        } else {
+            read_sysfs(Path::new("/sys/class/power_supply"))
+        }
+    }
+}
+
+/// Reads `type`, `online`, `capacity` and `status` of each supply under
+/// `dir` (the layout of `/sys/class/power_supply`).
+///
+/// Supplies scoped to a device (a wireless mouse's or headset's battery)
+/// are skipped. With several system batteries the charge is their combined
+/// energy, or the lowest capacity when energy isn't reported.
+fn read_sysfs(dir: &Path) -> Option<PowerState> {
+    let read = |supply: &Path, file: &str| {
+        std::fs::read_to_string(supply.join(file))
+            .ok()
+            .map(|value| value.trim().to_string())
+    };
+    let read_number = |supply: &Path, file: &str| read(supply, file)?.parse::<u64>().ok();
+
+    let mut ac_online = None;
+    let mut batteries = Vec::new();
+    let mut discharging = false;
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let supply = entry.path();
+        if read(&supply, "scope").as_deref() == Some("Device") {
+            continue;
+        }
+        match read(&supply, "type").as_deref() {
+            Some("Mains" | "USB") => {
+                let online = read(&supply, "online").is_some_and(|v| v == "1");
+                ac_online = Some(ac_online.unwrap_or(false) || online);
+            }
+            Some("Battery") => {
+                if let Some(percent) = read(&supply, "capacity").and_then(|v| v.parse().ok()) {
+                    let energy = read_number(&supply, "energy_now")
+                        .zip(read_number(&supply, "energy_full"))
+                        .or_else(|| {
+                            read_number(&supply, "charge_now")
+                                .zip(read_number(&supply, "charge_full"))
+                        });
+                    batteries.push((percent, energy));
+                }
+                discharging |= read(&supply, "status").is_some_and(|v| v == "Discharging");
+            }
+            _ => {}
+        }
+    }
+
+    let battery_percent = combined_percent(&batteries);
+    if ac_online.is_none() && battery_percent.is_none() {
+        return None;
+    }
+    Some(PowerState {
+        on_battery: ac_online.map_or(discharging, |online| !online),
+        battery_percent,
+    })
+}
+
+/// Combines per-battery `(capacity, (energy_now, energy_full))` readings:
+/// the share of total energy left when every battery reports it, else the
+/// lowest capacity.
+fn combined_percent(batteries: &[(u8, Option<(u64, u64)>)]) -> Option<u8> {
+    let energy = batteries
+        .iter()
+        .map(|(_, energy)| *energy)
+        .collect::<Option<Vec<_>>>()
+        .map(|energy| {
+            energy
+                .iter()
+                .fold((0, 0), |(now, full), (n, f)| (now + n, full + f))
+        })
+        .filter(|(_, full)| *full > 0);
+    match energy {
+        Some((now, full)) => u8::try_from((now.min(full) * 100 + full / 2) / full).ok(),
+        None => batteries.iter().map(|(percent, _)| *percent).min(),
+    }
+}
+
+/// Parses `pmset -g batt`: the power source on the first line, then one
+/// line per battery with its charge, e.g. `-InternalBattery-0 (id=1) 42%; ...`.
+fn parse_pmset(output: &str) -> Option<PowerState> {
+    let source = output.lines().next()?;
+    let on_battery = source.contains("'Battery Power'");
+    let battery_percent = output.lines().skip(1).find_map(|line| {
+        let percent = line.split('%').next()?;
+        percent
+            .rsplit(|c: char| c.is_whitespace())
+            .next()?
+            .parse()
+            .ok()
+    });
+    if !on_battery && !source.contains("'AC Power'") {
+        return None;
+    }
+    Some(PowerState {
+        on_battery,
+        battery_percent,
+    })
+}
+
+/// What the loop has to know about suspends since it last checked.
+#[derive(Debug, Default)]
+struct SleepState {
+    /// Between `PrepareForSleep(true)` and `PrepareForSleep(false)`.
+    preparing: bool,
+    /// A suspend started and the loop hasn't waited out the wake-up yet.
+    slept: bool,
+    woke_at: Option<Instant>,
+}
+
+impl SleepState {
+    fn on_prepare_for_sleep(&mut self, sleeping: bool, now: Instant) {
+        self.preparing = sleeping;
+        if sleeping {
+            self.slept = true;
+            self.woke_at = None;
+        } else {
+            self.woke_at = Some(now);
+        }
+    }
+
+    fn pause_reason(&mut self, now: Instant) -> Option<String> {
+        if self.preparing {
+            return Some("system is suspending".to_string());
+        }
+        if !self.slept {
+            return None;
+        }
+        match self.woke_at {
+            Some(woke_at) if now.duration_since(woke_at) < WAKE_SETTLE => {
+                Some("system resumed from suspend".to_string())
+            }
+            _ => {
+                self.slept = false;
+                None
+            }
+        }
+    }
+}
+
+/// Watches logind for suspends.
+struct SleepWatcher {
+    child: Child,
+    state: Arc<Mutex<SleepState>>,
+}
+
+impl SleepWatcher {
+    fn start() -> Option<Self> {
+        let mut child = Command::new("dbus-monitor")
+            .args([
+                "--system",
+                "type='signal',interface='org.freedesktop.login1.Manager',member='PrepareForSleep'",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| debug!("Cannot watch for suspend (dbus-monitor): {}", e))
+            .ok()?;
+        let stdout = child.stdout.take()?;
+        let state = Arc::new(Mutex::new(SleepState::default()));
+        let shared = Arc::clone(&state);
+        std::thread::spawn(move || {
+            // The signal's only argument is `true` before sleep, `false` after
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                let sleeping = match line.trim() {
+                    "boolean true" => true,
+                    "boolean false" => false,
+                    _ => continue,
+                };
+                if let Ok(mut state) = shared.lock() {
+                    state.on_prepare_for_sleep(sleeping, Instant::now());
+                }
+            }
+        });
+        Some(Self { child, state })
+    }
+}
+
+impl Drop for SleepWatcher {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Decides, between iterations, whether the loop should wait for power.
+pub struct PowerMonitor {
+    pause_below_percent: Option<u8>,
+    sleep: Option<SleepWatcher>,
+}
+
+impl PowerMonitor {
+    /// Starts monitoring as configured, or returns `None` when `power:`
+    /// enables nothing.
+    pub fn from_config(config: &PowerConfig) -> Option<Self> {
+        if config.pause_on_battery_below.is_none() && !config.pause_on_suspend {
+            return None;
+        }
+        let sleep = if config.pause_on_suspend && cfg!(target_os = "linux") {
+            SleepWatcher::start()
+        } else {
+            None
+        };
+        Some(Self {
+            pause_below_percent: config.pause_on_battery_below,
+            sleep,
+        })
+    }
+
+    /// Why the loop should wait right now, if it should.
+    pub fn pause_reason(&self) -> Option<String> {
+        if let Some(reason) = self
+            .sleep
+            .as_ref()
+            .and_then(|sleep| sleep.state.lock().ok()?.pause_reason(Instant::now()))
+        {
+            return Some(reason);
+        }
+        let threshold = self.pause_below_percent?;
+        battery_pause_reason(PowerState::read()?, threshold)
+    }
+}
+
+fn battery_pause_reason(state: PowerState, threshold: u8) -> Option<String> {
+    let percent = state.battery_percent?;
+    (state.on_battery && percent < threshold)
+        .then(|| format!("on battery at {percent}% (below {threshold}%)"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn supply(dir: &Path, name: &str, files: &[(&str, &str)]) {
+        let path = dir.join(name);
+        fs::create_dir_all(&path).unwrap();
+        for (file, value) in files {
+            fs::write(path.join(file), format!("{value}\n")).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_read_sysfs_laptop_on_battery() {
+        let temp = TempDir::new().unwrap();
+        supply(temp.path(), "AC", &[("type", "Mains"), ("online", "0")]);
+        supply(
+            temp.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "15"),
+                ("status", "Discharging"),
+            ],
+        );
+
+        let state = read_sysfs(temp.path()).unwrap();
+        assert_eq!(
+            state,
+            PowerState {
+                on_battery: true,
+                battery_percent: Some(15),
+            }
+        );
+        assert_eq!(
+            battery_pause_reason(state, 20).as_deref(),
+            Some("on battery at 15% (below 20%)")
+        );
+        assert_eq!(battery_pause_reason(state, 10), None);
+
+        // Back on AC: no pause, whatever the charge
+        fs::write(temp.path().join("AC/online"), "1\n").unwrap();
+        let state = read_sysfs(temp.path()).unwrap();
+        assert!(!state.on_battery);
+        assert_eq!(battery_pause_reason(state, 20), None);
+    }
+
+    #[test]
+    fn test_read_sysfs_two_batteries_and_a_device() {
+        let temp = TempDir::new().unwrap();
+        supply(temp.path(), "AC", &[("type", "Mains"), ("online", "0")]);
+        // The external battery drains first; most energy is left internally
+        supply(
+            temp.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "90"),
+                ("status", "Unknown"),
+                ("energy_now", "45000000"),
+                ("energy_full", "50000000"),
+            ],
+        );
+        supply(
+            temp.path(),
+            "BAT1",
+            &[
+                ("type", "Battery"),
+                ("capacity", "10"),
+                ("status", "Discharging"),
+                ("energy_now", "2300000"),
+                ("energy_full", "23000000"),
+            ],
+        );
+        // A wireless mouse: neither its charge nor its status counts
+        supply(
+            temp.path(),
+            "hidpp_battery_0",
+            &[
+                ("type", "Battery"),
+                ("scope", "Device"),
+                ("capacity", "5"),
+                ("status", "Discharging"),
+            ],
+        );
+
+        let state = read_sysfs(temp.path()).unwrap();
+        assert_eq!(
+            state,
+            PowerState {
+                on_battery: true,
+                battery_percent: Some(65),
+            }
+        );
+
+        // Without energy readings, the lowest system battery counts
+        for bat in ["BAT0", "BAT1"] {
+            fs::remove_file(temp.path().join(bat).join("energy_now")).unwrap();
+        }
+        let state = read_sysfs(temp.path()).unwrap();
+        assert_eq!(state.battery_percent, Some(10));
+    }
+
+    #[test]
+    fn test_read_sysfs_ignores_device_batteries() {
+        let temp = TempDir::new().unwrap();
+        supply(
+            temp.path(),
+            "hidpp_battery_0",
+            &[
+                ("type", "Battery"),
+                ("scope", "Device"),
+                ("capacity", "5"),
+                ("status", "Discharging"),
+            ],
+        );
+        // A desktop with a wireless mouse has no power state to act on
+        assert_eq!(read_sysfs(temp.path()), None);
+    }
+
+    #[test]
+    fn test_read_sysfs_without_supplies() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(read_sysfs(temp.path()), None);
+        assert_eq!(read_sysfs(&temp.path().join("missing")), None);
+    }
+
+    #[test]
+    fn test_parse_pmset() {
+        let battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t42%; discharging; 3:10 remaining present: true\n";
+        assert_eq!(
+            parse_pmset(battery),
+            Some(PowerState {
+                on_battery: true,
+                battery_percent: Some(42),
+            })
+        );
+        let ac = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t100%; charged; 0:00 remaining present: true\n";
+        assert_eq!(parse_pmset(ac).map(|state| state.on_battery), Some(false));
+        assert_eq!(parse_pmset(""), None);
+    }
+
+    #[test]
+    fn test_suspend_mid_iteration_pauses_after_wake() {
+        let mut state = SleepState::default();
+        let start = Instant::now();
+        assert_eq!(state.pause_reason(start), None);
+
+        // Suspended and woke up while an iteration was running
+        state.on_prepare_for_sleep(true, start);
+        state.on_prepare_for_sleep(false, start + Duration::from_secs(1));
+
+        assert_eq!(
+            state
+                .pause_reason(start + Duration::from_secs(5))
+                .as_deref(),
+            Some("system resumed from suspend")
+        );
+        assert_eq!(state.pause_reason(start + Duration::from_secs(31)), None);
+        assert_eq!(state.pause_reason(start + Duration::from_secs(32)), None);
+    }
+
+    #[test]
+    fn test_preparing_for_sleep_pauses() {
+        let mut state = SleepState::default();
+        let now = Instant::now();
+        state.on_prepare_for_sleep(true, now);
+        assert_eq!(
+            state.pause_reason(now).as_deref(),
+            Some("system is suspending")
+        );
+    }
+
+    #[test]
+    fn test_monitor_disabled_without_config() {
+        assert!(PowerMonitor::from_config(&PowerConfig::default()).is_none());
+    }
+}
@@ -0,0 +1,213 @@
+//! Archive of the exact prompt each iteration sent to the backend.
+//!
+//! With `archive_prompts: true`, every composed prompt is appended to
+//! `.ralph/prompts/<run-id>.jsonl`. `ralph prompts` lists and diffs them, so
+//! you can see how injected memories, scratchpad trimming and failure
+//! feedback change what the model actually receives from one iteration to
+//! the next. With `encryption.enabled` each line is sealed like the run logs.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::encryption::{Cipher, open_lines};
+
+/// One archived prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptRecord {
+    /// RFC 3339 timestamp of when the prompt was sent.
+    pub ts: String,
+    pub iteration: u32,
+    pub hat: String,
+    pub prompt: String,
+}
+
+impl PromptRecord {
+    /// Records `prompt`, sent now by `hat` in `iteration`.
+    pub fn new(iteration: u32, hat: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            ts: Utc::now().to_rfc3339(),
+            iteration,
+            hat: hat.into(),
+            prompt: prompt.into(),
+        }
+    }
+}
+
+/// A run's prompt archive file.
+#[derive(Debug, Clone)]
+pub struct PromptArchive {
+    path: PathBuf,
+    cipher: Option<Cipher>,
+}
+
+impl PromptArchive {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            cipher: None,
+        }
+    }
+
+    /// Seals appended prompts and opens sealed ones when reading (see
+    /// [`crate::Cipher`]).
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: Option<Cipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends a prompt, creating the file and its directory if needed.
+    pub fn append(&self, record: &PromptRecord) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let line = serde_json::to_string(record)?;
+        match &self.cipher {
+            Some(cipher) => writeln!(file, "{}", cipher.seal(&line)),
+            None => writeln!(file, "{line}"),
+        }
+    }
+
+    /// Reads every archived prompt, oldest first. Malformed lines, and
+    /// sealed lines that cannot be opened, are skipped; a missing file reads
+    /// as empty.
+    pub fn read_all(&self) -> std::io::Result<Vec<PromptRecord>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(open_lines(self.cipher.as_ref(), &content)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect())
+    }
+}
+
+/// A titled part of a prompt and its length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptSection {
+    /// The heading (`## PENDING EVENTS`) or opening tag (`<scratchpad>`)
+    /// starting the section; `(preamble)` for text before the first one.
+    pub title: String,
+    pub chars: usize,
+}
+
+/// Splits a prompt into sections at top-level headings (`#`, `##`) and at
+/// injected blocks (`<scratchpad ...>`, `<gates>`), ignoring code fences.
+pub fn prompt_sections(prompt: &str) -> Vec<PromptSection> {
+    let mut sections = vec![PromptSection {
+        title: "(preamble)".to_string(),
+        chars: 0,
+    }];
+    let mut in_fence = false;
+    for line in prompt.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if let Some(title) = (!in_fence).then(|| section_title(line)).flatten() {
+            sections.push(PromptSection { title, chars: 0 });
+        }
+        if let Some(section) = sections.last_mut() {
+            section.chars += line.chars().count();
+        }
+    }
+    if sections[0].chars == 0 {
+        sections.remove(0);
+    }
+    sections
+}
+
+fn section_title(line: &str) -> Option<String> {
+    let line = line.trim_end();
+    if line.starts_with("# ") || line.starts_with("## ") {
+        return Some(line.to_string());
+    }
+    let tag = line.strip_prefix('<')?;
+    let name_len = tag
+        .find(|c: char| !(c.is_ascii_lowercase() || c == '-' || c == '_'))
+        .unwrap_or(tag.len());
+    let rest = &tag[name_len..];
+    (name_len > 0 && (rest.starts_with('>') || rest.starts_with(' ')))
+        .then(|| format!("<{}>", &tag[..name_len]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_and_read_all() {
+        let temp = TempDir::new().unwrap();
+        let archive = PromptArchive::new(temp.path().join("prompts/run.jsonl"));
+        assert!(archive.read_all().unwrap().is_empty());
+
+        archive
+            .append(&PromptRecord::new(1, "ralph", "first\nprompt"))
+            .unwrap();
+        archive
+            .append(&PromptRecord::new(2, "builder", "second"))
+            .unwrap();
+
+        let records = archive.read_all().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].prompt, "first\nprompt");
+        assert_eq!(records[1].hat, "builder");
+    }
+
+    #[test]
+    fn test_sealed_archive_needs_the_cipher() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("prompts/run.jsonl");
+        let cipher = Cipher::from_base64(&Cipher::generate_key()).unwrap();
+        let archive = PromptArchive::new(&path).with_cipher(Some(cipher));
+
+        archive
+            .append(&PromptRecord::new(1, "ralph", "fn secret() {}"))
+            .unwrap();
+
+        assert!(!fs::read_to_string(&path).unwrap().contains("secret"));
+        assert_eq!(archive.read_all().unwrap()[0].prompt, "fn secret() {}");
+        assert!(PromptArchive::new(&path).read_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_prompt_sections() {
+        let prompt = "<scratchpad path=\"s.md\">\nnotes\n</scratchpad>\n\n# Memories\n- one\n\
+                      ## PENDING EVENTS\n```sh\n# not a heading\n```\n<gates>\ntests\n</gates>\n";
+        let titles: Vec<_> = prompt_sections(prompt)
+            .into_iter()
+            .map(|section| section.title)
+            .collect();
+        assert_eq!(
+            titles,
+            ["<scratchpad>", "# Memories", "## PENDING EVENTS", "<gates>"]
+        );
+
+        let sections = prompt_sections("intro\n## DONE\nok\n");
+        assert_eq!(
+            sections,
+            vec![
+                PromptSection {
+                    title: "(preamble)".to_string(),
+                    chars: 6,
+                },
+                PromptSection {
+                    title: "## DONE".to_string(),
+                    chars: 11,
+                },
+            ]
+        );
+    }
+}
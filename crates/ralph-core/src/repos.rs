@@ -0,0 +1,217 @@
+//! Repositories taking part in a multi-repo run.
+//!
+//! A run normally works inside its workspace. With `repos:` configured it
+//! also works across other repositories, e.g. an API and its client: the
+//! prompt lists every repository with its branch and uncommitted files,
+//! landing commits each repository separately, and the handoff and
+//! `ralph repos diff` group changes by repository.
+
+use crate::config::RepoConfig;
+use crate::git_ops::{
+    AutoCommitResult, auto_commit_changes, get_commit_summary, get_current_branch,
+    get_uncommitted_files,
+};
+use std::path::{Path, PathBuf};
+
+/// Uncommitted files listed per repository in the prompt.
+const PROMPT_FILES_PER_REPO: usize = 10;
+
+/// A configured repository with its path resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repo {
+    pub name: String,
+    /// Absolute (or workspace-joined) repository root.
+    pub path: PathBuf,
+    pub description: Option<String>,
+}
+
+/// Outcome of committing one repository's changes.
+#[derive(Debug, Clone)]
+pub struct RepoCheckpoint {
+    pub repo: String,
+    /// The commit made, or why the repository couldn't be committed.
+    pub result: Result<AutoCommitResult, String>,
+}
+
+/// The repositories of a run, in configuration order.
+#[derive(Debug, Clone, Default)]
+pub struct RepoSet {
+    repos: Vec<Repo>,
+}
+
+impl RepoSet {
+    /// Resolves `repos` against the workspace root.
+    pub fn from_config(repos: &[RepoConfig], workspace_root: &Path) -> Self {
+        let repos = repos
+            .iter()
+            .map(|repo| Repo {
+                name: repo.name.clone(),
+                path: workspace_root.join(&repo.path),
+                description: repo.description.clone(),
+            })
+            .collect();
+        Self { repos }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.repos.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Repo> {
+        self.repos.iter()
+    }
+
+    /// Looks a repository up by name.
+    pub fn get(&self, name: &str) -> Option<&Repo> {
+        self.repos.iter().find(|repo| repo.name == name)
+    }
+
+    /// Commits each repository's uncommitted changes in that repository.
+    ///
+    /// A repository that fails to commit (missing, not a git repo, no git
+    /// identity) doesn't stop the others.
+    pub fn checkpoint(&self, loop_id: &str) -> Vec<RepoCheckpoint> {
+        self.repos
+            .iter()
+            .map(|repo| RepoCheckpoint {
+                repo: repo.name.clone(),
+                result: auto_commit_changes(&repo.path, loop_id).map_err(|e| e.to_string()),
+            })
+            .collect()
+    }
+
+    /// Builds the `<repositories>` prompt section, or an empty string when
+    /// no repositories are configured.
+    pub fn prompt_section(&self) -> String {
+        if self.repos.is_empty() {
+            return String::new();
+        }
+
+        let mut section = String::from("<repositories>\n");
+        section.push_str(
+            "This run spans several repositories. Run git, build and test commands \
+             inside the repository they apply to, and keep each repository's \
+             changes self-contained.\n\n",
+        );
+        for repo in &self.repos {
+            section.push_str(&format!("## {} (`{}`)\n", repo.name, repo.path.display()));
+            if let Some(description) = &repo.description {
+                section.push_str(&format!("{description}\n"));
+            }
+            let Ok(branch) = get_current_branch(&repo.path) else {
+                section.push_str("- Not a git repository (or missing)\n\n");
+                continue;
+            };
+            section.push_str(&format!("- Branch: `{branch}`\n"));
+            if let Ok(summary) = get_commit_summary(&repo.path)
+                && !summary.is_empty()
+            {
+                section.push_str(&format!("- HEAD: {summary}\n"));
+            }
+            let files = get_uncommitted_files(&repo.path).unwrap_or_default();
+            if files.is_empty() {
+                section.push_str("- No uncommitted changes\n");
+            } else {
+                section.push_str(&format!("- Uncommitted changes ({}):\n", files.len()));
+                for file in files.iter().take(PROMPT_FILES_PER_REPO) {
+                    section.push_str(&format!("  - `{file}`\n"));
+                }
+                if files.len() > PROMPT_FILES_PER_REPO {
+                    section.push_str(&format!(
+                        "  - ... and {} more\n",
+                        files.len() - PROMPT_FILES_PER_REPO
+                    ));
+                }
+            }
+            section.push('\n');
+        }
+        section.push_str("</repositories>\n\n");
+        section
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+    }
+
+    fn init_git_repo(dir: &Path) {
+        fs::create_dir_all(dir).unwrap();
+        git(dir, &["init", "--initial-branch=main"]);
+        git(dir, &["config", "user.email", "test@test.local"]);
+        git(dir, &["config", "user.name", "Test User"]);
+        fs::write(dir.join("README.md"), "# Test").unwrap();
+        git(dir, &["add", "README.md"]);
+        git(dir, &["commit", "-m", "Initial commit"]);
+    }
+
+    fn two_repos(workspace: &Path) -> RepoSet {
+        init_git_repo(&workspace.join("api"));
+        init_git_repo(&workspace.join("client"));
+        RepoSet::from_config(
+            &[
+                RepoConfig {
+                    name: "api".to_string(),
+                    path: PathBuf::from("api"),
+                    description: Some("REST API".to_string()),
+                },
+                RepoConfig {
+                    name: "client".to_string(),
+                    path: PathBuf::from("client"),
+                    description: None,
+                },
+            ],
+            workspace,
+        )
+    }
+
+    #[test]
+    fn test_prompt_section_lists_each_repo() {
+        let temp = TempDir::new().unwrap();
+        let repos = two_repos(temp.path());
+        fs::write(temp.path().join("api/routes.rs"), "").unwrap();
+
+        let section = repos.prompt_section();
+        assert!(section.starts_with("<repositories>\n"));
+        assert!(section.contains("## api ("));
+        assert!(section.contains("REST API\n- Branch: `main`\n"));
+        assert!(section.contains("- Uncommitted changes (1):\n  - `routes.rs`\n"));
+        assert!(section.contains("## client ("));
+        assert!(section.contains("- No uncommitted changes\n"));
+        assert!(RepoSet::default().prompt_section().is_empty());
+    }
+
+    #[test]
+    fn test_checkpoint_commits_each_repo_separately() {
+        let temp = TempDir::new().unwrap();
+        let mut repos = two_repos(temp.path());
+        fs::write(temp.path().join("api/routes.rs"), "").unwrap();
+        repos.repos.push(Repo {
+            name: "missing".to_string(),
+            path: temp.path().join("missing"),
+            description: None,
+        });
+
+        let checkpoints = repos.checkpoint("primary");
+        assert_eq!(checkpoints.len(), 3);
+        assert_eq!(checkpoints[0].repo, "api");
+        assert!(checkpoints[0].result.as_ref().unwrap().committed);
+        assert!(!checkpoints[1].result.as_ref().unwrap().committed);
+        assert!(checkpoints[2].result.is_err());
+        assert!(
+            get_uncommitted_files(temp.path().join("api"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
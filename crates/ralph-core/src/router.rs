@@ -0,0 +1,175 @@
+//! The router: a cheap classification step choosing the next hat.
+//!
+//! In multi-hat mode every hat with pending events is activated in the same
+//! iteration, so an iteration can end up juggling a reviewer, a builder and
+//! a planner at once. With `features.router` enabled the loop first asks a
+//! (preferably cheap) backend which of the competing hats should work next,
+//! given the run's summary and recent events. The other hats' events stay
+//! queued for later iterations. Each decision is published as a
+//! `router.decision` event, so it shows up in `ralph events` and the TUI.
+
+use crate::event_logger::EventRecord;
+use crate::text::truncate_with_ellipsis;
+use ralph_proto::{Event, HatId};
+use serde::Deserialize;
+
+/// Topic of the event recording a routing decision.
+pub const ROUTER_DECISION_TOPIC: &str = "router.decision";
+
+/// Characters of each history payload shown to the router.
+const HISTORY_PAYLOAD_CHARS: usize = 200;
+
+/// Characters of the run summary shown to the router (its end is kept).
+const SUMMARY_CHARS: usize = 2000;
+
+/// A hat the router may choose.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouterCandidate {
+    pub hat: HatId,
+    /// What the hat is for, from its config.
+    pub description: String,
+    /// Topics of the hat's pending events.
+    pub topics: Vec<String>,
+}
+
+/// The router's choice and why it made it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouterDecision {
+    pub hat: HatId,
+    pub rationale: String,
+}
+
+#[derive(Deserialize)]
+struct RawDecision {
+    hat: String,
+    #[serde(default)]
+    rationale: String,
+}
+
+impl RouterDecision {
+    /// Reads the decision from the router's response: a JSON object
+    /// `{"hat": ..., "rationale": ...}`, possibly surrounded by other text.
+    ///
+    /// Returns `None` if there is no such object or it names a hat that
+    /// isn't a candidate.
+    pub fn parse(output: &str, candidates: &[RouterCandidate]) -> Option<Self> {
+        let start = output.find('{')?;
+        let end = output.rfind('}')?;
+        let raw: RawDecision = serde_json::from_str(output.get(start..=end)?).ok()?;
+        let candidate = candidates.iter().find(|c| c.hat.as_str() == raw.hat)?;
+        Some(Self {
+            hat: candidate.hat.clone(),
+            rationale: raw.rationale.trim().to_string(),
+        })
+    }
+
+    /// The `router.decision` event recording this choice.
+    pub fn to_event(&self) -> Event {
+        Event::new(
+            ROUTER_DECISION_TOPIC,
+            format!("hat: {}\nrationale: {}", self.hat, self.rationale),
+        )
+    }
+}
+
+/// Builds the classification prompt.
+pub(crate) fn router_prompt(
+    candidates: &[RouterCandidate],
+    summary: &str,
+    history: &[EventRecord],
+) -> String {
+    let mut prompt = String::from(
+        "You are the router of a multi-agent loop. Several hats (agent roles) have \
+         pending work; choose the ONE that should work next.\n\n## Candidates\n\n",
+    );
+    for candidate in candidates {
+        prompt.push_str(&format!(
+            "- `{}`: {} (pending: {})\n",
+            candidate.hat,
+            candidate.description,
+            candidate.topics.join(", ")
+        ));
+    }
+
+    let summary = summary.trim();
+    if !summary.is_empty() {
+        let start = summary
+            .char_indices()
+            .rev()
+            .nth(SUMMARY_CHARS)
+            .map_or(0, |(i, _)| i);
+        prompt.push_str("\n## Run summary\n\n");
+        prompt.push_str(&summary[start..]);
+        prompt.push('\n');
+    }
+
+    if !history.is_empty() {
+        prompt.push_str("\n## Recent events (oldest first)\n\n");
+        for record in history {
+            prompt.push_str(&format!(
+                "- [{}] {}: {}\n",
+                record.iteration,
+                record.topic,
+                truncate_with_ellipsis(&record.payload.replace('\n', " "), HISTORY_PAYLOAD_CHARS)
+            ));
+        }
+    }
+
+    prompt.push_str(
+        "\n## Answer\n\nReply with only a JSON object: \
+         {\"hat\": \"<candidate id>\", \"rationale\": \"<one sentence>\"}\n",
+    );
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> Vec<RouterCandidate> {
+        vec![
+            RouterCandidate {
+                hat: HatId::new("builder"),
+                description: "Implements tasks".to_string(),
+                topics: vec!["build.task".to_string()],
+            },
+            RouterCandidate {
+                hat: HatId::new("reviewer"),
+                description: "Reviews changes".to_string(),
+                topics: vec!["review.request".to_string()],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_parse_decision_from_json_in_text() {
+        let output =
+            "Sure.\n```json\n{\"hat\": \"reviewer\", \"rationale\": \" Review first. \"}\n```";
+        assert_eq!(
+            RouterDecision::parse(output, &candidates()),
+            Some(RouterDecision {
+                hat: HatId::new("reviewer"),
+                rationale: "Review first.".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_hat_and_garbage() {
+        assert_eq!(
+            RouterDecision::parse("{\"hat\": \"planner\"}", &candidates()),
+            None
+        );
+        assert_eq!(RouterDecision::parse("builder", &candidates()), None);
+    }
+
+    #[test]
+    fn test_prompt_lists_candidates_summary_and_history() {
+        let event = Event::new("build.done", "tests: pass\nlint: pass");
+        let history = [EventRecord::new(3, "builder", &event, None::<&HatId>)];
+        let prompt = router_prompt(&candidates(), "Auth module is done.", &history);
+        assert!(prompt.contains("- `builder`: Implements tasks (pending: build.task)"));
+        assert!(prompt.contains("Auth module is done."));
+        assert!(prompt.contains("- [3] build.done: tests: pass lint: pass"));
+    }
+}
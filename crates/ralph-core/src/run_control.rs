@@ -0,0 +1,271 @@
+//! Controller and spectator roles for a running loop.
+//!
+//! Any number of clients may watch a run: they only read the run log and
+//! events file. Pause, resume and abort are reserved for a single controller,
+//! whose name is recorded in `.ralph/control.json`. The controller can hand
+//! the role to another client, and a client can take it over with `force`.
+//!
+//! Commands are queued in the same file. The loop drains them with
+//! [`RunControl::take_pending`] and applies them; the file also records
+//! whether the loop is currently paused so `ralph control status` can show it.
+
+use crate::file_lock::FileLock;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A command the controller can send to the loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Hold the loop before its next iteration.
+    Pause,
+    /// Continue a paused loop.
+    Resume,
+    /// Stop the loop as if interrupted.
+    Abort,
+}
+
+/// Contents of the control file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlState {
+    /// Client currently allowed to send commands.
+    #[serde(default)]
+    pub controller: Option<String>,
+
+    /// Whether the loop is paused by the controller.
+    #[serde(default)]
+    pub paused: bool,
+
+    /// Commands not yet picked up by the loop.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending: Vec<ControlCommand>,
+}
+
+/// Errors from run control operations.
+#[derive(Debug, thiserror::Error)]
+pub enum ControlError {
+    /// The client is not the run's controller.
+    #[error("run is controlled by '{0}'")]
+    NotController(String),
+
+    /// No client holds the controller role.
+    #[error("run has no controller; take control first")]
+    NoController,
+
+    /// Reading or writing the control file failed.
+    #[error("control file error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The control file is not valid JSON.
+    #[error("invalid control file: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// Access to a run's control file.
+#[derive(Debug, Clone)]
+pub struct RunControl {
+    path: PathBuf,
+}
+
+impl RunControl {
+    /// Creates a handle for the control file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the control file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the current state. A missing file means no controller.
+    pub fn state(&self) -> Result<ControlState, ControlError> {
+        let lock = FileLock::new(&self.path)?;
+        let _guard = lock.shared()?;
+        parse(&read_if_exists(&self.path)?)
+    }
+
+    /// Starts a fresh control state for a new loop process.
+    pub fn reset(&self, controller: Option<&str>) -> Result<(), ControlError> {
+        self.update(|state| {
+            *state = ControlState {
+                controller: controller.map(str::to_string),
+                ..ControlState::default()
+            };
+            Ok(())
+        })
+    }
+
+    /// Makes `client` the controller.
+    ///
+    /// Fails if another client holds the role, unless `force` is set.
+    pub fn claim(&self, client: &str, force: bool) -> Result<(), ControlError> {
+        self.update(|state| {
+            if let Some(holder) = &state.controller
+                && holder != client
+                && !force
+            {
+                return Err(ControlError::NotController(holder.clone()));
+            }
+            state.controller = Some(client.to_string());
+            Ok(())
+        })
+    }
+
+    /// Passes the controller role from `from` to `to`.
+    pub fn hand_over(&self, from: &str, to: &str) -> Result<(), ControlError> {
+        self.update(|state| {
+            check_controller(state, from)?;
+            state.controller = Some(to.to_string());
+            Ok(())
+        })
+    }
+
+    /// Gives up the controller role, leaving the run without one.
+    pub fn release(&self, client: &str) -> Result<(), ControlError> {
+        self.update(|state| {
+            check_controller(state, client)?;
+            state.controller = None;
+            Ok(())
+        })
+    }
+
+    /// Queues a command from `client`, who must be the controller.
+    pub fn send(&self, client: &str, command: ControlCommand) -> Result<(), ControlError> {
+        self.update(|state| {
+            check_controller(state, client)?;
+            state.pending.push(command);
+            Ok(())
+        })
+    }
+
+    /// Removes and returns queued commands, updating the paused flag to
+    /// match. Called by the loop.
+    pub fn take_pending(&self) -> Result<Vec<ControlCommand>, ControlError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut taken = Vec::new();
+        self.update(|state| {
+            taken = std::mem::take(&mut state.pending);
+            for command in &taken {
+                match command {
+                    ControlCommand::Pause => state.paused = true,
+                    ControlCommand::Resume | ControlCommand::Abort => state.paused = false,
+                }
+            }
+            Ok(())
+        })?;
+        Ok(taken)
+    }
+
+    /// Applies `f` to the state under an exclusive lock and writes it back.
+    fn update<F>(&self, f: F) -> Result<(), ControlError>
+    where
+        F: FnOnce(&mut ControlState) -> Result<(), ControlError>,
+    {
+        let lock = FileLock::new(&self.path)?;
+        let _guard = lock.exclusive()?;
+        let mut state = parse(&read_if_exists(&self.path)?)?;
+        f(&mut state)?;
+        std::fs::write(&self.path, serde_json::to_string_pretty(&state)?)?;
+        Ok(())
+    }
+}
+
+fn read_if_exists(path: &Path) -> io::Result<String> {
+    match std::fs::read_to_string(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        result => result,
+    }
+}
+
+fn parse(content: &str) -> Result<ControlState, ControlError> {
+    if content.trim().is_empty() {
+        return Ok(ControlState::default());
+    }
+    Ok(serde_json::from_str(content)?)
+}
+
+fn check_controller(state: &ControlState, client: &str) -> Result<(), ControlError> {
+    match &state.controller {
+        Some(holder) if holder == client => Ok(()),
+        Some(holder) => Err(ControlError::NotController(holder.clone())),
+        None => Err(ControlError::NoController),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn control(temp: &TempDir) -> RunControl {
+        RunControl::new(temp.path().join("control.json"))
+    }
+
+    #[test]
+    fn test_missing_file_has_no_controller() {
+        let temp = TempDir::new().unwrap();
+        let control = control(&temp);
+
+        assert_eq!(control.state().unwrap(), ControlState::default());
+        assert!(control.take_pending().unwrap().is_empty());
+        assert!(matches!(
+            control.send("alice", ControlCommand::Pause),
+            Err(ControlError::NoController)
+        ));
+    }
+
+    #[test]
+    fn test_only_one_controller_at_a_time() {
+        let temp = TempDir::new().unwrap();
+        let control = control(&temp);
+        control.reset(Some("tui")).unwrap();
+
+        assert!(matches!(
+            control.claim("alice", false),
+            Err(ControlError::NotController(holder)) if holder == "tui"
+        ));
+        assert!(matches!(
+            control.send("alice", ControlCommand::Abort),
+            Err(ControlError::NotController(_))
+        ));
+
+        control.claim("alice", true).unwrap();
+        assert_eq!(
+            control.state().unwrap().controller.as_deref(),
+            Some("alice")
+        );
+    }
+
+    #[test]
+    fn test_hand_over_and_release() {
+        let temp = TempDir::new().unwrap();
+        let control = control(&temp);
+        control.claim("alice", false).unwrap();
+
+        assert!(control.hand_over("bob", "carol").is_err());
+        control.hand_over("alice", "bob").unwrap();
+        assert!(control.release("alice").is_err());
+        control.release("bob").unwrap();
+        assert_eq!(control.state().unwrap().controller, None);
+    }
+
+    #[test]
+    fn test_commands_are_drained_and_track_pause() {
+        let temp = TempDir::new().unwrap();
+        let control = control(&temp);
+        control.reset(Some("alice")).unwrap();
+
+        control.send("alice", ControlCommand::Pause).unwrap();
+        assert_eq!(control.take_pending().unwrap(), vec![ControlCommand::Pause]);
+        assert!(control.state().unwrap().paused);
+        assert!(control.take_pending().unwrap().is_empty());
+
+        control.send("alice", ControlCommand::Resume).unwrap();
+        control.take_pending().unwrap();
+        assert!(!control.state().unwrap().paused);
+    }
+}
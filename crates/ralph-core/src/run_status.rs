@@ -0,0 +1,171 @@
+//! Progress of the current run, persisted for cheap polling.
+//!
+//! The loop rewrites `.ralph/run-status.json` at the start and end of every
+//! iteration. Shell prompts, tmux status lines and `ralph status` read that
+//! one small file instead of talking to the loop process, so they can poll
+//! it as often as they like. The file is replaced atomically, so readers
+//! never see a partial write.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+/// Whether the run is still going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunPhase {
+    /// The loop is executing or between iterations.
+    Running,
+    /// The loop terminated; see `termination_reason`.
+    Finished,
+}
+
+/// Contents of the run status file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunStatus {
+    /// Run ID (e.g. `20260127-143022`), if the run has an events file.
+    pub run_id: Option<String>,
+    /// Process running the loop.
+    pub pid: u32,
+    /// When the loop started.
+    pub started: DateTime<Utc>,
+    /// When this status was written.
+    pub updated: DateTime<Utc>,
+    pub phase: RunPhase,
+    /// Current iteration, 1-indexed; 0 before the first one starts.
+    pub iteration: u32,
+    pub max_iterations: u32,
+    /// Spend so far.
+    pub cost_usd: f64,
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+    /// Hat running the current (or last) iteration.
+    #[serde(default)]
+    pub active_hat: Option<String>,
+    /// Why the loop stopped, once finished.
+    #[serde(default)]
+    pub termination_reason: Option<String>,
+    /// Concurrency group the next iteration is waiting for a slot in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queued_for: Option<String>,
+}
+
+impl RunStatus {
+    /// Creates the status of a loop starting now in this process.
+    pub fn new(run_id: Option<String>, max_iterations: u32, max_cost_usd: Option<f64>) -> Self {
+        let now = Utc::now();
+        Self {
+            run_id,
+            pid: std::process::id(),
+            started: now,
+            updated: now,
+            phase: RunPhase::Running,
+            iteration: 0,
+            max_iterations,
+            cost_usd: 0.0,
+            max_cost_usd,
+            active_hat: None,
+            termination_reason: None,
+            queued_for: None,
+        }
+    }
+
+    /// Reads the status file. A missing file means no run has written one.
+    pub fn read(path: &Path) -> io::Result<Option<Self>> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Stamps the status with the current time and replaces the file.
+    pub fn write(&mut self, path: &Path) -> io::Result<()> {
+        self.updated = Utc::now();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Time since the loop started, or its total runtime once finished.
+    pub fn elapsed(&self, now: DateTime<Utc>) -> chrono::Duration {
+        let end = match self.phase {
+            RunPhase::Running => now,
+            RunPhase::Finished => self.updated,
+        };
+        end - self.started
+    }
+
+    /// Whether the loop is running, i.e. not finished and its process exists.
+    ///
+    /// A loop killed with SIGKILL never marks itself finished, so the process
+    /// is checked as well.
+    pub fn is_running(&self) -> bool {
+        self.phase == RunPhase::Running && process_exists(self.pid)
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn process_exists(pid: u32) -> bool {
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    // Signal 0 (None) checks if process exists without sending a signal
+    i32::try_from(pid).is_ok_and(|pid| kill(Pid::from_raw(pid), None).is_ok())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn process_exists(_pid: u32) -> bool {
+    // On non-Unix platforms, assume alive (conservative)
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_and_read_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(".ralph/run-status.json");
+        assert_eq!(RunStatus::read(&path).unwrap(), None);
+
+        let mut status = RunStatus::new(Some("20260127-143022".to_string()), 100, Some(5.0));
+        status.iteration = 3;
+        status.active_hat = Some("builder".to_string());
+        status.write(&path).unwrap();
+
+        assert_eq!(RunStatus::read(&path).unwrap(), Some(status.clone()));
+        assert!(status.is_running());
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn test_finished_run_is_not_running() {
+        let mut status = RunStatus::new(None, 10, None);
+        status.phase = RunPhase::Finished;
+        assert!(!status.is_running());
+
+        // A finished run's elapsed time stops at its last update.
+        let later = status.updated + chrono::Duration::hours(1);
+        assert_eq!(status.elapsed(later), status.updated - status.started);
+    }
+
+    #[test]
+    fn test_invalid_file_is_an_error() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("run-status.json");
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(
+            RunStatus::read(&path).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
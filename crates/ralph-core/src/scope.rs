@@ -0,0 +1,316 @@
+//! Scoping a run to some packages of a monorepo.
+//!
+//! With `scope.packages` set, three things follow from the package list:
+//! the prompt names the packages the agent should read and change, each
+//! package's manifest determines the test command that gates it (see
+//! `gates`), and `build.done` is rejected while files outside the packages
+//! have changed since the run started.
+
+use crate::config::ScopeConfig;
+use std::path::Path;
+use std::process::Command;
+
+/// Paths every run may change regardless of scope (Ralph's own state).
+const ALWAYS_ALLOWED: &[&str] = &[".ralph", ".agent"];
+
+/// The kind of package, from the manifest found in its directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageKind {
+    /// `Cargo.toml` with a `[package]` name.
+    Cargo { name: String },
+    /// `package.json`.
+    Npm,
+    /// `pyproject.toml` or `setup.py`.
+    Python,
+    /// `go.mod`.
+    Go,
+    /// No manifest Ralph knows about.
+    Unknown,
+}
+
+impl PackageKind {
+    /// Detects the package kind from the manifests in `dir`.
+    pub fn detect(dir: &Path) -> Self {
+        if let Ok(manifest) = std::fs::read_to_string(dir.join("Cargo.toml"))
+            && let Some(name) = cargo_package_name(&manifest)
+        {
+            return Self::Cargo { name };
+        }
+        if dir.join("package.json").is_file() {
+            Self::Npm
+        } else if dir.join("pyproject.toml").is_file() || dir.join("setup.py").is_file() {
+            Self::Python
+        } else if dir.join("go.mod").is_file() {
+            Self::Go
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// The command that tests this package, run from the workspace root.
+    /// `path` is the package directory relative to the root.
+    pub fn test_command(&self, path: &str) -> Option<String> {
+        match self {
+            Self::Cargo { name } => Some(format!("cargo test -p {name}")),
+            Self::Npm => Some(format!("npm test --workspace={path}")),
+            Self::Python => Some(format!("pytest {path}")),
+            Self::Go => Some(format!("go test ./{path}/...")),
+            Self::Unknown => None,
+        }
+    }
+}
+
+/// Reads `name` from the `[package]` table of a `Cargo.toml`.
+fn cargo_package_name(manifest: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim() == "name"
+        {
+            return Some(value.trim().trim_matches('"').to_string());
+        }
+    }
+    None
+}
+
+/// One package of the scope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopedPackage {
+    /// Directory relative to the workspace root, without trailing slash.
+    pub path: String,
+    pub kind: PackageKind,
+}
+
+/// The packages a run is limited to. An empty scope covers everything.
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    packages: Vec<ScopedPackage>,
+    allow: Vec<String>,
+}
+
+impl Scope {
+    /// Resolves the configured packages under `workspace_root`.
+    pub fn from_config(config: &ScopeConfig, workspace_root: &Path) -> Self {
+        let packages = config
+            .packages
+            .iter()
+            .map(|path| {
+                let path = normalize(path);
+                ScopedPackage {
+                    kind: PackageKind::detect(&workspace_root.join(&path)),
+                    path,
+                }
+            })
+            .collect();
+        Self {
+            packages,
+            allow: config.allow.iter().map(|path| normalize(path)).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    pub fn packages(&self) -> &[ScopedPackage] {
+        &self.packages
+    }
+
+    /// Test commands covering the scoped packages, one per package with a
+    /// known manifest.
+    pub fn test_commands(&self) -> Vec<String> {
+        self.packages
+            .iter()
+            .filter_map(|package| package.kind.test_command(&package.path))
+            .collect()
+    }
+
+    /// Whether the run may change `file` (relative to the workspace root).
+    pub fn contains(&self, file: &str) -> bool {
+        if self.packages.is_empty() {
+            return true;
+        }
+        self.packages
+            .iter()
+            .map(|package| package.path.as_str())
+            .chain(self.allow.iter().map(String::as_str))
+            .chain(ALWAYS_ALLOWED.iter().copied())
+            .any(|prefix| is_under(file, prefix))
+    }
+
+    /// Files changed since `base` (or uncommitted, without a base) that lie
+    /// outside the scope.
+    pub fn changes_outside(&self, workspace_root: &Path, base: Option<&str>) -> Vec<String> {
+        if self.packages.is_empty() {
+            return Vec::new();
+        }
+        let mut changed = git_lines(
+            workspace_root,
+            &["diff", "--name-only", base.unwrap_or("HEAD")],
+        );
+        changed.extend(git_lines(
+            workspace_root,
+            &["ls-files", "--others", "--exclude-standard"],
+        ));
+        changed.sort();
+        changed.dedup();
+        changed.retain(|file| !self.contains(file));
+        changed
+    }
+
+    /// Builds the `<scope>` prompt section, or an empty string for an empty
+    /// scope.
+    pub fn prompt_section(&self) -> String {
+        if self.packages.is_empty() {
+            return String::new();
+        }
+
+        let mut section = String::from("<scope>\n");
+        section.push_str(
+            "This run is limited to the packages below. Read other code only when \
+             a scoped package depends on it, and change files only inside these \
+             packages",
+        );
+        if self.allow.is_empty() {
+            section.push_str(".\n\n");
+        } else {
+            section.push_str(&format!(" and {}.\n\n", self.allow.join(", ")));
+        }
+        for package in &self.packages {
+            section.push_str(&format!("- `{}/`\n", package.path));
+        }
+        section.push_str("</scope>\n\n");
+        section
+    }
+}
+
+fn normalize(path: &str) -> String {
+    path.trim()
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn is_under(file: &str, prefix: &str) -> bool {
+    file.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+fn git_lines(dir: &Path, args: &[&str]) -> Vec<String> {
+    Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+    }
+
+    fn monorepo() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("crates/core")).unwrap();
+        fs::write(
+            root.join("crates/core/Cargo.toml"),
+            "[package]\nname = \"acme-core\"\nversion = \"0.1.0\"\n\n[dependencies]\nname = \"x\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::write(root.join("web/package.json"), "{}").unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        temp
+    }
+
+    fn scope(root: &Path, packages: &[&str]) -> Scope {
+        Scope::from_config(
+            &ScopeConfig {
+                packages: packages.iter().map(ToString::to_string).collect(),
+                allow: vec!["Cargo.lock".to_string()],
+            },
+            root,
+        )
+    }
+
+    #[test]
+    fn test_packages_derive_test_commands() {
+        let temp = monorepo();
+        let scope = scope(temp.path(), &["./crates/core/", "web", "docs"]);
+
+        assert_eq!(
+            scope.packages()[0].kind,
+            PackageKind::Cargo {
+                name: "acme-core".to_string()
+            }
+        );
+        assert_eq!(scope.packages()[2].kind, PackageKind::Unknown);
+        assert_eq!(
+            scope.test_commands(),
+            vec!["cargo test -p acme-core", "npm test --workspace=web"]
+        );
+
+        let section = scope.prompt_section();
+        assert!(section.contains("packages and Cargo.lock.\n\n- `crates/core/`\n"));
+    }
+
+    #[test]
+    fn test_contains_matches_whole_path_components() {
+        let temp = monorepo();
+        let scope = scope(temp.path(), &["crates/core"]);
+
+        assert!(scope.contains("crates/core/src/lib.rs"));
+        assert!(scope.contains("Cargo.lock"));
+        assert!(scope.contains(".ralph/agent/scratchpad.md"));
+        assert!(!scope.contains("crates/core-macros/src/lib.rs"));
+        assert!(!scope.contains("web/index.ts"));
+        assert!(Scope::default().contains("anything"));
+    }
+
+    #[test]
+    fn test_changes_outside_since_base() {
+        let temp = monorepo();
+        let root = temp.path();
+        git(root, &["init", "--initial-branch=main"]);
+        git(root, &["config", "user.email", "test@test.local"]);
+        git(root, &["config", "user.name", "Test User"]);
+        git(root, &["add", "."]);
+        git(root, &["commit", "-m", "Initial commit"]);
+        let base = crate::git_ops::get_head_sha(root).unwrap();
+
+        // A committed change outside scope, and uncommitted ones in and out.
+        fs::write(root.join("web/index.ts"), "").unwrap();
+        git(root, &["add", "web/index.ts"]);
+        git(root, &["commit", "-m", "Touch web"]);
+        fs::write(root.join("crates/core/lib.rs"), "").unwrap();
+        fs::write(root.join("docs/notes.md"), "").unwrap();
+
+        let scope = scope(root, &["crates/core"]);
+        assert_eq!(
+            scope.changes_outside(root, Some(&base)),
+            vec!["docs/notes.md", "web/index.ts"]
+        );
+        assert_eq!(scope.changes_outside(root, None), vec!["docs/notes.md"]);
+    }
+}
@@ -4,6 +4,7 @@
 //! and UX captures (terminal output) into a unified JSONL format for replay
 //! and analysis.
 
+use crate::EnvironmentSnapshot;
 use ralph_proto::{Event, UxEvent};
 use serde::{Deserialize, Serialize};
 use std::io::{self, Write};
@@ -82,6 +83,17 @@ impl Record {
         )
     }
 
+    /// Creates a metadata record for the environment an iteration started in.
+    pub fn meta_environment(iteration: u32, snapshot: &EnvironmentSnapshot) -> Self {
+        Self::new(
+            "_meta.environment",
+            serde_json::json!({
+                "n": iteration,
+                "snapshot": snapshot,
+            }),
+        )
+    }
+
     /// Creates a metadata record for termination.
     pub fn meta_termination(
         reason: &str,
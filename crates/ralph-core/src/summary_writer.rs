@@ -3,6 +3,7 @@
 //! Per spec: "On termination, the orchestrator writes `.ralph/agent/summary.md`"
 //! with status, iterations, duration, task list, events summary, and commit info.
 
+use crate::audit_log::AuditLog;
 use crate::event_logger::EventHistory;
 use crate::event_loop::{LoopState, TerminationReason};
 use crate::landing::LandingResult;
@@ -43,6 +44,8 @@ pub struct SummaryWriter {
     /// Path to the events file for reading history.
     /// If None, uses the default path relative to current directory.
     events_path: Option<PathBuf>,
+    /// Audit log whose entries for this run are listed in the summary.
+    audit_log: Option<AuditLog>,
 }
 
 impl Default for SummaryWriter {
@@ -57,6 +60,7 @@ impl SummaryWriter {
         Self {
             path: path.into(),
             events_path: None,
+            audit_log: None,
         }
     }
 
@@ -69,9 +73,22 @@ impl SummaryWriter {
         Self {
             path: context.summary_path(),
             events_path: Some(context.events_path()),
+            audit_log: Some(context.audit_log()),
         }
     }
 
+    /// Lists the run's control actions from `audit_log` in the summary.
+    #[must_use]
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Returns the summary file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     /// Writes the summary file based on loop state and termination reason.
     ///
     /// This is called by the orchestrator when the loop terminates.
@@ -153,6 +170,22 @@ impl SummaryWriter {
         content.push_str("## Events\n\n");
         content.push_str(&self.summarize_events());
 
+        // Cost anomalies section (only when an iteration was flagged)
+        if !state.cost_anomalies.is_empty() {
+            content.push('\n');
+            content.push_str("## Cost Anomalies\n\n");
+            for anomaly in &state.cost_anomalies {
+                content.push_str(&format!("- {anomaly}\n"));
+            }
+        }
+
+        // Control actions section (only when someone intervened)
+        if let Some(actions) = self.summarize_audit() {
+            content.push('\n');
+            content.push_str("## Control Actions\n\n");
+            content.push_str(&actions);
+        }
+
         // Final commit section
         if let Some(commit) = final_commit {
             content.push('\n');
@@ -202,6 +235,18 @@ impl SummaryWriter {
                     "No"
                 }
             ));
+
+            for checkpoint in &landing_result.repo_checkpoints {
+                let outcome = match &checkpoint.result {
+                    Ok(result) if result.committed => format!(
+                        "committed `{}`",
+                        result.commit_sha.as_deref().unwrap_or("unknown")
+                    ),
+                    Ok(_) => "no changes".to_string(),
+                    Err(e) => format!("not committed ({})", e.trim()),
+                };
+                content.push_str(&format!("- **Repo `{}`:** {}\n", checkpoint.repo, outcome));
+            }
         }
 
         content
@@ -209,20 +254,7 @@ impl SummaryWriter {
 
     /// Returns a human-readable status based on termination reason.
     fn status_text(&self, reason: &TerminationReason) -> &'static str {
-        match reason {
-            TerminationReason::CompletionPromise => "Completed successfully",
-            TerminationReason::MaxIterations => "Stopped: max iterations reached",
-            TerminationReason::MaxRuntime => "Stopped: max runtime exceeded",
-            TerminationReason::MaxCost => "Stopped: max cost exceeded",
-            TerminationReason::ConsecutiveFailures => "Failed: too many consecutive failures",
-            TerminationReason::LoopThrashing => "Failed: loop thrashing detected",
-            TerminationReason::ValidationFailure => "Failed: too many malformed JSONL events",
-            TerminationReason::Stopped => "Stopped manually",
-            TerminationReason::Interrupted => "Interrupted by signal",
-            TerminationReason::ChaosModeComplete => "Chaos mode: exploration complete",
-            TerminationReason::ChaosModeMaxIterations => "Chaos mode: max iterations reached",
-            TerminationReason::RestartRequested => "Restarting by human request",
-        }
+        status_text(reason)
     }
 
     /// Extracts task lines from the scratchpad file.
@@ -280,10 +312,54 @@ impl SummaryWriter {
 
         summary
     }
+
+    /// Lists the audit entries recorded for this run, one per line.
+    fn summarize_audit(&self) -> Option<String> {
+        let entries = self.audit_log.as_ref()?.run_entries().ok()?;
+        if entries.is_empty() {
+            return None;
+        }
+
+        let mut summary = String::new();
+        for entry in entries {
+            summary.push_str(&format!(
+                "- {} {} via {}",
+                entry.ts,
+                entry.action.as_str(),
+                entry.source.as_str()
+            ));
+            if let Some(actor) = &entry.actor {
+                summary.push_str(&format!(" by {actor}"));
+            }
+            if let Some(detail) = &entry.detail {
+                summary.push_str(&format!(": {detail}"));
+            }
+            summary.push('\n');
+        }
+        Some(summary)
+    }
+}
+
+/// Returns a human-readable status based on termination reason.
+pub(crate) fn status_text(reason: &TerminationReason) -> &'static str {
+    match reason {
+        TerminationReason::CompletionPromise => "Completed successfully",
+        TerminationReason::MaxIterations => "Stopped: max iterations reached",
+        TerminationReason::MaxRuntime => "Stopped: max runtime exceeded",
+        TerminationReason::MaxCost => "Stopped: max cost exceeded",
+        TerminationReason::ConsecutiveFailures => "Failed: too many consecutive failures",
+        TerminationReason::LoopThrashing => "Failed: loop thrashing detected",
+        TerminationReason::ValidationFailure => "Failed: too many malformed JSONL events",
+        TerminationReason::Stopped => "Stopped manually",
+        TerminationReason::Interrupted => "Interrupted by signal",
+        TerminationReason::ChaosModeComplete => "Chaos mode: exploration complete",
+        TerminationReason::ChaosModeMaxIterations => "Chaos mode: max iterations reached",
+        TerminationReason::RestartRequested => "Restarting by human request",
+    }
 }
 
 /// Formats a duration as human-readable string (e.g., "23m 45s" or "1h 5m 30s").
-fn format_duration(d: Duration) -> String {
+pub(crate) fn format_duration(d: Duration) -> String {
     let total_secs = d.as_secs();
     let hours = total_secs / 3600;
     let minutes = (total_secs % 3600) / 60;
@@ -320,6 +396,7 @@ mod tests {
             hat_activation_counts: std::collections::HashMap::new(),
             exhausted_hats: std::collections::HashSet::new(),
             last_checkin_at: None,
+            cost_anomalies: Vec::new(),
         }
     }
 
@@ -434,6 +511,10 @@ More text here.
             open_tasks: vec!["task-1".to_string(), "task-2".to_string()],
             stashes_cleared: 2,
             working_tree_clean: true,
+            repo_checkpoints: vec![crate::repos::RepoCheckpoint {
+                repo: "client".to_string(),
+                result: Ok(crate::git_ops::AutoCommitResult::no_commit()),
+            }],
         };
 
         writer
@@ -453,5 +534,74 @@ More text here.
         assert!(content.contains("**Open tasks:** 2"));
         assert!(content.contains("**Stashes cleared:** 2"));
         assert!(content.contains("**Working tree clean:** Yes"));
+        assert!(content.contains("**Repo `client`:** no changes"));
+    }
+
+    #[test]
+    fn test_summary_lists_control_actions_for_run() {
+        use crate::audit_log::{AuditAction, AuditEntry, AuditSource};
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("summary.md");
+        let audit_path = tmp.path().join("audit.jsonl");
+        AuditLog::new(&audit_path)
+            .with_run_id(Some("other".to_string()))
+            .record(AuditEntry::new(AuditAction::Abort, AuditSource::Signal))
+            .unwrap();
+        let audit = AuditLog::new(&audit_path).with_run_id(Some("run".to_string()));
+        audit
+            .record(AuditEntry::new(AuditAction::Pause, AuditSource::Alert).with_detail("panic"))
+            .unwrap();
+        audit
+            .record(AuditEntry::new(AuditAction::Resume, AuditSource::Cli).with_actor("alice"))
+            .unwrap();
+
+        let writer = SummaryWriter::new(&path).with_audit_log(audit);
+        writer
+            .write(&TerminationReason::Stopped, &test_state(), None, None)
+            .unwrap();
+
+        let content = fs::read_to_string(path).unwrap();
+        assert!(content.contains("## Control Actions"));
+        assert!(content.contains("pause via alert: panic"));
+        assert!(content.contains("resume via cli by alice"));
+        assert!(!content.contains("abort"));
+    }
+
+    #[test]
+    fn test_cost_anomalies_listed() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("summary.md");
+        let mut state = test_state();
+        state.cost_anomalies.push(crate::CostAnomaly {
+            iteration: 7,
+            hat: "builder".to_string(),
+            metric: crate::CostMetric::Tokens,
+            value: 48_000.0,
+            median: 12_000.0,
+        });
+
+        let writer = SummaryWriter::new(&path);
+        writer
+            .write(&TerminationReason::Stopped, &state, None, None)
+            .unwrap();
+
+        let content = fs::read_to_string(path).unwrap();
+        assert!(content.contains("## Cost Anomalies"));
+        assert!(content.contains(
+            "- iteration 7 (builder) used 48.0k tokens, 4.0× the median of 12.0k tokens"
+        ));
+        // Without anomalies the section is left out
+        assert!(
+            !SummaryWriter::new(temp_dir.path().join("other.md"))
+                .generate_content_with_landing(
+                    &TerminationReason::Stopped,
+                    &test_state(),
+                    None,
+                    None,
+                    None
+                )
+                .contains("Cost Anomalies")
+        );
     }
 }
@@ -0,0 +1,152 @@
+//! Token-per-minute throttling of iterations.
+//!
+//! Organizations often cap how many tokens a key may use per minute, and a
+//! loop that starts iterations back to back can run into that cap (or burn
+//! through a budget faster than intended). With
+//! `adapters.<backend>.tokens_per_minute` set, the loop records what each
+//! iteration used and holds the next one on that backend until the last
+//! minute's usage is back under the limit.
+
+use crate::clock::{Clock, SystemClock};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Width of the sliding window the limit applies to.
+const WINDOW: Duration = Duration::from_mins(1);
+
+/// Rough token count for backends that don't report usage (~4 characters
+/// per token).
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// Sliding one-minute token usage per backend.
+#[derive(Debug)]
+pub struct TokenThrottle {
+    clock: Arc<dyn Clock>,
+    usage: HashMap<String, VecDeque<(Instant, u64)>>,
+}
+
+impl Default for TokenThrottle {
+    fn default() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+}
+
+impl TokenThrottle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A throttle that reads the time from `clock`.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            usage: HashMap::new(),
+        }
+    }
+
+    /// Records `tokens` used on `backend` by an iteration that just ended.
+    pub fn record(&mut self, backend: &str, tokens: u64) {
+        if tokens > 0 {
+            let now = self.clock.now();
+            self.usage
+                .entry(backend.to_string())
+                .or_default()
+                .push_back((now, tokens));
+        }
+    }
+
+    /// Tokens used on `backend` in the last minute.
+    pub fn used(&mut self, backend: &str) -> u64 {
+        self.prune(backend)
+            .map_or(0, |usage| usage.iter().map(|(_, tokens)| tokens).sum())
+    }
+
+    /// How long to wait before `backend` is under `tokens_per_minute` again,
+    /// or `None` if an iteration can start now.
+    pub fn wait_for(&mut self, backend: &str, tokens_per_minute: u64) -> Option<Duration> {
+        let now = self.clock.now();
+        let usage = self.prune(backend)?;
+        let mut used: u64 = usage.iter().map(|(_, tokens)| tokens).sum();
+        for (at, tokens) in usage {
+            if used < tokens_per_minute {
+                break;
+            }
+            used -= tokens;
+            if used < tokens_per_minute {
+                return Some((*at + WINDOW).saturating_duration_since(now));
+            }
+        }
+        None
+    }
+
+    /// Drops usage older than the window and returns what is left.
+    fn prune(&mut self, backend: &str) -> Option<&VecDeque<(Instant, u64)>> {
+        let now = self.clock.now();
+        let usage = self.usage.get_mut(backend)?;
+        while usage
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) >= WINDOW)
+        {
+            usage.pop_front();
+        }
+        Some(usage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn throttle() -> (TokenThrottle, MockClock) {
+        let clock = MockClock::new();
+        (TokenThrottle::with_clock(Arc::new(clock.clone())), clock)
+    }
+
+    #[test]
+    fn test_under_limit_starts_immediately() {
+        let (mut throttle, _clock) = throttle();
+        assert_eq!(throttle.wait_for("claude", 1000), None);
+
+        throttle.record("claude", 400);
+        assert_eq!(throttle.wait_for("claude", 1000), None);
+        assert_eq!(throttle.used("claude"), 400);
+    }
+
+    #[test]
+    fn test_waits_until_oldest_usage_leaves_window() {
+        let (mut throttle, clock) = throttle();
+        throttle.record("claude", 600);
+        clock.advance(Duration::from_secs(20));
+        throttle.record("claude", 600);
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(
+            throttle.wait_for("claude", 1000),
+            Some(Duration::from_secs(30))
+        );
+
+        // Once the first iteration is a minute old, the second fits
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(throttle.wait_for("claude", 1000), None);
+        assert_eq!(throttle.used("claude"), 600);
+    }
+
+    #[test]
+    fn test_backends_are_throttled_separately() {
+        let (mut throttle, _clock) = throttle();
+        throttle.record("claude", 5000);
+
+        assert!(throttle.wait_for("claude", 1000).is_some());
+        assert_eq!(throttle.wait_for("gemini", 1000), None);
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+}
@@ -0,0 +1,135 @@
+//! On-disk storage for full tool results.
+//!
+//! Tool results can be arbitrarily large (file dumps, test logs), so the TUI
+//! only keeps a short preview in memory. The full text is written here, one
+//! file per tool invocation under `.ralph/tool-results/`, and loaded back
+//! when the user asks to see it.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::encryption::Cipher;
+
+/// Directory-backed store of full tool results, keyed by tool use ID.
+#[derive(Debug, Clone)]
+pub struct ToolResultStore {
+    dir: PathBuf,
+    cipher: Option<Cipher>,
+}
+
+impl ToolResultStore {
+    /// Creates a store rooted at `dir`. The directory is created on first save.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            cipher: None,
+        }
+    }
+
+    /// Seals stored results with `cipher` (see [`crate::Cipher`]).
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: Option<Cipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Returns the directory results are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes the full result for a tool invocation, replacing any previous one.
+    pub fn save(&self, tool_use_id: &str, content: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        match &self.cipher {
+            Some(cipher) => fs::write(self.path_for(tool_use_id), cipher.seal(content)),
+            None => fs::write(self.path_for(tool_use_id), content),
+        }
+    }
+
+    /// Reads the full result for a tool invocation.
+    pub fn load(&self, tool_use_id: &str) -> io::Result<String> {
+        let content = fs::read_to_string(self.path_for(tool_use_id))?;
+        match &self.cipher {
+            Some(cipher) => cipher
+                .open(&content)
+                .map(|plain| plain.into_owned())
+                .map_err(io::Error::other),
+            None => Ok(content),
+        }
+    }
+
+    /// Removes all stored results (called when a new loop starts).
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Maps a tool use ID to a file path, replacing characters that are not
+    /// safe in file names.
+    fn path_for(&self, tool_use_id: &str) -> PathBuf {
+        let name: String = tool_use_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{name}.txt"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let store = ToolResultStore::new(temp.path().join("tool-results"));
+
+        store.save("toolu_01", "line 1\nline 2").unwrap();
+        assert_eq!(store.load("toolu_01").unwrap(), "line 1\nline 2");
+        assert!(store.load("missing").is_err());
+    }
+
+    #[test]
+    fn test_ids_cannot_escape_directory() {
+        let temp = TempDir::new().unwrap();
+        let store = ToolResultStore::new(temp.path().join("tool-results"));
+
+        store.save("../../etc/passwd", "nope").unwrap();
+        assert!(store.dir().join("______etc_passwd.txt").exists());
+        assert_eq!(store.load("../../etc/passwd").unwrap(), "nope");
+    }
+
+    #[test]
+    fn test_sealed_results_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let cipher = Cipher::from_base64(&Cipher::generate_key()).unwrap();
+        let store = ToolResultStore::new(temp.path()).with_cipher(Some(cipher));
+
+        store.save("toolu_01", "line 1\nline 2").unwrap();
+        let on_disk = fs::read_to_string(temp.path().join("toolu_01.txt")).unwrap();
+        assert!(crate::is_sealed(&on_disk));
+        assert_eq!(store.load("toolu_01").unwrap(), "line 1\nline 2");
+    }
+
+    #[test]
+    fn test_clear_removes_results() {
+        let temp = TempDir::new().unwrap();
+        let store = ToolResultStore::new(temp.path().join("tool-results"));
+
+        store.clear().unwrap(); // No-op when nothing is stored yet
+        store.save("toolu_01", "output").unwrap();
+        store.clear().unwrap();
+        assert!(!store.dir().exists());
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 /// An event in the pub/sub system.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Event {
     /// The routing topic for this event.
     pub topic: Topic,
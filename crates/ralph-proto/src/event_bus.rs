@@ -56,6 +56,13 @@ impl EventBus {
         self.observers.push(Box::new(observer));
     }
 
+    /// Shows an event to the observers without routing it to any hat.
+    pub fn notify_observers(&self, event: &Event) {
+        for observer in &self.observers {
+            observer(event);
+        }
+    }
+
     /// Clears all observer callbacks.
     pub fn clear_observers(&mut self) {
         self.observers.clear();
@@ -41,6 +41,7 @@ impl std::fmt::Display for HatId {
 
 /// A hat (persona) that defines agent behavior.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Hat {
     /// Unique identifier for this hat.
     pub id: HatId,
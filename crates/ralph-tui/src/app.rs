@@ -5,8 +5,14 @@
 //! scroll, and search functionality.
 
 use crate::input::{Action, map_key};
+use crate::runs::RunList;
+use crate::settings;
 use crate::state::TuiState;
-use crate::widgets::{content::ContentPane, footer, header, help};
+use crate::theme;
+use crate::widgets::{
+    content::ContentPane, footer, header, help, result_viewer, runs as run_selector,
+    settings as settings_popup, splash,
+};
 use anyhow::Result;
 use crossterm::{
     cursor::Show,
@@ -18,6 +24,7 @@ use crossterm::{
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use futures::StreamExt;
+use ralph_core::{AlertMatcher, FooterSegment};
 use ratatui::{
     Terminal,
     backend::CrosstermBackend,
@@ -34,6 +41,26 @@ use tracing::info;
 ///
 /// Returns `true` if the action signals to quit the application.
 pub fn dispatch_action(action: Action, state: &mut TuiState, viewport_height: usize) -> bool {
+    // The result viewer captures navigation keys while it is open. It covers
+    // the content area, less its top and bottom borders.
+    if let Some(viewer) = state.result_viewer.as_mut() {
+        let height = viewport_height.saturating_sub(2);
+        match action {
+            Action::ScrollDown => viewer.scroll_down(1, height),
+            Action::ScrollUp => viewer.scroll_up(1),
+            Action::ScrollTop => viewer.scroll = 0,
+            Action::ScrollBottom => viewer.scroll_bottom(height),
+            Action::Quit
+            | Action::DismissHelp
+            | Action::OpenToolResult
+            | Action::ShowIterationInfo => {
+                state.result_viewer = None;
+            }
+            _ => {}
+        }
+        return false;
+    }
+
     match action {
         Action::Quit => return true,
         Action::ScrollDown => {
@@ -78,6 +105,24 @@ pub fn dispatch_action(action: Action, state: &mut TuiState, viewport_height: us
         Action::SearchPrev => {
             state.prev_match();
         }
+        Action::Resume => {
+            state.awaiting_start = false;
+            state.alert_pause = None;
+            state.alert_pause_at = None;
+        }
+        Action::OpenToolResult => {
+            state.open_tool_result(viewport_height);
+        }
+        Action::ShowIterationInfo => {
+            state.open_iteration_info();
+        }
+        Action::OpenSettings => {
+            state.open_settings();
+        }
+        // Needs the run list, so `App` handles it before dispatching.
+        Action::SelectRun => {}
+        // Needs the terminal, so `App` handles it before dispatching.
+        Action::OpenPager => {}
         Action::None => {}
     }
     false
@@ -93,6 +138,12 @@ pub struct App {
     /// In raw terminal mode, SIGINT is not generated, so TUI must signal
     /// the main orchestration loop through this channel.
     interrupt_tx: Option<watch::Sender<bool>>,
+    /// Footer segment layout.
+    footer_segments: Vec<FooterSegment>,
+    /// Output alert rules highlighted in the content pane.
+    alerts: AlertMatcher,
+    /// Runs to switch between; `state` follows the current one.
+    runs: Option<Arc<Mutex<RunList>>>,
 }
 
 impl App {
@@ -101,11 +152,17 @@ impl App {
         state: Arc<Mutex<TuiState>>,
         terminated_rx: watch::Receiver<bool>,
         interrupt_tx: Option<watch::Sender<bool>>,
+        footer_segments: Vec<FooterSegment>,
+        alerts: AlertMatcher,
+        runs: Option<Arc<Mutex<RunList>>>,
     ) -> Self {
         Self {
             state,
             terminated_rx,
             interrupt_tx,
+            footer_segments,
+            alerts,
+            runs,
         }
     }
 
@@ -134,7 +191,42 @@ impl App {
         // Track viewport height for scroll calculations
         let mut viewport_height: usize = 24; // Default, updated on render
 
+        // Text queued for the external pager by the `p` key
+        let mut pager_text: Option<String> = None;
+
         loop {
+            if let Some(text) = pager_text.take() {
+                // The event stream reads stdin in the background, so it must be
+                // dropped while the pager owns the terminal.
+                drop(events);
+                disable_raw_mode()?;
+                execute!(
+                    terminal.backend_mut(),
+                    LeaveAlternateScreen,
+                    DisableMouseCapture,
+                    Show
+                )?;
+                if let Err(e) = crate::pager::page(text).await {
+                    tracing::warn!("Failed to run pager: {}", e);
+                }
+                enable_raw_mode()?;
+                execute!(
+                    terminal.backend_mut(),
+                    EnterAlternateScreen,
+                    EnableMouseCapture
+                )?;
+                terminal.clear()?;
+                events = EventStream::new();
+            }
+
+            // Show whichever run is current
+            if let Some(runs) = &self.runs
+                && let Some(state) = runs.lock().unwrap().current_state()
+                && !Arc::ptr_eq(&state, &self.state)
+            {
+                self.state = state;
+            }
+
             // Use biased select to prioritize input over render ticks
             tokio::select! {
                 biased;
@@ -179,18 +271,50 @@ impl App {
                                     }
                                 }
                                 Event::Key(key) if key.kind == KeyEventKind::Press => {
-                                    // Dismiss help on any key when help is showing
+                                    // Dismiss help on any key when help is showing;
+                                    // the settings popup takes all keys while open
                                     {
                                         let mut state = self.state.lock().unwrap();
                                         if state.show_help {
                                             state.show_help = false;
                                             continue;
                                         }
+                                        if state.settings_editor.is_some() {
+                                            settings::handle_key(&mut state, key);
+                                            continue;
+                                        }
+                                    }
+
+                                    // The run selector takes all keys while open
+                                    if let Some(runs) = &self.runs {
+                                        let mut runs = runs.lock().unwrap();
+                                        if runs.selecting {
+                                            match key.code {
+                                                KeyCode::Down | KeyCode::Char('j') => runs.select_next(),
+                                                KeyCode::Up | KeyCode::Char('k') => runs.select_prev(),
+                                                KeyCode::Enter => runs.choose(),
+                                                KeyCode::Esc | KeyCode::Char('q' | 'r') => {
+                                                    runs.selecting = false;
+                                                }
+                                                _ => {}
+                                            }
+                                            continue;
+                                        }
                                     }
 
                                     // Map key to action and dispatch
                                     let action = map_key(key);
+                                    if action == Action::SelectRun {
+                                        if let Some(runs) = &self.runs {
+                                            runs.lock().unwrap().open_selector();
+                                        }
+                                        continue;
+                                    }
                                     let mut state = self.state.lock().unwrap();
+                                    if action == Action::OpenPager {
+                                        pager_text = state.pager_text();
+                                        continue;
+                                    }
                                     if dispatch_action(action, &mut state, viewport_height) {
                                         break;
                                     }
@@ -244,20 +368,56 @@ impl App {
 
                         // Render content using ContentPane
                         if let Some(buffer) = state.current_iteration() {
-                            let mut content_widget = ContentPane::new(buffer);
+                            let mut content_widget =
+                                ContentPane::new(buffer).with_alerts(&self.alerts);
                             if let Some(query) = &state.search_state.query {
                                 content_widget = content_widget.with_search(query);
                             }
+                            if let Some(marked) = state
+                                .nav
+                                .line
+                                .filter(|marked| marked.iteration == buffer.number)
+                            {
+                                content_widget = content_widget.with_marked_line(marked.index());
+                            }
+                            if let Some(color) = state.iteration_color(buffer) {
+                                content_widget = content_widget.with_gutter(color);
+                            }
                             f.render_widget(content_widget, content_area);
+                        } else {
+                            splash::render(f, content_area, &state);
                         }
 
                         // Render footer
-                        f.render_widget(footer::render(&state), chunks[2]);
+                        f.render_widget(
+                            footer::render(&state).segments(&self.footer_segments),
+                            chunks[2],
+                        );
+
+                        // Render full tool result over the content if open
+                        if let Some(viewer) = &state.result_viewer {
+                            result_viewer::render(f, content_area, viewer);
+                        }
+
+                        // Render settings popup if open
+                        if let Some(editor) = &state.settings_editor {
+                            settings_popup::render(f, f.area(), &state, editor);
+                        }
+
+                        // Render run selector if open
+                        if let Some(runs) = &self.runs {
+                            let runs = runs.lock().unwrap();
+                            if runs.selecting {
+                                run_selector::render(f, f.area(), &runs);
+                            }
+                        }
 
                         // Render help overlay if active
                         if state.show_help {
                             help::render(f, f.area());
                         }
+
+                        theme::apply(state.theme, f.buffer_mut());
                     })?;
                 }
 
@@ -383,12 +543,12 @@ mod tests {
         state.start_new_iteration();
         state.start_new_iteration();
         state.start_new_iteration();
-        state.current_view = 0;
-        state.following_latest = false;
+        state.nav.view = 0;
+        state.nav.following_latest = false;
 
         dispatch_action(Action::NextIteration, &mut state, 10);
 
-        assert_eq!(state.current_view, 1);
+        assert_eq!(state.nav.view, 1);
     }
 
     #[test]
@@ -397,11 +557,11 @@ mod tests {
         state.start_new_iteration();
         state.start_new_iteration();
         state.start_new_iteration();
-        state.current_view = 2;
+        state.nav.view = 2;
 
         dispatch_action(Action::PrevIteration, &mut state, 10);
 
-        assert_eq!(state.current_view, 1);
+        assert_eq!(state.nav.view, 1);
     }
 
     #[test]
@@ -414,6 +574,16 @@ mod tests {
         assert!(state.show_help);
     }
 
+    #[test]
+    fn dispatch_action_resume_clears_alert_pause() {
+        let mut state = TuiState::new();
+        state.alert_pause = Some("payments".to_string());
+
+        dispatch_action(Action::Resume, &mut state, 10);
+
+        assert!(state.alert_pause.is_none());
+    }
+
     #[test]
     fn dispatch_action_dismiss_help_clears_show_help() {
         let mut state = TuiState::new();
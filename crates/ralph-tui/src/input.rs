@@ -36,6 +36,19 @@ pub enum Action {
     ShowHelp,
     /// Dismiss help overlay or cancel search
     DismissHelp,
+    /// Start the run from the startup screen, or resume a loop paused by
+    /// an output alert
+    Resume,
+    /// Open the full tool result in view (or close the result viewer)
+    OpenToolResult,
+    /// Open the transcript (or open tool result) in the external pager
+    OpenPager,
+    /// Show the environment the viewed iteration started in
+    ShowIterationInfo,
+    /// Open the settings popup
+    OpenSettings,
+    /// Open the run selector when watching several runs
+    SelectRun,
     /// Key not mapped to any action
     None,
 }
@@ -55,6 +68,12 @@ pub enum Action {
 /// - `N`: Previous search match
 /// - `?`: Show help
 /// - `Esc`: Dismiss help/cancel search
+/// - `Enter`: Start the run, or resume after an alert pause
+/// - `o`: Open/close the full tool result in view
+/// - `p`: Open the transcript or tool result in `$PAGER`
+/// - `i`: Show the viewed iteration's environment snapshot
+/// - `s`: Open the settings popup
+/// - `r`: Switch runs when watching several
 pub fn map_key(key: KeyEvent) -> Action {
     match key.code {
         // Quit
@@ -79,6 +98,22 @@ pub fn map_key(key: KeyEvent) -> Action {
         KeyCode::Char('?') => Action::ShowHelp,
         KeyCode::Esc => Action::DismissHelp,
 
+        // Alerts
+        KeyCode::Enter => Action::Resume,
+
+        // Tool results
+        KeyCode::Char('o') => Action::OpenToolResult,
+        KeyCode::Char('p') => Action::OpenPager,
+
+        // Iteration details
+        KeyCode::Char('i') => Action::ShowIterationInfo,
+
+        // Settings
+        KeyCode::Char('s') => Action::OpenSettings,
+
+        // Runs
+        KeyCode::Char('r') => Action::SelectRun,
+
         // Unknown
         _ => Action::None,
     }
@@ -173,6 +208,42 @@ mod tests {
         assert_eq!(map_key(key), Action::DismissHelp);
     }
 
+    #[test]
+    fn o_returns_open_tool_result() {
+        let key = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE);
+        assert_eq!(map_key(key), Action::OpenToolResult);
+    }
+
+    #[test]
+    fn i_returns_show_iteration_info() {
+        let key = KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE);
+        assert_eq!(map_key(key), Action::ShowIterationInfo);
+    }
+
+    #[test]
+    fn s_returns_open_settings() {
+        let key = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(map_key(key), Action::OpenSettings);
+    }
+
+    #[test]
+    fn r_returns_select_run() {
+        let key = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE);
+        assert_eq!(map_key(key), Action::SelectRun);
+    }
+
+    #[test]
+    fn p_returns_open_pager() {
+        let key = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE);
+        assert_eq!(map_key(key), Action::OpenPager);
+    }
+
+    #[test]
+    fn enter_returns_resume() {
+        let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(map_key(key), Action::Resume);
+    }
+
     // AC13: Vim l Next Iteration
     #[test]
     fn l_returns_next_iteration() {
@@ -9,17 +9,25 @@
 
 mod app;
 pub mod input;
+mod line_id;
+mod pager;
+pub mod runs;
+pub mod settings;
 pub mod state;
+mod theme;
 pub mod widgets;
 
 use anyhow::Result;
 use app::App;
+use ralph_core::{AlertMatcher, FooterSegment, ToolResultStore};
 use ralph_proto::{Event, HatId};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tokio::sync::watch;
 
 pub use app::dispatch_action;
+pub use line_id::LineId;
+pub use runs::RunList;
 pub use state::TuiState;
 pub use widgets::{footer, header};
 
@@ -31,6 +39,12 @@ pub struct Tui {
     /// In raw terminal mode, SIGINT is not generated by the OS, so TUI must
     /// detect Ctrl+C via crossterm events and signal the main loop directly.
     interrupt_tx: Option<watch::Sender<bool>>,
+    /// Footer segment layout (defaults to `footer::DEFAULT_SEGMENTS`).
+    footer_segments: Vec<FooterSegment>,
+    /// Output alert rules highlighted in the content pane.
+    alerts: AlertMatcher,
+    /// Runs to switch between, when watching several.
+    runs: Option<Arc<Mutex<RunList>>>,
 }
 
 impl Tui {
@@ -40,6 +54,9 @@ impl Tui {
             state: Arc::new(Mutex::new(TuiState::new())),
             terminated_rx: None,
             interrupt_tx: None,
+            footer_segments: footer::DEFAULT_SEGMENTS.to_vec(),
+            alerts: AlertMatcher::default(),
+            runs: None,
         }
     }
 
@@ -75,6 +92,48 @@ impl Tui {
         self
     }
 
+    /// Sets per-hat display colors for the header.
+    ///
+    /// Must be called after `with_hat_map()`, which replaces the state.
+    #[must_use]
+    pub fn with_hat_colors(self, colors: HashMap<HatId, ratatui::style::Color>) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state.set_hat_colors(colors);
+        }
+        self
+    }
+
+    /// Sets the footer segment layout (from `tui.footer` in config).
+    #[must_use]
+    pub fn with_footer_segments(mut self, segments: Vec<FooterSegment>) -> Self {
+        self.footer_segments = segments;
+        self
+    }
+
+    /// Sets the store full tool results are loaded from when opened with `o`.
+    #[must_use]
+    pub fn with_tool_result_store(self, store: ToolResultStore) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state.set_tool_result_store(store);
+        }
+        self
+    }
+
+    /// Sets the output alert rules whose matches are highlighted.
+    #[must_use]
+    pub fn with_alerts(mut self, alerts: AlertMatcher) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// Watches several runs, each with its own state, instead of the single
+    /// shared state. Runs can be added to the list while the TUI runs.
+    #[must_use]
+    pub fn with_runs(mut self, runs: Arc<Mutex<RunList>>) -> Self {
+        self.runs = Some(runs);
+        self
+    }
+
     /// Returns the shared state for external updates.
     pub fn state(&self) -> Arc<Mutex<TuiState>> {
         Arc::clone(&self.state)
@@ -104,7 +163,14 @@ impl Tui {
         let terminated_rx = self
             .terminated_rx
             .expect("Termination signal not set - call with_termination_signal() first");
-        let app = App::new(Arc::clone(&self.state), terminated_rx, self.interrupt_tx);
+        let app = App::new(
+            Arc::clone(&self.state),
+            terminated_rx,
+            self.interrupt_tx,
+            self.footer_segments,
+            self.alerts,
+            self.runs,
+        );
         app.run().await
     }
 }
@@ -114,3 +180,22 @@ impl Default for Tui {
         Self::new()
     }
 }
+
+/// Why the TUI can't run in the current terminal, or `None` if it can.
+///
+/// Crossterm needs stdin for keyboard input and stdout for rendering, and a
+/// terminal that reports its size; `TERM=dumb` can't draw the screen.
+pub fn terminal_unavailable() -> Option<String> {
+    use std::io::IsTerminal;
+
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Some("stdin and stdout must both be terminals".to_string());
+    }
+    if std::env::var("TERM").is_ok_and(|term| term == "dumb") {
+        return Some("TERM=dumb doesn't support full-screen output".to_string());
+    }
+    match crossterm::terminal::size() {
+        Ok(_) => None,
+        Err(e) => Some(format!("can't read the terminal size: {e}")),
+    }
+}
@@ -0,0 +1,80 @@
+//! Stable addresses for lines of iteration output.
+//!
+//! Iteration buffers only ever grow, and replaying a run log through the
+//! stream handler produces the same lines as the live run did, so an
+//! iteration number plus a line number names the same line in the live TUI,
+//! in `ralph attach` and in `ralph open`. The text form is `iter12:3401`,
+//! with 1-based line numbers as shown to users.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Address of one line of output: iteration and 1-based line number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LineId {
+    /// Iteration number (1-indexed).
+    pub iteration: u32,
+    /// Line number within the iteration (1-indexed).
+    pub line: usize,
+}
+
+impl LineId {
+    /// Creates the id of the line at `index` (0-based) of `iteration`.
+    pub fn from_index(iteration: u32, index: usize) -> Self {
+        Self {
+            iteration,
+            line: index + 1,
+        }
+    }
+
+    /// Returns the 0-based index of the line in its iteration buffer.
+    pub fn index(self) -> usize {
+        self.line.saturating_sub(1)
+    }
+}
+
+impl fmt::Display for LineId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "iter{}:{}", self.iteration, self.line)
+    }
+}
+
+impl FromStr for LineId {
+    type Err = String;
+
+    /// Parses `iter12:3401`; the `iter` prefix is optional.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid line location '{s}', expected e.g. iter12:3401");
+        let (iteration, line) = s.trim().split_once(':').ok_or_else(invalid)?;
+        let iteration = iteration.strip_prefix("iter").unwrap_or(iteration);
+        let iteration: u32 = iteration.parse().map_err(|_| invalid())?;
+        let line: usize = line.parse().map_err(|_| invalid())?;
+        if iteration == 0 || line == 0 {
+            return Err(invalid());
+        }
+        Ok(Self { iteration, line })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_and_parse_roundtrip() {
+        let id = LineId::from_index(12, 3400);
+        assert_eq!(id.to_string(), "iter12:3401");
+        assert_eq!("iter12:3401".parse::<LineId>(), Ok(id));
+        assert_eq!("12:3401".parse::<LineId>(), Ok(id));
+        assert_eq!(id.index(), 3400);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_locations() {
+        for bad in [
+            "", "iter12", "iter:3", "iterx:3", "iter0:1", "iter1:0", "1:-2",
+        ] {
+            assert!(bad.parse::<LineId>().is_err(), "{bad} should not parse");
+        }
+    }
+}
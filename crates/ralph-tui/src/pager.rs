@@ -0,0 +1,171 @@
+//! External pager support.
+//!
+//! Long transcripts and tool results can be handed off to `$PAGER` (falling
+//! back to `less -R`) for users who prefer its navigation. Styled TUI lines are
+//! converted back to ANSI escape sequences so colors survive the trip.
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use std::fmt::Write as _;
+use std::io;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Pager used when `$PAGER` is unset or empty.
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Renders styled lines as text with ANSI SGR escapes.
+pub fn lines_to_ansi(lines: &[Line<'_>]) -> String {
+    let mut out = String::new();
+    for line in lines {
+        for span in &line.spans {
+            let style = line.style.patch(span.style);
+            let sgr = sgr_codes(style);
+            if sgr.is_empty() {
+                out.push_str(&span.content);
+            } else {
+                let _ = write!(out, "\x1b[{sgr}m{}\x1b[0m", span.content);
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Returns the `;`-separated SGR parameters for a style.
+fn sgr_codes(style: Style) -> String {
+    let mut codes: Vec<String> = Vec::new();
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(fg) = style.fg.and_then(|c| color_code(c, false)) {
+        codes.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|c| color_code(c, true)) {
+        codes.push(bg);
+    }
+    codes.join(";")
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let (base, extended) = if background { (40, 48) } else { (30, 38) };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+        Color::Indexed(i) => return Some(format!("{extended};5;{i}")),
+        Color::Rgb(r, g, b) => return Some(format!("{extended};2;{r};{g};{b}")),
+    };
+    Some(code.to_string())
+}
+
+/// Splits a pager command line into program and arguments.
+fn pager_command(pager: Option<&str>) -> Vec<String> {
+    let words: Vec<String> = pager
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    if words.is_empty() {
+        DEFAULT_PAGER
+            .split_whitespace()
+            .map(str::to_string)
+            .collect()
+    } else {
+        words
+    }
+}
+
+/// Pipes `content` through the user's pager and waits for it to exit.
+///
+/// The caller is responsible for releasing the terminal first.
+pub async fn page(content: String) -> io::Result<()> {
+    let pager = std::env::var("PAGER").ok();
+    let words = pager_command(pager.as_deref());
+    let mut command = Command::new(&words[0]);
+    command.args(&words[1..]).stdin(Stdio::piped());
+    // A bare `less` would show escapes literally; default it to raw-color mode
+    // unless the user already configured it.
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "-R");
+    }
+
+    let mut child = command.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may quit before reading everything; that's not an error.
+        match stdin.write_all(content.as_bytes()).await {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+            _ => {}
+        }
+    }
+    child.wait().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::text::Span;
+
+    #[test]
+    fn plain_lines_have_no_escapes() {
+        let lines = vec![Line::from("hello"), Line::from("world")];
+        assert_eq!(lines_to_ansi(&lines), "hello\nworld\n");
+    }
+
+    #[test]
+    fn styled_spans_are_wrapped_in_sgr() {
+        let lines = vec![Line::from(vec![
+            Span::styled("ok", Style::default().fg(Color::Green)),
+            Span::raw(" "),
+            Span::styled(
+                "bad",
+                Style::default()
+                    .fg(Color::Rgb(255, 0, 0))
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ])];
+        assert_eq!(
+            lines_to_ansi(&lines),
+            "\x1b[32mok\x1b[0m \x1b[1;38;2;255;0;0;100mbad\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn line_style_applies_to_spans() {
+        let lines = vec![Line::from("dim").style(Style::default().fg(Color::Indexed(8)))];
+        assert_eq!(lines_to_ansi(&lines), "\x1b[38;5;8mdim\x1b[0m\n");
+    }
+
+    #[test]
+    fn pager_command_defaults_to_less() {
+        assert_eq!(pager_command(None), vec!["less", "-R"]);
+        assert_eq!(pager_command(Some("  ")), vec!["less", "-R"]);
+        assert_eq!(pager_command(Some("most -s")), vec!["most", "-s"]);
+    }
+}
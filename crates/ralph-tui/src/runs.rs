@@ -0,0 +1,121 @@
+//! Several runs in one TUI.
+//!
+//! Each run is fed into its own [`TuiState`], so switching runs keeps every
+//! run's iteration buffers, scroll position and search. The app shows the
+//! current run; `r` opens the run selector to switch to another.
+
+use crate::state::TuiState;
+use std::sync::{Arc, Mutex};
+
+/// A run the TUI can show.
+pub struct RunEntry {
+    /// Run label, e.g. the loop ID or `(primary)`.
+    pub id: String,
+    /// One-line status shown in the selector.
+    pub status: String,
+    /// Whether the run is still going.
+    pub active: bool,
+    /// The run's own view state.
+    pub state: Arc<Mutex<TuiState>>,
+}
+
+/// The runs being watched and the run selector.
+#[derive(Default)]
+pub struct RunList {
+    pub entries: Vec<RunEntry>,
+    /// Index of the run on screen.
+    pub current: usize,
+    /// Row highlighted in the selector.
+    pub selected: usize,
+    /// Whether the selector is open.
+    pub selecting: bool,
+}
+
+impl RunList {
+    /// Adds a run, returning its state. A run already listed keeps its state.
+    pub fn add(&mut self, id: &str, state: TuiState) -> Arc<Mutex<TuiState>> {
+        if let Some(entry) = self.get(id) {
+            return Arc::clone(&entry.state);
+        }
+        let state = Arc::new(Mutex::new(state));
+        self.entries.push(RunEntry {
+            id: id.to_string(),
+            status: String::new(),
+            active: true,
+            state: Arc::clone(&state),
+        });
+        state
+    }
+
+    pub fn get(&self, id: &str) -> Option<&RunEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// Updates the status line of a run.
+    pub fn set_status(&mut self, id: &str, status: impl Into<String>, active: bool) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) {
+            entry.status = status.into();
+            entry.active = active;
+        }
+    }
+
+    /// State of the run on screen.
+    pub fn current_state(&self) -> Option<Arc<Mutex<TuiState>>> {
+        self.entries
+            .get(self.current)
+            .map(|entry| Arc::clone(&entry.state))
+    }
+
+    /// Opens the selector on the run on screen.
+    pub fn open_selector(&mut self) {
+        self.selecting = true;
+        self.selected = self.current;
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Shows the highlighted run and closes the selector.
+    pub fn choose(&mut self) {
+        if self.selected < self.entries.len() {
+            self.current = self.selected;
+        }
+        self.selecting = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_keep_their_own_state() {
+        let mut runs = RunList::default();
+        let first = runs.add("loop-a", TuiState::new());
+        runs.add("loop-b", TuiState::new());
+        first.lock().unwrap().start_new_iteration();
+
+        // Adding a listed run again keeps its buffers
+        let again = runs.add("loop-a", TuiState::new());
+        assert_eq!(again.lock().unwrap().total_iterations(), 1);
+
+        runs.open_selector();
+        runs.select_next();
+        runs.select_next();
+        runs.choose();
+        assert_eq!(runs.current, 1);
+        assert!(!runs.selecting);
+        let current = runs.current_state().unwrap();
+        assert_eq!(current.lock().unwrap().total_iterations(), 0);
+
+        runs.set_status("loop-a", "finished", false);
+        assert!(!runs.get("loop-a").unwrap().active);
+    }
+}
@@ -0,0 +1,286 @@
+//! The settings popup (`s`).
+//!
+//! Adjusts the budget, model, verbosity, follow mode and theme of a running
+//! loop. Follow mode and theme change the TUI at once; the loop picks up the
+//! budget, model and verbosity before its next iteration. `w` writes the
+//! changed settings to `.ralph/local.toml` so later runs start with them.
+
+use crate::state::TuiState;
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// A setting the popup can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsField {
+    Budget,
+    Model,
+    Verbose,
+    Follow,
+    Theme,
+}
+
+/// The popup's rows, top to bottom.
+pub const FIELDS: [SettingsField; 5] = [
+    SettingsField::Budget,
+    SettingsField::Model,
+    SettingsField::Verbose,
+    SettingsField::Follow,
+    SettingsField::Theme,
+];
+
+impl SettingsField {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Budget => "Budget",
+            Self::Model => "Model",
+            Self::Verbose => "Verbose",
+            Self::Follow => "Follow",
+            Self::Theme => "Theme",
+        }
+    }
+
+    /// Whether Enter opens a text input rather than toggling the value.
+    fn is_text(self) -> bool {
+        matches!(self, Self::Budget | Self::Model)
+    }
+
+    /// The current value, as shown in the popup.
+    pub fn value(self, state: &TuiState) -> String {
+        fn on_off(value: bool) -> String {
+            if value { "on" } else { "off" }.to_string()
+        }
+        match self {
+            Self::Budget => state
+                .max_cost_usd
+                .map_or_else(|| "none".to_string(), |budget| format!("${budget:.2}")),
+            Self::Model => state
+                .model
+                .clone()
+                .unwrap_or_else(|| "backend default".to_string()),
+            Self::Verbose => on_off(state.verbose),
+            Self::Follow => on_off(state.nav.following_latest),
+            Self::Theme => state.theme.as_str().to_string(),
+        }
+    }
+
+    /// The text a new input for this field starts with.
+    fn input(self, state: &TuiState) -> String {
+        match self {
+            Self::Budget => state
+                .max_cost_usd
+                .map(|budget| budget.to_string())
+                .unwrap_or_default(),
+            Self::Model => state.model.clone().unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// State of the open settings popup.
+#[derive(Debug, Clone, Default)]
+pub struct SettingsEditor {
+    /// Index of the selected row in [`FIELDS`].
+    pub selected: usize,
+    /// Text typed for the selected field, while editing it.
+    pub input: Option<String>,
+    /// Result of the last save, or why a value was rejected.
+    pub message: Option<String>,
+}
+
+impl SettingsEditor {
+    pub fn field(&self) -> SettingsField {
+        FIELDS[self.selected]
+    }
+}
+
+/// Handles a key press while the popup is open.
+pub fn handle_key(state: &mut TuiState, key: KeyEvent) {
+    let Some(mut editor) = state.settings_editor.take() else {
+        return;
+    };
+
+    if let Some(input) = editor.input.as_mut() {
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let input = editor.input.take().unwrap_or_default();
+                editor.message = set_text(state, editor.field(), input.trim()).err();
+            }
+            KeyCode::Esc => editor.input = None,
+            _ => {}
+        }
+        state.settings_editor = Some(editor);
+        return;
+    }
+
+    editor.message = None;
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => editor.selected = editor.selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => {
+            editor.selected = (editor.selected + 1).min(FIELDS.len() - 1);
+        }
+        KeyCode::Enter | KeyCode::Char(' ') => {
+            let field = editor.field();
+            if field.is_text() {
+                editor.input = Some(field.input(state));
+            } else {
+                toggle(state, field);
+            }
+        }
+        KeyCode::Char('w') => editor.message = Some(save(state)),
+        KeyCode::Esc | KeyCode::Char('q' | 's') => return,
+        _ => {}
+    }
+    state.settings_editor = Some(editor);
+}
+
+/// Applies typed input to a text field.
+fn set_text(state: &mut TuiState, field: SettingsField, input: &str) -> Result<(), String> {
+    match field {
+        SettingsField::Budget => {
+            let budget = match input {
+                "" | "none" => 0.0,
+                _ => input
+                    .trim_start_matches('$')
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|budget| budget.is_finite() && *budget >= 0.0)
+                    .ok_or_else(|| format!("Not a budget in USD: {input}"))?,
+            };
+            state.settings.max_cost_usd = Some(budget);
+            state.max_cost_usd = state.settings.budget().flatten();
+        }
+        SettingsField::Model => {
+            state.settings.model = Some(input.to_string());
+            state.model = (!input.is_empty()).then(|| input.to_string());
+        }
+        _ => {}
+    }
+    state.settings_changed = true;
+    Ok(())
+}
+
+/// Flips a toggle field.
+fn toggle(state: &mut TuiState, field: SettingsField) {
+    match field {
+        SettingsField::Verbose => {
+            state.verbose = !state.verbose;
+            state.settings.verbose = Some(state.verbose);
+        }
+        SettingsField::Follow => {
+            let follow = !state.nav.following_latest;
+            state.set_following(follow);
+            state.settings.follow = Some(follow);
+        }
+        SettingsField::Theme => {
+            state.theme = state.theme.next();
+            state.settings.theme = Some(state.theme);
+        }
+        SettingsField::Budget | SettingsField::Model => return,
+    }
+    state.settings_changed = true;
+}
+
+/// Writes the settings to the local settings file and describes the result.
+fn save(state: &TuiState) -> String {
+    let Some(path) = &state.settings_path else {
+        return "Nowhere to save settings".to_string();
+    };
+    match state.settings.save(path) {
+        Ok(()) => format!("Saved to {}", path.display()),
+        Err(e) => format!("Save failed: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use ralph_core::{LocalSettings, TuiTheme};
+
+    fn press(state: &mut TuiState, code: KeyCode) {
+        handle_key(state, KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_text(state: &mut TuiState, text: &str) {
+        for c in text.chars() {
+            press(state, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn edits_budget_and_saves() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(".ralph/local.toml");
+        let mut state = TuiState::new();
+        state.settings_path = Some(path.clone());
+        state.max_cost_usd = Some(5.0);
+        state.open_settings();
+
+        press(&mut state, KeyCode::Enter);
+        assert_eq!(
+            state.settings_editor.as_ref().unwrap().input.as_deref(),
+            Some("5")
+        );
+        press(&mut state, KeyCode::Backspace);
+        type_text(&mut state, "12.5");
+        press(&mut state, KeyCode::Enter);
+
+        assert_eq!(state.max_cost_usd, Some(12.5));
+        assert!(state.settings_changed);
+
+        press(&mut state, KeyCode::Char('w'));
+        let saved = LocalSettings::load(&path).unwrap();
+        assert_eq!(saved.max_cost_usd, Some(12.5));
+        assert!(
+            state
+                .settings_editor
+                .as_ref()
+                .unwrap()
+                .message
+                .as_ref()
+                .unwrap()
+                .starts_with("Saved")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_budget() {
+        let mut state = TuiState::new();
+        state.open_settings();
+        press(&mut state, KeyCode::Enter);
+        type_text(&mut state, "lots");
+        press(&mut state, KeyCode::Enter);
+
+        assert_eq!(state.max_cost_usd, None);
+        assert!(!state.settings_changed);
+        assert_eq!(
+            state.settings_editor.as_ref().unwrap().message.as_deref(),
+            Some("Not a budget in USD: lots")
+        );
+    }
+
+    #[test]
+    fn toggles_apply_immediately_and_esc_closes() {
+        let mut state = TuiState::new();
+        state.open_settings();
+        press(&mut state, KeyCode::Char('j'));
+        press(&mut state, KeyCode::Char('j'));
+        press(&mut state, KeyCode::Enter);
+        assert!(state.verbose);
+
+        press(&mut state, KeyCode::Char('j'));
+        press(&mut state, KeyCode::Enter);
+        assert!(!state.nav.following_latest);
+
+        press(&mut state, KeyCode::Char('j'));
+        press(&mut state, KeyCode::Enter);
+        assert_eq!(state.theme, TuiTheme::Light);
+        assert_eq!(state.settings.theme, Some(TuiTheme::Light));
+
+        press(&mut state, KeyCode::Esc);
+        assert!(state.settings_editor.is_none());
+    }
+}
@@ -1,9 +1,21 @@
 //! State management for the TUI.
 
+use crate::line_id::LineId;
+use crate::settings::SettingsEditor;
+use ralph_adapters::{IterationBuffers, ProbeStatus};
+use ralph_core::{Clock, LocalSettings, SystemClock, ToolResultStore, TuiTheme};
 use ralph_proto::{Event, HatId};
-use std::collections::HashMap;
+use ratatui::style::Color;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
+/// Number of previously active hats kept for the header history strip.
+pub const HAT_HISTORY_LEN: usize = 5;
+
+/// Lines of context kept above a line the view jumps to.
+const JUMP_CONTEXT_LINES: usize = 3;
+
 // ============================================================================
 // TaskSummary - Summary of a single task for TUI display
 // ============================================================================
@@ -60,6 +72,43 @@ impl TaskCounts {
     }
 }
 
+// ============================================================================
+// Navigation - Where the user is looking
+// ============================================================================
+
+/// Where the user is looking: the viewed iteration, the line last jumped
+/// to, and whether the view follows new iterations.
+///
+/// Iteration keys, search matches, alert pauses and line jumps all move the
+/// view through [`TuiState`]'s navigation methods, so these never disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Navigation {
+    /// Index of the iteration being viewed (0-indexed).
+    pub view: usize,
+    /// Line the view last jumped to, highlighted in the content pane.
+    pub line: Option<LineId>,
+    /// Whether to automatically follow the latest iteration.
+    pub following_latest: bool,
+    /// Iteration that started while the user was viewing history. Cleared
+    /// when the view returns to the latest iteration.
+    pub new_iteration_alert: Option<usize>,
+    /// Jump to a line that hasn't been output yet, retried by
+    /// [`TuiState::apply_pending_jump`].
+    pub pending_jump: Option<LineId>,
+}
+
+impl Default for Navigation {
+    fn default() -> Self {
+        Self {
+            view: 0,
+            line: None,
+            following_latest: true,
+            new_iteration_alert: None,
+            pending_jump: None,
+        }
+    }
+}
+
 // ============================================================================
 // SearchState - Search functionality for TUI content
 // ============================================================================
@@ -93,6 +142,46 @@ impl SearchState {
     }
 }
 
+// ============================================================================
+// ResultViewer - Popup for paging through a full tool result or details
+// ============================================================================
+
+/// Text shown in a scrollable popup: a full tool result loaded from disk, or
+/// an iteration's environment snapshot.
+#[derive(Debug, Clone)]
+pub struct ResultViewer {
+    /// Popup title, e.g. `Tool result toolu_01`.
+    pub title: String,
+    /// Result text, split into lines.
+    pub lines: Vec<String>,
+    /// Index of the first visible line.
+    pub scroll: usize,
+    /// Whether the text is an edit's word diff, with `[-removed-]` and
+    /// `{+added+}` runs to color.
+    pub word_diff: bool,
+}
+
+impl ResultViewer {
+    /// Scrolls down by `n` lines, stopping when the last line is at the bottom.
+    pub fn scroll_down(&mut self, n: usize, viewport_height: usize) {
+        self.scroll = (self.scroll + n).min(self.max_scroll(viewport_height));
+    }
+
+    /// Scrolls up by `n` lines.
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+    }
+
+    /// Scrolls so the last line is at the bottom of the viewport.
+    pub fn scroll_bottom(&mut self, viewport_height: usize) {
+        self.scroll = self.max_scroll(viewport_height);
+    }
+
+    fn max_scroll(&self, viewport_height: usize) -> usize {
+        self.lines.len().saturating_sub(viewport_height)
+    }
+}
+
 /// Observable state derived from loop events.
 pub struct TuiState {
     /// Which hat will process next event (ID + display name).
@@ -113,31 +202,34 @@ pub struct TuiState {
     pub show_help: bool,
     /// Whether in scroll mode.
     pub in_scroll_mode: bool,
-    /// Current search query (if in search input mode).
-    pub search_query: String,
-    /// Search direction (true = forward, false = backward).
-    pub search_forward: bool,
     /// Maximum iterations from config.
     pub max_iterations: Option<u32>,
+    /// Cost accumulated across completed iterations (USD).
+    pub total_cost_usd: f64,
+    /// Maximum cost from config (USD).
+    pub max_cost_usd: Option<f64>,
     /// Idle timeout countdown.
     pub idle_timeout_remaining: Option<Duration>,
     /// Map of event topics to hat display information (for custom hats).
     /// Key: event topic (e.g., "review.security")
     /// Value: (HatId, display name including emoji)
     hat_map: HashMap<String, (HatId, String)>,
+    /// Per-hat display colors from config.
+    hat_colors: HashMap<HatId, Color>,
+    /// Previously active hats, oldest first (at most `HAT_HISTORY_LEN`).
+    pub hat_history: VecDeque<(HatId, String)>,
 
     // ========================================================================
     // Iteration Management (new fields for TUI refactor)
     // ========================================================================
     /// Content buffers for each iteration.
     pub iterations: Vec<IterationBuffer>,
-    /// Index of the iteration currently being viewed (0-indexed).
-    pub current_view: usize,
-    /// Whether to automatically follow the latest iteration.
-    pub following_latest: bool,
-    /// Alert about a new iteration (shown when viewing history and new iteration arrives).
-    /// Contains the iteration number to alert about. Cleared when navigating to latest.
-    pub new_iteration_alert: Option<usize>,
+    /// Viewed iteration and line, and whether the view follows new output.
+    pub nav: Navigation,
+    /// When the most recent iteration buffer was started (for run ETA estimates).
+    pub latest_iteration_started: Option<Instant>,
+    /// Environment snapshot for the next iteration buffer to be started.
+    next_environment: Option<Vec<String>>,
 
     // ========================================================================
     // Search State
@@ -160,65 +252,103 @@ pub struct TuiState {
     pub task_counts: TaskCounts,
     /// Currently active task (if any) for display in TUI widgets.
     pub active_task: Option<TaskSummary>,
+
+    // ========================================================================
+    // Alert State
+    // ========================================================================
+    /// Label of the alert rule that paused the loop. The loop waits until
+    /// the user clears this by resuming.
+    pub alert_pause: Option<String>,
+    /// Line where the pausing alert matched, if it could be found.
+    pub alert_pause_at: Option<LineId>,
+    /// Client holding the run's controller role while it has the loop paused.
+    /// Cleared by the loop when the controller resumes.
+    pub control_pause: Option<String>,
+    /// Why the loop is waiting for power (low battery, suspend). Cleared by
+    /// the loop once the machine is back on AC or awake.
+    pub power_pause: Option<String>,
+    /// Token-per-minute throttle holding the next iteration: why, and when
+    /// it may start. The footer counts down to it.
+    pub throttle_wait: Option<(String, Instant)>,
+    /// Concurrency group the next iteration is queued for while other runs
+    /// hold all its slots.
+    pub queued_for: Option<String>,
+    /// Last iteration whose cost or tokens were an outlier, e.g. "iter 7 used
+    /// 4.1× median tokens". Cleared by the loop after a normal iteration.
+    pub cost_anomaly: Option<String>,
+
+    // ========================================================================
+    // Tool Result State
+    // ========================================================================
+    /// Where full tool results are loaded from (previews only stay in memory).
+    tool_result_store: Option<ToolResultStore>,
+    /// Full tool result popup, when open.
+    pub result_viewer: Option<ResultViewer>,
+
+    // ========================================================================
+    // Startup State
+    // ========================================================================
+    /// Label/value rows describing the run, shown on the startup screen.
+    pub startup_summary: Vec<(String, String)>,
+    /// Backend probe result, shown on the startup screen.
+    pub probe: Option<ProbeStatus>,
+    /// The loop waits for Enter before iteration 1 while this is set.
+    pub awaiting_start: bool,
+
+    // ========================================================================
+    // Settings State
+    // ========================================================================
+    /// Model the backend runs with, if set.
+    pub model: Option<String>,
+    /// Whether backend output is verbose.
+    pub verbose: bool,
+    /// Color theme the TUI renders with.
+    pub theme: TuiTheme,
+    /// Local setting overrides, loaded at startup and edited in the popup.
+    pub settings: LocalSettings,
+    /// Set when the popup changed a setting; the loop clears it once it has
+    /// applied the settings.
+    pub settings_changed: bool,
+    /// Where `w` in the settings popup saves the settings.
+    pub settings_path: Option<PathBuf>,
+    /// Settings popup, when open.
+    pub settings_editor: Option<SettingsEditor>,
+    /// Source of "now" for the timers, the activity indicator and the ETA.
+    clock: Arc<dyn Clock>,
 }
 
 impl TuiState {
     /// Creates empty state. Timer starts immediately at creation.
     pub fn new() -> Self {
-        Self {
-            pending_hat: None,
-            iteration: 0,
-            prev_iteration: 0,
-            loop_started: Some(Instant::now()),
-            iteration_started: None,
-            last_event: None,
-            last_event_at: None,
-            show_help: false,
-            in_scroll_mode: false,
-            search_query: String::new(),
-            search_forward: true,
-            max_iterations: None,
-            idle_timeout_remaining: None,
-            hat_map: HashMap::new(),
-            // Iteration management
-            iterations: Vec::new(),
-            current_view: 0,
-            following_latest: true,
-            new_iteration_alert: None,
-            // Search state
-            search_state: SearchState::new(),
-            // Completion state
-            loop_completed: false,
-            final_iteration_elapsed: None,
-            // Task tracking state
-            task_counts: TaskCounts::default(),
-            active_task: None,
-        }
+        Self::with_hat_map(HashMap::new())
     }
 
     /// Creates state with a custom hat map for dynamic topic-to-hat resolution.
     /// Timer starts immediately at creation.
     pub fn with_hat_map(hat_map: HashMap<String, (HatId, String)>) -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
         Self {
             pending_hat: None,
             iteration: 0,
             prev_iteration: 0,
-            loop_started: Some(Instant::now()),
+            loop_started: Some(clock.now()),
             iteration_started: None,
             last_event: None,
             last_event_at: None,
             show_help: false,
             in_scroll_mode: false,
-            search_query: String::new(),
-            search_forward: true,
             max_iterations: None,
+            total_cost_usd: 0.0,
+            max_cost_usd: None,
             idle_timeout_remaining: None,
             hat_map,
+            hat_colors: HashMap::new(),
+            hat_history: VecDeque::new(),
             // Iteration management
             iterations: Vec::new(),
-            current_view: 0,
-            following_latest: true,
-            new_iteration_alert: None,
+            nav: Navigation::default(),
+            latest_iteration_started: None,
+            next_environment: None,
             // Search state
             search_state: SearchState::new(),
             // Completion state
@@ -227,20 +357,58 @@ impl TuiState {
             // Task tracking state
             task_counts: TaskCounts::default(),
             active_task: None,
+            // Alert state
+            alert_pause: None,
+            alert_pause_at: None,
+            control_pause: None,
+            power_pause: None,
+            throttle_wait: None,
+            queued_for: None,
+            cost_anomaly: None,
+            // Tool result state
+            tool_result_store: None,
+            result_viewer: None,
+            // Startup state
+            startup_summary: Vec::new(),
+            probe: None,
+            awaiting_start: false,
+            // Settings state
+            model: None,
+            verbose: false,
+            theme: TuiTheme::default(),
+            settings: LocalSettings::default(),
+            settings_changed: false,
+            settings_path: None,
+            settings_editor: None,
+            clock,
         }
     }
 
+    /// Reads the time from `clock` instead of the system clock; the loop
+    /// timer restarts at the clock's current time.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.loop_started = Some(clock.now());
+        self.clock = clock;
+        self
+    }
+
+    /// The current instant, as seen by this state's clock.
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
     /// Updates state based on event topic.
     pub fn update(&mut self, event: &Event) {
-        let now = Instant::now();
+        let now = self.now();
         let topic = event.topic.as_str();
 
         self.last_event = Some(topic.to_string());
         self.last_event_at = Some(now);
 
         // First, check if we have a custom hat mapping for this topic
-        if let Some((hat_id, hat_display)) = self.hat_map.get(topic) {
-            self.pending_hat = Some((hat_id.clone(), hat_display.clone()));
+        if let Some((hat_id, hat_display)) = self.hat_map.get(topic).cloned() {
+            self.set_pending_hat(hat_id, hat_display);
             // Handle iteration timing for custom hats
             if topic.starts_with("build.") {
                 self.iteration_started = Some(now);
@@ -253,35 +421,43 @@ impl TuiState {
             "task.start" => {
                 // Save state we want to preserve across reset
                 let saved_hat_map = std::mem::take(&mut self.hat_map);
+                let saved_hat_colors = std::mem::take(&mut self.hat_colors);
+                let saved_result_store = self.tool_result_store.take();
                 let saved_loop_started = self.loop_started; // Preserve timer from TUI init
+                let saved_limits = (self.max_iterations, self.max_cost_usd); // Set from config
+                let saved_clock = Arc::clone(&self.clock);
                 *self = Self::new();
+                self.clock = saved_clock;
                 self.hat_map = saved_hat_map;
+                self.hat_colors = saved_hat_colors;
+                self.tool_result_store = saved_result_store;
                 self.loop_started = saved_loop_started; // Keep original timer
-                self.pending_hat = Some((HatId::new("planner"), "📋Planner".to_string()));
+                (self.max_iterations, self.max_cost_usd) = saved_limits;
+                self.set_pending_hat(HatId::new("planner"), "📋Planner".to_string());
                 self.last_event = Some(topic.to_string());
                 self.last_event_at = Some(now);
             }
             "task.resume" => {
                 // Don't reset timer on resume - keep counting from TUI init
-                self.pending_hat = Some((HatId::new("planner"), "📋Planner".to_string()));
+                self.set_pending_hat(HatId::new("planner"), "📋Planner".to_string());
             }
             "build.task" => {
-                self.pending_hat = Some((HatId::new("builder"), "🔨Builder".to_string()));
+                self.set_pending_hat(HatId::new("builder"), "🔨Builder".to_string());
                 self.iteration_started = Some(now);
             }
             "build.done" => {
-                self.pending_hat = Some((HatId::new("planner"), "📋Planner".to_string()));
+                self.set_pending_hat(HatId::new("planner"), "📋Planner".to_string());
                 self.prev_iteration = self.iteration;
                 self.iteration += 1;
             }
             "build.blocked" => {
-                self.pending_hat = Some((HatId::new("planner"), "📋Planner".to_string()));
+                self.set_pending_hat(HatId::new("planner"), "📋Planner".to_string());
             }
             "loop.terminate" => {
                 self.pending_hat = None;
                 self.loop_completed = true;
                 // Freeze the iteration timer at its current value
-                self.final_iteration_elapsed = self.iteration_started.map(|start| start.elapsed());
+                self.final_iteration_elapsed = self.get_iteration_elapsed();
             }
             _ => {
                 // Unknown topic - don't change pending_hat
@@ -289,6 +465,36 @@ impl TuiState {
         }
     }
 
+    /// Switches the pending hat, pushing the previous one onto the history strip.
+    fn set_pending_hat(&mut self, hat_id: HatId, display: String) {
+        if let Some(previous) = self.pending_hat.take()
+            && previous.0 != hat_id
+        {
+            if self.hat_history.len() == HAT_HISTORY_LEN {
+                self.hat_history.pop_front();
+            }
+            self.hat_history.push_back(previous);
+        }
+        self.pending_hat = Some((hat_id, display));
+    }
+
+    /// Sets per-hat display colors (from the `color` field of hat config).
+    pub fn set_hat_colors(&mut self, colors: HashMap<HatId, Color>) {
+        self.hat_colors = colors;
+    }
+
+    /// Returns the configured color for a hat, if any.
+    pub fn hat_color(&self, hat_id: &HatId) -> Option<Color> {
+        self.hat_colors.get(hat_id).copied()
+    }
+
+    /// Returns the gutter color of an iteration: its hat's configured
+    /// color, else the hat's palette color.
+    pub fn iteration_color(&self, buffer: &IterationBuffer) -> Option<Color> {
+        let hat = buffer.hat.as_ref()?;
+        Some(ralph_adapters::hat_color(&self.hat_colors, hat.as_str()))
+    }
+
     /// Returns formatted hat display (emoji + name).
     pub fn get_pending_hat_display(&self) -> String {
         self.pending_hat
@@ -298,7 +504,8 @@ impl TuiState {
 
     /// Time since loop started.
     pub fn get_loop_elapsed(&self) -> Option<Duration> {
-        self.loop_started.map(|start| start.elapsed())
+        self.loop_started
+            .map(|start| self.now().saturating_duration_since(start))
     }
 
     /// Time since iteration started, or frozen value if loop completed.
@@ -307,13 +514,50 @@ impl TuiState {
         if let Some(final_elapsed) = self.final_iteration_elapsed {
             return Some(final_elapsed);
         }
-        self.iteration_started.map(|start| start.elapsed())
+        self.iteration_started
+            .map(|start| self.now().saturating_duration_since(start))
+    }
+
+    /// Estimates time remaining until the run hits its iteration or budget limit.
+    ///
+    /// Uses the average duration (and cost) of completed iterations. When both
+    /// `max_iterations` and `max_cost_usd` are set, the nearer limit wins.
+    /// Returns `None` once the loop has completed, before any iteration has
+    /// finished, or when no limit is configured.
+    pub fn estimate_remaining(&self) -> Option<Duration> {
+        if self.loop_completed {
+            return None;
+        }
+        let loop_started = self.loop_started?;
+        let current_started = self.latest_iteration_started?;
+        let completed = self.iterations.len().saturating_sub(1) as u32;
+        if completed == 0 {
+            return None;
+        }
+        let avg_duration = current_started.saturating_duration_since(loop_started) / completed;
+
+        let by_iterations = self
+            .max_iterations
+            .map(|max| f64::from(max.saturating_sub(completed)));
+        let by_budget = self.max_cost_usd.and_then(|max| {
+            let avg_cost = self.total_cost_usd / f64::from(completed);
+            (avg_cost > 0.0).then(|| ((max - self.total_cost_usd) / avg_cost).max(0.0))
+        });
+        let remaining_iterations = match (by_iterations, by_budget) {
+            (Some(a), Some(b)) => a.min(b),
+            (a, b) => a.or(b)?,
+        };
+
+        // The current iteration is already partly done
+        let total =
+            Duration::try_from_secs_f64(avg_duration.as_secs_f64() * remaining_iterations).ok()?;
+        Some(total.saturating_sub(self.now().saturating_duration_since(current_started)))
     }
 
     /// True if event received in last 2 seconds.
     pub fn is_active(&self) -> bool {
         self.last_event_at
-            .is_some_and(|t| t.elapsed() < Duration::from_secs(2))
+            .is_some_and(|t| self.now().saturating_duration_since(t) < Duration::from_secs(2))
     }
 
     /// True if iteration changed since last check.
@@ -367,29 +611,31 @@ impl TuiState {
     // ========================================================================
 
     /// Starts a new iteration, creating a new IterationBuffer.
-    /// If following_latest is true, current_view is updated to the new iteration.
-    /// If not following, sets the new_iteration_alert to notify the user.
+    /// If following the latest iteration, the view moves to the new one;
+    /// otherwise the new iteration alert notifies the user.
     pub fn start_new_iteration(&mut self) {
         let number = (self.iterations.len() + 1) as u32;
-        self.iterations.push(IterationBuffer::new(number));
+        let mut buffer = IterationBuffer::new(number);
+        buffer.environment = self.next_environment.take();
+        self.iterations.push(buffer);
+        self.latest_iteration_started = Some(self.now());
 
-        // Auto-follow if enabled
-        if self.following_latest {
-            self.current_view = self.iterations.len().saturating_sub(1);
+        if self.nav.following_latest {
+            self.show_iteration(self.iterations.len() - 1);
         } else {
             // Alert user about new iteration when reviewing history
-            self.new_iteration_alert = Some(number as usize);
+            self.nav.new_iteration_alert = Some(number as usize);
         }
     }
 
     /// Returns a reference to the currently viewed iteration buffer.
     pub fn current_iteration(&self) -> Option<&IterationBuffer> {
-        self.iterations.get(self.current_view)
+        self.iterations.get(self.nav.view)
     }
 
     /// Returns a mutable reference to the currently viewed iteration buffer.
     pub fn current_iteration_mut(&mut self) -> Option<&mut IterationBuffer> {
-        self.iterations.get_mut(self.current_view)
+        self.iterations.get_mut(self.nav.view)
     }
 
     /// Returns a shared handle to the current iteration's lines buffer.
@@ -400,7 +646,7 @@ impl TuiState {
         &self,
     ) -> Option<std::sync::Arc<std::sync::Mutex<Vec<Line<'static>>>>> {
         self.iterations
-            .get(self.current_view)
+            .get(self.nav.view)
             .map(|buffer| buffer.lines_handle())
     }
 
@@ -416,29 +662,154 @@ impl TuiState {
         self.iterations.last().map(|buffer| buffer.lines_handle())
     }
 
-    /// Navigates to the next iteration (if not at the last one).
-    /// If reaching the last iteration, re-enables following_latest and clears alerts.
-    pub fn navigate_next(&mut self) {
-        if self.iterations.is_empty() {
+    /// Returns a shared handle to the latest iteration's tool result references.
+    pub fn latest_iteration_tool_results_handle(&self) -> Option<Arc<Mutex<Vec<(usize, String)>>>> {
+        self.iterations
+            .last()
+            .map(|buffer| Arc::clone(&buffer.tool_results))
+    }
+
+    /// Buffers for a `TuiStreamHandler` that streams a whole run: each
+    /// iteration start begins a new iteration in `state`.
+    pub fn iteration_buffers(state: &Arc<Mutex<TuiState>>) -> IterationBuffers {
+        let state = Arc::clone(state);
+        Box::new(move |info| {
+            let mut state = state.lock().ok()?;
+            state.start_new_iteration();
+            state.set_latest_hat(HatId::new(&info.hat));
+            Some((
+                state.latest_iteration_lines_handle()?,
+                state.latest_iteration_tool_results_handle()?,
+            ))
+        })
+    }
+
+    /// Sets the store full tool results are loaded from.
+    pub fn set_tool_result_store(&mut self, store: ToolResultStore) {
+        self.tool_result_store = Some(store);
+    }
+
+    /// Opens the full result of the last tool result visible in the current
+    /// iteration. Does nothing when no result is in view.
+    pub fn open_tool_result(&mut self, viewport_height: usize) {
+        let Some(store) = &self.tool_result_store else {
             return;
+        };
+        let Some(tool_use_id) = self
+            .current_iteration()
+            .and_then(|buffer| buffer.tool_result_in_view(viewport_height))
+        else {
+            return;
+        };
+
+        let lines = match store.load(&tool_use_id) {
+            Ok(content) => content.lines().map(str::to_string).collect(),
+            Err(e) => vec![format!("Failed to load tool result: {e}")],
+        };
+        // Edit diffs are stored next to the results as `<id>-diff`
+        let (title, word_diff) = match tool_use_id.strip_suffix("-diff") {
+            Some(id) => (format!("Edit diff {id}"), true),
+            None => (format!("Tool result {tool_use_id}"), false),
+        };
+        self.result_viewer = Some(ResultViewer {
+            title,
+            lines,
+            scroll: 0,
+            word_diff,
+        });
+    }
+
+    /// Records the hat the latest iteration runs as.
+    pub fn set_latest_hat(&mut self, hat: HatId) {
+        if let Some(buffer) = self.iterations.last_mut() {
+            buffer.hat = Some(hat);
         }
-        let max_index = self.iterations.len().saturating_sub(1);
-        if self.current_view < max_index {
-            self.current_view += 1;
-            // Re-enable following when reaching the latest
-            if self.current_view == max_index {
-                self.following_latest = true;
-                self.new_iteration_alert = None;
-            }
+    }
+
+    /// Records the environment snapshot of the iteration about to start; the
+    /// next [`start_new_iteration`](Self::start_new_iteration) attaches it.
+    pub fn set_next_environment(&mut self, lines: Vec<String>) {
+        self.next_environment = Some(lines);
+    }
+
+    /// Opens the environment snapshot of the viewed iteration.
+    pub fn open_iteration_info(&mut self) {
+        let Some(buffer) = self.current_iteration() else {
+            return;
+        };
+        let lines = buffer
+            .environment
+            .clone()
+            .unwrap_or_else(|| vec!["No environment snapshot for this iteration.".to_string()]);
+        self.result_viewer = Some(ResultViewer {
+            title: format!("Iteration {} environment", buffer.number),
+            lines,
+            scroll: 0,
+            word_diff: false,
+        });
+    }
+
+    /// Opens the settings popup.
+    pub fn open_settings(&mut self) {
+        self.settings_editor = Some(SettingsEditor::default());
+    }
+
+    /// Starts or stops following the latest iteration. Starting jumps to it.
+    pub fn set_following(&mut self, follow: bool) {
+        if follow && !self.iterations.is_empty() {
+            self.show_iteration(self.iterations.len() - 1);
+        }
+        self.nav.following_latest = follow;
+    }
+
+    /// Returns the text to hand to an external pager: the open tool result if
+    /// the result viewer is showing, otherwise the current iteration's
+    /// transcript with its styling as ANSI escapes.
+    pub fn pager_text(&self) -> Option<String> {
+        if let Some(viewer) = &self.result_viewer {
+            let mut text = viewer.lines.join("\n");
+            text.push('\n');
+            return Some(text);
+        }
+        let buffer = self.current_iteration()?;
+        let lines = buffer.lines.lock().unwrap();
+        Some(crate::pager::lines_to_ansi(&lines))
+    }
+
+    /// Navigates to the next iteration (if not at the last one).
+    /// Reaching the last iteration resumes following it and clears the alert.
+    pub fn navigate_next(&mut self) {
+        if self.nav.view + 1 < self.iterations.len() {
+            self.show_iteration(self.nav.view + 1);
         }
     }
 
-    /// Navigates to the previous iteration (if not at the first one).
-    /// Disables following_latest when navigating backwards.
+    /// Navigates to the previous iteration (if not at the first one),
+    /// which stops following the latest iteration.
     pub fn navigate_prev(&mut self) {
-        if self.current_view > 0 {
-            self.current_view -= 1;
-            self.following_latest = false;
+        if self.nav.view > 0 {
+            self.show_iteration(self.nav.view - 1);
+        }
+    }
+
+    /// Views the iteration at index `view`.
+    ///
+    /// The view follows new iterations exactly when it is on the latest one.
+    /// Moving to another iteration drops the marked line and re-runs an
+    /// active search there, so matches always refer to the viewed buffer.
+    fn show_iteration(&mut self, view: usize) {
+        let changed = view != self.nav.view;
+        self.nav.view = view;
+        self.nav.following_latest = view + 1 >= self.iterations.len();
+        if self.nav.following_latest {
+            self.nav.new_iteration_alert = None;
+        }
+        if changed {
+            self.nav.line = None;
+            if let Some(query) = self.search_state.query.clone() {
+                self.search_state.matches = self.find_matches(&query);
+                self.search_state.current_match = 0;
+            }
         }
     }
 
@@ -456,20 +827,19 @@ impl TuiState {
     /// Search is case-insensitive.
     pub fn search(&mut self, query: &str) {
         self.search_state.query = Some(query.to_string());
-        self.search_state.matches.clear();
+        self.search_state.matches = self.find_matches(query);
         self.search_state.current_match = 0;
 
-        // Check if we have an iteration to search
-        if self.iterations.get(self.current_view).is_none() {
-            return;
+        // Jump to first match if any exist
+        if !self.search_state.matches.is_empty() {
+            self.jump_to_current_match();
         }
+    }
 
+    /// Finds the case-insensitive matches of `query` in the viewed iteration.
+    fn find_matches(&self, query: &str) -> Vec<(usize, usize)> {
         let query_lower = query.to_lowercase();
-
-        // Collect matches first (avoid borrow conflicts)
-        let matches: Vec<(usize, usize)> = self
-            .iterations
-            .get(self.current_view)
+        self.current_iteration()
             .and_then(|buffer| {
                 let lines = buffer.lines.lock().ok()?;
                 let mut found = Vec::new();
@@ -488,14 +858,7 @@ impl TuiState {
                 }
                 Some(found)
             })
-            .unwrap_or_default();
-
-        self.search_state.matches = matches;
-
-        // Jump to first match if any exist
-        if !self.search_state.matches.is_empty() {
-            self.jump_to_current_match();
-        }
+            .unwrap_or_default()
     }
 
     /// Navigates to the next match, cycling back to the first if at the end.
@@ -528,26 +891,94 @@ impl TuiState {
         self.search_state.clear();
     }
 
-    /// Jumps to the current match by adjusting scroll_offset to show the match line.
-    fn jump_to_current_match(&mut self) {
-        if self.search_state.matches.is_empty() {
-            return;
+    /// Returns the id of the current search match's line.
+    pub fn current_match_line_id(&self) -> Option<LineId> {
+        let (line_idx, _) = self
+            .search_state
+            .matches
+            .get(self.search_state.current_match)?;
+        let buffer = self.current_iteration()?;
+        Some(LineId::from_index(buffer.number, *line_idx))
+    }
+
+    // ========================================================================
+    // Jump Methods
+    // ========================================================================
+
+    /// Shows the line `id` near the top of the view and marks it.
+    ///
+    /// Returns false, leaving the view alone, if the line doesn't exist.
+    pub fn jump_to(&mut self, id: LineId) -> bool {
+        let Some(view) = self
+            .iterations
+            .iter()
+            .position(|buffer| buffer.number == id.iteration)
+        else {
+            return false;
+        };
+        let buffer = &mut self.iterations[view];
+        if id.index() >= buffer.line_count() {
+            return false;
         }
+        buffer.scroll_offset = id.index().saturating_sub(JUMP_CONTEXT_LINES);
+        buffer.following_bottom = false;
 
-        let (line_idx, _) = self.search_state.matches[self.search_state.current_match];
+        self.show_iteration(view);
+        self.nav.line = Some(id);
+        true
+    }
 
-        // Adjust scroll to show the match line
-        // Use a default viewport height for calculation (will be overridden by actual render)
-        let viewport_height = 20;
-        if let Some(buffer) = self.current_iteration_mut() {
-            // If the match line is above the current view, scroll up to it
-            if line_idx < buffer.scroll_offset {
-                buffer.scroll_offset = line_idx;
-            }
-            // If the match line is below the current view, scroll down to show it
-            else if line_idx >= buffer.scroll_offset + viewport_height {
-                buffer.scroll_offset = line_idx.saturating_sub(viewport_height / 2);
-            }
+    /// Jumps to `id` as soon as the line exists.
+    pub fn request_jump(&mut self, id: LineId) {
+        self.nav.pending_jump = Some(id);
+        self.apply_pending_jump();
+    }
+
+    /// Retries a requested jump; call after new output has been added.
+    pub fn apply_pending_jump(&mut self) {
+        if let Some(id) = self.nav.pending_jump
+            && self.jump_to(id)
+        {
+            self.nav.pending_jump = None;
+        }
+    }
+
+    /// Pauses the loop for the alert `name`, remembering the latest line
+    /// containing the matched text so the footer can point at it, and
+    /// bringing that line into view.
+    pub fn pause_for_alert(&mut self, name: &str, matched: &str) {
+        self.alert_pause = Some(name.to_string());
+        self.alert_pause_at = None;
+
+        let needle = matched.lines().map(str::trim).find(|line| !line.is_empty());
+        let (Some(needle), Some(buffer)) = (needle, self.iterations.last()) else {
+            return;
+        };
+        let Ok(lines) = buffer.lines.lock() else {
+            return;
+        };
+        let at = lines
+            .iter()
+            .rposition(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+                    .contains(needle)
+            })
+            .map(|index| LineId::from_index(buffer.number, index));
+        drop(lines);
+
+        self.alert_pause_at = at;
+        if let Some(at) = at {
+            self.jump_to(at);
+        }
+    }
+
+    /// Jumps to the current match's line like any other line jump.
+    fn jump_to_current_match(&mut self) {
+        if let Some(id) = self.current_match_line_id() {
+            self.jump_to(id);
         }
     }
 }
@@ -576,12 +1007,20 @@ pub struct IterationBuffer {
     pub number: u32,
     /// Formatted lines of output (shared for streaming)
     pub lines: Arc<Mutex<Vec<Line<'static>>>>,
+    /// Line index and tool use ID of each tool result preview in `lines`
+    pub tool_results: Arc<Mutex<Vec<(usize, String)>>>,
+    /// Hat the iteration runs as
+    pub hat: Option<HatId>,
+    /// Environment snapshot lines captured when the iteration started
+    pub environment: Option<Vec<String>>,
     /// Scroll position within this buffer
     pub scroll_offset: usize,
     /// Whether to auto-scroll to bottom as new content arrives.
     /// Starts true, becomes false when user scrolls up, restored when user
     /// scrolls to bottom (G key) or manually scrolls down to reach bottom.
     pub following_bottom: bool,
+    /// Number of trailing lines that belong to the provisional block
+    provisional: usize,
 }
 
 impl IterationBuffer {
@@ -590,8 +1029,12 @@ impl IterationBuffer {
         Self {
             number,
             lines: Arc::new(Mutex::new(Vec::new())),
+            tool_results: Arc::new(Mutex::new(Vec::new())),
+            hat: None,
+            environment: None,
             scroll_offset: 0,
             following_bottom: true, // Start following bottom for auto-scroll
+            provisional: 0,
         }
     }
 
@@ -610,6 +1053,30 @@ impl IterationBuffer {
         }
     }
 
+    /// Appends a finalized block of lines in place of the provisional block.
+    ///
+    /// The swap happens under one lock, so a render never sees the block
+    /// half-appended or alongside the provisional lines it replaces.
+    pub fn append_block(&mut self, block: Vec<Line<'static>>) {
+        if let Ok(mut lines) = self.lines.lock() {
+            let keep = lines.len().saturating_sub(self.provisional);
+            lines.truncate(keep);
+            lines.extend(block);
+            self.provisional = 0;
+        }
+    }
+
+    /// Replaces the trailing provisional block, e.g. text still streaming
+    /// whose formatting isn't final yet.
+    pub fn replace_provisional(&mut self, block: Vec<Line<'static>>) {
+        if let Ok(mut lines) = self.lines.lock() {
+            let keep = lines.len().saturating_sub(self.provisional);
+            lines.truncate(keep);
+            self.provisional = block.len();
+            lines.extend(block);
+        }
+    }
+
     /// Returns the total number of lines in the buffer.
     pub fn line_count(&self) -> usize {
         self.lines.lock().map(|l| l.len()).unwrap_or(0)
@@ -664,6 +1131,18 @@ impl IterationBuffer {
         self.following_bottom = true;
     }
 
+    /// Returns the tool use ID of the last tool result preview in view.
+    pub fn tool_result_in_view(&self, viewport_height: usize) -> Option<String> {
+        let visible = self.scroll_offset..self.scroll_offset + viewport_height;
+        self.tool_results
+            .lock()
+            .ok()?
+            .iter()
+            .rev()
+            .find(|(line, _)| visible.contains(line))
+            .map(|(_, id)| id.clone())
+    }
+
     /// Calculates the maximum scroll offset for the given viewport height.
     fn max_scroll_offset(&self, viewport_height: usize) -> usize {
         self.lines
@@ -708,6 +1187,26 @@ mod tests {
             assert_eq!(lines[2].spans[0].content, "third");
         }
 
+        #[test]
+        fn append_block_replaces_provisional_lines() {
+            let mut buffer = IterationBuffer::new(1);
+            buffer.append_line(Line::from("intro"));
+            buffer.replace_provisional(vec![Line::from("**bo")]);
+            buffer.replace_provisional(vec![Line::from("**bold"), Line::from("te")]);
+            assert_eq!(buffer.line_count(), 3);
+
+            buffer.append_block(vec![Line::from("bold"), Line::from("text")]);
+            buffer.replace_provisional(vec![Line::from("next")]);
+            let lines: Vec<String> = buffer
+                .lines
+                .lock()
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            assert_eq!(lines, ["intro", "bold", "text", "next"]);
+        }
+
         #[test]
         fn line_count_returns_correct_count() {
             let mut buffer = IterationBuffer::new(1);
@@ -1108,17 +1607,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hat_changes_are_recorded_in_history() {
+        let mut state = TuiState::new();
+
+        state.update(&Event::new("task.resume", ""));
+        state.update(&Event::new("build.task", ""));
+        state.update(&Event::new("build.task", "")); // same hat, no new entry
+        state.update(&Event::new("build.done", ""));
+
+        let history: Vec<&str> = state
+            .hat_history
+            .iter()
+            .map(|(id, _)| id.as_str())
+            .collect();
+        assert_eq!(history, vec!["planner", "builder"]);
+        assert_eq!(state.get_pending_hat_display(), "📋Planner");
+    }
+
+    #[test]
+    fn hat_history_is_bounded() {
+        let mut state = TuiState::new();
+        for _ in 0..HAT_HISTORY_LEN {
+            state.update(&Event::new("build.task", ""));
+            state.update(&Event::new("build.done", ""));
+        }
+
+        assert_eq!(state.hat_history.len(), HAT_HISTORY_LEN);
+    }
+
+    #[test]
+    fn task_start_preserves_hat_colors() {
+        let mut state = TuiState::new();
+        state.set_hat_colors(HashMap::from([(HatId::new("builder"), Color::Cyan)]));
+
+        state.update(&Event::new("task.start", ""));
+
+        assert_eq!(state.hat_color(&HatId::new("builder")), Some(Color::Cyan));
+    }
+
+    #[test]
+    fn iteration_color_follows_the_iteration_hat() {
+        let mut state = TuiState::new();
+        state.set_hat_colors(HashMap::from([(HatId::new("builder"), Color::Cyan)]));
+        state.start_new_iteration();
+        assert_eq!(state.iteration_color(&state.iterations[0]), None);
+
+        state.set_latest_hat(HatId::new("builder"));
+        state.start_new_iteration();
+        state.set_latest_hat(HatId::new("reviewer"));
+
+        assert_eq!(
+            state.iteration_color(&state.iterations[0]),
+            Some(Color::Cyan)
+        );
+        assert_eq!(
+            state.iteration_color(&state.iterations[1]),
+            Some(ralph_adapters::default_hat_color("reviewer"))
+        );
+    }
+
     #[test]
     fn loop_terminate_freezes_iteration_timer() {
         // Given a running iteration with elapsed time
-        let mut state = TuiState::new();
+        let clock = ralph_core::MockClock::new();
+        let mut state = TuiState::new().with_clock(Arc::new(clock.clone()));
         let start_event = Event::new("build.task", "");
         state.update(&start_event);
 
         // Verify timer is running
         assert!(state.iteration_started.is_some());
-        let elapsed_before = state.get_iteration_elapsed().unwrap();
-        assert!(elapsed_before.as_nanos() > 0);
+        clock.advance(Duration::from_secs(42));
+        assert_eq!(state.get_iteration_elapsed(), Some(Duration::from_secs(42)));
 
         // When loop.terminate is received
         let terminate_event = Event::new("loop.terminate", "");
@@ -1129,16 +1689,28 @@ mod tests {
         assert!(state.final_iteration_elapsed.is_some());
 
         // The elapsed time should be frozen (not increasing)
-        let frozen_elapsed = state.get_iteration_elapsed().unwrap();
-        std::thread::sleep(std::time::Duration::from_millis(10));
-        let elapsed_after_sleep = state.get_iteration_elapsed().unwrap();
-
+        clock.advance(Duration::from_secs(10));
         assert_eq!(
-            frozen_elapsed, elapsed_after_sleep,
+            state.get_iteration_elapsed(),
+            Some(Duration::from_secs(42)),
             "Timer should be frozen after loop.terminate"
         );
     }
 
+    #[test]
+    fn activity_lapses_two_seconds_after_last_event() {
+        let clock = ralph_core::MockClock::new();
+        let mut state = TuiState::new().with_clock(Arc::new(clock.clone()));
+        assert!(!state.is_active());
+
+        state.update(&Event::new("build.task", ""));
+        clock.advance(Duration::from_millis(1999));
+        assert!(state.is_active());
+
+        clock.advance(Duration::from_millis(1));
+        assert!(!state.is_active());
+    }
+
     // ========================================================================
     // TuiState Iteration Management Tests
     // ========================================================================
@@ -1180,7 +1752,7 @@ mod tests {
             state.start_new_iteration();
             state.start_new_iteration();
             state.start_new_iteration();
-            state.current_view = 1;
+            state.nav.view = 1;
 
             // When current_iteration() is called
             let current = state.current_iteration();
@@ -1217,14 +1789,14 @@ mod tests {
             state.start_new_iteration();
             state.start_new_iteration();
             state.start_new_iteration();
-            state.current_view = 1;
-            state.following_latest = false;
+            state.nav.view = 1;
+            state.nav.following_latest = false;
 
             // When navigate_next() is called
             state.navigate_next();
 
             // Then current_view == 2
-            assert_eq!(state.current_view, 2);
+            assert_eq!(state.nav.view, 2);
         }
 
         #[test]
@@ -1234,13 +1806,13 @@ mod tests {
             state.start_new_iteration();
             state.start_new_iteration();
             state.start_new_iteration();
-            state.current_view = 2;
+            state.nav.view = 2;
 
             // When navigate_prev() is called
             state.navigate_prev();
 
             // Then current_view == 1
-            assert_eq!(state.current_view, 1);
+            assert_eq!(state.nav.view, 1);
         }
 
         #[test]
@@ -1250,13 +1822,13 @@ mod tests {
             state.start_new_iteration();
             state.start_new_iteration();
             state.start_new_iteration();
-            state.current_view = 2;
+            state.nav.view = 2;
 
             // When navigate_next() is called
             state.navigate_next();
 
             // Then current_view stays at 2
-            assert_eq!(state.current_view, 2);
+            assert_eq!(state.nav.view, 2);
         }
 
         #[test]
@@ -1264,13 +1836,13 @@ mod tests {
             // Given TuiState with current_view = 0
             let mut state = TuiState::new();
             state.start_new_iteration();
-            state.current_view = 0;
+            state.nav.view = 0;
 
             // When navigate_prev() is called
             state.navigate_prev();
 
             // Then current_view stays at 0
-            assert_eq!(state.current_view, 0);
+            assert_eq!(state.nav.view, 0);
         }
 
         #[test]
@@ -1280,7 +1852,7 @@ mod tests {
             let state = TuiState::new();
 
             // Then following_latest == true
-            assert!(state.following_latest);
+            assert!(state.nav.following_latest);
         }
 
         #[test]
@@ -1290,14 +1862,14 @@ mod tests {
             state.start_new_iteration();
             state.start_new_iteration();
             state.start_new_iteration();
-            state.current_view = 2;
-            state.following_latest = true;
+            state.nav.view = 2;
+            state.nav.following_latest = true;
 
             // When navigate_prev() is called
             state.navigate_prev();
 
             // Then following_latest == false
-            assert!(!state.following_latest);
+            assert!(!state.nav.following_latest);
         }
 
         #[test]
@@ -1307,14 +1879,14 @@ mod tests {
             state.start_new_iteration();
             state.start_new_iteration();
             state.start_new_iteration();
-            state.current_view = 1;
-            state.following_latest = false;
+            state.nav.view = 1;
+            state.nav.following_latest = false;
 
             // When navigate_next() reaches the last iteration
             state.navigate_next(); // 1 -> 2 (last)
 
             // Then following_latest == true
-            assert!(state.following_latest);
+            assert!(state.nav.following_latest);
         }
 
         #[test]
@@ -1333,12 +1905,12 @@ mod tests {
         #[test]
         fn start_new_iteration_auto_follows_latest() {
             let mut state = TuiState::new();
-            state.following_latest = true;
+            state.nav.following_latest = true;
             state.start_new_iteration();
             state.start_new_iteration();
 
             // When following latest, current_view should track new iterations
-            assert_eq!(state.current_view, 1); // Index of second iteration
+            assert_eq!(state.nav.view, 1); // Index of second iteration
         }
 
         // ========================================================================
@@ -1357,7 +1929,7 @@ mod tests {
             state.iterations[1].scroll_offset = 0;
 
             // When switching between iterations
-            state.current_view = 0;
+            state.nav.view = 0;
             assert_eq!(
                 state.current_iteration().unwrap().scroll_offset,
                 5,
@@ -1405,7 +1977,7 @@ mod tests {
             state.iterations[2].scroll_offset = 10;
 
             // When scrolling in iteration 2
-            state.current_view = 1;
+            state.nav.view = 1;
             state.current_iteration_mut().unwrap().scroll_down(10);
 
             // Then only iteration 2's scroll changed
@@ -1439,21 +2011,21 @@ mod tests {
             state.start_new_iteration(); // Iteration 3
 
             // Then new_iteration_alert is set to the new iteration number
-            assert_eq!(state.new_iteration_alert, Some(3));
+            assert_eq!(state.nav.new_iteration_alert, Some(3));
         }
 
         #[test]
         fn new_iteration_alert_not_set_when_following() {
             // Given following_latest = true
             let mut state = TuiState::new();
-            state.following_latest = true;
+            state.nav.following_latest = true;
             state.start_new_iteration();
 
             // When start_new_iteration() is called
             state.start_new_iteration();
 
             // Then new_iteration_alert remains None
-            assert_eq!(state.new_iteration_alert, None);
+            assert_eq!(state.nav.new_iteration_alert, None);
         }
 
         #[test]
@@ -1463,16 +2035,16 @@ mod tests {
             state.start_new_iteration();
             state.start_new_iteration();
             state.start_new_iteration();
-            state.current_view = 0;
-            state.following_latest = false;
-            state.new_iteration_alert = Some(3);
+            state.nav.view = 0;
+            state.nav.following_latest = false;
+            state.nav.new_iteration_alert = Some(3);
 
             // When navigation restores following_latest = true
             state.navigate_next(); // 0 -> 1
             state.navigate_next(); // 1 -> 2 (last, restores following)
 
             // Then new_iteration_alert is cleared to None
-            assert_eq!(state.new_iteration_alert, None);
+            assert_eq!(state.nav.new_iteration_alert, None);
         }
 
         #[test]
@@ -1482,16 +2054,16 @@ mod tests {
             state.start_new_iteration();
             state.start_new_iteration();
             state.start_new_iteration();
-            state.current_view = 0;
-            state.following_latest = false;
-            state.new_iteration_alert = Some(3);
+            state.nav.view = 0;
+            state.nav.following_latest = false;
+            state.nav.new_iteration_alert = Some(3);
 
             // When navigate_next() but not reaching last
             state.navigate_next(); // 0 -> 1
 
             // Then alert is still set (not at latest yet)
-            assert_eq!(state.new_iteration_alert, Some(3));
-            assert!(!state.following_latest);
+            assert_eq!(state.nav.new_iteration_alert, Some(3));
+            assert!(!state.nav.following_latest);
         }
 
         #[test]
@@ -1503,13 +2075,185 @@ mod tests {
             state.navigate_prev(); // Go back, stop following
 
             state.start_new_iteration(); // 3 arrives
-            assert_eq!(state.new_iteration_alert, Some(3));
+            assert_eq!(state.nav.new_iteration_alert, Some(3));
 
             // When another iteration arrives
             state.start_new_iteration(); // 4 arrives
 
             // Then alert should show the newest
-            assert_eq!(state.new_iteration_alert, Some(4));
+            assert_eq!(state.nav.new_iteration_alert, Some(4));
+        }
+    }
+
+    // ========================================================================
+    // Run ETA Estimate Tests
+    // ========================================================================
+
+    mod run_eta {
+        use super::*;
+        use ralph_core::MockClock;
+
+        /// State with `completed` iterations of 60s each; the current one began 10s ago.
+        fn state_with_history(completed: u32) -> TuiState {
+            let clock = MockClock::new();
+            let mut state = TuiState::new().with_clock(Arc::new(clock.clone()));
+            state.start_new_iteration();
+            for _ in 0..completed {
+                clock.advance(Duration::from_secs(60));
+                state.start_new_iteration();
+            }
+            clock.advance(Duration::from_secs(10));
+            state
+        }
+
+        #[test]
+        fn no_estimate_before_first_iteration_completes() {
+            let mut state = TuiState::new();
+            state.max_iterations = Some(10);
+            state.start_new_iteration();
+            assert!(state.estimate_remaining().is_none());
+        }
+
+        #[test]
+        fn no_estimate_without_limits() {
+            let state = state_with_history(2);
+            assert!(state.estimate_remaining().is_none());
+        }
+
+        #[test]
+        fn estimate_from_remaining_iterations() {
+            let mut state = state_with_history(2);
+            state.max_iterations = Some(5);
+            // 3 iterations left at 60s each, minus 10s already spent on the current one
+            assert_eq!(state.estimate_remaining(), Some(Duration::from_secs(170)));
+        }
+
+        #[test]
+        fn estimate_uses_nearer_budget_limit() {
+            let mut state = state_with_history(2);
+            state.max_iterations = Some(100);
+            state.total_cost_usd = 1.0; // $0.50 per iteration
+            state.max_cost_usd = Some(2.0);
+            // Budget allows 2 more iterations
+            assert_eq!(state.estimate_remaining(), Some(Duration::from_secs(110)));
+        }
+
+        #[test]
+        fn task_start_preserves_configured_limits() {
+            let mut state = TuiState::new();
+            state.max_iterations = Some(50);
+            state.max_cost_usd = Some(10.0);
+
+            state.update(&Event::new("task.start", ""));
+
+            assert_eq!(state.max_iterations, Some(50));
+            assert_eq!(state.max_cost_usd, Some(10.0));
+        }
+
+        #[test]
+        fn no_estimate_after_completion() {
+            let mut state = state_with_history(2);
+            state.max_iterations = Some(5);
+            state.loop_completed = true;
+            assert!(state.estimate_remaining().is_none());
+        }
+    }
+
+    mod tool_results {
+        use super::*;
+        use ratatui::text::Line;
+
+        fn state_with_results(store: ToolResultStore) -> TuiState {
+            let mut state = TuiState::new();
+            state.set_tool_result_store(store);
+            state.start_new_iteration();
+            let buffer = state.current_iteration_mut().unwrap();
+            for i in 0..30 {
+                buffer.append_line(Line::from(format!("line {i}")));
+            }
+            *buffer.tool_results.lock().unwrap() =
+                vec![(2, "tool_a".to_string()), (5, "tool_b".to_string())];
+            state
+        }
+
+        #[test]
+        fn tool_result_in_view_picks_last_visible() {
+            let temp = tempfile::TempDir::new().unwrap();
+            let mut state = state_with_results(ToolResultStore::new(temp.path()));
+            let buffer = state.current_iteration_mut().unwrap();
+
+            assert_eq!(buffer.tool_result_in_view(10).as_deref(), Some("tool_b"));
+            buffer.scroll_offset = 3;
+            assert_eq!(buffer.tool_result_in_view(2).as_deref(), None);
+            assert_eq!(buffer.tool_result_in_view(3).as_deref(), Some("tool_b"));
+            buffer.scroll_offset = 10;
+            assert_eq!(buffer.tool_result_in_view(10), None);
+        }
+
+        #[test]
+        fn pager_text_defaults_to_transcript() {
+            let temp = tempfile::TempDir::new().unwrap();
+            let state = state_with_results(ToolResultStore::new(temp.path()));
+
+            let text = state.pager_text().unwrap();
+            assert!(text.starts_with("line 0\nline 1\n"));
+            assert!(text.ends_with("line 29\n"));
+            assert_eq!(TuiState::new().pager_text(), None);
+        }
+
+        #[test]
+        fn open_tool_result_loads_full_text() {
+            let temp = tempfile::TempDir::new().unwrap();
+            let store = ToolResultStore::new(temp.path());
+            store.save("tool_b", "full\nresult\ntext").unwrap();
+            let mut state = state_with_results(store);
+
+            state.open_tool_result(10);
+
+            assert_eq!(state.pager_text().as_deref(), Some("full\nresult\ntext\n"));
+            let viewer = state.result_viewer.as_ref().unwrap();
+            assert_eq!(viewer.title, "Tool result tool_b");
+            assert_eq!(viewer.lines, vec!["full", "result", "text"]);
+        }
+
+        #[test]
+        fn iteration_info_shows_viewed_iteration_environment() {
+            let mut state = TuiState::new();
+            state.set_next_environment(vec!["Git HEAD:  abc (main)".to_string()]);
+            state.start_new_iteration();
+            state.start_new_iteration();
+
+            state.open_iteration_info();
+            let viewer = state.result_viewer.take().unwrap();
+            assert_eq!(viewer.title, "Iteration 2 environment");
+            assert_eq!(
+                viewer.lines,
+                vec!["No environment snapshot for this iteration."]
+            );
+
+            state.navigate_prev();
+            state.open_iteration_info();
+            let viewer = state.result_viewer.take().unwrap();
+            assert_eq!(viewer.lines, vec!["Git HEAD:  abc (main)"]);
+        }
+
+        #[test]
+        fn result_viewer_scroll_is_bounded() {
+            let mut viewer = ResultViewer {
+                title: "t".to_string(),
+                lines: (0..10).map(|i| i.to_string()).collect(),
+                scroll: 0,
+                word_diff: false,
+            };
+
+            viewer.scroll_down(100, 4);
+            assert_eq!(viewer.scroll, 6);
+            viewer.scroll_up(2);
+            assert_eq!(viewer.scroll, 4);
+            viewer.scroll_up(100);
+            assert_eq!(viewer.scroll, 0);
+            viewer.scroll_bottom(4);
+            assert_eq!(viewer.scroll, 6);
         }
     }
 
@@ -1728,10 +2472,11 @@ mod tests {
             // Navigate to second match (at line 30)
             state.next_match();
 
-            // Then scroll should position line 30 in view
+            // Then line 30 is shown with the usual jump context above it
             let buffer = state.current_iteration().unwrap();
-            // Match at line 30, scroll should be adjusted
-            assert!(buffer.scroll_offset <= 30, "scroll should show line 30");
+            assert_eq!(buffer.scroll_offset, 30 - JUMP_CONTEXT_LINES);
+            assert!(!buffer.following_bottom);
+            assert_eq!(state.nav.line, Some(LineId::from_index(1, 30)));
         }
 
         #[test]
@@ -1743,8 +2488,8 @@ mod tests {
             state.start_new_iteration(); // iteration 3
 
             // User navigates back to iteration 1
-            state.current_view = 0;
-            state.following_latest = false;
+            state.nav.view = 0;
+            state.nav.following_latest = false;
 
             // When getting line handles
             let current_handle = state.current_iteration_lines_handle();
@@ -1792,8 +2537,8 @@ mod tests {
             }
 
             // User navigates to iteration 3 (index 2)
-            state.current_view = 2;
-            state.following_latest = false;
+            state.nav.view = 2;
+            state.nav.following_latest = false;
 
             // New iteration starts (iteration 7)
             state.start_new_iteration();
@@ -1827,4 +2572,221 @@ mod tests {
             );
         }
     }
+
+    mod jumps {
+        use super::*;
+
+        fn state_with_iterations(count: u32, lines: usize) -> TuiState {
+            let mut state = TuiState::new();
+            for _ in 0..count {
+                state.start_new_iteration();
+                let buffer = state.iterations.last_mut().unwrap();
+                for i in 0..lines {
+                    buffer.append_line(Line::from(format!(
+                        "iter {} line {}",
+                        buffer.number,
+                        i + 1
+                    )));
+                }
+            }
+            state
+        }
+
+        #[test]
+        fn jump_to_shows_line_in_older_iteration() {
+            let mut state = state_with_iterations(3, 50);
+            let id = LineId {
+                iteration: 2,
+                line: 20,
+            };
+
+            assert!(state.jump_to(id));
+            assert_eq!(state.nav.view, 1);
+            assert!(!state.nav.following_latest);
+            let buffer = state.current_iteration().unwrap();
+            assert_eq!(buffer.scroll_offset, 19 - JUMP_CONTEXT_LINES);
+            assert!(!buffer.following_bottom);
+            assert_eq!(state.nav.line, Some(id));
+        }
+
+        #[test]
+        fn jump_to_missing_line_leaves_view_alone() {
+            let mut state = state_with_iterations(2, 5);
+            for id in [
+                LineId {
+                    iteration: 3,
+                    line: 1,
+                },
+                LineId {
+                    iteration: 1,
+                    line: 6,
+                },
+            ] {
+                assert!(!state.jump_to(id));
+            }
+            assert_eq!(state.nav.view, 1);
+            assert_eq!(state.nav.line, None);
+        }
+
+        #[test]
+        fn pending_jump_applies_once_line_exists() {
+            let mut state = TuiState::new();
+            let id = LineId {
+                iteration: 1,
+                line: 2,
+            };
+            state.request_jump(id);
+            assert_eq!(state.nav.pending_jump, Some(id));
+
+            state.start_new_iteration();
+            let buffer = state.iterations.last_mut().unwrap();
+            buffer.append_line(Line::from("one"));
+            buffer.append_line(Line::from("two"));
+            state.apply_pending_jump();
+
+            assert_eq!(state.nav.pending_jump, None);
+            assert_eq!(state.nav.line, Some(id));
+        }
+
+        #[test]
+        fn pause_for_alert_finds_latest_matching_line() {
+            let mut state = state_with_iterations(2, 3);
+            state
+                .iterations
+                .last_mut()
+                .unwrap()
+                .append_line(Line::from("panic: iter 2 line 2 again"));
+
+            state.pause_for_alert("panics", "iter 2 line 2");
+            assert_eq!(state.alert_pause.as_deref(), Some("panics"));
+            assert_eq!(
+                state.alert_pause_at,
+                Some(LineId {
+                    iteration: 2,
+                    line: 4
+                })
+            );
+
+            state.pause_for_alert("other", "not in output");
+            assert_eq!(state.alert_pause_at, None);
+        }
+
+        #[test]
+        fn alert_pause_brings_matching_line_into_view() {
+            let mut state = state_with_iterations(2, 3);
+            state.navigate_prev();
+
+            state.pause_for_alert("panics", "iter 2 line 3");
+            assert_eq!(state.nav.view, 1);
+            assert!(state.nav.following_latest);
+            assert_eq!(state.nav.line, state.alert_pause_at);
+        }
+
+        #[test]
+        fn search_match_is_marked_and_stops_autoscroll() {
+            let mut state = state_with_iterations(1, 30);
+            state.search("line 25");
+
+            let id = LineId::from_index(1, 24);
+            assert_eq!(state.nav.line, Some(id));
+            let buffer = state.current_iteration().unwrap();
+            assert!(!buffer.following_bottom);
+            assert_eq!(buffer.scroll_offset, 24 - JUMP_CONTEXT_LINES);
+        }
+
+        #[test]
+        fn changing_iteration_reruns_search_there() {
+            let mut state = state_with_iterations(2, 3);
+            state.search("iter 2");
+            assert_eq!(state.search_state.matches.len(), 3);
+
+            state.navigate_prev();
+            assert_eq!(state.nav.line, None);
+            assert!(state.search_state.matches.is_empty());
+
+            state.navigate_next();
+            assert_eq!(state.search_state.matches.len(), 3);
+            assert_eq!(
+                state.current_match_line_id(),
+                Some(LineId::from_index(2, 0))
+            );
+        }
+
+        #[test]
+        fn changing_iteration_restarts_at_first_match_and_unmarks_line() {
+            let mut state = state_with_iterations(2, 40);
+            state.search("line 3");
+            state.next_match();
+            assert_eq!(state.search_state.current_match, 1);
+            assert!(state.nav.line.is_some());
+
+            state.navigate_prev();
+            assert_eq!(state.nav.line, None);
+            assert_eq!(state.search_state.current_match, 0);
+            assert_eq!(state.search_state.query.as_deref(), Some("line 3"));
+            assert_eq!(
+                state.current_match_line_id(),
+                Some(LineId::from_index(1, 2))
+            );
+            // Re-running the search doesn't move the newly viewed buffer
+            assert!(state.current_iteration().unwrap().following_bottom);
+        }
+
+        #[test]
+        fn jump_within_viewed_iteration_keeps_search_position() {
+            let mut state = state_with_iterations(1, 40);
+            state.search("line 3");
+            state.next_match();
+
+            assert!(state.jump_to(LineId::from_index(1, 5)));
+            assert_eq!(state.search_state.current_match, 1);
+            assert_eq!(state.nav.line, Some(LineId::from_index(1, 5)));
+        }
+
+        #[test]
+        fn search_jumps_keep_context_above_match() {
+            let mut state = state_with_iterations(1, 50);
+            state.search("line 40");
+            assert_eq!(state.nav.line, Some(LineId::from_index(1, 39)));
+            assert_eq!(
+                state.current_iteration().unwrap().scroll_offset,
+                39 - JUMP_CONTEXT_LINES
+            );
+
+            // A match near the top can't scroll above the first line
+            state.search("line 2");
+            state.prev_match();
+            state.next_match();
+            assert_eq!(state.nav.line, Some(LineId::from_index(1, 1)));
+            assert_eq!(state.current_iteration().unwrap().scroll_offset, 0);
+        }
+
+        #[test]
+        fn alert_pause_scrolls_to_line_with_context() {
+            let mut state = state_with_iterations(2, 50);
+            state.navigate_prev();
+            assert!(!state.nav.following_latest);
+
+            state.pause_for_alert("panics", "iter 2 line 30");
+            let id = LineId::from_index(2, 29);
+            assert_eq!(state.alert_pause_at, Some(id));
+            assert_eq!(state.nav.view, 1);
+            assert_eq!(state.nav.line, Some(id));
+            let buffer = state.current_iteration().unwrap();
+            assert_eq!(buffer.scroll_offset, 29 - JUMP_CONTEXT_LINES);
+            assert!(!buffer.following_bottom);
+        }
+
+        #[test]
+        fn alert_pause_without_match_leaves_view_alone() {
+            let mut state = state_with_iterations(2, 5);
+            state.navigate_prev();
+
+            state.pause_for_alert("panics", "not in output");
+            assert_eq!(state.alert_pause.as_deref(), Some("panics"));
+            assert_eq!(state.nav.view, 0);
+            assert_eq!(state.nav.line, None);
+            assert!(!state.nav.following_latest);
+        }
+    }
 }
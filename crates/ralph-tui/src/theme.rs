@@ -0,0 +1,71 @@
+//! Color themes (`tui.theme`).
+//!
+//! Widgets draw with the dark palette; other themes rewrite the colors of the
+//! finished frame, so a theme change takes effect on the next render.
+
+use ralph_core::TuiTheme;
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+
+/// Recolors a rendered frame for `theme`.
+pub fn apply(theme: TuiTheme, buf: &mut Buffer) {
+    match theme {
+        TuiTheme::Dark => {}
+        TuiTheme::Light => {
+            for cell in &mut buf.content {
+                cell.fg = light(cell.fg);
+                cell.bg = light(cell.bg);
+            }
+        }
+        TuiTheme::Mono => {
+            for cell in &mut buf.content {
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            }
+        }
+    }
+}
+
+/// Darkens the colors that fade into a light background.
+fn light(color: Color) -> Color {
+    match color {
+        Color::White => Color::Black,
+        Color::Black => Color::White,
+        Color::Gray => Color::DarkGray,
+        Color::Yellow | Color::LightYellow => Color::Red,
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::{Modifier, Style};
+
+    #[test]
+    fn mono_strips_colors_but_keeps_modifiers() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 1));
+        buf.set_string(
+            0,
+            0,
+            "ok",
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        );
+        apply(TuiTheme::Mono, &mut buf);
+        assert_eq!(buf[(0, 0)].fg, Color::Reset);
+        assert!(buf[(0, 0)].modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn light_darkens_pale_colors() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 2, 1));
+        buf.set_string(0, 0, "x", Style::default().fg(Color::Gray));
+        buf.set_string(1, 0, "y", Style::default().fg(Color::Cyan));
+        apply(TuiTheme::Light, &mut buf);
+        assert_eq!(buf[(0, 0)].fg, Color::DarkGray);
+        assert_eq!(buf[(1, 0)].fg, Color::Cyan);
+    }
+}
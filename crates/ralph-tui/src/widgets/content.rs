@@ -4,6 +4,7 @@
 //! renderer that displays formatted Lines from an IterationBuffer.
 
 use crate::state::IterationBuffer;
+use ralph_core::AlertMatcher;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -15,12 +16,18 @@ use ratatui::{
 /// Widget that renders the content of an iteration buffer.
 ///
 /// The widget displays the visible lines from the buffer (respecting scroll offset)
-/// and optionally highlights search matches.
+/// and optionally highlights search matches, output alerts and a jumped-to line.
 pub struct ContentPane<'a> {
     /// Reference to the iteration buffer to render
     buffer: &'a IterationBuffer,
     /// Optional search query for highlighting matches
     search_query: Option<&'a str>,
+    /// Optional alert rules whose matches are highlighted
+    alerts: Option<&'a AlertMatcher>,
+    /// Index of a line to mark, e.g. the target of `ralph open`
+    marked_line: Option<usize>,
+    /// Color of the bar down the left edge marking the iteration's hat
+    gutter: Option<Color>,
 }
 
 impl<'a> ContentPane<'a> {
@@ -29,6 +36,9 @@ impl<'a> ContentPane<'a> {
         Self {
             buffer,
             search_query: None,
+            alerts: None,
+            marked_line: None,
+            gutter: None,
         }
     }
 
@@ -39,6 +49,26 @@ impl<'a> ContentPane<'a> {
         }
         self
     }
+
+    /// Sets the alert rules whose matches are highlighted.
+    pub fn with_alerts(mut self, alerts: &'a AlertMatcher) -> Self {
+        if !alerts.is_empty() {
+            self.alerts = Some(alerts);
+        }
+        self
+    }
+
+    /// Marks the line at `index` in the buffer with a background.
+    pub fn with_marked_line(mut self, index: usize) -> Self {
+        self.marked_line = Some(index);
+        self
+    }
+
+    /// Draws a bar in `color` down the left edge, beside every row.
+    pub fn with_gutter(mut self, color: Color) -> Self {
+        self.gutter = Some(color);
+        self
+    }
 }
 
 impl Widget for ContentPane<'_> {
@@ -49,33 +79,62 @@ impl Widget for ContentPane<'_> {
 
         // Get visible lines from the buffer (now returns owned Vec due to interior mutability)
         let visible = self.buffer.visible_lines(area.height as usize);
+        let marked = self
+            .marked_line
+            .and_then(|index| index.checked_sub(self.buffer.scroll_offset));
+        // Content starts right of the gutter, if there is one
+        let left = match self.gutter {
+            Some(_) if area.width > 2 => area.x + 2,
+            _ => area.x,
+        };
+        let draw_gutter = |buf: &mut Buffer, y: u16| {
+            if let Some(color) = self.gutter.filter(|_| left > area.x) {
+                buf[(area.x, y)]
+                    .set_char('\u{258e}')
+                    .set_style(Style::default().fg(color));
+                buf[(area.x + 1, y)]
+                    .set_char(' ')
+                    .set_style(Style::default());
+            }
+        };
 
         let mut y = area.y;
-        for line in &visible {
+        for (i, line) in visible.iter().enumerate() {
             if y >= area.y + area.height {
                 break;
             }
 
-            // Apply search highlighting if we have a query
-            let rendered_line = if let Some(query) = self.search_query {
-                highlight_search_matches(line, query)
-            } else {
-                line.clone()
+            // Apply alert highlighting, then search highlighting on top
+            let mut rendered_line = match self.alerts {
+                Some(alerts) => highlight_alert_matches(line, alerts),
+                None => line.clone(),
             };
+            if let Some(query) = self.search_query {
+                rendered_line = highlight_search_matches(&rendered_line, query);
+            }
+            if marked == Some(i) {
+                for span in &mut rendered_line.spans {
+                    if span.style.bg.is_none() {
+                        span.style = span.style.bg(Color::DarkGray);
+                    }
+                }
+            }
 
             // Render the line into the buffer with soft wrapping
-            let mut x = area.x;
+            draw_gutter(buf, y);
+            let mut x = left;
             for span in &rendered_line.spans {
                 let content = span.content.as_ref();
                 for ch in content.chars() {
                     // Soft wrap: when we reach the edge, move to next row
                     if x >= area.x + area.width {
                         y += 1;
-                        x = area.x;
+                        x = left;
                         // Stop if we've filled the viewport
                         if y >= area.y + area.height {
                             return;
                         }
+                        draw_gutter(buf, y);
                     }
                     buf[(x, y)].set_char(ch).set_style(span.style);
                     x += 1;
@@ -155,6 +214,45 @@ fn highlight_search_matches(line: &Line<'static>, query: &str) -> Line<'static>
     Line::from(new_spans)
 }
 
+/// Highlights text matched by alert rules with the `highlight` action.
+fn highlight_alert_matches(line: &Line<'static>, alerts: &AlertMatcher) -> Line<'static> {
+    let mut new_spans = Vec::with_capacity(line.spans.len());
+
+    for span in &line.spans {
+        let content = span.content.as_ref();
+        let ranges = alerts.highlight_ranges(content);
+        if ranges.is_empty() {
+            new_spans.push(span.clone());
+            continue;
+        }
+
+        let alert_style = span
+            .style
+            .fg(Color::Black)
+            .bg(Color::LightRed)
+            .add_modifier(Modifier::BOLD);
+        let mut last_end = 0;
+        for range in ranges {
+            if range.start > last_end {
+                new_spans.push(Span::styled(
+                    content[last_end..range.start].to_string(),
+                    span.style,
+                ));
+            }
+            new_spans.push(Span::styled(
+                content[range.clone()].to_string(),
+                alert_style,
+            ));
+            last_end = range.end;
+        }
+        if last_end < content.len() {
+            new_spans.push(Span::styled(content[last_end..].to_string(), span.style));
+        }
+    }
+
+    Line::from(new_spans)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -337,6 +435,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn marked_line_gets_background_relative_to_scroll() {
+        let mut buffer = IterationBuffer::new(1);
+        for i in 0..10 {
+            buffer.append_line(Line::from(format!("line {}", i)));
+        }
+        buffer.scroll_offset = 4;
+
+        let backend = TestBackend::new(20, 3);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| {
+                f.render_widget(ContentPane::new(&buffer).with_marked_line(5), f.area());
+            })
+            .unwrap();
+
+        let buf = terminal.backend().buffer();
+        assert_eq!(buf[(0, 0)].bg, Color::Reset, "line 4 is not marked");
+        assert_eq!(buf[(0, 1)].bg, Color::DarkGray, "line 5 is marked");
+    }
+
     // =========================================================================
     // Acceptance Criteria 3: Search Highlight
     // =========================================================================
@@ -386,6 +505,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn alert_matches_are_highlighted() {
+        use ralph_core::{AlertAction, AlertRule};
+
+        let mut buffer = IterationBuffer::new(1);
+        buffer.append_line(Line::from("now deleting the cache"));
+        let alerts = AlertMatcher::new(&[AlertRule {
+            name: None,
+            pattern: "delet\\w+".to_string(),
+            actions: vec![AlertAction::Highlight],
+        }])
+        .unwrap();
+
+        let backend = TestBackend::new(40, 1);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| {
+                let widget = ContentPane::new(&buffer).with_alerts(&alerts);
+                f.render_widget(widget, f.area());
+            })
+            .unwrap();
+
+        let buf = terminal.backend().buffer();
+        // "deleting" spans columns 4..12
+        assert_eq!(buf[(4, 0)].bg, Color::LightRed);
+        assert_eq!(buf[(11, 0)].bg, Color::LightRed);
+        assert_ne!(buf[(3, 0)].bg, Color::LightRed);
+        assert_ne!(buf[(12, 0)].bg, Color::LightRed);
+    }
+
     #[test]
     fn empty_search_query_no_highlight() {
         let mut buffer = IterationBuffer::new(1);
@@ -503,6 +652,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn gutter_marks_every_row_of_wrapped_lines() {
+        let mut buffer = IterationBuffer::new(1);
+        buffer.append_line(Line::from("short"));
+        buffer.append_line(Line::from("a line that wraps around"));
+
+        let area = Rect::new(0, 0, 12, 4);
+        let mut buf = Buffer::empty(area);
+        ContentPane::new(&buffer)
+            .with_gutter(Color::Magenta)
+            .render(area, &mut buf);
+
+        let row = |y: u16| (0..12).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert_eq!(row(0), "\u{258e} short     ");
+        assert_eq!(row(1), "\u{258e} a line tha");
+        assert_eq!(row(2), "\u{258e} t wraps ar");
+        assert_eq!(row(3), "\u{258e} ound      ");
+        assert_eq!(buf[(0, 2)].fg, Color::Magenta);
+    }
+
     // =========================================================================
     // Acceptance Criteria 6: Buffer Clearing (Artifact Prevention)
     // =========================================================================
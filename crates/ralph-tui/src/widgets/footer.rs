@@ -1,4 +1,5 @@
 use crate::state::TuiState;
+use ralph_core::FooterSegment;
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Style},
@@ -6,14 +7,140 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
-/// Footer widget that adapts to terminal width.
+/// Segment layout used when none is configured (matches `TuiConfig::default()`).
+pub const DEFAULT_SEGMENTS: &[FooterSegment] = &[
+    FooterSegment::Clock,
+    FooterSegment::Spacer,
+    FooterSegment::Indicator,
+];
+
+/// Separator between adjacent segments in the same group.
+const SEPARATOR: &str = " │ ";
+
+/// Footer widget composed of configurable segments.
+///
+/// Segments render left to right in configured order. A `Spacer` splits them
+/// into groups separated by flexible space. When the terminal is too narrow,
+/// segments are dropped lowest-priority first (see `FooterSegment::priority`).
 pub struct Footer<'a> {
     state: &'a TuiState,
+    segments: &'a [FooterSegment],
 }
 
 impl<'a> Footer<'a> {
     pub fn new(state: &'a TuiState) -> Self {
-        Self { state }
+        Self {
+            state,
+            segments: DEFAULT_SEGMENTS,
+        }
+    }
+
+    /// Sets the segment layout.
+    #[must_use]
+    pub fn segments(mut self, segments: &'a [FooterSegment]) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Splits segments into groups at each `Spacer`, joining each group's
+    /// segments with separators.
+    ///
+    /// A leading or trailing spacer yields an empty group, so the segments on
+    /// the other side of it still align to their edge.
+    fn group_spans(&self, segments: &[FooterSegment]) -> Vec<Vec<Span<'static>>> {
+        let mut groups = vec![Vec::new()];
+        for segment in segments {
+            if *segment == FooterSegment::Spacer {
+                groups.push(Vec::new());
+                continue;
+            }
+            let spans = self.segment_spans(*segment);
+            let Some(group) = groups.last_mut() else {
+                continue;
+            };
+            if !group.is_empty() && !spans.is_empty() {
+                group.push(Span::styled(
+                    SEPARATOR,
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            group.extend(spans);
+        }
+        groups
+    }
+
+    /// Builds the spans for a single segment.
+    fn segment_spans(&self, segment: FooterSegment) -> Vec<Span<'static>> {
+        let state = self.state;
+        match segment {
+            FooterSegment::LastEvent => {
+                let topic = state.last_event.as_deref().unwrap_or("—");
+                vec![Span::raw(format!("Last: {topic}"))]
+            }
+            FooterSegment::Hat => vec![Span::raw(state.get_pending_hat_display())],
+            FooterSegment::Indicator => {
+                if state.loop_completed {
+                    vec![Span::styled("■ DONE", Style::default().fg(Color::Blue))]
+                } else {
+                    vec![Span::styled("◉ ACTIVE", Style::default().fg(Color::Green))]
+                }
+            }
+            FooterSegment::Budget => {
+                let spent = state.total_cost_usd;
+                match state.max_cost_usd {
+                    Some(max) if max > 0.0 => {
+                        let color = if spent >= max {
+                            Color::Red
+                        } else if spent >= max * 0.8 {
+                            Color::Yellow
+                        } else {
+                            Color::Reset
+                        };
+                        vec![Span::styled(
+                            format!("${spent:.2}/${max:.2}"),
+                            Style::default().fg(color),
+                        )]
+                    }
+                    _ => vec![Span::raw(format!("${spent:.2}"))],
+                }
+            }
+            FooterSegment::Clock => {
+                // Default to 00:00 if loop hasn't started
+                let total_secs = state.get_loop_elapsed().map_or(0, |e| e.as_secs());
+                let mins = total_secs / 60;
+                let secs = total_secs % 60;
+                vec![Span::raw(format!(
+                    "Total Time Elapsed: {mins:02}:{secs:02}"
+                ))]
+            }
+            FooterSegment::WallClock => {
+                vec![Span::raw(chrono::Local::now().format("%H:%M").to_string())]
+            }
+            FooterSegment::Eta => {
+                let text = match state.estimate_remaining() {
+                    Some(remaining) => {
+                        let finish = chrono::Local::now()
+                            + chrono::Duration::from_std(remaining).unwrap_or_default();
+                        format!("ETA {} ({})", format_eta(remaining), finish.format("%H:%M"))
+                    }
+                    None => "ETA --".to_string(),
+                };
+                vec![Span::raw(text)]
+            }
+            FooterSegment::Iteration => {
+                let current = state.total_iterations();
+                let text = match state.max_iterations {
+                    Some(max) => format!("Iter {current}/{max}"),
+                    None => format!("Iter {current}"),
+                };
+                vec![Span::raw(text)]
+            }
+            FooterSegment::Keys => vec![Span::styled(
+                "←/→ iter  / search  ? help  q quit",
+                Style::default().fg(Color::DarkGray),
+            )],
+            FooterSegment::Spacer => Vec::new(),
+        }
     }
 }
 
@@ -24,16 +151,97 @@ impl Widget for Footer<'_> {
         let inner_area = block.inner(area);
         block.render(area, buf);
 
+        // An alert pause takes over the footer until the user resumes
+        if let Some(name) = &self.state.alert_pause {
+            let line = Line::from(vec![
+                Span::raw(" "),
+                Span::styled(
+                    match self.state.alert_pause_at {
+                        Some(at) => format!("⏸ Paused by alert '{name}' at {at}"),
+                        None => format!("⏸ Paused by alert '{name}'"),
+                    },
+                    Style::default().fg(Color::LightRed),
+                ),
+                Span::styled(" · Enter to resume", Style::default().fg(Color::DarkGray)),
+            ]);
+
+            Paragraph::new(line).render(inner_area, buf);
+            return;
+        }
+        if let Some(controller) = &self.state.control_pause {
+            let line = Line::from(vec![
+                Span::raw(" "),
+                Span::styled(
+                    format!("⏸ Paused by controller '{controller}'"),
+                    Style::default().fg(Color::LightRed),
+                ),
+            ]);
+
+            Paragraph::new(line).render(inner_area, buf);
+            return;
+        }
+        if let Some(reason) = &self.state.power_pause {
+            let line = Line::from(vec![
+                Span::raw(" "),
+                Span::styled(
+                    format!("⏸ Paused: {reason}"),
+                    Style::default().fg(Color::LightRed),
+                ),
+                Span::styled(
+                    " · resumes automatically",
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+
+            Paragraph::new(line).render(inner_area, buf);
+            return;
+        }
+
+        if let Some((reason, until)) = &self.state.throttle_wait {
+            let remaining = until.saturating_duration_since(self.state.now());
+            let line = Line::from(vec![
+                Span::raw(" "),
+                Span::styled(
+                    format!("⏳ Throttled: {reason}"),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    format!(" · next iteration in {}s", remaining.as_secs() + 1),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+
+            Paragraph::new(line).render(inner_area, buf);
+            return;
+        }
+
+        if let Some(group) = &self.state.queued_for {
+            let line = Line::from(vec![
+                Span::raw(" "),
+                Span::styled(
+                    format!("⏳ Queued: waiting for a slot in {group}"),
+                    Style::default().fg(Color::Yellow),
+                ),
+            ]);
+
+            Paragraph::new(line).render(inner_area, buf);
+            return;
+        }
+
         // If search state has an active query, render search display
         if let Some(query) = &self.state.search_state.query {
             let match_info = if self.state.search_state.matches.is_empty() {
                 "no matches".to_string()
             } else {
-                format!(
+                let position = format!(
                     "{}/{}",
                     self.state.search_state.current_match + 1,
                     self.state.search_state.matches.len()
-                )
+                );
+                match self.state.current_match_line_id() {
+                    Some(id) => format!("{position} · {id}"),
+                    None => position,
+                }
             };
 
             let line = Line::from(vec![
@@ -49,83 +257,99 @@ impl Widget for Footer<'_> {
             return;
         }
 
-        // Show search input prompt (legacy fallback for when search_query is used)
-        if !self.state.search_query.is_empty() {
-            let prompt = if self.state.search_forward { "/" } else { "?" };
-            let line = Line::from(vec![
-                Span::raw(" "),
-                Span::styled(
-                    format!("{}{}", prompt, self.state.search_query),
-                    Style::default().fg(Color::Yellow),
-                ),
-            ]);
+        // New iteration alert is shown ahead of all segments when viewing
+        // history, followed by any cost anomaly
+        let mut alert = self
+            .state
+            .nav
+            .new_iteration_alert
+            .filter(|_| !self.state.nav.following_latest)
+            .map(|iter_num| {
+                vec![
+                    Span::styled(
+                        format!("▶ New: iter {} ", iter_num),
+                        Style::default().fg(Color::Green),
+                    ),
+                    Span::raw("│ "),
+                ]
+            })
+            .unwrap_or_default();
+        if let Some(anomaly) = &self.state.cost_anomaly {
+            alert.push(Span::styled(
+                format!("⚠ {anomaly} "),
+                Style::default().fg(Color::Yellow),
+            ));
+            alert.push(Span::raw("│ "));
+        }
+        let alert_width: usize = alert.iter().map(Span::width).sum();
+
+        // Drop lowest-priority segments until everything fits (1 col padding each side,
+        // at least 1 col for each spacer between groups)
+        let available = usize::from(inner_area.width).saturating_sub(2 + alert_width);
+        let mut kept: Vec<FooterSegment> = self.segments.to_vec();
+        let mut groups = self.group_spans(&kept);
+        while groups_width(&groups) > available {
+            let Some(drop_idx) = kept
+                .iter()
+                .enumerate()
+                .filter(|(_, segment)| **segment != FooterSegment::Spacer)
+                .max_by_key(|(idx, segment)| (segment.priority(), *idx))
+                .map(|(idx, _)| idx)
+            else {
+                break;
+            };
+            kept.remove(drop_idx);
+            groups = self.group_spans(&kept);
+        }
 
-            Paragraph::new(line).render(inner_area, buf);
-            return;
+        // One Length constraint per group, with a flexible spacer between groups
+        let last_group = groups.len().saturating_sub(1);
+        let mut constraints = Vec::with_capacity(groups.len() * 2);
+        let mut lines = Vec::with_capacity(groups.len());
+        for (idx, group) in groups.into_iter().enumerate() {
+            let mut spans = Vec::new();
+            if idx == 0 {
+                spans.push(Span::raw(" "));
+                spans.extend(alert.iter().cloned());
+            }
+            spans.extend(group);
+            if idx == last_group {
+                spans.push(Span::raw(" "));
+            }
+            if idx > 0 {
+                constraints.push(Constraint::Fill(1));
+            }
+            constraints.push(Constraint::Length(spans_width(&spans) as u16));
+            lines.push(Line::from(spans));
         }
 
-        // Default footer with flexible layout
-        // Build left content: optional alert + elapsed time
-        let mut left_spans = vec![Span::raw(" ")];
-
-        // Show new iteration alert when viewing history and a new iteration arrived
-        if let Some(iter_num) = self.state.new_iteration_alert
-            && !self.state.following_latest
-        {
-            left_spans.push(Span::styled(
-                format!("▶ New: iter {} ", iter_num),
-                Style::default().fg(Color::Green),
-            ));
-            left_spans.push(Span::raw("│ "));
+        let chunks = Layout::horizontal(constraints).split(inner_area);
+        for (idx, line) in lines.into_iter().enumerate() {
+            // Groups occupy every other chunk, with spacers in between
+            Paragraph::new(line).render(chunks[idx * 2], buf);
         }
+    }
+}
 
-        // Show total elapsed time (default to 00:00 if loop hasn't started)
-        let elapsed_display = if let Some(elapsed) = self.state.get_loop_elapsed() {
-            let total_secs = elapsed.as_secs();
-            let mins = total_secs / 60;
-            let secs = total_secs % 60;
-            format!("Total Time Elapsed: {mins:02}:{secs:02}")
-        } else {
-            "Total Time Elapsed: 00:00".to_string()
-        };
-        left_spans.push(Span::raw(elapsed_display));
-
-        let indicator_text = if self.state.loop_completed {
-            "■ DONE"
-        } else {
-            "◉ ACTIVE"
-        };
-
-        let indicator_style = if self.state.loop_completed {
-            Style::default().fg(Color::Blue)
-        } else {
-            Style::default().fg(Color::Green)
-        };
-
-        // Calculate left content width for layout
-        let left_content_width: usize = left_spans.iter().map(|s| s.width()).sum();
-
-        // Use horizontal layout: left content | flexible spacer | right indicator
-        let chunks = Layout::horizontal([
-            Constraint::Length(left_content_width as u16), // Alert + " Last: event"
-            Constraint::Fill(1),                           // Flexible spacer
-            Constraint::Length((indicator_text.len() + 2) as u16), // "indicator "
-        ])
-        .split(inner_area);
-
-        // Render left side (alert + last event)
-        let left = Line::from(left_spans);
-        Paragraph::new(left).render(chunks[0], buf);
-
-        // Render right side (indicator)
-        let right = Line::from(vec![
-            Span::styled(indicator_text, indicator_style),
-            Span::raw(" "),
-        ]);
-        Paragraph::new(right).render(chunks[2], buf);
+/// Formats a remaining-time estimate compactly: "<1m", "12m", "1h05m".
+fn format_eta(remaining: std::time::Duration) -> String {
+    let total_mins = remaining.as_secs() / 60;
+    match (total_mins / 60, total_mins % 60) {
+        (0, 0) => "<1m".to_string(),
+        (0, mins) => format!("{mins}m"),
+        (hours, mins) => format!("{hours}h{mins:02}m"),
     }
 }
 
+fn spans_width(spans: &[Span<'_>]) -> usize {
+    spans.iter().map(Span::width).sum()
+}
+
+/// Total width of all groups, counting one column for each spacer.
+fn groups_width(groups: &[Vec<Span<'static>>]) -> usize {
+    groups.iter().map(|g| spans_width(g)).sum::<usize>() + groups.len().saturating_sub(1)
+}
+
 /// Convenience function for rendering the footer.
 pub fn render(state: &TuiState) -> Footer<'_> {
     Footer::new(state)
@@ -134,21 +358,33 @@ pub fn render(state: &TuiState) -> Footer<'_> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ralph_core::MockClock;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn state_with_clock() -> (TuiState, MockClock) {
+        let clock = MockClock::new();
+        (TuiState::new().with_clock(Arc::new(clock.clone())), clock)
+    }
 
     fn render_to_string(state: &TuiState) -> String {
         render_to_string_with_width(state, 80)
     }
 
     fn render_to_string_with_width(state: &TuiState, width: u16) -> String {
+        render_segments(state, DEFAULT_SEGMENTS, width)
+    }
+
+    fn render_segments(state: &TuiState, segments: &[FooterSegment], width: u16) -> String {
         // Height of 2: 1 for top border + 1 for content
         let backend = TestBackend::new(width, 2);
         let mut terminal = Terminal::new(backend).unwrap();
 
         terminal
             .draw(|f| {
-                let widget = render(state);
+                let widget = render(state).segments(segments);
                 f.render_widget(widget, f.area());
             })
             .unwrap();
@@ -169,8 +405,8 @@ mod tests {
     fn footer_shows_new_iteration_alert() {
         // Given new_iteration_alert = Some(5) and following_latest = false
         let mut state = TuiState::new();
-        state.new_iteration_alert = Some(5);
-        state.following_latest = false;
+        state.nav.new_iteration_alert = Some(5);
+        state.nav.following_latest = false;
 
         // When footer renders
         let text = render_to_string(&state);
@@ -183,12 +419,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn footer_shows_cost_anomaly_ahead_of_segments() {
+        let mut state = TuiState::new();
+        state.cost_anomaly = Some("iter 7 used 4.1× median tokens".to_string());
+
+        let text = render_to_string(&state);
+
+        assert!(
+            text.contains("⚠ iter 7 used 4.1× median tokens │"),
+            "should show cost anomaly, got: {}",
+            text
+        );
+    }
+
+    #[test]
+    fn footer_shows_control_pause() {
+        let mut state = TuiState::new();
+        state.control_pause = Some("alice".to_string());
+
+        let text = render_to_string(&state);
+
+        assert!(
+            text.contains("Paused by controller 'alice'"),
+            "should show controller pause, got: {}",
+            text
+        );
+        assert!(!text.contains("Enter to resume"));
+    }
+
+    #[test]
+    fn footer_counts_down_throttle_wait() {
+        let (mut state, clock) = state_with_clock();
+        state.throttle_wait = Some((
+            "claude at 52000/40000 tokens/min".to_string(),
+            state.now() + Duration::from_secs(45),
+        ));
+        clock.advance(Duration::from_millis(15_500));
+
+        let text = render_to_string(&state);
+
+        assert!(
+            text.contains("Throttled: claude at 52000/40000 tokens/min · next iteration in 30s"),
+            "should show throttle countdown, got: {}",
+            text
+        );
+    }
+
+    #[test]
+    fn footer_shows_concurrency_queue() {
+        let mut state = TuiState::new();
+        state.queued_for = Some("team (2/2 slots busy)".to_string());
+
+        let text = render_to_string(&state);
+
+        assert!(
+            text.contains("Queued: waiting for a slot in team (2/2 slots busy)"),
+            "should show queue, got: {}",
+            text
+        );
+    }
+
+    #[test]
+    fn footer_shows_power_pause() {
+        let mut state = TuiState::new();
+        state.power_pause = Some("on battery at 15% (below 20%)".to_string());
+
+        let text = render_to_string(&state);
+
+        assert!(
+            text.contains("Paused: on battery at 15% (below 20%)"),
+            "should show power pause, got: {}",
+            text
+        );
+    }
+
+    #[test]
+    fn footer_shows_alert_pause() {
+        let mut state = TuiState::new();
+        state.alert_pause = Some("payments".to_string());
+
+        let text = render_to_string(&state);
+
+        assert!(
+            text.contains("Paused by alert 'payments'"),
+            "should show alert pause, got: {}",
+            text
+        );
+        assert!(text.contains("Enter to resume"));
+    }
+
     #[test]
     fn footer_no_alert_when_following() {
         // Given following_latest = true (even if new_iteration_alert has a value)
         let mut state = TuiState::new();
-        state.new_iteration_alert = Some(5);
-        state.following_latest = true;
+        state.nav.new_iteration_alert = Some(5);
+        state.nav.following_latest = true;
 
         // When footer renders
         let text = render_to_string(&state);
@@ -203,13 +529,9 @@ mod tests {
 
     #[test]
     fn footer_shows_elapsed_time() {
-        // Given loop_started is set (simulating 2 minutes 30 seconds elapsed)
-        let mut state = TuiState::new();
-        state.loop_started = Some(
-            std::time::Instant::now()
-                .checked_sub(std::time::Duration::from_secs(150))
-                .unwrap(),
-        );
+        // Given the loop has been running for 2 minutes 30 seconds
+        let (state, clock) = state_with_clock();
+        clock.advance(Duration::from_secs(150));
 
         // When footer renders
         let text = render_to_string(&state);
@@ -262,6 +584,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn footer_shows_line_id_of_current_match() {
+        let mut state = TuiState::new();
+        state.start_new_iteration();
+        state.start_new_iteration();
+        if let Some(buffer) = state.current_iteration_mut() {
+            for i in 0..5 {
+                buffer.append_line(Line::from(format!("line {i} test")));
+            }
+        }
+        state.search("line 3");
+
+        let text = render_to_string(&state);
+        assert!(
+            text.contains("1/1 · iter2:4"),
+            "should show the match's line id, got: {}",
+            text
+        );
+    }
+
     #[test]
     fn footer_shows_no_matches_when_empty() {
         // Given search with no matches
@@ -312,4 +654,123 @@ mod tests {
             text
         );
     }
+
+    // =========================================================================
+    // Configurable Segments
+    // =========================================================================
+
+    #[test]
+    fn footer_renders_segments_in_configured_order() {
+        let mut state = TuiState::new();
+        state.pending_hat = Some((ralph_proto::HatId::new("builder"), "🔨Builder".to_string()));
+        state.last_event = Some("build.task".to_string());
+        state.start_new_iteration();
+        state.max_iterations = Some(10);
+
+        let segments = [
+            FooterSegment::Iteration,
+            FooterSegment::Hat,
+            FooterSegment::LastEvent,
+        ];
+        let text = render_segments(&state, &segments, 100);
+
+        let iter_pos = text.find("Iter 1/10").expect("iteration segment");
+        let hat_pos = text.find("Builder").expect("hat segment");
+        let event_pos = text.find("Last: build.task").expect("last event segment");
+        assert!(iter_pos < hat_pos && hat_pos < event_pos, "got: {}", text);
+        assert!(
+            !text.contains("ACTIVE"),
+            "indicator not configured, got: {}",
+            text
+        );
+    }
+
+    #[test]
+    fn footer_spacer_right_aligns_trailing_segments() {
+        let state = TuiState::new();
+        let text = render_segments(
+            &state,
+            &[FooterSegment::Spacer, FooterSegment::Indicator],
+            40,
+        );
+
+        // Second row is content; indicator sits at the right edge with 1 col padding
+        let content_row: String = text.chars().skip(40).collect();
+        assert!(
+            content_row.trim_end().ends_with("ACTIVE") && content_row.ends_with(' '),
+            "indicator should be right-aligned, got: {:?}",
+            content_row
+        );
+    }
+
+    #[test]
+    fn footer_budget_shows_spend_against_limit() {
+        let mut state = TuiState::new();
+        state.total_cost_usd = 1.5;
+        let text = render_segments(&state, &[FooterSegment::Budget], 80);
+        assert!(text.contains("$1.50"), "got: {}", text);
+
+        state.max_cost_usd = Some(5.0);
+        let text = render_segments(&state, &[FooterSegment::Budget], 80);
+        assert!(text.contains("$1.50/$5.00"), "got: {}", text);
+    }
+
+    #[test]
+    fn footer_drops_low_priority_segments_when_narrow() {
+        let mut state = TuiState::new();
+        state.last_event = Some("build.task".to_string());
+        let segments = [
+            FooterSegment::Keys,
+            FooterSegment::LastEvent,
+            FooterSegment::Clock,
+            FooterSegment::Spacer,
+            FooterSegment::Indicator,
+        ];
+
+        let wide = render_segments(&state, &segments, 120);
+        assert!(wide.contains("q quit") && wide.contains("Last: build.task"));
+
+        let narrow = render_segments(&state, &segments, 40);
+        assert!(
+            !narrow.contains("q quit"),
+            "keys should drop first, got: {}",
+            narrow
+        );
+        assert!(
+            !narrow.contains("Last:"),
+            "last event should drop next, got: {}",
+            narrow
+        );
+        assert!(narrow.contains("Total Time Elapsed"), "got: {}", narrow);
+        assert!(narrow.contains("ACTIVE"), "got: {}", narrow);
+    }
+
+    #[test]
+    fn footer_eta_shows_placeholder_without_estimate() {
+        let state = TuiState::new();
+        let text = render_segments(&state, &[FooterSegment::Eta], 80);
+        assert!(text.contains("ETA --"), "got: {}", text);
+    }
+
+    #[test]
+    fn footer_eta_shows_remaining_time() {
+        let (mut state, clock) = state_with_clock();
+        state.max_iterations = Some(4);
+        state.start_new_iteration();
+        clock.advance(Duration::from_secs(600));
+        state.start_new_iteration();
+        clock.advance(Duration::from_secs(60));
+
+        // 1 completed iteration at 10m, 3 remaining, 1m into the current one
+        let text = render_segments(&state, &[FooterSegment::Eta], 80);
+        assert!(text.contains("ETA 29m"), "got: {}", text);
+    }
+
+    #[test]
+    fn format_eta_compacts_units() {
+        use std::time::Duration;
+        assert_eq!(format_eta(Duration::from_secs(30)), "<1m");
+        assert_eq!(format_eta(Duration::from_secs(12 * 60 + 5)), "12m");
+        assert_eq!(format_eta(Duration::from_secs(65 * 60)), "1h05m");
+    }
 }
@@ -1,9 +1,10 @@
 use crate::state::TuiState;
 use ratatui::{
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
 };
+use unicode_segmentation::UnicodeSegmentation;
 
 // ============================================================================
 // Width Breakpoints for Priority-Based Progressive Disclosure
@@ -16,7 +17,7 @@ use ratatui::{
 // - Priority 3: Hat display, Scroll indicator - compressed at 50
 // - Priority 4: Iteration elapsed time MM:SS - hidden at 50
 // - Priority 5: Idle countdown - hidden at 40
-// - Priority 6: Help hint - hidden at 65
+// - Priority 6: Help hint, hat history strip - hidden at 65
 // ============================================================================
 
 /// Width breakpoint constants
@@ -35,7 +36,7 @@ pub fn render(state: &TuiState, width: u16) -> Paragraph<'static> {
 
     // Priority 1: Iteration counter - ALWAYS shown
     // Uses TUI pagination state (current_view/total_iterations) not Ralph loop iteration
-    let current = state.current_view + 1; // 0-indexed to 1-indexed
+    let current = state.nav.view + 1; // 0-indexed to 1-indexed
     let total = state.total_iterations();
     let iter_display = format!("[iter {}/{}]", current, total);
     spans.push(Span::raw(iter_display));
@@ -51,15 +52,46 @@ pub fn render(state: &TuiState, width: u16) -> Paragraph<'static> {
     }
 
     // Priority 3: Hat display - compressed at WIDTH_COMPRESS and below
+    // Colored per hat config, bold so the active hat stands out
     spans.push(Span::raw(" | "));
+    let hat_style = state
+        .pending_hat
+        .as_ref()
+        .and_then(|(hat_id, _)| state.hat_color(hat_id))
+        .map_or_else(Style::default, |color| Style::default().fg(color))
+        .add_modifier(Modifier::BOLD);
     if width > WIDTH_COMPRESS {
         // Full hat display: "🔨 Builder"
-        spans.push(Span::raw(state.get_pending_hat_display()));
+        spans.push(Span::styled(state.get_pending_hat_display(), hat_style));
     } else {
         // Compressed: emoji only (first character cluster)
         let hat_display = state.get_pending_hat_display();
-        let emoji = hat_display.chars().next().unwrap_or('?');
-        spans.push(Span::raw(emoji.to_string()));
+        spans.push(Span::styled(hat_emoji(&hat_display).to_string(), hat_style));
+    }
+
+    // Priority 6: Hat history strip (most recent first, emoji only, without
+    // the active hat) - shown only at WIDTH_FULL while a hat is active
+    let mut previous_hats: Vec<_> = match &state.pending_hat {
+        Some((active, _)) if width >= WIDTH_FULL => state
+            .hat_history
+            .iter()
+            .rev()
+            .filter(|(hat_id, _)| hat_id != active)
+            .collect(),
+        _ => Vec::new(),
+    };
+    previous_hats.dedup_by(|a, b| a.0 == b.0);
+    previous_hats.truncate(3);
+    if !previous_hats.is_empty() {
+        spans.push(Span::styled(" ‹", Style::default().fg(Color::DarkGray)));
+        for (hat_id, display) in previous_hats {
+            let emoji = hat_emoji(display);
+            let color = state.hat_color(hat_id).unwrap_or(Color::DarkGray);
+            spans.push(Span::styled(
+                format!(" {emoji}"),
+                Style::default().fg(color).add_modifier(Modifier::DIM),
+            ));
+        }
     }
 
     // Priority 5: Idle countdown - hidden at WIDTH_MINIMAL and below
@@ -72,7 +104,7 @@ pub fn render(state: &TuiState, width: u16) -> Paragraph<'static> {
     // Priority 2: Mode indicator - ALWAYS shown (compressed at WIDTH_COMPRESS and below)
     // Shows [LIVE] when following latest iteration, [REVIEW] when viewing history
     spans.push(Span::raw(" | "));
-    let mode = if state.following_latest {
+    let mode = if state.nav.following_latest {
         if width > WIDTH_COMPRESS {
             Span::styled("[LIVE]", Style::default().fg(Color::Green))
         } else {
@@ -107,14 +139,27 @@ pub fn render(state: &TuiState, width: u16) -> Paragraph<'static> {
     Paragraph::new(line).block(block)
 }
 
+/// The emoji a hat display starts with: its first grapheme cluster, so
+/// multi-codepoint emoji (ZWJ sequences, variation selectors) stay whole.
+fn hat_emoji(display: &str) -> &str {
+    display.graphemes(true).next().unwrap_or("?")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ralph_core::MockClock;
     use ralph_proto::{Event, HatId};
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
+    use std::sync::Arc;
     use std::time::Duration;
 
+    fn state_with_clock() -> (TuiState, MockClock) {
+        let clock = MockClock::new();
+        (TuiState::new().with_clock(Arc::new(clock.clone())), clock)
+    }
+
     fn render_to_string(state: &TuiState) -> String {
         render_to_string_with_width(state, 80)
     }
@@ -146,7 +191,7 @@ mod tests {
         state.start_new_iteration();
         state.start_new_iteration();
         state.start_new_iteration();
-        state.current_view = 2; // Viewing iteration 3
+        state.nav.view = 2; // Viewing iteration 3
 
         let text = render_to_string(&state);
         assert!(
@@ -163,7 +208,7 @@ mod tests {
         state.start_new_iteration();
         state.start_new_iteration();
         state.start_new_iteration();
-        state.current_view = 0; // Viewing first iteration
+        state.nav.view = 0; // Viewing first iteration
 
         let text = render_to_string(&state);
         assert!(
@@ -175,16 +220,13 @@ mod tests {
 
     #[test]
     fn header_shows_elapsed_time() {
-        let mut state = TuiState::new();
+        let (mut state, clock) = state_with_clock();
         let event = Event::new("task.start", "");
         state.update(&event);
 
         // Simulate 4 minutes 32 seconds elapsed for current iteration
-        state.iteration_started = Some(
-            std::time::Instant::now()
-                .checked_sub(Duration::from_secs(272))
-                .unwrap(),
-        );
+        state.iteration_started = Some(state.now());
+        clock.advance(Duration::from_secs(272));
 
         let text = render_to_string(&state);
         assert!(text.contains("04:32"), "should show 04:32, got: {}", text);
@@ -199,6 +241,84 @@ mod tests {
         assert!(text.contains("Builder"), "should show hat, got: {}", text);
     }
 
+    #[test]
+    fn header_hat_uses_configured_color() {
+        let mut state = TuiState::new();
+        state.set_hat_colors(std::collections::HashMap::from([(
+            HatId::new("builder"),
+            Color::Magenta,
+        )]));
+        state.pending_hat = Some((HatId::new("builder"), "🔨Builder".to_string()));
+
+        let paragraph = render(&state, 80);
+        let backend = TestBackend::new(80, 2);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| f.render_widget(paragraph, f.area()))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let hat_cell = buffer
+            .content()
+            .iter()
+            .find(|cell| cell.symbol() == "B")
+            .expect("hat name rendered");
+        assert_eq!(hat_cell.fg, Color::Magenta);
+        assert!(hat_cell.modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn header_shows_hat_history_strip() {
+        let mut state = TuiState::new();
+        state.update(&Event::new("task.resume", ""));
+        state.update(&Event::new("build.task", ""));
+
+        let text = render_to_string(&state);
+        assert!(
+            text.contains("Builder ‹ 📋"),
+            "should show previous hat after current, got: {}",
+            text
+        );
+
+        let narrow = render_to_string_with_width(&state, 60);
+        assert!(
+            !narrow.contains('‹'),
+            "history strip hidden below full width, got: {}",
+            narrow
+        );
+    }
+
+    #[test]
+    fn header_hat_history_strip_skips_active_hat() {
+        let mut state = TuiState::new();
+        state.update(&Event::new("task.resume", ""));
+        state.update(&Event::new("build.task", ""));
+        state.update(&Event::new("build.done", ""));
+
+        // Planner is active again; only the builder is history
+        let text = render_to_string(&state);
+        let strip = text.split('‹').nth(1).unwrap().split('|').next().unwrap();
+        assert_eq!(
+            strip.split_whitespace().collect::<Vec<_>>(),
+            vec!["🔨"],
+            "active hat left out of the strip, got: {}",
+            text
+        );
+
+        // No active hat, no strip
+        state.pending_hat = None;
+        let text = render_to_string(&state);
+        assert!(!text.contains('‹'), "strip hidden, got: {}", text);
+    }
+
+    #[test]
+    fn hat_emoji_keeps_whole_grapheme() {
+        assert_eq!(hat_emoji("🧑‍💻Coder"), "🧑‍💻");
+        assert_eq!(hat_emoji("❤️ Reviewer"), "❤️");
+        assert_eq!(hat_emoji("🔨Builder"), "🔨");
+        assert_eq!(hat_emoji(""), "?");
+    }
+
     #[test]
     fn header_shows_idle_countdown_when_present() {
         let mut state = TuiState::new();
@@ -240,7 +360,7 @@ mod tests {
 
     #[test]
     fn header_full_format() {
-        let mut state = TuiState::new();
+        let (mut state, clock) = state_with_clock();
         let event = Event::new("task.start", "");
         state.update(&event);
 
@@ -248,14 +368,11 @@ mod tests {
         for _ in 0..10 {
             state.start_new_iteration();
         }
-        state.current_view = 2; // Viewing iteration 3 of 10
-        state.following_latest = true;
+        state.nav.view = 2; // Viewing iteration 3 of 10
+        state.nav.following_latest = true;
 
-        state.iteration_started = Some(
-            std::time::Instant::now()
-                .checked_sub(Duration::from_secs(272))
-                .unwrap(),
-        );
+        state.iteration_started = Some(state.now());
+        clock.advance(Duration::from_secs(272));
         state.pending_hat = Some((HatId::new("builder"), "🔨Builder".to_string()));
         state.idle_timeout_remaining = Some(Duration::from_secs(25));
         state.in_scroll_mode = true;
@@ -297,7 +414,7 @@ mod tests {
     // =========================================================================
 
     fn create_full_state() -> TuiState {
-        let mut state = TuiState::new();
+        let (mut state, clock) = state_with_clock();
         let event = Event::new("task.start", "");
         state.update(&event);
 
@@ -305,14 +422,11 @@ mod tests {
         for _ in 0..10 {
             state.start_new_iteration();
         }
-        state.current_view = 2; // Viewing iteration 3 of 10
-        state.following_latest = true; // In LIVE mode
+        state.nav.view = 2; // Viewing iteration 3 of 10
+        state.nav.following_latest = true; // In LIVE mode
 
-        state.iteration_started = Some(
-            std::time::Instant::now()
-                .checked_sub(Duration::from_secs(272))
-                .unwrap(),
-        );
+        state.iteration_started = Some(state.now());
+        clock.advance(Duration::from_secs(272));
         state.pending_hat = Some((HatId::new("builder"), "🔨Builder".to_string()));
         state.idle_timeout_remaining = Some(Duration::from_secs(25));
         state.in_scroll_mode = true;
@@ -466,7 +580,7 @@ mod tests {
         for _ in 0..5 {
             state.start_new_iteration();
         }
-        state.current_view = 2; // Viewing iteration 3
+        state.nav.view = 2; // Viewing iteration 3
 
         let text = render_to_string(&state);
         assert!(
@@ -495,7 +609,7 @@ mod tests {
         // Given following_latest = true
         let mut state = TuiState::new();
         state.start_new_iteration();
-        state.following_latest = true;
+        state.nav.following_latest = true;
 
         let text = render_to_string(&state);
         assert!(
@@ -511,8 +625,8 @@ mod tests {
         let mut state = TuiState::new();
         state.start_new_iteration();
         state.start_new_iteration();
-        state.current_view = 0;
-        state.following_latest = false;
+        state.nav.view = 0;
+        state.nav.following_latest = false;
 
         let text = render_to_string(&state);
         assert!(
@@ -540,15 +654,12 @@ mod tests {
     #[test]
     fn header_preserves_elapsed_time_with_new_format() {
         // Given 5 minutes elapsed for current iteration
-        let mut state = TuiState::new();
+        let (mut state, clock) = state_with_clock();
         state.start_new_iteration();
         let event = Event::new("task.start", "");
         state.update(&event);
-        state.iteration_started = Some(
-            std::time::Instant::now()
-                .checked_sub(Duration::from_secs(300))
-                .unwrap(),
-        );
+        state.iteration_started = Some(state.now());
+        clock.advance(Duration::from_secs(300));
 
         let text = render_to_string(&state);
         assert!(
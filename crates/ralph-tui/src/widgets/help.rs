@@ -73,6 +73,30 @@ pub fn render(f: &mut Frame, area: Rect) {
             Span::styled("  Esc", Style::default().fg(Color::Cyan)),
             Span::raw("    Dismiss/cancel"),
         ]),
+        Line::from(vec![
+            Span::styled("  o", Style::default().fg(Color::Cyan)),
+            Span::raw("      Open/close full tool result"),
+        ]),
+        Line::from(vec![
+            Span::styled("  i", Style::default().fg(Color::Cyan)),
+            Span::raw("      Show iteration environment"),
+        ]),
+        Line::from(vec![
+            Span::styled("  p", Style::default().fg(Color::Cyan)),
+            Span::raw("      Open in $PAGER"),
+        ]),
+        Line::from(vec![
+            Span::styled("  s", Style::default().fg(Color::Cyan)),
+            Span::raw("      Settings"),
+        ]),
+        Line::from(vec![
+            Span::styled("  r", Style::default().fg(Color::Cyan)),
+            Span::raw("      Switch run (ralph attach --all)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Enter", Style::default().fg(Color::Cyan)),
+            Span::raw("  Start run / resume after alert pause"),
+        ]),
         Line::from(""),
         Line::from(Span::styled(
             "Press Esc to dismiss",
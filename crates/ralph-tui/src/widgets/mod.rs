@@ -2,3 +2,7 @@ pub mod content;
 pub mod footer;
 pub mod header;
 pub mod help;
+pub mod result_viewer;
+pub mod runs;
+pub mod settings;
+pub mod splash;
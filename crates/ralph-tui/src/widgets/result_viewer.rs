@@ -0,0 +1,131 @@
+//! Popup viewer for full tool results and iteration details.
+
+use crate::state::ResultViewer;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+/// Renders the viewer's text over `area` (the content pane).
+pub fn render(f: &mut Frame, area: Rect, viewer: &ResultViewer) {
+    let height = usize::from(area.height.saturating_sub(2));
+    let start = viewer.scroll.min(viewer.lines.len());
+    let end = (start + height).min(viewer.lines.len());
+
+    let position = if viewer.lines.is_empty() {
+        "empty".to_string()
+    } else {
+        format!("{}-{}/{}", start + 1, end, viewer.lines.len())
+    };
+    let block = Block::default()
+        .title(format!(" {} ({position}) ", viewer.title))
+        .title_bottom(Line::from(Span::styled(
+            " j/k scroll · g/G top/bottom · p pager · Esc close ",
+            Style::default().fg(Color::DarkGray),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let lines: Vec<Line> = viewer.lines[start..end]
+        .iter()
+        .map(|line| {
+            if viewer.word_diff {
+                word_diff_line(line)
+            } else {
+                Line::raw(line.as_str())
+            }
+        })
+        .collect();
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Colors the `[-removed-]` and `{+added+}` runs of a word diff line,
+/// dropping the markers.
+fn word_diff_line(line: &str) -> Line<'_> {
+    let mut spans = Vec::new();
+    let mut rest = line;
+    loop {
+        let next = [("[-", "-]", Color::Red), ("{+", "+}", Color::Green)]
+            .into_iter()
+            .filter_map(|(open, close, color)| {
+                let start = rest.find(open)?;
+                let len = rest[start + 2..].find(close)?;
+                Some((start, len, color))
+            })
+            .min_by_key(|(start, _, _)| *start);
+        let Some((start, len, color)) = next else {
+            break;
+        };
+        if start > 0 {
+            spans.push(Span::styled(
+                &rest[..start],
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        spans.push(Span::styled(
+            &rest[start + 2..start + 2 + len],
+            Style::default().fg(color),
+        ));
+        rest = &rest[start + 2 + len + 2..];
+    }
+    if !rest.is_empty() {
+        spans.push(Span::styled(rest, Style::default().fg(Color::DarkGray)));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    #[test]
+    fn renders_visible_window_with_position() {
+        let viewer = ResultViewer {
+            title: "Tool result toolu_1".to_string(),
+            lines: (1..=20).map(|i| format!("row {i}")).collect(),
+            scroll: 5,
+            word_diff: false,
+        };
+
+        let backend = TestBackend::new(50, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| render(f, f.area(), &viewer)).unwrap();
+
+        let buf = terminal.backend().buffer();
+        let rows: Vec<String> = (0..6)
+            .map(|y| (0..50).map(|x| buf[(x, y)].symbol()).collect())
+            .collect();
+        assert!(
+            rows[0].contains("Tool result toolu_1 (6-9/20)"),
+            "{}",
+            rows[0]
+        );
+        assert!(rows[1].contains("row 6"));
+        assert!(rows[4].contains("row 9"));
+    }
+
+    #[test]
+    fn word_diff_runs_are_colored_without_markers() {
+        let line = word_diff_line("let [-x-]{+y+} = 1;");
+        let runs: Vec<(String, Option<Color>)> = line
+            .spans
+            .iter()
+            .map(|span| (span.content.to_string(), span.style.fg))
+            .collect();
+        assert_eq!(
+            runs,
+            vec![
+                ("let ".to_string(), Some(Color::DarkGray)),
+                ("x".to_string(), Some(Color::Red)),
+                ("y".to_string(), Some(Color::Green)),
+                (" = 1;".to_string(), Some(Color::DarkGray)),
+            ]
+        );
+    }
+}
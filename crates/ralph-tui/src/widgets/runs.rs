@@ -0,0 +1,101 @@
+//! Run selector popup.
+
+use crate::runs::RunList;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+const WIDTH: u16 = 72;
+
+/// Renders the selector centered in `area`.
+pub fn render(f: &mut Frame, area: Rect, runs: &RunList) {
+    let mut lines: Vec<Line> = runs
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let marker = match (i == runs.selected, i == runs.current) {
+                (true, _) => ">",
+                (false, true) => "*",
+                (false, false) => " ",
+            };
+            let id_style = if i == runs.selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let status_style = if entry.active {
+                Style::default()
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            Line::from(vec![
+                Span::styled(format!(" {marker} {:<24} ", entry.id), id_style),
+                Span::styled(entry.status.clone(), status_style),
+            ])
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " No active runs yet",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let block = Block::default()
+        .title(format!(" Runs ({}) ", runs.entries.len()))
+        .title_bottom(Line::from(Span::styled(
+            " j/k select · Enter switch · Esc close ",
+            Style::default().fg(Color::DarkGray),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let width = WIDTH.min(area.width);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::TuiState;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    #[test]
+    fn lists_runs_with_status_and_markers() {
+        let mut runs = RunList::default();
+        runs.add("(primary)", TuiState::new());
+        runs.add("swift-fox", TuiState::new());
+        runs.set_status("(primary)", "iteration 3/100 · $0.42", true);
+        runs.set_status("swift-fox", "finished: completed", false);
+        runs.open_selector();
+        runs.select_next();
+
+        let backend = TestBackend::new(80, 8);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| render(f, f.area(), &runs)).unwrap();
+
+        let buf = terminal.backend().buffer();
+        let text: String = (0..8)
+            .map(|y| (0..80).map(|x| buf[(x, y)].symbol()).collect::<String>() + "\n")
+            .collect();
+        assert!(text.contains("Runs (2)"), "{text}");
+        assert!(text.contains("* (primary)"), "{text}");
+        assert!(text.contains("iteration 3/100 · $0.42"), "{text}");
+        assert!(text.contains("> swift-fox"), "{text}");
+    }
+}
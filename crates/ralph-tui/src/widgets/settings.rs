@@ -0,0 +1,118 @@
+//! Settings popup.
+
+use crate::settings::{FIELDS, SettingsEditor};
+use crate::state::TuiState;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+const WIDTH: u16 = 60;
+
+/// Renders the popup centered in `area`.
+pub fn render(f: &mut Frame, area: Rect, state: &TuiState, editor: &SettingsEditor) {
+    let mut lines: Vec<Line> = FIELDS
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let selected = i == editor.selected;
+            let value = match &editor.input {
+                Some(input) if selected => Span::styled(
+                    format!("{input}_"),
+                    Style::default().add_modifier(Modifier::UNDERLINED),
+                ),
+                _ => Span::raw(field.value(state)),
+            };
+            let label_style = if selected {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!(
+                        " {} {:<10}",
+                        if selected { ">" } else { " " },
+                        field.label()
+                    ),
+                    label_style,
+                ),
+                value,
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(match &editor.message {
+        Some(message) => Line::from(Span::styled(
+            format!(" {message}"),
+            Style::default().fg(Color::Yellow),
+        )),
+        None => Line::from(Span::styled(
+            " Budget, model and verbose apply from the next iteration",
+            Style::default().fg(Color::DarkGray),
+        )),
+    });
+
+    let hint = if editor.input.is_some() {
+        " Enter apply · Esc cancel "
+    } else {
+        " j/k select · Enter change · w save · Esc close "
+    };
+    let block = Block::default()
+        .title(" Settings ")
+        .title_bottom(Line::from(Span::styled(
+            hint,
+            Style::default().fg(Color::DarkGray),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let width = WIDTH.min(area.width);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    #[test]
+    fn renders_current_values_and_selection() {
+        let mut state = TuiState::new();
+        state.max_cost_usd = Some(5.0);
+        state.model = Some("opus".to_string());
+        let editor = SettingsEditor {
+            selected: 1,
+            ..SettingsEditor::default()
+        };
+
+        let backend = TestBackend::new(64, 12);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| render(f, f.area(), &state, &editor))
+            .unwrap();
+
+        let buf = terminal.backend().buffer();
+        let text: String = (0..12)
+            .map(|y| (0..64).map(|x| buf[(x, y)].symbol()).collect::<String>() + "\n")
+            .collect();
+        assert!(text.contains("Budget    $5.00"), "{text}");
+        assert!(text.contains("> Model     opus"), "{text}");
+        assert!(text.contains("Follow    on"), "{text}");
+        assert!(text.contains("Theme     dark"), "{text}");
+        assert!(text.contains("next iteration"), "{text}");
+    }
+}
@@ -0,0 +1,122 @@
+//! Startup screen shown in the content area until iteration 1 starts: the
+//! run summary, the backend probe and whether the run waits for Enter.
+
+use crate::state::TuiState;
+use ralph_adapters::ProbeStatus;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+/// Renders the startup screen over `area` (the content pane).
+pub fn render(f: &mut Frame, area: Rect, state: &TuiState) {
+    let block = Block::default()
+        .title(" Starting ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+
+    let label_width = state
+        .startup_summary
+        .iter()
+        .map(|(label, _)| label.len())
+        .chain(state.probe.as_ref().map(|_| "Probe".len()))
+        .max()
+        .unwrap_or(0)
+        + 2;
+    let label = |text: &str| {
+        Span::styled(
+            format!("{text:<label_width$}"),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    };
+
+    let mut lines: Vec<Line> = state
+        .startup_summary
+        .iter()
+        .map(|(name, value)| Line::from(vec![label(name), Span::raw(value.clone())]))
+        .collect();
+
+    if let Some(probe) = &state.probe {
+        let (symbol, color) = match probe {
+            ProbeStatus::Running { .. } => ("…", Color::Yellow),
+            ProbeStatus::Ok { .. } => ("✓", Color::Green),
+            ProbeStatus::Failed { .. } => ("✗", Color::Red),
+        };
+        lines.push(Line::from(vec![
+            label("Probe"),
+            Span::styled(format!("{symbol} "), Style::default().fg(color)),
+            Span::raw(probe.summary()),
+        ]));
+    }
+
+    if !lines.is_empty() {
+        lines.push(Line::from(""));
+    }
+    let (hint, color) = if state.probe.as_ref().is_some_and(ProbeStatus::is_failed) {
+        ("The run was not started. Press q to exit.", Color::Red)
+    } else if state.awaiting_start {
+        ("Press Enter to start, q to quit.", Color::Cyan)
+    } else {
+        ("Waiting for the first iteration...", Color::DarkGray)
+    };
+    lines.push(Line::from(Span::styled(hint, Style::default().fg(color))));
+
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn rows(state: &TuiState) -> Vec<String> {
+        let backend = TestBackend::new(60, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| render(f, f.area(), state)).unwrap();
+        let buf = terminal.backend().buffer();
+        (0..6)
+            .map(|y| (0..60).map(|x| buf[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn shows_summary_and_start_prompt() {
+        let mut state = TuiState::new();
+        state.startup_summary = vec![
+            ("Backend".to_string(), "claude".to_string()),
+            ("Est. cost".to_string(), "up to $5.00".to_string()),
+        ];
+        state.awaiting_start = true;
+
+        let rows = rows(&state);
+        assert!(rows[1].contains("Backend    claude"));
+        assert!(rows[2].contains("Est. cost  up to $5.00"));
+        assert!(rows[4].contains("Press Enter to start, q to quit."));
+    }
+
+    #[test]
+    fn shows_probe_status() {
+        let mut state = TuiState::new();
+        state.probe = Some(ProbeStatus::Ok {
+            backend: "claude".to_string(),
+            latency: std::time::Duration::from_millis(2400),
+        });
+        assert!(rows(&state)[1].contains("Probe  ✓ claude responded in 2.4s"));
+
+        state.probe = Some(ProbeStatus::Failed {
+            backend: "claude".to_string(),
+            reason: "exit 1: Invalid API key".to_string(),
+        });
+        let rows = rows(&state);
+        assert!(rows[1].contains("✗ claude probe failed: exit 1: Invalid API key"));
+        assert!(rows[3].contains("Press q to exit."));
+    }
+}
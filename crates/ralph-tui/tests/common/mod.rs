@@ -190,11 +190,11 @@ impl TuiTestHarness {
 
         TuiSnapshot {
             iteration: state.iteration,
-            current_view: state.current_view,
+            current_view: state.nav.view,
             total_iterations: state.total_iterations(),
-            following_latest: state.following_latest,
-            has_alert: state.new_iteration_alert.is_some(),
-            alert_iteration: state.new_iteration_alert,
+            following_latest: state.nav.following_latest,
+            has_alert: state.nav.new_iteration_alert.is_some(),
+            alert_iteration: state.nav.new_iteration_alert,
             search_query: state.search_state.query.clone(),
             search_matches: state.search_state.matches.len(),
             search_current_match: state.search_state.current_match,
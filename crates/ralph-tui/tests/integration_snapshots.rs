@@ -108,7 +108,10 @@ fn test_following_latest_behavior() {
     // Create iterations and verify following_latest
     {
         let state = harness.state().lock().unwrap();
-        assert!(state.following_latest, "Should follow latest by default");
+        assert!(
+            state.nav.following_latest,
+            "Should follow latest by default"
+        );
     }
 
     // Start iterations
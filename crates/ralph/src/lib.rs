@@ -0,0 +1,53 @@
+//! # ralph
+//!
+//! The stable API for embedding Ralph Orchestrator in another program.
+//!
+//! The `ralph-*` crates behind the CLI change shape from release to release.
+//! This crate curates the part of them an embedder needs — the event loop,
+//! events and hats, configuration, and the stream handler interface for
+//! rendering a backend's output — and follows semver for it: anything
+//! reachable from [`prelude`] only changes incompatibly in a major release.
+//!
+//! Every enum and every struct with public fields in the prelude is
+//! `#[non_exhaustive]`, so new variants and fields can arrive in a minor
+//! release. Match enums such as
+//! [`TerminationReason`](prelude::TerminationReason) with a wildcard arm, and
+//! build structs through their constructors or `Default` (e.g.
+//! [`SessionResult::new`](prelude::SessionResult::new),
+//! [`CliBackend::new`](prelude::CliBackend::new)) instead of struct
+//! literals; reading and assigning fields is fine.
+//!
+//! Everything else stays reachable through the `ralph-proto`, `ralph-core`
+//! and `ralph-adapters` crates directly, without these guarantees.
+//!
+//! ## Example
+//!
+//! Drive a loop with canned backend output instead of a real agent:
+//!
+//! ```
+//! use ralph::prelude::*;
+//!
+//! let workspace = tempfile::tempdir().unwrap();
+//! let mut config = RalphConfig::default();
+//! config.core.workspace_root = workspace.path().to_path_buf();
+//! config.core.scratchpad = workspace
+//!     .path()
+//!     .join(".agent/scratchpad.md")
+//!     .to_string_lossy()
+//!     .to_string();
+//!
+//! let context = LoopContext::primary(workspace.path().to_path_buf());
+//! let mut orchestrator = Orchestrator::with_context(config, context);
+//! orchestrator.initialize("Add a --version flag");
+//!
+//! let hat = orchestrator.next_hat().cloned().expect("a hat to run");
+//! let prompt = orchestrator.build_prompt(&hat).expect("a prompt");
+//! assert!(prompt.contains("--version"));
+//!
+//! // Whatever the backend answered for `prompt`
+//! let output = "Added the flag and its test. LOOP_COMPLETE";
+//! let reason = orchestrator.process_output(&hat, output, true);
+//! assert_eq!(reason, Some(TerminationReason::CompletionPromise));
+//! ```
+
+pub mod prelude;
@@ -0,0 +1,65 @@
+//! Everything an embedder needs, in one import.
+//!
+//! ```
+//! use ralph::prelude::*;
+//! ```
+//!
+//! ## Rendering backend output
+//!
+//! Backends report what they do through a [`StreamHandler`]. Use one of the
+//! bundled handlers or implement your own:
+//!
+//! ```
+//! use ralph::prelude::*;
+//!
+//! #[derive(Default)]
+//! struct Transcript {
+//!     lines: Vec<String>,
+//! }
+//!
+//! impl StreamHandler for Transcript {
+//!     fn on_text(&mut self, text: &str) {
+//!         self.lines.push(text.to_string());
+//!     }
+//!
+//!     fn on_tool_call(&mut self, name: &str, _id: &str, _input: &serde_json::Value) {
+//!         self.lines.push(format!("[{name}]"));
+//!     }
+//!
+//!     fn on_tool_result(&mut self, _id: &str, _output: &str) {}
+//!
+//!     fn on_error(&mut self, error: &str) {
+//!         self.lines.push(format!("error: {error}"));
+//!     }
+//!
+//!     fn on_complete(&mut self, result: &SessionResult) {
+//!         self.lines.push(format!("{} turn(s)", result.num_turns));
+//!     }
+//! }
+//!
+//! let mut transcript = Transcript::default();
+//! transcript.on_iteration_start(&IterationInfo::new(1, "builder", "Build it"));
+//! transcript.on_tool_call("Read", "t1", &serde_json::json!({ "file_path": "src/main.rs" }));
+//! transcript.on_text("Done.");
+//! transcript.on_complete(&SessionResult::new(1200, 0.02, 3, false));
+//! assert_eq!(transcript.lines, ["[Read]", "Done.", "3 turn(s)"]);
+//! ```
+
+// Events and hats
+pub use ralph_proto::{Event, EventBus, Hat, HatId, Topic};
+
+// The loop and its configuration
+pub use ralph_core::{
+    EventLoop, HatConfig, LoopContext, LoopState, RalphConfig, TerminationReason,
+};
+
+/// The event loop under the name embedders look for; the same type as
+/// [`EventLoop`].
+pub use ralph_core::EventLoop as Orchestrator;
+
+// Backends and their output
+pub use ralph_adapters::{
+    AdapterCapabilities, AdapterEntry, AdapterError, AdapterRegistry, CliBackend, CliExecutor,
+    ConsoleStreamHandler, ExecutionResult, IterationInfo, JsonStreamHandler, PrettyStreamHandler,
+    QuietStreamHandler, SessionResult, StreamHandler, TracingStreamHandler,
+};